@@ -0,0 +1,309 @@
+use anyhow::{bail, Result};
+use chrono::Utc;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Files of a feature crate, relative to its directory, with their template.
+const CRATE_FILES: [(&str, &str); 9] = [
+    (
+        "Cargo.toml",
+        include_str!("../templates/feature/Cargo.toml.tpl"),
+    ),
+    (
+        "src/lib.rs",
+        include_str!("../templates/feature/src/lib.rs.tpl"),
+    ),
+    (
+        "src/aggregate.rs",
+        include_str!("../templates/feature/src/aggregate.rs.tpl"),
+    ),
+    (
+        "src/command.rs",
+        include_str!("../templates/feature/src/command.rs.tpl"),
+    ),
+    (
+        "src/event.rs",
+        include_str!("../templates/feature/src/event.rs.tpl"),
+    ),
+    (
+        "src/query/mod.rs",
+        include_str!("../templates/feature/src/query/mod.rs.tpl"),
+    ),
+    (
+        "src/query/entries.rs",
+        include_str!("../templates/feature/src/query/entries.rs.tpl"),
+    ),
+    (
+        "tests/common.rs",
+        include_str!("../templates/feature/tests/common.rs.tpl"),
+    ),
+    (
+        "tests/command.rs",
+        include_str!("../templates/feature/tests/command.rs.tpl"),
+    ),
+];
+
+/// Creates the `starter-<name>` crate mirroring `starter-feed`, with its
+/// migration, page, template and translations, then wires it into the
+/// workspace and registers its module in `pages::modules`. Every file is
+/// checked before anything is written, so that a failure leaves the
+/// workspace untouched.
+pub fn feature(root: &Path, name: &str) -> Result<Vec<PathBuf>> {
+    if !name.starts_with(|c: char| c.is_ascii_lowercase())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        bail!("feature name must be a single lowercase word, like \"bookmark\"");
+    }
+
+    let workspace = root.join("Cargo.toml");
+    if !fs::read_to_string(&workspace).is_ok_and(|content| content.contains("[workspace]")) {
+        bail!("{} is not the workspace root", root.display());
+    }
+
+    let dir = root.join(name);
+    if dir.exists() {
+        bail!("{} already exists", dir.display());
+    }
+
+    let render = |template: &str| {
+        template
+            .replace("__name__", name)
+            .replace("__Name__", &capitalize(name))
+            .replace("__version__", env!("CARGO_PKG_VERSION"))
+    };
+
+    let mut files = CRATE_FILES
+        .iter()
+        .map(|(path, template)| (dir.join(path), render(template)))
+        .collect::<Vec<_>>();
+
+    let version = Utc::now().format("%Y%m%d%H%M%S");
+    let migrations = root.join("migrations");
+    files.push((
+        migrations.join(format!("{version}_{name}_entries.up.sql")),
+        render(include_str!("../templates/feature/migrations/up.sql.tpl")),
+    ));
+    files.push((
+        migrations.join(format!("{version}_{name}_entries.down.sql")),
+        render(include_str!("../templates/feature/migrations/down.sql.tpl")),
+    ));
+
+    let web = root.join("web");
+    let page = web.join("src/pages").join(format!("{name}.rs"));
+    let template = web.join("templates").join(name).join("index.html");
+    for path in [&page, &template] {
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+    }
+    files.push((
+        page,
+        render(include_str!("../templates/feature/web/page.rs.tpl")),
+    ));
+    files.push((
+        template,
+        render(include_str!("../templates/feature/web/index.html.tpl")),
+    ));
+
+    let title = capitalize(name);
+    let edits = [
+        edit(&workspace, |content| {
+            insert_after(content, "\"./feed\",", &format!("    \"./{name}\",\n"))
+        })?,
+        edit(&web.join("Cargo.toml"), |content| {
+            insert_after(
+                content,
+                "starter-feed = ",
+                &format!(
+                    "starter-{name} = {{ path = \"../{name}\", version = \"{}\" }}\n",
+                    env!("CARGO_PKG_VERSION")
+                ),
+            )
+        })?,
+        edit(&web.join("src/pages.rs"), |content| {
+            let content = insert_mod(content, name)?;
+
+            insert_module(&content, name, &title)
+        })?,
+        edit(&web.join("i18n/en/starter_web.ftl"), |content| {
+            translations(
+                content,
+                &format!("pages-routes_{name} = {title}"),
+                &render(include_str!("../templates/feature/web/en.ftl.tpl")),
+            )
+        })?,
+        edit(&web.join("i18n/fr/starter_web.ftl"), |content| {
+            translations(
+                content,
+                &format!("pages-routes_{name} = {title}"),
+                &render(include_str!("../templates/feature/web/fr.ftl.tpl")),
+            )
+        })?,
+    ];
+
+    let mut written = vec![];
+    for (path, content) in files.into_iter().chain(edits) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Creates the up and down files of a migration named after the current
+/// time. Given a `projection` crate, like `feed`, they create and drop the
+/// `<projection>_<name>` table with the columns every projection has.
+pub fn migration(root: &Path, name: &str, projection: Option<&str>) -> Result<Vec<PathBuf>> {
+    if !name.starts_with(|c: char| c.is_ascii_lowercase())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        bail!("migration name must be lowercase words joined by underscores, like \"feed_tags\"");
+    }
+
+    let migrations = root.join("migrations");
+    if !migrations.is_dir() {
+        bail!(
+            "{} not found, is it the workspace root?",
+            migrations.display()
+        );
+    }
+
+    let (name, up, down) = match projection {
+        Some(projection) => {
+            if !root.join(projection).join("Cargo.toml").is_file() {
+                bail!("{projection} is not a crate of the workspace");
+            }
+
+            (
+                format!("{projection}_{name}"),
+                include_str!("../templates/migration/projection.up.sql.tpl"),
+                include_str!("../templates/migration/projection.down.sql.tpl"),
+            )
+        }
+        None => (
+            name.to_owned(),
+            include_str!("../templates/migration/up.sql.tpl"),
+            include_str!("../templates/migration/down.sql.tpl"),
+        ),
+    };
+
+    let render = |template: &str| template.replace("__name__", &name);
+
+    let version = Utc::now().format("%Y%m%d%H%M%S");
+    let files = [
+        (
+            migrations.join(format!("{version}_{name}.up.sql")),
+            render(up),
+        ),
+        (
+            migrations.join(format!("{version}_{name}.down.sql")),
+            render(down),
+        ),
+    ];
+
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        bail!("{} already exists", path.display());
+    }
+
+    let mut written = vec![];
+    for (path, content) in files {
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Content of `path` changed by `f`, to be written along with the new files.
+fn edit(path: &Path, f: impl FnOnce(&str) -> Result<String>) -> Result<(PathBuf, String)> {
+    let content = fs::read_to_string(path)?;
+
+    match f(&content) {
+        Ok(content) => Ok((path.to_owned(), content)),
+        Err(e) => bail!("{}: {}", path.display(), e),
+    }
+}
+
+/// `text` inserted on the line following the first one containing `anchor`.
+fn insert_after(content: &str, anchor: &str, text: &str) -> Result<String> {
+    let Some(start) = content.find(anchor) else {
+        bail!("{anchor} not found, was it moved?");
+    };
+
+    let end = content[start..]
+        .find('\n')
+        .map(|pos| start + pos + 1)
+        .unwrap_or(content.len());
+
+    Ok(format!("{}{}{}", &content[..end], text, &content[end..]))
+}
+
+/// `mod <name>;` inserted among the leading module declarations, keeping
+/// them sorted.
+fn insert_mod(content: &str, name: &str) -> Result<String> {
+    let declaration = format!("mod {name};");
+    let mut lines = content.lines().collect::<Vec<_>>();
+    let mods = lines
+        .iter()
+        .take_while(|line| line.starts_with("mod "))
+        .count();
+
+    if mods == 0 {
+        bail!("module declarations not found, were they moved?");
+    }
+
+    let pos = lines[..mods].partition_point(|line| *line < declaration.as_str());
+    lines.insert(pos, &declaration);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// `<name>::<Name>Module` added at the end of `pages::modules`.
+fn insert_module(content: &str, name: &str, title: &str) -> Result<String> {
+    let Some(end) = content
+        .find("pub fn modules() -> Vec<Box<dyn FeatureModule>> {")
+        .and_then(|start| content[start..].find(")]\n").map(|pos| start + pos + 1))
+    else {
+        bail!("modules not found, was it moved?");
+    };
+
+    Ok(format!(
+        "{}, Box::new({name}::{title}Module){}",
+        &content[..end],
+        &content[end..]
+    ))
+}
+
+/// `route` added after the last route title and `messages` appended.
+fn translations(content: &str, route: &str, messages: &str) -> Result<String> {
+    let Some(last) = content
+        .lines()
+        .filter(|line| line.starts_with("pages-routes_"))
+        .last()
+    else {
+        bail!("route titles not found, were they moved?");
+    };
+
+    let content = insert_after(content, last, &format!("{route}\n"))?;
+
+    Ok(format!("{}{}", content.trim_end_matches('\n'), messages))
+}
@@ -0,0 +1,175 @@
+use anyhow::{bail, Result};
+use reqwest::Client;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use tokio::task::JoinSet;
+use ulid::Ulid;
+
+/// Pages read by every virtual user, like visitors browsing the site.
+const QUERIES: [&str; 5] = [
+    "/",
+    "/trending",
+    "/search?q=rust",
+    "/feed.atom",
+    "/feed/tag/rust",
+];
+
+/// Pause of a virtual user between two requests.
+const THINK_TIME: Duration = Duration::from_millis(100);
+
+pub struct Options {
+    pub url: String,
+    pub users: usize,
+    pub duration: Duration,
+    /// Bearer token the commands are sent with, only queries are sent
+    /// without it.
+    pub token: Option<String>,
+}
+
+/// Latencies of the successful requests of a route and the count of failed
+/// ones.
+#[derive(Default)]
+pub struct RouteStats {
+    pub latencies: Vec<Duration>,
+    pub errors: u64,
+}
+
+impl RouteStats {
+    /// Latency under which `percent` of the requests completed.
+    pub fn percentile(&self, percent: usize) -> Option<Duration> {
+        let last = self.latencies.len().checked_sub(1)?;
+
+        Some(self.latencies[last * percent / 100])
+    }
+}
+
+/// Sends `users` concurrent streams of requests to `url` for `duration`,
+/// mixing reads of public pages with draft autosaves when a token is given,
+/// then returns the stats of each route with their latencies sorted.
+pub async fn run(options: Options) -> Result<BTreeMap<String, RouteStats>> {
+    if options.users == 0 {
+        bail!("at least one user is required");
+    }
+
+    let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+    let url = options.url.trim_end_matches('/').to_owned();
+    let deadline = Instant::now() + options.duration;
+
+    let mut users = JoinSet::new();
+    for user in 0..options.users {
+        users.spawn(virtual_user(
+            client.clone(),
+            url.to_owned(),
+            options.token.to_owned(),
+            user,
+            deadline,
+        ));
+    }
+
+    let mut stats = BTreeMap::<String, RouteStats>::new();
+    while let Some(user_stats) = users.join_next().await {
+        for (route, route_stats) in user_stats? {
+            let entry = stats.entry(route).or_default();
+            entry.latencies.extend(route_stats.latencies);
+            entry.errors += route_stats.errors;
+        }
+    }
+
+    for route_stats in stats.values_mut() {
+        route_stats.latencies.sort();
+    }
+
+    Ok(stats)
+}
+
+async fn virtual_user(
+    client: Client,
+    url: String,
+    token: Option<String>,
+    user: usize,
+    deadline: Instant,
+) -> BTreeMap<String, RouteStats> {
+    let mut stats = BTreeMap::<String, RouteStats>::new();
+    let draft_id = Ulid::new().to_string();
+    let mut step = user;
+
+    while Instant::now() < deadline {
+        let (route, request) = match &token {
+            // One request out of four is a command, as users mostly read.
+            Some(token) if step % 4 == 3 => (
+                "/drafts/:id/_autosave".to_owned(),
+                client
+                    .post(format!("{url}/drafts/{draft_id}/_autosave"))
+                    .bearer_auth(token)
+                    .form(&[
+                        ("title", format!("Load test {user}")),
+                        ("content", format!("Autosave {step} of user {user}")),
+                    ]),
+            ),
+            _ => {
+                let path = QUERIES[step % QUERIES.len()];
+                let request = client.get(format!("{url}{path}"));
+                let request = match &token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                };
+
+                (path.to_owned(), request)
+            }
+        };
+
+        let started_at = Instant::now();
+        let response = request.send().await;
+        let route_stats = stats.entry(route).or_default();
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                route_stats.latencies.push(started_at.elapsed())
+            }
+            _ => route_stats.errors += 1,
+        }
+
+        step += 1;
+        tokio::time::sleep(THINK_TIME).await;
+    }
+
+    // Drafts are discarded so that runs don't pile them up.
+    if let Some(token) = token.filter(|_| stats.contains_key("/drafts/:id/_autosave")) {
+        let response = client
+            .post(format!("{url}/drafts/{draft_id}/delete"))
+            .bearer_auth(token)
+            .send()
+            .await;
+
+        if !response.is_ok_and(|response| response.status().is_success()) {
+            stats
+                .entry("/drafts/:id/delete".to_owned())
+                .or_default()
+                .errors += 1;
+        }
+    }
+
+    stats
+}
+
+/// Duration like `500ms`, `60s`, `5m` or `1h`.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+
+    let Ok(amount) = amount.parse::<u64>() else {
+        return Err(format!("{value} is not a duration, like 60s"));
+    };
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "s" | "" => Ok(Duration::from_secs(amount)),
+        "m" => Ok(Duration::from_secs(amount * 60)),
+        "h" => Ok(Duration::from_secs(amount * 3600)),
+        _ => Err(format!("{unit} is not a unit of ms, s, m or h")),
+    }
+}
@@ -1,11 +1,24 @@
-use clap::{value_parser, Arg, ArgAction, Command};
-use std::str::FromStr;
+mod generate;
+mod loadgen;
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use clap_complete::Shell;
+use starter_web::events::ExportFilter;
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 use tracing::error;
 use tracing_subscriber::{prelude::*, EnvFilter};
+use uuid::Uuid;
 
-#[tokio::main]
-async fn main() {
-    let matches = Command::new(env!("CARGO_PKG_NAME"))
+/// Command line of the binary, also used to generate its completions and man
+/// page.
+fn command() -> Command {
+    Command::new(env!("CARGO_PKG_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
         .arg_required_else_help(true)
         .arg(
@@ -16,7 +29,340 @@ async fn main() {
                 .action(ArgAction::Set),
         )
         .subcommand(Command::new("serve").about("Start starter server using bin"))
-        .get_matches();
+        .subcommand(
+            Command::new("worker").about("Run the consumers and the scheduler without the server"),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print the completions script of a shell")
+                .arg(
+                    Arg::new("shell")
+                        .required(true)
+                        .value_parser(value_parser!(Shell))
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(Command::new("man").about("Print the man page"))
+        .subcommand(
+            Command::new("doctor")
+                .about("Diagnose the environment and print how to fix its problems"),
+        )
+        .subcommand(
+            Command::new("loadgen")
+                .about("Send realistic traffic to a running server and report its latencies")
+                .arg(
+                    Arg::new("url")
+                        .long("url")
+                        .help("Url of the server")
+                        .default_value("http://127.0.0.1:3000")
+                        .value_parser(value_parser!(String))
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("users")
+                        .long("users")
+                        .help("Virtual users sending requests at once")
+                        .default_value("10")
+                        .value_parser(value_parser!(usize))
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("duration")
+                        .long("duration")
+                        .help("How long to send requests, like 60s or 5m")
+                        .default_value("60s")
+                        .value_parser(loadgen::parse_duration)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("token")
+                        .long("token")
+                        .env("STARTER_LOADGEN_TOKEN")
+                        .help("Bearer token of a user, to send commands too")
+                        .value_parser(value_parser!(String))
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Measure the server performance")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("render")
+                        .about("Render a page repeatedly without the server and print its timings")
+                        .arg(
+                            Arg::new("route")
+                                .long("route")
+                                .help("Path of the page, without the base url")
+                                .default_value("/")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("iterations")
+                                .long("iterations")
+                                .help("How many times to render the page")
+                                .default_value("1000")
+                                .value_parser(value_parser!(usize))
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(Command::new("openapi").about("Print the OpenAPI document of the json routes"))
+        .subcommand(Command::new("routes").about("List the routes with their handler and role"))
+        .subcommand(
+            Command::new("config")
+                .about("Inspect the configuration")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("check").about(
+                        "Validate the configuration, reach its services and print it masked",
+                    ),
+                ),
+        )
+        .subcommand(
+            Command::new("events")
+                .about("Read the stored events and replay them through the rules")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("tail")
+                        .about("Print the events appended from now on, pretty-printed")
+                        .arg(
+                            Arg::new("rule")
+                                .long("rule")
+                                .help("Rule or aggregate type of the events, like feed-details")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("export")
+                        .about("Stream events as json lines, oldest first")
+                        .arg(
+                            Arg::new("aggregate")
+                                .long("aggregate")
+                                .help("Aggregate type, like feed or comment")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("since")
+                                .long("since")
+                                .help("Rfc 3339 date of the oldest event")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("type")
+                                .long("type")
+                                .help("Event name, like created, can be repeated")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Append),
+                        )
+                        .arg(
+                            Arg::new("user")
+                                .long("user")
+                                .help("Id of the user who requested the events")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("out")
+                                .long("out")
+                                .help("File to write, the standard output by default")
+                                .value_parser(value_parser!(PathBuf))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("replay")
+                        .about("Read the events again through a rule, without its side effects")
+                        .arg(
+                            Arg::new("rule")
+                                .long("rule")
+                                .help("Rule to replay, like feed-details")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("from")
+                                .long("from")
+                                .help("Id of the first event to replay, all of them by default")
+                                .value_parser(value_parser!(Uuid))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(Command::new("rebuild").about(
+                    "Empty the read models and replay every event through the rules writing them",
+                )),
+        )
+        .subcommand(
+            Command::new("user")
+                .about("Manage users through their events")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("create")
+                        .about("Create a user ahead of their first sign in")
+                        .arg(
+                            Arg::new("id")
+                                .help("Subject of the user tokens")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .help("Name of the user")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("role")
+                                .long("role")
+                                .help("Role of the user, user or admin")
+                                .default_value("user")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("disable")
+                        .about("Sign a user out and keep them from signing in")
+                        .arg(
+                            Arg::new("id")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("set-role")
+                        .about("Change the role of a user")
+                        .arg(
+                            Arg::new("id")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("role")
+                                .help("user or admin")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("list").about("List users as handled by the running server"),
+                ),
+        )
+        .subcommand(
+            Command::new("migrate")
+                .about("Manage the migrations of the current workspace and of the database")
+                .subcommand_required(true)
+                .subcommand(Command::new("run").about("Run the pending migrations of every module"))
+                .subcommand(
+                    Command::new("status")
+                        .about("List the migrations with whether they were applied"),
+                )
+                .subcommand(
+                    Command::new("new")
+                        .about("Create the up and down files of a migration")
+                        .arg(
+                            Arg::new("name")
+                                .help("Lowercase words joined by underscores")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("projection")
+                                .long("projection")
+                                .help("Crate of the projection table to create, like feed")
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("role")
+                .about("Grant and revoke roles, for provisioning scripts")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("grant")
+                        .about("Give a role to a user")
+                        .arg(
+                            Arg::new("user")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("role")
+                                .help("user or admin")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("revoke")
+                        .about("Take a role back from a user, who keeps the user role")
+                        .arg(
+                            Arg::new("user")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("role")
+                                .help("admin")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("secrets")
+                .about("Create the secrets of the configuration")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("generate")
+                        .about("Print new secrets as a section of the config file")
+                        .arg(
+                            Arg::new("env")
+                                .long("env")
+                                .help("Append them as STARTER_ variables to this .env file instead")
+                                .value_parser(value_parser!(PathBuf))
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Generate code in the current workspace")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("feature")
+                        .about("Create an event-sourced feature crate wired into the workspace")
+                        .arg(
+                            Arg::new("name")
+                                .help("Single lowercase word naming the feature")
+                                .required(true)
+                                .value_parser(value_parser!(String))
+                                .action(ArgAction::Set),
+                        ),
+                ),
+        )
+}
+
+#[tokio::main]
+async fn main() {
+    let matches = command().get_matches();
 
     let log = matches
         .get_one::<String>("log")
@@ -31,11 +377,28 @@ async fn main() {
         }
     };
 
+    let (env_filter, log_filter) = tracing_subscriber::reload::Layer::new(env_filter);
+
+    let otlp = match starter_web::otlp_layer() {
+        Ok(layer) => layer,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
     tracing_subscriber::registry()
+        .with(otlp)
         .with(tracing_subscriber::fmt::layer())
         .with(env_filter)
         .init();
 
+    starter_web::on_log_filter(move |log| {
+        let filter = EnvFilter::from_str(log).map_err(|e| e.to_string())?;
+
+        log_filter.reload(filter).map_err(|e| e.to_string())
+    });
+
     match matches.subcommand() {
         Some(("serve", _sub_matches)) => {
             if let Err(e) = starter_web::serve().await {
@@ -43,6 +406,424 @@ async fn main() {
                 std::process::exit(1);
             }
         }
+        Some(("worker", _sub_matches)) => {
+            if let Err(e) = starter_web::work().await {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(("completions", sub_matches)) => {
+            let shell = *sub_matches
+                .get_one::<Shell>("shell")
+                .expect("shell is required");
+            let mut command = command();
+            let name = command.get_name().to_owned();
+
+            clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        }
+        Some(("man", _sub_matches)) => {
+            if let Err(e) = clap_mangen::Man::new(command()).render(&mut std::io::stdout()) {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(("doctor", _sub_matches)) => {
+            let mut healthy = true;
+
+            for diagnosis in starter_web::doctor::doctor().await {
+                if diagnosis.findings.is_empty() {
+                    println!("ok\t{}", diagnosis.name);
+                }
+
+                for finding in &diagnosis.findings {
+                    healthy = false;
+                    println!("problem\t{}\t{}", diagnosis.name, finding.problem);
+                    println!("fix\t{}\t{}", diagnosis.name, finding.fix);
+                }
+            }
+
+            if !healthy {
+                std::process::exit(1);
+            }
+        }
+        Some(("loadgen", sub_matches)) => {
+            let options = loadgen::Options {
+                url: sub_matches
+                    .get_one::<String>("url")
+                    .cloned()
+                    .unwrap_or_default(),
+                users: sub_matches.get_one::<usize>("users").copied().unwrap_or(10),
+                duration: sub_matches
+                    .get_one::<Duration>("duration")
+                    .copied()
+                    .unwrap_or_default(),
+                token: sub_matches.get_one::<String>("token").cloned(),
+            };
+
+            let stats = match loadgen::run(options).await {
+                Ok(stats) => stats,
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            println!("route\trequests\terrors\tp50\tp90\tp99\tmax");
+
+            for (route, route_stats) in stats {
+                let ms = |percent| {
+                    route_stats
+                        .percentile(percent)
+                        .map(|latency| format!("{}ms", latency.as_millis()))
+                        .unwrap_or("-".to_owned())
+                };
+
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    route,
+                    route_stats.latencies.len(),
+                    route_stats.errors,
+                    ms(50),
+                    ms(90),
+                    ms(99),
+                    ms(100)
+                );
+            }
+        }
+        Some(("bench", sub_matches)) => match sub_matches.subcommand() {
+            Some(("render", render_matches)) => {
+                let route = render_matches
+                    .get_one::<String>("route")
+                    .map(|s| s.as_str())
+                    .unwrap_or("/");
+                let iterations = render_matches
+                    .get_one::<usize>("iterations")
+                    .copied()
+                    .unwrap_or(1000);
+
+                let timings = match starter_web::bench::render(route, iterations).await {
+                    Ok(timings) => timings,
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                println!(
+                    "{} renders of {}: min {:?}, p50 {:?}, p99 {:?}, max {:?}",
+                    timings.iterations, route, timings.min, timings.p50, timings.p99, timings.max
+                );
+            }
+            _ => unreachable!(),
+        },
+        Some(("openapi", _sub_matches)) => match starter_web::openapi() {
+            Ok(spec) => println!("{spec}"),
+            Err(e) => {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(("routes", _sub_matches)) => {
+            let routes = match starter_web::routes() {
+                Ok(routes) => routes,
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            for route in routes {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    route.method, route.path, route.handler, route.role
+                );
+            }
+        }
+        Some(("config", sub_matches)) => match sub_matches.subcommand() {
+            Some(("check", _check_matches)) => {
+                let report = match starter_web::check_config().await {
+                    Ok(report) => report,
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                println!("{}", report.config);
+
+                for check in &report.checks {
+                    match &check.error {
+                        Some(error) => println!("error\t{}\t{}", check.name, error),
+                        None => println!("ok\t{}", check.name),
+                    }
+                }
+
+                if !report.is_ok() {
+                    std::process::exit(1);
+                }
+            }
+            _ => unreachable!(),
+        },
+        Some(("events", sub_matches)) => match sub_matches.subcommand() {
+            Some(("tail", tail_matches)) => {
+                let rule = tail_matches.get_one::<String>("rule");
+
+                if let Err(e) = starter_web::events::tail(rule.map(|rule| rule.as_str())).await {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Some(("export", export_matches)) => {
+                let filter = ExportFilter {
+                    aggregate: export_matches.get_one::<String>("aggregate").cloned(),
+                    since: export_matches.get_one::<String>("since").cloned(),
+                    names: export_matches
+                        .get_many::<String>("type")
+                        .map(|names| names.cloned().collect())
+                        .unwrap_or_default(),
+                    user_id: export_matches.get_one::<String>("user").cloned(),
+                };
+
+                let out = export_matches.get_one::<PathBuf>("out");
+
+                match starter_web::events::export(&filter, out.map(|out| out.as_path())).await {
+                    Ok(count) => eprintln!("{count} events exported"),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Some(("replay", replay_matches)) => {
+                let rule = replay_matches
+                    .get_one::<String>("rule")
+                    .expect("rule is required");
+                let from = replay_matches.get_one::<Uuid>("from").copied();
+
+                match starter_web::events::replay(rule, from).await {
+                    Ok(count) => eprintln!("{count} events to replay through {rule}"),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Some(("rebuild", _rebuild_matches)) => match starter_web::events::rebuild().await {
+                Ok(rules) => {
+                    for rule in rules {
+                        println!("{rule}");
+                    }
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            },
+            _ => unreachable!(),
+        },
+        Some(("user", sub_matches)) => {
+            if let Err(e) = user(sub_matches).await {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(("role", sub_matches)) => {
+            if let Err(e) = role(sub_matches).await {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(("migrate", sub_matches)) => match sub_matches.subcommand() {
+            Some(("run", _run_matches)) => {
+                if let Err(e) = starter_web::migrate().await {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Some(("status", _status_matches)) => {
+                let migrations = match starter_web::migrations().await {
+                    Ok(migrations) => migrations,
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                for migration in migrations {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        if migration.applied {
+                            "applied"
+                        } else {
+                            "pending"
+                        },
+                        migration.module,
+                        migration.version,
+                        migration.description
+                    );
+                }
+            }
+            Some(("new", new_matches)) => {
+                let name = new_matches
+                    .get_one::<String>("name")
+                    .expect("name is required");
+                let projection = new_matches.get_one::<String>("projection");
+
+                let files = match std::env::current_dir()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|root| {
+                        generate::migration(&root, name, projection.map(|p| p.as_str()))
+                    }) {
+                    Ok(files) => files,
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                for file in files {
+                    println!("{}", file.display());
+                }
+            }
+            _ => unreachable!(),
+        },
+        Some(("secrets", sub_matches)) => match sub_matches.subcommand() {
+            Some(("generate", generate_matches)) => {
+                let env = generate_matches.get_one::<PathBuf>("env");
+
+                if let Err(e) = secrets(env.map(|env| env.as_path())) {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            _ => unreachable!(),
+        },
+        Some(("generate", sub_matches)) => match sub_matches.subcommand() {
+            Some(("feature", feature_matches)) => {
+                let name = feature_matches
+                    .get_one::<String>("name")
+                    .expect("name is required");
+
+                let files = match std::env::current_dir()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|root| generate::feature(&root, name))
+                {
+                    Ok(files) => files,
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                for file in files {
+                    println!("{}", file.display());
+                }
+
+                println!("Run `make fmt` to format the generated code");
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+}
+
+fn arg(matches: &ArgMatches, name: &str) -> String {
+    matches
+        .get_one::<String>(name)
+        .map(|s| s.to_owned())
+        .unwrap_or_default()
+}
+
+async fn user(matches: &ArgMatches) -> anyhow::Result<()> {
+    match matches.subcommand() {
+        Some(("create", sub_matches)) => {
+            starter_web::users::create(
+                arg(sub_matches, "id"),
+                arg(sub_matches, "name"),
+                arg(sub_matches, "role"),
+            )
+            .await?;
+        }
+        Some(("disable", sub_matches)) => {
+            if !starter_web::users::disable(arg(sub_matches, "id")).await? {
+                println!("User already disabled");
+            }
+        }
+        Some(("set-role", sub_matches)) => {
+            if !starter_web::users::set_role(arg(sub_matches, "id"), arg(sub_matches, "role"))
+                .await?
+            {
+                println!("User already has this role");
+            }
+        }
+        Some(("list", _sub_matches)) => {
+            for user in starter_web::users::list().await? {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    user.id,
+                    user.role,
+                    if user.disabled { "disabled" } else { "enabled" },
+                    user.name
+                );
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    Ok(())
+}
+
+async fn role(matches: &ArgMatches) -> anyhow::Result<()> {
+    match matches.subcommand() {
+        Some(("grant", sub_matches)) => {
+            if !starter_web::users::set_role(arg(sub_matches, "user"), arg(sub_matches, "role"))
+                .await?
+            {
+                println!("User already has this role");
+            }
+        }
+        Some(("revoke", sub_matches)) => {
+            if !starter_web::users::revoke_role(arg(sub_matches, "user"), arg(sub_matches, "role"))
+                .await?
+            {
+                println!("User doesn't have this role");
+            }
+        }
         _ => unreachable!(),
     };
+
+    Ok(())
+}
+
+/// Prints new secrets as the sections of the config file they belong to, or
+/// appends them to the `env` file as the `STARTER_` variables of their key.
+fn secrets(env: Option<&Path>) -> anyhow::Result<()> {
+    let secrets = starter_web::secrets::generate()?;
+
+    let Some(env) = env else {
+        for (key, value) in secrets {
+            let (section, name) = key.split_once('.').unwrap_or(("", key));
+
+            println!("[{section}]\n{name} = \"{value}\"\n");
+        }
+
+        return Ok(());
+    };
+
+    // Keeps the last line of the file, if it isn't ended, apart.
+    let separator = match std::fs::read(env) {
+        Ok(content) if !content.is_empty() && !content.ends_with(b"\n") => "\n",
+        _ => "",
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(env)?;
+
+    write!(file, "{separator}")?;
+
+    for (key, value) in secrets {
+        writeln!(file, "STARTER_{}={value}", key.to_uppercase())?;
+    }
+
+    println!("{}", env.display());
+
+    Ok(())
 }
@@ -0,0 +1,33 @@
+[package]
+name = "starter-__name__"
+version = "__version__"
+edition = "2021"
+license = "AGPL-3.0"
+description = "__Name__ module for timada starter"
+repository = "https://github.com/timayz/starter"
+homepage = "https://timada.co"
+
+[dependencies]
+evento = { version = "0.10.2", features = ["pg"] }
+evento-query = { version = "0.10.2", features = ["pg"] }
+anyhow = "1.0.80"
+ulid = "1.1.2"
+parse-display = "0.9.0"
+serde = "1.0.197"
+validator = { version = "0.16.1", features = ["derive"] }
+tracing = "0.1.40"
+sqlx = { version = "0.7.3", features = [ "runtime-tokio", "tls-rustls", "postgres", "chrono", "uuid", "json" ] }
+chrono = { version = "0.4.34", default-features = false, features = ["clock", "serde"] }
+async-trait = "0.1.77"
+
+[dependencies.uuid]
+version = "1.7.0"
+features = [
+	"v4",
+	"fast-rng",
+	"macro-diagnostics",
+]
+
+[dev-dependencies]
+starter-test = { path = "../test", default-features = false }
+tokio = { version = "1.36.0", features = ["rt-multi-thread", "macros"] }
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS __name___entries
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    title VARCHAR(100) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON __name___entries (user_id, created_at DESC);
@@ -0,0 +1,47 @@
+use evento::{
+    store::{Applier, Event},
+    Aggregate,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{__Name__Created, __Name__Event, __Name__Metadata};
+
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct __Name__ {
+    pub title: String,
+    pub user_id: Uuid,
+    pub deleted: bool,
+}
+
+impl Applier for __Name__ {
+    fn apply(&mut self, event: &Event) {
+        let Ok(__name___event) = event.name.parse() else {
+            warn!(
+                "__Name__Event.{} not handled by __Name__ aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match __name___event {
+            __Name__Event::Created => {
+                let (data, metadata) = match (
+                    event.to_data::<__Name__Created>(),
+                    event.to_metadata::<__Name__Metadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("__Name__.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.title = data.title;
+                self.user_id = metadata.req_user;
+            }
+            __Name__Event::Deleted => self.deleted = true,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use evento::{Command, CommandError, CommandHandler, CommandOutput};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use ulid::Ulid;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{__Name__, __Name__Created, __Name__Deleted};
+
+#[derive(Deserialize, Serialize)]
+pub struct __Name__Metadata {
+    pub req_id: String,
+    pub req_user: Uuid,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct Create__Name__Input {
+    #[validate(length(min = 3, max = 100))]
+    pub title: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for Create__Name__Input {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+
+        let events = cmd
+            .write(Ulid::new())
+            .metadata(__Name__Metadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(__Name__Created {
+                title: self.title.to_owned(),
+            })?
+            .commit::<__Name__>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Deletes a __name__ of the user, nothing being written for one already
+/// deleted.
+#[derive(Deserialize, Validate)]
+pub struct Delete__Name__Input {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for Delete__Name__Input {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+
+        let Some((__name__, version)) = cmd
+            .load::<__Name__>(self.id.to_owned())
+            .await?
+            .filter(|(__name__, _)| __name__.user_id == req_user)
+        else {
+            return Err(CommandError::NotFound(format!(
+                "__name__ {} not found",
+                self.id
+            )));
+        };
+
+        if __name__.deleted {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(__Name__Metadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(__Name__Deleted {})?
+            .commit::<__Name__>()
+            .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,18 @@
+use evento::PublisherEvent;
+use parse_display::{Display, FromStr};
+use serde::{Deserialize, Serialize};
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum __Name__Event {
+    Created,
+    Deleted,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct __Name__Created {
+    pub title: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct __Name__Deleted {}
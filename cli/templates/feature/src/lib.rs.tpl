@@ -0,0 +1,9 @@
+mod aggregate;
+mod command;
+mod event;
+mod query;
+
+pub use aggregate::*;
+pub use command::*;
+pub use event::*;
+pub use query::*;
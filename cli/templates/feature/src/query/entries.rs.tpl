@@ -0,0 +1,84 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{__Name__, __Name__Created, __Name__Event, __Name__Metadata};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct User__Name__ {
+    pub id: String,
+    pub user_id: Uuid,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct EntriesHandler;
+
+#[async_trait]
+impl RuleHandler for EntriesHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: __Name__Event = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<__Name__Metadata>()? else {
+            return Ok(());
+        };
+
+        let id = __Name__::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            __Name__Event::Created => {
+                let data: __Name__Created = event.to_data()?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO __name___entries (id, user_id, title, created_at)
+                    VALUES ( $1, $2, $3, $4 )
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(&id)
+                .bind(metadata.req_user)
+                .bind(&data.title)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            __Name__Event::Deleted => {
+                sqlx::query("DELETE FROM __name___entries WHERE id = $1")
+                    .bind(&id)
+                    .execute(&db)
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Entries of a user, last created first.
+#[derive(Deserialize)]
+pub struct List__Name__EntriesInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for List__Name__EntriesInput {
+    type Output = Vec<User__Name__>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, User__Name__>(
+            "SELECT * FROM __name___entries WHERE user_id = $1::uuid ORDER BY created_at DESC, id",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,21 @@
+mod entries;
+
+pub use entries::*;
+use evento::Rule;
+use parse_display::{Display, FromStr};
+
+#[derive(Display, FromStr)]
+#[display(style = "kebab-case")]
+pub enum __Name__Rule {
+    Entries,
+}
+
+impl From<__Name__Rule> for String {
+    fn from(value: __Name__Rule) -> Self {
+        value.to_string()
+    }
+}
+
+pub fn rules() -> Vec<Rule> {
+    vec![Rule::new(__Name__Rule::Entries).handler("__name__/**", EntriesHandler)]
+}
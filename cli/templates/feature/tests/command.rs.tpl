@@ -0,0 +1,76 @@
+mod common;
+
+use evento::{Aggregate, Command};
+use starter___name__::{Create__Name__Input, Delete__Name__Input, __Name__};
+use uuid::Uuid;
+
+use crate::common::get_producer;
+
+async fn command() -> Command {
+    Command::new(&get_producer().await.clone())
+}
+
+#[tokio::test]
+async fn create() {
+    let cmd = command().await;
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &Create__Name__Input {
+                title: "a".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &Create__Name__Input {
+                title: "aze".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "created");
+}
+
+#[tokio::test]
+async fn delete() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &Create__Name__Input {
+                title: "aze".into(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let input = Delete__Name__Input {
+        id: __Name__::from_aggregate_id(&events[0].aggregate_id),
+        user_id: Uuid::new_v4().to_string(),
+        request_id: None,
+    };
+
+    let result = cmd.execute("en".to_owned(), &input).await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let input = Delete__Name__Input { user_id, ..input };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "deleted");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
@@ -0,0 +1,17 @@
+use evento::{PgConsumer, Producer};
+use tokio::sync::OnceCell;
+
+static ONCE: OnceCell<Producer> = OnceCell::const_new();
+
+pub async fn get_producer() -> &'static Producer {
+    ONCE.get_or_init(|| async {
+        let pool = starter_test::db::schema().await.connect().await;
+
+        PgConsumer::new(&pool)
+            .rules(starter___name__::rules())
+            .start(0)
+            .await
+            .unwrap()
+    })
+    .await
+}
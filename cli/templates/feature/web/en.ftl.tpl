@@ -0,0 +1,7 @@
+
+pages___name__-EntriesPage_title = Title
+pages___name__-EntriesPage_create = Create
+pages___name__-EntriesPage_created = __Name__ created.
+pages___name__-EntriesPage_deleted = __Name__ deleted.
+pages___name__-EntriesPage_delete = Delete
+pages___name__-EntriesPage_empty = Nothing yet.
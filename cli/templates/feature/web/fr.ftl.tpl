@@ -0,0 +1,7 @@
+
+pages___name__-EntriesPage_title = Titre
+pages___name__-EntriesPage_create = Créer
+pages___name__-EntriesPage_created = __Name__ créé.
+pages___name__-EntriesPage_deleted = __Name__ supprimé.
+pages___name__-EntriesPage_delete = Supprimer
+pages___name__-EntriesPage_empty = Rien pour l'instant.
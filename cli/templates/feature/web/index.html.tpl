@@ -0,0 +1,27 @@
+{% extends "_layout.html" %}
+{% import "_forms.html" as forms %}
+
+{% block title %}{{ ctx.t("pages-routes___name__") }}{% endblock %}
+
+{% block content %}
+{{ breadcrumbs|safe }}
+<h1 class="text-2xl mb-4">{{ ctx.t("pages-routes___name__") }}</h1>
+<form class="flex items-end gap-2 mb-8" method="post" action="{{ ctx.create_url("/__name__") }}">
+    {% call forms::text_input("title", ctx.t("pages___name__-EntriesPage_title"), title, true, errors) %}
+    <button class="btn btn-primary" type="submit">{{ ctx.t("pages___name__-EntriesPage_create") }}</button>
+</form>
+{% if entries.is_empty() %}
+<p class="text-center opacity-70 my-16" role="status">{{ ctx.t("pages___name__-EntriesPage_empty") }}</p>
+{% else %}
+<ol class="flex flex-col gap-4">
+    {% for entry in entries %}
+    <li class="flex items-center justify-between gap-4 border-b pb-4">
+        <div class="truncate">{{ entry.title }}</div>
+        <form method="post" action="{{ ctx.create_url(format!("/__name__/{}/delete", entry.id)) }}">
+            <button class="btn btn-sm btn-error btn-outline" type="submit">{{ ctx.t("pages___name__-EntriesPage_delete") }}</button>
+        </form>
+    </li>
+    {% endfor %}
+</ol>
+{% endif %}
+{% endblock %}
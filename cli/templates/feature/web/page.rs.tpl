@@ -0,0 +1,132 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use evento::Rule;
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use starter___name__::{Create__Name__Input, Delete__Name__Input, List__Name__EntriesInput, User__Name__};
+use std::collections::HashMap;
+
+use crate::{
+    components::Breadcrumbs,
+    context::UserContext,
+    extract::{Form, Path},
+    flash::Flash,
+    module::FeatureModule,
+    pages::RouteMeta,
+    routes::{on, Routes},
+};
+
+/// __Name__ entries of the signed in users.
+pub struct __Name__Module;
+
+impl FeatureModule for __Name__Module {
+    fn name(&self) -> &'static str {
+        "__name__"
+    }
+
+    fn routes(&self) -> Routes {
+        Routes::new().nest("/__name__", create_router())
+    }
+
+    fn rules(&self) -> Vec<Rule> {
+        starter___name__::rules()
+    }
+
+    fn nav(&self) -> Vec<RouteMeta> {
+        vec![RouteMeta {
+            name: "__name__",
+            path: "/__name__",
+            title: "pages-routes___name__",
+            parent: Some("index"),
+        }]
+    }
+}
+
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/", on!(User get(entries), User post(create)))
+        .route("/:id/delete", on!(User post(delete)))
+}
+
+#[derive(Template)]
+#[template(path = "__name__/index.html")]
+pub struct EntriesTemplate {
+    ctx: UserContext,
+    entries: Vec<User__Name__>,
+    title: String,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+async fn template(
+    ctx: UserContext,
+    title: String,
+    errors: HashMap<String, Vec<String>>,
+) -> Result<EntriesTemplate, Response> {
+    let entries = ctx
+        .query(List__Name__EntriesInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?;
+
+    Ok(EntriesTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "__name__", &[], None),
+        ctx,
+        entries,
+        title,
+        errors,
+    })
+}
+
+pub async fn entries(ctx: UserContext) -> Result<EntriesTemplate, Response> {
+    template(ctx, "".to_owned(), Default::default()).await
+}
+
+#[derive(Deserialize)]
+pub struct EntryInput {
+    pub title: String,
+}
+
+pub async fn create(
+    ctx: UserContext,
+    Form(input): Form<EntryInput>,
+) -> Result<Response, Response> {
+    if let Some(errors) = ctx
+        .execute(Create__Name__Input {
+            title: input.title.trim().to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: None,
+        })
+        .await?
+    {
+        let template = template(ctx, input.title, errors).await?;
+
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, template).into_response());
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages___name__-EntriesPage_created"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/__name__"))).into_response())
+}
+
+pub async fn delete(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(Delete__Name__Input {
+            id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: None,
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages___name__-EntriesPage_deleted"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/__name__"))).into_response())
+}
@@ -0,0 +1 @@
+-- Revert __name__
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS __name__
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NULL
+);
+
+CREATE INDEX ON __name__ (user_id, created_at DESC);
@@ -0,0 +1 @@
+-- __name__
@@ -0,0 +1,159 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use evento::{Command, PgConsumer, Producer, Query};
+use sqlx::PgPool;
+use starter_feed::{CreateFeedInput, Feed, ListFeedsInput, ReactFeedInput, REACTIONS};
+use std::time::Duration;
+use tokio::{runtime::Runtime, time::sleep};
+use uuid::Uuid;
+
+/// Feeds listed by the pagination benchmarks, five pages of `PAGE_SIZE`.
+const FEEDS: usize = 100;
+const PAGE_SIZE: u16 = 20;
+
+/// Reactions toggled on the feed replayed, one event each.
+const REACTED: usize = 500;
+
+struct Env {
+    pool: PgPool,
+    producer: Producer,
+}
+
+async fn env() -> Env {
+    let pool = starter_test::db::schema().await.connect().await;
+    let producer = PgConsumer::new(&pool)
+        .data(starter_feed::ConsumerGate::default())
+        .rules(starter_feed::rules())
+        .start(0)
+        .await
+        .unwrap();
+
+    Env { pool, producer }
+}
+
+async fn create_feed(cmd: &Command, user_id: &str, title: String) -> String {
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title,
+                content: Some("# Hello\n\nworld".into()),
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    Feed::from_aggregate_id(&events[0].aggregate_id)
+}
+
+fn list(after: Option<evento_query::CursorType>) -> ListFeedsInput {
+    ListFeedsInput {
+        first: Some(PAGE_SIZE),
+        after,
+        last: None,
+        before: None,
+        tag: None,
+        author: None,
+        unpinned: false,
+    }
+}
+
+/// First and last pages of the feeds, once all of them are projected.
+fn pagination(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let (query, last_cursor) = runtime.block_on(async {
+        let env = env().await;
+        let cmd = Command::new(&env.producer);
+        let user_id = Uuid::new_v4().to_string();
+
+        for index in 0..FEEDS {
+            create_feed(&cmd, &user_id, format!("Feed {index}")).await;
+        }
+
+        let query = Query::new().data(env.pool.clone());
+
+        loop {
+            let all = query
+                .execute(&ListFeedsInput {
+                    first: Some(FEEDS as u16),
+                    ..list(None)
+                })
+                .await
+                .unwrap();
+
+            if all.edges.len() == FEEDS {
+                break;
+            }
+
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        let mut cursor = None;
+
+        for _ in 1..FEEDS / PAGE_SIZE as usize {
+            cursor = query
+                .execute(&list(cursor))
+                .await
+                .unwrap()
+                .page_info
+                .end_cursor;
+        }
+
+        (query, cursor)
+    });
+
+    let mut group = c.benchmark_group("feeds");
+
+    group.bench_function("first_page", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(query.execute(&list(None)).await.unwrap()) })
+    });
+
+    group.bench_function("last_page", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(query.execute(&list(last_cursor.clone())).await.unwrap()) })
+    });
+
+    group.finish();
+}
+
+/// Loading of a feed from its `REACTED` + 1 events.
+fn replay(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let (cmd, feed_id) = runtime.block_on(async {
+        let env = env().await;
+        let cmd = Command::new(&env.producer);
+        let user_id = Uuid::new_v4().to_string();
+        let feed_id = create_feed(&cmd, &user_id, "Replayed".to_owned()).await;
+
+        for index in 0..REACTED {
+            cmd.execute(
+                "en".to_owned(),
+                &ReactFeedInput {
+                    feed_id: feed_id.to_owned(),
+                    reaction: REACTIONS[index % REACTIONS.len()].to_owned(),
+                    user_id: user_id.to_owned(),
+                    request_id: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        (cmd, feed_id)
+    });
+
+    c.bench_function("replay/feed", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(cmd.load::<Feed>(feed_id.to_owned()).await.unwrap()) })
+    });
+}
+
+criterion_group!(benches, pagination, replay);
+criterion_main!(benches);
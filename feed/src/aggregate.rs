@@ -1,16 +1,43 @@
-use crate::FeedEvent;
+use crate::{
+    BillingEvent, CommentEvent, DeliveryEvent, DraftEvent, ExportEvent, ExternalEvent, FeedEvent,
+    FeedMetadata, FollowerEvent, PinboardEvent, PreferencesEvent, UserEvent, WebhookEvent,
+};
 
-use super::event::Created;
+use super::event::{
+    Archived, Attached, CommentCreated, CommentEdited, Created, DeliveryFailed, DeliverySucceeded,
+    DraftPublished, DraftSaved, Edited, EmailSet, ExportRequested, ExternalReceived, Followed,
+    MutedNotification, NotificationsUpdated, Pinned, Reacted, SubscriptionUpdated, Tagged, Trashed,
+    Unfollowed, Unpinned, Unreacted, Untagged, UserCreated, UserRoleSet, WebhookRegistered,
+};
+use chrono::{DateTime, Utc};
 use evento::{
     store::{Applier, Event},
     Aggregate,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tracing::{error, warn};
+use uuid::Uuid;
 
 #[derive(Default, Serialize, Deserialize, Aggregate)]
 pub struct Feed {
     pub title: String,
+    pub content: String,
+    pub user_id: Uuid,
+    pub visibility: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub attachments: Vec<String>,
+    pub reactions: HashSet<(Uuid, String)>,
+    pub reporters: HashSet<Uuid>,
+    pub hidden: bool,
+    pub archived: bool,
+    pub delete_at: Option<DateTime<Utc>>,
+    pub deleted: bool,
+    /// Request id of the last bulk operation on the feed, undoable until
+    /// `undo_until`.
+    pub batch_id: Option<String>,
+    pub undo_until: Option<DateTime<Utc>>,
 }
 
 impl Applier for Feed {
@@ -22,7 +49,54 @@ impl Applier for Feed {
 
         match feed_event {
             FeedEvent::Created => {
-                let data = match event.to_data::<Created>() {
+                let (data, metadata) = match (
+                    event.to_data::<Created>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Feed.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.title = data.title;
+                self.content = data.content;
+                self.user_id = metadata.req_user;
+                self.visibility = data.visibility;
+                self.publish_at = data.publish_at;
+                self.tags = data.tags;
+            }
+            FeedEvent::Reacted => {
+                let (data, metadata) = match (
+                    event.to_data::<Reacted>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Feed.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.reactions.insert((metadata.req_user, data.reaction));
+            }
+            FeedEvent::Unreacted => {
+                let (data, metadata) = match (
+                    event.to_data::<Unreacted>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Feed.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.reactions.remove(&(metadata.req_user, data.reaction));
+            }
+            FeedEvent::Tagged => {
+                let data = match event.to_data::<Tagged>() {
                     Ok(data) => data,
                     Err(e) => {
                         error!("Feed.apply {} {}", event.name, e);
@@ -30,7 +104,601 @@ impl Applier for Feed {
                     }
                 };
 
+                self.tags.push(data.tag);
+            }
+            FeedEvent::Untagged => {
+                let data = match event.to_data::<Untagged>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Feed.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.tags.retain(|tag| tag != &data.tag);
+            }
+            FeedEvent::Attached => {
+                let data = match event.to_data::<Attached>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Feed.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.attachments.push(data.key);
+            }
+            FeedEvent::Edited => {
+                let data = match event.to_data::<Edited>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Feed.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.title = data.title;
+                self.content = data.content;
+            }
+            FeedEvent::Reported => {
+                let Ok(Some(metadata)) = event.to_metadata::<FeedMetadata>() else {
+                    error!("Feed.apply {} invalid metadata", event.name);
+                    return;
+                };
+
+                self.reporters.insert(metadata.req_user);
+            }
+            FeedEvent::Hidden => {
+                self.hidden = true;
+            }
+            FeedEvent::Restored => {
+                self.hidden = false;
+            }
+            FeedEvent::Published => {
+                self.publish_at = None;
+            }
+            FeedEvent::Mentioned => {}
+            FeedEvent::Archived => {
+                let (data, metadata) = match (
+                    event.to_data::<Archived>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Feed.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.archived = true;
+                self.batch_id = Some(metadata.req_id);
+                self.undo_until = Some(data.undo_until);
+            }
+            FeedEvent::Trashed => {
+                let (data, metadata) = match (
+                    event.to_data::<Trashed>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Feed.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.delete_at = Some(data.delete_at);
+                self.batch_id = Some(metadata.req_id);
+                self.undo_until = Some(data.delete_at);
+            }
+            FeedEvent::Unarchived | FeedEvent::Untrashed => {
+                if matches!(feed_event, FeedEvent::Unarchived) {
+                    self.archived = false;
+                } else {
+                    self.delete_at = None;
+                }
+
+                self.batch_id = None;
+                self.undo_until = None;
+            }
+            FeedEvent::Deleted => {
+                self.deleted = true;
+            }
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Comment {
+    pub feed_id: String,
+    pub user_id: Uuid,
+    pub content: String,
+    pub deleted: bool,
+}
+
+impl Applier for Comment {
+    fn apply(&mut self, event: &Event) {
+        let Ok(comment_event) = event.name.parse() else {
+            warn!(
+                "CommentEvent.{} not handled by Comment aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match comment_event {
+            CommentEvent::Created => {
+                let (data, metadata) = match (
+                    event.to_data::<CommentCreated>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Comment.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.feed_id = data.feed_id;
+                self.user_id = metadata.req_user;
+                self.content = data.content;
+            }
+            CommentEvent::Edited => {
+                let data = match event.to_data::<CommentEdited>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Comment.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.content = data.content;
+            }
+            CommentEvent::Deleted => {
+                self.deleted = true;
+            }
+            CommentEvent::Mentioned => {}
+        }
+    }
+}
+
+/// Users followed by the user of the aggregate id.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Follower {
+    pub following: HashSet<Uuid>,
+}
+
+impl Applier for Follower {
+    fn apply(&mut self, event: &Event) {
+        let Ok(follower_event) = event.name.parse() else {
+            warn!(
+                "FollowerEvent.{} not handled by Follower aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match follower_event {
+            FollowerEvent::Followed => {
+                let data = match event.to_data::<Followed>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Follower.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.following.insert(data.user_id);
+            }
+            FollowerEvent::Unfollowed => {
+                let data = match event.to_data::<Unfollowed>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Follower.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.following.remove(&data.user_id);
+            }
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Draft {
+    pub user_id: Uuid,
+    pub title: String,
+    pub content: String,
+    /// Set once published, a draft being closed once published or discarded.
+    pub feed_id: Option<String>,
+    pub discarded: bool,
+}
+
+impl Draft {
+    pub fn is_open(&self) -> bool {
+        self.feed_id.is_none() && !self.discarded
+    }
+}
+
+impl Applier for Draft {
+    fn apply(&mut self, event: &Event) {
+        let Ok(draft_event) = event.name.parse() else {
+            warn!("DraftEvent.{} not handled by Draft aggregate", event.name);
+            return;
+        };
+
+        match draft_event {
+            DraftEvent::Saved => {
+                let (data, metadata) = match (
+                    event.to_data::<DraftSaved>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Draft.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.user_id = metadata.req_user;
                 self.title = data.title;
+                self.content = data.content;
+            }
+            DraftEvent::Published => {
+                let data = match event.to_data::<DraftPublished>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Draft.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.feed_id = Some(data.feed_id);
+            }
+            DraftEvent::Discarded => {
+                self.discarded = true;
+            }
+        }
+    }
+}
+
+/// Pinned feeds of an author, oldest pin first.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Pinboard {
+    pub feed_ids: Vec<String>,
+}
+
+impl Applier for Pinboard {
+    fn apply(&mut self, event: &Event) {
+        let Ok(pinboard_event) = event.name.parse() else {
+            warn!(
+                "PinboardEvent.{} not handled by Pinboard aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match pinboard_event {
+            PinboardEvent::Pinned => {
+                let data = match event.to_data::<Pinned>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Pinboard.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.feed_ids.push(data.feed_id);
+            }
+            PinboardEvent::Unpinned => {
+                let data = match event.to_data::<Unpinned>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Pinboard.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.feed_ids.retain(|feed_id| feed_id != &data.feed_id);
+            }
+        }
+    }
+}
+
+/// Archive of the feeds, comments and reactions of a user, generated in the
+/// background once requested.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Export {
+    pub user_id: Uuid,
+    pub format: String,
+}
+
+impl Applier for Export {
+    fn apply(&mut self, event: &Event) {
+        let Ok(export_event) = event.name.parse() else {
+            warn!("ExportEvent.{} not handled by Export aggregate", event.name);
+            return;
+        };
+
+        match export_event {
+            ExportEvent::Requested => {
+                let (data, metadata) = match (
+                    event.to_data::<ExportRequested>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Export.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.user_id = metadata.req_user;
+                self.format = data.format;
+            }
+        }
+    }
+}
+
+/// Settings of a user, keyed by their id.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Preferences {
+    pub muted: Vec<MutedNotification>,
+    pub email: Option<String>,
+    pub email_token: String,
+    pub email_verified: bool,
+}
+
+impl Applier for Preferences {
+    fn apply(&mut self, event: &Event) {
+        let Ok(preferences_event) = event.name.parse() else {
+            warn!(
+                "PreferencesEvent.{} not handled by Preferences aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match preferences_event {
+            PreferencesEvent::NotificationsUpdated => {
+                let data = match event.to_data::<NotificationsUpdated>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Preferences.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.muted = data.muted;
+            }
+            PreferencesEvent::EmailSet => {
+                let data = match event.to_data::<EmailSet>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Preferences.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.email = Some(data.email);
+                self.email_token = data.token;
+                self.email_verified = false;
+            }
+            PreferencesEvent::EmailVerified => self.email_verified = true,
+        }
+    }
+}
+
+/// Url of a user receiving their new public feeds.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Webhook {
+    pub user_id: Uuid,
+    pub url: String,
+    pub removed: bool,
+}
+
+impl Applier for Webhook {
+    fn apply(&mut self, event: &Event) {
+        let Ok(webhook_event) = event.name.parse() else {
+            warn!(
+                "WebhookEvent.{} not handled by Webhook aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match webhook_event {
+            WebhookEvent::Registered => {
+                let (data, metadata) = match (
+                    event.to_data::<WebhookRegistered>(),
+                    event.to_metadata::<FeedMetadata>(),
+                ) {
+                    (Ok(data), Ok(Some(metadata))) => (data, metadata),
+                    _ => {
+                        error!("Webhook.apply {} invalid data or metadata", event.name);
+                        return;
+                    }
+                };
+
+                self.user_id = metadata.req_user;
+                self.url = data.url;
+            }
+            WebhookEvent::Removed => self.removed = true,
+        }
+    }
+}
+
+/// Delivery of an event to a webhook, keyed by the webhook id and the
+/// subject of the event.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Delivery {
+    pub attempts: u16,
+    /// Attempts made before the last requeue, the others counting towards
+    /// `MAX_WEBHOOK_ATTEMPTS`.
+    pub requeued_attempts: u16,
+    pub delivered: bool,
+    /// Unset once delivered or out of attempts.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl Applier for Delivery {
+    fn apply(&mut self, event: &Event) {
+        let Ok(delivery_event) = event.name.parse() else {
+            warn!(
+                "DeliveryEvent.{} not handled by Delivery aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match delivery_event {
+            DeliveryEvent::Failed => {
+                let data = match event.to_data::<DeliveryFailed>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Delivery.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.attempts = data.attempt;
+                self.next_attempt_at = data.next_attempt_at;
+            }
+            DeliveryEvent::Succeeded => {
+                let data = match event.to_data::<DeliverySucceeded>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Delivery.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.attempts = data.attempt;
+                self.delivered = true;
+                self.next_attempt_at = None;
+            }
+            DeliveryEvent::Requeued => {
+                self.requeued_attempts = self.attempts;
+                self.next_attempt_at = None;
+            }
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct User {
+    pub name: String,
+    pub role: String,
+    pub disabled: bool,
+}
+
+impl Applier for User {
+    fn apply(&mut self, event: &Event) {
+        let Ok(user_event) = event.name.parse() else {
+            warn!("UserEvent.{} not handled by User aggregate", event.name);
+            return;
+        };
+
+        match user_event {
+            UserEvent::Created => {
+                let data = match event.to_data::<UserCreated>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("User.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.name = data.name;
+                self.role = data.role;
+            }
+            UserEvent::Disabled => self.disabled = true,
+            UserEvent::RoleSet => {
+                let data = match event.to_data::<UserRoleSet>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("User.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.role = data.role;
+            }
+        }
+    }
+}
+
+/// Subscription of a user to the plan, keyed by their id and kept in sync
+/// with Stripe by its webhook.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct Billing {
+    pub customer_id: String,
+    pub subscription_id: String,
+    pub status: String,
+    pub current_period_end: Option<DateTime<Utc>>,
+    /// Creation of the last Stripe event applied, older ones arriving late
+    /// being ignored.
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+impl Applier for Billing {
+    fn apply(&mut self, event: &Event) {
+        let Ok(billing_event) = event.name.parse() else {
+            warn!(
+                "BillingEvent.{} not handled by Billing aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match billing_event {
+            BillingEvent::SubscriptionUpdated => {
+                let data = match event.to_data::<SubscriptionUpdated>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Billing.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.customer_id = data.customer_id;
+                self.subscription_id = data.subscription_id;
+                self.status = data.status;
+                self.current_period_end = data.current_period_end;
+                self.changed_at = Some(data.changed_at);
+            }
+        }
+    }
+}
+
+/// Message of the event bus ingested by the bridge, keyed by its id there so
+/// that a message delivered again is received once.
+#[derive(Default, Serialize, Deserialize, Aggregate)]
+pub struct External {
+    pub subject: String,
+}
+
+impl Applier for External {
+    fn apply(&mut self, event: &Event) {
+        let Ok(external_event) = event.name.parse() else {
+            warn!(
+                "ExternalEvent.{} not handled by External aggregate",
+                event.name
+            );
+            return;
+        };
+
+        match external_event {
+            ExternalEvent::Received => {
+                let data = match event.to_data::<ExternalReceived>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("External.apply {} {}", event.name, e);
+                        return;
+                    }
+                };
+
+                self.subject = data.subject;
             }
         }
     }
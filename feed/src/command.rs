@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use evento::{Command, CommandHandler, CommandOutput};
+use chrono::{DateTime, Duration, Utc};
+use evento::{Command, CommandError, CommandHandler, CommandOutput};
 use fake::{
     faker::company::en::Buzzword,
     faker::lorem::en::{Paragraph, Sentence},
@@ -7,12 +8,124 @@ use fake::{
 };
 use rand::{seq::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    str::FromStr,
+};
 use ulid::Ulid;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    default_visibility, webhook, Archived, Attached, Billing, Comment, CommentCreated,
+    CommentDeleted, CommentEdited, CommentMentioned, Created, Deleted, Delivery, DeliveryFailed,
+    DeliveryRequeued, DeliverySucceeded, Draft, DraftDiscarded, DraftPublished, DraftSaved, Edited,
+    EmailSet, EmailVerified, Export, ExportRequested, External, ExternalReceived, Feed, Followed,
+    Follower, Hidden, Mentioned, MutedNotification, NotificationsUpdated, Pinboard, Pinned,
+    Preferences, Published, Reacted, Reported, Restored, SubscriptionUpdated, Tagged, Trashed,
+    Unarchived, Unfollowed, Unpinned, Unreacted, Untagged, Untrashed, User, UserCreated,
+    UserDisabled, UserRoleSet, Webhook, WebhookRegistered, WebhookRemoved, NOTIFICATION_MENTION,
+};
+
+/// Reactions a user can toggle on a feed.
+pub const REACTIONS: [&str; 6] = ["👍", "❤️", "😂", "😮", "😢", "🎉"];
+
+/// Maximum number of tags of a feed.
+pub const MAX_TAGS: usize = 10;
+
+/// Maximum number of images attached to a feed.
+pub const MAX_ATTACHMENTS: usize = 4;
+
+/// Feeds listed everywhere.
+pub const VISIBILITY_PUBLIC: &str = "public";
+
+/// Feeds only reachable by their link.
+pub const VISIBILITY_UNLISTED: &str = "unlisted";
+
+/// Feeds only visible to their author.
+pub const VISIBILITY_PRIVATE: &str = "private";
+
+/// Who a feed is visible to, from `CreateFeedInput::visibility`.
+pub const VISIBILITIES: [&str; 3] = [VISIBILITY_PUBLIC, VISIBILITY_UNLISTED, VISIBILITY_PRIVATE];
+
+/// Maximum number of users notified of a mention by a feed or a comment.
+pub const MAX_MENTIONS: usize = 10;
+
+/// Reasons a user can report a feed for.
+pub const REPORT_REASONS: [&str; 4] = ["spam", "harassment", "inappropriate", "other"];
+
+/// Feeds archived or deleted by a single bulk operation at most.
+pub const MAX_BULK_FEEDS: usize = 50;
+
+/// Seconds a bulk operation can be undone for, trashed feeds being deleted
+/// afterwards.
+pub const UNDO_WINDOW_SECS: i64 = 30;
+
+/// Kind of the notifications of a reporter once a moderator hid or restored
+/// the feed.
+pub const NOTIFICATION_MODERATION: &str = "moderation";
+
+/// Kind of the notifications of a user once their export is ready.
+pub const NOTIFICATION_EXPORT: &str = "export";
 
-use crate::{Created, Feed};
+/// Kinds of notifications a user can mute.
+pub const NOTIFICATION_KINDS: [&str; 3] = [
+    NOTIFICATION_MENTION,
+    NOTIFICATION_MODERATION,
+    NOTIFICATION_EXPORT,
+];
+
+/// Notifications listed on the notifications page and toasted.
+pub const CHANNEL_IN_APP: &str = "in_app";
+
+/// Notifications emailed to the verified address of the user.
+pub const CHANNEL_EMAIL: &str = "email";
+
+/// Notifications pushed to the browsers the user subscribed, see
+/// `PushSubscriptions`.
+pub const CHANNEL_PUSH: &str = "push";
+
+/// Channels a notification can be sent to.
+pub const NOTIFICATION_CHANNELS: [&str; 3] = [CHANNEL_IN_APP, CHANNEL_EMAIL, CHANNEL_PUSH];
+
+pub const EXPORT_FORMAT_JSON: &str = "json";
+
+pub const EXPORT_FORMAT_CSV: &str = "csv";
+
+/// Formats a user can export their data to.
+pub const EXPORT_FORMATS: [&str; 2] = [EXPORT_FORMAT_JSON, EXPORT_FORMAT_CSV];
+
+/// Statuses of a Stripe subscription.
+pub const SUBSCRIPTION_STATUSES: [&str; 8] = [
+    "incomplete",
+    "incomplete_expired",
+    "trialing",
+    "active",
+    "past_due",
+    "canceled",
+    "unpaid",
+    "paused",
+];
+
+/// Statuses of the subscriptions giving access to the paid features.
+pub const ACTIVE_SUBSCRIPTION_STATUSES: [&str; 2] = ["trialing", "active"];
+
+/// Attempts at delivering an event to a webhook before leaving it dead, for
+/// an admin to requeue.
+pub const MAX_WEBHOOK_ATTEMPTS: u16 = 5;
+
+/// Seconds before retrying a failed delivery, quadrupled after each attempt.
+pub const WEBHOOK_RETRY_DELAY_SECS: i64 = 30;
+
+const MAX_WEBHOOK_URL_LEN: usize = 2048;
+
+pub const ROLE_USER: &str = "user";
+
+/// Role granting the same access as being listed in the admins of the config.
+pub const ROLE_ADMIN: &str = "admin";
+
+pub const ROLES: [&str; 2] = [ROLE_USER, ROLE_ADMIN];
 
 #[derive(Deserialize, Serialize)]
 pub struct FeedMetadata {
@@ -24,6 +137,18 @@ pub struct FeedMetadata {
 pub struct CreateFeedInput {
     #[validate(length(min = 3, max = 100))]
     pub title: String,
+    #[validate(length(max = 10000))]
+    pub content: Option<String>,
+    /// Stored images, each one emitting an `attached` event after `created`.
+    #[serde(default)]
+    #[validate(custom = "validate_attachments")]
+    pub attachments: Vec<Attached>,
+    #[serde(default = "default_visibility")]
+    #[validate(custom = "validate_visibility")]
+    pub visibility: String,
+    /// Publishes the feed later, once `PublishFeedInput` runs after it.
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
     pub user_id: String,
     pub request_id: Option<String>,
 }
@@ -31,6 +156,16 @@ pub struct CreateFeedInput {
 #[async_trait]
 impl CommandHandler for CreateFeedInput {
     async fn handle(&self, cmd: &Command) -> CommandOutput {
+        if self
+            .publish_at
+            .is_some_and(|publish_at| publish_at <= Utc::now())
+        {
+            return Err(CommandError::Validation(HashMap::from([(
+                "publish_at".to_owned(),
+                vec!["a feed can only be scheduled in the future".to_owned()],
+            )])));
+        }
+
         let tags: Vec<String> = [
             Buzzword().fake(),
             Buzzword().fake(),
@@ -44,10 +179,22 @@ impl CommandHandler for CreateFeedInput {
         .into_iter()
         .collect::<Vec<_>>();
 
-        let events = cmd
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let content = self
+            .content
+            .to_owned()
+            .unwrap_or_else(|| Paragraph(50..100).fake());
+
+        // Private feeds can't be read by the users they mention.
+        let mentions = match self.visibility.as_str() {
+            VISIBILITY_PRIVATE => vec![],
+            _ => new_mentions(&content, None, req_user),
+        };
+
+        let mut writer = cmd
             .write(Ulid::new())
             .metadata(FeedMetadata {
-                req_user: Uuid::from_str(self.user_id.as_str())?,
+                req_user,
                 req_id: self
                     .request_id
                     .to_owned()
@@ -55,8 +202,335 @@ impl CommandHandler for CreateFeedInput {
             })?
             .event(Created {
                 title: Sentence(5..10).fake(),
-                content: Paragraph(50..100).fake(),
+                content,
                 tags,
+                visibility: self.visibility.to_owned(),
+                publish_at: self.publish_at,
+            })?;
+
+        for attachment in self.attachments.iter() {
+            writer = writer.event(attachment.clone())?;
+        }
+
+        if !mentions.is_empty() {
+            writer = writer.event(Mentioned { user_ids: mentions })?;
+        }
+
+        let events = writer.commit::<Feed>().await?;
+
+        Ok(events)
+    }
+}
+
+/// Mentions of `text`, written `@` followed by the id of the user, with their
+/// byte range.
+pub fn find_mentions(text: &str) -> Vec<(Range<usize>, Uuid)> {
+    text.match_indices('@')
+        .filter_map(|(start, _)| {
+            let end = start + 37;
+            let user_id = Uuid::parse_str(text.get(start + 1..end)?).ok()?;
+            let word_char = |c: char| c.is_alphanumeric() || c == '-';
+
+            if text[..start].chars().next_back().is_some_and(word_char)
+                || text[end..].chars().next().is_some_and(word_char)
+            {
+                return None;
+            }
+
+            Some((start..end, user_id))
+        })
+        .collect()
+}
+
+/// Users mentioned by `content` and not by `previous`, leaving out the
+/// author.
+fn new_mentions(content: &str, previous: Option<&str>, author: Uuid) -> Vec<Uuid> {
+    let previous = previous
+        .map(|previous| {
+            find_mentions(previous)
+                .into_iter()
+                .map(|(_, user_id)| user_id)
+                .collect::<HashSet<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut user_ids = vec![];
+
+    for (_, user_id) in find_mentions(content) {
+        if user_id != author && !previous.contains(&user_id) && !user_ids.contains(&user_id) {
+            user_ids.push(user_id);
+        }
+    }
+
+    user_ids.truncate(MAX_MENTIONS);
+
+    user_ids
+}
+
+fn validate_attachments(attachments: &[Attached]) -> Result<(), ValidationError> {
+    if attachments.len() > MAX_ATTACHMENTS {
+        return Err(ValidationError::new("attachments"));
+    }
+
+    Ok(())
+}
+
+fn validate_visibility(visibility: &str) -> Result<(), ValidationError> {
+    if !VISIBILITIES.contains(&visibility) {
+        return Err(ValidationError::new("visibility"));
+    }
+
+    Ok(())
+}
+
+fn validate_bulk_ids(ids: &[String]) -> Result<(), ValidationError> {
+    if ids.is_empty() || ids.len() > MAX_BULK_FEEDS {
+        return Err(ValidationError::new("ids"));
+    }
+
+    Ok(())
+}
+
+fn validate_muted_notifications(muted: &[MutedNotification]) -> Result<(), ValidationError> {
+    if muted.iter().any(|muted| {
+        !NOTIFICATION_KINDS.contains(&muted.kind.as_str())
+            || !NOTIFICATION_CHANNELS.contains(&muted.channel.as_str())
+    }) {
+        return Err(ValidationError::new("muted"));
+    }
+
+    Ok(())
+}
+
+fn validate_export_format(format: &str) -> Result<(), ValidationError> {
+    if !EXPORT_FORMATS.contains(&format) {
+        return Err(ValidationError::new("format"));
+    }
+
+    Ok(())
+}
+
+fn validate_subscription_status(status: &str) -> Result<(), ValidationError> {
+    if !SUBSCRIPTION_STATUSES.contains(&status) {
+        return Err(ValidationError::new("status"));
+    }
+
+    Ok(())
+}
+
+fn validate_report_reason(reason: &str) -> Result<(), ValidationError> {
+    if !REPORT_REASONS.contains(&reason) {
+        return Err(ValidationError::new("reason"));
+    }
+
+    Ok(())
+}
+
+fn validate_reaction(reaction: &str) -> Result<(), ValidationError> {
+    if !REACTIONS.contains(&reaction) {
+        return Err(ValidationError::new("reaction"));
+    }
+
+    Ok(())
+}
+
+/// Adds the reaction of the user to the feed, or removes it when the user
+/// already reacted with it.
+#[derive(Deserialize, Validate)]
+pub struct ReactFeedInput {
+    pub feed_id: String,
+    #[validate(custom = "validate_reaction")]
+    pub reaction: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for ReactFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.feed_id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        };
+
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let writer = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?;
+
+        let reaction = self.reaction.to_owned();
+        let writer = if feed.reactions.contains(&(req_user, reaction.to_owned())) {
+            writer.event(Unreacted { reaction })?
+        } else {
+            writer.event(Reacted { reaction })?
+        };
+
+        Ok(writer.commit::<Feed>().await?)
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<(), ValidationError> {
+    if tag.trim() != tag || tag.contains(['#', '/', '?', '&']) {
+        return Err(ValidationError::new("tag"));
+    }
+
+    Ok(())
+}
+
+/// Loads a feed that was created by `user_id`.
+async fn load_own_feed(
+    cmd: &Command,
+    id: &str,
+    user_id: Uuid,
+) -> Result<(Feed, u16), CommandError> {
+    match cmd.load::<Feed>(id.to_owned()).await? {
+        Some((feed, version)) if feed.user_id == user_id => Ok((feed, version)),
+        _ => Err(CommandError::NotFound(format!("feed {id} not found"))),
+    }
+}
+
+/// Replaces the title and content of a feed of the user, every edit being
+/// kept as an `edited` event. Nothing is written when they didn't change.
+#[derive(Deserialize, Validate)]
+pub struct EditFeedInput {
+    pub id: String,
+    #[validate(length(min = 3, max = 100))]
+    pub title: String,
+    #[validate(length(max = 10000))]
+    pub content: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for EditFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (feed, version) = load_own_feed(cmd, &self.id, req_user).await?;
+
+        if feed.title == self.title && feed.content == self.content {
+            return Ok(vec![]);
+        }
+
+        let mentions = match feed.visibility.as_str() {
+            VISIBILITY_PRIVATE => vec![],
+            _ => new_mentions(&self.content, Some(&feed.content), req_user),
+        };
+
+        let mut writer = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Edited {
+                title: self.title.to_owned(),
+                content: self.content.to_owned(),
+            })?;
+
+        if !mentions.is_empty() {
+            writer = writer.event(Mentioned { user_ids: mentions })?;
+        }
+
+        let events = writer.commit::<Feed>().await?;
+
+        Ok(events)
+    }
+}
+
+/// Adds a tag to a feed of the user, doing nothing when the feed already has
+/// it.
+#[derive(Deserialize, Validate)]
+pub struct TagFeedInput {
+    pub feed_id: String,
+    #[validate(length(min = 1, max = 30), custom = "validate_tag")]
+    pub tag: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for TagFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (feed, version) = load_own_feed(cmd, &self.feed_id, req_user).await?;
+
+        if feed.tags.contains(&self.tag) {
+            return Ok(vec![]);
+        }
+
+        if feed.tags.len() >= MAX_TAGS {
+            return Err(CommandError::Validation(HashMap::from([(
+                "tag".to_owned(),
+                vec![format!("a feed can't have more than {MAX_TAGS} tags")],
+            )])));
+        }
+
+        let events = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Tagged {
+                tag: self.tag.to_owned(),
+            })?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Removes a tag from a feed of the user, doing nothing when the feed doesn't
+/// have it.
+#[derive(Deserialize, Validate)]
+pub struct UntagFeedInput {
+    pub feed_id: String,
+    pub tag: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UntagFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (feed, version) = load_own_feed(cmd, &self.feed_id, req_user).await?;
+
+        if !feed.tags.contains(&self.tag) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Untagged {
+                tag: self.tag.to_owned(),
             })?
             .commit::<Feed>()
             .await?;
@@ -64,3 +538,1696 @@ impl CommandHandler for CreateFeedInput {
         Ok(events)
     }
 }
+
+#[derive(Deserialize, Validate)]
+pub struct CreateCommentInput {
+    pub feed_id: String,
+    pub parent_id: Option<String>,
+    #[validate(length(min = 1, max = 2000))]
+    pub content: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for CreateCommentInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, _)) = cmd.load::<Feed>(self.feed_id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        };
+
+        if let Some(parent_id) = &self.parent_id {
+            let parent = cmd.load::<Comment>(parent_id.to_owned()).await?;
+
+            if !parent.is_some_and(|(parent, _)| parent.feed_id == self.feed_id) {
+                return Err(CommandError::NotFound(format!(
+                    "comment {parent_id} not found"
+                )));
+            }
+        }
+
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let mentions = match feed.visibility.as_str() {
+            VISIBILITY_PRIVATE => vec![],
+            _ => new_mentions(&self.content, None, req_user),
+        };
+
+        let mut writer = cmd
+            .write(Ulid::new())
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(CommentCreated {
+                feed_id: self.feed_id.to_owned(),
+                parent_id: self.parent_id.to_owned(),
+                content: self.content.to_owned(),
+            })?;
+
+        if !mentions.is_empty() {
+            writer = writer.event(CommentMentioned {
+                feed_id: self.feed_id.to_owned(),
+                user_ids: mentions,
+            })?;
+        }
+
+        let events = writer.commit::<Comment>().await?;
+
+        Ok(events)
+    }
+}
+
+/// Loads a comment that is not deleted and was written by `user_id`.
+async fn load_own_comment(
+    cmd: &Command,
+    id: &str,
+    user_id: Uuid,
+) -> Result<(Comment, u16), CommandError> {
+    match cmd.load::<Comment>(id.to_owned()).await? {
+        Some((comment, version)) if !comment.deleted && comment.user_id == user_id => {
+            Ok((comment, version))
+        }
+        _ => Err(CommandError::NotFound(format!("comment {id} not found"))),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditCommentInput {
+    pub id: String,
+    #[validate(length(min = 1, max = 2000))]
+    pub content: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for EditCommentInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (comment, version) = load_own_comment(cmd, &self.id, req_user).await?;
+        let private = cmd
+            .load::<Feed>(comment.feed_id.to_owned())
+            .await?
+            .is_some_and(|(feed, _)| feed.visibility == VISIBILITY_PRIVATE);
+
+        let mentions = if private {
+            vec![]
+        } else {
+            new_mentions(&self.content, Some(&comment.content), req_user)
+        };
+
+        let mut writer = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(CommentEdited {
+                feed_id: comment.feed_id.to_owned(),
+                content: self.content.to_owned(),
+            })?;
+
+        if !mentions.is_empty() {
+            writer = writer.event(CommentMentioned {
+                feed_id: comment.feed_id,
+                user_ids: mentions,
+            })?;
+        }
+
+        let events = writer.commit::<Comment>().await?;
+
+        Ok(events)
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct DeleteCommentInput {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for DeleteCommentInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (comment, version) = load_own_comment(cmd, &self.id, req_user).await?;
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(CommentDeleted {
+                feed_id: comment.feed_id,
+            })?
+            .commit::<Comment>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Publishes a scheduled feed once its `publish_at` is due, on behalf of its
+/// author. Nothing is written for a feed that isn't scheduled or due yet.
+#[derive(Deserialize, Validate)]
+pub struct PublishFeedInput {
+    pub id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for PublishFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.id
+            )));
+        };
+
+        if !feed
+            .publish_at
+            .is_some_and(|publish_at| publish_at <= Utc::now())
+        {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: feed.user_id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Published {})?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Reports a feed to the moderators, a user reporting it only once.
+#[derive(Deserialize, Validate)]
+pub struct ReportFeedInput {
+    pub feed_id: String,
+    #[validate(custom = "validate_report_reason")]
+    pub reason: String,
+    pub lang: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for ReportFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.feed_id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        };
+
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+
+        if feed.reporters.contains(&req_user) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Reported {
+                reason: self.reason.to_owned(),
+                lang: self.lang.to_owned(),
+            })?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Hides a feed from every listing, `user_id` being the moderator.
+#[derive(Deserialize, Validate)]
+pub struct HideFeedInput {
+    pub feed_id: String,
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for HideFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.feed_id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        };
+
+        if feed.hidden {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: Uuid::from_str(self.user_id.as_str())?,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Hidden {
+                reason: self.reason.to_owned(),
+            })?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Shows a hidden feed again, `user_id` being the moderator.
+#[derive(Deserialize, Validate)]
+pub struct RestoreFeedInput {
+    pub feed_id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RestoreFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.feed_id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        };
+
+        if !feed.hidden {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.feed_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: Uuid::from_str(self.user_id.as_str())?,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Restored {})?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Follows `followed_id`, whose new feeds then fan out to the timeline of
+/// the user. Nothing is written when the user already follows them.
+#[derive(Deserialize, Validate)]
+pub struct FollowUserInput {
+    pub followed_id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for FollowUserInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let followed_id = Uuid::from_str(self.followed_id.as_str())?;
+
+        if followed_id == req_user {
+            return Err(CommandError::Validation(HashMap::from([(
+                "followed_id".to_owned(),
+                vec!["a user can't follow themselves".to_owned()],
+            )])));
+        }
+
+        let (follower, version) = cmd
+            .load::<Follower>(self.user_id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if follower.following.contains(&followed_id) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Followed {
+                user_id: followed_id,
+            })?
+            .commit::<Follower>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Stops following `followed_id`, removing their feeds from the timeline of
+/// the user.
+#[derive(Deserialize, Validate)]
+pub struct UnfollowUserInput {
+    pub followed_id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UnfollowUserInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let followed_id = Uuid::from_str(self.followed_id.as_str())?;
+        let Some((follower, version)) = cmd.load::<Follower>(self.user_id.to_owned()).await? else {
+            return Ok(vec![]);
+        };
+
+        if !follower.following.contains(&followed_id) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: Uuid::from_str(self.user_id.as_str())?,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Unfollowed {
+                user_id: followed_id,
+            })?
+            .commit::<Follower>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+fn validate_ulid(id: &str) -> Result<(), ValidationError> {
+    if Ulid::from_string(id).is_err() {
+        return Err(ValidationError::new("invalid_id"));
+    }
+
+    Ok(())
+}
+
+async fn load_open_draft(
+    cmd: &Command,
+    id: &str,
+    user_id: Uuid,
+) -> Result<(Draft, u16), CommandError> {
+    match cmd.load::<Draft>(id.to_owned()).await? {
+        Some((draft, version)) if draft.user_id == user_id && draft.is_open() => {
+            Ok((draft, version))
+        }
+        _ => Err(CommandError::NotFound(format!("draft {id} not found"))),
+    }
+}
+
+/// Autosaves a draft of the user under the ULID picked by the editor, the
+/// first save creating it. Nothing is written when it didn't change.
+#[derive(Deserialize, Validate)]
+pub struct SaveDraftInput {
+    #[validate(custom = "validate_ulid")]
+    pub id: String,
+    #[validate(length(max = 100))]
+    pub title: String,
+    #[validate(length(max = 10000))]
+    pub content: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for SaveDraftInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (draft, version) = match cmd.load::<Draft>(self.id.to_owned()).await? {
+            Some((draft, version)) if draft.user_id == req_user && draft.is_open() => {
+                (draft, version)
+            }
+            Some(_) => {
+                return Err(CommandError::NotFound(format!(
+                    "draft {} not found",
+                    self.id
+                )))
+            }
+            None => (Draft::default(), 0),
+        };
+
+        if version > 0 && draft.title == self.title && draft.content == self.content {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(DraftSaved {
+                title: self.title.to_owned(),
+                content: self.content.to_owned(),
+            })?
+            .commit::<Draft>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Turns a draft of the user into a public feed sharing its id, closing the
+/// draft.
+#[derive(Deserialize, Validate)]
+pub struct PublishDraftInput {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for PublishDraftInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (draft, version) = load_open_draft(cmd, &self.id, req_user).await?;
+
+        if !(3..=100).contains(&draft.title.chars().count()) {
+            return Err(CommandError::Validation(HashMap::from([(
+                "title".to_owned(),
+                vec!["a draft needs a title of 3 to 100 characters to be published".to_owned()],
+            )])));
+        }
+
+        let req_id = self
+            .request_id
+            .to_owned()
+            .unwrap_or(Uuid::new_v4().to_string());
+
+        let mentions = new_mentions(&draft.content, None, req_user);
+        let mut writer = cmd
+            .write(self.id.to_owned())
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: req_id.to_owned(),
+            })?
+            .event(Created {
+                title: draft.title,
+                content: draft.content,
+                tags: vec![],
+                visibility: VISIBILITY_PUBLIC.to_owned(),
+                publish_at: None,
+            })?;
+
+        if !mentions.is_empty() {
+            writer = writer.event(Mentioned { user_ids: mentions })?;
+        }
+
+        let mut events = writer.commit::<Feed>().await?;
+
+        events.extend(
+            cmd.write(self.id.to_owned())
+                .original_version(version)
+                .metadata(FeedMetadata { req_user, req_id })?
+                .event(DraftPublished {
+                    feed_id: self.id.to_owned(),
+                })?
+                .commit::<Draft>()
+                .await?,
+        );
+
+        Ok(events)
+    }
+}
+
+/// Throws away a draft of the user.
+#[derive(Deserialize, Validate)]
+pub struct DiscardDraftInput {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for DiscardDraftInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (_, version) = load_open_draft(cmd, &self.id, req_user).await?;
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(DraftDiscarded {})?
+            .commit::<Draft>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Loads a feed the user is allowed to pin or unpin, being its author or an
+/// admin.
+async fn load_pinnable_feed(
+    cmd: &Command,
+    id: &str,
+    user_id: Uuid,
+    is_admin: bool,
+) -> Result<Feed, CommandError> {
+    match cmd.load::<Feed>(id.to_owned()).await? {
+        Some((feed, _)) if is_admin || feed.user_id == user_id => Ok(feed),
+        _ => Err(CommandError::NotFound(format!("feed {id} not found"))),
+    }
+}
+
+/// Pins a listed feed to the pinboard of its author, floating it above the
+/// other feeds. An author can't have more than `max_pins` pinned feeds.
+#[derive(Deserialize, Validate)]
+pub struct PinFeedInput {
+    pub feed_id: String,
+    pub max_pins: usize,
+    /// Lets the user pin feeds of other authors.
+    #[serde(default)]
+    pub is_admin: bool,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for PinFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let feed = load_pinnable_feed(cmd, &self.feed_id, req_user, self.is_admin).await?;
+
+        if feed.hidden || feed.visibility != VISIBILITY_PUBLIC || feed.publish_at.is_some() {
+            return Err(CommandError::Validation(HashMap::from([(
+                "feed_id".to_owned(),
+                vec!["only listed feeds can be pinned".to_owned()],
+            )])));
+        }
+
+        let (pinboard, version) = cmd
+            .load::<Pinboard>(feed.user_id.to_string())
+            .await?
+            .unwrap_or_default();
+
+        if pinboard.feed_ids.contains(&self.feed_id) {
+            return Ok(vec![]);
+        }
+
+        if pinboard.feed_ids.len() >= self.max_pins {
+            return Err(CommandError::Validation(HashMap::from([(
+                "feed_id".to_owned(),
+                vec![format!("at most {} feeds can be pinned", self.max_pins)],
+            )])));
+        }
+
+        let events = cmd
+            .write(feed.user_id.to_string())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Pinned {
+                feed_id: self.feed_id.to_owned(),
+            })?
+            .commit::<Pinboard>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Removes a feed from the pinboard of its author.
+#[derive(Deserialize, Validate)]
+pub struct UnpinFeedInput {
+    pub feed_id: String,
+    /// Lets the user unpin feeds of other authors.
+    #[serde(default)]
+    pub is_admin: bool,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UnpinFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let feed = load_pinnable_feed(cmd, &self.feed_id, req_user, self.is_admin).await?;
+
+        let Some((pinboard, version)) = cmd.load::<Pinboard>(feed.user_id.to_string()).await?
+        else {
+            return Ok(vec![]);
+        };
+
+        if !pinboard.feed_ids.contains(&self.feed_id) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(feed.user_id.to_string())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Unpinned {
+                feed_id: self.feed_id.to_owned(),
+            })?
+            .commit::<Pinboard>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Requests an archive of the data of the user under the ULID picked by the
+/// settings page, generated in the background. Requesting it again is a
+/// no-op.
+#[derive(Deserialize, Validate)]
+pub struct RequestExportInput {
+    #[validate(custom = "validate_ulid")]
+    pub id: String,
+    #[validate(custom = "validate_export_format")]
+    pub format: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RequestExportInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+
+        if cmd.load::<Export>(self.id.to_owned()).await?.is_some() {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(ExportRequested {
+                format: self.format.to_owned(),
+            })?
+            .commit::<Export>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Feeds of the user's bulk selection, each id once, a feed of another
+/// author or already deleted failing the whole batch.
+async fn load_own_feeds(
+    cmd: &Command,
+    ids: &[String],
+    user_id: Uuid,
+) -> Result<Vec<(String, Feed, u16)>, CommandError> {
+    let mut feeds = vec![];
+    let mut seen = HashSet::new();
+
+    for id in ids.iter().filter(|id| seen.insert(id.as_str())) {
+        match cmd.load::<Feed>(id.to_owned()).await? {
+            Some((feed, version)) if feed.user_id == user_id && !feed.deleted => {
+                feeds.push((id.to_owned(), feed, version));
+            }
+            _ => return Err(CommandError::NotFound(format!("feed {id} not found"))),
+        }
+    }
+
+    Ok(feeds)
+}
+
+/// Archives feeds of the user as one batch, the request id being shared by
+/// all its events to undo it with `UndoBatchInput`. Feeds already archived
+/// or trashed are skipped.
+#[derive(Deserialize, Validate)]
+pub struct ArchiveFeedsInput {
+    #[validate(custom = "validate_bulk_ids")]
+    pub ids: Vec<String>,
+    pub user_id: String,
+    pub request_id: String,
+}
+
+#[async_trait]
+impl CommandHandler for ArchiveFeedsInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let undo_until = Utc::now() + Duration::seconds(UNDO_WINDOW_SECS);
+        let mut events = vec![];
+
+        for (id, feed, version) in load_own_feeds(cmd, &self.ids, req_user).await? {
+            if feed.archived || feed.delete_at.is_some() {
+                continue;
+            }
+
+            events.extend(
+                cmd.write(id)
+                    .original_version(version)
+                    .metadata(FeedMetadata {
+                        req_user,
+                        req_id: self.request_id.to_owned(),
+                    })?
+                    .event(Archived { undo_until })?
+                    .commit::<Feed>()
+                    .await?,
+            );
+        }
+
+        Ok(events)
+    }
+}
+
+/// Trashes feeds of the user as one batch, deleted once `UNDO_WINDOW_SECS`
+/// elapsed unless undone with `UndoBatchInput`. Feeds already trashed are
+/// skipped.
+#[derive(Deserialize, Validate)]
+pub struct TrashFeedsInput {
+    #[validate(custom = "validate_bulk_ids")]
+    pub ids: Vec<String>,
+    pub user_id: String,
+    pub request_id: String,
+}
+
+#[async_trait]
+impl CommandHandler for TrashFeedsInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let delete_at = Utc::now() + Duration::seconds(UNDO_WINDOW_SECS);
+        let mut events = vec![];
+
+        for (id, feed, version) in load_own_feeds(cmd, &self.ids, req_user).await? {
+            if feed.delete_at.is_some() {
+                continue;
+            }
+
+            events.extend(
+                cmd.write(id)
+                    .original_version(version)
+                    .metadata(FeedMetadata {
+                        req_user,
+                        req_id: self.request_id.to_owned(),
+                    })?
+                    .event(Trashed { delete_at })?
+                    .commit::<Feed>()
+                    .await?,
+            );
+        }
+
+        Ok(events)
+    }
+}
+
+/// Unarchives archived feeds of the user, once the undo window of their batch
+/// elapsed too.
+#[derive(Deserialize, Validate)]
+pub struct UnarchiveFeedsInput {
+    #[validate(custom = "validate_bulk_ids")]
+    pub ids: Vec<String>,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UnarchiveFeedsInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let req_id = self
+            .request_id
+            .to_owned()
+            .unwrap_or(Uuid::new_v4().to_string());
+        let mut events = vec![];
+
+        for (id, feed, version) in load_own_feeds(cmd, &self.ids, req_user).await? {
+            if !feed.archived || feed.delete_at.is_some() {
+                continue;
+            }
+
+            events.extend(
+                cmd.write(id)
+                    .original_version(version)
+                    .metadata(FeedMetadata {
+                        req_user,
+                        req_id: req_id.to_owned(),
+                    })?
+                    .event(Unarchived {})?
+                    .commit::<Feed>()
+                    .await?,
+            );
+        }
+
+        Ok(events)
+    }
+}
+
+/// Undoes the batch `batch_id` on its feeds still within their undo window,
+/// unarchiving or untrashing them. Fails when none of them is anymore.
+#[derive(Deserialize, Validate)]
+pub struct UndoBatchInput {
+    #[validate(custom = "validate_bulk_ids")]
+    pub ids: Vec<String>,
+    pub batch_id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UndoBatchInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let req_id = self
+            .request_id
+            .to_owned()
+            .unwrap_or(Uuid::new_v4().to_string());
+        let now = Utc::now();
+        let mut events = vec![];
+
+        for (id, feed, version) in load_own_feeds(cmd, &self.ids, req_user).await? {
+            if feed.batch_id.as_ref() != Some(&self.batch_id)
+                || !feed.undo_until.is_some_and(|undo_until| undo_until > now)
+            {
+                continue;
+            }
+
+            let writer = cmd
+                .write(id)
+                .original_version(version)
+                .metadata(FeedMetadata {
+                    req_user,
+                    req_id: req_id.to_owned(),
+                })?;
+
+            let writer = if feed.delete_at.is_some() {
+                writer.event(Untrashed {})?
+            } else {
+                writer.event(Unarchived {})?
+            };
+
+            events.extend(writer.commit::<Feed>().await?);
+        }
+
+        if events.is_empty() {
+            return Err(CommandError::Validation(HashMap::from([(
+                "batch_id".to_owned(),
+                vec!["nothing left to undo, the undo window elapsed".to_owned()],
+            )])));
+        }
+
+        Ok(events)
+    }
+}
+
+/// Deletes a trashed feed once its `delete_at` is due, on behalf of its
+/// author. Nothing is written for a feed that isn't trashed or due yet.
+#[derive(Deserialize, Validate)]
+pub struct DeleteFeedInput {
+    pub id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for DeleteFeedInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let Some((feed, version)) = cmd.load::<Feed>(self.id.to_owned()).await? else {
+            return Err(CommandError::NotFound(format!(
+                "feed {} not found",
+                self.id
+            )));
+        };
+
+        if feed.deleted
+            || !feed
+                .delete_at
+                .is_some_and(|delete_at| delete_at <= Utc::now())
+        {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: feed.user_id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(Deleted {})?
+            .commit::<Feed>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Replaces the notifications muted by the user. Nothing is written when
+/// they didn't change.
+#[derive(Deserialize, Validate)]
+pub struct UpdateNotificationPreferencesInput {
+    #[validate(custom = "validate_muted_notifications")]
+    pub muted: Vec<MutedNotification>,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UpdateNotificationPreferencesInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (preferences, version) = cmd
+            .load::<Preferences>(self.user_id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        let mut muted = vec![];
+        for notification in self.muted.iter() {
+            if !muted.contains(notification) {
+                muted.push(notification.clone());
+            }
+        }
+
+        if muted.iter().collect::<HashSet<_>>() == preferences.muted.iter().collect::<HashSet<_>>()
+        {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(NotificationsUpdated { muted })?
+            .commit::<Preferences>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Sets the address notifications are emailed to, which must be verified
+/// with the token emailed to it. Setting it again sends a new token until it
+/// is verified.
+#[derive(Deserialize, Validate)]
+pub struct SetEmailInput {
+    #[validate(email, length(max = 254))]
+    pub email: String,
+    /// Language of the emails.
+    pub lang: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for SetEmailInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (preferences, version) = cmd
+            .load::<Preferences>(self.user_id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if preferences.email_verified && preferences.email.as_ref() == Some(&self.email) {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(EmailSet {
+                email: self.email.to_owned(),
+                lang: self.lang.to_owned(),
+                token: random_hex(),
+            })?
+            .commit::<Preferences>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Verifies the address of the user with the token emailed to it.
+#[derive(Deserialize, Validate)]
+pub struct VerifyEmailInput {
+    pub token: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for VerifyEmailInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (preferences, version) = cmd
+            .load::<Preferences>(self.user_id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if preferences.email.is_none() {
+            return Err(CommandError::NotFound(format!(
+                "email of {} not found",
+                self.user_id
+            )));
+        }
+
+        if preferences.email_verified {
+            return Ok(vec![]);
+        }
+
+        if preferences.email_token != self.token {
+            return Err(CommandError::Validation(HashMap::from([(
+                "token".to_owned(),
+                vec!["the verification link is invalid or outdated".to_owned()],
+            )])));
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(EmailVerified {})?
+            .commit::<Preferences>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// 32 random bytes as hex, for secrets and tokens.
+fn random_hex() -> String {
+    rand::thread_rng()
+        .gen::<[u8; 32]>()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Webhooks are posted to the default port of their scheme only, as links
+/// are unfurled.
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    let valid = url.len() <= MAX_WEBHOOK_URL_LEN
+        && reqwest::Url::parse(url).is_ok_and(|url| {
+            matches!(url.scheme(), "http" | "https")
+                && url.host_str().is_some()
+                && matches!(url.port_or_known_default(), Some(80 | 443))
+        });
+
+    if !valid {
+        return Err(ValidationError::new("invalid_webhook_url"));
+    }
+
+    Ok(())
+}
+
+/// Registers a url receiving the new public feeds of the user under the
+/// ULID picked by the settings page, with a secret generated to sign them.
+/// Registering it again is a no-op.
+#[derive(Deserialize, Validate)]
+pub struct RegisterWebhookInput {
+    #[validate(custom = "validate_ulid")]
+    pub id: String,
+    #[validate(custom = "validate_webhook_url")]
+    pub url: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RegisterWebhookInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+
+        if cmd.load::<Webhook>(self.id.to_owned()).await?.is_some() {
+            return Ok(vec![]);
+        }
+
+        let secret = random_hex();
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(WebhookRegistered {
+                url: self.url.to_owned(),
+                secret,
+            })?
+            .commit::<Webhook>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Stops sending feeds to a webhook of the user, its pending deliveries
+/// being dropped.
+#[derive(Deserialize, Validate)]
+pub struct RemoveWebhookInput {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RemoveWebhookInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let version = match cmd.load::<Webhook>(self.id.to_owned()).await? {
+            Some((webhook, version)) if webhook.user_id == req_user && !webhook.removed => version,
+            _ => {
+                return Err(CommandError::NotFound(format!(
+                    "webhook {} not found",
+                    self.id
+                )))
+            }
+        };
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(WebhookRemoved {})?
+            .commit::<Webhook>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Posts the signed `body` of a delivery to its webhook, run by the
+/// scheduler for the deliveries due. A failed attempt is retried after
+/// `WEBHOOK_RETRY_DELAY_SECS`, quadrupled each time, until
+/// `MAX_WEBHOOK_ATTEMPTS`. Nothing is sent once delivered or before the
+/// next attempt is due.
+#[derive(Deserialize, Validate)]
+pub struct DeliverWebhookInput {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub body: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for DeliverWebhookInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (delivery, version) = cmd
+            .load::<Delivery>(self.id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if delivery.delivered
+            || delivery.attempts - delivery.requeued_attempts >= MAX_WEBHOOK_ATTEMPTS
+            || delivery
+                .next_attempt_at
+                .is_some_and(|next_attempt_at| next_attempt_at > Utc::now())
+        {
+            return Ok(vec![]);
+        }
+
+        let attempt = delivery.attempts + 1;
+        // Attempts since the last requeue.
+        let round = attempt - delivery.requeued_attempts;
+        let writer = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?;
+
+        let writer = match webhook::send(&self.url, &self.secret, &self.id, &self.body).await {
+            Ok(status_code) if (200..300).contains(&status_code) => {
+                writer.event(DeliverySucceeded {
+                    attempt,
+                    status_code,
+                })?
+            }
+            result => {
+                let (status_code, error) = match result {
+                    Ok(status_code) => (Some(status_code), None),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+
+                let next_attempt_at = (round < MAX_WEBHOOK_ATTEMPTS).then(|| {
+                    Utc::now()
+                        + Duration::seconds(
+                            WEBHOOK_RETRY_DELAY_SECS * 4_i64.pow(u32::from(round - 1)),
+                        )
+                });
+
+                writer.event(DeliveryFailed {
+                    attempt,
+                    status_code,
+                    error,
+                    next_attempt_at,
+                })?
+            }
+        };
+
+        let events = writer.commit::<Delivery>().await?;
+
+        Ok(events)
+    }
+}
+
+/// Gives a delivery that failed since it was last requeued all of its
+/// attempts again, the next one being due at once. Deliveries of a removed
+/// webhook stay dead.
+#[derive(Deserialize, Validate)]
+pub struct RequeueWebhookDeliveryInput {
+    pub id: String,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RequeueWebhookDeliveryInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let version = match cmd.load::<Delivery>(self.id.to_owned()).await? {
+            Some((delivery, version))
+                if !delivery.delivered && delivery.attempts > delivery.requeued_attempts =>
+            {
+                version
+            }
+            _ => {
+                return Err(CommandError::NotFound(format!(
+                    "failed delivery {} not found",
+                    self.id
+                )))
+            }
+        };
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(DeliveryRequeued {})?
+            .commit::<Delivery>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+fn validate_role(role: &str) -> Result<(), ValidationError> {
+    if !ROLES.contains(&role) {
+        return Err(ValidationError::new("role"));
+    }
+
+    Ok(())
+}
+
+fn validate_revocable_role(role: &str) -> Result<(), ValidationError> {
+    if role == ROLE_USER {
+        return Err(ValidationError::new("revocable_role"));
+    }
+
+    validate_role(role)
+}
+
+fn validate_uuid(id: &str) -> Result<(), ValidationError> {
+    if Uuid::from_str(id).is_err() {
+        return Err(ValidationError::new("invalid_id"));
+    }
+
+    Ok(())
+}
+
+/// Creates the user with the `sub` of their tokens as `id`, so that they have
+/// their role from their first sign in.
+#[derive(Deserialize, Validate)]
+pub struct CreateUserInput {
+    #[validate(custom = "validate_uuid")]
+    pub id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(custom = "validate_role")]
+    pub role: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for CreateUserInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let id = Uuid::from_str(self.id.as_str())?;
+
+        if cmd.load::<User>(id.to_string()).await?.is_some() {
+            return Err(CommandError::Validation(HashMap::from([(
+                "id".to_owned(),
+                vec!["user already exists".to_owned()],
+            )])));
+        }
+
+        let events = cmd
+            .write(id.to_string())
+            .metadata(FeedMetadata {
+                req_user: id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(UserCreated {
+                name: self.name.to_owned(),
+                role: self.role.to_owned(),
+            })?
+            .commit::<User>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Disables a user, who is then signed out everywhere. Nothing is written for
+/// a user already disabled.
+#[derive(Deserialize, Validate)]
+pub struct DisableUserInput {
+    pub id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for DisableUserInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let (user, version, id) = load_user(cmd, &self.id).await?;
+
+        if user.disabled {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(id.to_string())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(UserDisabled {})?
+            .commit::<User>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct SetUserRoleInput {
+    pub id: String,
+    #[validate(custom = "validate_role")]
+    pub role: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for SetUserRoleInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let (user, version, id) = load_user(cmd, &self.id).await?;
+
+        if user.role == self.role {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(id.to_string())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(UserRoleSet {
+                role: self.role.to_owned(),
+            })?
+            .commit::<User>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Gives the user role back to a user having `role`, every user having the
+/// user role.
+#[derive(Deserialize, Validate)]
+pub struct RevokeUserRoleInput {
+    pub id: String,
+    #[validate(custom = "validate_revocable_role")]
+    pub role: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for RevokeUserRoleInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let (user, version, id) = load_user(cmd, &self.id).await?;
+
+        if user.role != self.role {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(id.to_string())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: id,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(UserRoleSet {
+                role: ROLE_USER.to_owned(),
+            })?
+            .commit::<User>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Syncs the subscription of the user with the Stripe event that changed
+/// it. Nothing is written for an event already applied or older than the
+/// last one applied, Stripe retrying and reordering them.
+#[derive(Deserialize, Validate)]
+pub struct UpdateSubscriptionInput {
+    pub customer_id: String,
+    pub subscription_id: String,
+    #[validate(custom = "validate_subscription_status")]
+    pub status: String,
+    pub current_period_end: Option<DateTime<Utc>>,
+    pub stripe_event_id: String,
+    pub changed_at: DateTime<Utc>,
+    pub user_id: String,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for UpdateSubscriptionInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let req_user = Uuid::from_str(self.user_id.as_str())?;
+        let (billing, version) = cmd
+            .load::<Billing>(self.user_id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if billing
+            .changed_at
+            .is_some_and(|changed_at| changed_at >= self.changed_at)
+        {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.user_id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user,
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(self.stripe_event_id.to_owned()),
+            })?
+            .event(SubscriptionUpdated {
+                customer_id: self.customer_id.to_owned(),
+                subscription_id: self.subscription_id.to_owned(),
+                status: self.status.to_owned(),
+                current_period_end: self.current_period_end,
+                stripe_event_id: self.stripe_event_id.to_owned(),
+                changed_at: self.changed_at,
+            })?
+            .commit::<Billing>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+/// Records a message of the event bus for the rules of the `external`
+/// aggregate, once per `id` however often the bus delivers it.
+#[derive(Deserialize, Validate)]
+pub struct IngestExternalEventInput {
+    #[validate(length(min = 1, max = 255))]
+    pub id: String,
+    #[validate(length(min = 1, max = 255))]
+    pub subject: String,
+    pub payload: serde_json::Value,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl CommandHandler for IngestExternalEventInput {
+    async fn handle(&self, cmd: &Command) -> CommandOutput {
+        let (_, version) = cmd
+            .load::<External>(self.id.to_owned())
+            .await?
+            .unwrap_or_default();
+
+        if version > 0 {
+            return Ok(vec![]);
+        }
+
+        let events = cmd
+            .write(self.id.to_owned())
+            .original_version(version)
+            .metadata(FeedMetadata {
+                req_user: Uuid::nil(),
+                req_id: self
+                    .request_id
+                    .to_owned()
+                    .unwrap_or(Uuid::new_v4().to_string()),
+            })?
+            .event(ExternalReceived {
+                subject: self.subject.to_owned(),
+                payload: self.payload.to_owned(),
+            })?
+            .commit::<External>()
+            .await?;
+
+        Ok(events)
+    }
+}
+
+async fn load_user(cmd: &Command, id: &str) -> Result<(User, u16, Uuid), CommandError> {
+    let not_found = || CommandError::NotFound(format!("user {id} not found"));
+    let id = Uuid::from_str(id).map_err(|_| not_found())?;
+
+    let Some((user, version)) = cmd.load::<User>(id.to_string()).await? else {
+        return Err(not_found());
+    };
+
+    Ok((user, version, id))
+}
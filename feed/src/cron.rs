@@ -0,0 +1,233 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+use sqlx::PgPool;
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    str::FromStr,
+    sync::Arc,
+};
+use tracing::{error, info, warn};
+
+use crate::{prune_trending, purge_link_previews, purge_webhook_deliveries, Jobs};
+
+/// Days the delivered webhooks and the failed jobs are kept for.
+const RETENTION_DAYS: i64 = 30;
+
+/// Attempts of an occurrence of a task after the first one failed, by
+/// default.
+const RETRIES: u32 = 3;
+
+/// Wait before the first retry, doubled on each of the next ones.
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Runs a task of `Cron` on each occurrence of its schedule.
+#[async_trait]
+pub trait CronTask: Send + Sync {
+    async fn run(&self, ctx: &CronContext) -> Result<()>;
+}
+
+/// Services given to `Cron::data`, shared by every task as they are by the
+/// rules with `ConsumerContext`.
+#[derive(Clone, Default)]
+pub struct CronContext {
+    data: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl CronContext {
+    /// `T` given to `Cron::data`, panicking when it wasn't.
+    pub fn extract<T: Clone + Send + Sync + 'static>(&self) -> T {
+        self.data
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+            .unwrap_or_else(|| panic!("{} not in the cron context", type_name::<T>()))
+    }
+}
+
+/// Task of `Cron` on a cron expression, with seconds, like `0 0 3 * * *`.
+pub struct CronJob {
+    name: String,
+    expression: String,
+    task: Arc<dyn CronTask>,
+    retries: u32,
+    retry_delay: std::time::Duration,
+}
+
+impl CronJob {
+    pub fn new(
+        name: impl Into<String>,
+        expression: impl Into<String>,
+        task: impl CronTask + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            expression: expression.into(),
+            task: Arc::new(task),
+            retries: RETRIES,
+            retry_delay: RETRY_DELAY,
+        }
+    }
+
+    /// Attempts after the first one failed, `delay` apart then twice longer
+    /// each time, until the next occurrence is due.
+    pub fn retries(mut self, retries: u32, delay: std::time::Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+}
+
+/// Runs the jobs of the modules on their schedule. Every process running the
+/// consumers schedules them, the first one claiming an occurrence in the
+/// `feed_cron_runs` table being the only one running it.
+pub struct Cron {
+    db: PgPool,
+    ctx: CronContext,
+    jobs: Vec<(Schedule, CronJob)>,
+}
+
+impl Cron {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            ctx: CronContext::default(),
+            db,
+            jobs: vec![],
+        }
+    }
+
+    /// Gives `value` to the tasks, read with `CronContext::extract`.
+    pub fn data<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.ctx.data.insert(TypeId::of::<T>(), Arc::new(value));
+        self
+    }
+
+    /// Schedules `jobs`, failing on the first one without a cron
+    /// expression.
+    pub fn jobs(mut self, jobs: Vec<CronJob>) -> Result<Self> {
+        for job in jobs {
+            let schedule = Schedule::from_str(&job.expression)
+                .map_err(|err| anyhow::anyhow!("cron expression of {}: {err}", job.name))?;
+
+            self.jobs.push((schedule, job));
+        }
+
+        Ok(self)
+    }
+
+    pub fn spawn(self) {
+        for (schedule, job) in self.jobs {
+            let db = self.db.clone();
+            let ctx = self.ctx.clone();
+
+            tokio::spawn(async move {
+                while let Some(run_at) = schedule.after(&Utc::now()).next() {
+                    let delay = (run_at - Utc::now()).to_std().unwrap_or_default();
+                    tokio::time::sleep(delay).await;
+
+                    match claim(&db, &job.name, run_at).await {
+                        Ok(true) => run(&schedule, &job, &ctx).await,
+                        Ok(false) => {}
+                        Err(err) => error!("{err}"),
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Runs `job` once claimed, retrying it with backoff while it fails.
+async fn run(schedule: &Schedule, job: &CronJob, ctx: &CronContext) {
+    let name = &job.name;
+    let mut delay = job.retry_delay;
+
+    for attempt in 0..=job.retries {
+        let Err(err) = job.task.run(ctx).await else {
+            return;
+        };
+
+        let next_run = schedule.after(&Utc::now()).next();
+        let retry_at = Utc::now() + Duration::from_std(delay).unwrap_or_default();
+
+        if attempt == job.retries || next_run.is_some_and(|next_run| next_run <= retry_at) {
+            error!("cron {name} failed: {err}");
+
+            return;
+        }
+
+        warn!("cron {name} failed, retrying in {delay:?}: {err}");
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+/// Claims the occurrence of `task` at `run_at`, `false` when another process
+/// did first. Occurrences are the same for every process, whatever the time
+/// they woke up at.
+async fn claim(db: &PgPool, task: &str, run_at: DateTime<Utc>) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO feed_cron_runs (task, run_at) VALUES ( $1, $2 )
+        ON CONFLICT (task) DO UPDATE SET run_at = EXCLUDED.run_at
+        WHERE feed_cron_runs.run_at < EXCLUDED.run_at
+        "#,
+    )
+    .bind(task)
+    .bind(run_at)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Drops the rows of the projections that are no longer read: expired link
+/// previews and old webhook deliveries that are not retried.
+pub struct CompactTask;
+
+#[async_trait]
+impl CronTask for CompactTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let previews = purge_link_previews(&db).await?;
+        let deliveries =
+            purge_webhook_deliveries(&db, Utc::now() - Duration::days(RETENTION_DAYS)).await?;
+
+        info!("compacted {previews} link previews and {deliveries} webhook deliveries");
+
+        Ok(())
+    }
+}
+
+/// Drops the old jobs left failed, which admins no longer retry.
+pub struct CleanupTask;
+
+#[async_trait]
+impl CronTask for CleanupTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        let jobs = ctx
+            .extract::<Jobs>()
+            .purge_failed(Utc::now() - Duration::days(RETENTION_DAYS))
+            .await?;
+
+        info!("cleaned up {jobs} failed jobs");
+
+        Ok(())
+    }
+}
+
+/// Forgets the interactions that no longer weigh in the trending feeds and
+/// ranks their feeds again.
+pub struct TrendingTask;
+
+#[async_trait]
+impl CronTask for TrendingTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        let interactions = prune_trending(&ctx.extract::<PgPool>()).await?;
+
+        info!("forgot {interactions} trending interactions");
+
+        Ok(())
+    }
+}
@@ -1,11 +1,31 @@
+use chrono::{DateTime, Utc};
 use evento::PublisherEvent;
 use parse_display::{Display, FromStr};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::VISIBILITY_PUBLIC;
 
 #[derive(Display, FromStr, PublisherEvent)]
 #[display(style = "kebab-case")]
 pub enum FeedEvent {
     Created,
+    Reacted,
+    Unreacted,
+    Tagged,
+    Untagged,
+    Attached,
+    Edited,
+    Reported,
+    Hidden,
+    Restored,
+    Published,
+    Mentioned,
+    Archived,
+    Unarchived,
+    Trashed,
+    Untrashed,
+    Deleted,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -13,4 +33,334 @@ pub struct Created {
     pub title: String,
     pub content: String,
     pub tags: Vec<String>,
+    /// One of `VISIBILITIES`, feeds created before it existed being public.
+    #[serde(default = "default_visibility")]
+    pub visibility: String,
+    /// Set for scheduled feeds, kept from listings until `published`.
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+pub(crate) fn default_visibility() -> String {
+    VISIBILITY_PUBLIC.to_owned()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Reacted {
+    pub reaction: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Unreacted {
+    pub reaction: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Tagged {
+    pub tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Untagged {
+    pub tag: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Attached {
+    pub key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Edited {
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Reported {
+    pub reason: String,
+    /// Language of the reporter, to notify them of the moderation decision.
+    pub lang: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Hidden {
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Restored {}
+
+#[derive(Serialize, Deserialize)]
+pub struct Published {}
+
+/// Users newly mentioned by the content of a feed.
+#[derive(Serialize, Deserialize)]
+pub struct Mentioned {
+    pub user_ids: Vec<Uuid>,
+}
+
+/// Kept from listings, the batch archiving it being undoable until
+/// `undo_until`.
+#[derive(Serialize, Deserialize)]
+pub struct Archived {
+    pub undo_until: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Unarchived {}
+
+/// Kept from listings then `deleted` once `delete_at` is due, unless the
+/// batch trashing it was undone before.
+#[derive(Serialize, Deserialize)]
+pub struct Trashed {
+    pub delete_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Untrashed {}
+
+#[derive(Serialize, Deserialize)]
+pub struct Deleted {}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum CommentEvent {
+    Created,
+    Edited,
+    Deleted,
+    Mentioned,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommentCreated {
+    pub feed_id: String,
+    pub parent_id: Option<String>,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommentEdited {
+    pub feed_id: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommentDeleted {
+    pub feed_id: String,
+}
+
+/// Users newly mentioned by the content of a comment.
+#[derive(Serialize, Deserialize)]
+pub struct CommentMentioned {
+    pub feed_id: String,
+    pub user_ids: Vec<Uuid>,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum FollowerEvent {
+    Followed,
+    Unfollowed,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Followed {
+    pub user_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Unfollowed {
+    pub user_id: Uuid,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum DraftEvent {
+    Saved,
+    Published,
+    Discarded,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DraftSaved {
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DraftPublished {
+    pub feed_id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DraftDiscarded {}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum PinboardEvent {
+    Pinned,
+    Unpinned,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Pinned {
+    pub feed_id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Unpinned {
+    pub feed_id: String,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum ExportEvent {
+    Requested,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportRequested {
+    /// One of `EXPORT_FORMATS`.
+    pub format: String,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum PreferencesEvent {
+    NotificationsUpdated,
+    EmailSet,
+    EmailVerified,
+}
+
+/// Notifications of `kind` a user doesn't want on `channel`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MutedNotification {
+    /// One of `NOTIFICATION_KINDS`.
+    pub kind: String,
+    /// One of `NOTIFICATION_CHANNELS`.
+    pub channel: String,
+}
+
+/// Every notification is sent unless muted, `muted` replacing the previous
+/// list.
+#[derive(Serialize, Deserialize)]
+pub struct NotificationsUpdated {
+    pub muted: Vec<MutedNotification>,
+}
+
+/// Address notifications are emailed to, in `lang`, once verified with
+/// `token`.
+#[derive(Serialize, Deserialize)]
+pub struct EmailSet {
+    pub email: String,
+    pub lang: String,
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailVerified {}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum WebhookEvent {
+    Registered,
+    Removed,
+}
+
+/// Url receiving the new public feeds of the user, signed with `secret`.
+#[derive(Serialize, Deserialize)]
+pub struct WebhookRegistered {
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WebhookRemoved {}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum DeliveryEvent {
+    Failed,
+    Succeeded,
+    Requeued,
+}
+
+/// Attempt to deliver an event to a webhook, retried at `next_attempt_at`
+/// unless it was the last one.
+#[derive(Serialize, Deserialize)]
+pub struct DeliveryFailed {
+    pub attempt: u16,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeliverySucceeded {
+    pub attempt: u16,
+    pub status_code: u16,
+}
+
+/// Dead delivery given all of its attempts again by an admin, due at once.
+#[derive(Serialize, Deserialize)]
+pub struct DeliveryRequeued {}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum UserEvent {
+    Created,
+    Disabled,
+    RoleSet,
+}
+
+/// User known by the `sub` of their tokens, created ahead of signing in to
+/// give them a role.
+#[derive(Serialize, Deserialize)]
+pub struct UserCreated {
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDisabled {}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRoleSet {
+    pub role: String,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum BillingEvent {
+    SubscriptionUpdated,
+}
+
+/// Subscription of a user to the plan as told by the Stripe event
+/// `stripe_event_id`, created at `changed_at`.
+#[derive(Serialize, Deserialize)]
+pub struct SubscriptionUpdated {
+    pub customer_id: String,
+    pub subscription_id: String,
+    /// One of `SUBSCRIPTION_STATUSES`.
+    pub status: String,
+    pub current_period_end: Option<DateTime<Utc>>,
+    pub stripe_event_id: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Display, FromStr, PublisherEvent)]
+#[display(style = "kebab-case")]
+pub enum ExternalEvent {
+    Received,
+}
+
+/// Message published on `subject` of the event bus, its payload being kept
+/// as sent.
+#[derive(Serialize, Deserialize)]
+pub struct ExternalReceived {
+    pub subject: String,
+    pub payload: serde_json::Value,
 }
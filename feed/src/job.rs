@@ -0,0 +1,299 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{types::Json, PgPool};
+use std::{collections::HashMap, sync::Arc};
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{error, warn};
+use ulid::Ulid;
+
+/// Attempts of a job before it is left failed, for an admin to retry.
+pub const MAX_JOB_ATTEMPTS: i32 = 5;
+
+/// First retry delay, doubled on every attempt.
+const RETRY_DELAY_SECS: i64 = 10;
+
+/// Time a job is locked by the worker running it, other workers taking it
+/// over past it as the worker is considered gone.
+const LOCK_SECS: i64 = 300;
+
+/// Pause of an idle worker before looking for jobs again.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Job left failed once its attempts ran out.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct FailedJob {
+    pub id: String,
+    pub kind: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ClaimedJob {
+    id: String,
+    kind: String,
+    payload: Json<serde_json::Value>,
+    attempts: i32,
+}
+
+/// Queue of the work too slow for a request or a rule, kept in the
+/// `feed_jobs` table so that any worker runs it and retries it.
+#[derive(Clone)]
+pub struct Jobs {
+    db: PgPool,
+}
+
+impl Jobs {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Queues a job of `kind` to run as soon as a worker is free.
+    pub async fn enqueue(&self, kind: &str, payload: &impl Serialize) -> Result<String> {
+        self.schedule(kind, payload, Utc::now()).await
+    }
+
+    /// Queues a job of `kind` to run from `run_at`.
+    pub async fn schedule(
+        &self,
+        kind: &str,
+        payload: &impl Serialize,
+        run_at: DateTime<Utc>,
+    ) -> Result<String> {
+        let id = Ulid::new().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO feed_jobs (id, kind, payload, status, attempts, run_at, created_at, updated_at)
+            VALUES ( $1, $2, $3, 'pending', 0, $4, $5, $5 )
+            "#,
+        )
+        .bind(&id)
+        .bind(kind)
+        .bind(Json(serde_json::to_value(payload)?))
+        .bind(run_at)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Failed jobs, the latest first.
+    pub async fn failed(&self, limit: i64) -> Result<Vec<FailedJob>> {
+        Ok(sqlx::query_as::<_, FailedJob>(
+            r#"
+            SELECT id, kind, attempts, last_error, updated_at FROM feed_jobs
+            WHERE status = 'failed'
+            ORDER BY updated_at DESC, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Queues a failed job again with all of its attempts, `false` when it
+    /// is not failed.
+    pub async fn retry(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE feed_jobs SET status = 'pending', attempts = 0, run_at = $2, updated_at = $2
+            WHERE id = $1 AND status = 'failed'
+            "#,
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Drops the jobs left failed before `before`, returning how many were
+    /// dropped.
+    pub async fn purge_failed(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM feed_jobs WHERE status = 'failed' AND updated_at < $1")
+                .bind(before)
+                .execute(&self.db)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Locks the next due job, or one whose worker is gone.
+    async fn claim(&self) -> Result<Option<ClaimedJob>> {
+        let now = Utc::now();
+
+        Ok(sqlx::query_as::<_, ClaimedJob>(
+            r#"
+            UPDATE feed_jobs SET status = 'running', attempts = attempts + 1, locked_until = $2, updated_at = $1
+            WHERE id = (
+                SELECT id FROM feed_jobs
+                WHERE (status = 'pending' AND run_at <= $1)
+                OR (status = 'running' AND locked_until < $1)
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, kind, payload, attempts
+            "#,
+        )
+        .bind(now)
+        .bind(now + Duration::seconds(LOCK_SECS))
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    async fn complete(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM feed_jobs WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Retries the job with an exponential delay, or leaves it failed once
+    /// its attempts ran out.
+    async fn fail(&self, job: &ClaimedJob, err: &str) -> Result<()> {
+        let now = Utc::now();
+        let (status, run_at) = if job.attempts < MAX_JOB_ATTEMPTS {
+            let delay = RETRY_DELAY_SECS * 2i64.pow(job.attempts.max(1) as u32 - 1);
+
+            ("pending", now + Duration::seconds(delay))
+        } else {
+            ("failed", now)
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE feed_jobs SET status = $2, run_at = $3, last_error = $4, locked_until = NULL, updated_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(&job.id)
+        .bind(status)
+        .bind(run_at)
+        .bind(err)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Runs the jobs of a kind, an error retrying the job.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, payload: serde_json::Value) -> Result<()>;
+}
+
+/// Pool of workers running the queued jobs with their handler.
+pub struct Worker {
+    jobs: Jobs,
+    handlers: HashMap<String, Arc<dyn JobHandler>>,
+    concurrency: usize,
+}
+
+impl Worker {
+    pub fn new(jobs: Jobs) -> Self {
+        Self {
+            jobs,
+            handlers: HashMap::new(),
+            concurrency: 4,
+        }
+    }
+
+    pub fn handler(mut self, kind: impl Into<String>, handler: impl JobHandler + 'static) -> Self {
+        self.handlers.insert(kind.into(), Arc::new(handler));
+        self
+    }
+
+    /// Jobs run at once by this process.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn spawn(self) -> WorkerHandle {
+        let handlers = Arc::new(self.handlers);
+        let (stop, stopped) = watch::channel(false);
+
+        let tasks = (0..self.concurrency)
+            .map(|_| {
+                let jobs = self.jobs.clone();
+                let handlers = handlers.clone();
+                let mut stopped = stopped.clone();
+
+                tokio::spawn(async move {
+                    while !*stopped.borrow() {
+                        let idle = match run_next(&jobs, &handlers).await {
+                            Ok(ran) => !ran,
+                            Err(err) => {
+                                error!("{err}");
+                                true
+                            }
+                        };
+
+                        if idle {
+                            tokio::select! {
+                                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                                _ = stopped.changed() => {}
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        WorkerHandle { stop, tasks }
+    }
+}
+
+/// Workers spawned by `Worker::spawn`, running until stopped.
+pub struct WorkerHandle {
+    stop: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl WorkerHandle {
+    /// Stops claiming jobs and waits for the running ones to finish.
+    pub async fn stop(self) {
+        let _ = self.stop.send(true);
+
+        for task in self.tasks {
+            if let Err(err) = task.await {
+                error!("job worker failed: {err}");
+            }
+        }
+    }
+}
+
+/// Runs the next due job, `false` when there is none.
+async fn run_next(jobs: &Jobs, handlers: &HashMap<String, Arc<dyn JobHandler>>) -> Result<bool> {
+    let Some(job) = jobs.claim().await? else {
+        return Ok(false);
+    };
+
+    let result = match handlers.get(&job.kind) {
+        Some(handler) => handler.run(job.payload.0.clone()).await,
+        None => Err(anyhow::anyhow!("no handler for jobs of kind {}", job.kind)),
+    };
+
+    match result {
+        Ok(()) => jobs.complete(&job.id).await?,
+        Err(err) => {
+            warn!("job {} {} failed: {err}", job.kind, job.id);
+            jobs.fail(&job, &err.to_string()).await?;
+        }
+    }
+
+    Ok(true)
+}
@@ -1,9 +1,20 @@
 mod aggregate;
 mod command;
+mod cron;
 mod event;
+mod job;
+mod push;
 mod query;
+mod slug;
+mod unfurl;
+mod webhook;
 
 pub use aggregate::*;
 pub use command::*;
+pub use cron::*;
 pub use event::*;
+pub use job::*;
+pub use push::*;
 pub use query::*;
+pub use unfurl::{is_public, parse_preview, unfurl, LinkPreview};
+pub use webhook::verify_signature;
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::CHANNEL_PUSH;
+
+/// Subscription of a browser to the pushes of a user, as serialized by
+/// `PushSubscription.toJSON()` of the Push API.
+#[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+pub struct PushSubscription {
+    #[validate(url, length(max = 2048))]
+    pub endpoint: String,
+    #[validate]
+    pub keys: PushKeys,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+pub struct PushKeys {
+    #[validate(length(min = 1, max = 256))]
+    pub p256dh: String,
+    #[validate(length(min = 1, max = 256))]
+    pub auth: String,
+}
+
+/// Browser to push a notification to, in the language it subscribed in.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct PushRecipient {
+    pub user_id: Uuid,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub lang: String,
+}
+
+/// Browsers subscribed to the pushes of the users, kept in the
+/// `feed_push_subscriptions` table by endpoint. An endpoint belongs to one
+/// browser, a user signing in on it taking its pushes over.
+#[derive(Clone)]
+pub struct PushSubscriptions {
+    db: PgPool,
+}
+
+impl PushSubscriptions {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn subscribe(
+        &self,
+        user_id: Uuid,
+        subscription: &PushSubscription,
+        lang: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO feed_push_subscriptions (endpoint, user_id, p256dh, auth, lang, created_at)
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (endpoint) DO UPDATE SET
+            user_id = $2, p256dh = $3, auth = $4, lang = $5, created_at = $6
+            "#,
+        )
+        .bind(&subscription.endpoint)
+        .bind(user_id)
+        .bind(&subscription.keys.p256dh)
+        .bind(&subscription.keys.auth)
+        .bind(lang)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drops the subscription of `endpoint` if it is one of `user_id`'s.
+    pub async fn unsubscribe(&self, user_id: Uuid, endpoint: &str) -> Result<()> {
+        sqlx::query("DELETE FROM feed_push_subscriptions WHERE endpoint = $1 AND user_id = $2")
+            .bind(endpoint)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drops the subscription of an endpoint its push service told gone.
+    pub async fn remove(&self, endpoint: &str) -> Result<()> {
+        sqlx::query("DELETE FROM feed_push_subscriptions WHERE endpoint = $1")
+            .bind(endpoint)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Browsers of the users out of `user_ids` not muting the pushes of
+    /// `kind`.
+    pub async fn recipients(&self, user_ids: &[Uuid], kind: &str) -> Result<Vec<PushRecipient>> {
+        Ok(sqlx::query_as::<_, PushRecipient>(
+            r#"
+            SELECT user_id, endpoint, p256dh, auth, lang FROM feed_push_subscriptions
+            WHERE user_id = ANY($1)
+            AND user_id NOT IN (
+                SELECT user_id FROM feed_muted_notifications
+                WHERE user_id = ANY($1) AND kind = $2 AND channel = $3
+            )
+            "#,
+        )
+        .bind(user_ids)
+        .bind(kind)
+        .bind(CHANNEL_PUSH)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{Billing, BillingEvent, SubscriptionUpdated, ACTIVE_SUBSCRIPTION_STATUSES};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, FromRow)]
+pub struct UserSubscription {
+    pub customer_id: String,
+    pub subscription_id: String,
+    pub status: String,
+    pub current_period_end: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserSubscription {
+    /// Whether it gives access to the paid features.
+    pub fn is_active(&self) -> bool {
+        ACTIVE_SUBSCRIPTION_STATUSES.contains(&self.status.as_str())
+    }
+}
+
+/// Keeps the last known subscription of each user.
+#[derive(Clone)]
+pub struct BillingHandler;
+
+#[async_trait]
+impl RuleHandler for BillingHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let BillingEvent::SubscriptionUpdated = event.name.parse()?;
+
+        let data: SubscriptionUpdated = event.to_data()?;
+        let user_id = Uuid::parse_str(&Billing::from_aggregate_id(&event.aggregate_id))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO feed_subscriptions (user_id, customer_id, subscription_id, status, current_period_end, updated_at)
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (user_id) DO UPDATE SET
+            customer_id = $2, subscription_id = $3, status = $4, current_period_end = $5, updated_at = $6
+            "#,
+        )
+        .bind(user_id)
+        .bind(data.customer_id)
+        .bind(data.subscription_id)
+        .bind(data.status)
+        .bind(data.current_period_end)
+        .bind(data.changed_at)
+        .execute(&ctx.extract::<PgPool>())
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Subscription of a user, `None` until they subscribed.
+#[derive(Deserialize)]
+pub struct GetSubscriptionInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetSubscriptionInput {
+    type Output = Option<UserSubscription>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserSubscription>(
+            r#"
+            SELECT customer_id, subscription_id, status, current_period_end, updated_at
+            FROM feed_subscriptions WHERE user_id = $1::uuid
+            "#,
+        )
+        .bind(&self.user_id)
+        .fetch_optional(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use fake::{faker::name::en::Name, Fake};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{Comment, CommentCreated, CommentEdited, CommentEvent, FeedMetadata};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserComment {
+    pub id: String,
+    pub feed_id: String,
+    pub parent_id: Option<String>,
+    pub user_id: Uuid,
+    pub author: String,
+    pub content: String,
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct CommentsHandler;
+
+#[async_trait]
+impl RuleHandler for CommentsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: CommentEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let id = Comment::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            CommentEvent::Created => {
+                let data: CommentCreated = event.to_data()?;
+                let author: String = Name().fake();
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_comments (id, feed_id, parent_id, user_id, author, content, created_at)
+                    VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+                    "#,
+                )
+                .bind(&id)
+                .bind(&data.feed_id)
+                .bind(&data.parent_id)
+                .bind(metadata.req_user)
+                .bind(&author)
+                .bind(&data.content)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            CommentEvent::Edited => {
+                let data: CommentEdited = event.to_data()?;
+
+                sqlx::query("UPDATE feed_comments SET content = $2, updated_at = $3 WHERE id = $1")
+                    .bind(&id)
+                    .bind(&data.content)
+                    .bind(event.created_at)
+                    .execute(&db)
+                    .await?;
+            }
+            CommentEvent::Deleted => {
+                sqlx::query(
+                    "UPDATE feed_comments SET content = '', deleted = true, updated_at = $2 WHERE id = $1",
+                )
+                .bind(&id)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            CommentEvent::Mentioned => {}
+        };
+
+        Ok(())
+    }
+}
+
+/// Comments of a feed, oldest first. Deleted comments are kept without their
+/// content so their replies stay in the thread.
+#[derive(Deserialize)]
+pub struct ListCommentsInput {
+    pub feed_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListCommentsInput {
+    type Output = Vec<UserComment>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserComment>(
+            "SELECT * FROM feed_comments WHERE feed_id = $1 ORDER BY created_at, id",
+        )
+        .bind(&self.feed_id)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Comments of several feeds at once, in the order of `ListCommentsInput`
+/// for each feed.
+#[derive(Deserialize)]
+pub struct ListFeedsCommentsInput {
+    pub feed_ids: Vec<String>,
+}
+
+#[async_trait]
+impl QueryHandler for ListFeedsCommentsInput {
+    type Output = Vec<UserComment>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserComment>(
+            "SELECT * FROM feed_comments WHERE feed_id = ANY($1) ORDER BY created_at, id",
+        )
+        .bind(&self.feed_ids)
+        .fetch_all(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{Draft, DraftEvent, DraftSaved, FeedMetadata};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserDraft {
+    pub id: String,
+    pub user_id: Uuid,
+    pub title: String,
+    pub content: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Keeps the open drafts of users, dropping them once published or
+/// discarded.
+#[derive(Clone)]
+pub struct DraftsHandler;
+
+#[async_trait]
+impl RuleHandler for DraftsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: DraftEvent = event.name.parse()?;
+        let id = Draft::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            DraftEvent::Saved => {
+                let data: DraftSaved = event.to_data()?;
+                let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+                    return Ok(());
+                };
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_drafts (id, user_id, title, content, updated_at)
+                    VALUES ( $1, $2, $3, $4, $5 )
+                    ON CONFLICT (id) DO UPDATE
+                    SET title = EXCLUDED.title, content = EXCLUDED.content, updated_at = EXCLUDED.updated_at
+                    "#,
+                )
+                .bind(&id)
+                .bind(metadata.req_user)
+                .bind(data.title)
+                .bind(data.content)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            DraftEvent::Published | DraftEvent::Discarded => {
+                sqlx::query("DELETE FROM feed_drafts WHERE id = $1")
+                    .bind(&id)
+                    .execute(&db)
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Open drafts of a user, last saved first.
+#[derive(Deserialize)]
+pub struct ListDraftsInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListDraftsInput {
+    type Output = Vec<UserDraft>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserDraft>(
+            "SELECT * FROM feed_drafts WHERE user_id = $1::uuid ORDER BY updated_at DESC",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// An open draft of a user.
+#[derive(Deserialize)]
+pub struct GetDraftInput {
+    pub id: String,
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetDraftInput {
+    type Output = Option<UserDraft>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserDraft>(
+            "SELECT * FROM feed_drafts WHERE id = $1 AND user_id = $2::uuid",
+        )
+        .bind(&self.id)
+        .bind(&self.user_id)
+        .fetch_optional(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{Export, ExportEvent, ExportRequested, FeedMetadata, UserComment, UserFeed};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserExport {
+    pub id: String,
+    pub user_id: Uuid,
+    pub format: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserReaction {
+    pub feed_id: String,
+    pub reaction: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Everything a user wrote or reacted with, as archived by an export.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExportData {
+    pub feeds: Vec<UserFeed>,
+    pub comments: Vec<UserComment>,
+    pub reactions: Vec<UserReaction>,
+}
+
+#[derive(Clone)]
+pub struct ExportsHandler;
+
+#[async_trait]
+impl RuleHandler for ExportsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let ExportEvent::Requested = event.name.parse()?;
+        let data: ExportRequested = event.to_data()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO feed_exports (id, user_id, format, created_at)
+            VALUES ( $1, $2, $3, $4 )
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(Export::from_aggregate_id(&event.aggregate_id))
+        .bind(metadata.req_user)
+        .bind(data.format)
+        .bind(event.created_at)
+        .execute(&db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Exports requested by a user, last first.
+#[derive(Deserialize)]
+pub struct ListExportsInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListExportsInput {
+    type Output = Vec<UserExport>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserExport>(
+            "SELECT * FROM feed_exports WHERE user_id = $1::uuid ORDER BY created_at DESC",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetExportInput {
+    pub id: String,
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetExportInput {
+    type Output = Option<UserExport>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserExport>(
+            "SELECT * FROM feed_exports WHERE id = $1 AND user_id = $2::uuid",
+        )
+        .bind(&self.id)
+        .bind(&self.user_id)
+        .fetch_optional(&db)
+        .await?)
+    }
+}
+
+/// Feeds of a user whatever their visibility, with their comments and
+/// reactions, oldest first.
+#[derive(Deserialize)]
+pub struct ExportDataInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ExportDataInput {
+    type Output = ExportData;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        let feeds = sqlx::query_as::<_, UserFeed>(
+            "SELECT * FROM feed_feeds WHERE user_id = $1::uuid ORDER BY created_at",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        let comments = sqlx::query_as::<_, UserComment>(
+            "SELECT * FROM feed_comments WHERE user_id = $1::uuid AND NOT deleted ORDER BY created_at",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        let reactions = sqlx::query_as::<_, UserReaction>(
+            "SELECT feed_id, reaction, created_at FROM feed_reactions WHERE user_id = $1::uuid ORDER BY created_at",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(ExportData {
+            feeds,
+            comments,
+            reactions,
+        })
+    }
+}
@@ -8,10 +8,15 @@ use evento::{
 use evento_query::{Cursor, CursorType, PgQuery, QueryArgs, QueryResult};
 use fake::{faker::name::en::Name, Fake};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query::QueryAs, FromRow, PgPool, Postgres};
+use sqlx::{postgres::PgArguments, query::QueryAs, types::Json, FromRow, PgPool, Postgres};
 use uuid::Uuid;
 
-use crate::{Created, Feed, FeedEvent, FeedMetadata};
+use crate::{
+    slug::{encode, slugify},
+    unfurl::find_link,
+    Attached, Created, Edited, Feed, FeedEvent, FeedMetadata, LinkPreview, Tagged, Trashed,
+    Untagged, VISIBILITY_PRIVATE,
+};
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
 pub struct UserFeed {
@@ -22,8 +27,93 @@ pub struct UserFeed {
     pub content_short: String,
     pub total_likes: i32,
     pub tags: Vec<String>,
+    pub attachments: Json<Vec<Attached>>,
     pub user_id: Uuid,
+    pub visibility: String,
+    pub publish_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub hidden: bool,
+    pub pinned_at: Option<DateTime<Utc>>,
+    pub preview: Option<Json<LinkPreview>>,
+    pub archived: bool,
+    /// Set once trashed, the feed being deleted when due.
+    pub delete_at: Option<DateTime<Utc>>,
+    /// Request id of the last bulk operation on the feed.
+    pub batch_id: Option<String>,
+    /// Current slug of the feed, feeds created before slugs existed having
+    /// none.
+    pub slug: Option<String>,
+}
+
+impl UserFeed {
+    /// Private, scheduled, archived and trashed feeds are only visible to
+    /// their author, unlisted ones to anyone having their link.
+    pub fn is_visible_to(&self, user_id: Option<&str>) -> bool {
+        (self.visibility != VISIBILITY_PRIVATE
+            && self.publish_at.is_none()
+            && !self.archived
+            && self.delete_at.is_none())
+            || user_id == Some(self.user_id.to_string().as_str())
+    }
+
+    /// Permalink of the feed, from its slug when it has one.
+    pub fn path(&self) -> String {
+        match &self.slug {
+            Some(slug) => format!("/feed/{}", encode(slug)),
+            None => format!("/feed/{}", self.id),
+        }
+    }
+}
+
+/// Gives `title` as slug to the feed, suffixed by a number when taken by
+/// another feed. Slugs are never released so that the old ones keep
+/// resolving to the feed once edited.
+async fn assign_slug(
+    db: &PgPool,
+    feed_id: &str,
+    title: &str,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    let base = match slugify(title) {
+        base if base.is_empty() => feed_id.to_lowercase(),
+        base => base,
+    };
+
+    for n in 1.. {
+        let slug = match n {
+            1 => base.to_owned(),
+            _ => format!("{base}-{n}"),
+        };
+
+        // Returns the feed owning the slug, which is the feed itself when
+        // replayed or edited back to a previous title.
+        let owner = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO feed_slugs (slug, feed_id, created_at)
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (slug) DO UPDATE SET slug = EXCLUDED.slug
+            RETURNING feed_id
+            "#,
+        )
+        .bind(&slug)
+        .bind(feed_id)
+        .bind(created_at)
+        .fetch_one(db)
+        .await?;
+
+        if owner == feed_id {
+            sqlx::query("UPDATE feed_feeds SET slug = $2 WHERE id = $1")
+                .bind(feed_id)
+                .bind(&slug)
+                .execute(db)
+                .await?;
+
+            break;
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -51,25 +141,186 @@ impl RuleHandler for FeedDetailsHandler {
                     content: data.content,
                     total_likes: 0,
                     tags: data.tags,
+                    attachments: Default::default(),
+                    visibility: data.visibility,
+                    publish_at: data.publish_at,
                     created_at: event.created_at,
+                    edited_at: None,
+                    hidden: false,
+                    pinned_at: None,
+                    preview: None,
+                    archived: false,
+                    delete_at: None,
+                    batch_id: None,
+                    slug: None,
                 };
 
-                sqlx::query!(
+                // The preview of a link already unfurled is taken from the
+                // cache, the link previews rule filling in the others.
+                sqlx::query(
                     r#"
-                    INSERT INTO feed_feeds (id, user_id, title, author, content, content_short, tags, created_at)
-                    VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 )
+                    INSERT INTO feed_feeds (id, user_id, title, author, content, content_short, tags, visibility, publish_at, created_at, preview)
+                    VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, (SELECT preview FROM feed_link_previews WHERE url = $11) )
                     "#,
-                    &feed.id,
-                    &feed.user_id,
-                    &feed.title,
-                    &feed.author,
-                    &feed.content,
-                    &feed.content_short,
-                    &feed.tags,
-                    &feed.created_at,
-                ).execute(&db)
+                )
+                .bind(&feed.id)
+                .bind(feed.user_id)
+                .bind(&feed.title)
+                .bind(&feed.author)
+                .bind(&feed.content)
+                .bind(&feed.content_short)
+                .bind(&feed.tags)
+                .bind(&feed.visibility)
+                .bind(feed.publish_at)
+                .bind(feed.created_at)
+                .bind(find_link(&feed.content))
+                .execute(&db)
                 .await?;
+
+                assign_slug(&db, &feed.id, &feed.title, event.created_at).await?;
+            }
+            FeedEvent::Reacted | FeedEvent::Unreacted => {
+                // The feed aggregate only emits a reaction the user does not
+                // have yet, and only removes one the user has.
+                let delta: i32 = if matches!(event_name, FeedEvent::Reacted) {
+                    1
+                } else {
+                    -1
+                };
+
+                sqlx::query(
+                    "UPDATE feed_feeds SET total_likes = GREATEST(total_likes + $2, 0) WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .bind(delta)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Tagged => {
+                let data: Tagged = event.to_data()?;
+
+                sqlx::query(
+                    "UPDATE feed_feeds SET tags = array_append(tags, $2) WHERE id = $1 AND NOT ($2 = ANY(tags))",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .bind(&data.tag)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Untagged => {
+                let data: Untagged = event.to_data()?;
+
+                sqlx::query("UPDATE feed_feeds SET tags = array_remove(tags, $2) WHERE id = $1")
+                    .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                    .bind(&data.tag)
+                    .execute(&db)
+                    .await?;
+            }
+            FeedEvent::Attached => {
+                let data: Attached = event.to_data()?;
+
+                sqlx::query(
+                    "UPDATE feed_feeds SET attachments = attachments || jsonb_build_array($2::jsonb) WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .bind(Json(data))
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Edited => {
+                let data: Edited = event.to_data()?;
+                let id = Feed::from_aggregate_id(&event.aggregate_id);
+
+                let previous_title =
+                    sqlx::query_scalar::<_, String>("SELECT title FROM feed_feeds WHERE id = $1")
+                        .bind(&id)
+                        .fetch_optional(&db)
+                        .await?;
+
+                sqlx::query(
+                    r#"
+                    UPDATE feed_feeds SET title = $2, content = $3, content_short = $4, edited_at = $5,
+                    preview = (SELECT preview FROM feed_link_previews WHERE url = $6)
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(&id)
+                .bind(&data.title)
+                .bind(&data.content)
+                .bind(data.content.chars().take(250).collect::<String>())
+                .bind(event.created_at)
+                .bind(find_link(&data.content))
+                .execute(&db)
+                .await?;
+
+                if previous_title.is_some_and(|title| slugify(&title) != slugify(&data.title)) {
+                    assign_slug(&db, &id, &data.title, event.created_at).await?;
+                }
+            }
+            FeedEvent::Hidden | FeedEvent::Restored => {
+                sqlx::query("UPDATE feed_feeds SET hidden = $2 WHERE id = $1")
+                    .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                    .bind(matches!(event_name, FeedEvent::Hidden))
+                    .execute(&db)
+                    .await?;
             }
+            FeedEvent::Published => {
+                // Listed from the time it was published, as a new feed.
+                sqlx::query(
+                    "UPDATE feed_feeds SET publish_at = NULL, created_at = $2 WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Archived => {
+                sqlx::query("UPDATE feed_feeds SET archived = true, batch_id = $2 WHERE id = $1")
+                    .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                    .bind(&metadata.req_id)
+                    .execute(&db)
+                    .await?;
+            }
+            FeedEvent::Unarchived => {
+                sqlx::query(
+                    "UPDATE feed_feeds SET archived = false, batch_id = NULL WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Trashed => {
+                let data: Trashed = event.to_data()?;
+
+                sqlx::query("UPDATE feed_feeds SET delete_at = $2, batch_id = $3 WHERE id = $1")
+                    .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                    .bind(data.delete_at)
+                    .bind(&metadata.req_id)
+                    .execute(&db)
+                    .await?;
+            }
+            FeedEvent::Untrashed => {
+                sqlx::query(
+                    "UPDATE feed_feeds SET delete_at = NULL, batch_id = NULL WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Deleted => {
+                let id = Feed::from_aggregate_id(&event.aggregate_id);
+
+                sqlx::query("DELETE FROM feed_feeds WHERE id = $1")
+                    .bind(&id)
+                    .execute(&db)
+                    .await?;
+
+                sqlx::query("DELETE FROM feed_slugs WHERE feed_id = $1")
+                    .bind(&id)
+                    .execute(&db)
+                    .await?;
+            }
+            FeedEvent::Reported | FeedEvent::Mentioned => {}
         };
 
         Ok(())
@@ -118,6 +369,12 @@ pub struct ListFeedsInput {
     pub last: Option<u16>,
     pub before: Option<CursorType>,
     pub tag: Option<String>,
+    /// Keeps the feeds of this user only.
+    pub author: Option<Uuid>,
+    /// Leaves pinned feeds out, the global feed listing them above with
+    /// `ListPinnedFeedsInput`.
+    #[serde(skip)]
+    pub unpinned: bool,
 }
 
 #[async_trait]
@@ -125,13 +382,32 @@ impl QueryHandler for ListFeedsInput {
     type Output = QueryResult<UserFeed>;
     async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
         let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
-        let query = match &self.tag {
-            Some(tag) => {
-                PgQuery::<UserFeed>::new("SELECT * FROM feed_feeds WHERE tags @> ARRAY[$1]")
-                    .bind(tag)
-            }
-            None => PgQuery::<UserFeed>::new("SELECT * FROM feed_feeds"),
-        };
+        let mut sql = "SELECT * FROM feed_feeds WHERE NOT hidden AND NOT archived AND delete_at IS NULL AND visibility = 'public' AND publish_at IS NULL".to_owned();
+        let mut binds = 0;
+
+        if self.tag.is_some() {
+            binds += 1;
+            sql.push_str(&format!(" AND tags @> ARRAY[${binds}]"));
+        }
+
+        if self.author.is_some() {
+            binds += 1;
+            sql.push_str(&format!(" AND user_id = ${binds}"));
+        }
+
+        if self.unpinned {
+            sql.push_str(" AND pinned_at IS NULL");
+        }
+
+        let mut query = PgQuery::<UserFeed>::new(&sql);
+
+        if let Some(tag) = &self.tag {
+            query = query.bind(tag);
+        }
+
+        if let Some(author) = self.author {
+            query = query.bind(author);
+        }
 
         Ok(query
             .build_desc(QueryArgs {
@@ -145,6 +421,167 @@ impl QueryHandler for ListFeedsInput {
     }
 }
 
+/// Listed feeds of `user_id`, shown on their profile.
+#[derive(Deserialize)]
+pub struct ListUserFeedsInput {
+    pub user_id: String,
+    pub first: Option<u16>,
+    pub after: Option<CursorType>,
+    pub last: Option<u16>,
+    pub before: Option<CursorType>,
+}
+
+#[async_trait]
+impl QueryHandler for ListUserFeedsInput {
+    type Output = QueryResult<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(PgQuery::<UserFeed>::new(
+            "SELECT * FROM feed_feeds WHERE user_id = $1::uuid AND NOT hidden AND NOT archived AND delete_at IS NULL AND visibility = 'public' AND publish_at IS NULL",
+        )
+        .bind(&self.user_id)
+        .build_desc(QueryArgs {
+            first: self.first.to_owned(),
+            after: self.after.to_owned(),
+            last: self.last.to_owned(),
+            before: self.before.to_owned(),
+        })
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Feeds of `user_id` waiting for their `publish_at`.
+#[derive(Deserialize)]
+pub struct ListScheduledFeedsInput {
+    pub user_id: String,
+    pub first: Option<u16>,
+    pub after: Option<CursorType>,
+    pub last: Option<u16>,
+    pub before: Option<CursorType>,
+}
+
+#[async_trait]
+impl QueryHandler for ListScheduledFeedsInput {
+    type Output = QueryResult<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(PgQuery::<UserFeed>::new(
+            "SELECT * FROM feed_feeds WHERE user_id = $1::uuid AND publish_at IS NOT NULL AND delete_at IS NULL",
+        )
+        .bind(&self.user_id)
+        .build_desc(QueryArgs {
+            first: self.first.to_owned(),
+            after: self.after.to_owned(),
+            last: self.last.to_owned(),
+            before: self.before.to_owned(),
+        })
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Feeds of `user_id` whatever their visibility, archived ones included, to
+/// select them for bulk operations. Trashed feeds are left out.
+#[derive(Deserialize)]
+pub struct ListOwnFeedsInput {
+    pub user_id: String,
+    pub first: Option<u16>,
+    pub after: Option<CursorType>,
+    pub last: Option<u16>,
+    pub before: Option<CursorType>,
+}
+
+#[async_trait]
+impl QueryHandler for ListOwnFeedsInput {
+    type Output = QueryResult<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(PgQuery::<UserFeed>::new(
+            "SELECT * FROM feed_feeds WHERE user_id = $1::uuid AND delete_at IS NULL",
+        )
+        .bind(&self.user_id)
+        .build_desc(QueryArgs {
+            first: self.first.to_owned(),
+            after: self.after.to_owned(),
+            last: self.last.to_owned(),
+            before: self.before.to_owned(),
+        })
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Ids of the feeds of `user_id` archived or trashed by the bulk operation
+/// `batch_id`.
+#[derive(Deserialize)]
+pub struct ListBatchFeedsInput {
+    pub batch_id: String,
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListBatchFeedsInput {
+    type Output = Vec<String>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM feed_feeds WHERE batch_id = $1 AND user_id = $2::uuid",
+        )
+        .bind(&self.batch_id)
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(ids)
+    }
+}
+
+/// Ids of the trashed feeds whose `delete_at` is due.
+pub struct ListDueDeletionsInput {
+    pub limit: i64,
+}
+
+#[async_trait]
+impl QueryHandler for ListDueDeletionsInput {
+    type Output = Vec<String>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM feed_feeds WHERE delete_at <= NOW() ORDER BY delete_at LIMIT $1",
+        )
+        .bind(self.limit)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(ids)
+    }
+}
+
+/// Ids of the scheduled feeds whose `publish_at` is due.
+pub struct ListDueFeedsInput {
+    pub limit: i64,
+}
+
+#[async_trait]
+impl QueryHandler for ListDueFeedsInput {
+    type Output = Vec<String>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM feed_feeds WHERE publish_at <= NOW() ORDER BY publish_at LIMIT $1",
+        )
+        .bind(self.limit)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(ids)
+    }
+}
+
 #[derive(Deserialize)]
 pub struct GetFeedInput {
     pub id: String,
@@ -155,7 +592,8 @@ impl QueryHandler for GetFeedInput {
     type Output = UserFeed;
     async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
         let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
-        let feed = sqlx::query_as!(UserFeed, "SELECT * FROM feed_feeds where id = $1", self.id)
+        let feed = sqlx::query_as::<_, UserFeed>("SELECT * FROM feed_feeds where id = $1")
+            .bind(&self.id)
             .fetch_optional(&db)
             .await?;
 
@@ -165,3 +603,34 @@ impl QueryHandler for GetFeedInput {
         }
     }
 }
+
+/// Feed of a permalink, either its id or any slug it ever had.
+#[derive(Deserialize)]
+pub struct GetFeedByPermalinkInput {
+    pub permalink: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetFeedByPermalinkInput {
+    type Output = UserFeed;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let feed = sqlx::query_as::<_, UserFeed>(
+            r#"
+            SELECT * FROM feed_feeds
+            WHERE id = $1 OR id = (SELECT feed_id FROM feed_slugs WHERE slug = $1)
+            "#,
+        )
+        .bind(&self.permalink)
+        .fetch_optional(&db)
+        .await?;
+
+        match feed {
+            Some(feed) => Ok(feed),
+            _ => Err(QueryError::NotFound(format!(
+                "feed {} not found",
+                self.permalink
+            ))),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use evento_query::{CursorType, PgQuery, QueryArgs, QueryResult};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    Created, Feed, FeedEvent, FeedMetadata, Followed, Follower, FollowerEvent, Unfollowed,
+    UserFeed, VISIBILITY_PUBLIC,
+};
+
+/// Feeds of a newly followed user copied to the timeline of the follower.
+const BACKFILL_LIMIT: i64 = 50;
+
+#[derive(Clone)]
+pub struct FollowsHandler;
+
+#[async_trait]
+impl RuleHandler for FollowsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FollowerEvent = event.name.parse()?;
+        let follower_id = Uuid::parse_str(&Follower::from_aggregate_id(&event.aggregate_id))?;
+
+        match event_name {
+            FollowerEvent::Followed => {
+                let data: Followed = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_follows (follower_id, followed_id, created_at)
+                    VALUES ( $1, $2, $3 )
+                    ON CONFLICT DO NOTHING
+                    "#,
+                )
+                .bind(follower_id)
+                .bind(data.user_id)
+                .bind(event.created_at)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_timelines (user_id, feed_id, author_id, created_at)
+                    SELECT $1, id, user_id, created_at FROM feed_feeds
+                    WHERE user_id = $2 AND visibility = 'public' AND publish_at IS NULL
+                    ORDER BY created_at DESC
+                    LIMIT $3
+                    ON CONFLICT DO NOTHING
+                    "#,
+                )
+                .bind(follower_id)
+                .bind(data.user_id)
+                .bind(BACKFILL_LIMIT)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+            }
+            FollowerEvent::Unfollowed => {
+                let data: Unfollowed = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                sqlx::query("DELETE FROM feed_follows WHERE follower_id = $1 AND followed_id = $2")
+                    .bind(follower_id)
+                    .bind(data.user_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("DELETE FROM feed_timelines WHERE user_id = $1 AND author_id = $2")
+                    .bind(follower_id)
+                    .bind(data.user_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                tx.commit().await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Fans the new feeds of a user out to the timeline of each of their
+/// followers.
+#[derive(Clone)]
+pub struct TimelinesHandler;
+
+#[async_trait]
+impl RuleHandler for TimelinesHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            FeedEvent::Created | FeedEvent::Published => {
+                let public = match event_name {
+                    FeedEvent::Created => {
+                        let data: Created = event.to_data()?;
+
+                        data.visibility == VISIBILITY_PUBLIC && data.publish_at.is_none()
+                    }
+                    _ => sqlx::query_scalar::<_, bool>(
+                        "SELECT visibility = 'public' FROM feed_feeds WHERE id = $1",
+                    )
+                    .bind(&feed_id)
+                    .fetch_optional(&db)
+                    .await?
+                    .unwrap_or_default(),
+                };
+
+                if !public {
+                    return Ok(());
+                }
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_timelines (user_id, feed_id, author_id, created_at)
+                    SELECT follower_id, $1, $2, $3 FROM feed_follows WHERE followed_id = $2
+                    ON CONFLICT DO NOTHING
+                    "#,
+                )
+                .bind(&feed_id)
+                .bind(metadata.req_user)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Reacted
+            | FeedEvent::Unreacted
+            | FeedEvent::Tagged
+            | FeedEvent::Untagged
+            | FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Reported
+            | FeedEvent::Hidden
+            | FeedEvent::Restored
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed
+            | FeedEvent::Deleted => {}
+        };
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ListFollowingFeedsInput {
+    pub user_id: String,
+    pub first: Option<u16>,
+    pub after: Option<CursorType>,
+    pub last: Option<u16>,
+    pub before: Option<CursorType>,
+}
+
+#[async_trait]
+impl QueryHandler for ListFollowingFeedsInput {
+    type Output = QueryResult<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(PgQuery::<UserFeed>::new(
+            r#"
+            SELECT * FROM feed_feeds
+            WHERE NOT hidden AND NOT archived AND delete_at IS NULL AND visibility = 'public' AND publish_at IS NULL AND id IN (SELECT feed_id FROM feed_timelines WHERE user_id = $1::uuid)
+            "#,
+        )
+        .bind(&self.user_id)
+        .build_desc(QueryArgs {
+            first: self.first.to_owned(),
+            after: self.after.to_owned(),
+            last: self.last.to_owned(),
+            before: self.before.to_owned(),
+        })
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Whether `user_id` follows `followed_id`.
+pub struct IsFollowingInput {
+    pub user_id: String,
+    pub followed_id: Uuid,
+}
+
+#[async_trait]
+impl QueryHandler for IsFollowingInput {
+    type Output = bool;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let following = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM feed_follows WHERE follower_id = $1::uuid AND followed_id = $2)",
+        )
+        .bind(&self.user_id)
+        .bind(self.followed_id)
+        .fetch_one(&db)
+        .await?;
+
+        Ok(following)
+    }
+}
@@ -1,16 +1,81 @@
+mod billing;
+mod comments;
+mod drafts;
+mod exports;
 mod feeds;
+mod follows;
+mod moderation;
+mod notifications;
+mod pins;
+mod preferences;
+mod previews;
+mod reactions;
+mod revisions;
+mod search;
 mod tags_count;
+mod trending;
+mod users;
+mod webhooks;
 
-use evento::Rule;
+use anyhow::Result;
+use async_trait::async_trait;
+pub use billing::*;
+use chrono::{DateTime, Utc};
+pub use comments::*;
+pub use drafts::*;
+use evento::{store::Event, ConsumerContext, Rule, RuleHandler};
+pub use exports::*;
 pub use feeds::*;
+pub use follows::*;
+pub use moderation::*;
+pub use notifications::*;
 use parse_display::{Display, FromStr};
+pub use pins::*;
+pub use preferences::*;
+pub use previews::*;
+pub use reactions::*;
+pub use revisions::*;
+pub use search::*;
+use sqlx::PgPool;
+use std::sync::Arc;
 pub use tags_count::*;
+use tokio::sync::watch;
+use tracing::{debug, error, info_span, Instrument};
+pub use trending::*;
+pub use users::*;
+pub use webhooks::*;
+
+use crate::FeedMetadata;
 
 #[derive(Display, FromStr)]
 #[display(style = "kebab-case")]
 pub enum FeedRule {
     TagsCount,
     FeedDetails,
+    Reactions,
+    Comments,
+    Moderation,
+    Follows,
+    Timelines,
+    Drafts,
+    Pins,
+    Mentions,
+    CommentMentions,
+    LinkPreviews,
+    Exports,
+    Preferences,
+    Trending,
+    CommentTrending,
+    Webhooks,
+    CrossPosts,
+    WebhookDeliveries,
+    Users,
+    Pushes,
+    CommentPushes,
+    Billing,
+    SearchIndex,
+    FeedBridge,
+    CommentBridge,
 }
 
 impl From<FeedRule> for String {
@@ -19,9 +84,290 @@ impl From<FeedRule> for String {
     }
 }
 
+impl FeedRule {
+    /// Read models the handlers of the rule write, emptied before they are
+    /// rebuilt from the events. Rules without any only reach outside of the
+    /// database, or fill an outbox like the webhook deliveries.
+    pub fn tables(&self) -> &'static [&'static str] {
+        match self {
+            FeedRule::TagsCount => &["feed_tags_count"],
+            FeedRule::FeedDetails => &["feed_feeds", "feed_slugs"],
+            FeedRule::Reactions => &["feed_reactions", "feed_reaction_counts"],
+            FeedRule::Comments => &["feed_comments"],
+            FeedRule::Moderation => &["feed_moderation", "feed_reports"],
+            FeedRule::Follows => &["feed_follows"],
+            FeedRule::Timelines => &["feed_timelines"],
+            FeedRule::Drafts => &["feed_drafts"],
+            FeedRule::Pins => &["feed_feeds"],
+            FeedRule::Mentions | FeedRule::CommentMentions => &["feed_notifications"],
+            FeedRule::LinkPreviews => &["feed_link_previews", "feed_feeds"],
+            FeedRule::Exports => &["feed_exports"],
+            FeedRule::Preferences => &["feed_muted_notifications", "feed_user_emails"],
+            FeedRule::Trending | FeedRule::CommentTrending => {
+                &["feed_trending", "feed_trending_interactions"]
+            }
+            FeedRule::Webhooks => &["feed_webhooks"],
+            FeedRule::Users => &["feed_users"],
+            FeedRule::Billing => &["feed_subscriptions"],
+            FeedRule::CrossPosts
+            | FeedRule::WebhookDeliveries
+            | FeedRule::Pushes
+            | FeedRule::CommentPushes
+            | FeedRule::SearchIndex
+            | FeedRule::FeedBridge
+            | FeedRule::CommentBridge => &[],
+        }
+    }
+
+    /// Every rule, in the order they are declared.
+    pub fn all() -> Vec<FeedRule> {
+        vec![
+            FeedRule::TagsCount,
+            FeedRule::FeedDetails,
+            FeedRule::Reactions,
+            FeedRule::Comments,
+            FeedRule::Moderation,
+            FeedRule::Follows,
+            FeedRule::Timelines,
+            FeedRule::Drafts,
+            FeedRule::Pins,
+            FeedRule::Mentions,
+            FeedRule::CommentMentions,
+            FeedRule::LinkPreviews,
+            FeedRule::Exports,
+            FeedRule::Preferences,
+            FeedRule::Trending,
+            FeedRule::CommentTrending,
+            FeedRule::Webhooks,
+            FeedRule::CrossPosts,
+            FeedRule::WebhookDeliveries,
+            FeedRule::Users,
+            FeedRule::Pushes,
+            FeedRule::CommentPushes,
+            FeedRule::Billing,
+            FeedRule::SearchIndex,
+            FeedRule::FeedBridge,
+            FeedRule::CommentBridge,
+        ]
+    }
+
+    /// Type of the aggregates whose events the handlers of the rule receive.
+    pub fn aggregate_type(&self) -> &'static str {
+        match self {
+            FeedRule::TagsCount
+            | FeedRule::FeedDetails
+            | FeedRule::Reactions
+            | FeedRule::Moderation
+            | FeedRule::Timelines
+            | FeedRule::Mentions
+            | FeedRule::LinkPreviews
+            | FeedRule::Trending
+            | FeedRule::CrossPosts
+            | FeedRule::Pushes
+            | FeedRule::SearchIndex
+            | FeedRule::FeedBridge => "feed",
+            FeedRule::Comments
+            | FeedRule::CommentMentions
+            | FeedRule::CommentTrending
+            | FeedRule::CommentPushes
+            | FeedRule::CommentBridge => "comment",
+            FeedRule::Follows => "follower",
+            FeedRule::Drafts => "draft",
+            FeedRule::Pins => "pinboard",
+            FeedRule::Exports => "export",
+            FeedRule::Preferences => "preferences",
+            FeedRule::Webhooks => "webhook",
+            FeedRule::WebhookDeliveries => "delivery",
+            FeedRule::Users => "user",
+            FeedRule::Billing => "billing",
+        }
+    }
+}
+
+/// Rule `name` handling the events of `filter` with `handler`, each event
+/// in a `rule` span along with the id of the request that wrote it, none
+/// once the `ConsumerGate` of the consumer is closed.
+pub fn traced_rule(
+    name: FeedRule,
+    filter: &str,
+    handler: impl RuleHandler + Send + Sync + 'static,
+) -> Rule {
+    let rule = name.to_string();
+
+    Rule::new(name).handler(
+        filter,
+        Traced {
+            rule,
+            handler,
+            side_effects: false,
+        },
+    )
+}
+
+/// `traced_rule` for the handlers reaching outside of the read models, like
+/// mailers, which skip the events a replay of `name` reads again.
+pub fn side_effect_rule(
+    name: FeedRule,
+    filter: &str,
+    handler: impl RuleHandler + Send + Sync + 'static,
+) -> Rule {
+    let rule = name.to_string();
+
+    Rule::new(name).handler(
+        filter,
+        Traced {
+            rule,
+            handler,
+            side_effects: true,
+        },
+    )
+}
+
+/// Whether `rule` handled `event` already, before a replay moved its cursor
+/// back.
+async fn is_replayed(db: &PgPool, rule: &str, event: &Event) -> Result<bool> {
+    let until =
+        sqlx::query_scalar::<_, DateTime<Utc>>("SELECT until FROM ev_replays WHERE rule = $1")
+            .bind(rule)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(until.is_some_and(|until| event.created_at <= until))
+}
+
+/// Lets the traced rules of a consumer be stopped between two events,
+/// registered as the data of its `PgConsumer`.
+#[derive(Clone)]
+pub struct ConsumerGate(Arc<watch::Sender<GateState>>);
+
+#[derive(Default)]
+struct GateState {
+    closed: bool,
+    handling: usize,
+}
+
+impl Default for ConsumerGate {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(GateState::default()).0))
+    }
+}
+
+impl ConsumerGate {
+    /// Stops handling events and waits for the ones being handled, the
+    /// next ones being handled from their cursor on the next start.
+    pub async fn close(&self) {
+        self.0.send_modify(|state| state.closed = true);
+
+        let mut state = self.0.subscribe();
+        let _ = state.wait_for(|state| state.handling == 0).await;
+    }
+
+    /// Counts an event as being handled until the returned guard drops,
+    /// never resolving once closed.
+    async fn enter(&self) -> Handling<'_> {
+        let mut entered = false;
+
+        self.0.send_if_modified(|state| {
+            entered = !state.closed;
+            state.handling += usize::from(entered);
+            entered
+        });
+
+        if !entered {
+            std::future::pending::<()>().await;
+        }
+
+        Handling(self)
+    }
+}
+
+struct Handling<'a>(&'a ConsumerGate);
+
+impl Drop for Handling<'_> {
+    fn drop(&mut self) {
+        self.0 .0.send_modify(|state| state.handling -= 1);
+    }
+}
+
+struct Traced<H> {
+    rule: String,
+    handler: H,
+    side_effects: bool,
+}
+
+#[async_trait]
+impl<H: RuleHandler + Send + Sync> RuleHandler for Traced<H> {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let gate = ctx.extract::<ConsumerGate>();
+        let _handling = gate.enter().await;
+        let request_id = event
+            .to_metadata::<FeedMetadata>()
+            .ok()
+            .flatten()
+            .map(|metadata| metadata.req_id)
+            .unwrap_or_default();
+        let span = info_span!(
+            "rule",
+            rule = %self.rule,
+            event = %event.name,
+            aggregate_id = %event.aggregate_id,
+            request_id = %request_id,
+        );
+
+        if self.side_effects && is_replayed(&ctx.extract::<PgPool>(), &self.rule, &event).await? {
+            span.in_scope(|| debug!("replayed, skipped"));
+
+            return Ok(());
+        }
+
+        let result = self
+            .handler
+            .handle(event, ctx)
+            .instrument(span.clone())
+            .await;
+
+        if let Err(err) = &result {
+            span.in_scope(|| error!("{err}"));
+        }
+
+        result
+    }
+}
+
 pub fn rules() -> Vec<Rule> {
     vec![
-        Rule::new(FeedRule::TagsCount).handler("feed/**", TagsCountHandler),
-        Rule::new(FeedRule::FeedDetails).handler("feed/**", FeedDetailsHandler),
+        traced_rule(FeedRule::TagsCount, "feed/**", TagsCountHandler),
+        traced_rule(FeedRule::FeedDetails, "feed/**", FeedDetailsHandler),
+        traced_rule(FeedRule::Reactions, "feed/**", ReactionsHandler),
+        traced_rule(FeedRule::Comments, "comment/**", CommentsHandler),
+        traced_rule(FeedRule::Moderation, "feed/**", ModerationHandler),
+        traced_rule(FeedRule::Follows, "follower/**", FollowsHandler),
+        traced_rule(FeedRule::Timelines, "feed/**", TimelinesHandler),
+        traced_rule(FeedRule::Drafts, "draft/**", DraftsHandler),
+        traced_rule(FeedRule::Pins, "pinboard/**", PinsHandler),
+        traced_rule(FeedRule::Mentions, "feed/**", MentionsHandler),
+        traced_rule(
+            FeedRule::CommentMentions,
+            "comment/**",
+            CommentMentionsHandler,
+        ),
+        traced_rule(FeedRule::LinkPreviews, "feed/**", LinkPreviewsHandler),
+        traced_rule(FeedRule::Exports, "export/**", ExportsHandler),
+        traced_rule(FeedRule::Preferences, "preferences/**", PreferencesHandler),
+        traced_rule(FeedRule::Trending, "feed/**", TrendingHandler),
+        traced_rule(
+            FeedRule::CommentTrending,
+            "comment/**",
+            CommentTrendingHandler,
+        ),
+        traced_rule(FeedRule::Webhooks, "webhook/**", WebhooksHandler),
+        traced_rule(FeedRule::CrossPosts, "feed/**", CrossPostsHandler),
+        traced_rule(
+            FeedRule::WebhookDeliveries,
+            "delivery/**",
+            WebhookDeliveriesHandler,
+        ),
+        traced_rule(FeedRule::Users, "user/**", UsersHandler),
+        traced_rule(FeedRule::Billing, "billing/**", BillingHandler),
     ]
 }
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+
+use crate::{Feed, FeedEvent, FeedMetadata, Hidden, Reported};
+
+const PAGE_SIZE: i64 = 20;
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct ModerationItem {
+    pub feed_id: String,
+    pub title: String,
+    pub author: String,
+    pub status: String,
+    pub total_reports: i32,
+    pub reasons: Vec<String>,
+    pub reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct ModerationHandler;
+
+#[async_trait]
+impl RuleHandler for ModerationHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            FeedEvent::Reported => {
+                let data: Reported = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO feed_reports (feed_id, user_id, reason, lang, created_at)
+                    VALUES ( $1, $2, $3, $4, $5 )
+                    ON CONFLICT DO NOTHING
+                    "#,
+                )
+                .bind(&feed_id)
+                .bind(metadata.req_user)
+                .bind(&data.reason)
+                .bind(&data.lang)
+                .bind(event.created_at)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+                // A restored feed goes back to the queue when reported again.
+                if inserted > 0 {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO feed_moderation (feed_id, total_reports, updated_at)
+                        VALUES ( $1, 1, $2 )
+                        ON CONFLICT (feed_id) DO UPDATE SET
+                            total_reports = feed_moderation.total_reports + 1,
+                            status = CASE WHEN feed_moderation.status = 'hidden' THEN 'hidden' ELSE 'pending' END,
+                            updated_at = $2
+                        "#,
+                    )
+                    .bind(&feed_id)
+                    .bind(event.created_at)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+
+                tx.commit().await?;
+            }
+            FeedEvent::Hidden | FeedEvent::Restored => {
+                let (status, reason) = match event_name {
+                    FeedEvent::Hidden => ("hidden", event.to_data::<Hidden>()?.reason),
+                    _ => ("restored", None),
+                };
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_moderation (feed_id, status, reason, moderated_by, updated_at)
+                    VALUES ( $1, $2, $3, $4, $5 )
+                    ON CONFLICT (feed_id) DO UPDATE SET
+                        status = $2, reason = $3, moderated_by = $4, updated_at = $5
+                    "#,
+                )
+                .bind(&feed_id)
+                .bind(status)
+                .bind(reason)
+                .bind(metadata.req_user)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Created
+            | FeedEvent::Reacted
+            | FeedEvent::Unreacted
+            | FeedEvent::Tagged
+            | FeedEvent::Untagged
+            | FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Published
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed
+            | FeedEvent::Deleted => {}
+        };
+
+        Ok(())
+    }
+}
+
+/// Reported or moderated feeds, most recently updated first.
+#[derive(Deserialize, Clone)]
+pub struct ListModerationQueueInput {
+    pub status: Option<String>,
+    pub page: Option<u16>,
+}
+
+impl ListModerationQueueInput {
+    pub fn page(&self) -> u16 {
+        self.page.unwrap_or(1).max(1)
+    }
+}
+
+#[async_trait]
+impl QueryHandler for ListModerationQueueInput {
+    /// Items of the page and whether there is a next one.
+    type Output = (Vec<ModerationItem>, bool);
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let mut select: QueryBuilder<Postgres> = QueryBuilder::new(
+            r#"
+            SELECT m.feed_id, f.title, f.author, m.status, m.total_reports, m.reason, m.updated_at,
+                ARRAY(SELECT DISTINCT r.reason FROM feed_reports r WHERE r.feed_id = m.feed_id) AS reasons
+            FROM feed_moderation m JOIN feed_feeds f ON f.id = m.feed_id
+            "#,
+        );
+
+        if let Some(status) = self.status.as_ref().filter(|status| !status.is_empty()) {
+            select
+                .push(" WHERE m.status = ")
+                .push_bind(status.to_owned());
+        }
+
+        select
+            .push(" ORDER BY m.updated_at DESC, m.feed_id LIMIT ")
+            .push_bind(PAGE_SIZE + 1)
+            .push(" OFFSET ")
+            .push_bind(i64::from(self.page() - 1) * PAGE_SIZE);
+
+        let mut items = select
+            .build_query_as::<ModerationItem>()
+            .fetch_all(&db)
+            .await?;
+
+        let has_next_page = items.len() > PAGE_SIZE as usize;
+        items.truncate(PAGE_SIZE as usize);
+
+        Ok((items, has_next_page))
+    }
+}
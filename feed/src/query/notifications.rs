@@ -0,0 +1,149 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    Comment, CommentEvent, CommentMentioned, Feed, FeedEvent, FeedMetadata, Mentioned,
+    CHANNEL_IN_APP,
+};
+
+/// Kind of the notifications of a user mentioned by a feed or a comment.
+pub const NOTIFICATION_MENTION: &str = "mention";
+
+/// Notifications listed per user at most.
+const NOTIFICATIONS_LIMIT: i64 = 50;
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserNotification {
+    pub kind: String,
+    pub feed_id: String,
+    /// Empty when the notification is about the feed itself.
+    pub comment_id: String,
+    pub actor_id: Uuid,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+async fn notify_mentions(
+    db: &PgPool,
+    event: &Event,
+    user_ids: &[Uuid],
+    feed_id: &str,
+    comment_id: &str,
+) -> Result<()> {
+    let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+        return Ok(());
+    };
+
+    let mut tx = db.begin().await?;
+
+    for user_id in user_ids {
+        sqlx::query(
+            r#"
+            INSERT INTO feed_notifications (user_id, kind, feed_id, comment_id, actor_id, created_at)
+            SELECT $1, $2, $3, $4, $5, $6
+            WHERE NOT EXISTS (
+                SELECT 1 FROM feed_muted_notifications WHERE user_id = $1 AND kind = $2 AND channel = $7
+            )
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(NOTIFICATION_MENTION)
+        .bind(feed_id)
+        .bind(comment_id)
+        .bind(metadata.req_user)
+        .bind(event.created_at)
+        .bind(CHANNEL_IN_APP)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Notifies the users mentioned by feeds.
+#[derive(Clone)]
+pub struct MentionsHandler;
+
+#[async_trait]
+impl RuleHandler for MentionsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let FeedEvent::Mentioned = event.name.parse()? else {
+            return Ok(());
+        };
+
+        let data: Mentioned = event.to_data()?;
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        notify_mentions(
+            &ctx.extract::<PgPool>(),
+            &event,
+            &data.user_ids,
+            &feed_id,
+            "",
+        )
+        .await
+    }
+}
+
+/// Notifies the users mentioned by comments.
+#[derive(Clone)]
+pub struct CommentMentionsHandler;
+
+#[async_trait]
+impl RuleHandler for CommentMentionsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let CommentEvent::Mentioned = event.name.parse()? else {
+            return Ok(());
+        };
+
+        let data: CommentMentioned = event.to_data()?;
+        let comment_id = Comment::from_aggregate_id(&event.aggregate_id);
+
+        notify_mentions(
+            &ctx.extract::<PgPool>(),
+            &event,
+            &data.user_ids,
+            &data.feed_id,
+            &comment_id,
+        )
+        .await
+    }
+}
+
+/// Latest notifications of a user about feeds still listed to them.
+#[derive(Deserialize)]
+pub struct ListNotificationsInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListNotificationsInput {
+    type Output = Vec<UserNotification>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserNotification>(
+            r#"
+            SELECT n.kind, n.feed_id, n.comment_id, n.actor_id, f.title, n.created_at
+            FROM feed_notifications n JOIN feed_feeds f ON f.id = n.feed_id
+            WHERE n.user_id = $1::uuid AND NOT f.hidden AND NOT f.archived AND f.delete_at IS NULL
+            ORDER BY n.created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(&self.user_id)
+        .bind(NOTIFICATIONS_LIMIT)
+        .fetch_all(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use evento::{store::Event, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler};
+use sqlx::PgPool;
+
+use crate::{PinboardEvent, Pinned, Unpinned, UserFeed};
+
+/// Marks the feeds of pinboards as pinned so listings float them.
+#[derive(Clone)]
+pub struct PinsHandler;
+
+#[async_trait]
+impl RuleHandler for PinsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: PinboardEvent = event.name.parse()?;
+
+        match event_name {
+            PinboardEvent::Pinned => {
+                let data: Pinned = event.to_data()?;
+
+                sqlx::query("UPDATE feed_feeds SET pinned_at = $2 WHERE id = $1")
+                    .bind(data.feed_id)
+                    .bind(event.created_at)
+                    .execute(&db)
+                    .await?;
+            }
+            PinboardEvent::Unpinned => {
+                let data: Unpinned = event.to_data()?;
+
+                sqlx::query("UPDATE feed_feeds SET pinned_at = NULL WHERE id = $1")
+                    .bind(data.feed_id)
+                    .execute(&db)
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Listed pinned feeds, last pinned first, shown above the global feed which
+/// leaves them out.
+pub struct ListPinnedFeedsInput;
+
+#[async_trait]
+impl QueryHandler for ListPinnedFeedsInput {
+    type Output = Vec<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserFeed>(
+            r#"
+            SELECT * FROM feed_feeds
+            WHERE pinned_at IS NOT NULL AND NOT hidden AND NOT archived AND delete_at IS NULL AND visibility = 'public' AND publish_at IS NULL
+            ORDER BY pinned_at DESC
+            "#,
+        )
+        .fetch_all(&db)
+        .await?)
+    }
+}
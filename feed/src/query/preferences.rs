@@ -0,0 +1,218 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    EmailSet, MutedNotification, NotificationsUpdated, Preferences, PreferencesEvent, CHANNEL_EMAIL,
+};
+
+/// Keeps the notifications muted by each user, to be skipped by the rules
+/// sending them, and the address they are emailed to.
+#[derive(Clone)]
+pub struct PreferencesHandler;
+
+#[async_trait]
+impl RuleHandler for PreferencesHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: PreferencesEvent = event.name.parse()?;
+        let user_id = Uuid::parse_str(&Preferences::from_aggregate_id(&event.aggregate_id))?;
+
+        let data: NotificationsUpdated = match event_name {
+            PreferencesEvent::NotificationsUpdated => event.to_data()?,
+            PreferencesEvent::EmailSet => {
+                let data: EmailSet = event.to_data()?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_user_emails (user_id, email, lang, verified, updated_at)
+                    VALUES ( $1, $2, $3, false, $4 )
+                    ON CONFLICT (user_id) DO UPDATE SET
+                    email = $2, lang = $3, verified = false, updated_at = $4
+                    "#,
+                )
+                .bind(user_id)
+                .bind(data.email)
+                .bind(data.lang)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+
+                return Ok(());
+            }
+            PreferencesEvent::EmailVerified => {
+                sqlx::query(
+                    "UPDATE feed_user_emails SET verified = true, updated_at = $2 WHERE user_id = $1",
+                )
+                .bind(user_id)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+
+                return Ok(());
+            }
+        };
+
+        let mut tx = db.begin().await?;
+
+        sqlx::query("DELETE FROM feed_muted_notifications WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for muted in data.muted {
+            sqlx::query(
+                r#"
+                INSERT INTO feed_muted_notifications (user_id, kind, channel)
+                VALUES ( $1, $2, $3 )
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(user_id)
+            .bind(muted.kind)
+            .bind(muted.channel)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Notifications muted by a user.
+#[derive(Deserialize)]
+pub struct GetNotificationPreferencesInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetNotificationPreferencesInput {
+    type Output = Vec<MutedNotification>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        let muted = sqlx::query_as::<_, (String, String)>(
+            "SELECT kind, channel FROM feed_muted_notifications WHERE user_id = $1::uuid",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(muted
+            .into_iter()
+            .map(|(kind, channel)| MutedNotification { kind, channel })
+            .collect())
+    }
+}
+
+/// Users out of `user_ids` not muting the notifications of `kind` on
+/// `channel`.
+#[derive(Deserialize)]
+pub struct ListNotifiedUsersInput {
+    pub user_ids: Vec<Uuid>,
+    pub kind: String,
+    pub channel: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListNotifiedUsersInput {
+    type Output = Vec<Uuid>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        let muted = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT user_id FROM feed_muted_notifications
+            WHERE user_id = ANY($1) AND kind = $2 AND channel = $3
+            "#,
+        )
+        .bind(&self.user_ids)
+        .bind(&self.kind)
+        .bind(&self.channel)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(self
+            .user_ids
+            .iter()
+            .filter(|user_id| !muted.contains(user_id))
+            .copied()
+            .collect())
+    }
+}
+
+/// Address notifications are emailed to.
+#[derive(Clone, Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct UserEmail {
+    pub email: String,
+    pub lang: String,
+    pub verified: bool,
+}
+
+/// Address of a user, if set.
+#[derive(Deserialize)]
+pub struct GetUserEmailInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetUserEmailInput {
+    type Output = Option<UserEmail>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserEmail>(
+            "SELECT email, lang, verified FROM feed_user_emails WHERE user_id = $1::uuid",
+        )
+        .bind(&self.user_id)
+        .fetch_optional(&db)
+        .await?)
+    }
+}
+
+/// Verified address of a user to email a notification to.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct EmailRecipient {
+    pub user_id: Uuid,
+    pub email: String,
+    pub lang: String,
+}
+
+/// Users out of `user_ids` with a verified address, not muting the
+/// notifications of `kind` by email.
+#[derive(Deserialize)]
+pub struct ListEmailRecipientsInput {
+    pub user_ids: Vec<Uuid>,
+    pub kind: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListEmailRecipientsInput {
+    type Output = Vec<EmailRecipient>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, EmailRecipient>(
+            r#"
+            SELECT user_id, email, lang FROM feed_user_emails
+            WHERE user_id = ANY($1) AND verified = true
+            AND user_id NOT IN (
+                SELECT user_id FROM feed_muted_notifications
+                WHERE user_id = ANY($1) AND kind = $2 AND channel = $3
+            )
+            "#,
+        )
+        .bind(&self.user_ids)
+        .bind(&self.kind)
+        .bind(CHANNEL_EMAIL)
+        .fetch_all(&db)
+        .await?)
+    }
+}
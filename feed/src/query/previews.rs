@@ -0,0 +1,164 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use evento::{store::Event, Aggregate, ConsumerContext, RuleHandler};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool};
+use tracing::warn;
+
+use crate::{
+    unfurl::{find_link, unfurl},
+    Created, Edited, Feed, FeedEvent, JobHandler, Jobs, LinkPreview, VISIBILITY_PRIVATE,
+};
+
+/// Kind of the jobs unfurling the link of a feed.
+pub const JOB_UNFURL: &str = "unfurl";
+
+/// Hours a link stays unfurled before its page is fetched again.
+const PREVIEW_TTL_HOURS: i64 = 24;
+
+/// Preview of `link` from the cache, unfurling it when missing or expired.
+/// Links failing to unfurl are cached too, so a broken page is not fetched
+/// by every feed sharing it.
+async fn preview(db: &PgPool, link: &str) -> Result<Option<LinkPreview>> {
+    let cached = sqlx::query_scalar::<_, Option<Json<LinkPreview>>>(
+        "SELECT preview FROM feed_link_previews WHERE url = $1 AND fetched_at > $2",
+    )
+    .bind(link)
+    .bind(Utc::now() - Duration::hours(PREVIEW_TTL_HOURS))
+    .fetch_optional(db)
+    .await?;
+
+    if let Some(preview) = cached {
+        return Ok(preview.map(|preview| preview.0));
+    }
+
+    let preview = match unfurl(link).await {
+        Ok(preview) => preview,
+        Err(e) => {
+            warn!("{e}");
+
+            None
+        }
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO feed_link_previews (url, preview, fetched_at) VALUES ( $1, $2, $3 )
+        ON CONFLICT (url) DO UPDATE SET preview = EXCLUDED.preview, fetched_at = EXCLUDED.fetched_at
+        "#,
+    )
+    .bind(link)
+    .bind(preview.clone().map(Json))
+    .bind(Utc::now())
+    .execute(db)
+    .await?;
+
+    Ok(preview)
+}
+
+/// Drops the expired previews, which `preview` would fetch again anyway,
+/// returning how many were dropped.
+pub(crate) async fn purge_link_previews(db: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM feed_link_previews WHERE fetched_at <= $1")
+        .bind(Utc::now() - Duration::hours(PREVIEW_TTL_HOURS))
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Unfurls the first link of feeds into a preview card, queueing an
+/// unfurl job as fetching the page may be slow. The feed details rule reads
+/// the cache too, whichever of both projects last on a feed setting its
+/// preview.
+#[derive(Clone)]
+pub struct LinkPreviewsHandler;
+
+#[async_trait]
+impl RuleHandler for LinkPreviewsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+
+        let content = match event_name {
+            FeedEvent::Created => {
+                let data: Created = event.to_data()?;
+
+                // Fetching the links of private feeds would tell the pages
+                // they point to about them.
+                if data.visibility == VISIBILITY_PRIVATE {
+                    return Ok(());
+                }
+
+                data.content
+            }
+            FeedEvent::Edited => {
+                let visibility = sqlx::query_scalar::<_, String>(
+                    "SELECT visibility FROM feed_feeds WHERE id = $1",
+                )
+                .bind(Feed::from_aggregate_id(&event.aggregate_id))
+                .fetch_optional(&db)
+                .await?;
+
+                if visibility.is_none() || visibility.as_deref() == Some(VISIBILITY_PRIVATE) {
+                    return Ok(());
+                }
+
+                event.to_data::<Edited>()?.content
+            }
+            _ => return Ok(()),
+        };
+
+        if find_link(&content).is_none() {
+            return Ok(());
+        }
+
+        Jobs::new(db)
+            .enqueue(
+                JOB_UNFURL,
+                &UnfurlJobPayload {
+                    feed_id: Feed::from_aggregate_id(&event.aggregate_id),
+                    content,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnfurlJobPayload {
+    pub feed_id: String,
+    /// Content the link was found in, the preview being left out once the
+    /// feed is edited again.
+    pub content: String,
+}
+
+/// Sets the preview of the link of a feed, cached or fetched.
+pub struct UnfurlJob {
+    pub db: PgPool,
+}
+
+#[async_trait]
+impl JobHandler for UnfurlJob {
+    async fn run(&self, payload: serde_json::Value) -> Result<()> {
+        let payload: UnfurlJobPayload = serde_json::from_value(payload)?;
+
+        let Some(link) = find_link(&payload.content) else {
+            return Ok(());
+        };
+
+        let preview = preview(&self.db, link).await?;
+
+        sqlx::query("UPDATE feed_feeds SET preview = $2 WHERE id = $1 AND content = $3")
+            .bind(&payload.feed_id)
+            .bind(preview.map(Json))
+            .bind(&payload.content)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{Feed, FeedEvent, FeedMetadata, Reacted, Unreacted, REACTIONS};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct ReactionCount {
+    pub reaction: String,
+    pub total_count: i64,
+    pub reacted: bool,
+}
+
+#[derive(Clone)]
+pub struct ReactionsHandler;
+
+#[async_trait]
+impl RuleHandler for ReactionsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            FeedEvent::Reacted => {
+                let data: Reacted = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO feed_reactions (feed_id, user_id, reaction, created_at)
+                    VALUES ( $1, $2, $3, $4 )
+                    ON CONFLICT DO NOTHING
+                    "#,
+                )
+                .bind(&feed_id)
+                .bind(metadata.req_user)
+                .bind(&data.reaction)
+                .bind(event.created_at)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+                // Only count reactions that were not already projected so
+                // that replaying an event never counts a user twice.
+                if inserted > 0 {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO feed_reaction_counts (feed_id, reaction, total_count)
+                        VALUES ( $1, $2, 1 )
+                        ON CONFLICT (feed_id, reaction)
+                        DO UPDATE SET total_count = feed_reaction_counts.total_count + 1
+                        "#,
+                    )
+                    .bind(&feed_id)
+                    .bind(&data.reaction)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+
+                tx.commit().await?;
+            }
+            FeedEvent::Unreacted => {
+                let data: Unreacted = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                let deleted = sqlx::query(
+                    "DELETE FROM feed_reactions WHERE feed_id = $1 AND user_id = $2 AND reaction = $3",
+                )
+                .bind(&feed_id)
+                .bind(metadata.req_user)
+                .bind(&data.reaction)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+                if deleted > 0 {
+                    sqlx::query(
+                        r#"
+                        UPDATE feed_reaction_counts SET total_count = total_count - 1
+                        WHERE feed_id = $1 AND reaction = $2
+                        "#,
+                    )
+                    .bind(&feed_id)
+                    .bind(&data.reaction)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+
+                tx.commit().await?;
+            }
+            FeedEvent::Created
+            | FeedEvent::Tagged
+            | FeedEvent::Untagged
+            | FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Reported
+            | FeedEvent::Hidden
+            | FeedEvent::Restored
+            | FeedEvent::Published
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed
+            | FeedEvent::Deleted => {}
+        };
+
+        Ok(())
+    }
+}
+
+/// Count of every reaction of a feed, in `REACTIONS` order, `reacted` being
+/// set for the ones of `user_id`.
+#[derive(Deserialize)]
+pub struct ListFeedReactionsInput {
+    pub feed_id: String,
+    pub user_id: Option<Uuid>,
+}
+
+#[async_trait]
+impl QueryHandler for ListFeedReactionsInput {
+    type Output = Vec<ReactionCount>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let counts = sqlx::query_as::<_, ReactionCount>(
+            r#"
+            SELECT c.reaction, c.total_count, (r.user_id IS NOT NULL) AS reacted
+            FROM feed_reaction_counts c
+            LEFT JOIN feed_reactions r
+                ON r.feed_id = c.feed_id AND r.reaction = c.reaction AND r.user_id = $2
+            WHERE c.feed_id = $1 AND c.total_count > 0
+            "#,
+        )
+        .bind(&self.feed_id)
+        .bind(self.user_id)
+        .fetch_all(&db)
+        .await?;
+
+        Ok(REACTIONS
+            .iter()
+            .map(|reaction| {
+                counts
+                    .iter()
+                    .find(|count| count.reaction == *reaction)
+                    .cloned()
+                    .unwrap_or_else(|| ReactionCount {
+                        reaction: reaction.to_string(),
+                        ..Default::default()
+                    })
+            })
+            .collect())
+    }
+}
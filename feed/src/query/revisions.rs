@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{Aggregate, Query, QueryError, QueryHandler, QueryOutput};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+
+use crate::Feed;
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct FeedRevision {
+    pub version: i32,
+    pub title: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Title and content of a feed after its creation and each of its edits,
+/// read from the events of the feed, oldest first.
+#[derive(Deserialize)]
+pub struct ListFeedRevisionsInput {
+    pub feed_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListFeedRevisionsInput {
+    type Output = Vec<FeedRevision>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let revisions = sqlx::query_as::<_, FeedRevision>(
+            r#"
+            SELECT version, data->>'title' AS title, data->>'content' AS content, created_at
+            FROM ev_event
+            WHERE aggregate_id = $1 AND name IN ('created', 'edited')
+            ORDER BY version
+            "#,
+        )
+        .bind(Feed::to_aggregate_id(&self.feed_id))
+        .fetch_all(&db)
+        .await?;
+
+        if revisions.is_empty() {
+            return Err(QueryError::NotFound(format!(
+                "feed {} not found",
+                self.feed_id
+            )));
+        }
+
+        Ok(revisions)
+    }
+}
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{Query, QueryHandler, QueryOutput};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+/// Marks around the matched words of `FeedSearchResult::snippet`, they are
+/// control characters so that the snippet can be escaped before replacing
+/// them with html.
+pub const SNIPPET_START: char = '\u{2}';
+pub const SNIPPET_STOP: char = '\u{3}';
+
+const PAGE_SIZE: i64 = 20;
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct FeedSearchResult {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    pub snippet: String,
+    pub tags: Vec<String>,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub rank: f32,
+}
+
+/// Feeds matching `q` ordered by rank, title matches weighting more than
+/// content ones.
+#[derive(Deserialize, Clone)]
+pub struct SearchFeedsInput {
+    #[serde(default)]
+    pub q: String,
+    pub tag: Option<String>,
+    pub author: Option<String>,
+    pub page: Option<u16>,
+}
+
+impl SearchFeedsInput {
+    pub fn page(&self) -> u16 {
+        self.page.unwrap_or(1).max(1)
+    }
+}
+
+#[async_trait]
+impl QueryHandler for SearchFeedsInput {
+    /// Results of the page and whether there is a next one.
+    type Output = (Vec<FeedSearchResult>, bool);
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let q = self.q.trim();
+
+        if q.is_empty() {
+            return Ok((vec![], false));
+        }
+
+        let mut select: QueryBuilder<Postgres> =
+            QueryBuilder::new("WITH search AS (SELECT websearch_to_tsquery('simple', ");
+        select
+            .push_bind(q.to_owned())
+            .push(
+                r#") AS query)
+                SELECT id, title, author, tags, user_id, created_at,
+                    ts_rank(feed_search_document(title, content), search.query) AS rank,
+                    ts_headline('simple', content, search.query, "#,
+            )
+            .push_bind(format!(
+                "StartSel={SNIPPET_START}, StopSel={SNIPPET_STOP}, MaxWords=35, MinWords=15"
+            ))
+            .push(
+                r#") AS snippet
+                FROM feed_feeds, search
+                WHERE NOT hidden AND NOT archived AND delete_at IS NULL AND visibility = 'public' AND publish_at IS NULL AND feed_search_document(title, content) @@ search.query"#,
+            );
+
+        if let Some(tag) = self.tag.as_ref().filter(|tag| !tag.is_empty()) {
+            select
+                .push(" AND tags @> ARRAY[")
+                .push_bind(tag.to_owned())
+                .push("]::VARCHAR[]");
+        }
+
+        if let Some(author) = self.author.as_ref().filter(|author| !author.is_empty()) {
+            select
+                .push(" AND author ILIKE ")
+                .push_bind(format!("%{author}%"));
+        }
+
+        select
+            .push(" ORDER BY rank DESC, created_at DESC, id LIMIT ")
+            .push_bind(PAGE_SIZE + 1)
+            .push(" OFFSET ")
+            .push_bind(i64::from(self.page() - 1) * PAGE_SIZE);
+
+        let mut results = select
+            .build_query_as::<FeedSearchResult>()
+            .fetch_all(&db)
+            .await?;
+
+        let has_next_page = results.len() > PAGE_SIZE as usize;
+        results.truncate(PAGE_SIZE as usize);
+
+        Ok((results, has_next_page))
+    }
+}
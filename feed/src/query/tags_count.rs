@@ -2,11 +2,11 @@ use anyhow::Result;
 use async_trait::async_trait;
 use evento::{store::Event, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
 
-use crate::{Created, FeedEvent};
+use crate::{Created, FeedEvent, Tagged, Untagged};
 
-#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
 pub struct TagCount {
     pub tag: String,
     pub total_count: i32,
@@ -34,6 +34,43 @@ impl RuleHandler for TagsCountHandler {
                 query_builder.push(" ON CONFLICT (tag) DO UPDATE SET total_count = feed_tags_count.total_count + 1");
                 query_builder.build().execute(&db).await?;
             }
+            FeedEvent::Tagged => {
+                let data: Tagged = event.to_data()?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_tags_count (tag, total_count) VALUES ( $1, 1 )
+                    ON CONFLICT (tag) DO UPDATE SET total_count = feed_tags_count.total_count + 1
+                    "#,
+                )
+                .bind(&data.tag)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Untagged => {
+                let data: Untagged = event.to_data()?;
+
+                sqlx::query(
+                    "UPDATE feed_tags_count SET total_count = GREATEST(total_count - 1, 0) WHERE tag = $1",
+                )
+                .bind(&data.tag)
+                .execute(&db)
+                .await?;
+            }
+            FeedEvent::Reacted
+            | FeedEvent::Unreacted
+            | FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Reported
+            | FeedEvent::Hidden
+            | FeedEvent::Restored
+            | FeedEvent::Published
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed
+            | FeedEvent::Deleted => {}
         };
 
         Ok(())
@@ -55,3 +92,30 @@ impl QueryHandler for ListPopularTagsInput {
         .await?)
     }
 }
+
+/// Most used tags, alphabetically ordered to be rendered as a cloud.
+#[derive(Deserialize)]
+pub struct ListTagsInput {
+    pub limit: Option<u16>,
+}
+
+#[async_trait]
+impl QueryHandler for ListTagsInput {
+    type Output = Vec<TagCount>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+        let tags = sqlx::query_as::<_, TagCount>(
+            r#"
+            SELECT * FROM (
+                SELECT * FROM feed_tags_count WHERE total_count > 0
+                ORDER BY total_count DESC LIMIT $1
+            ) AS tags ORDER BY tag
+            "#,
+        )
+        .bind(i64::from(self.limit.unwrap_or(50)))
+        .fetch_all(&db)
+        .await?;
+
+        Ok(tags)
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::Deserialize;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    CommentCreated, CommentDeleted, CommentEvent, Feed, FeedEvent, FeedMetadata, Reacted,
+    Unreacted, UserFeed,
+};
+
+/// Time after which an interaction counts half as much in the ranking.
+const HALF_LIFE_SECS: f64 = 86400.0;
+const REACTION_WEIGHT: f64 = 1.0;
+/// Comments take more effort than reactions, they weigh more.
+const COMMENT_WEIGHT: f64 = 2.0;
+/// Half-lives after which an interaction is forgotten by `prune_trending`,
+/// weighing less than a billionth of what it did.
+const FORGOTTEN_HALF_LIVES: f64 = 30.0;
+
+/// Records or forgets an interaction on a feed then ranks it again, `weight`
+/// being `None` to forget it.
+///
+/// The score is the `log2` of the interactions weights, each halved every
+/// `HALF_LIFE_SECS` since the unix epoch, so that scores computed at
+/// different times still compare without being refreshed as time goes by.
+async fn refresh(
+    tx: &mut Transaction<'_, Postgres>,
+    feed_id: &str,
+    source: &str,
+    weight: Option<f64>,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    // Locks the score of the feed, reactions and comments being ranked by
+    // separate rules.
+    sqlx::query(
+        r#"
+        INSERT INTO feed_trending (feed_id, score) VALUES ( $1, 0 )
+        ON CONFLICT (feed_id) DO UPDATE SET score = feed_trending.score
+        "#,
+    )
+    .bind(feed_id)
+    .execute(&mut **tx)
+    .await?;
+
+    match weight {
+        Some(weight) => {
+            sqlx::query(
+                r#"
+                INSERT INTO feed_trending_interactions (feed_id, source, weight, created_at)
+                VALUES ( $1, $2, $3, $4 )
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(feed_id)
+            .bind(source)
+            .bind(weight)
+            .bind(created_at)
+            .execute(&mut **tx)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                "DELETE FROM feed_trending_interactions WHERE feed_id = $1 AND source = $2",
+            )
+            .bind(feed_id)
+            .bind(source)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    rank(tx, feed_id).await
+}
+
+/// Scores a feed from its interactions, forgetting it once it has none.
+async fn rank(tx: &mut Transaction<'_, Postgres>, feed_id: &str) -> Result<()> {
+    // Relative to the latest interaction so that powers of two never
+    // overflow.
+    sqlx::query(
+        r#"
+        WITH decayed AS (
+            SELECT weight, EXTRACT(EPOCH FROM created_at)::float8 / $2 AS age
+            FROM feed_trending_interactions WHERE feed_id = $1
+        ), latest AS (
+            SELECT MAX(age) AS age FROM decayed
+        )
+        UPDATE feed_trending SET score = COALESCE((
+            SELECT latest.age + LN(SUM(decayed.weight * POWER(2, decayed.age - latest.age))) / LN(2)
+            FROM decayed, latest GROUP BY latest.age
+        ), 0)
+        WHERE feed_id = $1
+        "#,
+    )
+    .bind(feed_id)
+    .bind(HALF_LIFE_SECS)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM feed_trending WHERE feed_id = $1
+        AND NOT EXISTS (SELECT 1 FROM feed_trending_interactions WHERE feed_id = $1)
+        "#,
+    )
+    .bind(feed_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets the interactions past `FORGOTTEN_HALF_LIVES` and ranks their
+/// feeds again, returning how many were forgotten.
+pub(crate) async fn prune_trending(db: &PgPool) -> Result<u64> {
+    let mut tx = db.begin().await?;
+    let before = Utc::now() - Duration::seconds((HALF_LIFE_SECS * FORGOTTEN_HALF_LIVES) as i64);
+
+    let mut feed_ids = sqlx::query_scalar::<_, String>(
+        "DELETE FROM feed_trending_interactions WHERE created_at < $1 RETURNING feed_id",
+    )
+    .bind(before)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let pruned = feed_ids.len() as u64;
+    feed_ids.sort();
+    feed_ids.dedup();
+
+    for feed_id in feed_ids {
+        rank(&mut tx, &feed_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(pruned)
+}
+
+/// Ranks feeds by their reactions, keeping its own copy of them so that it
+/// never waits on the reactions projection.
+#[derive(Clone)]
+pub struct TrendingHandler;
+
+#[async_trait]
+impl RuleHandler for TrendingHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            FeedEvent::Reacted | FeedEvent::Unreacted => {
+                let (reaction, weight) = match event_name {
+                    FeedEvent::Reacted => {
+                        (event.to_data::<Reacted>()?.reaction, Some(REACTION_WEIGHT))
+                    }
+                    _ => (event.to_data::<Unreacted>()?.reaction, None),
+                };
+
+                let source = format!("reaction/{}/{reaction}", metadata.req_user);
+                let mut tx = db.begin().await?;
+
+                refresh(&mut tx, &feed_id, &source, weight, event.created_at).await?;
+
+                tx.commit().await?;
+            }
+            FeedEvent::Deleted => {
+                let mut tx = db.begin().await?;
+
+                sqlx::query("DELETE FROM feed_trending WHERE feed_id = $1")
+                    .bind(&feed_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("DELETE FROM feed_trending_interactions WHERE feed_id = $1")
+                    .bind(&feed_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                tx.commit().await?;
+            }
+            FeedEvent::Created
+            | FeedEvent::Tagged
+            | FeedEvent::Untagged
+            | FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Reported
+            | FeedEvent::Hidden
+            | FeedEvent::Restored
+            | FeedEvent::Published
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed => {}
+        };
+
+        Ok(())
+    }
+}
+
+/// Ranks feeds by their comments.
+#[derive(Clone)]
+pub struct CommentTrendingHandler;
+
+#[async_trait]
+impl RuleHandler for CommentTrendingHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: CommentEvent = event.name.parse()?;
+        let source = event.aggregate_id.to_owned();
+
+        let (feed_id, weight) = match event_name {
+            CommentEvent::Created => (
+                event.to_data::<CommentCreated>()?.feed_id,
+                Some(COMMENT_WEIGHT),
+            ),
+            CommentEvent::Deleted => (event.to_data::<CommentDeleted>()?.feed_id, None),
+            CommentEvent::Edited | CommentEvent::Mentioned => return Ok(()),
+        };
+
+        let mut tx = db.begin().await?;
+
+        refresh(&mut tx, &feed_id, &source, weight, event.created_at).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Public feeds with the most recent interactions, best ranked first.
+#[derive(Deserialize)]
+pub struct ListTrendingFeedsInput {
+    pub limit: u16,
+}
+
+#[async_trait]
+impl QueryHandler for ListTrendingFeedsInput {
+    type Output = Vec<UserFeed>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserFeed>(
+            r#"
+            SELECT f.* FROM feed_trending t
+            JOIN feed_feeds f ON f.id = t.feed_id
+            WHERE NOT f.hidden AND NOT f.archived AND f.delete_at IS NULL AND f.visibility = 'public' AND f.publish_at IS NULL
+            ORDER BY t.score DESC, f.created_at DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(i64::from(self.limit.min(50)))
+        .fetch_all(&db)
+        .await?)
+    }
+}
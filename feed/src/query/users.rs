@@ -0,0 +1,112 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{User, UserCreated, UserEvent, UserRoleSet};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserDetails {
+    pub id: Uuid,
+    pub name: String,
+    pub role: String,
+    pub disabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct UsersHandler;
+
+#[async_trait]
+impl RuleHandler for UsersHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: UserEvent = event.name.parse()?;
+        let id = Uuid::parse_str(&User::from_aggregate_id(&event.aggregate_id))?;
+
+        match event_name {
+            UserEvent::Created => {
+                let data: UserCreated = event.to_data()?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_users (id, name, role, created_at)
+                    VALUES ( $1, $2, $3, $4 )
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(id)
+                .bind(&data.name)
+                .bind(&data.role)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            UserEvent::Disabled => {
+                sqlx::query("UPDATE feed_users SET disabled = true WHERE id = $1")
+                    .bind(id)
+                    .execute(&db)
+                    .await?;
+            }
+            UserEvent::RoleSet => {
+                let data: UserRoleSet = event.to_data()?;
+
+                sqlx::query("UPDATE feed_users SET role = $2 WHERE id = $1")
+                    .bind(id)
+                    .bind(&data.role)
+                    .execute(&db)
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Users created ahead of signing in, first created first.
+pub struct ListUsersInput;
+
+#[async_trait]
+impl QueryHandler for ListUsersInput {
+    type Output = Vec<UserDetails>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(
+            sqlx::query_as::<_, UserDetails>("SELECT * FROM feed_users ORDER BY created_at, id")
+                .fetch_all(&db)
+                .await?,
+        )
+    }
+}
+
+/// The user signed in with `id`, unknown to most of them as they needn't be
+/// created.
+#[derive(Deserialize)]
+pub struct GetUserInput {
+    pub id: String,
+}
+
+#[async_trait]
+impl QueryHandler for GetUserInput {
+    type Output = Option<UserDetails>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        let Ok(id) = Uuid::parse_str(&self.id) else {
+            return Ok(None);
+        };
+
+        Ok(
+            sqlx::query_as::<_, UserDetails>("SELECT * FROM feed_users WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&db)
+                .await?,
+        )
+    }
+}
@@ -0,0 +1,497 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{
+    store::Event, Aggregate, ConsumerContext, Query, QueryHandler, QueryOutput, RuleHandler,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    Created, Delivery, DeliveryEvent, DeliveryFailed, DeliverySucceeded, Feed, FeedEvent,
+    FeedMetadata, Webhook, WebhookEvent, WebhookRegistered, VISIBILITY_PUBLIC,
+};
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserWebhook {
+    pub id: String,
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attempts at sending an event to a webhook, the url being kept once the
+/// webhook is removed.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct UserWebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub user_id: Uuid,
+    pub event: String,
+    /// Id of what the event is about, like the feed of `feed.created`.
+    pub subject_id: String,
+    pub url: String,
+    pub attempts: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Event sent to the webhooks of a user once one of their feeds is public,
+/// as the `event` of the body.
+pub const WEBHOOK_FEED_CREATED: &str = "feed.created";
+
+/// One of the attempts at sending a delivery.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct WebhookAttempt {
+    pub delivery_id: String,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Queues `body` as `event` to the webhooks `user_id` had registered by
+/// `created_at`, once per webhook and `subject_id` so that replaying what
+/// triggered it never sends it twice. Integrations send their events
+/// through it, the scheduler signing and delivering them.
+pub(crate) async fn queue_webhook_deliveries(
+    db: &PgPool,
+    user_id: Uuid,
+    event: &str,
+    subject_id: &str,
+    body: &serde_json::Value,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO feed_webhook_deliveries (id, webhook_id, user_id, event, subject_id, url, body, next_attempt_at, created_at)
+        SELECT w.id || '-' || $3, w.id, w.user_id, $2, $3, w.url, $4, $5, $5
+        FROM feed_webhooks w
+        WHERE w.user_id = $1 AND w.created_at <= $5
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(event)
+    .bind(subject_id)
+    .bind(serde_json::to_string(body)?)
+    .bind(created_at)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct WebhooksHandler;
+
+#[async_trait]
+impl RuleHandler for WebhooksHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: WebhookEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let id = Webhook::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            WebhookEvent::Registered => {
+                let data: WebhookRegistered = event.to_data()?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO feed_webhooks (id, user_id, url, secret, created_at)
+                    VALUES ( $1, $2, $3, $4, $5 )
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(&id)
+                .bind(metadata.req_user)
+                .bind(&data.url)
+                .bind(&data.secret)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+            WebhookEvent::Removed => {
+                let mut tx = db.begin().await?;
+
+                sqlx::query("DELETE FROM feed_webhooks WHERE id = $1")
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query(
+                    "UPDATE feed_webhook_deliveries SET next_attempt_at = NULL WHERE webhook_id = $1",
+                )
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Queues a delivery of every new public feed to the webhooks its author
+/// had registered by then, so that replaying feeds never cross-posts them
+/// to webhooks registered afterwards.
+#[derive(Clone)]
+pub struct CrossPostsHandler;
+
+#[async_trait]
+impl RuleHandler for CrossPostsHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: FeedEvent = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        let (user_id, title, content, tags) = match event_name {
+            FeedEvent::Created => {
+                let data: Created = event.to_data()?;
+
+                // Scheduled feeds are cross-posted once published.
+                if data.visibility != VISIBILITY_PUBLIC || data.publish_at.is_some() {
+                    return Ok(());
+                }
+
+                (metadata.req_user, data.title, data.content, data.tags)
+            }
+            FeedEvent::Published => {
+                let feed = sqlx::query_as::<_, (Uuid, String, String, Vec<String>)>(
+                    "SELECT user_id, title, content, tags FROM feed_feeds WHERE id = $1 AND visibility = 'public'",
+                )
+                .bind(&feed_id)
+                .fetch_optional(&db)
+                .await?;
+
+                let Some(feed) = feed else {
+                    return Ok(());
+                };
+
+                feed
+            }
+            _ => return Ok(()),
+        };
+
+        let body = serde_json::json!({
+            "event": WEBHOOK_FEED_CREATED,
+            "feed": {
+                "id": feed_id,
+                "title": title,
+                "content": content,
+                "tags": tags,
+                "created_at": event.created_at,
+            },
+        });
+
+        queue_webhook_deliveries(
+            &db,
+            user_id,
+            WEBHOOK_FEED_CREATED,
+            &feed_id,
+            &body,
+            event.created_at,
+        )
+        .await
+    }
+}
+
+/// Logs the attempts at delivering feeds to webhooks.
+#[derive(Clone)]
+pub struct WebhookDeliveriesHandler;
+
+#[async_trait]
+impl RuleHandler for WebhookDeliveriesHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let db = ctx.extract::<PgPool>();
+        let event_name: DeliveryEvent = event.name.parse()?;
+        let id = Delivery::from_aggregate_id(&event.aggregate_id);
+
+        match event_name {
+            DeliveryEvent::Failed => {
+                let data: DeliveryFailed = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                insert_attempt(
+                    &mut tx,
+                    &id,
+                    data.attempt,
+                    data.status_code,
+                    data.error.as_deref(),
+                    event.created_at,
+                )
+                .await?;
+
+                // Deliveries of a removed webhook are not retried.
+                sqlx::query(
+                    r#"
+                    UPDATE feed_webhook_deliveries SET attempts = $2, status_code = $3, error = $4,
+                    next_attempt_at = CASE WHEN webhook_id IN (SELECT id FROM feed_webhooks) THEN $5 END
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(&id)
+                .bind(i32::from(data.attempt))
+                .bind(data.status_code.map(i32::from))
+                .bind(&data.error)
+                .bind(data.next_attempt_at)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+            }
+            DeliveryEvent::Succeeded => {
+                let data: DeliverySucceeded = event.to_data()?;
+                let mut tx = db.begin().await?;
+
+                insert_attempt(
+                    &mut tx,
+                    &id,
+                    data.attempt,
+                    Some(data.status_code),
+                    None,
+                    event.created_at,
+                )
+                .await?;
+
+                sqlx::query(
+                    r#"
+                    UPDATE feed_webhook_deliveries SET attempts = $2, status_code = $3, error = NULL,
+                    next_attempt_at = NULL, delivered_at = $4
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(&id)
+                .bind(i32::from(data.attempt))
+                .bind(i32::from(data.status_code))
+                .bind(event.created_at)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+            }
+            DeliveryEvent::Requeued => {
+                sqlx::query(
+                    r#"
+                    UPDATE feed_webhook_deliveries SET
+                    next_attempt_at = CASE WHEN webhook_id IN (SELECT id FROM feed_webhooks) THEN $2 END
+                    WHERE id = $1 AND delivered_at IS NULL
+                    "#,
+                )
+                .bind(&id)
+                .bind(event.created_at)
+                .execute(&db)
+                .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+async fn insert_attempt(
+    tx: &mut Transaction<'_, Postgres>,
+    delivery_id: &str,
+    attempt: u16,
+    status_code: Option<u16>,
+    error: Option<&str>,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO feed_webhook_attempts (delivery_id, attempt, status_code, error, created_at)
+        SELECT $1, $2, $3, $4, $5 WHERE EXISTS (SELECT 1 FROM feed_webhook_deliveries WHERE id = $1)
+        ON CONFLICT (delivery_id, attempt) DO NOTHING
+        "#,
+    )
+    .bind(delivery_id)
+    .bind(i32::from(attempt))
+    .bind(status_code.map(i32::from))
+    .bind(error)
+    .bind(created_at)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops the deliveries created before `before` that are no longer retried,
+/// returning how many were dropped.
+pub(crate) async fn purge_webhook_deliveries(db: &PgPool, before: DateTime<Utc>) -> Result<u64> {
+    let mut tx = db.begin().await?;
+
+    let ids = sqlx::query_scalar::<_, String>(
+        "DELETE FROM feed_webhook_deliveries WHERE next_attempt_at IS NULL AND created_at < $1 RETURNING id",
+    )
+    .bind(before)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM feed_webhook_attempts WHERE delivery_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(ids.len() as u64)
+}
+
+/// Webhooks registered by a user, first registered first.
+#[derive(Deserialize)]
+pub struct ListWebhooksInput {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl QueryHandler for ListWebhooksInput {
+    type Output = Vec<UserWebhook>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserWebhook>(
+            "SELECT * FROM feed_webhooks WHERE user_id = $1::uuid ORDER BY created_at, id",
+        )
+        .bind(&self.user_id)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Last deliveries to the webhooks of a user, last first.
+#[derive(Deserialize)]
+pub struct ListWebhookDeliveriesInput {
+    pub user_id: String,
+    pub limit: i64,
+}
+
+#[async_trait]
+impl QueryHandler for ListWebhookDeliveriesInput {
+    type Output = Vec<UserWebhookDelivery>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserWebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, user_id, event, subject_id, url, attempts, status_code, error, next_attempt_at, delivered_at, created_at
+            FROM feed_webhook_deliveries
+            WHERE user_id = $1::uuid
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(&self.user_id)
+        .bind(self.limit)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Delivery to attempt, with what `DeliverWebhookInput` needs to send it.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, FromRow)]
+pub struct DueWebhookDelivery {
+    pub id: String,
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub body: String,
+}
+
+/// Deliveries whose next attempt is due, oldest first, for the scheduler to
+/// send.
+#[derive(Deserialize)]
+pub struct ListDueWebhookDeliveriesInput {
+    pub limit: i64,
+}
+
+#[async_trait]
+impl QueryHandler for ListDueWebhookDeliveriesInput {
+    type Output = Vec<DueWebhookDelivery>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, DueWebhookDelivery>(
+            r#"
+            SELECT d.id, d.user_id, d.url, w.secret, d.body
+            FROM feed_webhook_deliveries d
+            JOIN feed_webhooks w ON w.id = d.webhook_id
+            WHERE d.next_attempt_at <= NOW()
+            ORDER BY d.next_attempt_at
+            LIMIT $1
+            "#,
+        )
+        .bind(self.limit)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Last deliveries of every user, last first, only the dead ones given
+/// `dead`: out of attempts or to a removed webhook.
+#[derive(Deserialize)]
+pub struct ListAllWebhookDeliveriesInput {
+    pub dead: bool,
+    pub limit: i64,
+}
+
+#[async_trait]
+impl QueryHandler for ListAllWebhookDeliveriesInput {
+    type Output = Vec<UserWebhookDelivery>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, UserWebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, user_id, event, subject_id, url, attempts, status_code, error, next_attempt_at, delivered_at, created_at
+            FROM feed_webhook_deliveries
+            WHERE NOT $1 OR (delivered_at IS NULL AND next_attempt_at IS NULL)
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(self.dead)
+        .bind(self.limit)
+        .fetch_all(&db)
+        .await?)
+    }
+}
+
+/// Attempts at sending the deliveries `ids`, first attempted first.
+#[derive(Deserialize)]
+pub struct ListWebhookAttemptsInput {
+    pub ids: Vec<String>,
+}
+
+#[async_trait]
+impl QueryHandler for ListWebhookAttemptsInput {
+    type Output = Vec<WebhookAttempt>;
+    async fn handle(&self, query: &Query) -> QueryOutput<Self::Output> {
+        let db: sqlx::Pool<sqlx::Postgres> = query.extract::<PgPool>();
+
+        Ok(sqlx::query_as::<_, WebhookAttempt>(
+            r#"
+            SELECT * FROM feed_webhook_attempts
+            WHERE delivery_id = ANY($1)
+            ORDER BY delivery_id, attempt
+            "#,
+        )
+        .bind(&self.ids)
+        .fetch_all(&db)
+        .await?)
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt::Write;
+
+const MAX_SLUG_LEN: usize = 80;
+
+/// Latin letters written without their diacritics, or spelled out for the
+/// ones having no ASCII equivalent.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Lowercase words of `title` joined by dashes. Latin letters lose their
+/// diacritics while letters of other scripts are kept, so that titles
+/// written in any language get a readable slug.
+pub(crate) fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    let mut dash = false;
+
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if slug.chars().count() >= MAX_SLUG_LEN {
+            break;
+        }
+
+        let letters = match transliterate(c) {
+            Some(letters) => letters.to_owned(),
+            None if c.is_alphanumeric() => c.to_string(),
+            None => {
+                dash = !slug.is_empty();
+                continue;
+            }
+        };
+
+        if dash {
+            slug.push('-');
+            dash = false;
+        }
+
+        slug.push_str(&letters);
+    }
+
+    slug
+}
+
+/// `slug` percent-encoded to be used as a path segment, letters of other
+/// scripts than Latin not being valid in headers such as `Location`.
+pub(crate) fn encode(slug: &str) -> String {
+    slug.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+
+        encoded
+    })
+}
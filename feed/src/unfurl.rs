@@ -0,0 +1,293 @@
+use anyhow::{bail, Result};
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION},
+    redirect::Policy,
+    Url,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+pub(crate) const USER_AGENT: &str = "TimadaStarterBot/1.0 (+https://timada.co)";
+const TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REDIRECTS: usize = 3;
+const MAX_BODY_SIZE: usize = 512 * 1024;
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 300;
+
+/// Card of the first link of a feed, from the open graph metadata of the
+/// page.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LinkPreview {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub site_name: String,
+}
+
+/// First http(s) link of `content`, trailing punctuation left out.
+pub fn find_link(content: &str) -> Option<&str> {
+    content
+        .split(|c: char| {
+            c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '<' | '>' | '"' | '\'')
+        })
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|link| link.trim_end_matches(['.', ',', ';', ':', '!', '?']))
+}
+
+/// Loopback, private, link-local and other special purpose addresses can't
+/// be fetched, so a feed can't make the server reach its own network.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+                || (a == 198 && (b & 0xfe) == 18))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let segment = ip.segments()[0];
+
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (segment & 0xfe00) == 0xfc00
+                    || (segment & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Address `url` is fetched from, every address of its host having to be
+/// public for it to be resolved again by a rebinding dns.
+pub(crate) async fn public_addr(url: &Url) -> Result<SocketAddr> {
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("{url} scheme not allowed");
+    }
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        bail!("{url} has no host");
+    };
+
+    if port != 80 && port != 443 {
+        bail!("{url} port not allowed");
+    }
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs = tokio::net::lookup_host((host, port))
+        .await?
+        .collect::<Vec<_>>();
+
+    if addrs.iter().any(|addr| !is_public(addr.ip())) {
+        bail!("{url} resolves to a non public address");
+    }
+
+    match addrs.first() {
+        Some(addr) => Ok(*addr),
+        None => bail!("{url} does not resolve"),
+    }
+}
+
+/// Fetches the page of `link` to build its preview, following at most
+/// `MAX_REDIRECTS` redirects checked like the link itself. `None` when the
+/// page is not html or has no title.
+pub async fn unfurl(link: &str) -> Result<Option<LinkPreview>> {
+    let mut url = Url::parse(link)?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let addr = public_addr(&url).await?;
+        let mut client = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .timeout(TIMEOUT)
+            .user_agent(USER_AGENT);
+
+        if let Some(domain) = url.domain() {
+            client = client.resolve(domain, addr);
+        }
+
+        let mut res = client
+            .build()?
+            .get(url.clone())
+            .header(ACCEPT, "text/html")
+            .send()
+            .await?;
+
+        if res.status().is_redirection() {
+            let Some(location) = res
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+            else {
+                return Ok(None);
+            };
+
+            url = url.join(location)?;
+            continue;
+        }
+
+        let html = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/html"));
+
+        if !res.status().is_success() || !html {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = res.chunk().await? {
+            body.extend_from_slice(&chunk);
+
+            if body.len() >= MAX_BODY_SIZE {
+                break;
+            }
+        }
+
+        return Ok(parse_preview(&url, &String::from_utf8_lossy(&body)));
+    }
+
+    Ok(None)
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn truncate(value: &str, len: usize) -> String {
+    decode_entities(value.trim()).chars().take(len).collect()
+}
+
+/// Attributes of the tag starting `tag`, names lowercased.
+fn attributes(tag: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = tag.trim_start_matches(|c: char| c != ' ' && c != '\t' && c != '\n');
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+
+        if name_len == 0 {
+            break;
+        }
+
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = rest[name_len..].trim_start();
+
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+
+        let value = value.trim_start();
+        let (value, next) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => match value[1..].find(quote) {
+                Some(end) => (&value[1..end + 1], &value[end + 2..]),
+                None => (&value[1..], ""),
+            },
+            _ => {
+                let end = value
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(value.len());
+
+                (&value[..end], &value[end..])
+            }
+        };
+
+        attributes.entry(name).or_insert_with(|| value.to_owned());
+        rest = next;
+    }
+
+    attributes
+}
+
+/// Preview of the page `html` of `url` from its open graph metadata, falling
+/// back to its `<title>` and description.
+pub fn parse_preview(url: &Url, html: &str) -> Option<LinkPreview> {
+    // ascii lowercase keeps the byte offsets of `html`
+    let lower = html.to_ascii_lowercase();
+    let mut metas = HashMap::new();
+
+    for (start, _) in lower.match_indices("<meta") {
+        let Some(len) = lower[start..].find('>') else {
+            break;
+        };
+
+        let attributes = attributes(&html[start..start + len]);
+        let (Some(key), Some(content)) = (
+            attributes
+                .get("property")
+                .or_else(|| attributes.get("name")),
+            attributes.get("content"),
+        ) else {
+            continue;
+        };
+
+        metas
+            .entry(key.to_ascii_lowercase())
+            .or_insert_with(|| content.to_owned());
+    }
+
+    let title = metas
+        .get("og:title")
+        .map(|title| title.to_owned())
+        .or_else(|| {
+            let start = lower.find("<title")?;
+            let start = start + lower[start..].find('>')? + 1;
+            let end = start + lower[start..].find("</title")?;
+
+            Some(html[start..end].to_owned())
+        })
+        .map(|title| truncate(&title, MAX_TITLE_LEN))
+        .filter(|title| !title.is_empty())?;
+
+    let description = metas
+        .get("og:description")
+        .or_else(|| metas.get("description"))
+        .map(|description| truncate(description, MAX_DESCRIPTION_LEN))
+        .filter(|description| !description.is_empty());
+
+    let image_url = metas
+        .get("og:image")
+        .and_then(|image| url.join(&decode_entities(image.trim())).ok())
+        .filter(|image| matches!(image.scheme(), "http" | "https"))
+        .map(|image| image.to_string());
+
+    let site_name = metas
+        .get("og:site_name")
+        .map(|site_name| truncate(site_name, MAX_TITLE_LEN))
+        .filter(|site_name| !site_name.is_empty())
+        .or_else(|| url.host_str().map(|host| host.to_owned()))
+        .unwrap_or_default();
+
+    Some(LinkPreview {
+        url: url.to_string(),
+        title,
+        description,
+        image_url,
+        site_name,
+    })
+}
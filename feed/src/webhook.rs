@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, redirect::Policy, Url};
+use sha2::Sha256;
+use std::time::Duration;
+
+use crate::unfurl::{public_addr, USER_AGENT};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Hex encoded HMAC-SHA256 of `{timestamp}.{body}` with the secret of the
+/// webhook, sent as `X-Starter-Signature: sha256={signature}`.
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes keys of any size");
+
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body.as_bytes());
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Whether `signature`, hex encoded, is the one of `body` at `timestamp`
+/// with `secret`, computed like `sign` does. Stripe signs its events the
+/// same way.
+pub fn verify_signature(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    let Some(signature) = decode_hex(signature) else {
+        return false;
+    };
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes keys of any size");
+
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body.as_bytes());
+
+    mac.verify_slice(&signature).is_ok()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Posts `body` to the webhook `url`, checked like unfurled links so that a
+/// user can't make the server reach its own network. Redirects are not
+/// followed, the status code telling how it went.
+pub(crate) async fn send(url: &str, secret: &str, delivery_id: &str, body: &str) -> Result<u16> {
+    let url = Url::parse(url)?;
+    let addr = public_addr(&url).await?;
+    let mut client = reqwest::Client::builder()
+        .redirect(Policy::none())
+        .timeout(TIMEOUT)
+        .user_agent(USER_AGENT);
+
+    if let Some(domain) = url.domain() {
+        client = client.resolve(domain, addr);
+    }
+
+    let timestamp = Utc::now().timestamp();
+    let res = client
+        .build()?
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Starter-Delivery", delivery_id)
+        .header("X-Starter-Timestamp", timestamp)
+        .header(
+            "X-Starter-Signature",
+            format!("sha256={}", sign(secret, timestamp, body)),
+        )
+        .body(body.to_owned())
+        .send()
+        .await?;
+
+    Ok(res.status().as_u16())
+}
@@ -1,9 +1,22 @@
 mod common;
 
-use evento::Command;
-use starter_feed::CreateFeedInput;
+use chrono::Utc;
+use evento::{Aggregate, Command};
+use starter_feed::{
+    ArchiveFeedsInput, Attached, Comment, CommentMentioned, CreateCommentInput, CreateFeedInput,
+    CreateUserInput, Created, DeleteCommentInput, DeleteFeedInput, DeliverWebhookInput,
+    DisableUserInput, DiscardDraftInput, EditCommentInput, EditFeedInput, EmailSet, Feed,
+    FeedMetadata, FollowUserInput, HideFeedInput, IngestExternalEventInput, Mentioned,
+    MutedNotification, PinFeedInput, PublishDraftInput, PublishFeedInput, ReactFeedInput,
+    RegisterWebhookInput, RemoveWebhookInput, ReportFeedInput, RequestExportInput,
+    RequeueWebhookDeliveryInput, RestoreFeedInput, RevokeUserRoleInput, SaveDraftInput,
+    SetEmailInput, SetUserRoleInput, TagFeedInput, TrashFeedsInput, UndoBatchInput,
+    UnfollowUserInput, UnpinFeedInput, UntagFeedInput, UpdateNotificationPreferencesInput,
+    UpdateSubscriptionInput, VerifyEmailInput,
+};
 use std::time::Duration;
 use tokio::time::sleep;
+use ulid::Ulid;
 use uuid::Uuid;
 
 use crate::common::get_producer;
@@ -20,6 +33,10 @@ async fn create() {
             "en".to_owned(),
             &CreateFeedInput {
                 title: "aze".into(),
+                content: None,
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
                 user_id: Uuid::new_v4().to_string(),
                 request_id: None,
             },
@@ -31,3 +48,1249 @@ async fn create() {
 
     assert!(!events.is_empty());
 }
+
+#[tokio::test]
+async fn create_with_content() {
+    let cmd = command().await;
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title: "aze".into(),
+                content: Some("# Hello\n\nworld".into()),
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let data: Created = events[0].to_data().unwrap();
+
+    assert_eq!(data.content, "# Hello\n\nworld");
+}
+
+#[tokio::test]
+async fn create_with_visibility() {
+    let cmd = command().await;
+    let input = CreateFeedInput {
+        title: "aze".into(),
+        content: None,
+        attachments: vec![],
+        visibility: "private".into(),
+        publish_at: None,
+        user_id: Uuid::new_v4().to_string(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    let data: Created = events[0].to_data().unwrap();
+    assert_eq!(data.visibility, "private");
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                visibility: "friends".into(),
+                publish_at: None,
+                ..input
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+}
+
+#[tokio::test]
+async fn schedule() {
+    let cmd = command().await;
+    let input = CreateFeedInput {
+        title: "aze".into(),
+        content: None,
+        attachments: vec![],
+        visibility: "public".into(),
+        publish_at: Some(Utc::now() + chrono::Duration::seconds(1)),
+        user_id: Uuid::new_v4().to_string(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    let data: Created = events[0].to_data().unwrap();
+    assert!(data.publish_at.is_some());
+
+    let publish = PublishFeedInput {
+        id: Feed::from_aggregate_id(&events[0].aggregate_id),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &publish).await.unwrap();
+    assert!(events.is_empty());
+
+    sleep(Duration::from_millis(1100)).await;
+
+    let events = cmd.execute("en".to_owned(), &publish).await.unwrap();
+    assert_eq!(events[0].name, "published");
+
+    let events = cmd.execute("en".to_owned(), &publish).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                publish_at: Some(Utc::now()),
+                ..input
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+}
+
+#[tokio::test]
+async fn create_with_attachments() {
+    let cmd = command().await;
+    let attachment = Attached {
+        key: "01HR3G3TQ4ZV1Y5X0W8M2N6K7P.png".into(),
+        thumbnail_key: "01HR3G3TQ4ZV1Y5X0W8M2N6K7P.thumb.png".into(),
+        content_type: "image/png".into(),
+    };
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title: "aze".into(),
+                content: None,
+                attachments: vec![attachment.clone()],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events[1].name, "attached");
+    assert_eq!(events[1].to_data::<Attached>().unwrap(), attachment);
+}
+
+#[tokio::test]
+async fn react() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title: "aze".into(),
+                content: None,
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let input = ReactFeedInput {
+        feed_id: Feed::from_aggregate_id(&events[0].aggregate_id),
+        reaction: "🎉".into(),
+        user_id,
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "reacted");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "unreacted");
+}
+
+#[tokio::test]
+async fn react_by_many_users() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let feed_id = create_feed(&cmd, &user_id).await;
+
+    for user_id in [user_id, Uuid::new_v4().to_string()] {
+        let events = cmd
+            .execute(
+                "en".to_owned(),
+                &ReactFeedInput {
+                    feed_id: feed_id.to_owned(),
+                    reaction: "🎉".into(),
+                    user_id,
+                    request_id: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(events[0].name, "reacted");
+    }
+}
+
+#[tokio::test]
+async fn react_with_unknown_reaction() {
+    let cmd = command().await;
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &ReactFeedInput {
+                feed_id: "01HQZ5B0KJ2V6Q9S8ZC0T4Y6XW".into(),
+                reaction: "🦀".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+}
+
+async fn create_feed(cmd: &Command, user_id: &str) -> String {
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title: "aze".into(),
+                content: None,
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    Feed::from_aggregate_id(&events[0].aggregate_id)
+}
+
+#[tokio::test]
+async fn comment() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let feed_id = create_feed(&cmd, &user_id).await;
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateCommentInput {
+                feed_id: feed_id.to_owned(),
+                parent_id: None,
+                content: "first".into(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let id = Comment::from_aggregate_id(&events[0].aggregate_id);
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateCommentInput {
+                feed_id,
+                parent_id: Some(id.to_owned()),
+                content: "reply".into(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events[0].name, "created");
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &EditCommentInput {
+                id: id.to_owned(),
+                content: "edited".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &DeleteCommentInput {
+                id,
+                user_id,
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events[0].name, "deleted");
+}
+
+#[tokio::test]
+async fn comment_on_unknown_feed() {
+    let cmd = command().await;
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateCommentInput {
+                feed_id: "01HQZ5B0KJ2V6Q9S8ZC0T4Y6XW".into(),
+                parent_id: None,
+                content: "first".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn tag() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let feed_id = create_feed(&cmd, &user_id).await;
+
+    let input = TagFeedInput {
+        feed_id: feed_id.to_owned(),
+        tag: "rust".into(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "tagged");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &UntagFeedInput {
+                feed_id,
+                tag: "rust".into(),
+                user_id,
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events[0].name, "untagged");
+}
+
+#[tokio::test]
+async fn tag_feed_of_other_user() {
+    let cmd = command().await;
+    let feed_id = create_feed(&cmd, &Uuid::new_v4().to_string()).await;
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &TagFeedInput {
+                feed_id,
+                tag: "rust".into(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn edit() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let feed_id = create_feed(&cmd, &user_id).await;
+
+    let input = EditFeedInput {
+        id: feed_id,
+        title: "edited".into(),
+        content: "edited content".into(),
+        user_id,
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "edited");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn report() {
+    let cmd = command().await;
+    let feed_id = create_feed(&cmd, &Uuid::new_v4().to_string()).await;
+
+    let input = ReportFeedInput {
+        feed_id,
+        reason: "spam".into(),
+        lang: "en".into(),
+        user_id: Uuid::new_v4().to_string(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "reported");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &ReportFeedInput {
+                reason: "boring".into(),
+                ..input
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+}
+
+#[tokio::test]
+async fn hide_and_restore() {
+    let cmd = command().await;
+    let feed_id = create_feed(&cmd, &Uuid::new_v4().to_string()).await;
+    let moderator_id = Uuid::new_v4().to_string();
+
+    let hide = HideFeedInput {
+        feed_id: feed_id.to_owned(),
+        reason: Some("spam".into()),
+        user_id: moderator_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &hide).await.unwrap();
+    assert_eq!(events[0].name, "hidden");
+
+    let events = cmd.execute("en".to_owned(), &hide).await.unwrap();
+    assert!(events.is_empty());
+
+    let restore = RestoreFeedInput {
+        feed_id,
+        user_id: moderator_id,
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &restore).await.unwrap();
+    assert_eq!(events[0].name, "restored");
+
+    let events = cmd.execute("en".to_owned(), &restore).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn follow() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let followed_id = Uuid::new_v4().to_string();
+
+    let input = FollowUserInput {
+        followed_id: followed_id.to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "followed");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let input = UnfollowUserInput {
+        followed_id,
+        user_id,
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "unfollowed");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn follow_self() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &FollowUserInput {
+                followed_id: user_id.to_owned(),
+                user_id,
+                request_id: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+}
+
+#[tokio::test]
+async fn draft() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let id = Ulid::new().to_string();
+
+    let input = SaveDraftInput {
+        id: id.to_owned(),
+        title: "My draft".to_owned(),
+        content: "Work in progress".to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "saved");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &PublishDraftInput {
+                id: id.to_owned(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &PublishDraftInput {
+                id: id.to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "created");
+    assert_eq!(Feed::from_aggregate_id(&events[0].aggregate_id), id);
+    assert_eq!(events[1].name, "published");
+
+    let data = events[0].to_data::<Created>().unwrap();
+    assert_eq!(data.title, "My draft");
+    assert_eq!(data.content, "Work in progress");
+
+    let result = cmd.execute("en".to_owned(), &input).await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn discard_draft() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let id = Ulid::new().to_string();
+
+    cmd.execute(
+        "en".to_owned(),
+        &SaveDraftInput {
+            id: id.to_owned(),
+            title: "".to_owned(),
+            content: "".to_owned(),
+            user_id: user_id.to_owned(),
+            request_id: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let input = PublishDraftInput {
+        id: id.to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+    let result = cmd.execute("en".to_owned(), &input).await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &DiscardDraftInput {
+                id,
+                user_id,
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "discarded");
+
+    let result = cmd.execute("en".to_owned(), &input).await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn pin() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let feed_id = create_feed(&cmd, &user_id).await;
+    let other_feed_id = create_feed(&cmd, &user_id).await;
+
+    let input = PinFeedInput {
+        feed_id: feed_id.to_owned(),
+        max_pins: 1,
+        is_admin: false,
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "pinned");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &PinFeedInput {
+                feed_id: other_feed_id,
+                max_pins: 1,
+                is_admin: false,
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &UnpinFeedInput {
+                feed_id: feed_id.to_owned(),
+                is_admin: false,
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &UnpinFeedInput {
+                feed_id,
+                is_admin: true,
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "unpinned");
+}
+
+#[tokio::test]
+async fn mention() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4();
+    let mentioned_id = Uuid::new_v4();
+    let other_id = Uuid::new_v4();
+
+    let create = |visibility: &str| CreateFeedInput {
+        title: "aze".into(),
+        content: Some(format!(
+            "Hi @{mentioned_id}, @{mentioned_id} and @{user_id}"
+        )),
+        attachments: vec![],
+        visibility: visibility.into(),
+        publish_at: None,
+        user_id: user_id.to_string(),
+        request_id: None,
+    };
+
+    let events = cmd
+        .execute("en".to_owned(), &create("private"))
+        .await
+        .unwrap();
+    assert!(events.iter().all(|event| event.name != "mentioned"));
+
+    let events = cmd
+        .execute("en".to_owned(), &create("public"))
+        .await
+        .unwrap();
+    assert_eq!(events[1].name, "mentioned");
+    assert_eq!(
+        events[1].to_data::<Mentioned>().unwrap().user_ids,
+        vec![mentioned_id]
+    );
+
+    let feed_id = Feed::from_aggregate_id(&events[0].aggregate_id);
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &EditFeedInput {
+                id: feed_id.to_owned(),
+                title: "aze".into(),
+                content: format!("Hi @{mentioned_id} and @{other_id}"),
+                user_id: user_id.to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[1].name, "mentioned");
+    assert_eq!(
+        events[1].to_data::<Mentioned>().unwrap().user_ids,
+        vec![other_id]
+    );
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateCommentInput {
+                feed_id: feed_id.to_owned(),
+                parent_id: None,
+                content: format!("cc @{other_id}"),
+                user_id: mentioned_id.to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[1].name, "mentioned");
+
+    let data = events[1].to_data::<CommentMentioned>().unwrap();
+    assert_eq!(data.feed_id, feed_id);
+    assert_eq!(data.user_ids, vec![other_id]);
+}
+
+#[tokio::test]
+async fn export() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let id = Ulid::new().to_string();
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &RequestExportInput {
+                id: id.to_owned(),
+                format: "xml".to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let input = RequestExportInput {
+        id: id.to_owned(),
+        format: "csv".to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "requested");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn bulk_archive_and_delete() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let ids = vec![
+        create_feed(&cmd, &user_id).await,
+        create_feed(&cmd, &user_id).await,
+    ];
+    let other_id = create_feed(&cmd, &Uuid::new_v4().to_string()).await;
+    let batch_id = Uuid::new_v4().to_string();
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &TrashFeedsInput {
+                ids: vec![ids[0].to_owned(), other_id],
+                user_id: user_id.to_owned(),
+                request_id: batch_id.to_owned(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &ArchiveFeedsInput {
+                ids: ids.to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: batch_id.to_owned(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.name == "archived"
+        && event.to_metadata::<FeedMetadata>().unwrap().unwrap().req_id == batch_id));
+
+    let undo = UndoBatchInput {
+        ids: ids.to_owned(),
+        batch_id: batch_id.to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &undo).await.unwrap();
+    assert!(events.iter().all(|event| event.name == "unarchived"));
+
+    let result = cmd.execute("en".to_owned(), &undo).await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &TrashFeedsInput {
+                ids: ids.to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: Uuid::new_v4().to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(events.iter().all(|event| event.name == "trashed"));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &DeleteFeedInput {
+                id: ids[0].to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn notification_preferences() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &UpdateNotificationPreferencesInput {
+                muted: vec![MutedNotification {
+                    kind: "digest".to_owned(),
+                    channel: "in_app".to_owned(),
+                }],
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let input = UpdateNotificationPreferencesInput {
+        muted: vec![MutedNotification {
+            kind: "mention".to_owned(),
+            channel: "email".to_owned(),
+        }],
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "notifications-updated");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn mute_push_notifications() {
+    let cmd = command().await;
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &UpdateNotificationPreferencesInput {
+                muted: vec![MutedNotification {
+                    kind: "export".to_owned(),
+                    channel: "push".to_owned(),
+                }],
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "notifications-updated");
+}
+
+#[tokio::test]
+async fn email() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &VerifyEmailInput {
+                token: "token".to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &SetEmailInput {
+                email: "not an email".to_owned(),
+                lang: "en".to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let input = SetEmailInput {
+        email: "john@example.com".to_owned(),
+        lang: "en".to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "email-set");
+    let token = events[0].to_data::<EmailSet>().unwrap().token;
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &VerifyEmailInput {
+                token: "token".to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let verify = VerifyEmailInput {
+        token,
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &verify).await.unwrap();
+    assert_eq!(events[0].name, "email-verified");
+
+    let events = cmd.execute("en".to_owned(), &verify).await.unwrap();
+    assert!(events.is_empty());
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn webhooks() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let id = Ulid::new().to_string();
+
+    for url in ["ftp://example.com/hook", "http://example.com:8080/hook"] {
+        let result = cmd
+            .execute(
+                "en".to_owned(),
+                &RegisterWebhookInput {
+                    id: id.to_owned(),
+                    url: url.to_owned(),
+                    user_id: user_id.to_owned(),
+                    request_id: None,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+    }
+
+    let input = RegisterWebhookInput {
+        id: id.to_owned(),
+        url: "https://example.com/hook".to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "registered");
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &RemoveWebhookInput {
+                id: id.to_owned(),
+                user_id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &RemoveWebhookInput {
+                id: id.to_owned(),
+                user_id: user_id.to_owned(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "removed");
+}
+
+#[tokio::test]
+async fn requeue_webhook_delivery() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let id = format!("{}-{}", Ulid::new(), Ulid::new());
+    let deliver = DeliverWebhookInput {
+        id: id.to_owned(),
+        url: "https://127.0.0.1/hook".to_owned(),
+        secret: "secret".to_owned(),
+        body: "{}".to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+    let requeue = RequeueWebhookDeliveryInput {
+        id: id.to_owned(),
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let result = cmd.execute("en".to_owned(), &requeue).await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd.execute("en".to_owned(), &deliver).await.unwrap();
+    assert_eq!(events[0].name, "failed");
+
+    let events = cmd.execute("en".to_owned(), &deliver).await.unwrap();
+    assert!(events.is_empty());
+
+    let events = cmd.execute("en".to_owned(), &requeue).await.unwrap();
+    assert_eq!(events[0].name, "requeued");
+
+    let result = cmd.execute("en".to_owned(), &requeue).await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let events = cmd.execute("en".to_owned(), &deliver).await.unwrap();
+    assert_eq!(events[0].name, "failed");
+}
+
+#[tokio::test]
+async fn users() {
+    let cmd = command().await;
+    let id = Uuid::new_v4().to_string();
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateUserInput {
+                id: id.to_owned(),
+                name: "john".to_owned(),
+                role: "owner".to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let input = CreateUserInput {
+        id: id.to_owned(),
+        name: "john".to_owned(),
+        role: "user".to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "created");
+
+    let result = cmd.execute("en".to_owned(), &input).await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let set_role = SetUserRoleInput {
+        id: id.to_owned(),
+        role: "admin".to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &set_role).await.unwrap();
+    assert_eq!(events[0].name, "role-set");
+
+    let events = cmd.execute("en".to_owned(), &set_role).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &RevokeUserRoleInput {
+                id: id.to_owned(),
+                role: "user".to_owned(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let revoke = RevokeUserRoleInput {
+        id: id.to_owned(),
+        role: "admin".to_owned(),
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &revoke).await.unwrap();
+    assert_eq!(events[0].name, "role-set");
+
+    let events = cmd.execute("en".to_owned(), &revoke).await.unwrap();
+    assert!(events.is_empty());
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &DisableUserInput {
+                id: Uuid::new_v4().to_string(),
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::NotFound(_))));
+
+    let disable = DisableUserInput {
+        id,
+        request_id: None,
+    };
+
+    let events = cmd.execute("en".to_owned(), &disable).await.unwrap();
+    assert_eq!(events[0].name, "disabled");
+
+    let events = cmd.execute("en".to_owned(), &disable).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn update_subscription() {
+    let cmd = command().await;
+    let user_id = Uuid::new_v4().to_string();
+    let changed_at = Utc::now();
+
+    let input = |status: &str, changed_at| UpdateSubscriptionInput {
+        customer_id: "cus_1".to_owned(),
+        subscription_id: "sub_1".to_owned(),
+        status: status.to_owned(),
+        current_period_end: None,
+        stripe_event_id: Ulid::new().to_string(),
+        changed_at,
+        user_id: user_id.to_owned(),
+        request_id: None,
+    };
+
+    let result = cmd
+        .execute("en".to_owned(), &input("unknown", changed_at))
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let events = cmd
+        .execute("en".to_owned(), &input("active", changed_at))
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "subscription-updated");
+
+    let events = cmd
+        .execute("en".to_owned(), &input("active", changed_at))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &input("incomplete", changed_at - chrono::Duration::seconds(5)),
+        )
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &input("canceled", changed_at + chrono::Duration::seconds(5)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(events[0].name, "subscription-updated");
+}
+
+#[tokio::test]
+async fn ingest_external_event() {
+    let cmd = command().await;
+    let input = IngestExternalEventInput {
+        id: Ulid::new().to_string(),
+        subject: "orders.created".to_owned(),
+        payload: serde_json::json!({ "order": 1 }),
+        request_id: None,
+    };
+
+    let result = cmd
+        .execute(
+            "en".to_owned(),
+            &IngestExternalEventInput {
+                id: input.id.to_owned(),
+                subject: "".to_owned(),
+                payload: serde_json::Value::Null,
+                request_id: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(evento::CommandError::Validation(_))));
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert_eq!(events[0].name, "received");
+    assert_eq!(events[0].aggregate_id, format!("external/{}", input.id));
+
+    let events = cmd.execute("en".to_owned(), &input).await.unwrap();
+    assert!(events.is_empty());
+}
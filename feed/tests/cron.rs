@@ -0,0 +1,89 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use starter_feed::{Cron, CronContext, CronJob, CronTask};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time::sleep;
+
+/// Runs of the tasks, given to them as data of the cron.
+#[derive(Clone, Default)]
+struct Runs(Arc<AtomicUsize>);
+
+impl Runs {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct CountTask;
+
+#[async_trait]
+impl CronTask for CountTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        ctx.extract::<Runs>().0.fetch_add(1, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+/// Fails on its first attempt only.
+struct FlakyTask;
+
+#[async_trait]
+impl CronTask for FlakyTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        match ctx.extract::<Runs>().0.fetch_add(1, Ordering::SeqCst) {
+            0 => anyhow::bail!("first attempt"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn invalid_expression() {
+    let db = starter_test::db::schema().await.connect().await;
+
+    assert!(Cron::new(db)
+        .jobs(vec![CronJob::new("count", "every day", CountTask)])
+        .is_err());
+}
+
+#[tokio::test]
+async fn retry() {
+    let db = starter_test::db::schema().await.connect().await;
+    let runs = Runs::default();
+
+    Cron::new(db)
+        .data(runs.clone())
+        .jobs(vec![CronJob::new("flaky", "*/5 * * * * *", FlakyTask)
+            .retries(2, Duration::from_millis(100))])
+        .unwrap()
+        .spawn();
+
+    sleep(Duration::from_secs(6)).await;
+
+    assert!(runs.count() >= 2);
+}
+
+#[tokio::test]
+async fn claim_once() {
+    let db = starter_test::db::schema().await.connect().await;
+    let runs = Runs::default();
+
+    for _ in 0..2 {
+        Cron::new(db.clone())
+            .data(runs.clone())
+            .jobs(vec![CronJob::new("count", "*/2 * * * * *", CountTask)])
+            .unwrap()
+            .spawn();
+    }
+
+    sleep(Duration::from_millis(4500)).await;
+
+    assert!((1..=3).contains(&runs.count()));
+}
@@ -0,0 +1,259 @@
+use evento::{store::Event, Command, PgConsumer, Producer, Query};
+use evento_query::Cursor;
+use proptest::{collection::vec, prelude::*};
+use sqlx::PgPool;
+use starter_feed::{CreateFeedInput, Feed, ListFeedReactionsInput, ReactFeedInput, REACTIONS};
+use std::{
+    collections::HashSet,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+use tokio::{runtime::Runtime, sync::OnceCell, time::sleep};
+use ulid::Ulid;
+use uuid::Uuid;
+
+/// Users reacting in a sequence, few enough for them to toggle the same
+/// reactions.
+const USERS: usize = 3;
+
+/// Time given to the consumers to project the events of a sequence.
+const PROJECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Env {
+    /// Url of the schema, for the replays to connect on their own runtime.
+    url: String,
+    pool: PgPool,
+    producer: Producer,
+}
+
+/// Runtime of every case, which the consumers keep running on between them.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    RUNTIME.get_or_init(|| Runtime::new().expect("runtime not built"))
+}
+
+async fn env() -> &'static Env {
+    static ENV: OnceCell<Env> = OnceCell::const_new();
+
+    ENV.get_or_init(|| async {
+        let schema = starter_test::db::schema().await;
+        let pool = schema.connect().await;
+        let producer = PgConsumer::new(&pool)
+            .data(starter_feed::ConsumerGate::default())
+            .rules(starter_feed::rules())
+            .start(0)
+            .await
+            .unwrap();
+
+        Env {
+            url: schema.url,
+            pool,
+            producer,
+        }
+    })
+    .await
+}
+
+/// Reactions of `feed_id` by `REACTIONS` index as projected, and whether any
+/// count went below zero.
+async fn projected(env: &Env, feed_id: &str) -> (Vec<i64>, bool) {
+    let counts = Query::new()
+        .data(env.pool.clone())
+        .execute(&ListFeedReactionsInput {
+            feed_id: feed_id.to_owned(),
+            user_id: None,
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|count| count.total_count)
+        .collect();
+
+    let negative = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM feed_reaction_counts WHERE feed_id = $1 AND total_count < 0)",
+    )
+    .bind(feed_id)
+    .fetch_one(&env.pool)
+    .await
+    .unwrap();
+
+    (counts, negative)
+}
+
+/// Waits for the projection of `feed_id` to reach `expected`, returning the
+/// last one seen otherwise.
+async fn wait_for(env: &Env, feed_id: &str, expected: &[i64]) -> (Vec<i64>, bool) {
+    let started = Instant::now();
+
+    loop {
+        let projected = projected(env, feed_id).await;
+
+        if projected.0 == expected || started.elapsed() > PROJECTION_TIMEOUT {
+            return projected;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Consumer of another name handling every event of the schema again from
+/// the start, into the same tables. It runs on a runtime of its own, with
+/// its own pool, for it to be stopped with the runtime once dropped.
+struct Replay {
+    name: String,
+    runtime: Option<Runtime>,
+}
+
+impl Replay {
+    fn start(env: &Env) -> Self {
+        let name = format!("replay-{}", Ulid::new().to_string().to_lowercase());
+        let runtime = Runtime::new().expect("replay runtime not built");
+        let url = env.url.to_owned();
+        let consumer = name.to_owned();
+
+        runtime.spawn(async move {
+            let pool = PgPool::connect(&url).await.expect("schema unreachable");
+            let _producer = PgConsumer::new(&pool)
+                .name(&consumer)
+                .data(starter_feed::ConsumerGate::default())
+                .rules(starter_feed::rules())
+                .start(0)
+                .await
+                .unwrap();
+
+            std::future::pending::<()>().await;
+        });
+
+        Self {
+            name,
+            runtime: Some(runtime),
+        }
+    }
+
+    /// Waits for the cursors of the rules of the replay to reach the last
+    /// event of the schema, returning whether they did within
+    /// `PROJECTION_TIMEOUT`.
+    async fn caught_up(&self, env: &Env) -> bool {
+        let last = sqlx::query_as::<_, Event>(
+            "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event ORDER BY created_at DESC, version DESC, id DESC LIMIT 1",
+        )
+        .fetch_one(&env.pool)
+        .await
+        .unwrap();
+        let started = Instant::now();
+
+        while started.elapsed() <= PROJECTION_TIMEOUT {
+            let (rules, behind) = sqlx::query_as::<_, (i64, i64)>(
+                "SELECT COUNT(*), COUNT(*) FILTER (WHERE cursor IS DISTINCT FROM $2) FROM ev_queue WHERE rule LIKE $1",
+            )
+            .bind(format!("%{}%", self.name))
+            .bind(last.to_cursor().0)
+            .fetch_one(&env.pool)
+            .await
+            .unwrap();
+
+            if rules > 0 && behind == 0 {
+                return true;
+            }
+
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        false
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Toggles the reactions of `reactions` by user and `REACTIONS` index on a
+/// new feed, checking the events, the aggregate and the projection against
+/// a model of the reactions, then the projection once the events replayed.
+async fn check_reactions(reactions: Vec<(usize, usize)>) -> Result<(), TestCaseError> {
+    let env = env().await;
+    let cmd = Command::new(&env.producer);
+    let users: Vec<Uuid> = (0..USERS).map(|_| Uuid::new_v4()).collect();
+
+    let events = cmd
+        .execute(
+            "en".to_owned(),
+            &CreateFeedInput {
+                title: "aze".into(),
+                content: None,
+                attachments: vec![],
+                visibility: "public".into(),
+                publish_at: None,
+                user_id: users[0].to_string(),
+                request_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let feed_id = Feed::from_aggregate_id(&events[0].aggregate_id);
+    let mut model = HashSet::new();
+
+    for (user, reaction) in reactions {
+        let key = (users[user], REACTIONS[reaction].to_owned());
+        let expected = if model.insert(key.clone()) {
+            "reacted"
+        } else {
+            model.remove(&key);
+            "unreacted"
+        };
+
+        let events = cmd
+            .execute(
+                "en".to_owned(),
+                &ReactFeedInput {
+                    feed_id: feed_id.to_owned(),
+                    reaction: key.1,
+                    user_id: key.0.to_string(),
+                    request_id: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        prop_assert_eq!(events.len(), 1);
+        prop_assert_eq!(events[0].name.as_str(), expected);
+    }
+
+    let (feed, _) = cmd.load::<Feed>(feed_id.to_owned()).await.unwrap().unwrap();
+    prop_assert_eq!(&feed.reactions, &model);
+
+    let expected: Vec<i64> = REACTIONS
+        .iter()
+        .map(|reaction| model.iter().filter(|(_, r)| r == reaction).count() as i64)
+        .collect();
+
+    let (counts, negative) = wait_for(env, &feed_id, &expected).await;
+    prop_assert!(!negative, "negative reaction count");
+    prop_assert_eq!(&counts, &expected);
+
+    // Stopped once the case is done, by the drop of the replay.
+    let replay = Replay::start(env);
+    prop_assert!(replay.caught_up(env).await, "replay never caught up");
+
+    let (counts, negative) = projected(env, &feed_id).await;
+    prop_assert!(!negative, "negative reaction count after replay");
+    prop_assert_eq!(&counts, &expected, "replay changed the projection");
+
+    Ok(())
+}
+
+proptest! {
+    // Every case creates a feed and replays the whole schema, keep them few.
+    #![proptest_config(ProptestConfig::with_cases(12))]
+
+    #[test]
+    fn reactions(reactions in vec((0..USERS, 0..REACTIONS.len()), 1..24)) {
+        runtime().block_on(check_reactions(reactions))?;
+    }
+}
@@ -0,0 +1,148 @@
+use reqwest::Url;
+use starter_feed::{is_public, parse_preview, unfurl, LinkPreview};
+use std::net::IpAddr;
+
+fn preview(html: &str) -> Option<LinkPreview> {
+    parse_preview(&Url::parse("https://example.com/posts/1").unwrap(), html)
+}
+
+#[test]
+fn public_addresses() {
+    let blocked = [
+        "127.0.0.1",
+        "127.1.2.3",
+        "10.0.0.1",
+        "10.255.255.255",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "100.127.255.255",
+        "198.18.0.1",
+        "0.0.0.0",
+        "0.1.2.3",
+        "255.255.255.255",
+        "224.0.0.1",
+        "192.0.2.1",
+        "::1",
+        "::",
+        "::ffff:10.0.0.1",
+        "::ffff:127.0.0.1",
+        "fc00::1",
+        "fd12:3456::1",
+        "fe80::1",
+        "ff02::1",
+    ];
+
+    for ip in blocked {
+        assert!(!is_public(ip.parse::<IpAddr>().unwrap()), "{ip} is public");
+    }
+
+    let public = [
+        "8.8.8.8",
+        "100.63.255.255",
+        "100.128.0.1",
+        "172.32.0.1",
+        "2606:4700::1111",
+        "::ffff:8.8.8.8",
+    ];
+
+    for ip in public {
+        assert!(
+            is_public(ip.parse::<IpAddr>().unwrap()),
+            "{ip} is not public"
+        );
+    }
+}
+
+#[tokio::test]
+async fn refused_links() {
+    // Refused before any lookup or request: addresses are literal, and
+    // schemes and ports are checked first.
+    let links = [
+        ("http://127.0.0.1/", "non public"),
+        ("http://10.1.2.3/", "non public"),
+        ("http://169.254.169.254/latest/meta-data/", "non public"),
+        ("http://100.64.0.1/", "non public"),
+        ("http://[::1]/", "non public"),
+        ("http://[::ffff:10.0.0.1]/", "non public"),
+        ("https://[fc00::1]/", "non public"),
+        ("http://example.com:8080/", "port not allowed"),
+        ("https://example.com:22/", "port not allowed"),
+        ("http://127.0.0.1:6379/", "port not allowed"),
+        ("ftp://example.com/", "scheme not allowed"),
+        ("file:///etc/passwd", "scheme not allowed"),
+        ("gopher://example.com/", "scheme not allowed"),
+    ];
+
+    for (link, reason) in links {
+        let err = unfurl(link).await.expect_err(link).to_string();
+
+        assert!(err.contains(reason), "{link}: {err}");
+    }
+}
+
+#[test]
+fn open_graph() {
+    let preview = preview(
+        r#"<html><head>
+            <title>Fallback</title>
+            <meta property="og:title" content="Double &quot;quoted&quot;" />
+            <meta property='og:description' content='Single &amp; quoted'>
+            <meta property=og:image content=/images/cover.png>
+            <META PROPERTY="og:site_name" CONTENT="Example">
+        </head></html>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        preview,
+        LinkPreview {
+            url: "https://example.com/posts/1".to_owned(),
+            title: "Double \"quoted\"".to_owned(),
+            description: Some("Single & quoted".to_owned()),
+            image_url: Some("https://example.com/images/cover.png".to_owned()),
+            site_name: "Example".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn fallbacks() {
+    let preview = preview(
+        r#"<title lang="en">Tom &amp; Jerry &lt;3 &#39;1940&#x27; &amp;lt;</title>
+        <meta name=description content="Café ☕">
+        <meta property="og:image" content="javascript:alert(1)">"#,
+    )
+    .unwrap();
+
+    assert_eq!(preview.title, "Tom & Jerry <3 '1940' &lt;");
+    assert_eq!(preview.description.as_deref(), Some("Café ☕"));
+    assert_eq!(preview.image_url, None);
+    assert_eq!(preview.site_name, "example.com");
+}
+
+#[test]
+fn malformed() {
+    assert_eq!(preview("<title>No end"), None);
+    assert_eq!(preview("<title>   </title>"), None);
+    assert_eq!(preview(r#"<meta property="og:title" content="">"#), None);
+
+    // Unterminated quotes and tags keep the rest as the value, without
+    // reading out of the tag.
+    let unterminated = preview(
+        r#"<meta property="og:title" content="Unterminated>
+        <meta property="og:description" content='x"#,
+    )
+    .unwrap();
+    assert_eq!(unterminated.title, "Unterminated");
+    assert_eq!(unterminated.description, None);
+
+    for html in [
+        "<meta",
+        "<meta property= content>",
+        r#"<meta ="og:title" content=x>"#,
+    ] {
+        assert_eq!(preview(html), None, "{html}");
+    }
+}
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS feature_flags
+(
+    name VARCHAR(100) NOT NULL PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_reactions
+(
+    feed_id VARCHAR(26) NOT NULL,
+    user_id UUID NOT NULL,
+    reaction VARCHAR(16) NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (feed_id, user_id, reaction)
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS feed_comments
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    feed_id VARCHAR(26) NOT NULL,
+    parent_id VARCHAR(26) NULL,
+    user_id UUID NOT NULL,
+    author VARCHAR(100) NOT NULL,
+    content TEXT NOT NULL,
+    deleted BOOLEAN NOT NULL DEFAULT false,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NULL
+);
+
+CREATE INDEX ON feed_comments (feed_id, created_at);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS feed_reaction_counts
+(
+    feed_id VARCHAR(26) NOT NULL,
+    reaction VARCHAR(16) NOT NULL,
+    total_count int8 NOT NULL DEFAULT 0 CHECK (total_count >= 0),
+    PRIMARY KEY (feed_id, reaction)
+);
+
+INSERT INTO feed_reaction_counts (feed_id, reaction, total_count)
+SELECT feed_id, reaction, COUNT(*) FROM feed_reactions GROUP BY feed_id, reaction;
+
+UPDATE feed_feeds SET total_likes = counts.total_count
+FROM (SELECT feed_id, COUNT(*) AS total_count FROM feed_reactions GROUP BY feed_id) AS counts
+WHERE feed_feeds.id = counts.feed_id;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS feed_feeds_search_idx;
+DROP FUNCTION IF EXISTS feed_search_document;
@@ -0,0 +1,6 @@
+CREATE OR REPLACE FUNCTION feed_search_document(title TEXT, content TEXT) RETURNS tsvector
+AS $$
+    SELECT setweight(to_tsvector('simple', title), 'A') || setweight(to_tsvector('simple', content), 'B')
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE INDEX IF NOT EXISTS feed_feeds_search_idx ON feed_feeds USING GIN (feed_search_document(title, content));
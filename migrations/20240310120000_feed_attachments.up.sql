@@ -0,0 +1 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS attachments JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS feed_moderation;
+DROP TABLE IF EXISTS feed_reports;
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS hidden;
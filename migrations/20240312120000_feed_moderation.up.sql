@@ -0,0 +1,23 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS hidden BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS feed_reports
+(
+    feed_id VARCHAR(26) NOT NULL,
+    user_id UUID NOT NULL,
+    reason VARCHAR(16) NOT NULL,
+    lang VARCHAR(16) NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (feed_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS feed_moderation
+(
+    feed_id VARCHAR(26) NOT NULL PRIMARY KEY,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    total_reports int4 NOT NULL DEFAULT 0,
+    reason TEXT NULL,
+    moderated_by UUID NULL,
+    updated_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_moderation (status, updated_at);
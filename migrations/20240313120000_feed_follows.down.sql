@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS feed_timelines;
+DROP TABLE IF EXISTS feed_follows;
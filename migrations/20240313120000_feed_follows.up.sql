@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS feed_follows
+(
+    follower_id UUID NOT NULL,
+    followed_id UUID NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (follower_id, followed_id)
+);
+
+CREATE INDEX ON feed_follows (followed_id);
+
+CREATE TABLE IF NOT EXISTS feed_timelines
+(
+    user_id UUID NOT NULL,
+    feed_id VARCHAR(26) NOT NULL,
+    author_id UUID NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (user_id, feed_id)
+);
+
+CREATE INDEX ON feed_timelines (user_id, author_id);
@@ -0,0 +1 @@
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS visibility;
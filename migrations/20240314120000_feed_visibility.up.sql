@@ -0,0 +1 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS visibility VARCHAR(16) NOT NULL DEFAULT 'public';
@@ -0,0 +1,3 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS publish_at timestamptz NULL;
+
+CREATE INDEX ON feed_feeds (publish_at) WHERE publish_at IS NOT NULL;
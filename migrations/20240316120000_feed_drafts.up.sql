@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS feed_drafts
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    title VARCHAR(100) NOT NULL,
+    content TEXT NOT NULL,
+    updated_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_drafts (user_id, updated_at);
@@ -0,0 +1,3 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS pinned_at timestamptz NULL;
+
+CREATE INDEX ON feed_feeds (pinned_at) WHERE pinned_at IS NOT NULL;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS feed_notifications
+(
+    user_id UUID NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    feed_id VARCHAR(26) NOT NULL,
+    comment_id VARCHAR(26) NOT NULL DEFAULT '',
+    actor_id UUID NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (user_id, kind, feed_id, comment_id)
+);
+
+CREATE INDEX ON feed_notifications (user_id, created_at);
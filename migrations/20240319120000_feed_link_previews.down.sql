@@ -0,0 +1,2 @@
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS preview;
+DROP TABLE IF EXISTS feed_link_previews;
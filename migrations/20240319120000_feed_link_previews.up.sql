@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_link_previews
+(
+    url TEXT NOT NULL PRIMARY KEY,
+    preview JSONB NULL,
+    fetched_at timestamptz NOT NULL
+);
+
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS preview JSONB NULL;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS feed_exports
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    format VARCHAR(10) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_exports (user_id, created_at);
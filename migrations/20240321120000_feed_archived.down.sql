@@ -0,0 +1,3 @@
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS batch_id;
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS delete_at;
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS archived;
@@ -0,0 +1,5 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS archived BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS delete_at timestamptz NULL;
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS batch_id VARCHAR(36) NULL;
+
+CREATE INDEX ON feed_feeds (delete_at) WHERE delete_at IS NOT NULL;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS feed_muted_notifications
+(
+    user_id UUID NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    channel VARCHAR(20) NOT NULL,
+    PRIMARY KEY (user_id, kind, channel)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS feed_trending;
+DROP TABLE IF EXISTS feed_trending_interactions;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS feed_trending_interactions
+(
+    feed_id VARCHAR(26) NOT NULL,
+    source VARCHAR(100) NOT NULL,
+    weight DOUBLE PRECISION NOT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (feed_id, source)
+);
+
+CREATE TABLE IF NOT EXISTS feed_trending
+(
+    feed_id VARCHAR(26) NOT NULL PRIMARY KEY,
+    score DOUBLE PRECISION NOT NULL
+);
+
+CREATE INDEX ON feed_trending (score DESC);
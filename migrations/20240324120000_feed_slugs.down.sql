@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS feed_slugs;
+
+ALTER TABLE feed_feeds DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,10 @@
+ALTER TABLE feed_feeds ADD COLUMN IF NOT EXISTS slug VARCHAR(100) NULL;
+
+CREATE TABLE IF NOT EXISTS feed_slugs
+(
+    slug VARCHAR(100) NOT NULL PRIMARY KEY,
+    feed_id VARCHAR(26) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_slugs (feed_id);
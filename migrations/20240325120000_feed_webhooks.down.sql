@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS feed_webhook_deliveries;
+DROP TABLE IF EXISTS feed_webhooks;
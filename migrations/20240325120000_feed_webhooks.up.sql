@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS feed_webhooks
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_webhooks (user_id, created_at);
+
+CREATE TABLE IF NOT EXISTS feed_webhook_deliveries
+(
+    id VARCHAR(53) NOT NULL PRIMARY KEY,
+    webhook_id VARCHAR(26) NOT NULL,
+    feed_id VARCHAR(26) NOT NULL,
+    user_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    body TEXT NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    status_code INT NULL,
+    error TEXT NULL,
+    next_attempt_at timestamptz NULL,
+    delivered_at timestamptz NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_webhook_deliveries (next_attempt_at) WHERE next_attempt_at IS NOT NULL;
+CREATE INDEX ON feed_webhook_deliveries (user_id, created_at);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_users
+(
+    id UUID NOT NULL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    role VARCHAR(10) NOT NULL,
+    disabled BOOLEAN NOT NULL DEFAULT false,
+    created_at timestamptz NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_user_emails
+(
+    user_id UUID NOT NULL PRIMARY KEY,
+    email VARCHAR(254) NOT NULL,
+    lang VARCHAR(35) NOT NULL,
+    verified BOOLEAN NOT NULL DEFAULT false,
+    updated_at timestamptz NOT NULL
+);
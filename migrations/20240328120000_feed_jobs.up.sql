@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS feed_jobs
+(
+    id VARCHAR(26) NOT NULL PRIMARY KEY,
+    kind VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(10) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    run_at timestamptz NOT NULL,
+    locked_until timestamptz NULL,
+    last_error TEXT NULL,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_jobs (status, run_at);
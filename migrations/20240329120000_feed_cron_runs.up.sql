@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS feed_cron_runs
+(
+    task VARCHAR(50) NOT NULL PRIMARY KEY,
+    run_at timestamptz NOT NULL
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS feed_webhook_attempts;
+
+ALTER TABLE feed_webhook_deliveries DROP COLUMN IF EXISTS event;
+ALTER TABLE feed_webhook_deliveries RENAME COLUMN subject_id TO feed_id;
@@ -0,0 +1,12 @@
+ALTER TABLE feed_webhook_deliveries RENAME COLUMN feed_id TO subject_id;
+ALTER TABLE feed_webhook_deliveries ADD COLUMN IF NOT EXISTS event VARCHAR(50) NOT NULL DEFAULT 'feed.created';
+
+CREATE TABLE IF NOT EXISTS feed_webhook_attempts
+(
+    delivery_id VARCHAR(53) NOT NULL,
+    attempt INT NOT NULL,
+    status_code INT NULL,
+    error TEXT NULL,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (delivery_id, attempt)
+);
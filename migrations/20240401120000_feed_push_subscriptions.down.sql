@@ -0,0 +1 @@
+DROP TABLE IF EXISTS feed_push_subscriptions;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS feed_push_subscriptions
+(
+    endpoint VARCHAR(2048) NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    p256dh VARCHAR(256) NOT NULL,
+    auth VARCHAR(256) NOT NULL,
+    lang VARCHAR(35) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON feed_push_subscriptions (user_id);
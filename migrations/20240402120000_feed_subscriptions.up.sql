@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS feed_subscriptions
+(
+    user_id UUID NOT NULL PRIMARY KEY,
+    customer_id VARCHAR(255) NOT NULL,
+    subscription_id VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    current_period_end timestamptz NULL,
+    updated_at timestamptz NOT NULL
+);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS analytics_daily_referrers;
+DROP TABLE IF EXISTS analytics_daily_pages;
+DROP TABLE IF EXISTS analytics_daily;
+DROP TABLE IF EXISTS analytics_salts;
+DROP TABLE IF EXISTS analytics_page_views;
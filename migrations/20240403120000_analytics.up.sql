@@ -0,0 +1,40 @@
+CREATE TABLE IF NOT EXISTS analytics_page_views
+(
+    id UUID NOT NULL PRIMARY KEY,
+    path VARCHAR(512) NOT NULL,
+    referrer VARCHAR(255) NULL,
+    visitor VARCHAR(32) NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX ON analytics_page_views (created_at);
+
+CREATE TABLE IF NOT EXISTS analytics_salts
+(
+    day DATE NOT NULL PRIMARY KEY,
+    salt VARCHAR(64) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily
+(
+    day DATE NOT NULL PRIMARY KEY,
+    views BIGINT NOT NULL,
+    visitors BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_pages
+(
+    day DATE NOT NULL,
+    path VARCHAR(512) NOT NULL,
+    views BIGINT NOT NULL,
+    visitors BIGINT NOT NULL,
+    PRIMARY KEY (day, path)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_referrers
+(
+    day DATE NOT NULL,
+    referrer VARCHAR(255) NOT NULL,
+    views BIGINT NOT NULL,
+    PRIMARY KEY (day, referrer)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS ev_replays
+(
+    rule VARCHAR(255) NOT NULL PRIMARY KEY,
+    until timestamptz NOT NULL,
+    created_at timestamptz NOT NULL
+);
@@ -0,0 +1,38 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use starter_test::TestApp;
+use tokio::runtime::Runtime;
+
+/// Pages rendered, from an empty database.
+const PATHS: [&str; 3] = ["/", "/trending", "/search?q=feed"];
+
+/// Renders through the router with the html minified or sent as rendered.
+fn render(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    for minify in ["true", "false"] {
+        let app = runtime.block_on(TestApp::builder().config("minify.enabled", minify).spawn());
+        let mut group = c.benchmark_group(if minify == "true" {
+            "render/minified"
+        } else {
+            "render/raw"
+        });
+
+        for path in PATHS {
+            group.bench_function(path, |b| {
+                b.to_async(&runtime)
+                    .iter(|| async { black_box(app.get(path).await.assert_status(200)) })
+            });
+        }
+
+        group.finish();
+    }
+}
+
+fn translations(c: &mut Criterion) {
+    c.bench_function("translations/load", |b| {
+        b.iter(|| black_box(starter_web::bench::load_translations()))
+    });
+}
+
+criterion_group!(benches, render, translations);
+criterion_main!(benches);
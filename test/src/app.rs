@@ -0,0 +1,401 @@
+use axum::{
+    body::{self, Body, Bytes},
+    extract::ConnectInfo,
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+use starter_web::{FakePikav, Reloader};
+use std::{net::SocketAddr, sync::OnceLock, thread, time::Duration};
+use tower::ServiceExt;
+use ulid::Ulid;
+
+/// `Config::base_url` of the app, prefixed to the paths requested.
+pub const BASE_URL: &str = "/starter";
+
+/// Key the tokens are signed with, its public half being served as the
+/// JWKS of the app. It signs nothing but the tokens of the tests.
+const KEY: &[u8] = include_bytes!("key.pem");
+const KEY_ID: &str = "starter-test";
+const JWKS: &str = include_str!("jwks.json");
+
+static JWKS_URL: OnceLock<String> = OnceLock::new();
+
+/// Url of the JWKS served to the app, from a thread of its own so that
+/// fetching it blocks no test runtime and it outlives them all.
+fn jwks_url() -> &'static str {
+    JWKS_URL.get_or_init(|| {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("jwks not bound");
+        let addr = listener.local_addr().expect("jwks without address");
+
+        thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("jwks runtime not built");
+
+            runtime.block_on(async move {
+                listener
+                    .set_nonblocking(true)
+                    .expect("jwks listener blocking");
+
+                let listener = tokio::net::TcpListener::from_std(listener).expect("jwks listener");
+
+                let router = Router::new().route(
+                    "/.well-known/jwks.json",
+                    get(|| async { ([(header::CONTENT_TYPE, "application/json")], JWKS) }),
+                );
+
+                axum::serve(listener, router).await.expect("jwks stopped");
+            });
+        });
+
+        format!("http://{addr}/.well-known/jwks.json")
+    })
+}
+
+/// Token of `user_id`, valid for an hour.
+pub fn token(user_id: &str) -> String {
+    token_with_permissions(user_id, &[])
+}
+
+/// `token` granting `permissions`, in the default `permissions` claim.
+pub fn token_with_permissions(user_id: &str, permissions: &[&str]) -> String {
+    sign(serde_json::json!({ "sub": user_id, "permissions": permissions }))
+}
+
+/// `token` of a user of `tenant`, in the default `tenant` claim.
+pub fn token_of_tenant(user_id: &str, tenant: &str) -> String {
+    sign(serde_json::json!({ "sub": user_id, "tenant": tenant }))
+}
+
+/// Token of `claims`, valid for an hour.
+fn sign(mut claims: serde_json::Value) -> String {
+    let now = Utc::now().timestamp();
+    claims["iat"] = now.into();
+    claims["exp"] = (now + 3600).into();
+
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(KEY_ID.to_owned());
+
+    jsonwebtoken::encode(
+        &header,
+        &claims,
+        &EncodingKey::from_rsa_pem(KEY).expect("invalid test key"),
+    )
+    .expect("token not signed")
+}
+
+/// Config of the app under test, see `TestApp::builder`.
+pub struct TestAppBuilder {
+    overrides: Vec<(String, String)>,
+}
+
+impl TestAppBuilder {
+    /// Sets the key of the config, like `("features.new_feed_ui", "true")`.
+    pub fn config(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+
+    pub async fn spawn(self) -> TestApp {
+        let schema = crate::db::schema().await;
+
+        // Each app consumes the events of its schema under a region of its
+        // own, so that they don't share a cursor.
+        let region = format!("test-{}", Ulid::new().to_string().to_lowercase());
+        let defaults = [
+            ("dsn", schema.url.as_str()),
+            ("jwks_url", jwks_url()),
+            ("base_url", BASE_URL),
+            ("region", region.as_str()),
+            ("evento_delay", "0"),
+            ("pikav.url", ""),
+        ];
+
+        let overrides = defaults
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .chain(self.overrides)
+            .collect::<Vec<_>>();
+
+        let (router, pikav, reloader) = starter_web::test_router(&pairs(&overrides))
+            .await
+            .expect("app not started");
+
+        TestApp {
+            router,
+            pikav: pikav.unwrap_or_default(),
+            db: schema.connect().await,
+            reloader,
+            overrides,
+            token: None,
+        }
+    }
+}
+
+fn pairs(overrides: &[(String, String)]) -> Vec<(&str, &str)> {
+    overrides
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect()
+}
+
+/// App answering requests without listening, signed out until `sign_in`.
+#[derive(Clone)]
+pub struct TestApp {
+    router: Router,
+    pikav: FakePikav,
+    db: PgPool,
+    reloader: Reloader,
+    /// Keys of the config the app was spawned with, `reload` keeping them.
+    overrides: Vec<(String, String)>,
+    token: Option<String>,
+}
+
+impl TestApp {
+    pub fn builder() -> TestAppBuilder {
+        TestAppBuilder { overrides: vec![] }
+    }
+
+    pub async fn spawn() -> Self {
+        Self::builder().spawn().await
+    }
+
+    /// The app as seen by `user_id`, a uuid which doesn't have to be a user
+    /// yet.
+    pub fn sign_in(&self, user_id: &str) -> Self {
+        Self {
+            token: Some(token(user_id)),
+            ..self.clone()
+        }
+    }
+
+    /// `sign_in` with a token granting `permissions`.
+    pub fn sign_in_with(&self, user_id: &str, permissions: &[&str]) -> Self {
+        Self {
+            token: Some(token_with_permissions(user_id, permissions)),
+            ..self.sign_in(user_id)
+        }
+    }
+
+    /// Reloads the config of the app with the keys of `overrides` on top of
+    /// the ones it was spawned with, returning the settings that changed.
+    #[track_caller]
+    pub fn reload(&self, overrides: &[(&str, &str)]) -> Vec<String> {
+        let overrides = pairs(&self.overrides)
+            .into_iter()
+            .chain(overrides.iter().copied())
+            .collect::<Vec<_>>();
+
+        self.reloader
+            .reload_with(&overrides)
+            .expect("config not reloaded")
+    }
+
+    /// Database of the app, the schema of the test, for the assertions on
+    /// the read models.
+    pub fn db(&self) -> &PgPool {
+        &self.db
+    }
+
+    /// Live updates published by the app, in place of pikav.
+    pub fn pikav(&self) -> &FakePikav {
+        &self.pikav
+    }
+
+    /// Data of the last live update `event` on `topic`, waiting for the
+    /// consumers to publish it for up to 5 seconds.
+    #[track_caller]
+    pub async fn published(&self, topic: &str, event: &str) -> String {
+        for _ in 0..50 {
+            if let Some(published) = self.pikav.find(topic, event) {
+                return published.data;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        panic!("{event} not published on {topic}");
+    }
+
+    /// Waits for the consumers to read the events appended so far, for up
+    /// to 5 seconds, so that the read models are up to date. The consumers
+    /// of the tenants, in schemas of their own, aren't waited for.
+    #[track_caller]
+    pub async fn wait_for_projection(&self) {
+        for _ in 0..50 {
+            if starter_web::events::caught_up(&self.db)
+                .await
+                .expect("consumers not read")
+            {
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        panic!("events still not read by the consumers");
+    }
+
+    pub async fn get(&self, path: &str) -> TestResponse {
+        self.send(Method::GET, path, None, Body::empty()).await
+    }
+
+    /// `get` as `user_id`, signed in for this request only.
+    pub async fn get_as(&self, user_id: &str, path: &str) -> TestResponse {
+        self.sign_in(user_id).get(path).await
+    }
+
+    /// Posts `form` url encoded, like the forms of the pages.
+    pub async fn post<T: Serialize + ?Sized>(&self, path: &str, form: &T) -> TestResponse {
+        let body = serde_urlencoded::to_string(form).expect("form not encoded");
+
+        self.send(
+            Method::POST,
+            path,
+            Some("application/x-www-form-urlencoded"),
+            Body::from(body),
+        )
+        .await
+    }
+
+    /// `post` as `user_id`, signed in for this request only.
+    pub async fn post_as<T: Serialize + ?Sized>(
+        &self,
+        user_id: &str,
+        path: &str,
+        form: &T,
+    ) -> TestResponse {
+        self.sign_in(user_id).post(path, form).await
+    }
+
+    pub async fn post_json<T: Serialize + ?Sized>(&self, path: &str, json: &T) -> TestResponse {
+        self.json(Method::POST, path, Some(json)).await
+    }
+
+    /// Sends `json`, if any, like the clients of the api.
+    pub async fn json<T: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        json: Option<&T>,
+    ) -> TestResponse {
+        match json {
+            Some(json) => {
+                let body = serde_json::to_vec(json).expect("json not encoded");
+
+                self.send(method, path, Some("application/json"), Body::from(body))
+                    .await
+            }
+            None => self.send(method, path, None, Body::empty()).await,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        content_type: Option<&str>,
+        body: Body,
+    ) -> TestResponse {
+        // Nested, the index of the app is at `BASE_URL` itself.
+        let uri = match path {
+            "/" => BASE_URL.to_owned(),
+            path => format!("{BASE_URL}{path}"),
+        };
+
+        let mut builder = Request::builder().method(method).uri(uri);
+
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+
+        let request = builder.body(body).expect("invalid request");
+
+        self.request(request).await
+    }
+
+    /// Sends `request` as it is, along with the token of the signed in user
+    /// and the address of a local client.
+    pub async fn request(&self, mut request: Request<Body>) -> TestResponse {
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+        if let Some(token) = &self.token {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                format!("Bearer {token}").parse().expect("invalid token"),
+            );
+        }
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("router failed");
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body not read");
+
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+/// Response read to the end.
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    #[track_caller]
+    pub fn assert_status(self, status: u16) -> Self {
+        assert_eq!(
+            self.status.as_u16(),
+            status,
+            "unexpected status, body: {}",
+            self.text()
+        );
+
+        self
+    }
+
+    #[track_caller]
+    pub fn assert_contains(self, text: &str) -> Self {
+        assert!(
+            self.text().contains(text),
+            "{text:?} not found in: {}",
+            self.text()
+        );
+
+        self
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    #[track_caller]
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).expect("body is not the json expected")
+    }
+}
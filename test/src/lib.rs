@@ -0,0 +1,29 @@
+//! End-to-end tests of the app, the requests going through the router of
+//! `serve` with the consumers running on a schema of their own.
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn bookmarks() {
+//!     let app = TestApp::spawn().await;
+//!
+//!     app.post_as("5f0c7b1e-...", "/bookmark", &[("feed_id", "...")])
+//!         .await
+//!         .assert_status(303);
+//!     app.wait_for_projection().await;
+//!
+//!     app.get_as("5f0c7b1e-...", "/bookmark").await.assert_status(200);
+//! }
+//! ```
+//!
+//! Without the default `app` feature, only the databases of `db` and the
+//! html snapshots of `snapshot` are provided, for the tests of the feature
+//! crates.
+
+pub mod db;
+pub mod snapshot;
+
+#[cfg(feature = "app")]
+mod app;
+
+#[cfg(feature = "app")]
+pub use app::*;
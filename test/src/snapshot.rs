@@ -0,0 +1,108 @@
+//! Html snapshots, stored in `tests/snapshots/` of the crate under test.
+//!
+//! ```ignore
+//! let page = app.get("/_components?lang=fr").await.text();
+//!
+//! for (name, html) in starter_test::snapshot::sections(&page, "data-component") {
+//!     starter_test::snapshot::assert_snapshot(&format!("fr/{name}"), &html);
+//! }
+//! ```
+//!
+//! Missing snapshots fail like the ones that changed, `UPDATE_SNAPSHOTS=1`,
+//! as run by `make snapshots`, writing them instead.
+
+use std::{env, fs, path::PathBuf};
+
+fn path(name: &str) -> PathBuf {
+    let dir = env::var("CARGO_MANIFEST_DIR").expect("snapshots need cargo test");
+
+    PathBuf::from(dir)
+        .join("tests/snapshots")
+        .join(format!("{name}.html"))
+}
+
+/// One tag per line, trimmed, so that snapshots neither depend on the
+/// minification of the responses nor on the indentation of the templates.
+/// The csrf tokens of the forms, minted on each request, are left out.
+pub fn normalize(html: &str) -> String {
+    html.replace('<', "\n<")
+        .replace('>', ">\n")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(
+            |line| match line.starts_with("<input") && line.contains("_csrf") {
+                true => "<input name=\"_csrf\">",
+                false => line,
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+/// Inner html of the elements bearing `attribute`, by its value. Elements
+/// are expected not to nest one another.
+pub fn sections(html: &str, attribute: &str) -> Vec<(String, String)> {
+    let marker = format!("{attribute}=\"");
+    let mut sections = vec![];
+
+    for part in html.split(&marker).skip(1) {
+        let Some((name, rest)) = part.split_once('"') else {
+            continue;
+        };
+
+        let Some((_, inner)) = rest.split_once('>') else {
+            continue;
+        };
+
+        let inner = inner
+            .rsplit_once("</section>")
+            .map_or(inner, |(inner, _)| inner);
+
+        sections.push((name.to_owned(), inner.to_owned()));
+    }
+
+    sections
+}
+
+/// Compares the normalized `html` with the snapshot `name`, which may be in
+/// a sub directory like `fr/avatar`.
+#[track_caller]
+pub fn assert_snapshot(name: &str, html: &str) {
+    let path = path(name);
+    let actual = normalize(html);
+    let update = env::var("UPDATE_SNAPSHOTS").is_ok_and(|value| value == "1");
+
+    if update {
+        fs::create_dir_all(path.parent().expect("snapshot without directory"))
+            .expect("snapshot directory not created");
+        fs::write(&path, &actual).expect("snapshot not written");
+
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "snapshot {name} is missing, run with UPDATE_SNAPSHOTS=1 to write it to {}",
+            path.display()
+        );
+    };
+
+    if actual == expected {
+        return;
+    }
+
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let line = (0..expected_lines.len().max(actual_lines.len()))
+        .find(|&line| expected_lines.get(line) != actual_lines.get(line))
+        .unwrap_or_default();
+    let expected_line = expected_lines.get(line).copied().unwrap_or_default();
+    let actual_line = actual_lines.get(line).copied().unwrap_or_default();
+
+    panic!(
+        "snapshot {name} differs at line {}, run with UPDATE_SNAPSHOTS=1 if expected\n- {expected_line}\n+ {actual_line}",
+        line + 1
+    );
+}
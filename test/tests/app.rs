@@ -0,0 +1,538 @@
+use axum::{body::Body, http::Request};
+use starter_test::{token, token_of_tenant, TestApp, BASE_URL};
+use ulid::Ulid;
+
+const USER_ID: &str = "5f0c7b1e-2d4a-4c38-9a57-6b1e0f3d2c11";
+
+#[tokio::test]
+async fn pages() {
+    let app = TestApp::spawn().await;
+
+    app.get("/").await.assert_status(200);
+    app.get("/healthz").await.assert_status(200);
+    app.get("/readyz").await.assert_status(200);
+    app.get("/drafts").await.assert_status(401);
+
+    app.sign_in(USER_ID).get("/drafts").await.assert_status(200);
+}
+
+#[tokio::test]
+async fn streamed_head() {
+    let app = TestApp::spawn().await;
+    let response = app.get("/feed/tag/rust").await.assert_status(200);
+    let head = response
+        .text()
+        .split("</head>")
+        .next()
+        .unwrap_or_default()
+        .to_owned();
+
+    assert!(head.contains("<title>#rust</title>"), "{head}");
+    assert!(
+        head.contains(r#"rel="canonical" href="http://127.0.0.1:3000/starter/feed/tag/rust""#),
+        "{head}"
+    );
+}
+
+#[tokio::test]
+async fn permissions() {
+    let app = TestApp::spawn().await;
+
+    app.get("/admin/moderation").await.assert_status(401);
+    app.sign_in(USER_ID)
+        .get("/admin/moderation")
+        .await
+        .assert_status(403);
+
+    let moderator = app.sign_in_with(USER_ID, &["moderate"]);
+    moderator.get("/admin/moderation").await.assert_status(200);
+    moderator.get("/admin/status").await.assert_status(404);
+}
+
+#[tokio::test]
+async fn admin_users() {
+    let app = TestApp::builder()
+        .config("admins[0]", USER_ID)
+        .spawn()
+        .await;
+
+    app.sign_in("0b6f2f04-3c5e-4a8e-9d1a-7f0e2b4c6d88")
+        .get("/admin/users")
+        .await
+        .assert_status(404);
+
+    let admin = app.sign_in(USER_ID);
+    admin.get("/admin/users").await.assert_status(200);
+    admin
+        .get("/admin/users?sort=name&dir=desc&q=nobody&page=2")
+        .await
+        .assert_status(200)
+        .assert_contains("/admin/users?sort=name");
+}
+
+#[tokio::test]
+async fn telemetry() {
+    let app = TestApp::spawn().await;
+
+    let request = Request::get(format!("{BASE_URL}/trending"))
+        .header("X-Request-Id", "telemetry-test")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.request(request).await.assert_status(200);
+    assert_eq!(response.header("x-request-id"), Some("telemetry-test"));
+
+    let response = app.get("/healthz").await.assert_status(200);
+    assert!(response.header("x-request-id").is_some(), "no request id");
+
+    app.get("/metrics").await.assert_status(404);
+    app.sign_in(USER_ID)
+        .get("/metrics")
+        .await
+        .assert_status(404);
+}
+
+#[tokio::test]
+async fn drain() {
+    let drain = |bearer: Option<&str>| {
+        let mut builder = Request::post(format!("{BASE_URL}/__drain"));
+
+        if let Some(bearer) = bearer {
+            builder = builder.header("Authorization", format!("Bearer {bearer}"));
+        }
+
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let app = TestApp::builder()
+        .config("drain.token", "drain-secret")
+        .spawn()
+        .await;
+
+    app.request(drain(None)).await.assert_status(404);
+    app.request(drain(Some("forged"))).await.assert_status(404);
+    app.sign_in(USER_ID)
+        .request(drain(None))
+        .await
+        .assert_status(404);
+    app.get("/healthz").await.assert_status(200);
+    app.get("/readyz").await.assert_status(200);
+
+    app.request(drain(Some("drain-secret")))
+        .await
+        .assert_status(202)
+        .assert_contains("stopping");
+    app.get("/healthz").await.assert_status(503);
+    app.get("/readyz").await.assert_status(503);
+
+    let app = TestApp::builder()
+        .config("admins[0]", USER_ID)
+        .spawn()
+        .await;
+
+    app.request(drain(None)).await.assert_status(404);
+    app.get("/healthz").await.assert_status(200);
+
+    app.sign_in(USER_ID)
+        .request(drain(None))
+        .await
+        .assert_status(202);
+    app.get("/healthz").await.assert_status(503);
+    app.get("/readyz").await.assert_status(503);
+}
+
+#[tokio::test]
+async fn feeds_pagination() {
+    let app = TestApp::spawn().await;
+
+    app.get(&format!("/?author={USER_ID}&first=1000"))
+        .await
+        .assert_status(200);
+    app.get(&format!("/_load-more?author={USER_ID}&first=10"))
+        .await
+        .assert_status(303);
+    app.get("/?author=nobody").await.assert_status(400);
+}
+
+#[tokio::test]
+async fn tenants() {
+    let app = TestApp::builder()
+        .config("tenancy.source", "header")
+        .config("tenancy.tenants[0]", "acme")
+        .config("tenancy.tenants[1]", "globex")
+        .config(
+            "tenancy.schema_prefix",
+            format!("t{}_", Ulid::new().to_string().to_lowercase()),
+        )
+        .config("tenancy.admins.acme[0]", USER_ID)
+        .spawn()
+        .await;
+
+    app.get("/trending").await.assert_status(404);
+    app.get("/healthz").await.assert_status(200);
+
+    for (tenant, status) in [("acme", 200), ("globex", 200), ("initech", 404)] {
+        let request = Request::get(format!("{BASE_URL}/trending"))
+            .header("x-tenant-id", tenant)
+            .body(Body::empty())
+            .unwrap();
+
+        app.request(request).await.assert_status(status);
+    }
+
+    // Signed in users reach their own tenant only, and are admins of the
+    // tenants listing them.
+    let signed_in = [
+        ("acme", Some("acme"), "/trending", 200),
+        ("acme", Some("acme"), "/admin/status", 200),
+        ("globex", Some("acme"), "/trending", 403),
+        ("acme", None, "/trending", 403),
+        ("globex", Some("globex"), "/trending", 200),
+        ("globex", Some("globex"), "/admin/status", 404),
+    ];
+
+    for (tenant, claimed, path, status) in signed_in {
+        let token = match claimed {
+            Some(claimed) => token_of_tenant(USER_ID, claimed),
+            None => token(USER_ID),
+        };
+        let request = Request::get(format!("{BASE_URL}{path}"))
+            .header("x-tenant-id", tenant)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+
+        app.request(request).await.assert_status(status);
+    }
+}
+
+#[tokio::test]
+async fn oidc_login() {
+    TestApp::spawn()
+        .await
+        .get("/auth/login")
+        .await
+        .assert_status(404);
+
+    let app = TestApp::builder()
+        .config("oidc.issuer", "http://127.0.0.1:9")
+        .config("oidc.session_secret", "0123456789abcdef0123456789abcdef")
+        .spawn()
+        .await;
+
+    app.get("/trending")
+        .await
+        .assert_status(200)
+        .assert_contains("/auth/login");
+    app.get("/auth/callback?code=code&state=state")
+        .await
+        .assert_status(400);
+}
+
+#[tokio::test]
+async fn live_updates() {
+    let app = TestApp::spawn().await.sign_in(USER_ID);
+
+    app.post("/_create-feed", &[("title", "Live updates")])
+        .await
+        .assert_status(200);
+
+    let html = app.published("index", "created").await;
+    assert!(html.contains("/_feed?id="), "unexpected skeleton: {html}");
+}
+
+#[tokio::test]
+async fn projections() {
+    let app = TestApp::spawn().await;
+
+    app.post_as(USER_ID, "/_create-feed", &[("title", "Projected feed")])
+        .await
+        .assert_status(200);
+    app.wait_for_projection().await;
+
+    app.get_as(USER_ID, &format!("/?author={USER_ID}"))
+        .await
+        .assert_status(200)
+        .assert_contains("Projected feed");
+}
+
+#[tokio::test]
+async fn chaos() {
+    let app = TestApp::builder()
+        .config("chaos.enabled", "true")
+        .config("chaos.error_rate", "1")
+        .config("chaos.db_delay_rate", "0")
+        .config("chaos.drop_publish_rate", "0")
+        .spawn()
+        .await;
+
+    app.get("/").await.assert_status(500);
+    app.get("/healthz").await.assert_status(200);
+    app.get("/readyz").await.assert_status(200);
+}
+
+#[tokio::test]
+async fn static_files() {
+    let app = TestApp::spawn().await;
+    let request = |if_none_match: Option<&str>| {
+        let mut builder = Request::get(format!("{BASE_URL}/static/main.css"))
+            .header("Accept-Encoding", "gzip, br;q=0");
+
+        if let Some(etag) = if_none_match {
+            builder = builder.header("If-None-Match", etag);
+        }
+
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = app.request(request(None)).await.assert_status(200);
+    assert_eq!(response.header("content-encoding"), Some("gzip"));
+    assert_eq!(
+        response.header("cache-control"),
+        Some("public, max-age=3600")
+    );
+
+    let etag = response.header("etag").expect("no etag").to_owned();
+    app.request(request(Some(&etag))).await.assert_status(304);
+}
+
+#[tokio::test]
+async fn language_cookie() {
+    let app = TestApp::spawn().await;
+
+    let response = app
+        .post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(303);
+    let cookie = response.header("set-cookie").expect("no cookie").to_owned();
+    assert!(cookie.starts_with("lang=fr"), "unexpected cookie: {cookie}");
+
+    let request = Request::get(format!("{BASE_URL}/trending?lang=en"))
+        .header("Cookie", "lang=fr")
+        .header("Accept-Language", "en")
+        .body(Body::empty())
+        .unwrap();
+
+    app.request(request)
+        .await
+        .assert_status(200)
+        .assert_contains("<html lang=\"fr\"");
+
+    app.post("/_language", &[("lang", "xx")])
+        .await
+        .assert_status(400);
+}
+
+#[tokio::test]
+async fn csrf() {
+    let app = TestApp::spawn().await;
+
+    let response = app.get("/").await.assert_status(200);
+    let token = response
+        .headers
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|cookie| cookie.to_str().ok())
+        .find_map(|cookie| cookie.strip_prefix("csrf="))
+        .and_then(|cookie| cookie.split(';').next())
+        .expect("no csrf cookie")
+        .to_owned();
+    assert!(
+        response.text().contains(&token),
+        "csrf token not in the page"
+    );
+
+    let request = |body: String, header: Option<&str>| {
+        let mut builder = Request::post(format!("{BASE_URL}/_language"))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Cookie", format!("csrf={token}"));
+
+        if let Some(header) = header {
+            builder = builder.header("X-CSRF-Token", header);
+        }
+
+        builder.body(Body::from(body)).unwrap()
+    };
+
+    app.request(request("lang=fr".to_owned(), None))
+        .await
+        .assert_status(403);
+    app.request(request("lang=fr&_csrf=forged".to_owned(), None))
+        .await
+        .assert_status(403);
+    app.request(request(format!("lang=fr&_csrf={token}"), None))
+        .await
+        .assert_status(303);
+    app.request(request("lang=fr".to_owned(), Some(&token)))
+        .await
+        .assert_status(303);
+
+    // Admins drain with their session too, so `/__drain` checks the token
+    // of the requests sending cookies.
+    let request = Request::post(format!("{BASE_URL}/__drain"))
+        .header("Cookie", format!("csrf={token}"))
+        .body(Body::empty())
+        .unwrap();
+    app.request(request).await.assert_status(403);
+
+    // The streamed pages carry the token to htmx in their head too.
+    let app = app.sign_in(USER_ID);
+    let response = app.get("/").await.assert_status(200);
+    let token = response
+        .text()
+        .split(r#"<meta name="csrf-token" content=""#)
+        .nth(1)
+        .and_then(|meta| meta.split('"').next())
+        .filter(|token| !token.is_empty())
+        .expect("no csrf token in the streamed head")
+        .to_owned();
+
+    let create_feed = |header: Option<&str>| {
+        let mut builder = Request::post(format!("{BASE_URL}/_create-feed"))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Cookie", format!("csrf={token}"))
+            .header("HX-Request", "true");
+
+        if let Some(header) = header {
+            builder = builder.header("X-CSRF-Token", header);
+        }
+
+        builder.body(Body::from("title=Streamed+form")).unwrap()
+    };
+
+    app.request(create_feed(None)).await.assert_status(403);
+    app.request(create_feed(Some(&token)))
+        .await
+        .assert_status(200);
+}
+
+#[tokio::test]
+async fn referer_redirects() {
+    let app = TestApp::spawn().await;
+    let redirects = [
+        (
+            "http://127.0.0.1:3000/starter/trending?tag=rust",
+            "/starter/trending?tag=rust",
+        ),
+        ("/starter/drafts", "/starter/drafts"),
+        ("https://evil.example/starter/trending", "/starter"),
+        ("http://127.0.0.1:3000/elsewhere", "/starter"),
+        ("//evil.example/starter", "/starter"),
+        ("/starter\\@evil.example", "/starter"),
+    ];
+
+    for (referer, location) in redirects {
+        let request = Request::post(format!("{BASE_URL}/_language"))
+            .header("content-type", "application/x-www-form-urlencoded")
+            .header("referer", referer)
+            .body(Body::from("lang=fr"))
+            .unwrap();
+
+        let response = app.request(request).await.assert_status(303);
+        assert_eq!(response.header("location"), Some(location), "{referer}");
+    }
+}
+
+#[tokio::test]
+async fn rate_limits() {
+    let app = TestApp::builder()
+        .config("rate_limits[0].group", "commands")
+        .config("rate_limits[0].paths[0]", "/_language")
+        .config("rate_limits[0].requests", "2")
+        .config("rate_limits[0].window", "3600")
+        .spawn()
+        .await;
+
+    for _ in 0..2 {
+        app.post("/_language", &[("lang", "fr")])
+            .await
+            .assert_status(303);
+    }
+
+    let response = app
+        .post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(429);
+    assert!(response.header("retry-after").is_some(), "no retry-after");
+
+    app.get("/").await.assert_status(200);
+    app.sign_in(USER_ID)
+        .post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(303);
+}
+
+#[tokio::test]
+async fn reload_rate_limits() {
+    let app = TestApp::builder()
+        .config("rate_limits[0].group", "commands")
+        .config("rate_limits[0].paths[0]", "/_language")
+        .config("rate_limits[0].requests", "1")
+        .config("rate_limits[0].window", "3600")
+        .spawn()
+        .await;
+
+    app.post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(303);
+    app.post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(429);
+
+    let changed = app.reload(&[("rate_limits[0].requests", "10")]);
+    assert_eq!(changed, ["rate_limits"]);
+
+    app.post("/_language", &[("lang", "fr")])
+        .await
+        .assert_status(303);
+}
+
+#[tokio::test]
+async fn trusted_proxies() {
+    let forwarded = |forwarded_for: &str| {
+        Request::post(format!("{BASE_URL}/_language"))
+            .header("content-type", "application/x-www-form-urlencoded")
+            .header("x-forwarded-for", forwarded_for)
+            .body(Body::from("lang=fr"))
+            .unwrap()
+    };
+
+    let untrusted = TestApp::builder()
+        .config("rate_limits[0].group", "commands")
+        .config("rate_limits[0].paths[0]", "/_language")
+        .config("rate_limits[0].requests", "1")
+        .config("rate_limits[0].window", "3600")
+        .spawn()
+        .await;
+
+    untrusted
+        .request(forwarded("203.0.113.1"))
+        .await
+        .assert_status(303);
+    untrusted
+        .request(forwarded("203.0.113.2"))
+        .await
+        .assert_status(429);
+
+    let trusted = TestApp::builder()
+        .config("rate_limits[0].group", "commands")
+        .config("rate_limits[0].paths[0]", "/_language")
+        .config("rate_limits[0].requests", "1")
+        .config("rate_limits[0].window", "3600")
+        .config("trusted_proxies[0]", "127.0.0.0/8")
+        .config("trusted_proxies[1]", "10.0.0.1")
+        .spawn()
+        .await;
+
+    trusted
+        .request(forwarded("203.0.113.1"))
+        .await
+        .assert_status(303);
+    trusted
+        .request(forwarded("203.0.113.2, 10.0.0.1"))
+        .await
+        .assert_status(303);
+    trusted
+        .request(forwarded("198.51.100.1, 203.0.113.2"))
+        .await
+        .assert_status(429);
+}
@@ -0,0 +1,95 @@
+use axum::{body::Body, http::Request};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use starter_test::{TestApp, BASE_URL};
+
+const SECRET: &str = "whsec_test";
+
+/// Event of a kind the webhook acknowledges without applying it, once its
+/// signature verified.
+const EVENT: &str =
+    r#"{"id":"evt_1","type":"invoice.paid","created":1700000000,"data":{"object":{}}}"#;
+
+/// `v1` signature of `body` at `timestamp`, as Stripe computes it.
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn webhook(signature: Option<&str>, body: &str) -> Request<Body> {
+    let mut builder = Request::post(format!("{BASE_URL}/billing/webhook"))
+        .header("Content-Type", "application/json");
+
+    if let Some(signature) = signature {
+        builder = builder.header("Stripe-Signature", signature);
+    }
+
+    builder.body(Body::from(body.to_owned())).unwrap()
+}
+
+#[tokio::test]
+async fn stripe_signatures() {
+    let app = TestApp::builder()
+        .config("billing.webhook_secret", SECRET)
+        .spawn()
+        .await;
+    let now = Utc::now().timestamp();
+    let valid = sign(SECRET, now, EVENT);
+
+    let signed = format!("t={now},v1={valid}");
+    app.request(webhook(Some(&signed), EVENT))
+        .await
+        .assert_status(200);
+
+    // Past `SIGNATURE_TOLERANCE`, either way, the event is taken for a
+    // replay.
+    for timestamp in [now - 301, now + 301] {
+        let signature = format!("t={timestamp},v1={}", sign(SECRET, timestamp, EVENT));
+        app.request(webhook(Some(&signature), EVENT))
+            .await
+            .assert_status(400);
+    }
+
+    let tampered = EVENT.replace("evt_1", "evt_2");
+    app.request(webhook(Some(&signed), &tampered))
+        .await
+        .assert_status(400);
+
+    // Any of the signatures sent while a secret is rolled is enough.
+    let rolled = sign("whsec_rolled", now, EVENT);
+    for signature in [
+        format!("t={now},v1={rolled},v1={valid}"),
+        format!("t={now}, v1={valid}, v1={rolled}"),
+    ] {
+        app.request(webhook(Some(&signature), EVENT))
+            .await
+            .assert_status(200);
+    }
+
+    let garbage = [
+        format!("t={now},v1={rolled}"),
+        format!("t={now},v0={valid}"),
+        format!("v1={valid}"),
+        format!("t=soon,v1={valid}"),
+        format!("t={now},v1={}", &valid[1..]),
+        "t=,v1=".to_owned(),
+        "garbage".to_owned(),
+        String::new(),
+    ];
+
+    for signature in garbage {
+        app.request(webhook(Some(&signature), EVENT))
+            .await
+            .assert_status(400);
+    }
+
+    app.request(webhook(None, EVENT)).await.assert_status(400);
+}
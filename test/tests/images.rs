@@ -0,0 +1,93 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use starter_test::TestApp;
+use std::{env, fs};
+use ulid::Ulid;
+
+const SECRET: &str = "image-secret";
+
+/// Transparent png of a single pixel.
+const PNG: &str = "89504e470d0a1a0a0000000d49484452000000010000000108060000001f15c4890000000d4944415478da636460f85f0f0002870180eb47ba920000000049454e44ae426082";
+
+/// `s` of `/img/:key`, the first 16 bytes of the HMAC of its parameters.
+fn sign(key: &str, width: u32, format: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+
+    mac.update(format!("{key}:{width}:{format}").as_bytes());
+
+    mac.finalize().into_bytes()[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn decode_hex(value: &str) -> Vec<u8> {
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn signed_urls() {
+    let dir = env::temp_dir().join(format!(
+        "starter-{}",
+        Ulid::new().to_string().to_lowercase()
+    ));
+    let key = format!("{}.png", Ulid::new().to_string().to_lowercase());
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(&key), decode_hex(PNG)).unwrap();
+
+    let app = TestApp::builder()
+        .config("upload.dir", dir.to_string_lossy())
+        .config("upload.image_secret", SECRET)
+        .spawn()
+        .await;
+
+    let signature = sign(&key, 1, "png");
+
+    let response = app
+        .get(&format!("/img/{key}?w=1&s={signature}"))
+        .await
+        .assert_status(200);
+    assert_eq!(response.header("content-type"), Some("image/png"));
+
+    let jpeg = sign(&key, 1, "jpeg");
+    app.get(&format!("/img/{key}?w=1&format=jpeg&s={jpeg}"))
+        .await
+        .assert_status(200);
+
+    let mut wrong = signature.to_owned();
+    wrong.replace_range(..1, if wrong.starts_with('0') { "1" } else { "0" });
+
+    let not_found = [
+        format!("/img/{key}?w=1&s={wrong}"),
+        format!("/img/{key}?w=1&s={}", &signature[1..]),
+        format!("/img/{key}?w=1&s={}", &signature[..30]),
+        format!("/img/{key}?w=1&s=zz{}", &signature[2..]),
+        // The signature ties the width and the format to the key.
+        format!("/img/{key}?w=2&s={signature}"),
+        format!("/img/{key}?w=1&format=jpeg&s={signature}"),
+        // Folders are out of reach, however signed.
+        format!(
+            "/img/exports%2F{key}?w=1&s={}",
+            sign(&format!("exports/{key}"), 1, "png")
+        ),
+    ];
+
+    for path in not_found {
+        app.get(&path).await.assert_status(404);
+    }
+
+    for width in [0, 2049] {
+        app.get(&format!(
+            "/img/{key}?w={width}&s={}",
+            sign(&key, width, "png")
+        ))
+        .await
+        .assert_status(400);
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,107 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use starter_web::localized::{self, parse_date, parse_decimal};
+
+#[derive(Deserialize)]
+struct Form {
+    #[serde(deserialize_with = "localized::decimal")]
+    price: f64,
+    #[serde(default, deserialize_with = "localized::option_decimal")]
+    discount: Option<f64>,
+    #[serde(deserialize_with = "localized::date")]
+    starts_on: NaiveDate,
+    #[serde(default, deserialize_with = "localized::option_date")]
+    ends_on: Option<NaiveDate>,
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn decimals() {
+    let valid = [
+        ("en", "1234.56", 1234.56),
+        ("en", "1,234.56", 1234.56),
+        ("en", "1 234.56", 1234.56),
+        ("en", "12,345,678", 12345678.0),
+        ("en", " -1,234.5 ", -1234.5),
+        ("en", "+0.5", 0.5),
+        ("en", ".5", 0.5),
+        ("fr", "1234,56", 1234.56),
+        ("fr", "1 234,56", 1234.56),
+        ("fr", "1\u{202f}234,56", 1234.56),
+        ("fr", "1.234,56", 1234.56),
+        ("fr", "-12", -12.0),
+    ];
+
+    for (lang, value, expected) in valid {
+        assert_eq!(parse_decimal(lang, value), Some(expected), "{lang} {value}");
+    }
+
+    let invalid = [
+        ("en", ""),
+        ("en", "-"),
+        ("en", "1,23"),
+        ("en", "1234,567"),
+        ("en", "1,,234"),
+        ("en", ",123"),
+        ("en", "1,234.5,6"),
+        ("en", "1.2.3"),
+        ("en", "1."),
+        ("en", "1 2"),
+        ("en", "1e5"),
+        ("en", "inf"),
+        ("en", "- 5"),
+        ("fr", "1,234.56"),
+        ("fr", "1 23,4"),
+        ("fr", "12,3 4"),
+    ];
+
+    for (lang, value) in invalid {
+        assert_eq!(parse_decimal(lang, value), None, "{lang} {value}");
+    }
+}
+
+#[test]
+fn dates() {
+    assert_eq!(parse_date("en", "03/14/2024"), Some(date(2024, 3, 14)));
+    assert_eq!(parse_date("fr", "14/03/2024"), Some(date(2024, 3, 14)));
+    assert_eq!(parse_date("fr", " 2024-03-14 "), Some(date(2024, 3, 14)));
+    assert_eq!(parse_date("en", "14/03/2024"), None);
+    assert_eq!(parse_date("fr", "03/14/2024"), None);
+    assert_eq!(parse_date("en", "2024-02-30"), None);
+}
+
+#[tokio::test]
+async fn deserializers() {
+    let form = localized::scope("fr".to_owned(), async {
+        serde_urlencoded::from_str::<Form>(
+            "price=1+234%2C5&discount=&starts_on=14%2F03%2F2024&ends_on=2024-03-20",
+        )
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(form.price, 1234.5);
+    assert_eq!(form.discount, None);
+    assert_eq!(form.starts_on, date(2024, 3, 14));
+    assert_eq!(form.ends_on, Some(date(2024, 3, 20)));
+
+    let form = localized::scope("en".to_owned(), async {
+        serde_urlencoded::from_str::<Form>("price=1%2C234.5&discount=0.25&starts_on=03%2F14%2F2024")
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(form.price, 1234.5);
+    assert_eq!(form.discount, Some(0.25));
+    assert_eq!(form.ends_on, None);
+
+    let invalid = localized::scope("en".to_owned(), async {
+        serde_urlencoded::from_str::<Form>("price=1%2C23&starts_on=03%2F14%2F2024")
+    })
+    .await;
+
+    assert!(invalid.is_err(), "1,23 taken as a decimal");
+}
@@ -0,0 +1,185 @@
+use axum::http::Method;
+use jsonschema::JSONSchema;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use starter_test::{TestApp, TestResponse};
+use std::{collections::HashMap, time::Duration};
+
+const USER_ID: &str = "5f0c7b1e-2d4a-4c38-9a57-6b1e0f3d2c11";
+
+/// Exchange of `contracts/api.json`, `{name}` placeholders of `path` being
+/// replaced by the values captured from the responses before.
+#[derive(Deserialize)]
+struct Exchange {
+    name: String,
+    #[serde(default)]
+    signed_in: bool,
+    method: String,
+    path: String,
+    body: Option<Value>,
+    status: u16,
+    /// Json pointers of the response body, by placeholder.
+    #[serde(default)]
+    capture: HashMap<String, String>,
+}
+
+/// Schema of the document as JSON Schema, OpenAPI 3.0 marking nullable
+/// values with `nullable` rather than a `null` type.
+fn to_json_schema(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut schema: Map<String, Value> = object
+                .iter()
+                .filter(|(key, _)| key.as_str() != "nullable")
+                .map(|(key, value)| (key.to_owned(), to_json_schema(value)))
+                .collect();
+
+            if object.get("nullable") != Some(&Value::Bool(true)) {
+                return Value::Object(schema);
+            }
+
+            match schema.get("type").cloned() {
+                Some(Value::String(kind)) => {
+                    schema.insert("type".to_owned(), json!([kind, "null"]));
+                    Value::Object(schema)
+                }
+                _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+            }
+        }
+        Value::Array(values) => Value::Array(values.iter().map(to_json_schema).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Operation of the document for the `path` requested, matching its
+/// `{param}` segments.
+fn operation<'a>(doc: &'a Value, method: &str, path: &str) -> Option<&'a Value> {
+    let path = path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').collect();
+
+    doc["paths"]
+        .as_object()?
+        .iter()
+        .find(|(template, _)| {
+            let template: Vec<&str> = template.split('/').collect();
+
+            template.len() == segments.len()
+                && template
+                    .iter()
+                    .zip(&segments)
+                    .all(|(template, segment)| template.starts_with('{') || template == segment)
+        })
+        .and_then(|(_, item)| item.get(method.to_lowercase()))
+}
+
+/// Fails with the errors of `instance` against `schema`, whose `$ref`s
+/// point to the components of `doc`.
+fn validate(doc: &Value, schema: &Value, instance: &Value, what: &str) {
+    let schema = json!({
+        "components": to_json_schema(&doc["components"]),
+        "allOf": [to_json_schema(schema)],
+    });
+    let schema = JSONSchema::compile(&schema).expect("invalid schema");
+
+    if let Err(errors) = schema.validate(instance) {
+        let errors: Vec<String> = errors
+            .map(|error| format!("{}: {error}", error.instance_path))
+            .collect();
+
+        panic!("{what} doesn't match the spec:\n{}", errors.join("\n"));
+    }
+}
+
+/// Response of `exchange`, retried while the projections catch up on the
+/// commands before it.
+async fn send(app: &TestApp, exchange: &Exchange, path: &str) -> TestResponse {
+    let method = Method::from_bytes(exchange.method.as_bytes()).expect("invalid method");
+
+    for _ in 0..50 {
+        let response = app.json(method.clone(), path, exchange.body.as_ref()).await;
+
+        if response.status.as_u16() == exchange.status || method != Method::GET {
+            return response;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    app.json(method, path, exchange.body.as_ref()).await
+}
+
+/// Replays `contracts/api.json` through `/api/v1`, failing when a request or
+/// a response drifts from the document served at `/api/v1/openapi.json`.
+#[tokio::test]
+async fn api_matches_openapi() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_in(USER_ID);
+    let doc: Value = app
+        .get("/api/v1/openapi.json")
+        .await
+        .assert_status(200)
+        .json();
+    let exchanges: Vec<Exchange> =
+        serde_json::from_str(include_str!("contracts/api.json")).expect("invalid contracts");
+    let mut captured = HashMap::new();
+
+    for exchange in exchanges {
+        let name = &exchange.name;
+        let path = captured.iter().fold(
+            exchange.path.to_owned(),
+            |path, (key, value): (&String, &String)| path.replace(&format!("{{{key}}}"), value),
+        );
+
+        let operation = operation(&doc, &exchange.method, &path)
+            .unwrap_or_else(|| panic!("{name}: {} {path} not in the spec", exchange.method));
+
+        if let Some(body) = &exchange.body {
+            let schema = &operation["requestBody"]["content"]["application/json"]["schema"];
+            validate(&doc, schema, body, &format!("{name}: request"));
+        }
+
+        let client = if exchange.signed_in { &user } else { &app };
+        let response = send(client, &exchange, &path)
+            .await
+            .assert_status(exchange.status);
+
+        let documented = &operation["responses"][exchange.status.to_string()];
+        assert!(
+            !documented.is_null(),
+            "{name}: status {} not in the spec",
+            exchange.status
+        );
+
+        match documented["content"].as_object() {
+            Some(content) => {
+                let content_type = response
+                    .header("content-type")
+                    .unwrap_or_default()
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned();
+                let schema = &content
+                    .get(&content_type)
+                    .unwrap_or_else(|| panic!("{name}: {content_type} not in the spec"))["schema"];
+
+                let body: Value = response.json();
+                validate(&doc, schema, &body, &format!("{name}: response"));
+
+                for (key, pointer) in &exchange.capture {
+                    let value = body
+                        .pointer(pointer)
+                        .and_then(Value::as_str)
+                        .unwrap_or_else(|| panic!("{name}: {pointer} not in the response"));
+
+                    captured.insert(key.to_owned(), value.to_owned());
+                }
+            }
+            None => assert!(
+                response.body.is_empty(),
+                "{name}: undocumented body {}",
+                response.text()
+            ),
+        }
+    }
+}
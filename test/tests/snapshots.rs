@@ -0,0 +1,25 @@
+use starter_test::{
+    snapshot::{assert_snapshot, sections},
+    TestApp,
+};
+
+/// Components of the gallery of debug builds, rendered in every language.
+#[tokio::test]
+async fn components() {
+    let app = TestApp::spawn().await;
+
+    for lang in ["en", "fr"] {
+        let page = app
+            .get(&format!("/_components?lang={lang}"))
+            .await
+            .assert_status(200)
+            .text();
+
+        let components = sections(&page, "data-component");
+        assert!(!components.is_empty(), "no components rendered");
+
+        for (name, html) in components {
+            assert_snapshot(&format!("{lang}/{name}"), &html);
+        }
+    }
+}
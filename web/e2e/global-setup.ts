@@ -0,0 +1,20 @@
+import { request, type FullConfig } from "@playwright/test";
+
+/**
+ * Empties the database then creates the users and feeds of `/__test/seed`,
+ * the app being served with the `test-api` feature, see `make dev.e2e`.
+ */
+export default async function globalSetup(config: FullConfig) {
+  const { baseURL } = config.projects[0].use;
+  const context = await request.newContext();
+
+  for (const path of ["/__test/reset", "/__test/seed"]) {
+    const response = await context.post(`${baseURL}${path}`);
+
+    if (!response.ok()) {
+      throw new Error(`${path} failed with ${response.status()}`);
+    }
+  }
+
+  await context.dispose();
+}
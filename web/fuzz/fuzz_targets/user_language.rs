@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The header and the query are split on the first newline.
+fuzz_target!(|data: &[u8]| {
+    let (accept_language, query) = match data.iter().position(|byte| *byte == b'\n') {
+        Some(index) => (&data[..index], &data[index + 1..]),
+        None => (data, &[][..]),
+    };
+
+    if let Ok(query) = std::str::from_utf8(query) {
+        starter_web::fuzz::user_language(accept_language, query);
+    }
+});
@@ -6,4 +6,344 @@ pages_error-InternalServerErrorPage_title = System Error
 pages_error-InternalServerErrorPage_content = Something went wrong! Please try again later.
 pages_error-InternalServerErrorPage_HomeLink_title = Return home
 
-pages_feed_index-IndexTemplate_HomeLink_title = Return home
\ No newline at end of file
+pages_error-BadRequestPage_title = Bad request
+pages_error-BadRequestPage_content = We couldn't understand your request. Please check it and try again.
+pages_error-BadRequestPage_HomeLink_title = Return home
+
+pages_error-MaintenancePage_title = Down for maintenance
+pages_error-MaintenancePage_content = We are upgrading the service and will be back shortly. Please try again in a few minutes.
+
+pages_error-UnauthorizedPage_title = Sign in required
+pages_error-UnauthorizedPage_content = You need to be signed in to access this page.
+pages_error-UnauthorizedPage_HomeLink_title = Return home
+pages_error-ForbiddenPage_title = Access denied
+pages_error-ForbiddenPage_content = You don't have the permission to access this page.
+pages_error-ForbiddenPage_HomeLink_title = Return home
+pages_error-TooManyRequestsPage_title = Too many requests
+pages_error-TooManyRequestsPage_content = You are going a bit fast. Please wait a moment and try again.
+
+layout-Base_skip_link = Skip to content
+layout-Footer_languages = Languages
+layout-Header_HomeLink_title = Timada Starter
+layout-Header_ThemeToggle_title = Toggle theme
+layout-Header_Language_title = Language
+layout-Header_signed_in = Signed in
+layout-Header_sign_in = Sign in
+layout-Header_sign_out = Sign out
+layout-UserLayout_FeedsLink_title = Feeds
+layout-AdminLayout_title = Administration
+layout-AdminLayout_print = Print report
+
+components_paginator-Paginator_previous = Previous
+components_paginator-Paginator_next = Next
+components_paginator-Paginator_page_size = Per page
+
+components_error_boundary-ErrorCard_title = Unavailable
+components_error_boundary-ErrorCard_content = This section could not be loaded. Please try again later.
+
+components_data_table-DataTable_search = Search
+components_data_table-DataTable_empty = No results
+components_data_table-DataTable_previous = Previous
+components_data_table-DataTable_next = Next
+components_data_table-DataTable_page = Page { $page } of { $pages }
+
+components_modal-Modal_close = Close
+
+components_upload_field-UploadField_label = Image
+pages_upload-UploadResult_label = Upload progress
+
+components_comment_section-CommentSection_title = Comments
+components_comment_section-CommentSection_submit = Send
+components_comment_section-CommentSection_reply = Reply
+components_comment_section-CommentSection_edit = Edit
+components_comment_section-CommentSection_delete = Delete
+components_comment_section-CommentSection_delete_confirm = Delete this comment?
+components_comment_section-CommentSection_deleted = This comment was deleted.
+components_comment_section-CommentSection_edited = edited
+components_comment_section-CommentSection_empty = No comments yet.
+
+pages_admin-DeadLettersPage_name = Event
+pages_admin-DeadLettersPage_aggregate_id = Aggregate
+pages_admin-DeadLettersPage_version = Version
+pages_admin-DeadLettersPage_created_at = Failed at
+pages_admin-UsersPage_id = Id
+pages_admin-UsersPage_name = Name
+pages_admin-UsersPage_role = Role
+pages_admin-UsersPage_status = Status
+pages_admin-UsersPage_created_at = Created at
+pages_admin-UsersPage_active = Active
+pages_admin-UsersPage_disabled = Disabled
+pages_admin-AnalyticsPage_description = Page views of the last 30 days, recorded without cookies nor third party scripts. Visitors are told apart for a day only, from a hash of their truncated address, and browsers asking not to be tracked are left out.
+pages_admin-AnalyticsPage_empty = No page views yet.
+pages_admin-AnalyticsPage_views = Views
+pages_admin-AnalyticsPage_visitors = Visitors
+pages_admin-AnalyticsPage_days = By day
+pages_admin-AnalyticsPage_day = Day
+pages_admin-AnalyticsPage_pages = Top pages
+pages_admin-AnalyticsPage_path = Page
+pages_admin-AnalyticsPage_referrers = Top referrers
+pages_admin-AnalyticsPage_referrer = Referrer
+pages_admin-AnalyticsPage_no_referrers = No referrers.
+
+pages_admin-FeaturesPage_description = Features of the config, overridden here until reset. A rollout turns an enabled feature on for that percent of the users only.
+pages_admin-FeaturesPage_empty = No features.
+pages_admin-FeaturesPage_name = Feature
+pages_admin-FeaturesPage_default = Default
+pages_admin-FeaturesPage_override = Override
+pages_admin-FeaturesPage_enabled = Enabled
+pages_admin-FeaturesPage_rollout = Rollout (%)
+pages_admin-FeaturesPage_everyone = everyone
+pages_admin-FeaturesPage_disabled = disabled
+pages_admin-FeaturesPage_save = Save
+pages_admin-FeaturesPage_reset = Reset
+pages_admin-FeaturesPage_saved = The feature was saved.
+pages_admin-FeaturesPage_reset_done = The feature is back to its default.
+pages_admin-MailboxPage_description = Emails kept by the mailbox transport, the last 100 sent since the server started.
+pages_admin-MailboxPage_empty = No emails yet.
+pages_admin-JobsPage_description = Jobs whose attempts ran out, retried with all of their attempts again.
+pages_admin-JobsPage_empty = No failed jobs.
+pages_admin-JobsPage_kind = Job
+pages_admin-JobsPage_attempts = Attempts
+pages_admin-JobsPage_error = Last error
+pages_admin-JobsPage_failed_at = Failed on
+pages_admin-JobsPage_retry = Retry
+pages_admin-JobsPage_retried = The job was queued again.
+pages_admin-ModerationPage_status_all = All
+pages_admin-ModerationPage_status_pending = Pending
+pages_admin-ModerationPage_status_hidden = Hidden
+pages_admin-ModerationPage_status_restored = Restored
+pages_admin-ModerationPage_empty = Nothing to moderate.
+pages_admin-ModerationPage_feed = Feed
+pages_admin-ModerationPage_reports = Reports
+pages_admin-ModerationPage_status = Status
+pages_admin-ModerationPage_updated_at = Updated at
+pages_admin-ModerationPage_reason = Reason
+pages_admin-ModerationPage_hide = Hide
+pages_admin-ModerationPage_restore = Restore
+pages_admin-ModerationPage_pagination = Moderation queue pages
+pages_admin-StatusPage_live = Live updates
+pages_admin-StatusPage_live_pikav = pikav
+pages_admin-StatusPage_live_in_process = In process, pikav was unreachable at start
+pages_admin-StatusPage_cache = Cache
+pages_admin-StatusPage_cache_memory = In memory
+pages_admin-StatusPage_mail = Mail transport
+pages_admin-StatusPage_storage = Storage
+pages_admin-StatusPage_region = Region
+pages_admin-StatusPage_maintenance = Maintenance
+pages_admin-StatusPage_maintenance_on = The maintenance page is served to everyone but the admins, /healthz reporting draining.
+pages_admin-StatusPage_maintenance_off = The app is open to everyone.
+pages_admin-StatusPage_maintenance_start = Start maintenance
+pages_admin-StatusPage_maintenance_stop = End maintenance
+pages_admin-StatusPage_maintenance_started = Maintenance started
+pages_admin-StatusPage_maintenance_ended = Maintenance ended
+pages_admin-WebhooksPage_description = Deliveries of every webhook with their attempts. Dead deliveries ran out of attempts or were sent to a removed webhook, requeueing gives those of existing webhooks all of their attempts again.
+pages_admin-WebhooksPage_status_all = All
+pages_admin-WebhooksPage_status_dead = Dead
+pages_admin-WebhooksPage_empty = No deliveries.
+pages_admin-WebhooksPage_event = Event
+pages_admin-WebhooksPage_url = Url
+pages_admin-WebhooksPage_status = Status
+pages_admin-WebhooksPage_attempts = Attempts
+pages_admin-WebhooksPage_delivered = Delivered
+pages_admin-WebhooksPage_pending = Next attempt
+pages_admin-WebhooksPage_dead = Dead
+pages_admin-WebhooksPage_requeue = Requeue
+pages_admin-WebhooksPage_requeued = The delivery was queued again.
+
+pages_index-NewFeedModal_title = New feed
+pages_index-IndexPage_following = Following
+pages_index-IndexPage_following_empty = Follow authors to see their feeds here.
+pages_index-IndexPage_scheduled = Scheduled
+pages_index-IndexPage_scheduled_empty = No scheduled feeds.
+pages_index-FeedItem_scheduled = Scheduled for
+pages_index-FeedItem_pinned = Pinned
+pages_index-FeedsList_loading = Loading more feeds…
+pages_index-FeedItem_attachment = Attached image
+pages_index-FeedItem_edited = edited
+pages_index-CreateFeedForm_content = Content (Markdown)
+pages_index-CreateFeedForm_visibility = Visibility
+pages_index-CreateFeedForm_visibility_public = Public
+pages_index-CreateFeedForm_visibility_unlisted = Unlisted
+pages_index-CreateFeedForm_visibility_private = Private
+pages_index-CreateFeedForm_publish_at = Publish later (optional)
+pages_index-CreateFeedForm_submit = Publish
+
+pages_feed-IndexPage_print = Print
+pages_feed-IndexPage_tag = Tag
+pages_feed-IndexPage_add_tag = Add tag
+pages_feed-IndexPage_remove_tag = Remove tag
+pages_feed-IndexPage_follow = Follow
+pages_feed-IndexPage_unfollow = Unfollow
+pages_feed-IndexPage_pin = Pin
+pages_feed-IndexPage_unpin = Unpin
+pages_feed-IndexPage_hidden = This feed was hidden by a moderator.
+pages_feed-IndexPage_report = Report this feed
+pages_feed-IndexPage_report_reason = Reason
+pages_feed-IndexPage_report_reason_spam = Spam
+pages_feed-IndexPage_report_reason_harassment = Harassment
+pages_feed-IndexPage_report_reason_inappropriate = Inappropriate content
+pages_feed-IndexPage_report_reason_other = Other
+pages_feed-IndexPage_report_submit = Report
+pages_feed-IndexPage_reported = Thanks, a moderator will review this feed.
+pages_feed-IndexPage_report_hidden = A feed you reported was hidden.
+pages_feed-IndexPage_report_restored = A feed you reported was reviewed and restored.
+pages_feed-EditPage_title = Title
+pages_feed-EditPage_submit = Save
+pages_feed-EditPage_cancel = Cancel
+pages_feed-HistoryPage_created = Created
+pages_feed-HistoryPage_edited = Edited
+
+pages_search-SearchPage_q = Search feeds
+pages_search-SearchPage_tag = Tag
+pages_search-SearchPage_author = Author
+pages_search-SearchPage_submit = Search
+pages_search-SearchPage_empty = No feeds match “{ $q }”.
+pages_search-SearchPage_pagination = Search results pages
+pages_search-SearchPage_previous = Previous
+pages_search-SearchPage_next = Next
+
+pages_atom-AtomFeed_title = Timada Starter feeds
+
+pages_embed-EmbedFeed_view = View on Timada Starter
+
+pages-routes_index = Home
+pages-routes_feed = Feed
+pages-routes_feed_edit = Edit
+pages-routes_feed_history = Edit history
+pages-routes_search = Search
+pages-routes_following = Following
+pages-routes_scheduled = Scheduled
+pages-routes_admin_analytics = Analytics
+pages-routes_admin_dead_letters = Dead letters
+pages-routes_admin_features = Features
+pages-routes_admin_jobs = Failed jobs
+pages-routes_admin_mailbox = Mailbox
+pages-routes_admin_moderation = Moderation
+pages-routes_admin_status = Status
+pages-routes_admin_users = Users
+pages-routes_admin_webhooks = Webhook deliveries
+pages-routes_drafts = Drafts
+pages-routes_drafts_edit = Edit draft
+pages-routes_user = Profile
+pages-routes_notifications = Notifications
+pages-routes_settings_billing = Billing
+pages-routes_settings_export = Export my data
+pages-routes_settings_feeds = My feeds
+pages-routes_settings_notifications = Notification settings
+pages-routes_trending = Trending
+pages-routes_settings_webhooks = Webhooks
+
+pages_drafts-DraftsPage_new = New draft
+pages_drafts-DraftsPage_empty = No drafts.
+pages_drafts-DraftsPage_untitled = Untitled draft
+pages_drafts-DraftStatus_saved = Saved at
+pages_drafts-DraftStatus_unsaved = Not saved yet
+pages_drafts-EditDraftPage_publish = Publish
+pages_drafts-EditDraftPage_back = Back to drafts
+pages_drafts-EditDraftPage_discard = Discard draft
+
+pages_user-UserPage_empty = No feeds yet.
+
+pages_notifications-NotificationsPage_empty = No notifications.
+pages_notifications-NotificationsPage_mention_feed = You were mentioned in
+pages_notifications-NotificationsPage_mention_comment = You were mentioned in a comment on
+pages_notifications-NotificationsPage_mention_toast = You were mentioned, see your notifications.
+
+pages_settings-ExportPage_description = Download an archive of your feeds, comments and reactions. It is prepared in the background, you will be notified once ready.
+pages_settings-ExportPage_format = Format
+pages_settings-ExportPage_request = Request an export
+pages_settings-ExportPage_requested = Your export is being prepared.
+pages_settings-ExportPage_ready_toast = Your export is ready to download.
+pages_settings-ExportPage_empty = No exports yet.
+pages_settings-ExportPage_pending = Being prepared…
+pages_settings-ExportPage_download = Download
+
+pages_settings-FeedsPage_archive = Archive
+pages_settings-FeedsPage_unarchive = Unarchive
+pages_settings-FeedsPage_delete = Delete
+pages_settings-FeedsPage_archived_badge = Archived
+pages_settings-FeedsPage_archived = { $count ->
+    [one] 1 feed archived.
+   *[other] { $count } feeds archived.
+} You can undo it for { $seconds } seconds.
+pages_settings-FeedsPage_trashed = { $count ->
+    [one] 1 feed will be deleted.
+   *[other] { $count } feeds will be deleted.
+} You can undo it for { $seconds } seconds.
+pages_settings-FeedsPage_undo = Undo
+pages_settings-FeedsPage_undone = Undone.
+pages_settings-FeedsPage_undo_expired = Too late, it can't be undone anymore.
+
+pages_settings-NotificationsPage_description = Choose how you want to be notified.
+pages_settings-NotificationsPage_kind = Notification
+pages_settings-NotificationsPage_kind_mention = Mentions
+pages_settings-NotificationsPage_kind_moderation = Moderation of feeds I reported
+pages_settings-NotificationsPage_kind_export = Export ready
+pages_settings-NotificationsPage_channel_in_app = In app
+pages_settings-NotificationsPage_channel_email = Email
+pages_settings-NotificationsPage_channel_push = Push
+pages_settings-NotificationsPage_save = Save
+pages_settings-NotificationsPage_saved = Notification settings saved.
+pages_settings-NotificationsPage_email = Email address
+pages_settings-NotificationsPage_email_description = Notifications are emailed once the address is verified.
+pages_settings-NotificationsPage_email_address = Email
+pages_settings-NotificationsPage_email_save = Send verification link
+pages_settings-NotificationsPage_email_sent = A verification link was emailed to this address.
+pages_settings-NotificationsPage_email_verified = Your email address is verified.
+pages_settings-NotificationsPage_email_invalid_link = This verification link is invalid or outdated.
+pages_settings-NotificationsPage_email_verified_badge = Verified
+pages_settings-NotificationsPage_email_unverified_badge = Not verified
+pages_settings-NotificationsPage_push = Browser notifications
+pages_settings-NotificationsPage_push_description = Notifications are pushed to the browsers you enable them on, even when the site is closed.
+pages_settings-NotificationsPage_push_enable = Enable on this browser
+pages_settings-NotificationsPage_push_disable = Disable on this browser
+pages_settings-NotificationsPage_push_unsupported = This browser does not support push notifications.
+
+pages_trending-TrendingPage_empty = Nothing trending yet.
+
+components_trending_feeds-TrendingFeeds_title = Trending
+components_trending_feeds-TrendingFeeds_more = See all
+
+pages_settings-WebhooksPage_description = Your new public feeds are posted as JSON to these urls, signed with their secret in the X-Starter-Signature header. Failed deliveries are retried a few times.
+pages_settings-WebhooksPage_url = Url
+pages_settings-WebhooksPage_register = Add webhook
+pages_settings-WebhooksPage_registered = Webhook added.
+pages_settings-WebhooksPage_removed = Webhook removed.
+pages_settings-WebhooksPage_empty = No webhooks yet.
+pages_settings-WebhooksPage_secret = Secret
+pages_settings-WebhooksPage_remove = Remove
+pages_settings-WebhooksPage_deliveries = Deliveries
+pages_settings-WebhooksPage_deliveries_empty = No deliveries yet.
+pages_settings-WebhooksPage_created_at = Date
+pages_settings-WebhooksPage_status = Status
+pages_settings-WebhooksPage_attempts = Attempts
+pages_settings-WebhooksPage_delivered = Delivered
+pages_settings-WebhooksPage_pending = Next attempt
+pages_settings-WebhooksPage_failed = Failed
+
+pages_settings-BillingPage_description = Your subscription to the paid plan, billed by Stripe. Changes made on Stripe show here once Stripe tells us about them.
+pages_settings-BillingPage_none = You are not subscribed.
+pages_settings-BillingPage_status = Status
+pages_settings-BillingPage_period_end = Current period ends
+pages_settings-BillingPage_subscribe = Subscribe
+pages_settings-BillingPage_required = A subscription is required for this page.
+pages_settings-BillingPage_status_incomplete = Incomplete
+pages_settings-BillingPage_status_incomplete_expired = Expired
+pages_settings-BillingPage_status_trialing = Trial
+pages_settings-BillingPage_status_active = Active
+pages_settings-BillingPage_status_past_due = Past due
+pages_settings-BillingPage_status_canceled = Canceled
+pages_settings-BillingPage_status_unpaid = Unpaid
+pages_settings-BillingPage_status_paused = Paused
+
+emails_layout_footer = You receive this email from Starter. Choose which ones you get in your
+emails_mention_subject = You were mentioned
+emails_mention_body = Someone mentioned you in a feed or a comment.
+emails_mention_action = See your notifications
+emails_verification_subject = Verify your email address
+emails_verification_body = Confirm that notifications of Starter can be emailed to this address.
+emails_verification_action = Verify my address
+emails_verification_ignore = If you didn't ask for it, ignore this email.
+emails_export_ready_subject = Your export is ready
+emails_export_ready_body = The archive of your feeds, comments and reactions is ready to download.
+emails_export_ready_action = Download my export
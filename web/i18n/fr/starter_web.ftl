@@ -6,4 +6,344 @@ pages_error-InternalServerErrorPage_title = Erreur du système
 pages_error-InternalServerErrorPage_content = Quelque chose n'a pas fonctionné ! Veuillez réessayer plus tard.
 pages_error-InternalServerErrorPage_HomeLink_title = Retourner à la page d'accueil
 
-pages_feed_index-IndexTemplate_HomeLink_title = Retourner à la page d'accueil
+pages_error-BadRequestPage_title = Requête invalide
+pages_error-BadRequestPage_content = Nous n'avons pas pu comprendre votre requête. Veuillez la vérifier et réessayer.
+pages_error-BadRequestPage_HomeLink_title = Retourner à la page d'accueil
+
+pages_error-MaintenancePage_title = En maintenance
+pages_error-MaintenancePage_content = Nous mettons le service à jour et serons de retour très vite. Veuillez réessayer dans quelques minutes.
+
+pages_error-UnauthorizedPage_title = Connexion requise
+pages_error-UnauthorizedPage_content = Vous devez être connecté pour accéder à cette page.
+pages_error-UnauthorizedPage_HomeLink_title = Retourner à la page d'accueil
+pages_error-ForbiddenPage_title = Accès refusé
+pages_error-ForbiddenPage_content = Vous n'avez pas la permission d'accéder à cette page.
+pages_error-ForbiddenPage_HomeLink_title = Retourner à la page d'accueil
+pages_error-TooManyRequestsPage_title = Trop de requêtes
+pages_error-TooManyRequestsPage_content = Vous allez un peu vite. Veuillez patienter un instant et réessayer.
+
+layout-Base_skip_link = Aller au contenu
+layout-Footer_languages = Langues
+layout-Header_HomeLink_title = Timada Starter
+layout-Header_ThemeToggle_title = Changer de thème
+layout-Header_Language_title = Langue
+layout-Header_signed_in = Connecté
+layout-Header_sign_in = Se connecter
+layout-Header_sign_out = Se déconnecter
+layout-UserLayout_FeedsLink_title = Fils d'actualité
+layout-AdminLayout_title = Administration
+layout-AdminLayout_print = Imprimer le rapport
+
+components_paginator-Paginator_previous = Précédent
+components_paginator-Paginator_next = Suivant
+components_paginator-Paginator_page_size = Par page
+
+components_error_boundary-ErrorCard_title = Indisponible
+components_error_boundary-ErrorCard_content = Cette section n'a pas pu être chargée. Veuillez réessayer plus tard.
+
+components_data_table-DataTable_search = Rechercher
+components_data_table-DataTable_empty = Aucun résultat
+components_data_table-DataTable_previous = Précédent
+components_data_table-DataTable_next = Suivant
+components_data_table-DataTable_page = Page { $page } sur { $pages }
+
+components_modal-Modal_close = Fermer
+
+components_upload_field-UploadField_label = Image
+pages_upload-UploadResult_label = Progression du téléversement
+
+components_comment_section-CommentSection_title = Commentaires
+components_comment_section-CommentSection_submit = Envoyer
+components_comment_section-CommentSection_reply = Répondre
+components_comment_section-CommentSection_edit = Modifier
+components_comment_section-CommentSection_delete = Supprimer
+components_comment_section-CommentSection_delete_confirm = Supprimer ce commentaire ?
+components_comment_section-CommentSection_deleted = Ce commentaire a été supprimé.
+components_comment_section-CommentSection_edited = modifié
+components_comment_section-CommentSection_empty = Aucun commentaire pour le moment.
+
+pages_admin-DeadLettersPage_name = Événement
+pages_admin-DeadLettersPage_aggregate_id = Agrégat
+pages_admin-DeadLettersPage_version = Version
+pages_admin-DeadLettersPage_created_at = Échoué le
+pages_admin-UsersPage_id = Identifiant
+pages_admin-UsersPage_name = Nom
+pages_admin-UsersPage_role = Rôle
+pages_admin-UsersPage_status = Statut
+pages_admin-UsersPage_created_at = Créé le
+pages_admin-UsersPage_active = Actif
+pages_admin-UsersPage_disabled = Désactivé
+pages_admin-AnalyticsPage_description = Pages vues des 30 derniers jours, enregistrées sans cookies ni scripts tiers. Les visiteurs ne sont distingués que sur une journée, d'après une empreinte de leur adresse tronquée, et les navigateurs demandant à ne pas être suivis sont ignorés.
+pages_admin-AnalyticsPage_empty = Aucune page vue pour l'instant.
+pages_admin-AnalyticsPage_views = Vues
+pages_admin-AnalyticsPage_visitors = Visiteurs
+pages_admin-AnalyticsPage_days = Par jour
+pages_admin-AnalyticsPage_day = Jour
+pages_admin-AnalyticsPage_pages = Pages les plus vues
+pages_admin-AnalyticsPage_path = Page
+pages_admin-AnalyticsPage_referrers = Principaux référents
+pages_admin-AnalyticsPage_referrer = Référent
+pages_admin-AnalyticsPage_no_referrers = Aucun référent.
+
+pages_admin-FeaturesPage_description = Fonctionnalités de la configuration, remplacées ici jusqu'à leur réinitialisation. Un déploiement n'active une fonctionnalité que pour ce pourcentage des utilisateurs.
+pages_admin-FeaturesPage_empty = Aucune fonctionnalité.
+pages_admin-FeaturesPage_name = Fonctionnalité
+pages_admin-FeaturesPage_default = Par défaut
+pages_admin-FeaturesPage_override = Remplacement
+pages_admin-FeaturesPage_enabled = Activée
+pages_admin-FeaturesPage_rollout = Déploiement (%)
+pages_admin-FeaturesPage_everyone = tout le monde
+pages_admin-FeaturesPage_disabled = désactivée
+pages_admin-FeaturesPage_save = Enregistrer
+pages_admin-FeaturesPage_reset = Réinitialiser
+pages_admin-FeaturesPage_saved = La fonctionnalité a été enregistrée.
+pages_admin-FeaturesPage_reset_done = La fonctionnalité est revenue à sa valeur par défaut.
+pages_admin-MailboxPage_description = E-mails gardés par le transport mailbox, les 100 derniers envoyés depuis le démarrage du serveur.
+pages_admin-MailboxPage_empty = Aucun e-mail pour le moment.
+pages_admin-JobsPage_description = Tâches dont les tentatives sont épuisées, relancées avec toutes leurs tentatives.
+pages_admin-JobsPage_empty = Aucune tâche en échec.
+pages_admin-JobsPage_kind = Tâche
+pages_admin-JobsPage_attempts = Tentatives
+pages_admin-JobsPage_error = Dernière erreur
+pages_admin-JobsPage_failed_at = Échouée le
+pages_admin-JobsPage_retry = Relancer
+pages_admin-JobsPage_retried = La tâche a été remise en file.
+pages_admin-ModerationPage_status_all = Tous
+pages_admin-ModerationPage_status_pending = En attente
+pages_admin-ModerationPage_status_hidden = Masqués
+pages_admin-ModerationPage_status_restored = Rétablis
+pages_admin-ModerationPage_empty = Rien à modérer.
+pages_admin-ModerationPage_feed = Fil
+pages_admin-ModerationPage_reports = Signalements
+pages_admin-ModerationPage_status = Statut
+pages_admin-ModerationPage_updated_at = Mis à jour le
+pages_admin-ModerationPage_reason = Motif
+pages_admin-ModerationPage_hide = Masquer
+pages_admin-ModerationPage_restore = Rétablir
+pages_admin-ModerationPage_pagination = Pages de la file de modération
+pages_admin-StatusPage_live = Mises à jour en direct
+pages_admin-StatusPage_live_pikav = pikav
+pages_admin-StatusPage_live_in_process = Dans le processus, pikav était injoignable au démarrage
+pages_admin-StatusPage_cache = Cache
+pages_admin-StatusPage_cache_memory = En mémoire
+pages_admin-StatusPage_mail = Transport des e-mails
+pages_admin-StatusPage_storage = Stockage
+pages_admin-StatusPage_region = Région
+pages_admin-StatusPage_maintenance = Maintenance
+pages_admin-StatusPage_maintenance_on = La page de maintenance est servie à tous sauf aux administrateurs, /healthz indiquant draining.
+pages_admin-StatusPage_maintenance_off = L'application est ouverte à tous.
+pages_admin-StatusPage_maintenance_start = Démarrer la maintenance
+pages_admin-StatusPage_maintenance_stop = Terminer la maintenance
+pages_admin-StatusPage_maintenance_started = Maintenance démarrée
+pages_admin-StatusPage_maintenance_ended = Maintenance terminée
+pages_admin-WebhooksPage_description = Livraisons de tous les webhooks avec leurs tentatives. Les livraisons mortes ont épuisé leurs tentatives ou visaient un webhook supprimé, remettre en file celles des webhooks existants leur redonne toutes leurs tentatives.
+pages_admin-WebhooksPage_status_all = Toutes
+pages_admin-WebhooksPage_status_dead = Mortes
+pages_admin-WebhooksPage_empty = Aucune livraison.
+pages_admin-WebhooksPage_event = Événement
+pages_admin-WebhooksPage_url = Url
+pages_admin-WebhooksPage_status = Statut
+pages_admin-WebhooksPage_attempts = Tentatives
+pages_admin-WebhooksPage_delivered = Livrée
+pages_admin-WebhooksPage_pending = Prochaine tentative
+pages_admin-WebhooksPage_dead = Morte
+pages_admin-WebhooksPage_requeue = Remettre en file
+pages_admin-WebhooksPage_requeued = La livraison a été remise en file.
+
+pages_index-NewFeedModal_title = Nouveau fil
+pages_index-IndexPage_following = Abonnements
+pages_index-IndexPage_following_empty = Suivez des auteurs pour voir leurs fils ici.
+pages_index-IndexPage_scheduled = Programmés
+pages_index-IndexPage_scheduled_empty = Aucun fil programmé.
+pages_index-FeedItem_scheduled = Programmé pour le
+pages_index-FeedItem_pinned = Épinglé
+pages_index-FeedsList_loading = Chargement des fils…
+pages_index-FeedItem_attachment = Image jointe
+pages_index-FeedItem_edited = modifié
+pages_index-CreateFeedForm_content = Contenu (Markdown)
+pages_index-CreateFeedForm_visibility = Visibilité
+pages_index-CreateFeedForm_visibility_public = Public
+pages_index-CreateFeedForm_visibility_unlisted = Non répertorié
+pages_index-CreateFeedForm_visibility_private = Privé
+pages_index-CreateFeedForm_publish_at = Publier plus tard (facultatif)
+pages_index-CreateFeedForm_submit = Publier
+
+pages_feed-IndexPage_print = Imprimer
+pages_feed-IndexPage_tag = Tag
+pages_feed-IndexPage_add_tag = Ajouter le tag
+pages_feed-IndexPage_remove_tag = Retirer le tag
+pages_feed-IndexPage_follow = Suivre
+pages_feed-IndexPage_unfollow = Ne plus suivre
+pages_feed-IndexPage_pin = Épingler
+pages_feed-IndexPage_unpin = Désépingler
+pages_feed-IndexPage_hidden = Ce fil a été masqué par un modérateur.
+pages_feed-IndexPage_report = Signaler ce fil
+pages_feed-IndexPage_report_reason = Motif
+pages_feed-IndexPage_report_reason_spam = Spam
+pages_feed-IndexPage_report_reason_harassment = Harcèlement
+pages_feed-IndexPage_report_reason_inappropriate = Contenu inapproprié
+pages_feed-IndexPage_report_reason_other = Autre
+pages_feed-IndexPage_report_submit = Signaler
+pages_feed-IndexPage_reported = Merci, un modérateur va examiner ce fil.
+pages_feed-IndexPage_report_hidden = Un fil que vous avez signalé a été masqué.
+pages_feed-IndexPage_report_restored = Un fil que vous avez signalé a été examiné et rétabli.
+pages_feed-EditPage_title = Titre
+pages_feed-EditPage_submit = Enregistrer
+pages_feed-EditPage_cancel = Annuler
+pages_feed-HistoryPage_created = Créé
+pages_feed-HistoryPage_edited = Modifié
+
+pages_search-SearchPage_q = Rechercher des fils
+pages_search-SearchPage_tag = Tag
+pages_search-SearchPage_author = Auteur
+pages_search-SearchPage_submit = Rechercher
+pages_search-SearchPage_empty = Aucun fil ne correspond à « { $q } ».
+pages_search-SearchPage_pagination = Pages des résultats de recherche
+pages_search-SearchPage_previous = Précédent
+pages_search-SearchPage_next = Suivant
+
+pages_atom-AtomFeed_title = Fils d'actualité Timada Starter
+
+pages_embed-EmbedFeed_view = Voir sur Timada Starter
+
+pages-routes_index = Accueil
+pages-routes_feed = Fil d'actualité
+pages-routes_feed_edit = Modifier
+pages-routes_feed_history = Historique des modifications
+pages-routes_search = Recherche
+pages-routes_following = Abonnements
+pages-routes_scheduled = Programmés
+pages-routes_admin_analytics = Statistiques
+pages-routes_admin_dead_letters = Lettres mortes
+pages-routes_admin_features = Fonctionnalités
+pages-routes_admin_jobs = Tâches en échec
+pages-routes_admin_mailbox = Boîte mail
+pages-routes_admin_moderation = Modération
+pages-routes_admin_status = Statut
+pages-routes_admin_users = Utilisateurs
+pages-routes_admin_webhooks = Livraisons des webhooks
+pages-routes_drafts = Brouillons
+pages-routes_drafts_edit = Modifier le brouillon
+pages-routes_user = Profil
+pages-routes_notifications = Notifications
+pages-routes_settings_billing = Facturation
+pages-routes_settings_export = Exporter mes données
+pages-routes_settings_feeds = Mes fils
+pages-routes_settings_notifications = Paramètres de notification
+pages-routes_trending = Tendances
+pages-routes_settings_webhooks = Webhooks
+
+pages_drafts-DraftsPage_new = Nouveau brouillon
+pages_drafts-DraftsPage_empty = Aucun brouillon.
+pages_drafts-DraftsPage_untitled = Brouillon sans titre
+pages_drafts-DraftStatus_saved = Enregistré à
+pages_drafts-DraftStatus_unsaved = Pas encore enregistré
+pages_drafts-EditDraftPage_publish = Publier
+pages_drafts-EditDraftPage_back = Retour aux brouillons
+pages_drafts-EditDraftPage_discard = Supprimer le brouillon
+
+pages_user-UserPage_empty = Aucun fil pour le moment.
+
+pages_notifications-NotificationsPage_empty = Aucune notification.
+pages_notifications-NotificationsPage_mention_feed = Vous avez été mentionné dans
+pages_notifications-NotificationsPage_mention_comment = Vous avez été mentionné dans un commentaire sur
+pages_notifications-NotificationsPage_mention_toast = Vous avez été mentionné, consultez vos notifications.
+
+pages_settings-ExportPage_description = Téléchargez une archive de vos fils, commentaires et réactions. Elle est préparée en arrière-plan, vous serez notifié une fois prête.
+pages_settings-ExportPage_format = Format
+pages_settings-ExportPage_request = Demander un export
+pages_settings-ExportPage_requested = Votre export est en préparation.
+pages_settings-ExportPage_ready_toast = Votre export est prêt à être téléchargé.
+pages_settings-ExportPage_empty = Aucun export pour le moment.
+pages_settings-ExportPage_pending = En préparation…
+pages_settings-ExportPage_download = Télécharger
+
+pages_settings-FeedsPage_archive = Archiver
+pages_settings-FeedsPage_unarchive = Désarchiver
+pages_settings-FeedsPage_delete = Supprimer
+pages_settings-FeedsPage_archived_badge = Archivé
+pages_settings-FeedsPage_archived = { $count ->
+    [one] 1 fil archivé.
+   *[other] { $count } fils archivés.
+} Vous pouvez annuler pendant { $seconds } secondes.
+pages_settings-FeedsPage_trashed = { $count ->
+    [one] 1 fil va être supprimé.
+   *[other] { $count } fils vont être supprimés.
+} Vous pouvez annuler pendant { $seconds } secondes.
+pages_settings-FeedsPage_undo = Annuler
+pages_settings-FeedsPage_undone = Annulé.
+pages_settings-FeedsPage_undo_expired = Trop tard, ce n'est plus annulable.
+
+pages_settings-NotificationsPage_description = Choisissez comment vous voulez être notifié.
+pages_settings-NotificationsPage_kind = Notification
+pages_settings-NotificationsPage_kind_mention = Mentions
+pages_settings-NotificationsPage_kind_moderation = Modération des fils que j'ai signalés
+pages_settings-NotificationsPage_kind_export = Export prêt
+pages_settings-NotificationsPage_channel_in_app = Dans l'application
+pages_settings-NotificationsPage_channel_email = E-mail
+pages_settings-NotificationsPage_channel_push = Push
+pages_settings-NotificationsPage_save = Enregistrer
+pages_settings-NotificationsPage_saved = Paramètres de notification enregistrés.
+pages_settings-NotificationsPage_email = Adresse e-mail
+pages_settings-NotificationsPage_email_description = Les notifications sont envoyées par e-mail une fois l'adresse vérifiée.
+pages_settings-NotificationsPage_email_address = E-mail
+pages_settings-NotificationsPage_email_save = Envoyer le lien de vérification
+pages_settings-NotificationsPage_email_sent = Un lien de vérification a été envoyé à cette adresse.
+pages_settings-NotificationsPage_email_verified = Votre adresse e-mail est vérifiée.
+pages_settings-NotificationsPage_email_invalid_link = Ce lien de vérification est invalide ou périmé.
+pages_settings-NotificationsPage_email_verified_badge = Vérifiée
+pages_settings-NotificationsPage_email_unverified_badge = Non vérifiée
+pages_settings-NotificationsPage_push = Notifications du navigateur
+pages_settings-NotificationsPage_push_description = Les notifications sont envoyées aux navigateurs sur lesquels vous les activez, même lorsque le site est fermé.
+pages_settings-NotificationsPage_push_enable = Activer sur ce navigateur
+pages_settings-NotificationsPage_push_disable = Désactiver sur ce navigateur
+pages_settings-NotificationsPage_push_unsupported = Ce navigateur ne prend pas en charge les notifications push.
+
+pages_trending-TrendingPage_empty = Aucune tendance pour le moment.
+
+components_trending_feeds-TrendingFeeds_title = Tendances
+components_trending_feeds-TrendingFeeds_more = Tout voir
+
+pages_settings-WebhooksPage_description = Vos nouveaux fils publics sont envoyés en JSON à ces urls, signés avec leur secret dans l'en-tête X-Starter-Signature. Les envois échoués sont réessayés quelques fois.
+pages_settings-WebhooksPage_url = Url
+pages_settings-WebhooksPage_register = Ajouter un webhook
+pages_settings-WebhooksPage_registered = Webhook ajouté.
+pages_settings-WebhooksPage_removed = Webhook supprimé.
+pages_settings-WebhooksPage_empty = Aucun webhook.
+pages_settings-WebhooksPage_secret = Secret
+pages_settings-WebhooksPage_remove = Supprimer
+pages_settings-WebhooksPage_deliveries = Envois
+pages_settings-WebhooksPage_deliveries_empty = Aucun envoi.
+pages_settings-WebhooksPage_created_at = Date
+pages_settings-WebhooksPage_status = Statut
+pages_settings-WebhooksPage_attempts = Tentatives
+pages_settings-WebhooksPage_delivered = Envoyé
+pages_settings-WebhooksPage_pending = Prochaine tentative
+pages_settings-WebhooksPage_failed = Échoué
+
+pages_settings-BillingPage_description = Votre abonnement à l'offre payante, facturé par Stripe. Les changements faits sur Stripe apparaissent ici dès que Stripe nous en informe.
+pages_settings-BillingPage_none = Vous n'êtes pas abonné.
+pages_settings-BillingPage_status = Statut
+pages_settings-BillingPage_period_end = Fin de la période en cours
+pages_settings-BillingPage_subscribe = S'abonner
+pages_settings-BillingPage_required = Un abonnement est nécessaire pour cette page.
+pages_settings-BillingPage_status_incomplete = Incomplet
+pages_settings-BillingPage_status_incomplete_expired = Expiré
+pages_settings-BillingPage_status_trialing = Essai
+pages_settings-BillingPage_status_active = Actif
+pages_settings-BillingPage_status_past_due = En retard de paiement
+pages_settings-BillingPage_status_canceled = Annulé
+pages_settings-BillingPage_status_unpaid = Impayé
+pages_settings-BillingPage_status_paused = En pause
+
+emails_layout_footer = Vous recevez cet e-mail de Starter. Choisissez lesquels vous recevez dans vos
+emails_mention_subject = Vous avez été mentionné
+emails_mention_body = Quelqu'un vous a mentionné dans un fil ou un commentaire.
+emails_mention_action = Voir vos notifications
+emails_verification_subject = Vérifiez votre adresse e-mail
+emails_verification_body = Confirmez que les notifications de Starter peuvent être envoyées à cette adresse.
+emails_verification_action = Vérifier mon adresse
+emails_verification_ignore = Si vous ne l'avez pas demandé, ignorez cet e-mail.
+emails_export_ready_subject = Votre export est prêt
+emails_export_ready_body = L'archive de vos fils, commentaires et réactions est prête à être téléchargée.
+emails_export_ready_action = Télécharger mon export
@@ -0,0 +1,44 @@
+(function () {
+  var selector = "a[href], button, input:not([type=hidden]), select, textarea, [role=img], img";
+
+  function label(element) {
+    if (element.tagName === "IMG") {
+      return element.hasAttribute("alt") ? "alt" : "";
+    }
+
+    if (element.getAttribute("aria-label") || element.getAttribute("aria-labelledby")) {
+      return "aria";
+    }
+
+    if (element.id && document.querySelector('label[for="' + element.id + '"]')) {
+      return "label";
+    }
+
+    if (element.closest("label")) {
+      return "label";
+    }
+
+    return (element.textContent || "").trim() || element.getAttribute("title") || element.getAttribute("placeholder") || "";
+  }
+
+  function audit(root) {
+    root.querySelectorAll(selector).forEach(function (element) {
+      if (label(element)) {
+        return;
+      }
+
+      element.style.outline = "3px dashed red";
+      console.warn("[a11y] element rendered without an accessible label", element);
+    });
+
+    if (!document.querySelector("main")) {
+      console.warn("[a11y] page rendered without a main landmark");
+    }
+  }
+
+  audit(document);
+
+  document.body.addEventListener("htmx:afterSettle", function (e) {
+    audit(e.detail.elt);
+  });
+})();
@@ -0,0 +1,18 @@
+self.addEventListener("push", function (event) {
+  var message = event.data ? event.data.json() : {};
+
+  event.waitUntil(
+    self.registration.showNotification(message.title || "", {
+      body: message.body,
+      data: { url: message.url },
+    })
+  );
+});
+
+self.addEventListener("notificationclick", function (event) {
+  event.notification.close();
+
+  if (event.notification.data && event.notification.data.url) {
+    event.waitUntil(clients.openWindow(event.notification.data.url));
+  }
+});
@@ -0,0 +1,345 @@
+use anyhow::Result;
+use askama_axum::Response;
+use axum::{
+    async_trait,
+    http::{header, HeaderMap, StatusCode},
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use starter_feed::{CronContext, CronTask};
+use std::net::IpAddr;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{context::Context, extract::ClientIp};
+
+/// Longest path recorded, longer ones being left out.
+const MAX_PATH_LEN: usize = 512;
+
+/// Paths and referrers listed by `Analytics::summary`.
+const TOP_LIMIT: i64 = 10;
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct DayStats {
+    pub day: NaiveDate,
+    pub views: i64,
+    pub visitors: i64,
+}
+
+/// Visitors of a page are counted once a day, then summed over the days.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct PageStats {
+    pub path: String,
+    pub views: i64,
+    pub visitors: i64,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct ReferrerStats {
+    pub referrer: String,
+    pub views: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalyticsSummary {
+    /// Latest first, days without views being left out.
+    pub days: Vec<DayStats>,
+    pub pages: Vec<PageStats>,
+    pub referrers: Vec<ReferrerStats>,
+}
+
+/// First party page views, without cookies nor addresses: a visitor is the
+/// hash of their truncated address and user agent salted for the day, the
+/// salt being dropped once the day is aggregated so that the hashes can't be
+/// joined with anything afterwards.
+#[derive(Clone)]
+pub struct Analytics {
+    db: PgPool,
+}
+
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&day.and_time(NaiveTime::default()))
+}
+
+/// `ip` with its host part zeroed, keeping a /24 of IPv4 and a /48 of IPv6.
+fn truncate_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+
+            IpAddr::from([a, b, c, 0])
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+
+            IpAddr::from([a, b, c, 0, 0, 0, 0, 0])
+        }
+    }
+}
+
+impl Analytics {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Salt of the visitors of `day`, created by the first view of the day.
+    async fn salt(&self, day: NaiveDate) -> Result<String> {
+        sqlx::query("INSERT INTO analytics_salts (day, salt) VALUES ( $1, $2 ) ON CONFLICT (day) DO NOTHING")
+            .bind(day)
+            .bind(Uuid::new_v4().simple().to_string())
+            .execute(&self.db)
+            .await?;
+
+        Ok(
+            sqlx::query_scalar::<_, String>("SELECT salt FROM analytics_salts WHERE day = $1")
+                .bind(day)
+                .fetch_one(&self.db)
+                .await?,
+        )
+    }
+
+    /// Records a view of `path`, coming from the host `referrer`.
+    pub async fn record(
+        &self,
+        path: &str,
+        referrer: Option<&str>,
+        ip: Option<IpAddr>,
+        user_agent: &str,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let salt = self.salt(now.date_naive()).await?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(salt.as_bytes());
+        hasher.update(
+            ip.map(truncate_ip)
+                .map(|ip| ip.to_string())
+                .unwrap_or_default(),
+        );
+        hasher.update(user_agent.as_bytes());
+        let visitor = hasher.finalize()[..16]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        sqlx::query(
+            "INSERT INTO analytics_page_views (id, path, referrer, visitor, created_at) VALUES ( $1, $2, $3, $4, $5 )",
+        )
+        .bind(Uuid::new_v4())
+        .bind(path)
+        .bind(referrer)
+        .bind(visitor)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Rolls the views of the days before `before` into the daily tables,
+    /// then drops them along with their salts. Days are only aggregated
+    /// once over, their visitors not being told apart afterwards.
+    pub async fn aggregate(&self, before: NaiveDate) -> Result<u64> {
+        let before_at = start_of_day(before);
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO analytics_daily (day, views, visitors)
+            SELECT (created_at AT TIME ZONE 'UTC')::DATE, COUNT(*), COUNT(DISTINCT visitor)
+            FROM analytics_page_views WHERE created_at < $1 GROUP BY 1
+            ON CONFLICT (day) DO UPDATE SET views = analytics_daily.views + EXCLUDED.views, visitors = analytics_daily.visitors + EXCLUDED.visitors
+            "#,
+        )
+        .bind(before_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO analytics_daily_pages (day, path, views, visitors)
+            SELECT (created_at AT TIME ZONE 'UTC')::DATE, path, COUNT(*), COUNT(DISTINCT visitor)
+            FROM analytics_page_views WHERE created_at < $1 GROUP BY 1, 2
+            ON CONFLICT (day, path) DO UPDATE SET views = analytics_daily_pages.views + EXCLUDED.views, visitors = analytics_daily_pages.visitors + EXCLUDED.visitors
+            "#,
+        )
+        .bind(before_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO analytics_daily_referrers (day, referrer, views)
+            SELECT (created_at AT TIME ZONE 'UTC')::DATE, referrer, COUNT(*)
+            FROM analytics_page_views WHERE created_at < $1 AND referrer IS NOT NULL GROUP BY 1, 2
+            ON CONFLICT (day, referrer) DO UPDATE SET views = analytics_daily_referrers.views + EXCLUDED.views
+            "#,
+        )
+        .bind(before_at)
+        .execute(&mut *tx)
+        .await?;
+
+        let views = sqlx::query("DELETE FROM analytics_page_views WHERE created_at < $1")
+            .bind(before_at)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("DELETE FROM analytics_salts WHERE day < $1")
+            .bind(before)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(views)
+    }
+
+    /// Views since `since`, the ones of the days not aggregated yet being
+    /// counted as they are.
+    pub async fn summary(&self, since: NaiveDate) -> Result<AnalyticsSummary> {
+        let since_at = start_of_day(since);
+
+        let days = sqlx::query_as::<_, DayStats>(
+            r#"
+            SELECT day, SUM(views)::BIGINT AS views, SUM(visitors)::BIGINT AS visitors FROM (
+                SELECT day, views, visitors FROM analytics_daily WHERE day >= $1
+                UNION ALL
+                SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, COUNT(*) AS views, COUNT(DISTINCT visitor) AS visitors
+                FROM analytics_page_views WHERE created_at >= $2 GROUP BY 1
+            ) AS days GROUP BY day ORDER BY day DESC
+            "#,
+        )
+        .bind(since)
+        .bind(since_at)
+        .fetch_all(&self.db);
+
+        let pages = sqlx::query_as::<_, PageStats>(
+            r#"
+            SELECT path, SUM(views)::BIGINT AS views, SUM(visitors)::BIGINT AS visitors FROM (
+                SELECT path, views, visitors FROM analytics_daily_pages WHERE day >= $1
+                UNION ALL
+                SELECT path, COUNT(*) AS views, COUNT(DISTINCT visitor) AS visitors
+                FROM analytics_page_views WHERE created_at >= $2 GROUP BY (created_at AT TIME ZONE 'UTC')::DATE, path
+            ) AS pages GROUP BY path ORDER BY views DESC, path LIMIT $3
+            "#,
+        )
+        .bind(since)
+        .bind(since_at)
+        .bind(TOP_LIMIT)
+        .fetch_all(&self.db);
+
+        let referrers = sqlx::query_as::<_, ReferrerStats>(
+            r#"
+            SELECT referrer, SUM(views)::BIGINT AS views FROM (
+                SELECT referrer, views FROM analytics_daily_referrers WHERE day >= $1
+                UNION ALL
+                SELECT referrer, COUNT(*) AS views
+                FROM analytics_page_views WHERE created_at >= $2 AND referrer IS NOT NULL GROUP BY referrer
+            ) AS referrers GROUP BY referrer ORDER BY views DESC, referrer LIMIT $3
+            "#,
+        )
+        .bind(since)
+        .bind(since_at)
+        .bind(TOP_LIMIT)
+        .fetch_all(&self.db);
+
+        let (days, pages, referrers) = tokio::join!(days, pages, referrers);
+
+        Ok(AnalyticsSummary {
+            days: days?,
+            pages: pages?,
+            referrers: referrers?,
+        })
+    }
+}
+
+/// Aggregates the views of the past days, run by `CronConfig::analytics`.
+pub struct AnalyticsTask;
+
+#[async_trait]
+impl CronTask for AnalyticsTask {
+    async fn run(&self, ctx: &CronContext) -> Result<()> {
+        let views = ctx
+            .extract::<Analytics>()
+            .aggregate(Utc::now().date_naive())
+            .await?;
+
+        info!("aggregated {views} page views");
+
+        Ok(())
+    }
+}
+
+/// Sent by the script of `_scripts.html` on each page.
+#[derive(Deserialize)]
+pub struct PageViewInput {
+    pub path: String,
+    #[serde(default)]
+    pub referrer: String,
+}
+
+/// Whether the browser asks not to be tracked.
+fn opted_out(headers: &HeaderMap) -> bool {
+    ["DNT", "Sec-GPC"]
+        .into_iter()
+        .any(|name| headers.get(name).is_some_and(|value| value == "1"))
+}
+
+/// Records a page view, its body being json sent as text by `sendBeacon`.
+/// Bots, visitors opting out and paths outside of `Config::base_url` are
+/// left out, referrers are kept as their host unless it is the site's.
+pub async fn collect(
+    ctx: Context,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    body: String,
+) -> Result<StatusCode, Response> {
+    if !ctx.is_analytics_enabled() || opted_out(&headers) {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let Ok(input) = serde_json::from_str::<PageViewInput>(&body) else {
+        return Err(ctx.error_response(StatusCode::BAD_REQUEST));
+    };
+
+    let path = input.path.split(['?', '#']).next().unwrap_or_default();
+    let path = match ctx.config.base_url.as_deref() {
+        Some(base_url) => path.strip_prefix(base_url).map(|path| match path {
+            "" => "/",
+            path => path,
+        }),
+        None => Some(path),
+    };
+
+    let Some(path) = path.filter(|path| path.starts_with('/') && path.len() <= MAX_PATH_LEN) else {
+        return Ok(StatusCode::NO_CONTENT);
+    };
+
+    let own_host = reqwest::Url::parse(&ctx.config.public_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned));
+
+    let referrer = reqwest::Url::parse(&input.referrer)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .filter(|host| Some(host) != own_host.as_ref() && host.len() <= 255);
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    ctx.analytics
+        .record(path, referrer.as_deref(), ip, user_agent)
+        .await
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
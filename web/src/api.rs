@@ -0,0 +1,342 @@
+mod comments;
+mod feeds;
+
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts, StatusCode},
+};
+use evento::{store::Event, CommandError, CommandHandler, QueryError, QueryHandler};
+use evento_query::QueryResult;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use tracing::{error, warn};
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        OpenApi as Document, Server,
+    },
+    Modify, OpenApi, ToSchema,
+};
+#[cfg(debug_assertions)]
+use utoipa_swagger_ui::SwaggerUi;
+use validator::Validate;
+
+use comments::*;
+use feeds::*;
+
+use crate::{
+    context::Context,
+    routes::{on, Routes},
+};
+
+/// Routes of `/api/v1`, answering json to the mobile clients authenticated
+/// with the same JWT as the pages.
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route(
+            "/feeds",
+            on!(Public get(list_feeds), User post(create_feed)),
+        )
+        .route(
+            "/feeds/:id",
+            on!(
+                Public get(get_feed),
+                User patch(edit_feed),
+                User delete(trash_feed)
+            ),
+        )
+        .route(
+            "/feeds/:id/comments",
+            on!(Public get(list_comments), User post(create_comment)),
+        )
+        .route("/feeds/:id/reactions", on!(User post(react_feed)))
+        .route("/openapi.json", on!(Public get(openapi_json)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "starter"),
+    paths(
+        list_feeds,
+        get_feed,
+        create_feed,
+        edit_feed,
+        trash_feed,
+        react_feed,
+        list_comments,
+        create_comment
+    ),
+    components(schemas(
+        ApiFeed,
+        ApiComment,
+        FeedPage,
+        CommentPage,
+        PageInfo,
+        Created,
+        CreateFeedBody,
+        EditFeedBody,
+        ReactFeedBody,
+        CreateCommentBody,
+        Problem
+    )),
+    modifiers(&BearerAuth)
+)]
+struct ApiDoc;
+
+/// Declares the `jwt` scheme the routes of users require.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut Document) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "jwt",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+/// OpenAPI document of the json routes, generated from the annotations of
+/// their handlers, `server` being the public url of the site.
+pub fn openapi(server: impl Into<String>) -> Document {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(crate::pages::PagesDoc::openapi());
+    doc.servers = Some(vec![Server::new(server)]);
+    doc
+}
+
+pub async fn openapi_json(api: ApiContext) -> Json<Document> {
+    Json(openapi(api.ctx.create_absolute_url("")))
+}
+
+/// Swagger UI of `/api/v1/openapi.json` at `/api/v1/docs`, only in debug
+/// builds.
+#[cfg(debug_assertions)]
+pub fn swagger_ui(base_url: Option<&str>) -> axum::Router {
+    let url = crate::routes::nested_path(base_url.unwrap_or_default(), "/api/v1/openapi.json");
+
+    SwaggerUi::new("/api/v1/docs")
+        .config(utoipa_swagger_ui::Config::from(url))
+        .into()
+}
+
+/// Error of the api as `application/problem+json`, see RFC 9457. Validation
+/// errors are listed by field in `errors`.
+#[derive(Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: String,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<HashMap<String, Vec<String>>>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            kind: "about:blank",
+            title: status.canonical_reason().unwrap_or_default().to_owned(),
+            status: status.as_u16(),
+            detail: None,
+            errors: None,
+        }
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    fn validation(errors: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            errors: Some(errors),
+            ..Self::new(StatusCode::UNPROCESSABLE_ENTITY)
+        }
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        match serde_json::to_vec(&self) {
+            Ok(body) => (
+                status,
+                [(header::CONTENT_TYPE, "application/problem+json")],
+                body,
+            )
+                .into_response(),
+            Err(err) => {
+                error!("{err}");
+
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+/// `Context` of the api routes, failing with a `Problem` rather than with
+/// the error pages.
+pub struct ApiContext {
+    pub ctx: Context,
+}
+
+impl ApiContext {
+    /// Signed in user, unauthorized otherwise.
+    pub fn user_id(&self) -> Result<String, Problem> {
+        self.ctx
+            .user_id
+            .to_owned()
+            .ok_or_else(|| Problem::new(StatusCode::UNAUTHORIZED))
+    }
+
+    pub async fn query<I: QueryHandler>(&self, input: I) -> Result<I::Output, Problem> {
+        self.ctx.query.execute(&input).await.map_err(|e| match e {
+            QueryError::Server(err) => {
+                error!("{err}");
+
+                Problem::new(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+            QueryError::NotFound(detail) => Problem::new(StatusCode::NOT_FOUND).detail(detail),
+        })
+    }
+
+    /// Events written by the command, to tell the id of what it created.
+    pub async fn execute<I: Validate + CommandHandler>(
+        &self,
+        input: I,
+    ) -> Result<Vec<Event>, Problem> {
+        self.ctx
+            .command
+            .execute(self.ctx.user_language(), &input)
+            .await
+            .map_err(|e| match e {
+                CommandError::Server(err) => {
+                    error!("{err}");
+
+                    Problem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                }
+                CommandError::Validation(errors) => Problem::validation(errors),
+                CommandError::NotFound(detail) => {
+                    Problem::new(StatusCode::NOT_FOUND).detail(detail)
+                }
+            })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = Context::from_request_parts(parts, state)
+            .await
+            .map_err(|res| Problem::new(res.status()))?;
+
+        Ok(Self { ctx })
+    }
+}
+
+/// Same as [`axum::Json`] but rejects with a `Problem`.
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("{rejection}");
+
+                Err(Problem::new(rejection.status()).detail(rejection.body_text()))
+            }
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Same as [`axum::extract::Query`] but rejects with a `Problem`.
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Query(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("{rejection}");
+
+                Err(Problem::new(rejection.status()).detail(rejection.body_text()))
+            }
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// Envelope of the lists, the next page being requested with
+/// `?after={end_cursor}`.
+#[derive(Serialize, ToSchema)]
+#[aliases(FeedPage = Page<ApiFeed>, CommentPage = Page<ApiComment>)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub page_info: PageInfo,
+}
+
+impl<T> Page<T> {
+    pub fn new<N>(result: QueryResult<N>, f: impl Fn(N) -> T) -> Self {
+        Self {
+            page_info: PageInfo {
+                has_next_page: result.page_info.has_next_page,
+                end_cursor: result.page_info.end_cursor.map(|cursor| cursor.0),
+            },
+            data: result.edges.into_iter().map(|edge| f(edge.node)).collect(),
+        }
+    }
+
+    /// Lists that are never paginated.
+    pub fn all(data: Vec<T>) -> Self {
+        Self {
+            data,
+            page_info: PageInfo {
+                has_next_page: false,
+                end_cursor: None,
+            },
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use evento::Aggregate;
+use serde::{Deserialize, Serialize};
+use starter_feed::{Comment, CreateCommentInput, ListCommentsInput, UserComment};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    feeds::{visible_feed, Created},
+    ApiContext, CommentPage, Json, Page, Problem,
+};
+
+/// Comment as listed by the api, deleted ones keeping their place in the
+/// thread without their content.
+#[derive(Serialize, ToSchema)]
+pub struct ApiComment {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub author: String,
+    pub user_id: Uuid,
+    pub content: Option<String>,
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserComment> for ApiComment {
+    fn from(comment: UserComment) -> Self {
+        Self {
+            content: (!comment.deleted).then_some(comment.content),
+            id: comment.id,
+            parent_id: comment.parent_id,
+            author: comment.author,
+            user_id: comment.user_id,
+            deleted: comment.deleted,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+        }
+    }
+}
+
+/// Comments of a feed, oldest first, replies listing their `parent_id`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/feeds/{id}/comments",
+    tag = "comments",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = CommentPage),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    ),
+    security((), ("jwt" = []))
+)]
+pub async fn list_comments(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Json<Page<ApiComment>>, Problem> {
+    visible_feed(&api, id.to_owned()).await?;
+
+    let comments = api.query(ListCommentsInput { feed_id: id }).await?;
+
+    Ok(Json(Page::all(
+        comments.into_iter().map(ApiComment::from).collect(),
+    )))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateCommentBody {
+    /// Comment replied to.
+    pub parent_id: Option<String>,
+    pub content: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/feeds/{id}/comments",
+    tag = "comments",
+    params(("id" = String, Path)),
+    request_body = CreateCommentBody,
+    responses(
+        (status = 201, body = Created, headers(("Location" = String))),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+        (status = 422, body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_comment(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+    Json(input): Json<CreateCommentBody>,
+) -> Result<Response, Problem> {
+    let user_id = api.user_id()?;
+    visible_feed(&api, id.to_owned()).await?;
+
+    let events = api
+        .execute(CreateCommentInput {
+            feed_id: id.to_owned(),
+            parent_id: input.parent_id,
+            content: input.content,
+            user_id,
+            request_id: api.ctx.request_id(),
+        })
+        .await?;
+
+    let Some(event) = events.first() else {
+        return Err(Problem::new(StatusCode::INTERNAL_SERVER_ERROR));
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        [(
+            header::LOCATION,
+            api.ctx
+                .create_absolute_url(format!("/api/v1/feeds/{id}/comments")),
+        )],
+        Json(Created {
+            id: Comment::from_aggregate_id(&event.aggregate_id),
+        }),
+    )
+        .into_response())
+}
@@ -0,0 +1,302 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use evento::Aggregate;
+use evento_query::CursorType;
+use serde::{Deserialize, Serialize};
+use starter_feed::{
+    CreateFeedInput, EditFeedInput, Feed, GetFeedInput, ListFeedsInput, ReactFeedInput,
+    TrashFeedsInput, UserFeed, VISIBILITY_PUBLIC,
+};
+use ulid::Ulid;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::{ApiContext, FeedPage, Json, Page, Problem, Query};
+
+const DEFAULT_PAGE_SIZE: u16 = 20;
+const MAX_PAGE_SIZE: u16 = 100;
+
+/// Feed as listed by the api, without what only its author sees.
+#[derive(Serialize, ToSchema)]
+pub struct ApiFeed {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub author: String,
+    pub user_id: Uuid,
+    pub tags: Vec<String>,
+    pub total_likes: i32,
+    pub visibility: String,
+    /// Absolute url of the feed page.
+    pub url: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub pinned_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl ApiFeed {
+    fn new(api: &ApiContext, feed: UserFeed) -> Self {
+        Self {
+            url: api.ctx.create_absolute_url(feed.path()),
+            id: feed.id,
+            title: feed.title,
+            content: feed.content,
+            author: feed.author,
+            user_id: feed.user_id,
+            tags: feed.tags,
+            total_likes: feed.total_likes,
+            visibility: feed.visibility,
+            publish_at: feed.publish_at,
+            pinned_at: feed.pinned_at,
+            created_at: feed.created_at,
+            edited_at: feed.edited_at,
+        }
+    }
+}
+
+/// Feed `id` when visible to the signed in user, not found otherwise.
+pub(super) async fn visible_feed(api: &ApiContext, id: String) -> Result<UserFeed, Problem> {
+    let feed = api.query(GetFeedInput { id }).await?;
+
+    if feed.hidden || !feed.is_visible_to(api.ctx.user_id.as_deref()) {
+        return Err(Problem::new(StatusCode::NOT_FOUND));
+    }
+
+    Ok(feed)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListFeedsQuery {
+    /// Feeds of the page, 20 by default and 100 at most.
+    pub first: Option<u16>,
+    /// `end_cursor` of the previous page.
+    #[param(value_type = Option<String>)]
+    pub after: Option<CursorType>,
+    pub tag: Option<String>,
+    /// Id of the user who wrote the feeds.
+    pub author: Option<Uuid>,
+}
+
+/// Public feeds, latest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/feeds",
+    tag = "feeds",
+    params(ListFeedsQuery),
+    responses(
+        (status = 200, body = FeedPage),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_feeds(
+    api: ApiContext,
+    Query(input): Query<ListFeedsQuery>,
+) -> Result<Json<Page<ApiFeed>>, Problem> {
+    let feeds = api
+        .query(ListFeedsInput {
+            first: Some(input.first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE)),
+            after: input.after,
+            last: None,
+            before: None,
+            tag: input.tag,
+            author: input.author,
+            unpinned: false,
+        })
+        .await?;
+
+    Ok(Json(Page::new(feeds, |feed| ApiFeed::new(&api, feed))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/feeds/{id}",
+    tag = "feeds",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = ApiFeed),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    ),
+    security((), ("jwt" = []))
+)]
+pub async fn get_feed(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Json<ApiFeed>, Problem> {
+    let feed = visible_feed(&api, id).await?;
+
+    Ok(Json(ApiFeed::new(&api, feed)))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateFeedBody {
+    pub title: String,
+    pub content: Option<String>,
+    /// `public`, `unlisted` or `private`, `public` by default.
+    pub visibility: Option<String>,
+    /// Schedules the feed instead of publishing it.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Created {
+    pub id: String,
+}
+
+/// Creates a feed of the signed in user, answering its id with its api url
+/// as `Location`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/feeds",
+    tag = "feeds",
+    request_body = CreateFeedBody,
+    responses(
+        (status = 201, body = Created, headers(("Location" = String))),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 422, body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_feed(
+    api: ApiContext,
+    Json(input): Json<CreateFeedBody>,
+) -> Result<Response, Problem> {
+    let events = api
+        .execute(CreateFeedInput {
+            title: input.title,
+            content: input.content.filter(|content| !content.trim().is_empty()),
+            attachments: vec![],
+            visibility: input
+                .visibility
+                .unwrap_or_else(|| VISIBILITY_PUBLIC.to_owned()),
+            publish_at: input.publish_at,
+            user_id: api.user_id()?,
+            request_id: api.ctx.request_id(),
+        })
+        .await?;
+
+    let Some(event) = events.first() else {
+        return Err(Problem::new(StatusCode::INTERNAL_SERVER_ERROR));
+    };
+
+    let id = Feed::from_aggregate_id(&event.aggregate_id);
+
+    Ok((
+        StatusCode::CREATED,
+        [(
+            header::LOCATION,
+            api.ctx.create_absolute_url(format!("/api/v1/feeds/{id}")),
+        )],
+        Json(Created { id }),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct EditFeedBody {
+    pub title: String,
+    pub content: String,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/feeds/{id}",
+    tag = "feeds",
+    params(("id" = String, Path)),
+    request_body = EditFeedBody,
+    responses(
+        (status = 204),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+        (status = 422, body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn edit_feed(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+    Json(input): Json<EditFeedBody>,
+) -> Result<StatusCode, Problem> {
+    api.execute(EditFeedInput {
+        id,
+        title: input.title,
+        content: input.content,
+        user_id: api.user_id()?,
+        request_id: api.ctx.request_id(),
+    })
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Trashes a feed of the signed in user, deleted once the undo window of the
+/// settings page elapsed.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/feeds/{id}",
+    tag = "feeds",
+    params(("id" = String, Path)),
+    responses(
+        (status = 204),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn trash_feed(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<StatusCode, Problem> {
+    api.execute(TrashFeedsInput {
+        ids: vec![id],
+        user_id: api.user_id()?,
+        request_id: Ulid::new().to_string(),
+    })
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ReactFeedBody {
+    pub reaction: String,
+}
+
+/// Toggles a reaction of the signed in user on a feed.
+#[utoipa::path(
+    post,
+    path = "/api/v1/feeds/{id}/reactions",
+    tag = "feeds",
+    params(("id" = String, Path)),
+    request_body = ReactFeedBody,
+    responses(
+        (status = 204),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+        (status = 422, body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn react_feed(
+    api: ApiContext,
+    Path((id,)): Path<(String,)>,
+    Json(input): Json<ReactFeedBody>,
+) -> Result<StatusCode, Problem> {
+    let user_id = api.user_id()?;
+    visible_feed(&api, id.to_owned()).await?;
+
+    api.execute(ReactFeedInput {
+        feed_id: id,
+        reaction: input.reaction,
+        user_id,
+        request_id: api.ctx.request_id(),
+    })
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,13 @@
-use std::str::FromStr;
+use std::{
+    collections::HashMap,
+    io::Write,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 use askama_axum::IntoResponse;
-use axum::http::{header, StatusCode, Uri};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use once_cell::sync::Lazy;
 use rust_embed::RustEmbed;
 
 use crate::{context::Context, pages::NotFoundPage};
@@ -11,7 +17,121 @@ use crate::{context::Context, pages::NotFoundPage};
 #[prefix = "/static/"]
 struct Assets;
 
-pub async fn static_handler(uri: Uri, ctx: Context) -> impl IntoResponse {
+/// Query parameter of the urls of `Context::create_fingerprinted_url`.
+const FINGERPRINT: &str = "h";
+
+/// Files smaller than this are sent as they are, compressing them saving
+/// less than the headers cost.
+const COMPRESS_MIN_SIZE: usize = 1024;
+
+/// Same assets being compressed once per encoding and content, by path.
+static COMPRESSED: Lazy<RwLock<HashMap<(String, Encoding, String), Arc<Vec<u8>>>>> =
+    Lazy::new(Default::default);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    /// Preferred encoding of `Accept-Encoding`, brotli over gzip, those with
+    /// `q=0` being refused.
+    fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let accepted = headers
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|value| {
+                let mut parts = value.split(';').map(str::trim);
+                let name = parts.next()?.to_lowercase();
+                let refused = parts.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q == 0.0)
+                });
+
+                (!refused).then_some(name)
+            })
+            .collect::<Vec<_>>();
+
+        [Encoding::Brotli, Encoding::Gzip]
+            .into_iter()
+            .find(|encoding| accepted.iter().any(|name| name == encoding.name()))
+    }
+
+    fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+                writer.write_all(data)?;
+                Ok(writer.into_inner())
+            }
+            Encoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Whether compressing `mime` pays off, images and fonts being compressed
+/// already.
+fn is_compressible(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/javascript" | "application/json" | "image/svg+xml"
+        )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hash of the content of the asset at `path`, below `/static/`, which
+/// changes with it.
+pub fn fingerprint(path: &str) -> Option<String> {
+    let path = path.split('?').next().unwrap_or_default();
+
+    Assets::get(&format!("/static/{path}")).map(|content| hex(&content.metadata.sha256_hash()[..8]))
+}
+
+/// `data` compressed with `encoding`, computed on the first request only.
+fn compressed(path: &str, hash: &str, encoding: Encoding, data: &[u8]) -> Option<Arc<Vec<u8>>> {
+    let key = (path.to_owned(), encoding, hash.to_owned());
+
+    if let Some(data) = COMPRESSED.read().expect("assets lock poisoned").get(&key) {
+        return Some(data.clone());
+    }
+
+    let data = Arc::new(encoding.compress(data).ok()?);
+
+    COMPRESSED
+        .write()
+        .expect("assets lock poisoned")
+        .insert(key, data.clone());
+
+    Some(data)
+}
+
+/// Serves the files of `public/` below `/static/` with a strong ETag of
+/// their content and encoding, answering 304 to an `If-None-Match` of it.
+/// Text files are compressed with brotli or gzip as the client accepts. Urls of
+/// `Context::create_fingerprinted_url` are cached for good, the others for
+/// `Config::static_max_age`.
+pub async fn static_handler(uri: Uri, headers: HeaderMap, ctx: Context) -> impl IntoResponse {
     let uri = uri.to_string();
     let mut path = ctx
         .config
@@ -41,15 +161,73 @@ pub async fn static_handler(uri: Uri, ctx: Context) -> impl IntoResponse {
         return (StatusCode::BAD_REQUEST, "400 Bad Request").into_response();
     };
 
-    if let Some(query) = uri.query() {
+    let query = uri.query().map(str::to_owned);
+
+    if let Some(query) = &query {
         path = path.replace(&format!("?{query}"), "");
     }
 
-    match Assets::get(path.as_str()) {
-        Some(content) => {
-            let mime = mime_guess::from_path(path).first_or_octet_stream();
-            ([(header::CONTENT_TYPE, mime.as_ref())], content.data).into_response()
+    let Some(content) = Assets::get(path.as_str()) else {
+        return (StatusCode::NOT_FOUND, "404 Not Found").into_response();
+    };
+
+    let hash = hex(&content.metadata.sha256_hash()[..8]);
+    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+    let encoding = Encoding::negotiate(&headers)
+        .filter(|_| content.data.len() >= COMPRESS_MIN_SIZE && is_compressible(mime.as_ref()));
+
+    // Each encoding is a representation of its own, with its own tag.
+    let etag = match encoding {
+        Some(encoding) => format!("\"{hash}-{}\"", encoding.name()),
+        None => format!("\"{hash}\""),
+    };
+
+    let fingerprinted = query.as_deref().is_some_and(|query| {
+        query
+            .split('&')
+            .any(|param| param == format!("{FINGERPRINT}={hash}"))
+    });
+
+    let cache_control = if fingerprinted {
+        "public, max-age=31536000, immutable".to_owned()
+    } else {
+        format!("public, max-age={}", ctx.config.static_max_age)
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        response_headers.insert(header::CACHE_CONTROL, value);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim() == "*" || value.split(',').any(|tag| tag.trim() == etag));
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, response_headers).into_response();
+    }
+
+    if let Ok(value) = HeaderValue::from_str(mime.as_ref()) {
+        response_headers.insert(header::CONTENT_TYPE, value);
+    }
+
+    if let Some(encoding) = encoding {
+        if let Some(data) = compressed(&path, &hash, encoding, &content.data) {
+            response_headers.insert(
+                header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.name()),
+            );
+
+            return (response_headers, data.as_ref().clone()).into_response();
         }
-        None => (StatusCode::NOT_FOUND, "404 Not Found").into_response(),
     }
+
+    (response_headers, content.data).into_response()
 }
@@ -0,0 +1,522 @@
+use anyhow::{bail, Result};
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::Request,
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Redirect,
+    Extension,
+};
+use axum_extra::extract::{
+    cookie::{Cookie, Key, SameSite},
+    PrivateCookieJar,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+use tracing::{error, warn};
+
+use crate::{
+    config::OidcConfig,
+    context::{Context, JwtClaims},
+    extract::Query,
+    routes::{on, Routes},
+};
+
+/// Cookie of the tokens of the signed in user, encrypted with
+/// `OidcConfig::session_secret`.
+const SESSION_COOKIE: &str = "session";
+
+/// Cookie of the login in progress, from `/auth/login` to `/auth/callback`.
+const FLOW_COOKIE: &str = "oidc_flow";
+
+/// Minutes given to the user to sign in at the provider.
+const FLOW_MAX_AGE: i64 = 10;
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("oidc client not built")
+});
+
+/// Endpoints of the providers, discovered once per issuer.
+static DISCOVERED: Lazy<RwLock<HashMap<String, Discovery>>> = Lazy::new(Default::default);
+
+/// `/auth`, signing in with the provider of `OidcConfig::issuer`.
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/login", on!(Public get(login)))
+        .route("/callback", on!(Public get(callback)))
+        .route("/logout", on!(Public post(logout)))
+}
+
+/// Endpoints of `/.well-known/openid-configuration` used by the login.
+#[derive(Deserialize, Clone)]
+pub struct Discovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub end_session_endpoint: Option<String>,
+}
+
+/// Discovery document of `issuer`, which must name it as its issuer.
+pub async fn discover(issuer: &str) -> Result<Discovery> {
+    let issuer = issuer.trim_end_matches('/');
+
+    if let Some(discovery) = DISCOVERED
+        .read()
+        .expect("discovery lock poisoned")
+        .get(issuer)
+    {
+        return Ok(discovery.clone());
+    }
+
+    let body = CLIENT
+        .get(format!("{issuer}/.well-known/openid-configuration"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let discovery = serde_json::from_str::<Discovery>(&body)?;
+
+    if discovery.issuer.trim_end_matches('/') != issuer {
+        bail!("{} is not the issuer {issuer}", discovery.issuer);
+    }
+
+    DISCOVERED
+        .write()
+        .expect("discovery lock poisoned")
+        .insert(issuer.to_owned(), discovery.clone());
+
+    Ok(discovery)
+}
+
+/// Key of the cookies, `None` while the login is off or its secret too
+/// short.
+fn key(config: &OidcConfig) -> Option<Key> {
+    config.issuer.as_ref()?;
+
+    config
+        .session_secret
+        .as_deref()
+        .filter(|secret| secret.len() >= 32)
+        .map(|secret| Key::derive_from(secret.as_bytes()))
+}
+
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Login in progress, checked against the query of `/auth/callback`.
+#[derive(Serialize, Deserialize)]
+struct Flow {
+    state: String,
+    nonce: String,
+    /// PKCE verifier of the `code_challenge` sent to the provider.
+    verifier: String,
+    redirect: String,
+}
+
+/// Tokens of the session cookie.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    id_token: String,
+    refresh_token: Option<String>,
+    /// Unix time the tokens expire at.
+    expires_at: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+/// Claims of the signed in user from the session cookie, in the extensions
+/// of the request for `Context`.
+#[derive(Clone)]
+pub struct SessionClaims(pub JwtClaims);
+
+/// Claims of `id_token`. Its signature is not checked: the token comes
+/// straight from the token endpoint and is kept encrypted after that.
+fn id_claims(id_token: &str) -> Result<JwtClaims> {
+    let Some(payload) = id_token.split('.').nth(1) else {
+        bail!("id_token is not a jwt");
+    };
+
+    Ok(serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload)?)?)
+}
+
+/// Fails unless the claims were issued by `discovery` to
+/// `OidcConfig::client_id`, with `nonce` when there is one.
+fn check_claims(
+    claims: &JwtClaims,
+    config: &OidcConfig,
+    discovery: &Discovery,
+    nonce: Option<&str>,
+) -> Result<()> {
+    let claim = |name: &str| claims.claims.get(name).and_then(|value| value.as_str());
+
+    if claim("iss") != Some(discovery.issuer.as_str()) {
+        bail!("id_token not issued by {}", discovery.issuer);
+    }
+
+    let audience = match claims.claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => aud == &config.client_id,
+        Some(serde_json::Value::Array(aud)) => aud
+            .iter()
+            .any(|aud| aud.as_str() == Some(config.client_id.as_str())),
+        _ => false,
+    };
+
+    if !audience {
+        bail!("id_token not issued to {}", config.client_id);
+    }
+
+    if nonce.is_some() && claim("nonce") != nonce {
+        bail!("id_token nonce mismatch");
+    }
+
+    Ok(())
+}
+
+/// Asks the token endpoint of `discovery` for the tokens of `params`, the
+/// ones it doesn't send back being taken from `previous`.
+async fn request_tokens(
+    config: &OidcConfig,
+    discovery: &Discovery,
+    params: &[(&str, &str)],
+    previous: Option<&Session>,
+) -> Result<Session> {
+    let mut form = params.to_vec();
+    form.push(("client_id", config.client_id.as_str()));
+
+    if let Some(secret) = config.client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+
+    let body = CLIENT
+        .post(&discovery.token_endpoint)
+        .form(&form)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let tokens = serde_json::from_str::<TokenResponse>(&body)?;
+
+    let Some(id_token) = tokens
+        .id_token
+        .or_else(|| previous.map(|session| session.id_token.to_owned()))
+    else {
+        bail!("no id_token");
+    };
+
+    let expires_at = match tokens.expires_in {
+        Some(expires_in) => Utc::now().timestamp() + expires_in,
+        None => id_claims(&id_token)?
+            .claims
+            .get("exp")
+            .and_then(|exp| exp.as_i64())
+            .unwrap_or_default(),
+    };
+
+    Ok(Session {
+        id_token,
+        refresh_token: tokens
+            .refresh_token
+            .or_else(|| previous.and_then(|session| session.refresh_token.to_owned())),
+        expires_at,
+    })
+}
+
+fn session_cookie(ctx: &Context, session: &Session) -> Result<Cookie<'static>> {
+    Ok(
+        Cookie::build((SESSION_COOKIE, serde_json::to_string(session)?))
+            .path("/")
+            .http_only(true)
+            .secure(ctx.config.cookies.secure)
+            .same_site(ctx.config.cookies.same_site())
+            .build(),
+    )
+}
+
+fn removal(name: &'static str) -> Cookie<'static> {
+    Cookie::build(name).path("/").build()
+}
+
+/// Puts the claims of the session cookie in the extensions of the request,
+/// refreshing its tokens `OidcConfig::refresh_margin` before they expire.
+/// A session that can't be refreshed is dropped, the user being signed out.
+pub async fn session(
+    Extension(mut ctx): Extension<Context>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    ctx.config = ctx.reloader.config();
+
+    let Some(key) = key(&ctx.config.oidc) else {
+        return next.run(req).await;
+    };
+
+    let jar = PrivateCookieJar::from_headers(req.headers(), key);
+    let Some(session) = jar
+        .get(SESSION_COOKIE)
+        .and_then(|cookie| serde_json::from_str::<Session>(cookie.value()).ok())
+    else {
+        return next.run(req).await;
+    };
+
+    if session.expires_at - ctx.config.oidc.refresh_margin > Utc::now().timestamp() {
+        if let Ok(claims) = id_claims(&session.id_token) {
+            req.extensions_mut().insert(SessionClaims(claims));
+        }
+
+        return next.run(req).await;
+    }
+
+    match refresh(&ctx, &session).await {
+        Ok((session, claims)) => {
+            req.extensions_mut().insert(SessionClaims(claims));
+
+            match session_cookie(&ctx, &session) {
+                Ok(cookie) => (jar.add(cookie), next.run(req).await).into_response(),
+                Err(err) => {
+                    error!("{err}");
+
+                    next.run(req).await
+                }
+            }
+        }
+        Err(err) => {
+            warn!("session dropped: {err}");
+
+            (jar.remove(removal(SESSION_COOKIE)), next.run(req).await).into_response()
+        }
+    }
+}
+
+async fn refresh(ctx: &Context, session: &Session) -> Result<(Session, JwtClaims)> {
+    let config = &ctx.config.oidc;
+
+    let (Some(issuer), Some(refresh_token)) =
+        (config.issuer.as_deref(), session.refresh_token.as_deref())
+    else {
+        bail!("session expired");
+    };
+
+    let discovery = discover(issuer).await?;
+    let session = request_tokens(
+        config,
+        &discovery,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ],
+        Some(session),
+    )
+    .await?;
+    let claims = id_claims(&session.id_token)?;
+    check_claims(&claims, config, &discovery, None)?;
+
+    Ok((session, claims))
+}
+
+#[derive(Deserialize)]
+pub struct LoginInput {
+    pub redirect: Option<String>,
+}
+
+/// Sends the user to the provider with an authorization code request,
+/// `redirect` being where they land once signed in.
+pub async fn login(ctx: Context, headers: HeaderMap, Query(input): Query<LoginInput>) -> Response {
+    let config = &ctx.config.oidc;
+
+    let (Some(issuer), Some(key)) = (config.issuer.as_deref(), key(config)) else {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    };
+
+    let discovery = match discover(issuer).await {
+        Ok(discovery) => discovery,
+        Err(err) => {
+            error!("{err}");
+
+            return ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let flow = Flow {
+        state: random_token(),
+        nonce: random_token(),
+        verifier: random_token(),
+        redirect: ctx.local_path(input.redirect.as_deref()),
+    };
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(flow.verifier.as_bytes()));
+    let redirect_uri = ctx.config.create_absolute_url("/auth/callback");
+
+    let url = match reqwest::Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        [
+            ("response_type", "code"),
+            ("client_id", config.client_id.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("scope", config.scopes.as_str()),
+            ("state", flow.state.as_str()),
+            ("nonce", flow.nonce.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    ) {
+        Ok(url) => url,
+        Err(err) => {
+            error!("{err}");
+
+            return ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let Ok(value) = serde_json::to_string(&flow) else {
+        return ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    // Lax whatever `Config::cookies` says, the provider sending the user
+    // back from another site.
+    let cookie = Cookie::build((FLOW_COOKIE, value))
+        .path("/")
+        .http_only(true)
+        .secure(ctx.config.cookies.secure)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::minutes(FLOW_MAX_AGE))
+        .build();
+
+    let jar = PrivateCookieJar::from_headers(&headers, key);
+
+    (jar.add(cookie), Redirect::to(url.as_str())).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct CallbackInput {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Exchanges the code the provider sent the user back with for the tokens
+/// kept in the session cookie.
+pub async fn callback(
+    ctx: Context,
+    headers: HeaderMap,
+    Query(input): Query<CallbackInput>,
+) -> Response {
+    let config = &ctx.config.oidc;
+
+    let (Some(issuer), Some(key)) = (config.issuer.as_deref(), key(config)) else {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    };
+
+    let jar = PrivateCookieJar::from_headers(&headers, key);
+    let flow = jar
+        .get(FLOW_COOKIE)
+        .and_then(|cookie| serde_json::from_str::<Flow>(cookie.value()).ok());
+
+    if let Some(error) = input.error {
+        warn!("login refused: {error}");
+    }
+
+    let (Some(flow), Some(code)) = (flow, input.code) else {
+        return ctx.error_response(StatusCode::BAD_REQUEST);
+    };
+
+    if input.state.as_deref() != Some(flow.state.as_str()) {
+        return ctx.error_response(StatusCode::BAD_REQUEST);
+    }
+
+    let result = async {
+        let discovery = discover(issuer).await?;
+        let redirect_uri = ctx.config.create_absolute_url("/auth/callback");
+        let session = request_tokens(
+            config,
+            &discovery,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("code_verifier", flow.verifier.as_str()),
+            ],
+            None,
+        )
+        .await?;
+
+        let claims = id_claims(&session.id_token)?;
+        check_claims(&claims, config, &discovery, Some(&flow.nonce))?;
+
+        session_cookie(&ctx, &session)
+    }
+    .await;
+
+    let jar = jar.remove(removal(FLOW_COOKIE));
+
+    match result {
+        Ok(cookie) => (jar.add(cookie), Redirect::to(&flow.redirect)).into_response(),
+        Err(err) => {
+            warn!("login failed: {err}");
+
+            (jar, ctx.error_response(StatusCode::UNAUTHORIZED)).into_response()
+        }
+    }
+}
+
+/// Drops the session cookie and signs the user out of the provider too when
+/// it has an `end_session_endpoint`.
+pub async fn logout(ctx: Context, headers: HeaderMap) -> Response {
+    let config = &ctx.config.oidc;
+
+    let (Some(issuer), Some(key)) = (config.issuer.as_deref(), key(config)) else {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    };
+
+    let jar = PrivateCookieJar::from_headers(&headers, key);
+    let id_token = jar
+        .get(SESSION_COOKIE)
+        .and_then(|cookie| serde_json::from_str::<Session>(cookie.value()).ok())
+        .map(|session| session.id_token);
+    let jar = jar.remove(removal(SESSION_COOKIE));
+    let home = ctx.config.create_absolute_url("/");
+
+    let end_session = match discover(issuer).await {
+        Ok(discovery) => discovery.end_session_endpoint,
+        Err(err) => {
+            warn!("{err}");
+
+            None
+        }
+    };
+
+    let url = end_session.and_then(|endpoint| {
+        let mut params = vec![
+            ("client_id", config.client_id.as_str()),
+            ("post_logout_redirect_uri", home.as_str()),
+        ];
+
+        if let Some(id_token) = id_token.as_deref() {
+            params.push(("id_token_hint", id_token));
+        }
+
+        reqwest::Url::parse_with_params(&endpoint, params).ok()
+    });
+
+    match url {
+        Some(url) => (jar, Redirect::to(url.as_str())).into_response(),
+        None => (jar, Redirect::to(&ctx.create_url("/"))).into_response(),
+    }
+}
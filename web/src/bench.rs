@@ -0,0 +1,76 @@
+use anyhow::{bail, Result};
+use axum::{
+    body::{self, Body},
+    extract::ConnectInfo,
+    http::Request,
+};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tower::ServiceExt;
+use twa_jwks::JwksClient;
+
+use crate::{config::Config, router, start_tenants};
+
+/// Parses the translations of every language and module, as done once on
+/// start, for the benchmarks of `starter-test`.
+pub fn load_translations() -> impl Sized {
+    crate::i18n::language_loader()
+}
+
+/// Timings of the renders of a page.
+pub struct RenderTimings {
+    pub iterations: usize,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Renders the page at `path` `iterations` times through the router of
+/// `serve`, without listening for requests nor running the consumers, the
+/// data being the one of the configured database. A render lasts until its
+/// body is read, pages being streamed.
+pub async fn render(path: &str, iterations: usize) -> Result<RenderTimings> {
+    if iterations == 0 {
+        bail!("at least one iteration is required");
+    }
+
+    let config = Config::load().await?;
+    let uri = config.create_url(path);
+    let jwks = JwksClient::build(config.jwks_url.to_owned()).await?;
+    let router = router(start_tenants(config, false).await?, jwks);
+
+    let mut timings = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let request = Request::get(&uri)
+            .extension(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))))
+            .body(Body::empty())?;
+
+        let started_at = Instant::now();
+        let response = router.clone().oneshot(request).await?;
+        let status = response.status();
+        body::to_bytes(response.into_body(), usize::MAX).await?;
+        let elapsed = started_at.elapsed();
+
+        if !status.is_success() {
+            bail!("{uri} responded with {status}");
+        }
+
+        timings.push(elapsed);
+    }
+
+    timings.sort();
+
+    let percentile = |percent: usize| timings[(timings.len() - 1) * percent / 100];
+
+    Ok(RenderTimings {
+        iterations,
+        min: timings[0],
+        p50: percentile(50),
+        p99: percentile(99),
+        max: percentile(100),
+    })
+}
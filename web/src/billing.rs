@@ -0,0 +1,239 @@
+use anyhow::Result;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::Redirect,
+};
+use chrono::{DateTime, Utc};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use starter_feed::{verify_signature, GetSubscriptionInput, UpdateSubscriptionInput};
+use std::{collections::HashMap, time::Duration};
+use tracing::warn;
+
+use crate::{
+    config::BillingConfig,
+    context::{Context, UserContext},
+    flash::Flash,
+    routes::{on, Routes},
+};
+
+/// Seconds a `Stripe-Signature` stays valid for, past which the event is
+/// taken for a replay.
+const SIGNATURE_TOLERANCE: i64 = 5 * 60;
+
+/// `/billing`, the endpoint Stripe sends the events of the account to.
+pub fn create_router() -> Routes {
+    Routes::new().route("/webhook", on!(Public post(webhook)))
+}
+
+/// Client of the Stripe api of `BillingConfig`, for the single plan of
+/// `BillingConfig::price_id`.
+#[derive(Clone)]
+pub struct Stripe {
+    client: reqwest::Client,
+    config: BillingConfig,
+}
+
+#[derive(Deserialize)]
+struct CheckoutSession {
+    url: String,
+}
+
+impl Stripe {
+    pub fn new(config: &BillingConfig) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            config: config.clone(),
+        })
+    }
+
+    /// Whether the users can subscribe, both the secret key and the price
+    /// being configured.
+    pub fn is_enabled(&self) -> bool {
+        self.config.secret_key.is_some() && self.config.price_id.is_some()
+    }
+
+    /// Url of a Checkout session subscribing `user_id` to the plan, `None`
+    /// when billing is not configured.
+    pub async fn create_checkout_session(
+        &self,
+        user_id: &str,
+        success_url: &str,
+        cancel_url: &str,
+    ) -> Result<Option<String>> {
+        let (Some(secret_key), Some(price_id)) = (&self.config.secret_key, &self.config.price_id)
+        else {
+            return Ok(None);
+        };
+
+        let body = self
+            .client
+            .post(format!(
+                "{}/v1/checkout/sessions",
+                self.config.api_url.trim_end_matches('/')
+            ))
+            .bearer_auth(secret_key)
+            .form(&[
+                ("mode", "subscription"),
+                ("line_items[0][price]", price_id.as_str()),
+                ("line_items[0][quantity]", "1"),
+                ("success_url", success_url),
+                ("cancel_url", cancel_url),
+                ("client_reference_id", user_id),
+                ("subscription_data[metadata][user_id]", user_id),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(Some(serde_json::from_str::<CheckoutSession>(&body)?.url))
+    }
+}
+
+/// Whether `header`, as `t=1700000000,v1=...`, signs `body` with `secret`
+/// less than `SIGNATURE_TOLERANCE` from `now`. Any of the `v1` signatures
+/// verifying is enough, Stripe sending several while a secret is rolled.
+fn verify_stripe_signature(secret: &str, header: &str, body: &str, now: DateTime<Utc>) -> bool {
+    let mut timestamp = None;
+    let mut signatures = vec![];
+
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+
+    if (now.timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE {
+        return false;
+    }
+
+    signatures
+        .into_iter()
+        .any(|signature| verify_signature(secret, timestamp, body, signature))
+}
+
+#[derive(Deserialize)]
+struct StripeEvent {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    created: i64,
+    data: StripeEventData,
+}
+
+#[derive(Deserialize)]
+struct StripeEventData {
+    object: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct StripeSubscription {
+    id: String,
+    customer: String,
+    status: String,
+    current_period_end: Option<i64>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+/// Events of Stripe, only the `customer.subscription.*` ones being applied.
+/// The user is the one of the `user_id` metadata set at checkout, events
+/// without it or with an unknown status being acknowledged and skipped so
+/// that Stripe doesn't retry them.
+pub async fn webhook(ctx: Context, headers: HeaderMap, body: String) -> Result<Response, Response> {
+    let Some(secret) = ctx.config.billing.webhook_secret.as_deref() else {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    };
+
+    let signature = headers
+        .get("stripe-signature")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    if !verify_stripe_signature(secret, signature, &body, Utc::now()) {
+        return Err(ctx.error_response(StatusCode::BAD_REQUEST));
+    }
+
+    let event = serde_json::from_str::<StripeEvent>(&body)
+        .map_err(|_| ctx.error_response(StatusCode::BAD_REQUEST))?;
+
+    if !event.kind.starts_with("customer.subscription.") {
+        return Ok(StatusCode::OK.into_response());
+    }
+
+    let subscription = serde_json::from_value::<StripeSubscription>(event.data.object)
+        .map_err(|_| ctx.error_response(StatusCode::BAD_REQUEST))?;
+
+    let Some(user_id) = subscription.metadata.get("user_id") else {
+        warn!("stripe event {} has no user_id metadata", event.id);
+
+        return Ok(StatusCode::OK.into_response());
+    };
+
+    let changed_at = DateTime::from_timestamp(event.created, 0)
+        .ok_or_else(|| ctx.error_response(StatusCode::BAD_REQUEST))?;
+
+    if let Some(errors) = ctx
+        .execute(UpdateSubscriptionInput {
+            customer_id: subscription.customer,
+            subscription_id: subscription.id,
+            status: subscription.status,
+            current_period_end: subscription
+                .current_period_end
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)),
+            stripe_event_id: event.id.to_owned(),
+            changed_at,
+            user_id: user_id.to_owned(),
+            request_id: None,
+        })
+        .await?
+    {
+        warn!("stripe event {} skipped: {errors:?}", event.id);
+    }
+
+    Ok(StatusCode::OK.into_response())
+}
+
+/// Redirects to `/settings/billing` unless the signed in user has an active
+/// subscription, either as a handler argument or as a guard with
+/// `middleware::from_extractor`.
+pub struct Subscribed;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Subscribed
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = UserContext::from_request_parts(parts, state).await?;
+
+        let subscription = ctx
+            .query(GetSubscriptionInput {
+                user_id: ctx.user_id.to_owned(),
+            })
+            .await?;
+
+        if subscription.is_some_and(|subscription| subscription.is_active()) {
+            return Ok(Subscribed);
+        }
+
+        let flash = Flash::warning(fl!(ctx.fl_loader(), "pages_settings-BillingPage_required"));
+
+        Err((flash, Redirect::to(&ctx.create_url("/settings/billing"))).into_response())
+    }
+}
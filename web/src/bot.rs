@@ -0,0 +1,135 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap},
+    Extension, RequestPartsExt,
+};
+use std::{convert::Infallible, net::IpAddr};
+use tracing::warn;
+
+use crate::{context::Context, extract::ClientIp};
+
+const BOT_PATTERNS: [&str; 12] = [
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "facebookexternalhit",
+    "embedly",
+    "preview",
+    "headless",
+    "curl",
+    "wget",
+    "python-requests",
+    "go-http-client",
+];
+
+/// User agent token and the reverse DNS domains its crawler is served from.
+const VERIFIABLE_CRAWLERS: [(&str, &[&str]); 4] = [
+    ("googlebot", &[".googlebot.com", ".google.com"]),
+    ("bingbot", &[".search.msn.com"]),
+    ("applebot", &[".applebot.apple.com"]),
+    ("yandex", &[".yandex.ru", ".yandex.net", ".yandex.com"]),
+];
+
+/// User agent heuristic, cheap enough to run on every request.
+pub fn is_bot_user_agent(headers: &HeaderMap) -> bool {
+    let Some(user_agent) = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return true;
+    };
+
+    let user_agent = user_agent.to_lowercase();
+
+    BOT_PATTERNS
+        .iter()
+        .any(|pattern| user_agent.contains(pattern))
+}
+
+/// Reverse DNS of `ip` must end with one of the crawler domains and resolve
+/// back to `ip`, see <https://developers.google.com/search/docs/crawling-indexing/verifying-googlebot>.
+async fn verify_crawler(user_agent: &str, ip: IpAddr) -> bool {
+    let user_agent = user_agent.to_lowercase();
+    let Some((_, domains)) = VERIFIABLE_CRAWLERS
+        .iter()
+        .find(|(token, _)| user_agent.contains(token))
+    else {
+        return false;
+    };
+
+    let hostname = match tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip)).await {
+        Ok(Ok(hostname)) => hostname,
+        Ok(Err(e)) => {
+            warn!("IsBot reverse lookup of {ip} failed: {e}");
+            return false;
+        }
+        Err(e) => {
+            warn!("IsBot reverse lookup of {ip} failed: {e}");
+            return false;
+        }
+    };
+
+    if !domains.iter().any(|domain| hostname.ends_with(domain)) {
+        return false;
+    }
+
+    match tokio::net::lookup_host((hostname.as_str(), 0)).await {
+        Ok(mut addrs) => addrs.any(|addr| addr.ip() == ip),
+        Err(e) => {
+            warn!("IsBot forward lookup of {hostname} failed: {e}");
+            false
+        }
+    }
+}
+
+/// `bot` comes from the user agent, `verified_crawler` is only resolved when
+/// `Config::verify_crawlers` is enabled because it costs two DNS lookups.
+#[derive(Clone, Debug, Default)]
+pub struct IsBot {
+    pub bot: bool,
+    pub verified_crawler: bool,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IsBot
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let bot = is_bot_user_agent(&parts.headers);
+        let Extension(ctx) = parts
+            .extract::<Extension<Context>>()
+            .await
+            .expect("Context not configured correctly");
+
+        let verify_crawlers = ctx.config.verify_crawlers;
+
+        if !bot || !verify_crawlers {
+            return Ok(Self {
+                bot,
+                verified_crawler: false,
+            });
+        }
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+
+        let verified_crawler = match ClientIp::from_request_parts(parts, state).await {
+            Ok(ClientIp(Some(ip))) => verify_crawler(&user_agent, ip).await,
+            _ => false,
+        };
+
+        Ok(Self {
+            bot,
+            verified_crawler,
+        })
+    }
+}
@@ -0,0 +1,420 @@
+use anyhow::{bail, Result};
+use axum::async_trait;
+use evento::{store::Event, Command, CommandError, ConsumerContext, RuleHandler};
+use futures_util::{stream, StreamExt};
+use serde::Deserialize;
+use starter_feed::IngestExternalEventInput;
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+use tracing::{error, warn};
+use ulid::Ulid;
+
+use crate::config::BridgeConfig;
+
+/// Wait before ingesting again once the bus failed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wait before polling the Kafka REST proxy again once it had no records.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Event bus the events are republished to and the external events read
+/// from.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Publishes `payload` on `topic`, `id` being unique to the event and
+    /// `key` keeping the events of an aggregate in order.
+    async fn publish(
+        &self,
+        topic: &str,
+        key: &str,
+        id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<()>;
+
+    /// Records the messages of `topics` as `external` events as they come,
+    /// the processes of `group` sharing them, until the bus fails.
+    async fn ingest(&self, topics: &[String], group: &str, command: &Command) -> Result<()>;
+
+    /// Reaches the bus, for `check_config`.
+    async fn health(&self) -> Result<()>;
+}
+
+/// Republishes the events to the topics of `BridgeConfig::topics` and
+/// ingests the ones of `BridgeConfig::ingest`, doing nothing while the
+/// transport is `none`.
+#[derive(Clone)]
+pub struct Bridge {
+    transport: Option<Arc<dyn Transport>>,
+    config: BridgeConfig,
+}
+
+impl Bridge {
+    pub async fn open(config: &BridgeConfig) -> Result<Self> {
+        let transport: Option<Arc<dyn Transport>> = match config.transport.as_str() {
+            "none" => None,
+            "nats" => Some(Arc::new(NatsTransport::connect(config).await?)),
+            "kafka" => Some(Arc::new(KafkaTransport::new(config)?)),
+            transport => bail!("bridge transport {transport} is not none, nats or kafka"),
+        };
+
+        Ok(Self {
+            transport,
+            config: config.clone(),
+        })
+    }
+
+    /// Topic of the events named `name` of the aggregates of `aggregate`,
+    /// configured for the event itself, like `feed/created`, or for all the
+    /// events of the aggregate, like `feed/*`.
+    pub fn topic(&self, aggregate: &str, name: &str) -> Option<&str> {
+        let topics = &self.config.topics;
+
+        topics
+            .get(&format!("{aggregate}/{name}"))
+            .or_else(|| topics.get(&format!("{aggregate}/*")))
+            .map(String::as_str)
+    }
+
+    pub async fn health(&self) -> Result<()> {
+        match &self.transport {
+            Some(transport) => transport.health().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Ingests `BridgeConfig::ingest` in the background, starting over after
+    /// `RETRY_INTERVAL` whenever the bus fails.
+    pub fn spawn_ingest(&self, command: Command) {
+        let Some(transport) = self.transport.clone() else {
+            return;
+        };
+
+        if self.config.ingest.is_empty() {
+            return;
+        }
+
+        let topics = self.config.ingest.to_owned();
+        let group = self.config.group.to_owned();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = transport.ingest(&topics, &group, &command).await {
+                    error!("bridge ingest failed: {err}");
+                }
+
+                sleep(RETRY_INTERVAL).await;
+            }
+        });
+    }
+}
+
+/// Records the message `id` of `subject`, messages refused by the command
+/// being logged and skipped so that they don't hold the others back.
+async fn receive(
+    command: &Command,
+    id: String,
+    subject: String,
+    payload: serde_json::Value,
+) -> Result<()> {
+    let input = IngestExternalEventInput {
+        id,
+        subject,
+        payload,
+        request_id: None,
+    };
+
+    match command.execute("en".to_owned(), &input).await {
+        Ok(_) => Ok(()),
+        Err(CommandError::Server(err)) => bail!("{err}"),
+        Err(err) => {
+            warn!("external event {} skipped: {err:?}", input.id);
+
+            Ok(())
+        }
+    }
+}
+
+/// Events of NATS, the subjects being the topics. Messages are received
+/// once per `Nats-Msg-Id` header, the ones without it each time they are
+/// delivered.
+pub struct NatsTransport {
+    client: async_nats::Client,
+}
+
+impl NatsTransport {
+    pub async fn connect(config: &BridgeConfig) -> Result<Self> {
+        let Some(url) = &config.url else {
+            bail!("bridge.url is required by nats");
+        };
+
+        Ok(Self {
+            client: async_nats::connect(url.as_str()).await?,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for NatsTransport {
+    async fn publish(
+        &self,
+        topic: &str,
+        _key: &str,
+        id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", id);
+
+        self.client
+            .publish_with_headers(
+                topic.to_owned(),
+                headers,
+                serde_json::to_vec(payload)?.into(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn ingest(&self, topics: &[String], group: &str, command: &Command) -> Result<()> {
+        let mut subscribers = vec![];
+
+        for topic in topics {
+            subscribers.push(
+                self.client
+                    .queue_subscribe(topic.to_owned(), group.to_owned())
+                    .await?,
+            );
+        }
+
+        let mut messages = stream::select_all(subscribers);
+
+        while let Some(message) = messages.next().await {
+            let id = message
+                .headers
+                .as_ref()
+                .and_then(|headers| headers.get("Nats-Msg-Id"))
+                .map(|id| id.as_str().to_owned())
+                .unwrap_or_else(|| Ulid::new().to_string());
+
+            let payload = serde_json::from_slice(&message.payload).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&message.payload).into_owned())
+            });
+
+            receive(command, id, message.subject.to_string(), payload).await?;
+        }
+
+        bail!("nats subscriptions ended")
+    }
+
+    async fn health(&self) -> Result<()> {
+        match self.client.connection_state() {
+            async_nats::connection::State::Connected => Ok(()),
+            state => bail!("nats is {state:?}"),
+        }
+    }
+}
+
+/// Events of Kafka through a Confluent REST proxy at `BridgeConfig::url`,
+/// json encoded. Messages are received once per topic, partition and
+/// offset, the offsets being committed once a batch is recorded.
+pub struct KafkaTransport {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct KafkaConsumer {
+    base_uri: String,
+}
+
+#[derive(Deserialize)]
+struct KafkaRecord {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    value: serde_json::Value,
+}
+
+impl KafkaTransport {
+    pub fn new(config: &BridgeConfig) -> Result<Self> {
+        let Some(url) = &config.url else {
+            bail!("bridge.url is required by kafka");
+        };
+
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+            url: url.trim_end_matches('/').to_owned(),
+        })
+    }
+
+    async fn consume(&self, base_uri: &str, topics: &[String], command: &Command) -> Result<()> {
+        self.client
+            .post(format!("{base_uri}/subscription"))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/vnd.kafka.v2+json",
+            )
+            .body(serde_json::to_vec(
+                &serde_json::json!({ "topics": topics }),
+            )?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        loop {
+            let body = self
+                .client
+                .get(format!("{base_uri}/records"))
+                .header(
+                    reqwest::header::ACCEPT,
+                    "application/vnd.kafka.json.v2+json",
+                )
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?;
+
+            let records = serde_json::from_str::<Vec<KafkaRecord>>(&body)?;
+
+            if records.is_empty() {
+                sleep(POLL_INTERVAL).await;
+                continue;
+            }
+
+            for record in records {
+                let id = format!("{}-{}-{}", record.topic, record.partition, record.offset);
+
+                receive(command, id, record.topic, record.value).await?;
+            }
+
+            // Without a body, the offsets of all the records fetched are
+            // committed.
+            self.client
+                .post(format!("{base_uri}/offsets"))
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    "application/vnd.kafka.v2+json",
+                )
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for KafkaTransport {
+    async fn publish(
+        &self,
+        topic: &str,
+        key: &str,
+        _id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<()> {
+        let body = serde_json::json!({
+            "records": [{ "key": key, "value": payload }],
+        });
+
+        self.client
+            .post(format!("{}/topics/{topic}", self.url))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/vnd.kafka.json.v2+json",
+            )
+            .body(serde_json::to_vec(&body)?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn ingest(&self, topics: &[String], group: &str, command: &Command) -> Result<()> {
+        let body = serde_json::json!({
+            "name": format!("{group}-{}", Ulid::new()),
+            "format": "json",
+            "auto.offset.reset": "earliest",
+            "auto.commit.enable": "false",
+        });
+
+        let text = self
+            .client
+            .post(format!("{}/consumers/{group}", self.url))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/vnd.kafka.v2+json",
+            )
+            .body(serde_json::to_vec(&body)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let consumer = serde_json::from_str::<KafkaConsumer>(&text)?;
+        let result = self.consume(&consumer.base_uri, topics, command).await;
+
+        // The proxy drops idle instances on its own, removing it only frees
+        // its partitions sooner.
+        if let Err(err) = self.client.delete(&consumer.base_uri).send().await {
+            warn!("kafka consumer not removed: {err}");
+        }
+
+        result
+    }
+
+    async fn health(&self) -> Result<()> {
+        self.client
+            .get(format!("{}/topics", self.url))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Republishes the events of the aggregates it is registered for as json,
+/// with their data and metadata as stored, to the topics of
+/// `BridgeConfig::topics`. Events of no topic are left out.
+pub struct EventBridge;
+
+#[async_trait]
+impl RuleHandler for EventBridge {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let bridge = ctx.extract::<Bridge>();
+
+        let Some(transport) = &bridge.transport else {
+            return Ok(());
+        };
+
+        let aggregate = event
+            .aggregate_id
+            .split_once('/')
+            .map(|(aggregate, _)| aggregate)
+            .unwrap_or_default();
+
+        let Some(topic) = bridge.topic(aggregate, &event.name) else {
+            return Ok(());
+        };
+
+        let id = event.id.to_string();
+        let payload = serde_json::json!({
+            "id": id,
+            "name": event.name,
+            "aggregate_id": event.aggregate_id,
+            "version": event.version,
+            "data": event.data,
+            "metadata": event.metadata,
+            "created_at": event.created_at,
+        });
+
+        transport
+            .publish(topic, &event.aggregate_id, &id, &payload)
+            .await
+    }
+}
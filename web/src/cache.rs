@@ -0,0 +1,149 @@
+use redis::{aio::ConnectionManager, AsyncCommands, FromRedisValue, ToRedisArgs};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use crate::i18n::LANGUAGES;
+
+/// Entries kept in memory, the expired ones being dropped once it is reached
+/// and then, if need be, the half expiring first.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Rendered html, or any other output `V`, of expensive components keyed by
+/// `{key}:{lang}`, shared by the request `Context` and the consumer rules
+/// invalidating it. Entries are kept in Redis when `Config::redis_url` is
+/// set, so that replicas share them and their invalidations, and in memory
+/// otherwise or while Redis is unreachable.
+#[derive(Clone, Default)]
+pub struct FragmentCache<V = String> {
+    entries: Arc<RwLock<HashMap<String, (Instant, V)>>>,
+    redis: Option<(String, ConnectionManager)>,
+}
+
+impl<V: Clone + ToRedisArgs + FromRedisValue + Send + Sync> FragmentCache<V> {
+    /// Cache kept in Redis under the `{namespace}:` prefix, or in memory
+    /// without `redis`.
+    pub fn new(namespace: impl Into<String>, redis: Option<ConnectionManager>) -> Self {
+        Self {
+            entries: Default::default(),
+            redis: redis.map(|redis| (namespace.into(), redis)),
+        }
+    }
+
+    pub async fn get(&self, key: &str, lang: &str) -> Option<V> {
+        let key = format!("{key}:{lang}");
+
+        if let Some((namespace, redis)) = &self.redis {
+            match redis
+                .clone()
+                .get::<_, Option<V>>(format!("{namespace}:{key}"))
+                .await
+            {
+                Ok(value) => return value,
+                Err(err) => warn!("fragment cache falls back to memory: {err}"),
+            }
+        }
+
+        let entries = self.entries.read().ok()?;
+        let (expires_at, value) = entries.get(&key)?;
+
+        if *expires_at < Instant::now() {
+            return None;
+        }
+
+        Some(value.clone())
+    }
+
+    pub async fn set(&self, key: &str, lang: &str, value: V, ttl: Duration) {
+        let key = format!("{key}:{lang}");
+
+        if let Some((namespace, redis)) = &self.redis {
+            let ttl = ttl.as_millis().try_into().unwrap_or(u64::MAX).max(1);
+
+            match redis
+                .clone()
+                .pset_ex::<_, _, ()>(format!("{namespace}:{key}"), &value, ttl)
+                .await
+            {
+                Ok(()) => return,
+                Err(err) => warn!("fragment cache falls back to memory: {err}"),
+            }
+        }
+
+        let Ok(mut entries) = self.entries.write() else {
+            return;
+        };
+
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+
+            if entries.len() >= MAX_ENTRIES {
+                let mut expiries = entries
+                    .values()
+                    .map(|(expires_at, _)| *expires_at)
+                    .collect::<Vec<_>>();
+                let half = expiries.len() / 2;
+                let (_, cutoff, _) = expiries.select_nth_unstable(half);
+                let cutoff = *cutoff;
+
+                entries.retain(|_, (expires_at, _)| *expires_at > cutoff);
+            }
+        }
+
+        entries.insert(key, (Instant::now() + ttl, value));
+    }
+
+    /// Removes `key` in `lang` only.
+    pub async fn remove(&self, key: &str, lang: &str) {
+        let key = format!("{key}:{lang}");
+
+        if let Some((namespace, redis)) = &self.redis {
+            if let Err(err) = redis
+                .clone()
+                .del::<_, ()>(format!("{namespace}:{key}"))
+                .await
+            {
+                warn!("fragment cache falls back to memory: {err}");
+            }
+        }
+
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(&key);
+        }
+    }
+
+    /// Removes `key` in every language.
+    pub async fn invalidate(&self, key: &str) {
+        if let Some((namespace, redis)) = &self.redis {
+            let keys = LANGUAGES
+                .iter()
+                .map(|lang| format!("{namespace}:{key}:{lang}"))
+                .collect::<Vec<_>>();
+
+            if let Err(err) = redis.clone().del::<_, ()>(keys).await {
+                warn!("fragment cache falls back to memory: {err}");
+            }
+        }
+
+        // Entries may have been set in memory while Redis was unreachable.
+        let prefix = format!("{key}:");
+
+        if let Ok(mut entries) = self.entries.write() {
+            entries.retain(|entry_key, _| !entry_key.starts_with(&prefix));
+        }
+    }
+}
+
+/// Html returned by `Context::cached`, rendered with `{{ component|safe }}`.
+pub struct Cached(pub String);
+
+impl fmt::Display for Cached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
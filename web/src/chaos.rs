@@ -0,0 +1,107 @@
+use anyhow::{bail, Result};
+use askama_axum::Response;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::StatusCode,
+    middleware::Next,
+    Extension,
+};
+use evento::{store::Event, ConsumerContext, Rule, RuleHandler};
+use rand::Rng;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::{
+    config::{ChaosConfig, Config},
+    context::Context,
+    maintenance::is_probe,
+};
+
+/// Name of the rule of `rule`, its failures showing in `/admin/dead-letters`
+/// under it.
+pub const CHAOS_RULE: &str = "chaos";
+
+/// Whether a fault of `rate` is injected this time.
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+}
+
+/// Answers a 500 to `ChaosConfig::error_rate` of the requests, the
+/// `is_probe` routes and the static files being served as usual so that the
+/// instance stays in rotation.
+pub async fn chaos(Extension(ctx): Extension<Context>, req: Request, next: Next) -> Response {
+    if !ctx.config.chaos.is_enabled() {
+        return next.run(req).await;
+    }
+
+    let path = req.uri().path();
+    let path = ctx
+        .config
+        .base_url
+        .as_deref()
+        .and_then(|base_url| path.strip_prefix(base_url))
+        .unwrap_or(path);
+
+    if is_probe(path) || path.starts_with("/static/") {
+        return next.run(req).await;
+    }
+
+    if !roll(ctx.config.chaos.error_rate) {
+        return next.run(req).await;
+    }
+
+    warn!("chaos: 500 on {path}");
+
+    let (mut parts, _) = req.into_parts();
+
+    match Context::from_request_parts(&mut parts, &()).await {
+        Ok(ctx) => ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(response) => response,
+    }
+}
+
+/// Waits `ChaosConfig::db_delay` before `ChaosConfig::db_delay_rate` of the
+/// commands and queries.
+pub async fn delay(chaos: &ChaosConfig) {
+    if chaos.is_enabled() && roll(chaos.db_delay_rate) {
+        warn!("chaos: database delayed by {}ms", chaos.db_delay);
+
+        tokio::time::sleep(Duration::from_millis(chaos.db_delay)).await;
+    }
+}
+
+/// Whether a live update is dropped, at `ChaosConfig::drop_publish_rate`.
+pub fn drop_publish(chaos: &ChaosConfig) -> bool {
+    chaos.is_enabled() && roll(chaos.drop_publish_rate)
+}
+
+/// Rule failing or panicking on the events of every feed, registered by
+/// `start` only while chaos is enabled. The handlers of the other rules are
+/// left as they are, their retries and dead letters being the same.
+pub fn rule() -> Rule {
+    Rule::new(CHAOS_RULE).handler("feed/**", ChaosHandler)
+}
+
+pub struct ChaosHandler;
+
+#[async_trait]
+impl RuleHandler for ChaosHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let chaos = ctx.extract::<Config>().chaos;
+
+        if !chaos.is_enabled() {
+            return Ok(());
+        }
+
+        if roll(chaos.rule_panic_rate) {
+            panic!("chaos: panic on {} of {}", event.name, event.aggregate_id);
+        }
+
+        if roll(chaos.rule_error_rate) {
+            bail!("chaos: failed {} of {}", event.name, event.aggregate_id);
+        }
+
+        Ok(())
+    }
+}
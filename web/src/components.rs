@@ -0,0 +1,39 @@
+mod avatar;
+mod breadcrumbs;
+mod comment_section;
+mod data_table;
+mod diff;
+mod error_boundary;
+mod link_preview;
+mod markdown;
+mod modal;
+mod paginator;
+mod pinned_badge;
+mod popular_tags;
+mod progress_bar;
+mod reaction_picker;
+mod skeleton;
+mod tag_cloud;
+mod trending_feeds;
+mod upload_field;
+mod wizard_steps;
+
+pub use avatar::*;
+pub use breadcrumbs::*;
+pub use comment_section::*;
+pub use data_table::*;
+pub use diff::*;
+pub use error_boundary::*;
+pub use link_preview::*;
+pub use markdown::*;
+pub use modal::*;
+pub use paginator::*;
+pub use pinned_badge::*;
+pub use popular_tags::*;
+pub use progress_bar::*;
+pub use reaction_picker::*;
+pub use skeleton::*;
+pub use tag_cloud::*;
+pub use trending_feeds::*;
+pub use upload_field::*;
+pub use wizard_steps::*;
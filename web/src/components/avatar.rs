@@ -0,0 +1,39 @@
+use askama::Template;
+use std::fmt;
+
+/// Initials of `name` over a background color derived from `seed`, so the
+/// same user always gets the same avatar. Rendered with `{{ avatar|safe }}`
+/// or straight from a template:
+///
+/// ```ignore
+/// {{ crate::components::Avatar::new(feed.node.author, feed.node.user_id)|safe }}
+/// ```
+#[derive(Template)]
+#[template(path = "components/avatar.html")]
+pub struct Avatar {
+    name: String,
+    initials: String,
+    hue: u32,
+}
+
+impl Avatar {
+    pub fn new(name: &str, seed: impl fmt::Display) -> Self {
+        let initials = name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect();
+
+        // fnv-1a, stable across builds unlike the std hasher
+        let hue = seed.to_string().bytes().fold(0x811c9dc5u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+        }) % 360;
+
+        Self {
+            name: name.to_owned(),
+            initials,
+            hue,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use askama::Template;
+
+use crate::{context::Context, pages::route_meta};
+
+pub struct Crumb {
+    title: String,
+    url: Option<String>,
+}
+
+/// Trail from the root page to the route `name` following `RouteMeta::parent`,
+/// rendered with `{{ breadcrumbs|safe }}`.
+#[derive(Template)]
+#[template(path = "components/breadcrumbs.html")]
+pub struct Breadcrumbs {
+    crumbs: Vec<Crumb>,
+}
+
+impl Breadcrumbs {
+    /// `params` replace the `:name` segments of every path of the trail,
+    /// `title` replaces the localized title of the current page.
+    pub fn new(ctx: &Context, name: &str, params: &[(&str, &str)], title: Option<String>) -> Self {
+        let mut crumbs = vec![];
+        let mut current = route_meta(name);
+
+        while let Some(route) = current {
+            let path = params
+                .iter()
+                .fold(route.path.to_owned(), |path, (key, value)| {
+                    path.replace(&format!(":{key}"), value)
+                });
+
+            crumbs.push(Crumb {
+                title: ctx.t(route.title),
+                url: Some(ctx.create_url(path.trim_end_matches('/'))),
+            });
+
+            current = route.parent.and_then(route_meta);
+        }
+
+        crumbs.reverse();
+
+        if let Some(last) = crumbs.last_mut() {
+            last.url = None;
+
+            if let Some(title) = title {
+                last.title = title;
+            }
+        }
+
+        Self { crumbs }
+    }
+}
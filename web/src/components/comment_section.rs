@@ -0,0 +1,113 @@
+use askama::Template;
+use i18n_embed_fl::fl;
+use starter_feed::UserComment;
+
+use crate::context::Context;
+
+/// Replies deeper than this are shown at this depth.
+const MAX_DEPTH: usize = 4;
+
+pub struct CommentItem {
+    comment: UserComment,
+    depth: usize,
+    own: bool,
+}
+
+pub struct CommentSectionFl {
+    title: String,
+    submit: String,
+    reply: String,
+    edit: String,
+    delete: String,
+    delete_confirm: String,
+    deleted: String,
+    edited: String,
+    empty: String,
+}
+
+/// Threaded comments of a feed, refreshed when its `feed-{id}` event is
+/// received on the `comments` pikav topic.
+#[derive(Template)]
+#[template(path = "components/comment_section.html")]
+pub struct CommentSection {
+    ctx: Context,
+    feed_id: String,
+    url: String,
+    items: Vec<CommentItem>,
+    fl: CommentSectionFl,
+}
+
+fn push_thread(
+    items: &mut Vec<CommentItem>,
+    comments: &[UserComment],
+    parent_id: Option<&str>,
+    depth: usize,
+    user_id: Option<&str>,
+) {
+    for comment in comments
+        .iter()
+        .filter(|comment| comment.parent_id.as_deref() == parent_id)
+    {
+        items.push(CommentItem {
+            own: user_id == Some(comment.user_id.to_string().as_str()),
+            comment: comment.clone(),
+            depth: depth.min(MAX_DEPTH),
+        });
+
+        push_thread(items, comments, Some(&comment.id), depth + 1, user_id);
+    }
+}
+
+impl CommentSection {
+    pub fn new(ctx: &Context, feed_id: impl Into<String>, comments: Vec<UserComment>) -> Self {
+        let feed_id = feed_id.into();
+        let mut items = vec![];
+
+        push_thread(&mut items, &comments, None, 0, ctx.user_id.as_deref());
+
+        Self {
+            ctx: ctx.clone(),
+            url: ctx.create_url(format!("/feed/{feed_id}/comments")),
+            feed_id,
+            items,
+            fl: CommentSectionFl {
+                title: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_title"
+                ),
+                submit: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_submit"
+                ),
+                reply: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_reply"
+                ),
+                edit: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_edit"
+                ),
+                delete: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_delete"
+                ),
+                delete_confirm: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_delete_confirm"
+                ),
+                deleted: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_deleted"
+                ),
+                edited: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_edited"
+                ),
+                empty: fl!(
+                    ctx.fl_loader(),
+                    "components_comment_section-CommentSection_empty"
+                ),
+            },
+        }
+    }
+}
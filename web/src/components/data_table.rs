@@ -0,0 +1,208 @@
+use askama::Template;
+use i18n_embed_fl::fl;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            SortDirection::Asc => SortDirection::Desc,
+            SortDirection::Desc => SortDirection::Asc,
+        }
+    }
+}
+
+/// Sorting, filtering and pagination of a `DataTable`, extracted with
+/// `Query<TableQuery>`.
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct TableQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub dir: SortDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+}
+
+impl TableQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size.unwrap_or(20).clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> i64 {
+        ((self.page() - 1) * self.size()).into()
+    }
+
+    pub fn search(&self) -> Option<&str> {
+        self.q
+            .as_deref()
+            .map(|q| q.trim())
+            .filter(|q| !q.is_empty())
+    }
+
+    /// Column to sort by, only one of `allowed` so it can be pushed to sql.
+    pub fn sort_column<'a>(&self, allowed: &[&'a str], default: &'a str) -> &'a str {
+        self.sort
+            .as_deref()
+            .and_then(|sort| allowed.iter().find(|column| **column == sort))
+            .copied()
+            .unwrap_or(default)
+    }
+
+    fn to_query_string(&self) -> String {
+        serde_urlencoded::to_string(self).unwrap_or_default()
+    }
+}
+
+pub struct Column {
+    pub key: &'static str,
+    pub title: String,
+    pub sortable: bool,
+}
+
+impl Column {
+    pub fn new(key: &'static str, title: impl Into<String>) -> Self {
+        Self {
+            key,
+            title: title.into(),
+            sortable: false,
+        }
+    }
+
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+}
+
+pub struct HeaderCell {
+    title: String,
+    url: Option<String>,
+    sort: Option<&'static str>,
+}
+
+pub struct DataTableFl {
+    search: String,
+    empty: String,
+    previous: String,
+    next: String,
+    page: String,
+}
+
+/// Table sorted, filtered and paginated by the server from a `TableQuery`,
+/// rendered with `{{ table|safe }}`.
+#[derive(Template)]
+#[template(path = "components/data_table.html")]
+pub struct DataTable {
+    action: String,
+    headers: Vec<HeaderCell>,
+    rows: Vec<Vec<String>>,
+    query: TableQuery,
+    previous_url: Option<String>,
+    next_url: Option<String>,
+    fl: DataTableFl,
+}
+
+impl DataTable {
+    pub fn new(
+        ctx: &Context,
+        uri: &str,
+        columns: Vec<Column>,
+        rows: Vec<Vec<String>>,
+        total: i64,
+        query: &TableQuery,
+    ) -> Self {
+        let create_url =
+            |query: TableQuery| ctx.create_url(format!("{uri}?{}", query.to_query_string()));
+
+        let headers = columns
+            .into_iter()
+            .map(|column| {
+                let active = query.sort.as_deref() == Some(column.key);
+                let url = column.sortable.then(|| {
+                    create_url(TableQuery {
+                        sort: Some(column.key.to_owned()),
+                        dir: if active {
+                            query.dir.toggle()
+                        } else {
+                            SortDirection::Asc
+                        },
+                        page: None,
+                        ..query.clone()
+                    })
+                });
+
+                HeaderCell {
+                    title: column.title,
+                    url,
+                    sort: active.then(|| match query.dir {
+                        SortDirection::Asc => "ascending",
+                        SortDirection::Desc => "descending",
+                    }),
+                }
+            })
+            .collect();
+
+        let page = query.page();
+        let pages = ((total.max(0) as u32) + query.size() - 1) / query.size();
+
+        let previous_url = (page > 1).then(|| {
+            create_url(TableQuery {
+                page: Some(page - 1),
+                ..query.clone()
+            })
+        });
+
+        let next_url = (page < pages).then(|| {
+            create_url(TableQuery {
+                page: Some(page + 1),
+                ..query.clone()
+            })
+        });
+
+        Self {
+            action: ctx.create_url(uri),
+            headers,
+            rows,
+            query: query.clone(),
+            previous_url,
+            next_url,
+            fl: DataTableFl {
+                search: fl!(ctx.fl_loader(), "components_data_table-DataTable_search"),
+                empty: fl!(ctx.fl_loader(), "components_data_table-DataTable_empty"),
+                previous: fl!(ctx.fl_loader(), "components_data_table-DataTable_previous"),
+                next: fl!(ctx.fl_loader(), "components_data_table-DataTable_next"),
+                page: fl!(
+                    ctx.fl_loader(),
+                    "components_data_table-DataTable_page",
+                    page = page,
+                    pages = pages.max(1)
+                ),
+            },
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use askama::Template;
+
+#[derive(PartialEq)]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+pub struct DiffLine {
+    kind: DiffKind,
+    text: String,
+}
+
+/// Line by line difference between two texts, from their longest common
+/// subsequence of lines.
+#[derive(Template)]
+#[template(path = "components/diff.html")]
+pub struct Diff {
+    lines: Vec<DiffLine>,
+}
+
+impl Diff {
+    pub fn new(old: &str, new: &str) -> Self {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+
+        // lengths[i][j] is the longest common subsequence of old[i..] and new[j..]
+        let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lengths[i][j] = if old[i] == new[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let mut lines = vec![];
+        let (mut i, mut j) = (0, 0);
+        let line = |kind, text: &str| DiffLine {
+            kind,
+            text: text.to_owned(),
+        };
+
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                lines.push(line(DiffKind::Same, old[i]));
+                i += 1;
+                j += 1;
+            } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+                lines.push(line(DiffKind::Removed, old[i]));
+                i += 1;
+            } else {
+                lines.push(line(DiffKind::Added, new[j]));
+                j += 1;
+            }
+        }
+
+        lines.extend(old[i..].iter().map(|text| line(DiffKind::Removed, text)));
+        lines.extend(new[j..].iter().map(|text| line(DiffKind::Added, text)));
+
+        Self { lines }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.kind == DiffKind::Same)
+    }
+}
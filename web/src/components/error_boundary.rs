@@ -0,0 +1,66 @@
+use askama::Template;
+use askama_axum::Response;
+use i18n_embed_fl::fl;
+use std::fmt;
+
+use crate::context::Context;
+
+pub struct ErrorCardFl {
+    title: String,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/error_card.html")]
+pub struct ErrorCard {
+    fl: ErrorCardFl,
+}
+
+impl ErrorCard {
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            fl: ErrorCardFl {
+                title: fl!(ctx.fl_loader(), "components_error_boundary-ErrorCard_title"),
+                content: fl!(
+                    ctx.fl_loader(),
+                    "components_error_boundary-ErrorCard_content"
+                ),
+            },
+        }
+    }
+}
+
+/// Renders `T` or an inline error card when building it failed, so a widget
+/// doesn't fail the whole page. Rendered with `{{ widget|safe }}`.
+///
+/// ```ignore
+/// let popular_tags = ctx
+///     .query(ListPopularTagsInput)
+///     .await
+///     .map(|tags| PopularTags::new(&ctx, tags));
+///
+/// ErrorBoundary::new(&ctx, popular_tags)
+/// ```
+pub enum ErrorBoundary<T> {
+    Ok(T),
+    Err(ErrorCard),
+}
+
+impl<T> ErrorBoundary<T> {
+    /// The error response is dropped, `Context::query` already logged it.
+    pub fn new(ctx: &Context, result: Result<T, Response>) -> Self {
+        match result {
+            Ok(component) => Self::Ok(component),
+            Err(_) => Self::Err(ErrorCard::new(ctx)),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ErrorBoundary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok(component) => component.fmt(f),
+            Self::Err(card) => card.render_into(f).map_err(|_| fmt::Error),
+        }
+    }
+}
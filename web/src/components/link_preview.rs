@@ -0,0 +1,23 @@
+use askama::Template;
+use starter_feed::LinkPreview;
+
+/// Card of the page the first link of a feed points to:
+///
+/// ```ignore
+/// {% if let Some(preview) = feed.preview %}
+/// {{ crate::components::LinkPreviewCard::new(preview.0)|safe }}
+/// {% endif %}
+/// ```
+#[derive(Template)]
+#[template(path = "components/link_preview.html")]
+pub struct LinkPreviewCard {
+    preview: LinkPreview,
+}
+
+impl LinkPreviewCard {
+    pub fn new(preview: &LinkPreview) -> Self {
+        Self {
+            preview: preview.clone(),
+        }
+    }
+}
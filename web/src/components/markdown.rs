@@ -0,0 +1,115 @@
+use ammonia::Builder;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, Event, LinkType, Options, Parser, Tag, TagEnd};
+use starter_feed::find_mentions;
+use std::{collections::HashSet, fmt};
+use uuid::Uuid;
+
+static SANITIZER: Lazy<Builder<'static>> = Lazy::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .add_tag_attributes("code", &["class"])
+        .attribute_filter(|element, attribute, value| {
+            if element == "code" && attribute == "class" && !value.starts_with("language-") {
+                return None;
+            }
+
+            Some(value.into())
+        })
+        .url_schemes(HashSet::from(["http", "https", "mailto"]));
+
+    builder
+});
+
+/// Markdown rendered to sanitized html, code blocks keep their
+/// `language-*` class for syntax highlighting. Rendered with
+/// `{{ markdown|safe }}`.
+pub struct Markdown(String);
+
+fn options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    options
+}
+
+/// Label of the mentions of `user_id`, the start of their id.
+pub fn mention_handle(user_id: &Uuid) -> String {
+    format!("@{}", &user_id.to_string()[..8])
+}
+
+/// Splits `text` around its mentions, each one becoming a link to the
+/// profile of the user labelled with their handle.
+fn mention_events(text: &str, profile_url: &impl Fn(Uuid) -> String) -> Vec<Event<'static>> {
+    let mut events = vec![];
+    let mut last = 0;
+
+    for (range, user_id) in find_mentions(text) {
+        if range.start > last {
+            events.push(Event::Text(text[last..range.start].to_owned().into()));
+        }
+
+        events.push(Event::Start(Tag::Link {
+            link_type: LinkType::Inline,
+            dest_url: profile_url(user_id).into(),
+            title: "".into(),
+            id: "".into(),
+        }));
+        events.push(Event::Text(mention_handle(&user_id).into()));
+        events.push(Event::End(TagEnd::Link));
+
+        last = range.end;
+    }
+
+    if last < text.len() {
+        events.push(Event::Text(text[last..].to_owned().into()));
+    }
+
+    events
+}
+
+impl Markdown {
+    pub fn new(source: &str) -> Self {
+        Self::render(Parser::new_ext(source, options()))
+    }
+
+    /// Same as `new`, rendering mentions outside of links and code blocks as
+    /// links to `profile_url`.
+    pub fn with_mentions(source: &str, profile_url: impl Fn(Uuid) -> String) -> Self {
+        let mut in_link = false;
+        let mut in_code_block = false;
+
+        let events = Parser::new_ext(source, options()).flat_map(|event| {
+            match &event {
+                Event::Start(Tag::Link { .. }) => in_link = true,
+                Event::End(TagEnd::Link) => in_link = false,
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Text(text) if !in_link && !in_code_block => {
+                    return mention_events(text, &profile_url);
+                }
+                _ => {}
+            }
+
+            vec![event]
+        });
+
+        Self::render(events)
+    }
+
+    fn render<'a>(events: impl Iterator<Item = Event<'a>>) -> Self {
+        let mut unsafe_html = String::new();
+        html::push_html(&mut unsafe_html, events);
+
+        Self(SANITIZER.clean(&unsafe_html).to_string())
+    }
+}
+
+impl fmt::Display for Markdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,32 @@
+use askama::Template;
+use i18n_embed_fl::fl;
+use std::fmt;
+
+use crate::context::Context;
+
+/// Dialog wrapping a server rendered fragment, such as an edit form or a
+/// delete confirmation. Htmx requests get the dialog alone, swapped into the
+/// layout `#modal` container, others get it opened on top of the layout.
+///
+/// ```ignore
+/// Modal::new(ctx.context(), ctx.t("pages_index-NewFeedModal_title"), form)
+/// ```
+#[derive(Template)]
+#[template(path = "components/modal.html")]
+pub struct Modal {
+    ctx: Context,
+    title: String,
+    body: String,
+    close: String,
+}
+
+impl Modal {
+    pub fn new(ctx: &Context, title: impl Into<String>, body: impl fmt::Display) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            title: title.into(),
+            body: body.to_string(),
+            close: fl!(ctx.fl_loader(), "components_modal-Modal_close"),
+        }
+    }
+}
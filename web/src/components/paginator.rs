@@ -0,0 +1,76 @@
+use askama::Template;
+use evento_query::PageInfo;
+use i18n_embed_fl::fl;
+
+use crate::context::Context;
+
+const PAGE_SIZES: [u16; 3] = [10, 20, 50];
+
+pub struct PaginatorFl {
+    previous: String,
+    next: String,
+    page_size: String,
+}
+
+pub struct PageSizeLink {
+    size: u16,
+    url: String,
+    active: bool,
+}
+
+/// Previous/next links of a cursor paginated list, rendered with
+/// `{{ paginator|safe }}`.
+#[derive(Template)]
+#[template(path = "components/paginator.html")]
+pub struct Paginator {
+    previous_url: Option<String>,
+    next_url: Option<String>,
+    page_sizes: Vec<PageSizeLink>,
+    fl: PaginatorFl,
+}
+
+impl Paginator {
+    /// `query` is appended to every link, ex: `&tag=rust`.
+    pub fn new(
+        ctx: &Context,
+        page_info: &PageInfo,
+        uri: impl Into<String>,
+        size: u16,
+        query: impl Into<String>,
+    ) -> Self {
+        let uri = uri.into();
+        let query = query.into();
+
+        let previous_url = page_info
+            .start_cursor
+            .as_ref()
+            .filter(|_| page_info.has_previous_page)
+            .map(|cursor| ctx.create_url(format!("{uri}?last={size}&before={}{query}", cursor.0)));
+
+        let next_url = page_info
+            .end_cursor
+            .as_ref()
+            .filter(|_| page_info.has_next_page)
+            .map(|cursor| ctx.create_url(format!("{uri}?first={size}&after={}{query}", cursor.0)));
+
+        let page_sizes = PAGE_SIZES
+            .iter()
+            .map(|page_size| PageSizeLink {
+                size: *page_size,
+                url: ctx.create_url(format!("{uri}?first={page_size}{query}")),
+                active: *page_size == size,
+            })
+            .collect();
+
+        Self {
+            previous_url,
+            next_url,
+            page_sizes,
+            fl: PaginatorFl {
+                previous: fl!(ctx.fl_loader(), "components_paginator-Paginator_previous"),
+                next: fl!(ctx.fl_loader(), "components_paginator-Paginator_next"),
+                page_size: fl!(ctx.fl_loader(), "components_paginator-Paginator_page_size"),
+            },
+        }
+    }
+}
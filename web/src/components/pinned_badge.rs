@@ -0,0 +1,20 @@
+use askama::Template;
+
+/// Badge of a feed floated above the others by its author or an admin:
+///
+/// ```ignore
+/// {{ crate::components::PinnedBadge::new(ctx.t("pages_index-FeedItem_pinned"))|safe }}
+/// ```
+#[derive(Template)]
+#[template(path = "components/pinned_badge.html")]
+pub struct PinnedBadge {
+    label: String,
+}
+
+impl PinnedBadge {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+        }
+    }
+}
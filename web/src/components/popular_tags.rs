@@ -0,0 +1,20 @@
+use askama::Template;
+use starter_feed::TagCount;
+
+use crate::context::Context;
+
+#[derive(Template)]
+#[template(path = "components/popular_tags.html")]
+pub struct PopularTags {
+    ctx: Context,
+    tags: Vec<TagCount>,
+}
+
+impl PopularTags {
+    pub fn new(ctx: &Context, tags: Vec<TagCount>) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            tags,
+        }
+    }
+}
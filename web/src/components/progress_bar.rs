@@ -0,0 +1,10 @@
+use askama::Template;
+
+/// Progress of a long running task in percent, published over pikav to swap
+/// the previous one.
+#[derive(Template)]
+#[template(path = "components/progress_bar.html")]
+pub struct ProgressBar {
+    pub label: String,
+    pub value: u8,
+}
@@ -0,0 +1,33 @@
+use askama::Template;
+use starter_feed::ReactionCount;
+
+use crate::context::Context;
+
+/// Reactions of a feed toggled with `/_react`, which swaps in the expected
+/// counts right away. Refreshed when its `feed-{id}` event is received on the
+/// `reactions` pikav topic, the parent element has to be connected to it.
+#[derive(Template)]
+#[template(path = "components/reaction_picker.html")]
+pub struct ReactionPicker {
+    feed_id: String,
+    url: String,
+    action: String,
+    reactions: Vec<ReactionCount>,
+    authenticated: bool,
+    csrf_token: String,
+}
+
+impl ReactionPicker {
+    pub fn new(ctx: &Context, feed_id: impl Into<String>, reactions: Vec<ReactionCount>) -> Self {
+        let feed_id = feed_id.into();
+
+        Self {
+            url: ctx.create_url(format!("/_reactions?feed_id={feed_id}")),
+            action: ctx.create_url("/_react"),
+            authenticated: ctx.is_authenticated(),
+            csrf_token: ctx.csrf_token().to_owned(),
+            feed_id,
+            reactions,
+        }
+    }
+}
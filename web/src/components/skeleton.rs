@@ -0,0 +1,9 @@
+use askama::Template;
+
+/// Placeholder of a feed item replaced by the html fetched from `url` once
+/// loaded, published over pikav when a feed is created.
+#[derive(Template)]
+#[template(path = "components/feed_item_skeleton.html")]
+pub struct FeedItemSkeleton {
+    pub url: String,
+}
@@ -0,0 +1,45 @@
+use askama::Template;
+use starter_feed::TagCount;
+
+use crate::context::Context;
+
+const SIZES: [&str; 5] = ["text-xs", "text-sm", "text-base", "text-lg", "text-xl"];
+
+pub struct TagCloudItem {
+    tag: String,
+    url: String,
+    size: &'static str,
+}
+
+/// Tags linking to `/feed/tag/:tag`, sized by how much they are used.
+#[derive(Template)]
+#[template(path = "components/tag_cloud.html")]
+pub struct TagCloud {
+    tags: Vec<TagCloudItem>,
+}
+
+impl TagCloud {
+    pub fn new(ctx: &Context, tags: Vec<TagCount>) -> Self {
+        let max = tags
+            .iter()
+            .map(|tag| tag.total_count)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        let tags = tags
+            .into_iter()
+            .map(|tag| {
+                let level = (tag.total_count.max(0) as usize * (SIZES.len() - 1)) / max as usize;
+
+                TagCloudItem {
+                    url: ctx.create_url(format!("/feed/tag/{}", tag.tag)),
+                    size: SIZES[level],
+                    tag: tag.tag,
+                }
+            })
+            .collect();
+
+        Self { tags }
+    }
+}
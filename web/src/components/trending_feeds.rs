@@ -0,0 +1,21 @@
+use askama::Template;
+use starter_feed::UserFeed;
+
+use crate::context::Context;
+
+/// Titles of the best ranked feeds, linking to the full `/trending` list.
+#[derive(Template)]
+#[template(path = "components/trending_feeds.html")]
+pub struct TrendingFeeds {
+    ctx: Context,
+    feeds: Vec<UserFeed>,
+}
+
+impl TrendingFeeds {
+    pub fn new(ctx: &Context, feeds: Vec<UserFeed>) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            feeds,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use askama::Template;
+use i18n_embed_fl::fl;
+
+use crate::context::Context;
+
+/// Image input sending the selected file to `/_upload` as multipart and
+/// following its progress on the `uploads/{request_id}` pikav topic. The
+/// stored file key is submitted with the parent form as `name`.
+#[derive(Template)]
+#[template(path = "components/upload_field.html")]
+pub struct UploadField {
+    name: String,
+    label: String,
+    action: String,
+    sse_url: String,
+}
+
+impl UploadField {
+    pub fn new(ctx: &Context, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            label: fl!(ctx.fl_loader(), "components_upload_field-UploadField_label"),
+            action: ctx.create_url("/_upload"),
+            sse_url: ctx.create_sse_url("/uploads/"),
+        }
+    }
+}
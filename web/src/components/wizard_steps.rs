@@ -0,0 +1,32 @@
+use askama::Template;
+
+use crate::context::Context;
+
+pub struct WizardStepItem {
+    title: String,
+    done: bool,
+    current: bool,
+}
+
+/// Step indicator of a `Wizard`, rendered with `{{ steps|safe }}`.
+#[derive(Template)]
+#[template(path = "components/wizard_steps.html")]
+pub struct WizardSteps {
+    steps: Vec<WizardStepItem>,
+}
+
+impl WizardSteps {
+    pub fn new(ctx: &Context, steps: &[&str], current: usize) -> Self {
+        Self {
+            steps: steps
+                .iter()
+                .enumerate()
+                .map(|(index, id)| WizardStepItem {
+                    title: ctx.t(id),
+                    done: index <= current,
+                    current: index == current,
+                })
+                .collect(),
+        }
+    }
+}
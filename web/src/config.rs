@@ -1,30 +1,632 @@
+use axum::http::{Method, Uri};
+use axum_extra::extract::cookie::SameSite;
 use config::{ConfigError, Environment, File};
-use serde::Deserialize;
-use std::env;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    env, fs,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+use tokio::{
+    net::{lookup_host, TcpStream},
+    time::timeout,
+};
+use twa_jwks::JwksClient;
+use uuid::Uuid;
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct PikavConfig {
+    /// Empty to serve the live updates in process, kept by `FakePikav`.
     pub url: String,
     pub namespace: String,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MinifyConfig {
+    pub enabled: bool,
+    /// Minifies the streamed pages chunk by chunk as they are sent, instead
+    /// of sending them as is.
+    pub streamed: bool,
+    pub keep_comments: bool,
+    pub keep_closing_tags: bool,
+    pub minify_js: bool,
+    pub minify_css: bool,
+}
+
+impl Default for MinifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: !cfg!(debug_assertions),
+            streamed: true,
+            keep_comments: false,
+            keep_closing_tags: true,
+            minify_js: true,
+            minify_css: true,
+        }
+    }
+}
+
+/// Css custom properties overriding the daisyUI theme, injected in the head
+/// of every page. `primary` is a daisyUI oklch color such as
+/// `"49.12% 0.3096 275.75"`, `tokens` sets any other property by name.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DesignConfig {
+    pub primary: Option<String>,
+    pub radius: Option<String>,
+    pub font: Option<String>,
+    pub tokens: HashMap<String, String>,
+}
+
+impl DesignConfig {
+    fn is_safe(value: &str) -> bool {
+        !value.contains(['<', '>', '{', '}', ';'])
+    }
+
+    pub fn css(&self) -> Option<String> {
+        let mut properties: Vec<(String, &str)> = vec![];
+
+        if let Some(primary) = &self.primary {
+            properties.push(("--p".to_owned(), primary));
+        }
+
+        if let Some(radius) = &self.radius {
+            properties.push(("--rounded-box".to_owned(), radius));
+            properties.push(("--rounded-btn".to_owned(), radius));
+        }
+
+        if let Some(font) = &self.font {
+            properties.push(("--font-family".to_owned(), font));
+        }
+
+        for (name, value) in &self.tokens {
+            properties.push((format!("--{}", name.trim_start_matches('-')), value));
+        }
+
+        let declarations = properties
+            .into_iter()
+            .filter(|(name, value)| Self::is_safe(name) && Self::is_safe(value))
+            .map(|(name, value)| format!("{name}:{value};"))
+            .collect::<String>();
+
+        if declarations.is_empty() {
+            return None;
+        }
+
+        let font = self
+            .font
+            .as_ref()
+            .map(|_| "body{font-family:var(--font-family);}")
+            .unwrap_or_default();
+
+        Some(format!(":root,[data-theme]{{{declarations}}}{font}"))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct UploadConfig {
+    pub dir: String,
+    pub max_size: u64,
+    /// Signs the urls of `/img/:key`, images being served as uploaded while
+    /// it is unset.
+    pub image_secret: Option<String>,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            dir: "uploads".to_owned(),
+            max_size: 10 * 1024 * 1024,
+            image_secret: None,
+        }
+    }
+}
+
+/// Backend keeping uploads and export archives: `local` writes them in
+/// `UploadConfig::dir`, `s3` in `bucket` of S3, or of a compatible service
+/// like MinIO at `endpoint`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: String,
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    /// Read from `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY` when unset.
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    /// MinIO addresses buckets by path rather than by subdomain.
+    pub path_style: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: "local".to_owned(),
+            bucket: "starter".to_owned(),
+            region: "eu-west-3".to_owned(),
+            endpoint: None,
+            access_key_id: None,
+            secret_access_key: None,
+            path_style: false,
+        }
+    }
+}
+
+/// Transport of the emails: `mailbox` keeps them in memory for the
+/// `/admin/mailbox` page, `smtp` sends them through `smtp_url` and `api`
+/// posts them to the `api_url` of a provider with `api_key`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MailConfig {
+    pub transport: String,
+    pub from: String,
+    pub smtp_url: Option<String>,
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: "mailbox".to_owned(),
+            from: "Starter <no-reply@starter.localhost>".to_owned(),
+            smtp_url: None,
+            api_url: None,
+            api_key: None,
+        }
+    }
+}
+
+/// Web Push signed with VAPID, `vapid_private_key` being the base64url
+/// encoded P-256 private key, like the one of `web-push generate-vapid-keys`.
+/// Nothing is pushed when it is unset.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PushConfig {
+    pub vapid_private_key: Option<String>,
+    /// Contact of the site given to the push services, a `mailto:` or an
+    /// `https:` url.
+    pub subject: String,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            vapid_private_key: None,
+            subject: "mailto:admin@starter.localhost".to_owned(),
+        }
+    }
+}
+
+/// Engine of `/search`, `postgres` searching the feeds table itself while
+/// `meilisearch` searches the index `index` of the server at `url`, kept
+/// up to date by a consumer rule.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SearchConfig {
+    pub backend: String,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub index: String,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: "postgres".to_owned(),
+            url: None,
+            api_key: None,
+            index: "feeds".to_owned(),
+        }
+    }
+}
+
+/// Event bus the events are republished to, `transport` being `none`,
+/// `nats` or `kafka`, the latter through a Kafka REST proxy at `url`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BridgeConfig {
+    pub transport: String,
+    pub url: Option<String>,
+    /// Topics of the events of the feeds and the comments, by event like
+    /// `feed/created` or by aggregate like `feed/*`. Events of no topic are
+    /// not published.
+    pub topics: HashMap<String, String>,
+    /// Topics recorded as `external` events, received by the rules of that
+    /// aggregate.
+    pub ingest: Vec<String>,
+    /// Consumer group of Kafka, or queue group of NATS, sharing the ingested
+    /// messages between the processes running the consumers.
+    pub group: String,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            transport: "none".to_owned(),
+            url: None,
+            topics: HashMap::new(),
+            ingest: vec![],
+            group: "starter".to_owned(),
+        }
+    }
+}
+
+/// Subscriptions paid with Stripe Checkout, to the single plan `price_id`.
+/// Checkout is unavailable while `secret_key` or `price_id` is unset, and
+/// the events of Stripe are refused without `webhook_secret`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BillingConfig {
+    pub secret_key: Option<String>,
+    /// Signing secret of the endpoint `/billing/webhook`, `whsec_...`.
+    pub webhook_secret: Option<String>,
+    pub price_id: Option<String>,
+    pub api_url: String,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            secret_key: None,
+            webhook_secret: None,
+            price_id: None,
+            api_url: "https://api.stripe.com".to_owned(),
+        }
+    }
+}
+
+/// Rolling deploys, `POST /__drain` taking the server out of rotation before
+/// it exits.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DrainConfig {
+    /// Bearer token of `/__drain`, only the admins being allowed while unset.
+    pub token: Option<String>,
+    /// Seconds `/healthz` fails before new requests are refused, for the
+    /// load balancers to notice.
+    pub delay: u64,
+    /// Seconds given to the requests in flight, the running jobs and the
+    /// events being handled by the consumers before exiting.
+    pub timeout: u64,
+}
+
+impl Default for DrainConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            delay: 10,
+            timeout: 30,
+        }
+    }
+}
+
+/// Traces sent to an OpenTelemetry collector and metrics scraped from
+/// `/metrics`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// OTLP gRPC endpoint of the collector, like `http://127.0.0.1:4317`,
+    /// traces being only logged when unset.
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+    /// Requests traced, from 0 to 1.
+    pub sample_ratio: f64,
+    /// Bearer token of `/metrics`, only the admins being allowed while
+    /// unset.
+    pub metrics_token: Option<String>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "starter".to_owned(),
+            sample_ratio: 1.0,
+            metrics_token: None,
+        }
+    }
+}
+
+/// Sign in with an OpenID Connect provider, for the deploys without a proxy
+/// putting a token of `jwks_url` in the requests.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    /// Issuer the endpoints are discovered from, like
+    /// `https://accounts.example.com`. `/auth/login` is not served when
+    /// unset.
+    pub issuer: Option<String>,
+    pub client_id: String,
+    /// Secret of `client_id`, unset for the public clients relying on PKCE
+    /// only.
+    pub client_secret: Option<String>,
+    /// Space separated, `openid` being required.
+    pub scopes: String,
+    /// Key the session cookie is encrypted with, of 32 bytes at least.
+    pub session_secret: Option<String>,
+    /// Seconds before the tokens expire they are refreshed at.
+    pub refresh_margin: i64,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            issuer: None,
+            client_id: "starter".to_owned(),
+            client_secret: None,
+            scopes: "openid profile email offline_access".to_owned(),
+            session_secret: None,
+            refresh_margin: 60,
+        }
+    }
+}
+
+/// Organisations served by one deploy, each with its own schema in the
+/// database, and where the tenant of a request is read from.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TenancyConfig {
+    /// `subdomain` of `public_url`, `header` or `claim`, every request being
+    /// of the same tenant while unset.
+    pub source: Option<String>,
+    pub header: String,
+    /// Claim of the tokens naming the tenant of the user, checked against
+    /// the tenant of the request whatever its source.
+    pub claim: String,
+    /// Lowercase letters, digits and dashes. Their schema is created and
+    /// migrated on start.
+    pub tenants: Vec<String>,
+    /// Prefix of the schemas, like `tenant_` for `tenant_acme`.
+    pub schema_prefix: String,
+    /// User ids of the admins of each tenant, taking the place of
+    /// `Config::admins` in the config of the tenant.
+    pub admins: HashMap<String, Vec<String>>,
+}
+
+impl Default for TenancyConfig {
+    fn default() -> Self {
+        Self {
+            source: None,
+            header: "x-tenant-id".to_owned(),
+            claim: "tenant".to_owned(),
+            tenants: vec![],
+            schema_prefix: "tenant_".to_owned(),
+            admins: HashMap::new(),
+        }
+    }
+}
+
+impl TenancyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.source.is_some()
+    }
+}
+
+/// Attributes of the cookies of the preferences of the visitors, like their
+/// language.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CookieConfig {
+    /// Sends them over https only.
+    pub secure: bool,
+    /// `strict`, `lax` or `none`, the latter requiring `secure`.
+    pub same_site: String,
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            secure: !cfg!(debug_assertions),
+            same_site: "lax".to_owned(),
+        }
+    }
+}
+
+impl CookieConfig {
+    pub fn same_site(&self) -> SameSite {
+        match self.same_site.as_str() {
+            "strict" => SameSite::Strict,
+            "none" => SameSite::None,
+            _ => SameSite::Lax,
+        }
+    }
+}
+
+/// Requests a signed in user, or a client address otherwise, can make to a
+/// group of routes within `window` seconds.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RateLimitConfig {
+    /// Name of the group, like `auth`.
+    pub group: String,
+    /// Prefixes of the paths of the group, below `Config::base_url`.
+    pub paths: Vec<String>,
+    /// Methods of the group, like `POST`, every method when empty.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    pub requests: u64,
+    pub window: u64,
+}
+
+impl RateLimitConfig {
+    fn new(group: &str, paths: &[&str], methods: &[&str], requests: u64, window: u64) -> Self {
+        Self {
+            group: group.to_owned(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            methods: methods.iter().map(|method| method.to_string()).collect(),
+            requests,
+            window,
+        }
+    }
+
+    /// Whether the request to `path` with `method` is of the group.
+    pub fn includes(&self, method: &Method, path: &str) -> bool {
+        (self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method.as_str())))
+            && self.paths.iter().any(|prefix| path.starts_with(prefix))
+    }
+}
+
+/// Faults injected on purpose to see the retries, the dead letters and the
+/// pages hold up, only in debug builds: release builds ignore it.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    /// Requests answered with a 500 instead of reaching their handler, from 0
+    /// to 1.
+    pub error_rate: f64,
+    /// Commands and queries of the pages delayed by `db_delay`.
+    pub db_delay_rate: f64,
+    /// Milliseconds a delayed command or query waits before it runs.
+    pub db_delay: u64,
+    /// Live updates dropped before reaching pikav and the `/ws` connections.
+    pub drop_publish_rate: f64,
+    /// Events the `chaos` rule fails to handle, for the consumer to retry.
+    pub rule_error_rate: f64,
+    /// Events the `chaos` rule panics on.
+    pub rule_panic_rate: f64,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            error_rate: 0.05,
+            db_delay_rate: 0.1,
+            db_delay: 2000,
+            drop_publish_rate: 0.1,
+            rule_error_rate: 0.05,
+            rule_panic_rate: 0.0,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Whether the faults are injected, never in release builds.
+    pub fn is_enabled(&self) -> bool {
+        cfg!(debug_assertions) && self.enabled
+    }
+}
+
+/// Cron expressions, with seconds, of the maintenance tasks run by the
+/// processes running the consumers.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CronConfig {
+    /// Drops the expired link previews and the old webhook deliveries.
+    pub compact: String,
+    /// Drops the old failed jobs.
+    pub cleanup: String,
+    /// Forgets the interactions no longer weighing in the trending feeds.
+    pub trending: String,
+    /// Rolls the page views of the past days into the daily analytics.
+    pub analytics: String,
+}
+
+impl Default for CronConfig {
+    fn default() -> Self {
+        Self {
+            compact: "0 0 3 * * *".to_owned(),
+            cleanup: "0 30 3 * * *".to_owned(),
+            trending: "0 0 * * * *".to_owned(),
+            analytics: "0 10 0 * * *".to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Config {
     pub addr: String,
+    /// Log filter, like `info,sqlx=warn`, taking over the one of `--log`
+    /// once the server started and whenever the config is reloaded.
+    pub log: Option<String>,
     pub base_url: Option<String>,
+    pub public_url: String,
     pub jwks_url: Option<String>,
     pub evento_delay: Option<u64>,
     pub pikav: PikavConfig,
     pub dsn: String,
     pub region: String,
+    pub features: HashMap<String, bool>,
+    /// Users an enabled feature of `features` is on for, in percent.
+    pub feature_rollouts: HashMap<String, u8>,
+    pub verify_crawlers: bool,
+    pub minify: MinifyConfig,
+    /// User ids of the admins, each tenant having the ones of
+    /// `TenancyConfig::admins` instead while tenancy is on.
+    pub admins: Vec<String>,
+    pub upload: UploadConfig,
+    pub embed_origins: Vec<String>,
+    pub design: DesignConfig,
+    pub a11y_audit: bool,
+    /// Records the page views for `/admin/analytics`, without cookies nor
+    /// third party scripts.
+    pub analytics: bool,
+    /// Feeds an author can have pinned at once.
+    pub max_pins: usize,
+    /// Runs `serve` as `work`, without listening for requests.
+    pub worker: bool,
+    /// Redis shared by the replicas for caching, in memory caches being used
+    /// when unset.
+    pub redis_url: Option<String>,
+    pub mail: MailConfig,
+    pub storage: StorageConfig,
+    pub push: PushConfig,
+    pub billing: BillingConfig,
+    pub search: SearchConfig,
+    pub bridge: BridgeConfig,
+    /// Jobs run at once by each process running the consumers.
+    pub job_workers: usize,
+    pub cron: CronConfig,
+    /// Address the gRPC service listens on, like `0.0.0.0:50051`, apart from
+    /// `addr`. The service is not served when unset.
+    pub grpc_addr: Option<String>,
+    pub drain: DrainConfig,
+    /// MaxMind country database, like `GeoLite2-Country.mmdb`, the language
+    /// of the country of a visitor being used when their browser sends none
+    /// of the languages of the app.
+    pub geoip_path: Option<String>,
+    pub chaos: ChaosConfig,
+    /// Seconds the browsers cache the static files requested without their
+    /// fingerprint, those with it being cached for good.
+    pub static_max_age: u64,
+    /// Claim of the tokens listing the permissions of the user, as an array
+    /// or space separated like `scope`.
+    pub permissions_claim: String,
+    pub cookies: CookieConfig,
+    pub telemetry: TelemetryConfig,
+    pub oidc: OidcConfig,
+    pub tenancy: TenancyConfig,
+    /// Limits of the groups of routes, the first group a request is of
+    /// limiting it.
+    pub rate_limits: Vec<RateLimitConfig>,
+    /// Proxies in front of the app, as addresses or ranges like
+    /// `10.0.0.0/8`, the `X-Forwarded-For` and `X-Real-IP` headers being
+    /// ignored on the connections from anyone else.
+    pub trusted_proxies: Vec<String>,
+    /// Tenant of `TenancyConfig::tenants` this config was scoped to by
+    /// `Config::for_tenant`.
+    #[serde(skip)]
+    pub tenant: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             addr: "0.0.0.0:3000".to_string(),
+            log: None,
             base_url: Some("/starter".to_owned()),
+            public_url: "http://127.0.0.1:3000".to_owned(),
             jwks_url: Some("http://127.0.0.1:4456/.well-known/jwks.json".to_owned()),
             evento_delay: Some(0),
             pikav: PikavConfig {
@@ -33,22 +635,219 @@ impl Default for Config {
             },
             dsn: "cockroach://starter@127.0.0.1:26257/starter?sslmode=disable".to_owned(),
             region: "eu-west-3".to_owned(),
+            features: HashMap::new(),
+            feature_rollouts: HashMap::new(),
+            verify_crawlers: false,
+            minify: MinifyConfig::default(),
+            admins: vec![],
+            upload: UploadConfig::default(),
+            embed_origins: vec![],
+            design: DesignConfig::default(),
+            a11y_audit: false,
+            analytics: true,
+            max_pins: 3,
+            worker: false,
+            redis_url: None,
+            mail: MailConfig::default(),
+            storage: StorageConfig::default(),
+            push: PushConfig::default(),
+            billing: BillingConfig::default(),
+            search: SearchConfig::default(),
+            bridge: BridgeConfig::default(),
+            job_workers: 4,
+            cron: CronConfig::default(),
+            grpc_addr: None,
+            drain: DrainConfig::default(),
+            geoip_path: None,
+            chaos: ChaosConfig::default(),
+            static_max_age: 3600,
+            permissions_claim: "permissions".to_owned(),
+            cookies: CookieConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            oidc: OidcConfig::default(),
+            tenancy: TenancyConfig::default(),
+            rate_limits: vec![
+                RateLimitConfig::new("auth", &["/auth/"], &[], 20, 60),
+                RateLimitConfig::new(
+                    "commands",
+                    &["/"],
+                    &["POST", "PUT", "PATCH", "DELETE"],
+                    120,
+                    60,
+                ),
+            ],
+            trusted_proxies: vec![],
+            tenant: None,
         }
     }
 }
 
 impl Config {
+    /// Config of `STARTER_CONFIG_PATH` and the `STARTER_` variables. A
+    /// variable ending with `_FILE`, like `STARTER_DSN_FILE`, sets its key to
+    /// the content of the file it names, taking precedence over the variable
+    /// without the suffix.
     pub fn new() -> Result<Self, ConfigError> {
+        Self::with_overrides(&[])
+    }
+
+    /// `Config::new` with the keys of `overrides`, like `("job_workers",
+    /// "1")` or `("drain.delay", "0")`, taking precedence over everything.
+    pub fn with_overrides(overrides: &[(&str, &str)]) -> Result<Self, ConfigError> {
         let config_path = env::var("STARTER_CONFIG_PATH");
         let config_path_required = config_path.is_ok();
 
-        config::Config::builder()
+        let mut builder = config::Config::builder()
             .add_source(
                 File::with_name(&config_path.unwrap_or_default()).required(config_path_required),
             )
-            .add_source(Environment::with_prefix("starter"))
-            .build()?
-            .try_deserialize()
+            .add_source(Environment::with_prefix("starter"));
+
+        for (name, path) in env::vars() {
+            let Some(key) = name
+                .to_uppercase()
+                .strip_prefix("STARTER_")
+                .and_then(|name| name.strip_suffix("_FILE"))
+                .map(str::to_lowercase)
+            else {
+                continue;
+            };
+
+            let value = fs::read_to_string(&path)
+                .map_err(|e| ConfigError::Message(format!("{name}: {path}: {e}")))?;
+
+            builder = builder.set_override(key, value.trim_end().to_owned())?;
+        }
+
+        for (key, value) in overrides {
+            builder = builder.set_override(*key, *value)?;
+        }
+
+        builder.build()?.try_deserialize()
+    }
+
+    /// `Config::new` with its secrets read from their provider, the ones
+    /// like `vault:secret/data/starter#dsn` from Vault and the ones like
+    /// `aws-sm:starter/prod#dsn` from AWS Secrets Manager.
+    pub async fn load() -> anyhow::Result<Self> {
+        let mut config = Self::new()?;
+
+        let secrets = [
+            Some(&mut config.dsn),
+            config.jwks_url.as_mut(),
+            config.redis_url.as_mut(),
+            config.mail.smtp_url.as_mut(),
+            config.mail.api_key.as_mut(),
+            config.storage.secret_access_key.as_mut(),
+            config.push.vapid_private_key.as_mut(),
+            config.billing.secret_key.as_mut(),
+            config.billing.webhook_secret.as_mut(),
+            config.search.api_key.as_mut(),
+            config.upload.image_secret.as_mut(),
+            config.drain.token.as_mut(),
+            config.telemetry.metrics_token.as_mut(),
+            config.oidc.client_secret.as_mut(),
+            config.oidc.session_secret.as_mut(),
+        ];
+
+        for secret in secrets.into_iter().flatten() {
+            *secret = crate::secrets::resolve(secret).await?;
+        }
+
+        Ok(config)
+    }
+
+    /// Configs of every tenant of `TenancyConfig::tenants`, or this one
+    /// alone while tenancy is off.
+    pub fn tenants(&self) -> Vec<Config> {
+        if !self.tenancy.is_enabled() {
+            return vec![self.clone()];
+        }
+
+        self.tenancy
+            .tenants
+            .iter()
+            .map(|tenant| self.for_tenant(tenant))
+            .collect()
+    }
+
+    /// Config of the services of `tenant`: its schema as the search path of
+    /// `dsn`, its name prefixing the pikav namespace, the search index and
+    /// the bridge topics, and its own admins.
+    pub fn for_tenant(&self, tenant: &str) -> Config {
+        let schema = self.schema_of(tenant);
+        let (dsn, query) = self.dsn.split_once('?').unwrap_or((&self.dsn, ""));
+        let params = query
+            .split('&')
+            .filter(|param| !param.is_empty() && !param.starts_with("options="))
+            .map(str::to_owned)
+            .chain([format!("options=-c%20search_path%3D{schema}")])
+            .collect::<Vec<_>>();
+
+        let mut config = self.clone();
+        config.dsn = format!("{dsn}?{}", params.join("&"));
+        config.pikav.namespace = format!("{}-{tenant}", self.pikav.namespace);
+        config.search.index = format!("{}_{}", self.search.index, tenant.replace('-', "_"));
+        config.bridge.topics = self
+            .bridge
+            .topics
+            .iter()
+            .map(|(pattern, topic)| (pattern.to_owned(), format!("{tenant}.{topic}")))
+            .collect();
+        config.bridge.ingest = self
+            .bridge
+            .ingest
+            .iter()
+            .map(|topic| format!("{tenant}.{topic}"))
+            .collect();
+        config.bridge.group = format!("{}-{tenant}", self.bridge.group);
+        config.admins = self.tenancy.admins.get(tenant).cloned().unwrap_or_default();
+        config.tenant = Some(tenant.to_owned());
+
+        config
+    }
+
+    /// Schema of the tenant of `for_tenant`, created by `start` before the
+    /// migrations run.
+    pub fn schema(&self) -> Option<String> {
+        self.tenant.as_deref().map(|tenant| self.schema_of(tenant))
+    }
+
+    fn schema_of(&self, tenant: &str) -> String {
+        format!("{}{}", self.tenancy.schema_prefix, tenant.replace('-', "_"))
+    }
+
+    /// `name` prefixed by the tenant, for the keys shared by the tenants
+    /// like the ones of Redis.
+    pub fn namespaced(&self, name: &str) -> String {
+        match &self.tenant {
+            Some(tenant) => format!("{tenant}:{name}"),
+            None => name.to_owned(),
+        }
+    }
+
+    /// Whether `ip` is one of `trusted_proxies`.
+    pub fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+
+        self.trusted_proxies
+            .iter()
+            .filter_map(|proxy| parse_ip_range(proxy))
+            .any(|(range, prefix)| {
+                let (range, ip, bits) = match (range, ip) {
+                    (IpAddr::V4(range), IpAddr::V4(ip)) => {
+                        (u32::from(range) as u128, u32::from(ip) as u128, 32)
+                    }
+                    (IpAddr::V6(range), IpAddr::V6(ip)) => (u128::from(range), u128::from(ip), 128),
+                    _ => return false,
+                };
+                let mask = u128::MAX.checked_shl(bits - prefix).unwrap_or(0);
+
+                (range ^ ip) & mask == 0
+            })
     }
 
     pub fn create_url(&self, uri: impl Into<String>) -> String {
@@ -58,4 +857,635 @@ impl Config {
             .map(|base_url| format!("{base_url}{}", uri))
             .unwrap_or(uri)
     }
+
+    pub fn create_absolute_url(&self, uri: impl Into<String>) -> String {
+        format!(
+            "{}{}",
+            self.public_url.trim_end_matches('/'),
+            self.create_url(uri)
+        )
+    }
+}
+
+/// Time given to each service to answer `check_config`.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of one of the checks of `check_config`.
+pub struct ConfigCheck {
+    pub name: &'static str,
+    pub error: Option<String>,
+}
+
+/// Effective configuration as json, its passwords masked, with the outcome
+/// of its checks.
+pub struct ConfigReport {
+    pub config: String,
+    pub checks: Vec<ConfigCheck>,
+}
+
+impl ConfigReport {
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| check.error.is_none())
+    }
+}
+
+/// Loads `Config` like `serve` does, then validates its urls and addresses
+/// and reaches the database, the jwks, pikav, Redis, the mail transport, the
+/// storage and the search engine.
+pub async fn check_config() -> anyhow::Result<ConfigReport> {
+    let config = Config::load().await?;
+
+    let checks = vec![
+        ConfigCheck::new("addr", check_addr(&config.addr).await),
+        ConfigCheck::new("base_url", check_base_url(config.base_url.as_deref())),
+        ConfigCheck::new(
+            "public_url",
+            parse_url(&config.public_url, &["http", "https"]).map(|_| ()),
+        ),
+        ConfigCheck::new("dsn", check_dsn(&config.dsn).await),
+        ConfigCheck::new("jwks_url", check_jwks(config.jwks_url.as_deref()).await),
+        ConfigCheck::new("oidc", check_oidc(&config.oidc).await),
+        ConfigCheck::new("pikav.url", check_pikav(&config.pikav.url).await),
+        ConfigCheck::new("redis_url", check_redis(config.redis_url.as_deref()).await),
+        ConfigCheck::new("mail", check_mail(&config.mail).await),
+        ConfigCheck::new("storage", check_storage(&config).await),
+        ConfigCheck::new("push", crate::push::check_push(&config.push)),
+        ConfigCheck::new("search", check_search(&config).await),
+        ConfigCheck::new("bridge", check_bridge(&config.bridge).await),
+        ConfigCheck::new(
+            "billing.api_url",
+            parse_url(&config.billing.api_url, &["http", "https"]).map(|_| ()),
+        ),
+        ConfigCheck::new("cron", check_cron(&config.cron)),
+        ConfigCheck::new("chaos", check_chaos(&config.chaos)),
+        ConfigCheck::new("cookies", check_cookies(&config.cookies)),
+        ConfigCheck::new("rate_limits", check_rate_limits(&config.rate_limits)),
+        ConfigCheck::new("telemetry", check_telemetry(&config.telemetry)),
+        ConfigCheck::new("tenancy", check_tenancy(&config)),
+        ConfigCheck::new("grpc_addr", check_grpc_addr(config.grpc_addr.as_deref())),
+        ConfigCheck::new(
+            "geoip_path",
+            crate::geoip::GeoIp::open(&config)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+        ),
+        ConfigCheck::new(
+            "embed_origins",
+            config
+                .embed_origins
+                .iter()
+                .try_for_each(|origin| parse_url(origin, &["http", "https"]).map(|_| ())),
+        ),
+        ConfigCheck::new("feature_rollouts", check_feature_rollouts(&config)),
+        ConfigCheck::new(
+            "trusted_proxies",
+            config.trusted_proxies.iter().try_for_each(|proxy| {
+                parse_ip_range(proxy)
+                    .map(|_| ())
+                    .ok_or_else(|| format!("{proxy} is not an address or range"))
+            }),
+        ),
+        ConfigCheck::new(
+            "admins",
+            config.admins.iter().try_for_each(|admin| {
+                Uuid::parse_str(admin)
+                    .map(|_| ())
+                    .map_err(|_| format!("{admin} is not a user id"))
+            }),
+        ),
+    ];
+
+    let masked = Config {
+        public_url: mask_url(&config.public_url),
+        jwks_url: config.jwks_url.as_deref().map(mask_url),
+        dsn: mask_url(&config.dsn),
+        redis_url: config.redis_url.as_deref().map(mask_url),
+        pikav: PikavConfig {
+            url: mask_url(&config.pikav.url),
+            ..config.pikav.clone()
+        },
+        mail: MailConfig {
+            smtp_url: config.mail.smtp_url.as_deref().map(mask_url),
+            api_url: config.mail.api_url.as_deref().map(mask_url),
+            api_key: config.mail.api_key.as_ref().map(|_| "***".to_owned()),
+            ..config.mail.clone()
+        },
+        upload: UploadConfig {
+            image_secret: config
+                .upload
+                .image_secret
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.upload.clone()
+        },
+        storage: StorageConfig {
+            endpoint: config.storage.endpoint.as_deref().map(mask_url),
+            secret_access_key: config
+                .storage
+                .secret_access_key
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.storage.clone()
+        },
+        billing: BillingConfig {
+            secret_key: config.billing.secret_key.as_ref().map(|_| "***".to_owned()),
+            webhook_secret: config
+                .billing
+                .webhook_secret
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.billing.clone()
+        },
+        search: SearchConfig {
+            url: config.search.url.as_deref().map(mask_url),
+            api_key: config.search.api_key.as_ref().map(|_| "***".to_owned()),
+            ..config.search.clone()
+        },
+        bridge: BridgeConfig {
+            url: config.bridge.url.as_deref().map(mask_url),
+            ..config.bridge.clone()
+        },
+        push: PushConfig {
+            vapid_private_key: config
+                .push
+                .vapid_private_key
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.push.clone()
+        },
+        drain: DrainConfig {
+            token: config.drain.token.as_ref().map(|_| "***".to_owned()),
+            ..config.drain.clone()
+        },
+        telemetry: TelemetryConfig {
+            otlp_endpoint: config.telemetry.otlp_endpoint.as_deref().map(mask_url),
+            metrics_token: config
+                .telemetry
+                .metrics_token
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.telemetry.clone()
+        },
+        oidc: OidcConfig {
+            client_secret: config.oidc.client_secret.as_ref().map(|_| "***".to_owned()),
+            session_secret: config
+                .oidc
+                .session_secret
+                .as_ref()
+                .map(|_| "***".to_owned()),
+            ..config.oidc.clone()
+        },
+        ..config
+    };
+
+    Ok(ConfigReport {
+        config: serde_json::to_string_pretty(&masked)?,
+        checks,
+    })
+}
+
+impl ConfigCheck {
+    fn new(name: &'static str, result: Result<(), String>) -> Self {
+        Self {
+            name,
+            error: result.err(),
+        }
+    }
+}
+
+async fn check_addr(addr: &str) -> Result<(), String> {
+    match lookup_host(addr).await {
+        Ok(mut addrs) if addrs.next().is_some() => Ok(()),
+        Ok(_) => Err(format!("{addr} does not resolve")),
+        Err(e) => Err(format!("{addr}: {e}")),
+    }
+}
+
+fn check_feature_rollouts(config: &Config) -> Result<(), String> {
+    config
+        .feature_rollouts
+        .iter()
+        .try_for_each(|(name, &rollout)| {
+            if !config.features.contains_key(name) {
+                return Err(format!("{name} is not one of features"));
+            }
+
+            if rollout > 100 {
+                return Err(format!("{name} is rolled out to more than 100%"));
+            }
+
+            Ok(())
+        })
+}
+
+fn check_grpc_addr(addr: Option<&str>) -> Result<(), String> {
+    let Some(addr) = addr else {
+        return Ok(());
+    };
+
+    addr.parse::<SocketAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("{addr} is not an ip address with a port"))
+}
+
+fn check_base_url(base_url: Option<&str>) -> Result<(), String> {
+    match base_url {
+        Some(base_url) if !base_url.starts_with('/') || base_url.ends_with('/') => Err(format!(
+            "{base_url} must start with a slash and not end with one"
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn check_dsn(dsn: &str) -> Result<(), String> {
+    parse_url(dsn, &["postgres", "postgresql", "cockroach"])?;
+
+    match timeout(CHECK_TIMEOUT, PgPool::connect(dsn)).await {
+        Ok(Ok(db)) => {
+            db.close().await;
+
+            Ok(())
+        }
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("{} did not answer in time", mask_url(dsn))),
+    }
+}
+
+async fn check_jwks(jwks_url: Option<&str>) -> Result<(), String> {
+    let Some(jwks_url) = jwks_url else {
+        return Ok(());
+    };
+
+    parse_url(jwks_url, &["http", "https"])?;
+
+    match timeout(CHECK_TIMEOUT, JwksClient::build(Some(jwks_url.to_owned()))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("{} did not answer in time", mask_url(jwks_url))),
+    }
+}
+
+async fn check_oidc(oidc: &OidcConfig) -> Result<(), String> {
+    let Some(issuer) = oidc.issuer.as_deref() else {
+        return Ok(());
+    };
+
+    parse_url(issuer, &["http", "https"])?;
+
+    if oidc.session_secret.as_deref().unwrap_or_default().len() < 32 {
+        return Err("session_secret must be of 32 bytes at least".to_owned());
+    }
+
+    if !oidc
+        .scopes
+        .split_whitespace()
+        .any(|scope| scope == "openid")
+    {
+        return Err("scopes must include openid".to_owned());
+    }
+
+    match timeout(CHECK_TIMEOUT, crate::auth::discover(issuer)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("{} did not answer in time", mask_url(issuer))),
+    }
+}
+
+/// Passes for an empty `url`, live updates being served in process then.
+pub(crate) async fn check_pikav(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Ok(());
+    }
+
+    let uri = parse_url(url, &["http", "https"])?;
+    let host = uri.host().unwrap_or_default();
+    let port = uri
+        .port_u16()
+        .unwrap_or(if uri.scheme_str() == Some("https") {
+            443
+        } else {
+            80
+        });
+
+    match timeout(CHECK_TIMEOUT, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("{}: {e}", mask_url(url))),
+        Err(_) => Err(format!("{} did not answer in time", mask_url(url))),
+    }
+}
+
+async fn check_redis(url: Option<&str>) -> Result<(), String> {
+    let Some(url) = url else {
+        return Ok(());
+    };
+
+    parse_url(url, &["redis", "rediss"])?;
+
+    let ping = async {
+        let mut conn = redis::Client::open(url)?
+            .get_multiplexed_async_connection()
+            .await?;
+
+        redis::cmd("PING").query_async::<_, ()>(&mut conn).await
+    };
+
+    match timeout(CHECK_TIMEOUT, ping).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(format!("{}: {e}", mask_url(url))),
+        Err(_) => Err(format!("{} did not answer in time", mask_url(url))),
+    }
+}
+
+async fn check_mail(mail: &MailConfig) -> Result<(), String> {
+    if mail.from.parse::<lettre::message::Mailbox>().is_err() {
+        return Err(format!("{} is not an email address", mail.from));
+    }
+
+    match (mail.transport.as_str(), &mail.smtp_url, &mail.api_url) {
+        ("mailbox", ..) => Ok(()),
+        ("smtp", Some(url), _) => {
+            parse_url(url, &["smtp", "smtps"])?;
+
+            let transport = lettre::AsyncSmtpTransport::<lettre::Tokio1Executor>::from_url(url)
+                .map_err(|e| format!("{}: {e}", mask_url(url)))?
+                .build();
+
+            match timeout(CHECK_TIMEOUT, transport.test_connection()).await {
+                Ok(Ok(true)) => Ok(()),
+                Ok(Ok(false)) => Err(format!("{} refused the connection", mask_url(url))),
+                Ok(Err(e)) => Err(format!("{}: {e}", mask_url(url))),
+                Err(_) => Err(format!("{} did not answer in time", mask_url(url))),
+            }
+        }
+        ("api", _, Some(url)) => parse_url(url, &["http", "https"]).map(|_| ()),
+        ("smtp", ..) => Err("the smtp transport requires smtp_url".to_owned()),
+        ("api", ..) => Err("the api transport requires api_url".to_owned()),
+        (transport, ..) => Err(format!(
+            "{transport} is not a transport, use mailbox, smtp or api"
+        )),
+    }
+}
+
+async fn check_storage(config: &Config) -> Result<(), String> {
+    if let Some(endpoint) = &config.storage.endpoint {
+        parse_url(endpoint, &["http", "https"])?;
+    }
+
+    let storage = crate::storage::open(config).map_err(|e| e.to_string())?;
+
+    match timeout(CHECK_TIMEOUT, storage.exists("config-check")).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("{} did not answer in time", config.storage.backend)),
+    }
+}
+
+async fn check_search(config: &Config) -> Result<(), String> {
+    if let Some(url) = &config.search.url {
+        parse_url(url, &["http", "https"])?;
+    }
+
+    // The postgres backend is checked with `dsn`, without querying it.
+    let search = crate::search::open(config, evento::Query::new()).map_err(|e| e.to_string())?;
+
+    match timeout(CHECK_TIMEOUT, search.health()).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("{} did not answer in time", config.search.backend)),
+    }
+}
+
+async fn check_bridge(bridge: &BridgeConfig) -> Result<(), String> {
+    if let Some(url) = &bridge.url {
+        match bridge.transport.as_str() {
+            "nats" => parse_url(url, &["nats", "tls"])?,
+            _ => parse_url(url, &["http", "https"])?,
+        };
+    }
+
+    if let Some(key) = bridge.topics.keys().find(|key| !key.contains('/')) {
+        return Err(format!(
+            "{key} is not an event like feed/created or an aggregate like feed/*"
+        ));
+    }
+
+    let bridge = match timeout(CHECK_TIMEOUT, crate::bridge::Bridge::open(bridge)).await {
+        Ok(bridge) => bridge.map_err(|e| e.to_string())?,
+        Err(_) => return Err(format!("{} did not answer in time", bridge.transport)),
+    };
+
+    match timeout(CHECK_TIMEOUT, bridge.health()).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err("bridge did not answer in time".to_owned()),
+    }
+}
+
+fn check_cron(cron: &CronConfig) -> Result<(), String> {
+    [
+        ("compact", &cron.compact),
+        ("cleanup", &cron.cleanup),
+        ("trending", &cron.trending),
+        ("analytics", &cron.analytics),
+    ]
+    .into_iter()
+    .try_for_each(|(name, expression)| {
+        expression
+            .parse::<cron::Schedule>()
+            .map(|_| ())
+            .map_err(|e| format!("{name}: {expression} is not a cron expression: {e}"))
+    })
+}
+
+fn check_telemetry(telemetry: &TelemetryConfig) -> Result<(), String> {
+    if let Some(endpoint) = telemetry.otlp_endpoint.as_deref() {
+        parse_url(endpoint, &["http", "https"])?;
+    }
+
+    if !(0.0..=1.0).contains(&telemetry.sample_ratio) {
+        return Err(format!(
+            "sample_ratio: {} is not between 0 and 1",
+            telemetry.sample_ratio
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether `name` can be a tenant, in a subdomain as in a schema name.
+pub fn is_tenant_name(name: &str) -> bool {
+    (1..=32).contains(&name.len())
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn check_tenancy(config: &Config) -> Result<(), String> {
+    let tenancy = &config.tenancy;
+
+    let Some(source) = tenancy.source.as_deref() else {
+        return Ok(());
+    };
+
+    if !["subdomain", "header", "claim"].contains(&source) {
+        return Err(format!("{source} is not subdomain, header or claim"));
+    }
+
+    if tenancy.tenants.is_empty() {
+        return Err("tenants must not be empty".to_owned());
+    }
+
+    if let Some(tenant) = tenancy
+        .tenants
+        .iter()
+        .find(|tenant| !is_tenant_name(tenant))
+    {
+        return Err(format!(
+            "{tenant} is not made of lowercase letters, digits and dashes"
+        ));
+    }
+
+    if !tenancy
+        .schema_prefix
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(
+            "schema_prefix is not made of lowercase letters, digits and underscores".to_owned(),
+        );
+    }
+
+    if config.grpc_addr.is_some() {
+        return Err("grpc_addr is not served to several tenants".to_owned());
+    }
+
+    for (tenant, admins) in &tenancy.admins {
+        if !tenancy.tenants.contains(tenant) {
+            return Err(format!("admins of {tenant}, which is not a tenant"));
+        }
+
+        if let Some(admin) = admins.iter().find(|admin| Uuid::parse_str(admin).is_err()) {
+            return Err(format!("{admin} is not a user id"));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_rate_limits(rate_limits: &[RateLimitConfig]) -> Result<(), String> {
+    rate_limits.iter().try_for_each(|limit| {
+        let group = &limit.group;
+
+        if limit.requests == 0 || limit.window == 0 {
+            return Err(format!("{group}: requests and window must be above 0"));
+        }
+
+        if limit.paths.iter().any(|path| !path.starts_with('/')) {
+            return Err(format!("{group}: paths must start with /"));
+        }
+
+        match limit
+            .methods
+            .iter()
+            .find(|method| Method::from_bytes(method.to_uppercase().as_bytes()).is_err())
+        {
+            Some(method) => Err(format!("{group}: {method} is not a method")),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Address and prefix length of `range`, like `10.0.0.0/8`, a single
+/// address being a range of its own.
+fn parse_ip_range(range: &str) -> Option<(IpAddr, u32)> {
+    let (ip, prefix) = match range.split_once('/') {
+        Some((ip, prefix)) => (ip.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+        None => (range.parse::<IpAddr>().ok()?, None),
+    };
+    let bits = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(bits);
+
+    (prefix <= bits).then_some((ip, prefix))
+}
+
+fn check_cookies(cookies: &CookieConfig) -> Result<(), String> {
+    match cookies.same_site.as_str() {
+        "none" if !cookies.secure => Err("same_site none requires secure".to_owned()),
+        "strict" | "lax" | "none" => Ok(()),
+        same_site => Err(format!("{same_site} is not strict, lax or none")),
+    }
+}
+
+fn check_chaos(chaos: &ChaosConfig) -> Result<(), String> {
+    [
+        ("error_rate", chaos.error_rate),
+        ("db_delay_rate", chaos.db_delay_rate),
+        ("drop_publish_rate", chaos.drop_publish_rate),
+        ("rule_error_rate", chaos.rule_error_rate),
+        ("rule_panic_rate", chaos.rule_panic_rate),
+    ]
+    .into_iter()
+    .try_for_each(|(name, rate)| {
+        if (0.0..=1.0).contains(&rate) {
+            Ok(())
+        } else {
+            Err(format!("{name}: {rate} is not between 0 and 1"))
+        }
+    })
+}
+
+/// `url` parsed with a host and one of `schemes`, errors leaving out its
+/// password.
+fn parse_url(url: &str, schemes: &[&str]) -> Result<Uri, String> {
+    let invalid = || format!("{} must be a {} url", mask_url(url), schemes.join(" or "));
+    let uri = url.parse::<Uri>().map_err(|_| invalid())?;
+
+    if !uri
+        .scheme_str()
+        .is_some_and(|scheme| schemes.contains(&scheme))
+        || uri.host().is_none()
+    {
+        return Err(invalid());
+    }
+
+    Ok(uri)
+}
+
+/// `url` with the password of its user info and of its `password` parameter
+/// replaced by `***`.
+fn mask_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_owned();
+    };
+
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..authority_end].rsplit_once('@') {
+        Some((user_info, _)) if user_info.contains(':') => {
+            let (user, _) = user_info.split_once(':').unwrap_or_default();
+
+            format!("{user}:***{}", &rest[user_info.len()..])
+        }
+        _ => rest.to_owned(),
+    };
+
+    let Some(query) = query else {
+        return format!("{scheme}://{rest}");
+    };
+
+    let query = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) if name.eq_ignore_ascii_case("password") => format!("{name}=***"),
+            _ => param.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{scheme}://{rest}?{query}")
 }
@@ -1,26 +1,53 @@
+use askama::Template;
 use askama_axum::{IntoResponse, Response};
 use axum::{
     async_trait,
     extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
-    response::Html,
+    http::{header, request::Parts, HeaderMap, StatusCode, Uri},
+    response::Redirect,
     Extension, RequestPartsExt,
 };
+use axum_extra::extract::CookieJar;
 use chrono::{DateTime, Locale, TimeZone};
 use evento::{Command, CommandHandler, Query, QueryHandler};
 use evento_axum::UserLanguage;
-use i18n_embed::{fluent::FluentLanguageLoader, LanguageLoader};
+use i18n_embed::fluent::FluentLanguageLoader;
+use pikav_client::timada::SimpleEvent;
 use serde::Deserialize;
-use std::{collections::HashMap, fmt, sync::Arc};
+use starter_feed::{GetUserInput, Jobs, ROLE_ADMIN};
+use std::{collections::HashMap, future::Future, marker::PhantomData, sync::Arc, time::Duration};
 use tracing::{error, warn};
 use twa_jwks::axum::JwtPayloadOption;
-use unic_langid::LanguageIdentifier;
 use validator::Validate;
 
 use crate::{
+    analytics::Analytics,
+    auth::SessionClaims,
+    billing::Stripe,
+    bot::is_bot_user_agent,
+    cache::{Cached, FragmentCache},
     config::Config,
-    i18n::{LANGUAGES, LANGUAGE_LOADER},
-    pages::{InternalServerErrorPage, NotFoundPage},
+    csrf::CsrfToken,
+    drain::Drain,
+    extract::{HxRequest, UserTimezone},
+    feature::Features,
+    flash::Flash,
+    geoip::{self, GeoIp},
+    i18n::select_languages,
+    live::Live,
+    mailer::Mailer,
+    pages::{
+        resized_image_path, BadRequestPage, ErrorAlert, ForbiddenPage, InternalServerErrorPage,
+        MaintenancePage, NotFoundPage, RouteMeta, TooManyRequestsPage, UnauthorizedPage,
+        MODULE_ROUTES,
+    },
+    push::Pusher,
+    rate_limit::RateLimiter,
+    reload::Reloader,
+    search::SearchBackend,
+    storage::Storage,
+    telemetry::RequestId,
+    theme::Theme,
 };
 
 #[derive(Clone)]
@@ -31,6 +58,39 @@ pub struct Context {
     pub user_language: Option<String>,
     pub fl_loader: Option<Arc<FluentLanguageLoader>>,
     pub user_id: Option<String>,
+    /// Role of the signed in user, unset for users that weren't created.
+    pub role: Option<String>,
+    /// Permissions of the token of the signed in user, in the claim of
+    /// `Config::permissions_claim`.
+    pub permissions: Vec<String>,
+    pub hx: HxRequest,
+    pub timezone: chrono_tz::Tz,
+    pub bot: bool,
+    pub streaming: bool,
+    pub print: bool,
+    pub flashes: Vec<Flash>,
+    pub theme: Theme,
+    pub cache: FragmentCache,
+    pub images: FragmentCache<Vec<u8>>,
+    pub live: Live,
+    pub mailer: Mailer,
+    pub storage: Arc<dyn Storage>,
+    pub jobs: Jobs,
+    pub features: Features,
+    pub pusher: Pusher,
+    pub stripe: Stripe,
+    pub search: Arc<dyn SearchBackend>,
+    pub analytics: Analytics,
+    pub reloader: Reloader,
+    pub drain: Drain,
+    pub geoip: GeoIp,
+    pub limiter: RateLimiter,
+    /// ISO 3166-1 code of the country of the client, as found by `geoip`.
+    pub country: Option<String>,
+    /// Id of the request, as set by `telemetry::trace`.
+    pub request_id: Option<String>,
+    /// Token of the forms, as set by `csrf::protect`.
+    pub csrf_token: Option<String>,
 }
 
 impl Context {
@@ -46,10 +106,95 @@ impl Context {
             .expect("fl_loader not configured correctly")
     }
 
+    /// Translates `id` at runtime for templates shared by every page, like
+    /// layouts, that can't carry their own `fl` struct.
+    pub fn t(&self, id: &str) -> String {
+        self.fl_loader().get(id)
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.user_id.is_some()
+    }
+
+    /// Whether users sign in with `/auth/login`, rather than through a
+    /// proxy.
+    pub fn can_sign_in(&self) -> bool {
+        self.config.oidc.issuer.is_some()
+    }
+
+    /// Country of the client, for the legal notices and the prices of
+    /// their region.
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    /// Id the events of the commands of the request carry, tying them to
+    /// its logs and traces.
+    pub fn request_id(&self) -> Option<String> {
+        self.request_id.to_owned()
+    }
+
+    /// Tenant of the request, as found by `tenant::scope`, `None` while
+    /// tenancy is off.
+    pub fn tenant(&self) -> Option<&str> {
+        self.config.tenant.as_deref()
+    }
+
+    /// Token the forms send back in their `_csrf` field, empty outside of
+    /// a request.
+    pub fn csrf_token(&self) -> &str {
+        self.csrf_token.as_deref().unwrap_or_default()
+    }
+
+    /// Currency prices are shown in by default, after `country`.
+    pub fn currency(&self) -> &'static str {
+        geoip::currency(self.country.as_deref())
+    }
+
+    /// Pages of the modules linked from the header.
+    pub fn nav(&self) -> &'static [RouteMeta] {
+        &MODULE_ROUTES
+    }
+
+    /// Whether the signed in user is listed in `Config::admins` or has the
+    /// admin role.
+    pub fn is_admin(&self) -> bool {
+        self.role.as_deref() == Some(ROLE_ADMIN)
+            || self
+                .user_id
+                .as_ref()
+                .is_some_and(|user_id| self.config.admins.contains(user_id))
+    }
+
+    /// Whether the signed in user was granted `P`, admins having every
+    /// permission.
+    pub fn has_permission<P: Permission>(&self) -> bool {
+        self.is_admin()
+            || (self.is_authenticated() && self.permissions.iter().any(|name| name == P::NAME))
+    }
+
+    pub fn flashes(&self) -> &[Flash] {
+        &self.flashes
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Languages of `i18n/`, to pick from with `/_language`.
+    pub fn languages(&self) -> Vec<String> {
+        crate::i18n::LANGUAGES
+            .iter()
+            .map(|language| language.to_string())
+            .collect()
+    }
+
     pub async fn execute<I: Validate + CommandHandler>(
         &self,
         input: I,
     ) -> Result<Option<HashMap<String, Vec<String>>>, Response> {
+        crate::chaos::delay(&self.config.chaos).await;
+
         let Err(err) = self.command.execute(self.user_language(), &input).await else {
             return Ok(None);
         };
@@ -58,40 +203,146 @@ impl Context {
             evento::CommandError::Server(err) => {
                 error!("{err}");
 
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    InternalServerErrorPage::new(self.clone()),
-                )
-                    .into_response())
+                Err(self.error_response(StatusCode::INTERNAL_SERVER_ERROR))
             }
             evento::CommandError::Validation(errors) => Ok(Some(errors)),
-            evento::CommandError::NotFound(_) => {
-                Err((StatusCode::NOT_FOUND, NotFoundPage::new(self.clone())).into_response())
-            }
+            evento::CommandError::NotFound(_) => Err(self.error_response(StatusCode::NOT_FOUND)),
         }
     }
 
     pub async fn query<I: QueryHandler>(&self, input: I) -> Result<I::Output, Response> {
+        crate::chaos::delay(&self.config.chaos).await;
+
         self.query.execute(&input).await.map_err(|e| match e {
             evento::QueryError::Server(err) => {
                 error!("{err}");
 
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    InternalServerErrorPage::new(self.clone()),
-                )
-                    .into_response()
-            }
-            evento::QueryError::NotFound(_) => {
-                (StatusCode::NOT_FOUND, NotFoundPage::new(self.clone())).into_response()
+                self.error_response(StatusCode::INTERNAL_SERVER_ERROR)
             }
+            evento::QueryError::NotFound(_) => self.error_response(StatusCode::NOT_FOUND),
         })
     }
 
-    pub fn format_localized<'a, Tz: TimeZone>(&self, dt: &'a DateTime<Tz>, fmt: &'a str) -> String
+    /// Whether the feature is on for the signed in user, see `Features`.
+    pub async fn is_feature_enabled(&self, name: impl Into<String>) -> Result<bool, Response> {
+        self.features
+            .is_enabled(&name.into(), self.user_id.as_deref())
+            .await
+            .map_err(|err| {
+                error!("{err}");
+
+                self.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+            })
+    }
+
+    /// Renders the component returned by `render` once per `ttl` and user
+    /// language, `FragmentCache::invalidate` forces the next render.
+    pub async fn cached<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        render: F,
+    ) -> Result<Cached, Response>
     where
-        Tz::Offset: fmt::Display,
+        T: Template,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, Response>>,
     {
+        let lang = self.user_language();
+
+        if let Some(html) = self.cache.get(key, &lang).await {
+            return Ok(Cached(html));
+        }
+
+        let html = render().await?.render().map_err(|err| {
+            error!("{err}");
+
+            self.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+        self.cache.set(key, &lang, html.to_owned(), ttl).await;
+
+        Ok(Cached(html))
+    }
+
+    /// Empty response for htmx requests, whose page is refreshed over pikav,
+    /// others are sent back to the referer.
+    pub fn submitted(&self, headers: &HeaderMap) -> Response {
+        if self.hx.request {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        Redirect::to(&self.referer_path(headers)).into_response()
+    }
+
+    /// `target`, a path or an url of `Config::public_url`, when it is a page
+    /// of the app below `Config::base_url`, its home page otherwise, so that
+    /// redirects can't send the user to another site.
+    pub fn local_path(&self, target: Option<&str>) -> String {
+        let base_url = self.config.base_url.as_deref().unwrap_or_default();
+        let public_url = self.config.public_url.parse::<Uri>().ok();
+
+        let path = target
+            .and_then(|target| target.parse::<Uri>().ok())
+            .filter(|uri| {
+                uri.authority().is_none()
+                    || public_url.as_ref().is_some_and(|public_url| {
+                        uri.scheme() == public_url.scheme()
+                            && uri.authority() == public_url.authority()
+                    })
+            })
+            .and_then(|uri| uri.path_and_query().map(|path| path.as_str().to_owned()))
+            .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.contains('\\'))
+            .filter(|path| {
+                path.strip_prefix(base_url)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+            });
+
+        match path {
+            Some(path) => path,
+            None if base_url.is_empty() => "/".to_owned(),
+            None => base_url.to_owned(),
+        }
+    }
+
+    /// Page the request was sent from, after its `Referer`, as a
+    /// `local_path`.
+    pub fn referer_path(&self, headers: &HeaderMap) -> String {
+        self.local_path(
+            headers
+                .get(header::REFERER)
+                .and_then(|value| value.to_str().ok()),
+        )
+    }
+
+    /// Renders the localized error page matching `status`, or an inline alert
+    /// when the request was sent by htmx so it can be swapped into the page.
+    pub fn error_response(&self, status: StatusCode) -> Response {
+        if self.hx.request || self.streaming {
+            return (status, ErrorAlert::new(self, status)).into_response();
+        }
+
+        match status {
+            StatusCode::UNAUTHORIZED => {
+                (status, UnauthorizedPage::new(self.clone())).into_response()
+            }
+            StatusCode::FORBIDDEN => (status, ForbiddenPage::new(self.clone())).into_response(),
+            StatusCode::NOT_FOUND => (status, NotFoundPage::new(self.clone())).into_response(),
+            StatusCode::TOO_MANY_REQUESTS => {
+                (status, TooManyRequestsPage::new(self.clone())).into_response()
+            }
+            status if status.is_client_error() => {
+                (status, BadRequestPage::new(self.clone())).into_response()
+            }
+            StatusCode::SERVICE_UNAVAILABLE => {
+                (status, MaintenancePage::new(self.clone())).into_response()
+            }
+            _ => (status, InternalServerErrorPage::new(self.clone())).into_response(),
+        }
+    }
+
+    /// Formats `dt` in the user timezone and locale.
+    pub fn format_localized<'a, Tz: TimeZone>(&self, dt: &'a DateTime<Tz>, fmt: &'a str) -> String {
         let locale = match self.user_language().as_str() {
             "en" => Locale::en_US,
             "fr" => Locale::fr_FR,
@@ -102,18 +353,83 @@ impl Context {
             }
         };
 
-        dt.format_localized(fmt, locale).to_string()
+        dt.with_timezone(&self.timezone)
+            .format_localized(fmt, locale)
+            .to_string()
+    }
+
+    /// Pages extending `_base.html` render only their body when requested by
+    /// htmx outside of a boosted navigation or when streamed.
+    pub fn fragment(&self) -> bool {
+        self.hx.is_partial() || self.streaming
+    }
+
+    /// Requested with `?format=print`, pages use the print stylesheet and
+    /// leave out the header, footer, forms and scripts.
+    pub fn print(&self) -> bool {
+        self.print
+    }
+
+    /// Design tokens of `Config::design` as a css rule, see `DesignConfig`.
+    pub fn design_css(&self) -> Option<String> {
+        self.config.design.css()
+    }
+
+    pub fn storage(&self) -> Arc<dyn Storage> {
+        self.storage.clone()
+    }
+
+    /// Outlines and logs elements without an accessible label, only in debug
+    /// builds with `Config::a11y_audit`.
+    pub fn a11y_audit(&self) -> bool {
+        cfg!(debug_assertions) && self.config.a11y_audit
+    }
+
+    /// Url of the attachment `key` scaled down to `width`, or of the file as
+    /// uploaded while `UploadConfig::image_secret` is unset.
+    pub fn image_url(&self, key: &str, width: u32) -> String {
+        match self.config.upload.image_secret.as_deref() {
+            Some(secret) => self.create_url(resized_image_path(secret, key, width)),
+            None => self.create_url(format!("/uploads/{key}")),
+        }
+    }
+
+    /// Whether the page views are recorded, never for the bots.
+    pub fn is_analytics_enabled(&self) -> bool {
+        self.config.analytics && !self.bot
     }
 
     pub fn create_url(&self, uri: impl Into<String>) -> String {
         self.config.create_url(uri)
     }
 
+    pub fn create_absolute_url(&self, uri: impl Into<String>) -> String {
+        self.config.create_absolute_url(uri)
+    }
+
     pub fn create_static_url(&self, uri: impl Into<String>) -> String {
         self.create_url(format!("/static/{}", uri.into()))
     }
 
+    /// `create_static_url` with the hash of the content of the asset, for
+    /// browsers to cache it until it changes.
+    pub fn create_fingerprinted_url(&self, uri: impl Into<String>) -> String {
+        let uri = uri.into();
+
+        match crate::assets::fingerprint(&uri) {
+            Some(hash) if uri.contains('?') => self.create_static_url(format!("{uri}&h={hash}")),
+            Some(hash) => self.create_static_url(format!("{uri}?h={hash}")),
+            None => self.create_static_url(uri),
+        }
+    }
+
+    /// Url of the live updates of a topic like `/comments`, served by pikav
+    /// or by `/sse` when it is unreachable.
     pub fn create_sse_url(&self, uri: impl Into<String>) -> String {
+        if !self.live.is_pikav() {
+            return self.create_url(format!("/sse{}", uri.into()));
+        }
+
         format!("/pikav/{}{}", self.config.pikav.namespace, uri.into())
     }
 
@@ -128,52 +444,133 @@ impl Context {
     }
 }
 
-#[async_trait]
-impl<S> FromRequestParts<S> for Context
-where
-    S: Send + Sync,
-{
-    type Rejection = (StatusCode, Html<&'static str>);
+/// Signed in user and country of a request, resolved by the first `Context`
+/// extracted from it and reused by the next ones, the extractors and
+/// middlewares of a request each building their own.
+#[derive(Clone)]
+struct Identity {
+    /// Tenant the user was looked up in.
+    tenant: Option<String>,
+    user_id: Option<String>,
+    permissions: Vec<String>,
+    role: Option<String>,
+    country: Option<String>,
+}
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+impl Identity {
+    async fn resolve<S>(ctx: &Context, parts: &mut Parts, state: &S) -> Result<Self, Response>
+    where
+        S: Send + Sync,
+    {
         let Ok(JwtPayloadOption(jwt_claims)) =
             JwtPayloadOption::<JwtClaims>::from_request_parts(parts, state).await
         else {
-            return Err((StatusCode::BAD_REQUEST, Html("Bad Request")));
+            return Err(ctx.error_response(StatusCode::BAD_REQUEST));
         };
 
-        let Ok(user_language) = UserLanguage::from_request_parts(parts, state).await else {
-            return Err((StatusCode::BAD_REQUEST, Html("Bad Request")));
+        // The token of the proxy, when there is one, over the session of
+        // `auth::login`.
+        let jwt_claims = jwt_claims.or_else(|| {
+            parts
+                .extensions
+                .get::<SessionClaims>()
+                .map(|claims| claims.0.clone())
+        });
+
+        let mut identity = Identity {
+            tenant: ctx.config.tenant.to_owned(),
+            user_id: None,
+            permissions: vec![],
+            role: None,
+            country: ctx.geoip.country_from_parts(parts).await,
         };
 
-        let langs = user_language
-            .preferred_languages()
-            .iter()
-            .map(|lang| lang.parse().unwrap_or_default())
-            .collect::<Vec<LanguageIdentifier>>();
-
-        let fl_loader = LANGUAGE_LOADER.select_languages(&langs);
+        let Some(claims) = jwt_claims else {
+            return Ok(identity);
+        };
 
-        let user_language = fl_loader
-            .current_languages()
-            .iter()
-            .find_map(|language| {
-                if LANGUAGES.contains(language) {
-                    Some(language.to_string())
-                } else {
-                    None
-                }
+        let user = ctx
+            .query(GetUserInput {
+                id: claims.sub.to_owned(),
             })
-            .unwrap_or(fl_loader.fallback_language().to_string());
+            .await;
+
+        match user {
+            // Disabled users browse as if they were signed out.
+            Ok(Some(user)) if user.disabled => {}
+            Ok(user) => {
+                identity.permissions = claims.permissions(&ctx.config.permissions_claim);
+                identity.user_id = Some(claims.sub);
+                identity.role = user.map(|user| user.role);
+            }
+            // Signed out rather than failing every page, as disabled users
+            // can't be told apart, the error being logged by `query`.
+            Err(_) => {}
+        }
+
+        Ok(identity)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Context
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let preferred = UserLanguage::from_request_parts(parts, state)
+            .await
+            .map(|user_language| user_language.preferred_languages().to_vec())
+            .unwrap_or_default();
+        let (fl_loader, user_language) = select_languages(&preferred);
 
         let Extension(mut ctx) = parts
             .extract::<Extension<Context>>()
             .await
             .expect("Context not configured correctly");
 
+        ctx.config = ctx.reloader.config();
         ctx.user_language = Some(user_language);
         ctx.fl_loader = Some(Arc::new(fl_loader));
-        ctx.user_id = jwt_claims.map(|claims| claims.sub);
+        ctx.hx = HxRequest::from_headers(&parts.headers);
+        ctx.timezone = UserTimezone::from_headers(&parts.headers).0;
+        ctx.bot = is_bot_user_agent(&parts.headers);
+        ctx.print = parts
+            .uri
+            .query()
+            .is_some_and(|query| query.split('&').any(|param| param == "format=print"));
+        let jar = CookieJar::from_headers(&parts.headers);
+        ctx.flashes = Flash::from_jar(&jar);
+        ctx.theme = Theme::from_jar(&jar);
+        ctx.request_id = parts
+            .extensions
+            .get::<RequestId>()
+            .map(|request_id| request_id.0.to_owned());
+        ctx.csrf_token = parts
+            .extensions
+            .get::<CsrfToken>()
+            .map(|token| token.0.to_owned());
+
+        let identity = match parts
+            .extensions
+            .get::<Identity>()
+            .filter(|identity| identity.tenant == ctx.config.tenant)
+        {
+            Some(identity) => identity.clone(),
+            None => {
+                let identity = Identity::resolve(&ctx, parts, state).await?;
+                parts.extensions.insert(identity.clone());
+
+                identity
+            }
+        };
+
+        ctx.user_id = identity.user_id;
+        ctx.permissions = identity.permissions;
+        ctx.role = identity.role;
+        ctx.country = identity.country;
 
         Ok(ctx)
     }
@@ -186,6 +583,10 @@ pub struct UserContext {
 }
 
 impl UserContext {
+    pub fn context(&self) -> &Context {
+        &self.inner
+    }
+
     pub fn user_language(&self) -> String {
         self.inner.user_language()
     }
@@ -194,6 +595,62 @@ impl UserContext {
         self.inner.fl_loader()
     }
 
+    pub fn t(&self, id: &str) -> String {
+        self.inner.t(id)
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        true
+    }
+
+    pub fn can_sign_in(&self) -> bool {
+        self.inner.can_sign_in()
+    }
+
+    pub fn nav(&self) -> &'static [RouteMeta] {
+        self.inner.nav()
+    }
+
+    pub fn country(&self) -> Option<&str> {
+        self.inner.country()
+    }
+
+    pub fn currency(&self) -> &'static str {
+        self.inner.currency()
+    }
+
+    pub fn request_id(&self) -> Option<String> {
+        self.inner.request_id()
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.inner.tenant()
+    }
+
+    pub fn csrf_token(&self) -> &str {
+        self.inner.csrf_token()
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.inner.is_admin()
+    }
+
+    pub fn has_permission<P: Permission>(&self) -> bool {
+        self.inner.has_permission::<P>()
+    }
+
+    pub fn flashes(&self) -> &[Flash] {
+        self.inner.flashes()
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.inner.theme()
+    }
+
+    pub fn languages(&self) -> Vec<String> {
+        self.inner.languages()
+    }
+
     pub async fn execute<I: Validate + CommandHandler>(
         &self,
         input: I,
@@ -205,28 +662,91 @@ impl UserContext {
         self.inner.query(input).await
     }
 
-    pub fn format_localized<'a, Tz: TimeZone>(&self, dt: &'a DateTime<Tz>, fmt: &'a str) -> String
-    where
-        Tz::Offset: fmt::Display,
-    {
+    pub fn format_localized<'a, Tz: TimeZone>(&self, dt: &'a DateTime<Tz>, fmt: &'a str) -> String {
         self.inner.format_localized(dt, fmt)
     }
 
+    pub fn fragment(&self) -> bool {
+        self.inner.fragment()
+    }
+
+    pub fn print(&self) -> bool {
+        self.inner.print()
+    }
+
+    pub fn design_css(&self) -> Option<String> {
+        self.inner.design_css()
+    }
+
+    pub fn storage(&self) -> Arc<dyn Storage> {
+        self.inner.storage()
+    }
+
+    pub fn a11y_audit(&self) -> bool {
+        self.inner.a11y_audit()
+    }
+
+    pub fn is_analytics_enabled(&self) -> bool {
+        self.inner.is_analytics_enabled()
+    }
+
+    pub fn image_url(&self, key: &str, width: u32) -> String {
+        self.inner.image_url(key, width)
+    }
+
     pub fn create_url(&self, uri: impl Into<String>) -> String {
         self.inner.create_url(uri)
     }
 
+    pub fn create_absolute_url(&self, uri: impl Into<String>) -> String {
+        self.inner.create_absolute_url(uri)
+    }
+
     pub fn create_static_url(&self, uri: impl Into<String>) -> String {
         self.inner.create_static_url(uri)
     }
 
+    pub fn create_fingerprinted_url(&self, uri: impl Into<String>) -> String {
+        self.inner.create_fingerprinted_url(uri)
+    }
+
     pub fn create_sse_url(&self, uri: impl Into<String>) -> String {
         self.inner.create_sse_url(uri)
     }
 
+    pub async fn is_feature_enabled(&self, name: impl Into<String>) -> Result<bool, Response> {
+        self.inner.is_feature_enabled(name).await
+    }
+
+    pub fn submitted(&self, headers: &HeaderMap) -> Response {
+        self.inner.submitted(headers)
+    }
+
+    pub fn local_path(&self, target: Option<&str>) -> String {
+        self.inner.local_path(target)
+    }
+
+    pub fn referer_path(&self, headers: &HeaderMap) -> String {
+        self.inner.referer_path(headers)
+    }
+
+    pub fn error_response(&self, status: StatusCode) -> Response {
+        self.inner.error_response(status)
+    }
+
     pub fn hot_reload(&self) -> bool {
         self.inner.hot_reload()
     }
+
+    /// Publishes `data` to the signed in user on the live `topic`.
+    pub fn publish(&self, topic: impl Into<String>, event: impl Into<String>, data: String) {
+        self.inner.live.publish(vec![SimpleEvent {
+            user_id: self.user_id.to_owned(),
+            topic: topic.into(),
+            event: event.into(),
+            data,
+        }]);
+    }
 }
 
 #[async_trait]
@@ -234,20 +754,92 @@ impl<S> FromRequestParts<S> for UserContext
 where
     S: Send + Sync,
 {
-    type Rejection = (StatusCode, Html<&'static str>);
+    type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let inner = Context::from_request_parts(parts, state).await?;
 
         let Some(user_id) = inner.user_id.to_owned() else {
-            return Err((StatusCode::UNAUTHORIZED, Html("Unauthorized")));
+            return Err(inner.error_response(StatusCode::UNAUTHORIZED));
         };
 
         Ok(UserContext { inner, user_id })
     }
 }
 
+/// Responds with the not found page unless the signed in user is listed in
+/// `Config::admins` or has the admin role, either as a handler argument or as a guard with
+/// `middleware::from_extractor`.
+pub struct Admin;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Admin
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = UserContext::from_request_parts(parts, state).await?;
+
+        if !ctx.is_admin() {
+            return Err(ctx.error_response(StatusCode::NOT_FOUND));
+        }
+
+        Ok(Admin)
+    }
+}
+
+/// Permission granted through `Config::permissions_claim`, checked with
+/// `Context::has_permission` or `RequirePermission`.
+pub trait Permission: Send + Sync + 'static {
+    const NAME: &'static str;
+}
+
+/// Responds with the unauthorized page to the signed out users and the
+/// forbidden page to those not granted `P`, either as a handler argument or
+/// as a guard with `middleware::from_extractor`.
+pub struct RequirePermission<P>(PhantomData<P>);
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    P: Permission,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = UserContext::from_request_parts(parts, state).await?;
+
+        if !ctx.has_permission::<P>() {
+            return Err(ctx.error_response(StatusCode::FORBIDDEN));
+        }
+
+        Ok(RequirePermission(PhantomData))
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct JwtClaims {
     pub sub: String,
+    #[serde(flatten)]
+    pub claims: HashMap<String, serde_json::Value>,
+}
+
+impl JwtClaims {
+    /// Permissions of the `claim`, an array of names or names separated by
+    /// spaces.
+    pub fn permissions(&self, claim: &str) -> Vec<String> {
+        match self.claims.get(claim) {
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_owned))
+                .collect(),
+            Some(serde_json::Value::String(value)) => {
+                value.split_whitespace().map(str::to_owned).collect()
+            }
+            _ => vec![],
+        }
+    }
 }
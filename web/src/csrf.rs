@@ -0,0 +1,163 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    body::{self, Body},
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    Extension,
+};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+
+use crate::{
+    auth::random_token,
+    context::Context,
+    maintenance::{app_path, is_probe},
+};
+
+/// Cookie of the token the forms must send back.
+const CSRF_COOKIE: &str = "csrf";
+
+/// Header of the token, sent by htmx and the scripts after the `csrf-token`
+/// meta of `_csrf.html`, in the head of the pages and of the streamed ones.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Field of the token in the forms, as rendered by `Context::csrf_token`.
+const CSRF_FIELD: &str = "_csrf";
+
+/// Bytes of a form read to find its token, forms being far smaller.
+const FORM_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Token of the request, in its extensions, read by `Context::csrf_token`.
+#[derive(Clone, Debug)]
+pub struct CsrfToken(pub String);
+
+/// Whether `path` takes requests from elsewhere than the pages of the app:
+/// the probes, the webhooks, the beacons of the analytics and the end-to-end
+/// suite. `/__drain` isn't, admins being let in by their session, its
+/// bearers sending no cookie for `is_unsafe` to check.
+fn is_exempt(path: &str) -> bool {
+    (is_probe(path) && path != "/__drain")
+        || matches!(path, "/billing/webhook" | "/_analytics")
+        || path.starts_with("/__test/")
+}
+
+/// Whether the browser could have sent the request from another site along
+/// with the cookies of the user. Json bodies can't be sent cross site
+/// without a preflight the app never allows, and requests without cookies
+/// carry no credentials of a browser.
+fn is_unsafe(parts: &Parts) -> bool {
+    let json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+
+    !matches!(
+        parts.method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    ) && !json
+        && parts.headers.contains_key(header::COOKIE)
+}
+
+fn is_form(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
+
+fn same_token(sent: &str, token: &str) -> bool {
+    sent.len() == token.len()
+        && sent
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Checks the token of the requests that change something against the one
+/// of the `csrf` cookie, the forbidden page answering the ones that don't
+/// send it back in `CSRF_HEADER` or in the `_csrf` field of their form.
+/// Html pages set the cookie of the browsers that don't have one yet.
+pub async fn protect(Extension(ctx): Extension<Context>, req: Request, next: Next) -> Response {
+    let jar = CookieJar::from_headers(req.headers());
+    let (mut parts, body) = req.into_parts();
+    let exempt = is_exempt(app_path(&ctx, parts.uri.path()));
+
+    let token = jar.get(CSRF_COOKIE).map(|cookie| cookie.value().to_owned());
+
+    let body = if is_unsafe(&parts) && !exempt {
+        let sent = parts
+            .headers
+            .get(CSRF_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        let (sent, body) = match sent {
+            Some(sent) => (Some(sent), body),
+            None if is_form(&parts.headers) => {
+                let Ok(bytes) = body::to_bytes(body, FORM_LIMIT).await else {
+                    return error_response(&mut parts, StatusCode::PAYLOAD_TOO_LARGE).await;
+                };
+
+                let sent = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+                    .ok()
+                    .and_then(|fields| {
+                        fields
+                            .into_iter()
+                            .find_map(|(name, value)| (name == CSRF_FIELD).then_some(value))
+                    });
+
+                (sent, Body::from(bytes))
+            }
+            None => (None, body),
+        };
+
+        let valid = sent
+            .zip(token.as_deref())
+            .is_some_and(|(sent, token)| same_token(&sent, token));
+
+        if !valid {
+            return error_response(&mut parts, StatusCode::FORBIDDEN).await;
+        }
+
+        body
+    } else {
+        body
+    };
+
+    let minted = token.is_none();
+    let token = token.unwrap_or_else(random_token);
+
+    parts.extensions.insert(CsrfToken(token.to_owned()));
+
+    let res = next.run(Request::from_parts(parts, body)).await;
+
+    let html = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+
+    if !minted || !html {
+        return res;
+    }
+
+    let cookies = ctx.reloader.config().cookies;
+    let cookie = Cookie::build((CSRF_COOKIE, token))
+        .path("/")
+        .http_only(true)
+        .secure(cookies.secure)
+        .same_site(cookies.same_site())
+        .build();
+
+    (CookieJar::new().add(cookie), res).into_response()
+}
+
+/// Page of `status` in the language of the request.
+async fn error_response(parts: &mut Parts, status: StatusCode) -> Response {
+    match Context::from_request_parts(parts, &()).await {
+        Ok(ctx) => ctx.error_response(status),
+        Err(response) => response,
+    }
+}
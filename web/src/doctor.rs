@@ -0,0 +1,332 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{env, path::Path, time::Duration};
+use tokio::time::timeout;
+
+use crate::config::Config;
+
+/// Time given to each service to answer.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Clock skew tolerated with the identity provider, which tokens are issued
+/// and expire by.
+const MAX_CLOCK_SKEW_SECS: i64 = 30;
+
+/// Problem found by `doctor`, with how to fix it.
+pub struct Finding {
+    pub problem: String,
+    pub fix: String,
+}
+
+impl Finding {
+    fn new(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// One of the checks of `doctor`, healthy without findings.
+pub struct Diagnosis {
+    pub name: &'static str,
+    pub findings: Vec<Finding>,
+}
+
+/// Checks the environment the server runs in: its variables, the migrations
+/// of the database, its version, the clock against the identity provider and
+/// the pikav namespace.
+pub async fn doctor() -> Vec<Diagnosis> {
+    let env = Diagnosis {
+        name: "env",
+        findings: check_env(),
+    };
+
+    let config = match Config::load().await {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![
+                env,
+                Diagnosis {
+                    name: "config",
+                    findings: vec![Finding::new(
+                        e.to_string(),
+                        "fix the config file of STARTER_CONFIG_PATH or the STARTER_ variables",
+                    )],
+                },
+            ]
+        }
+    };
+
+    let db = match timeout(TIMEOUT, PgPool::connect(&config.dsn)).await {
+        Ok(Ok(db)) => Ok(db),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("timed out".to_owned()),
+    };
+
+    let (migrations, database) = match &db {
+        Ok(db) => (check_migrations(db).await, check_database(db).await),
+        Err(e) => {
+            let findings = vec![Finding::new(
+                format!("can't connect to the database: {e}"),
+                "check the dsn with `config check`",
+            )];
+
+            (findings, vec![])
+        }
+    };
+
+    if let Ok(db) = db {
+        db.close().await;
+    }
+
+    vec![
+        env,
+        Diagnosis {
+            name: "migrations",
+            findings: migrations,
+        },
+        Diagnosis {
+            name: "database",
+            findings: database,
+        },
+        Diagnosis {
+            name: "clock",
+            findings: check_clock(config.jwks_url.as_deref()).await,
+        },
+        Diagnosis {
+            name: "pikav",
+            findings: check_pikav_namespace(&config.pikav.namespace),
+        },
+    ]
+}
+
+/// `STARTER_` variables that aren't settings are silently ignored, which
+/// hides typos.
+fn check_env() -> Vec<Finding> {
+    let mut findings = vec![];
+
+    if let Ok(path) = env::var("STARTER_CONFIG_PATH") {
+        if !Path::new(&path).is_file() {
+            findings.push(Finding::new(
+                format!("STARTER_CONFIG_PATH is {path}, which is not a file"),
+                "point it to the config file or unset it",
+            ));
+        }
+    }
+
+    let mut settings = match serde_json::to_value(Config::default()) {
+        Ok(serde_json::Value::Object(settings)) => settings.into_iter().map(|(key, _)| key),
+        _ => return findings,
+    }
+    .collect::<Vec<_>>();
+    settings.sort();
+
+    let mut unknown = env::vars()
+        .filter_map(|(name, _)| {
+            let key = name.strip_prefix("STARTER_")?.to_lowercase();
+            let setting = key.strip_suffix("_file").unwrap_or(&key);
+            // Nested settings, like `STARTER_OIDC.SESSION_SECRET`.
+            let setting = setting.split('.').next().unwrap_or(setting);
+
+            (key != "config_path" && !settings.iter().any(|known| known == setting)).then_some(name)
+        })
+        .collect::<Vec<_>>();
+    unknown.sort();
+
+    for name in unknown {
+        findings.push(Finding::new(
+            format!("{name} is not a setting and is ignored"),
+            format!(
+                "rename it after one of the settings, STARTER_ followed by {}",
+                settings.join(", ")
+            ),
+        ));
+    }
+
+    findings
+}
+
+async fn check_migrations(db: &PgPool) -> Vec<Finding> {
+    let applied = match sqlx::query_as::<_, (i64, String, bool, Vec<u8>)>(
+        "SELECT version, description, success, checksum FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(applied) => applied,
+        Err(e) => {
+            return vec![Finding::new(
+                format!("can't read the applied migrations: {e}"),
+                "start the server once, it applies the migrations",
+            )]
+        }
+    };
+
+    let migrators = crate::pages::modules()
+        .iter()
+        .filter_map(|module| module.migrator())
+        .collect::<Vec<_>>();
+
+    let embedded = migrators
+        .iter()
+        .flat_map(|migrator| migrator.iter())
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .collect::<Vec<_>>();
+
+    let mut findings = vec![];
+
+    for (version, description, success, checksum) in &applied {
+        match embedded.iter().find(|migration| migration.version == *version) {
+            None => findings.push(Finding::new(
+                format!("migration {version} {description} is applied but unknown to this binary"),
+                "deploy the release that added it, or revert the database",
+            )),
+            Some(_) if !success => findings.push(Finding::new(
+                format!("migration {version} {description} failed"),
+                "fix the database by hand, delete its row from _sqlx_migrations and restart the server",
+            )),
+            Some(migration) if *migration.checksum != checksum[..] => findings.push(Finding::new(
+                format!("migration {version} {description} was changed after it was applied"),
+                "restore the migration file, write a new migration for the change",
+            )),
+            _ => {}
+        }
+    }
+
+    for migration in embedded {
+        if !applied
+            .iter()
+            .any(|(version, ..)| *version == migration.version)
+        {
+            findings.push(Finding::new(
+                format!(
+                    "migration {} {} is not applied",
+                    migration.version, migration.description
+                ),
+                "start the server, it applies pending migrations",
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Feeds searches rely on `tsvector`, supported from CockroachDB 23.1 and
+/// Postgres 12.
+async fn check_database(db: &PgPool) -> Vec<Finding> {
+    let version = match sqlx::query_scalar::<_, String>("SELECT version()")
+        .fetch_one(db)
+        .await
+    {
+        Ok(version) => version,
+        Err(e) => {
+            return vec![Finding::new(
+                format!("can't read the database version: {e}"),
+                "check the permissions of the database user",
+            )]
+        }
+    };
+
+    let supported = parse_version(&version).is_some_and(|(major, minor)| {
+        if version.starts_with("CockroachDB") {
+            (major, minor) >= (23, 1)
+        } else {
+            major >= 12
+        }
+    });
+
+    let search = sqlx::query("SELECT to_tsvector('simple', 'doctor')")
+        .execute(db)
+        .await;
+
+    if supported && search.is_ok() {
+        return vec![];
+    }
+
+    vec![Finding::new(
+        format!("{version} is not supported"),
+        "upgrade to CockroachDB 23.1 or Postgres 12 at least",
+    )]
+}
+
+/// Major and minor of the first version number of `version`, such as
+/// `CockroachDB CCL v23.1.11 (...)` or `PostgreSQL 15.4 on ...`.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let number = version
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))?;
+
+    let mut parts = number
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>().ok());
+
+    let major = parts.next()??;
+    let minor = parts.next().flatten().unwrap_or(0);
+
+    Some((major, minor))
+}
+
+/// Compares the local clock with the `Date` of the jwks response.
+async fn check_clock(jwks_url: Option<&str>) -> Vec<Finding> {
+    let Some(jwks_url) = jwks_url else {
+        return vec![];
+    };
+
+    let response = match reqwest::Client::new()
+        .get(jwks_url)
+        .timeout(TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return vec![Finding::new(
+                format!("can't reach the jwks: {e}"),
+                "check the jwks_url with `config check`",
+            )]
+        }
+    };
+
+    let date = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+
+    let Some(date) = date else {
+        return vec![Finding::new(
+            "the jwks response has no date to compare the clock with",
+            "compare the clock with the identity provider by hand",
+        )];
+    };
+
+    let skew = (Utc::now() - date.with_timezone(&Utc)).num_seconds();
+
+    if skew.abs() <= MAX_CLOCK_SKEW_SECS {
+        return vec![];
+    }
+
+    vec![Finding::new(
+        format!("the clock is {skew}s off the identity provider, tokens may be rejected"),
+        "synchronize the clock with ntp",
+    )]
+}
+
+/// The namespace is a path segment of the sse urls.
+fn check_pikav_namespace(namespace: &str) -> Vec<Finding> {
+    let valid = !namespace.is_empty()
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+
+    if valid {
+        return vec![];
+    }
+
+    vec![Finding::new(
+        format!("pikav namespace {namespace:?} is not valid"),
+        "use lowercase letters, digits, dashes and underscores only",
+    )]
+}
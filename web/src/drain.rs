@@ -0,0 +1,204 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    Extension, Json,
+};
+use starter_feed::{ConsumerGate, WorkerHandle};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::watch, time::sleep};
+use tracing::{info, warn};
+
+use crate::{config::DrainConfig, context::Context};
+
+/// Takes the server out of rotation once `/__drain` is requested or the
+/// process is told to terminate: `/healthz`
+/// fails right away, new requests are refused `DrainConfig::delay` later,
+/// and `serve` returns once the requests in flight, the events being handled
+/// by the consumers and the running jobs are done, within
+/// `DrainConfig::timeout`.
+#[derive(Clone)]
+pub struct Drain {
+    requested: Arc<watch::Sender<bool>>,
+    workers: Arc<Mutex<Vec<WorkerHandle>>>,
+    consumers: Arc<Mutex<Vec<ConsumerGate>>>,
+    config: DrainConfig,
+}
+
+impl Drain {
+    pub fn new(config: &DrainConfig) -> Self {
+        Self {
+            requested: Arc::new(watch::channel(false).0),
+            workers: Default::default(),
+            consumers: Default::default(),
+            config: config.clone(),
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        *self.requested.borrow()
+    }
+
+    /// Starts draining, `false` when it already was.
+    pub fn start(&self) -> bool {
+        self.requested
+            .send_if_modified(|requested| !std::mem::replace(requested, true))
+    }
+
+    /// Job workers to stop before exiting, the ones of every tenant adding
+    /// theirs.
+    pub fn add_workers(&self, workers: WorkerHandle) {
+        self.workers
+            .lock()
+            .expect("drain lock poisoned")
+            .push(workers);
+    }
+
+    /// Consumer to stop before exiting, the one of every tenant adding its
+    /// own.
+    pub fn add_consumer(&self, gate: ConsumerGate) {
+        self.consumers
+            .lock()
+            .expect("drain lock poisoned")
+            .push(gate);
+    }
+
+    /// Resolves once draining started.
+    pub async fn requested(&self) {
+        let mut requested = self.requested.subscribe();
+        let _ = requested.wait_for(|requested| *requested).await;
+    }
+
+    /// Starts draining on `SIGTERM` or `SIGINT`, as sent by Kubernetes and
+    /// ctrl-c, a second one exiting right away.
+    pub fn spawn_signals(&self) {
+        let drain = self.clone();
+
+        tokio::spawn(async move {
+            terminated().await;
+
+            if drain.start() {
+                info!("terminating, refusing requests in {}s", drain.config.delay);
+            }
+
+            terminated().await;
+            warn!("terminated again, exiting without draining");
+
+            std::process::exit(1);
+        });
+    }
+
+    /// Resolves `DrainConfig::delay` after draining started, for the server
+    /// to stop accepting requests.
+    pub async fn stopped(self) {
+        self.requested().await;
+        sleep(Duration::from_secs(self.config.delay)).await;
+    }
+
+    /// Resolves `DrainConfig::timeout` after the server stopped accepting
+    /// requests, whether they are done or not.
+    pub async fn expired(self) {
+        let timeout = self.config.timeout;
+
+        self.stopped().await;
+        sleep(Duration::from_secs(timeout)).await;
+    }
+
+    /// Stops the consumers and the job workers, waiting for the events being
+    /// handled and the running jobs within `DrainConfig::timeout`. The
+    /// events left are handled from the cursors on the next start.
+    pub async fn finish(&self) {
+        let consumers = std::mem::take(&mut *self.consumers.lock().expect("drain lock poisoned"));
+        let workers = std::mem::take(&mut *self.workers.lock().expect("drain lock poisoned"));
+        let timeout = Duration::from_secs(self.config.timeout);
+        let stopped = futures_util::future::join(
+            futures_util::future::join_all(consumers.iter().map(ConsumerGate::close)),
+            futures_util::future::join_all(workers.into_iter().map(WorkerHandle::stop)),
+        );
+
+        if tokio::time::timeout(timeout, stopped).await.is_err() {
+            warn!(
+                "events or jobs still running after {}s, exiting",
+                self.config.timeout
+            );
+        }
+
+        info!("drained");
+    }
+
+    /// Whether `headers` bear `DrainConfig::token`.
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        self.config
+            .token
+            .as_deref()
+            .is_some_and(|token| is_bearer(headers, token))
+    }
+}
+
+/// Whether `headers` bear `token`, compared in constant time.
+pub fn is_bearer(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|bearer| {
+            bearer.len() == token.len()
+                && bearer
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0, |diff, (a, b)| diff | (a ^ b))
+                    == 0
+        })
+}
+
+/// Resolves on the next `SIGTERM` or `SIGINT`, only on ctrl-c where there
+/// are no unix signals.
+#[cfg(unix)]
+async fn terminated() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(err) => {
+            warn!("SIGTERM not handled: {err}");
+
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn terminated() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Starts draining for the admins and the bearers of `DrainConfig::token`,
+/// others getting the not found page. The token is checked before the
+/// signed in user, not being a JWT the user is read from.
+pub async fn drain(
+    Extension(app): Extension<Context>,
+    headers: HeaderMap,
+    ctx: Result<Context, Response>,
+) -> Response {
+    if !app.drain.is_authorized(&headers) && !ctx.is_ok_and(|ctx| ctx.is_admin()) {
+        return app.error_response(StatusCode::NOT_FOUND);
+    }
+
+    if app.drain.start() {
+        info!("draining, refusing requests in {}s", app.drain.config.delay);
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "status": "stopping" })),
+    )
+        .into_response()
+}
@@ -0,0 +1,351 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use evento::store::Event;
+use evento_query::Cursor;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use starter_feed::FeedRule;
+use std::{collections::HashMap, path::Path, time::Duration};
+use tokio::{
+    fs::File,
+    io::{self, AsyncWrite, AsyncWriteExt, BufWriter},
+    time::sleep,
+};
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Event as stored by evento, written as one json line by `export`.
+#[derive(sqlx::FromRow, Serialize)]
+pub struct RawEvent {
+    pub id: Uuid,
+    pub name: String,
+    pub aggregate_id: String,
+    pub version: i32,
+    pub data: serde_json::Value,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Events written by `export`, every one of them unless filtered.
+#[derive(Default)]
+pub struct ExportFilter {
+    /// Aggregate type, such as `feed` or `comment`.
+    pub aggregate: Option<String>,
+    /// Rfc 3339 date of the oldest event.
+    pub since: Option<String>,
+    /// Event names, such as `created`.
+    pub names: Vec<String>,
+    /// User who requested the events, from their metadata.
+    pub user_id: Option<String>,
+}
+
+impl ExportFilter {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>) -> Result<()> {
+        builder.push(" WHERE true");
+
+        if let Some(aggregate) = &self.aggregate {
+            builder
+                .push(" AND split_part(aggregate_id, '/', 1) = ")
+                .push_bind(aggregate.to_owned());
+        }
+
+        if let Some(since) = &self.since {
+            let Ok(since) = DateTime::parse_from_rfc3339(since) else {
+                bail!("since must be an rfc 3339 date, like 2024-03-01T00:00:00Z");
+            };
+
+            builder
+                .push(" AND created_at >= ")
+                .push_bind(since.with_timezone(&Utc));
+        }
+
+        if !self.names.is_empty() {
+            builder
+                .push(" AND name = ANY(")
+                .push_bind(self.names.to_owned())
+                .push(")");
+        }
+
+        if let Some(user_id) = &self.user_id {
+            builder
+                .push(" AND metadata @> jsonb_build_object('req_user', ")
+                .push_bind(user_id.to_owned())
+                .push("::text)");
+        }
+
+        Ok(())
+    }
+}
+
+/// Streams the events matching `filter` as json lines, oldest first, to
+/// `out` or to the standard output. Returns how many were written.
+pub async fn export(filter: &ExportFilter, out: Option<&Path>) -> Result<u64> {
+    let config = Config::load().await?;
+    let db = PgPool::connect(&config.dsn).await?;
+
+    let mut select = QueryBuilder::new(
+        "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event",
+    );
+    filter.push(&mut select)?;
+    select.push(" ORDER BY created_at, aggregate_id, version");
+
+    let out: Box<dyn AsyncWrite + Unpin> = match out {
+        Some(path) => Box::new(File::create(path).await?),
+        None => Box::new(io::stdout()),
+    };
+    let mut out = BufWriter::new(out);
+
+    let mut events = select.build_query_as::<RawEvent>().fetch(&db);
+    let mut count = 0;
+
+    while let Some(event) = events.try_next().await? {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+        out.write_all(&line).await?;
+        count += 1;
+    }
+
+    out.flush().await?;
+
+    Ok(count)
+}
+
+/// Events appended within this window are read again by `tail`, as those
+/// committed late may have an earlier date than the last one printed.
+const TAIL_OVERLAP_SECS: i64 = 5;
+
+/// How often `tail` reads the new events. Polling is used since CockroachDB
+/// has no `LISTEN`/`NOTIFY`.
+const TAIL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Prints the events appended from now on with their data and metadata
+/// pretty-printed, until interrupted. `rule` restricts them to the events
+/// received by a rule, like `feed-details`, or to an aggregate type, like
+/// `feed`.
+pub async fn tail(rule: Option<&str>) -> Result<()> {
+    let aggregate = rule.map(|rule| match rule.parse::<FeedRule>() {
+        Ok(rule) => rule.aggregate_type().to_owned(),
+        Err(_) => rule.to_owned(),
+    });
+
+    let filter = ExportFilter {
+        aggregate,
+        ..Default::default()
+    };
+
+    let config = Config::load().await?;
+    let db = PgPool::connect(&config.dsn).await?;
+
+    let mut since = Utc::now();
+    let mut printed = HashMap::<Uuid, DateTime<Utc>>::new();
+
+    loop {
+        let mut select = QueryBuilder::new(
+            "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event",
+        );
+        filter.push(&mut select)?;
+        select
+            .push(" AND created_at >= ")
+            .push_bind(since - chrono::Duration::seconds(TAIL_OVERLAP_SECS))
+            .push(" ORDER BY created_at, aggregate_id, version");
+
+        let events = select.build_query_as::<RawEvent>().fetch_all(&db).await?;
+
+        for event in events {
+            if printed.contains_key(&event.id) {
+                continue;
+            }
+
+            println!(
+                "{}\t{}\t{}\tv{}",
+                event.created_at.to_rfc3339(),
+                event.aggregate_id,
+                event.name,
+                event.version
+            );
+            println!("{}", serde_json::to_string_pretty(&event.data)?);
+
+            if let Some(metadata) = &event.metadata {
+                println!("{}", serde_json::to_string_pretty(metadata)?);
+            }
+
+            println!();
+
+            since = since.max(event.created_at);
+            printed.insert(event.id, event.created_at);
+        }
+
+        printed.retain(|_, created_at| {
+            *created_at >= since - chrono::Duration::seconds(TAIL_OVERLAP_SECS)
+        });
+
+        sleep(TAIL_INTERVAL).await;
+    }
+}
+
+/// Moves the cursor of the consumers of `rule` back to `cursor`, the first
+/// event when `None`, the events appended so far being marked as replayed
+/// for the handlers of `side_effect_rule` to skip. Returns whether `rule`
+/// has consumers.
+async fn rewind(conn: &mut PgConnection, rule: &str, cursor: Option<String>) -> Result<bool> {
+    let rewound = sqlx::query("UPDATE ev_queue SET cursor = $2 WHERE rule = $1")
+        .bind(rule)
+        .bind(cursor)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    if rewound == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        "INSERT INTO ev_replays (rule, until, created_at) SELECT $1, COALESCE(MAX(created_at), now()), now() FROM ev_event ON CONFLICT (rule) DO UPDATE SET until = GREATEST(ev_replays.until, EXCLUDED.until), created_at = EXCLUDED.created_at",
+    )
+    .bind(rule)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(true)
+}
+
+/// Has the handlers of `rule` read the events again from `from`, or from the
+/// first one, without mailing or pushing anything twice. The read models of
+/// the rule are left as they are, `rebuild` emptying them first. Returns how
+/// many events are read again.
+pub async fn replay(rule: &str, from: Option<Uuid>) -> Result<u64> {
+    let config = Config::load().await?;
+    let db = PgPool::connect(&config.dsn).await?;
+
+    let aggregate = rule
+        .parse::<FeedRule>()
+        .ok()
+        .map(|rule| rule.aggregate_type().to_owned());
+
+    let (cursor, since) = match from {
+        Some(from) => {
+            let Some(event) = sqlx::query_as::<_, Event>(
+                "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event WHERE id = $1",
+            )
+            .bind(from)
+            .fetch_optional(&db)
+            .await?
+            else {
+                bail!("event {from} not found");
+            };
+
+            // Consumers read the events after their cursor, the one of the
+            // event before `from` then.
+            let previous = sqlx::query_as::<_, Event>(
+                "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event WHERE (created_at, version, id) < ($1, $2, $3) ORDER BY created_at DESC, version DESC, id DESC LIMIT 1",
+            )
+            .bind(event.created_at)
+            .bind(event.version)
+            .bind(event.id)
+            .fetch_optional(&db)
+            .await?;
+
+            (
+                previous.map(|previous| previous.to_cursor().0),
+                Some((event.created_at, event.version, event.id)),
+            )
+        }
+        None => (None, None),
+    };
+
+    let mut tx = db.begin().await?;
+
+    if !rewind(&mut tx, rule, cursor).await? {
+        bail!("{rule} has no consumers, the worker must have run it once");
+    }
+
+    tx.commit().await?;
+
+    let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM ev_event WHERE true");
+
+    if let Some(aggregate) = aggregate {
+        count
+            .push(" AND split_part(aggregate_id, '/', 1) = ")
+            .push_bind(aggregate);
+    }
+
+    if let Some((created_at, version, id)) = since {
+        count
+            .push(" AND (created_at, version, id) >= (")
+            .push_bind(created_at)
+            .push(", ")
+            .push_bind(version)
+            .push(", ")
+            .push_bind(id)
+            .push(")");
+    }
+
+    let count = count.build_query_scalar::<i64>().fetch_one(&db).await?;
+
+    Ok(count as u64)
+}
+
+/// Empties the read models of `FeedRule::tables` and replays their rules
+/// from the first event, within a transaction. Returns the rules replayed,
+/// those without consumers yet reading every event once started anyway.
+pub async fn rebuild() -> Result<Vec<String>> {
+    let config = Config::load().await?;
+    let db = PgPool::connect(&config.dsn).await?;
+
+    let rules = FeedRule::all()
+        .into_iter()
+        .filter(|rule| !rule.tables().is_empty())
+        .collect::<Vec<_>>();
+
+    let mut tables = rules
+        .iter()
+        .flat_map(|rule| rule.tables())
+        .copied()
+        .collect::<Vec<_>>();
+    tables.sort_unstable();
+    tables.dedup();
+
+    let mut tx = db.begin().await?;
+
+    sqlx::query(&format!("TRUNCATE {}", tables.join(", ")))
+        .execute(&mut *tx)
+        .await?;
+
+    let mut replayed = vec![];
+
+    for rule in rules {
+        let rule = rule.to_string();
+
+        if rewind(&mut tx, &rule, None).await? {
+            replayed.push(rule);
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(replayed)
+}
+
+/// Whether the enabled rules of the consumers on `db` read every event
+/// appended so far, their cursor being the one of the last event.
+pub async fn caught_up(db: &PgPool) -> Result<bool> {
+    let Some(last) = sqlx::query_as::<_, Event>(
+        "SELECT id, name, aggregate_id, version, data, metadata, created_at FROM ev_event ORDER BY created_at DESC, version DESC, id DESC LIMIT 1",
+    )
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(true);
+    };
+
+    let behind = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM ev_queue WHERE enabled AND cursor IS DISTINCT FROM $1",
+    )
+    .bind(last.to_cursor().0)
+    .fetch_one(db)
+    .await?;
+
+    Ok(behind == 0)
+}
@@ -0,0 +1,277 @@
+use askama_axum::Response;
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequest, FromRequestParts, Request},
+    http::{request::Parts, HeaderMap, StatusCode},
+};
+use axum_extra::extract::CookieJar;
+use chrono_tz::Tz;
+use evento_axum::UserLanguage;
+use serde::de::DeserializeOwned;
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+use tracing::warn;
+
+use crate::{context::Context, i18n::select_languages, localized};
+
+/// Language the localized deserializers read in, as selected by
+/// `Context::user_language`.
+async fn user_language<S: Send + Sync>(parts: &mut Parts, state: &S) -> String {
+    let preferred = UserLanguage::from_request_parts(parts, state)
+        .await
+        .map(|user_language| user_language.preferred_languages().to_vec())
+        .unwrap_or_default();
+
+    select_languages(&preferred).1
+}
+
+/// Localized page of a rejection of `status`, the `Context` being built for
+/// the rejected requests only.
+async fn rejection_response<S: Send + Sync>(
+    parts: &mut Parts,
+    state: &S,
+    status: StatusCode,
+) -> Response {
+    match Context::from_request_parts(parts, state).await {
+        Ok(ctx) => ctx.error_response(status),
+        Err(response) => response,
+    }
+}
+
+/// Same as [`axum::Form`] but rejects with the localized error page.
+pub struct Form<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Form<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (mut parts, body) = req.into_parts();
+        let lang = user_language(&mut parts, state).await;
+        // The body is gone once the form is rejected, the page of the
+        // rejection being rendered from the head of the request.
+        let mut head = parts.clone();
+        let req = Request::from_parts(parts, body);
+
+        match localized::scope(lang, axum::Form::<T>::from_request(req, state)).await {
+            Ok(axum::Form(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("{rejection}");
+
+                Err(rejection_response(&mut head, state, rejection.status()).await)
+            }
+        }
+    }
+}
+
+/// Same as [`axum::extract::Query`] but rejects with the localized error page.
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let lang = user_language(parts, state).await;
+
+        match localized::scope(
+            lang,
+            axum::extract::Query::<T>::from_request_parts(parts, state),
+        )
+        .await
+        {
+            Ok(axum::extract::Query(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("{rejection}");
+
+                Err(rejection_response(parts, state, rejection.status()).await)
+            }
+        }
+    }
+}
+
+/// Same as [`axum::extract::Path`] but rejects with the localized error page.
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("{rejection}");
+
+                Err(rejection_response(parts, state, rejection.status()).await)
+            }
+        }
+    }
+}
+
+/// htmx request headers, see <https://htmx.org/reference/#request_headers>.
+#[derive(Default, Clone, Debug)]
+pub struct HxRequest {
+    pub request: bool,
+    pub boosted: bool,
+    pub target: Option<String>,
+    pub trigger: Option<String>,
+}
+
+impl HxRequest {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+
+        Self {
+            request: header("HX-Request").is_some_and(|value| value == "true"),
+            boosted: header("HX-Boosted").is_some_and(|value| value == "true"),
+            target: header("HX-Target"),
+            trigger: header("HX-Trigger"),
+        }
+    }
+
+    /// True when htmx swaps the response into an element of the current page,
+    /// boosted navigations expect a full document.
+    pub fn is_partial(&self) -> bool {
+        self.request && !self.boosted
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for HxRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// IANA timezone from the `X-Timezone` header or the `tz` cookie set by
+/// `_base.html`, falls back to UTC when missing or invalid.
+#[derive(Clone, Debug)]
+pub struct UserTimezone(pub Tz);
+
+impl UserTimezone {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = headers
+            .get("X-Timezone")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_owned());
+
+        let cookie = CookieJar::from_headers(headers)
+            .get("tz")
+            .map(|cookie| cookie.value().to_owned());
+
+        let timezone = header
+            .into_iter()
+            .chain(cookie)
+            .find_map(|name| name.parse::<Tz>().ok())
+            .unwrap_or(Tz::UTC);
+
+        Self(timezone)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for UserTimezone
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Client address of the connection or, when it comes from one of
+/// `Config::trusted_proxies`, the right-most address of `X-Forwarded-For`
+/// that is not one of them, or `X-Real-IP` without it.
+#[derive(Clone, Debug)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let connect_info = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        let Some(config) = parts
+            .extensions
+            .get::<Context>()
+            .map(|ctx| ctx.reloader.config())
+        else {
+            return Ok(Self(connect_info));
+        };
+
+        if !connect_info.is_some_and(|ip| config.is_trusted_proxy(ip)) {
+            return Ok(Self(connect_info));
+        }
+
+        let hops = parts
+            .headers
+            .get_all("X-Forwarded-For")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .collect::<Vec<_>>();
+
+        if hops.is_empty() {
+            let real_ip = parts
+                .headers
+                .get("X-Real-IP")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok());
+
+            return Ok(Self(real_ip.or(connect_info)));
+        }
+
+        // Going left, the first hop not added by a trusted proxy is the
+        // client, the ones before it being whatever the client sent.
+        let mut client = connect_info;
+
+        for hop in hops.into_iter().rev() {
+            let Some(ip) = hop else {
+                break;
+            };
+
+            client = Some(ip);
+
+            if !config.is_trusted_proxy(ip) {
+                break;
+            }
+        }
+
+        Ok(Self(client))
+    }
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use askama_axum::Response;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts, http::StatusCode};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
+
+use crate::{config::Config, context::Context};
+
+/// A named feature flag, enabled from `Config::features` and overridable at
+/// runtime from the `feature_flags` table.
+///
+/// ```ignore
+/// pub struct NewFeedUi;
+///
+/// impl FeatureFlag for NewFeedUi {
+///     const NAME: &'static str = "new_feed_ui";
+/// }
+/// ```
+pub trait FeatureFlag {
+    const NAME: &'static str;
+}
+
+/// Responds with the not found page when the feature `F` is disabled, either as
+/// a handler argument or as a guard with `middleware::from_extractor`.
+pub struct Feature<F>(PhantomData<F>);
+
+#[async_trait]
+impl<F, S> FromRequestParts<S> for Feature<F>
+where
+    F: FeatureFlag,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = Context::from_request_parts(parts, state).await?;
+
+        if !ctx.is_feature_enabled(F::NAME).await? {
+            return Err(ctx.error_response(StatusCode::NOT_FOUND));
+        }
+
+        Ok(Self(PhantomData))
+    }
+}
+
+/// Whether a feature is enabled, for a percentage of the users when rolled
+/// out.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeatureRule {
+    pub enabled: bool,
+    /// Users the enabled feature is on for, in percent, all of them when
+    /// unset. Signed out visitors only get it rolled out to everyone.
+    pub rollout: Option<u8>,
+}
+
+impl FeatureRule {
+    fn is_on_for(&self, name: &str, user_id: Option<&str>) -> bool {
+        match (self.enabled, self.rollout, user_id) {
+            (false, ..) => false,
+            (true, None, _) => true,
+            (true, Some(rollout), Some(user_id)) => bucket(name, user_id) < rollout,
+            (true, Some(rollout), None) => rollout >= 100,
+        }
+    }
+}
+
+/// Stable bucket from 0 to 99 of the user for the feature, FNV-1a hashed so
+/// that it doesn't change between releases and differs between features.
+fn bucket(name: &str, user_id: &str) -> u8 {
+    let hash = name
+        .bytes()
+        .chain(std::iter::once(b':'))
+        .chain(user_id.bytes())
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    (hash % 100) as u8
+}
+
+/// Feature as listed by the admins, its override winning over its default.
+#[derive(Clone, Debug)]
+pub struct FeatureState {
+    pub name: String,
+    pub default: Option<FeatureRule>,
+    pub override_rule: Option<FeatureRule>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl FeatureState {
+    pub fn rule(&self) -> FeatureRule {
+        self.override_rule.or(self.default).unwrap_or_default()
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct FeatureRow {
+    name: String,
+    enabled: bool,
+    rollout: Option<i16>,
+    updated_at: DateTime<Utc>,
+}
+
+impl FeatureRow {
+    fn rule(&self) -> FeatureRule {
+        FeatureRule {
+            enabled: self.enabled,
+            rollout: self.rollout.map(|rollout| rollout.clamp(0, 100) as u8),
+        }
+    }
+}
+
+/// Feature flags of `Config::features` and `Config::feature_rollouts`, the
+/// admins overriding them at runtime from `/admin/features`.
+#[derive(Clone)]
+pub struct Features {
+    db: PgPool,
+    defaults: Arc<RwLock<HashMap<String, FeatureRule>>>,
+}
+
+fn defaults(config: &Config) -> HashMap<String, FeatureRule> {
+    config
+        .features
+        .iter()
+        .map(|(name, &enabled)| {
+            let rule = FeatureRule {
+                enabled,
+                rollout: config
+                    .feature_rollouts
+                    .get(name)
+                    .map(|&rollout| rollout.min(100)),
+            };
+
+            (name.to_owned(), rule)
+        })
+        .collect()
+}
+
+impl Features {
+    pub fn new(db: PgPool, config: &Config) -> Self {
+        Self {
+            db,
+            defaults: Arc::new(RwLock::new(defaults(config))),
+        }
+    }
+
+    /// Replaces the defaults with the features of `config`, once reloaded.
+    pub fn set_defaults(&self, config: &Config) {
+        *self.defaults.write().expect("features lock poisoned") = defaults(config);
+    }
+
+    /// Whether `name` is on for `user_id`, features known neither to the
+    /// config nor to the table being disabled.
+    pub async fn is_enabled(&self, name: &str, user_id: Option<&str>) -> Result<bool> {
+        let row = sqlx::query_as::<_, FeatureRow>("SELECT * FROM feature_flags WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.db)
+            .await?;
+
+        let rule = match row {
+            Some(row) => row.rule(),
+            None => self
+                .defaults
+                .read()
+                .expect("features lock poisoned")
+                .get(name)
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        Ok(rule.is_on_for(name, user_id))
+    }
+
+    /// Features of the config and the overridden ones, by name.
+    pub async fn list(&self) -> Result<Vec<FeatureState>> {
+        let rows = sqlx::query_as::<_, FeatureRow>("SELECT * FROM feature_flags")
+            .fetch_all(&self.db)
+            .await?;
+
+        let mut features = self
+            .defaults
+            .read()
+            .expect("features lock poisoned")
+            .iter()
+            .map(|(name, &rule)| {
+                let state = FeatureState {
+                    name: name.to_owned(),
+                    default: Some(rule),
+                    override_rule: None,
+                    updated_at: None,
+                };
+
+                (name.to_owned(), state)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        for row in rows {
+            let state = features
+                .entry(row.name.to_owned())
+                .or_insert_with(|| FeatureState {
+                    name: row.name.to_owned(),
+                    default: None,
+                    override_rule: None,
+                    updated_at: None,
+                });
+
+            state.override_rule = Some(row.rule());
+            state.updated_at = Some(row.updated_at);
+        }
+
+        Ok(features.into_values().collect())
+    }
+
+    /// Overrides the default of `name`, or the lack of one.
+    pub async fn set(&self, name: &str, rule: FeatureRule) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO feature_flags (name, enabled, rollout, updated_at) VALUES ( $1, $2, $3, $4 )
+            ON CONFLICT (name) DO UPDATE SET enabled = EXCLUDED.enabled, rollout = EXCLUDED.rollout, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(rule.enabled)
+        .bind(rule.rollout.map(|rollout| rollout.min(100) as i16))
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drops the override of `name`, `false` when it had none.
+    pub async fn reset(&self, name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE name = $1")
+            .bind(name)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
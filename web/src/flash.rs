@@ -0,0 +1,128 @@
+use askama::Template;
+use axum::{
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::{IntoResponseParts, Response, ResponseParts},
+};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+
+use crate::extract::HxRequest;
+
+const FLASH_COOKIE: &str = "flash";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FlashLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// A message shown as a toast on the next page rendered for the user.
+///
+/// ```ignore
+/// Ok((Flash::success(fl!(ctx.fl_loader(), "...")), Redirect::to("/")))
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Flash {
+    pub level: FlashLevel,
+    pub message: String,
+}
+
+impl Flash {
+    pub fn new(level: FlashLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(FlashLevel::Info, message)
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(FlashLevel::Success, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(FlashLevel::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(FlashLevel::Error, message)
+    }
+
+    pub fn class(&self) -> &'static str {
+        match self.level {
+            FlashLevel::Info => "alert-info",
+            FlashLevel::Success => "alert-success",
+            FlashLevel::Warning => "alert-warning",
+            FlashLevel::Error => "alert-error",
+        }
+    }
+
+    pub fn from_jar(jar: &CookieJar) -> Vec<Self> {
+        jar.get(FLASH_COOKIE)
+            .and_then(|cookie| serde_json::from_str(cookie.value()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Html of the toast, published on the `toasts` pikav topic with the
+    /// `toast` event to show it live.
+    pub fn to_html(&self) -> String {
+        ToastTemplate {
+            toast: self.clone(),
+        }
+        .render()
+        .unwrap_or_default()
+    }
+}
+
+impl IntoResponseParts for Flash {
+    type Error = Infallible;
+
+    fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let value = serde_json::to_string(&vec![self]).unwrap_or_default();
+        let mut cookie = Cookie::new(FLASH_COOKIE, value);
+        cookie.set_path("/");
+        cookie.set_http_only(true);
+
+        CookieJar::new().add(cookie).into_response_parts(res)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "_toast.html")]
+struct ToastTemplate {
+    toast: Flash,
+}
+
+/// Removes the flash cookie once a full page displayed it.
+pub async fn clear_flash(req: Request, next: Next) -> Response {
+    let jar = CookieJar::from_headers(req.headers());
+    let hx = HxRequest::from_headers(req.headers());
+    let mut res = next.run(req).await;
+
+    if jar.get(FLASH_COOKIE).is_none()
+        || hx.is_partial()
+        || !res.status().is_success()
+        || res.headers().contains_key(header::SET_COOKIE)
+    {
+        return res;
+    }
+
+    let mut cookie = Cookie::from(FLASH_COOKIE);
+    cookie.set_path("/");
+    cookie.make_removal();
+
+    if let Ok(value) = cookie.to_string().parse() {
+        res.headers_mut().append(header::SET_COOKIE, value);
+    }
+
+    res
+}
@@ -0,0 +1,111 @@
+//! Entry points of the targets of `web/fuzz`, built with the `fuzz` feature.
+//! Each one takes input as a client could send it and panics on a broken
+//! invariant, errors being expected.
+
+use axum::{extract::FromRequestParts, http::Request};
+use evento_axum::{AcceptLanguageSource, QuerySource, UserLanguage};
+use once_cell::sync::Lazy;
+use starter_feed::{CreateCommentInput, CreateFeedInput, ListFeedsInput, SaveDraftInput};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    components::{Markdown, TableQuery},
+    extract::{ClientIp, HxRequest, UserTimezone},
+    i18n::{select_languages, CookieSource, LANGUAGES},
+};
+
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("fuzz runtime not built")
+});
+
+/// Language negotiated from the `lang` cookie, the `lang` query parameter and
+/// the `Accept-Language` header as for the pages, which must be one of `i18n/`.
+pub fn user_language(accept_language: &[u8], query: &str) {
+    let Ok(request) = Request::get(format!("/?{query}"))
+        .header("Accept-Language", accept_language)
+        .body(())
+    else {
+        return;
+    };
+
+    let (mut parts, _) = request.into_parts();
+    parts.extensions.insert(
+        UserLanguage::config()
+            .add_source(CookieSource)
+            .add_source(QuerySource::new("lang"))
+            .add_source(AcceptLanguageSource)
+            .build(),
+    );
+
+    let preferred = RUNTIME
+        .block_on(UserLanguage::from_request_parts(&mut parts, &()))
+        .map(|user_language| user_language.preferred_languages().to_vec())
+        .unwrap_or_default();
+    let (_, user_language) = select_languages(&preferred);
+
+    assert!(
+        LANGUAGES
+            .iter()
+            .any(|language| language.to_string() == user_language),
+        "{user_language} is not available"
+    );
+}
+
+/// Headers read by the extractors of `extract`, as `name: value` lines.
+pub fn headers(data: &[u8]) {
+    let mut request = Request::get("/");
+
+    for line in data.split(|byte| *byte == b'\n') {
+        if let Some(index) = line.iter().position(|byte| *byte == b':') {
+            request = request.header(&line[..index], &line[index + 1..]);
+        }
+    }
+
+    let Ok(request) = request.body(()) else {
+        return;
+    };
+
+    let (mut parts, _) = request.into_parts();
+
+    HxRequest::from_headers(&parts.headers);
+    UserTimezone::from_headers(&parts.headers);
+    let _ = RUNTIME.block_on(ClientIp::from_request_parts(&mut parts, &()));
+}
+
+fn form<T: serde::de::DeserializeOwned + Validate>(data: &[u8]) {
+    if let Ok(input) = serde_urlencoded::from_bytes::<T>(data) {
+        let _ = input.validate();
+    }
+}
+
+/// Url encoded bodies and queries as `Form` and `Query` deserialize them,
+/// validated like the commands do.
+pub fn forms(data: &[u8]) {
+    form::<CreateFeedInput>(data);
+    form::<CreateCommentInput>(data);
+    form::<SaveDraftInput>(data);
+
+    let _ = serde_urlencoded::from_bytes::<ListFeedsInput>(data);
+
+    if let Ok(query) = serde_urlencoded::from_bytes::<TableQuery>(data) {
+        query.offset();
+        query.search();
+    }
+}
+
+/// Markdown of feeds and comments, which must render without scripts.
+pub fn markdown(source: &str) {
+    for html in [
+        Markdown::new(source).to_string(),
+        Markdown::with_mentions(source, |user_id: Uuid| format!("/users/{user_id}")).to_string(),
+    ] {
+        let html = html.to_lowercase();
+
+        assert!(!html.contains("<script"), "script in {html}");
+        assert!(!html.contains("=\"javascript:"), "javascript url in {html}");
+    }
+}
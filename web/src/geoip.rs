@@ -0,0 +1,134 @@
+use anyhow::Result;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use evento_axum::UserLanguageSource;
+use maxminddb::{geoip2, Reader};
+use std::{fmt, net::IpAddr, sync::Arc};
+use tracing::debug;
+
+use crate::{config::Config, extract::ClientIp};
+
+/// Language spoken by default in a country, for the countries of the
+/// languages of `i18n/`, visitors of the others getting the fallback one.
+const COUNTRY_LANGUAGES: &[(&str, &str)] = &[
+    ("BE", "fr"),
+    ("BF", "fr"),
+    ("BJ", "fr"),
+    ("CD", "fr"),
+    ("CI", "fr"),
+    ("CM", "fr"),
+    ("FR", "fr"),
+    ("GA", "fr"),
+    ("GN", "fr"),
+    ("HT", "fr"),
+    ("LU", "fr"),
+    ("MC", "fr"),
+    ("MG", "fr"),
+    ("ML", "fr"),
+    ("NE", "fr"),
+    ("SN", "fr"),
+    ("TD", "fr"),
+    ("TG", "fr"),
+];
+
+/// Currency of the prices shown by default in a country, others being shown
+/// in `USD`.
+const COUNTRY_CURRENCIES: &[(&str, &str)] = &[
+    ("AT", "EUR"),
+    ("BE", "EUR"),
+    ("CA", "CAD"),
+    ("CH", "CHF"),
+    ("DE", "EUR"),
+    ("ES", "EUR"),
+    ("FI", "EUR"),
+    ("FR", "EUR"),
+    ("GB", "GBP"),
+    ("IE", "EUR"),
+    ("IT", "EUR"),
+    ("LU", "EUR"),
+    ("MC", "EUR"),
+    ("NL", "EUR"),
+    ("PT", "EUR"),
+];
+
+fn lookup<'a>(table: &'a [(&str, &'a str)], country: &str) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(code, _)| *code == country)
+        .map(|(_, value)| *value)
+}
+
+/// Country of the visitors from the MaxMind database of
+/// `Config::geoip_path`, like `GeoLite2-Country.mmdb`, unknown when unset.
+#[derive(Clone, Default)]
+pub struct GeoIp {
+    reader: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl fmt::Debug for GeoIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeoIp")
+            .field("enabled", &self.reader.is_some())
+            .finish()
+    }
+}
+
+impl GeoIp {
+    pub fn open(config: &Config) -> Result<Self> {
+        let reader = match config.geoip_path.as_deref() {
+            Some(path) => Some(Arc::new(Reader::open_readfile(path)?)),
+            None => None,
+        };
+
+        Ok(Self { reader })
+    }
+
+    /// ISO 3166-1 code of the country of `ip`, like `FR`.
+    pub fn country(&self, ip: IpAddr) -> Option<String> {
+        let reader = self.reader.as_ref()?;
+
+        match reader.lookup::<geoip2::Country>(ip) {
+            Ok(country) => country
+                .country
+                .and_then(|country| country.iso_code)
+                .map(str::to_owned),
+            Err(err) => {
+                debug!("{ip} not found: {err}");
+
+                None
+            }
+        }
+    }
+
+    /// Country of the client of the request.
+    pub async fn country_from_parts(&self, parts: &mut Parts) -> Option<String> {
+        self.reader.as_ref()?;
+
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, &()).await.ok()?;
+
+        self.country(ip?)
+    }
+}
+
+/// Language of the country of the client, the lowest in priority of the
+/// sources of `UserLanguage`.
+#[derive(Debug)]
+pub struct GeoIpSource(pub GeoIp);
+
+#[async_trait]
+impl UserLanguageSource for GeoIpSource {
+    async fn languages_from_parts(&self, parts: &mut Parts) -> Vec<String> {
+        self.0
+            .country_from_parts(parts)
+            .await
+            .and_then(|country| lookup(COUNTRY_LANGUAGES, &country))
+            .map(|language| vec![language.to_owned()])
+            .unwrap_or_default()
+    }
+}
+
+/// Currency prices default to in `country`.
+pub fn currency(country: Option<&str>) -> &'static str {
+    country
+        .and_then(|country| lookup(COUNTRY_CURRENCIES, country))
+        .unwrap_or("USD")
+}
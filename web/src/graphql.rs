@@ -0,0 +1,354 @@
+use async_graphql::{
+    connection::{Connection, Edge},
+    dataloader::{DataLoader, Loader},
+    Context as Resolver, EmptyMutation, EmptySubscription, Error, Object, Result, Schema, ID,
+};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use chrono::{DateTime, Utc};
+use evento::{QueryError, QueryHandler};
+use evento_query::QueryResult;
+use once_cell::sync::Lazy;
+use starter_feed::{
+    GetFeedInput, GetUserInput, ListFeedsCommentsInput, ListFeedsInput, ListUserFeedsInput,
+    UserComment, UserFeed,
+};
+use std::collections::HashMap;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    context::Context,
+    routes::{on, Routes},
+};
+
+const DEFAULT_PAGE_SIZE: i32 = 20;
+const MAX_PAGE_SIZE: i32 = 100;
+
+/// Nesting of a query, so that feeds of the authors of feeds can't be walked
+/// down forever.
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 500;
+
+pub type FeedSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+static SCHEMA: Lazy<FeedSchema> = Lazy::new(|| {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+});
+
+/// `/graphql`, with GraphiQL on `GET` in debug builds.
+pub fn create_router() -> Routes {
+    #[cfg(debug_assertions)]
+    let endpoint = on!(Public get(graphiql), Public post(graphql));
+    #[cfg(not(debug_assertions))]
+    let endpoint = on!(Public post(graphql));
+
+    Routes::new().route("/", endpoint)
+}
+
+/// Runs a query as the user signed in with the same JWT as the pages, the
+/// comments being loaded in batches for the whole request.
+pub async fn graphql(ctx: Context, req: GraphQLRequest) -> GraphQLResponse {
+    let comments = DataLoader::new(
+        CommentsLoader {
+            query: ctx.query.clone(),
+        },
+        tokio::spawn,
+    );
+
+    SCHEMA
+        .execute(req.into_inner().data(comments).data(ctx))
+        .await
+        .into()
+}
+
+#[cfg(debug_assertions)]
+pub async fn graphiql(ctx: Context) -> axum::response::Html<String> {
+    axum::response::Html(
+        async_graphql::http::GraphiQLSource::build()
+            .endpoint(&ctx.create_absolute_url("/graphql"))
+            .finish(),
+    )
+}
+
+/// Not found errors are told to the client, server ones are only logged.
+fn query_error(err: QueryError) -> Error {
+    match err {
+        QueryError::NotFound(detail) => Error::new(detail),
+        QueryError::Server(err) => {
+            error!("{err}");
+
+            Error::new("Internal Server Error")
+        }
+    }
+}
+
+async fn query<I: QueryHandler>(resolver: &Resolver<'_>, input: I) -> Result<I::Output> {
+    resolver
+        .data::<Context>()?
+        .query
+        .execute(&input)
+        .await
+        .map_err(query_error)
+}
+
+fn page_size(first: Option<i32>) -> u16 {
+    first.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as u16
+}
+
+fn connection(feeds: QueryResult<UserFeed>) -> Connection<String, FeedItem> {
+    let mut connection = Connection::new(
+        feeds.page_info.has_previous_page,
+        feeds.page_info.has_next_page,
+    );
+
+    connection.edges.extend(
+        feeds
+            .edges
+            .into_iter()
+            .map(|edge| Edge::new(edge.cursor.0, FeedItem(edge.node))),
+    );
+
+    connection
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Public feeds, latest first.
+    async fn feeds(
+        &self,
+        resolver: &Resolver<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        tag: Option<String>,
+    ) -> Result<Connection<String, FeedItem>> {
+        let feeds = query(
+            resolver,
+            ListFeedsInput {
+                first: Some(page_size(first)),
+                after: after.map(evento_query::CursorType),
+                last: None,
+                before: None,
+                tag,
+                author: None,
+                unpinned: false,
+            },
+        )
+        .await?;
+
+        Ok(connection(feeds))
+    }
+
+    /// Feed visible to the signed in user.
+    async fn feed(&self, resolver: &Resolver<'_>, id: ID) -> Result<Option<FeedItem>> {
+        let ctx = resolver.data::<Context>()?;
+
+        let feed = match ctx.query.execute(&GetFeedInput { id: id.0 }).await {
+            Ok(feed) => feed,
+            Err(QueryError::NotFound(_)) => return Ok(None),
+            Err(err) => return Err(query_error(err)),
+        };
+
+        if feed.hidden || !feed.is_visible_to(ctx.user_id.as_deref()) {
+            return Ok(None);
+        }
+
+        Ok(Some(FeedItem(feed)))
+    }
+
+    /// Profile of a user, known once they created a user or a public feed.
+    async fn user(&self, resolver: &Resolver<'_>, id: Uuid) -> Result<Option<Profile>> {
+        if let Some(user) = query(resolver, GetUserInput { id: id.to_string() }).await? {
+            return Ok(Some(Profile {
+                id,
+                name: user.name,
+            }));
+        }
+
+        let feeds = query(
+            resolver,
+            ListUserFeedsInput {
+                user_id: id.to_string(),
+                first: Some(1),
+                after: None,
+                last: None,
+                before: None,
+            },
+        )
+        .await?;
+
+        Ok(feeds.edges.into_iter().next().map(|edge| Profile {
+            id,
+            name: edge.node.author,
+        }))
+    }
+}
+
+/// Feed resolved only once checked visible to the signed in user.
+pub struct FeedItem(UserFeed);
+
+#[Object(name = "Feed")]
+impl FeedItem {
+    async fn id(&self) -> ID {
+        ID(self.0.id.to_owned())
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn content(&self) -> &str {
+        &self.0.content
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.0.tags
+    }
+
+    async fn total_likes(&self) -> i32 {
+        self.0.total_likes
+    }
+
+    async fn visibility(&self) -> &str {
+        &self.0.visibility
+    }
+
+    /// Absolute url of the feed page.
+    async fn url(&self, resolver: &Resolver<'_>) -> Result<String> {
+        Ok(resolver
+            .data::<Context>()?
+            .create_absolute_url(self.0.path()))
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn edited_at(&self) -> Option<DateTime<Utc>> {
+        self.0.edited_at
+    }
+
+    async fn author(&self) -> Profile {
+        Profile {
+            id: self.0.user_id,
+            name: self.0.author.to_owned(),
+        }
+    }
+
+    /// Comments, oldest first, replies listing their `parentId`.
+    async fn comments(&self, resolver: &Resolver<'_>) -> Result<Vec<CommentItem>> {
+        let comments = resolver
+            .data::<DataLoader<CommentsLoader>>()?
+            .load_one(self.0.id.to_owned())
+            .await?;
+
+        Ok(comments.unwrap_or_default())
+    }
+}
+
+#[derive(Clone)]
+pub struct CommentItem(UserComment);
+
+#[Object(name = "Comment")]
+impl CommentItem {
+    async fn id(&self) -> ID {
+        ID(self.0.id.to_owned())
+    }
+
+    async fn parent_id(&self) -> Option<ID> {
+        self.0.parent_id.to_owned().map(ID)
+    }
+
+    /// Left out of deleted comments, which keep their place in the thread.
+    async fn content(&self) -> Option<&str> {
+        (!self.0.deleted).then_some(self.0.content.as_str())
+    }
+
+    async fn deleted(&self) -> bool {
+        self.0.deleted
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn author(&self) -> Profile {
+        Profile {
+            id: self.0.user_id,
+            name: self.0.author.to_owned(),
+        }
+    }
+}
+
+pub struct Profile {
+    id: Uuid,
+    name: String,
+}
+
+#[Object]
+impl Profile {
+    async fn id(&self) -> Uuid {
+        self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Public feeds of the user, latest first.
+    async fn feeds(
+        &self,
+        resolver: &Resolver<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, FeedItem>> {
+        let feeds = query(
+            resolver,
+            ListUserFeedsInput {
+                user_id: self.id.to_string(),
+                first: Some(page_size(first)),
+                after: after.map(evento_query::CursorType),
+                last: None,
+                before: None,
+            },
+        )
+        .await?;
+
+        Ok(connection(feeds))
+    }
+}
+
+/// Comments of the feeds resolved by a request, with one query for all of
+/// them.
+pub struct CommentsLoader {
+    query: evento::Query,
+}
+
+impl Loader<String> for CommentsLoader {
+    type Value = Vec<CommentItem>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>> {
+        let comments = self
+            .query
+            .execute(&ListFeedsCommentsInput {
+                feed_ids: keys.to_vec(),
+            })
+            .await
+            .map_err(query_error)?;
+
+        let mut feeds = HashMap::<String, Vec<CommentItem>>::new();
+        for comment in comments {
+            feeds
+                .entry(comment.feed_id.to_owned())
+                .or_default()
+                .push(CommentItem(comment));
+        }
+
+        Ok(feeds)
+    }
+}
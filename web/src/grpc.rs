@@ -0,0 +1,363 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use evento::{store::Event, Aggregate, CommandError, CommandHandler, QueryError, QueryHandler};
+use starter_feed::{
+    Comment as CommentAggregate, CreateCommentInput, CreateFeedInput, EditFeedInput,
+    Feed as FeedAggregate, GetFeedInput, ListCommentsInput, ListFeedsInput, ReactFeedInput,
+    TrashFeedsInput, UserComment, UserFeed, VISIBILITY_PUBLIC,
+};
+use std::net::SocketAddr;
+use tonic::{transport::Server, Request, Response, Status};
+use tracing::{error, info};
+use twa_jwks::JwksClient;
+use ulid::Ulid;
+use validator::Validate;
+
+use crate::context::Context;
+
+mod proto {
+    tonic::include_proto!("starter.feed.v1");
+}
+
+use proto::{
+    feed_service_server::{FeedService, FeedServiceServer},
+    Comment, CreateCommentRequest, CreateCommentResponse, CreateFeedRequest, CreateFeedResponse,
+    EditFeedRequest, Empty, Feed, GetFeedRequest, ListCommentsRequest, ListCommentsResponse,
+    ListFeedsRequest, ListFeedsResponse, ReactFeedRequest, TrashFeedRequest,
+};
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Serves `FeedService` on `addr` in the background, for the services
+/// running next to the site rather than for its visitors.
+pub fn spawn(addr: SocketAddr, ctx: Context, jwks: JwksClient) {
+    tokio::spawn(async move {
+        info!("grpc listening on {addr}");
+
+        let service = FeedServiceServer::new(FeedGrpc { ctx, jwks });
+
+        if let Err(err) = Server::builder().add_service(service).serve(addr).await {
+            error!("grpc server failed: {err}");
+        }
+    });
+}
+
+struct FeedGrpc {
+    ctx: Context,
+    jwks: JwksClient,
+}
+
+impl FeedGrpc {
+    /// `Context` of the caller, signed in with the JWT of the
+    /// `authorization` metadata as the pages are with the header.
+    async fn context<T>(&self, request: &Request<T>) -> Result<Context, Status> {
+        let mut builder = axum::http::Request::builder()
+            .extension(self.ctx.clone())
+            .extension(self.jwks.clone());
+
+        if let Some(token) = request.metadata().get(header::AUTHORIZATION.as_str()) {
+            builder = builder.header(header::AUTHORIZATION, token.as_bytes());
+        }
+
+        let (mut parts, _) = builder
+            .body(())
+            .map_err(|_| Status::unauthenticated("invalid authorization metadata"))?
+            .into_parts();
+
+        Context::from_request_parts(&mut parts, &())
+            .await
+            .map_err(|res| match res.status() {
+                StatusCode::BAD_REQUEST => Status::unauthenticated("invalid token"),
+                _ => Status::internal("Internal Server Error"),
+            })
+    }
+}
+
+fn user_id(ctx: &Context) -> Result<String, Status> {
+    ctx.user_id
+        .to_owned()
+        .ok_or_else(|| Status::unauthenticated("sign in required"))
+}
+
+async fn query<I: QueryHandler>(ctx: &Context, input: I) -> Result<I::Output, Status> {
+    ctx.query.execute(&input).await.map_err(|e| match e {
+        QueryError::Server(err) => {
+            error!("{err}");
+
+            Status::internal("Internal Server Error")
+        }
+        QueryError::NotFound(detail) => Status::not_found(detail),
+    })
+}
+
+/// Events written by the command, validation errors being flattened into
+/// the message as `field: error, error; field: error`.
+async fn execute<I: Validate + CommandHandler>(
+    ctx: &Context,
+    input: I,
+) -> Result<Vec<Event>, Status> {
+    ctx.command
+        .execute(ctx.user_language(), &input)
+        .await
+        .map_err(|e| match e {
+            CommandError::Server(err) => {
+                error!("{err}");
+
+                Status::internal("Internal Server Error")
+            }
+            CommandError::Validation(errors) => {
+                let mut errors = errors
+                    .into_iter()
+                    .map(|(field, messages)| format!("{field}: {}", messages.join(", ")))
+                    .collect::<Vec<_>>();
+                errors.sort();
+
+                Status::invalid_argument(errors.join("; "))
+            }
+            CommandError::NotFound(detail) => Status::not_found(detail),
+        })
+}
+
+async fn visible_feed(ctx: &Context, id: String) -> Result<UserFeed, Status> {
+    let feed = query(ctx, GetFeedInput { id }).await?;
+
+    if feed.hidden || !feed.is_visible_to(ctx.user_id.as_deref()) {
+        return Err(Status::not_found("feed not found"));
+    }
+
+    Ok(feed)
+}
+
+fn parse_timestamp(value: Option<String>) -> Result<Option<DateTime<Utc>>, Status> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(&value)
+                .map(|date| date.with_timezone(&Utc))
+                .map_err(|_| Status::invalid_argument(format!("{value} is not RFC 3339")))
+        })
+        .transpose()
+}
+
+fn feed(ctx: &Context, feed: UserFeed) -> Feed {
+    Feed {
+        url: ctx.create_absolute_url(feed.path()),
+        id: feed.id,
+        title: feed.title,
+        content: feed.content,
+        author: feed.author,
+        user_id: feed.user_id.to_string(),
+        tags: feed.tags,
+        total_likes: feed.total_likes,
+        visibility: feed.visibility,
+        publish_at: feed.publish_at.map(|date| date.to_rfc3339()),
+        pinned_at: feed.pinned_at.map(|date| date.to_rfc3339()),
+        created_at: feed.created_at.to_rfc3339(),
+        edited_at: feed.edited_at.map(|date| date.to_rfc3339()),
+    }
+}
+
+fn comment(comment: UserComment) -> Comment {
+    Comment {
+        content: (!comment.deleted).then_some(comment.content),
+        id: comment.id,
+        parent_id: comment.parent_id,
+        author: comment.author,
+        user_id: comment.user_id.to_string(),
+        deleted: comment.deleted,
+        created_at: comment.created_at.to_rfc3339(),
+        updated_at: comment.updated_at.map(|date| date.to_rfc3339()),
+    }
+}
+
+#[tonic::async_trait]
+impl FeedService for FeedGrpc {
+    async fn list_feeds(
+        &self,
+        request: Request<ListFeedsRequest>,
+    ) -> Result<Response<ListFeedsResponse>, Status> {
+        let ctx = self.context(&request).await?;
+        let input = request.into_inner();
+
+        let feeds = query(
+            &ctx,
+            ListFeedsInput {
+                first: Some(
+                    input
+                        .first
+                        .unwrap_or(DEFAULT_PAGE_SIZE)
+                        .clamp(1, MAX_PAGE_SIZE) as u16,
+                ),
+                after: input.after.map(evento_query::CursorType),
+                last: None,
+                before: None,
+                tag: input.tag,
+                author: None,
+                unpinned: false,
+            },
+        )
+        .await?;
+
+        Ok(Response::new(ListFeedsResponse {
+            has_next_page: feeds.page_info.has_next_page,
+            end_cursor: feeds.page_info.end_cursor.map(|cursor| cursor.0),
+            feeds: feeds
+                .edges
+                .into_iter()
+                .map(|edge| feed(&ctx, edge.node))
+                .collect(),
+        }))
+    }
+
+    async fn get_feed(&self, request: Request<GetFeedRequest>) -> Result<Response<Feed>, Status> {
+        let ctx = self.context(&request).await?;
+        let found = visible_feed(&ctx, request.into_inner().id).await?;
+
+        Ok(Response::new(feed(&ctx, found)))
+    }
+
+    async fn create_feed(
+        &self,
+        request: Request<CreateFeedRequest>,
+    ) -> Result<Response<CreateFeedResponse>, Status> {
+        let ctx = self.context(&request).await?;
+        let input = request.into_inner();
+
+        let events = execute(
+            &ctx,
+            CreateFeedInput {
+                title: input.title,
+                content: input.content.filter(|content| !content.trim().is_empty()),
+                attachments: vec![],
+                visibility: input
+                    .visibility
+                    .unwrap_or_else(|| VISIBILITY_PUBLIC.to_owned()),
+                publish_at: parse_timestamp(input.publish_at)?,
+                user_id: user_id(&ctx)?,
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok(Response::new(CreateFeedResponse {
+            id: events
+                .first()
+                .map(|event| FeedAggregate::from_aggregate_id(&event.aggregate_id))
+                .ok_or_else(|| Status::internal("Internal Server Error"))?,
+        }))
+    }
+
+    async fn edit_feed(
+        &self,
+        request: Request<EditFeedRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let ctx = self.context(&request).await?;
+        let input = request.into_inner();
+
+        execute(
+            &ctx,
+            EditFeedInput {
+                id: input.id,
+                title: input.title,
+                content: input.content,
+                user_id: user_id(&ctx)?,
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn trash_feed(
+        &self,
+        request: Request<TrashFeedRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let ctx = self.context(&request).await?;
+
+        execute(
+            &ctx,
+            TrashFeedsInput {
+                ids: vec![request.into_inner().id],
+                user_id: user_id(&ctx)?,
+                request_id: Ulid::new().to_string(),
+            },
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn react_feed(
+        &self,
+        request: Request<ReactFeedRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let ctx = self.context(&request).await?;
+        let user_id = user_id(&ctx)?;
+        let input = request.into_inner();
+
+        visible_feed(&ctx, input.feed_id.to_owned()).await?;
+
+        execute(
+            &ctx,
+            ReactFeedInput {
+                feed_id: input.feed_id,
+                reaction: input.reaction,
+                user_id,
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn list_comments(
+        &self,
+        request: Request<ListCommentsRequest>,
+    ) -> Result<Response<ListCommentsResponse>, Status> {
+        let ctx = self.context(&request).await?;
+        let feed_id = request.into_inner().feed_id;
+
+        visible_feed(&ctx, feed_id.to_owned()).await?;
+
+        let comments = query(&ctx, ListCommentsInput { feed_id }).await?;
+
+        Ok(Response::new(ListCommentsResponse {
+            comments: comments.into_iter().map(comment).collect(),
+        }))
+    }
+
+    async fn create_comment(
+        &self,
+        request: Request<CreateCommentRequest>,
+    ) -> Result<Response<CreateCommentResponse>, Status> {
+        let ctx = self.context(&request).await?;
+        let user_id = user_id(&ctx)?;
+        let input = request.into_inner();
+
+        visible_feed(&ctx, input.feed_id.to_owned()).await?;
+
+        let events = execute(
+            &ctx,
+            CreateCommentInput {
+                feed_id: input.feed_id,
+                parent_id: input.parent_id,
+                content: input.content,
+                user_id,
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok(Response::new(CreateCommentResponse {
+            id: events
+                .first()
+                .map(|event| CommentAggregate::from_aggregate_id(&event.aggregate_id))
+                .ok_or_else(|| Status::internal("Internal Server Error"))?,
+        }))
+    }
+}
@@ -1,17 +1,29 @@
+use axum::{async_trait, http::request::Parts};
+use axum_extra::extract::CookieJar;
+use evento_axum::UserLanguageSource;
+use fluent::FluentResource;
 use i18n_embed::{
     fluent::{fluent_language_loader, FluentLanguageLoader},
     LanguageLoader,
 };
 use once_cell::sync::Lazy;
 use rust_embed::RustEmbed;
+use std::sync::Arc;
 use unic_langid::LanguageIdentifier;
 
+/// Language picked by the visitor with `/_language`.
+pub(crate) const LANGUAGE_COOKIE: &str = "lang";
+
 #[derive(RustEmbed)]
 #[folder = "i18n/"]
 pub(crate) struct Localizations;
 
-pub(crate) static LANGUAGE_LOADER: Lazy<FluentLanguageLoader> = Lazy::new(|| {
-    let loader: FluentLanguageLoader = fluent_language_loader!();
+pub(crate) static LANGUAGE_LOADER: Lazy<FluentLanguageLoader> = Lazy::new(language_loader);
+
+/// Translations of every language, the ones of the modules overriding the
+/// ones of `i18n/`.
+pub(crate) fn language_loader() -> FluentLanguageLoader {
+    let mut loader: FluentLanguageLoader = fluent_language_loader!();
 
     // Load the fallback langauge by default so that users of the
     // library don't need to if they don't care about localization.
@@ -19,11 +31,72 @@ pub(crate) static LANGUAGE_LOADER: Lazy<FluentLanguageLoader> = Lazy::new(|| {
         .load_available_languages(&Localizations)
         .expect("Error while loading fallback language");
 
+    for module in crate::pages::modules() {
+        for (language, source) in module.translations() {
+            loader.with_bundles_mut(|bundle| {
+                if bundle
+                    .locales
+                    .first()
+                    .is_some_and(|locale| locale.to_string() == language)
+                {
+                    let resource = FluentResource::try_new(source.to_owned()).unwrap_or_else(
+                        |(resource, errors)| {
+                            tracing::error!("{} {language}: {errors:?}", module.name());
+
+                            resource
+                        },
+                    );
+
+                    bundle.add_resource_overriding(Arc::new(resource));
+                }
+            });
+        }
+    }
+
     loader
-});
+}
 
 pub(crate) static LANGUAGES: Lazy<Vec<LanguageIdentifier>> = Lazy::new(|| {
     LANGUAGE_LOADER
         .available_languages(&Localizations)
         .expect("Error while loading fallback language")
 });
+
+/// Loader of the `preferred` languages along with the first of them that is
+/// available, the fallback language when none is.
+pub(crate) fn select_languages<S: AsRef<str>>(preferred: &[S]) -> (FluentLanguageLoader, String) {
+    let langs = preferred
+        .iter()
+        .map(|lang| lang.as_ref().parse().unwrap_or_default())
+        .collect::<Vec<LanguageIdentifier>>();
+
+    let fl_loader = LANGUAGE_LOADER.select_languages(&langs);
+
+    let user_language = fl_loader
+        .current_languages()
+        .iter()
+        .find_map(|language| {
+            if LANGUAGES.contains(language) {
+                Some(language.to_string())
+            } else {
+                None
+            }
+        })
+        .unwrap_or(fl_loader.fallback_language().to_string());
+
+    (fl_loader, user_language)
+}
+
+/// Language of `LANGUAGE_COOKIE`, taking over the others so that the one
+/// picked sticks across the pages and the live updates.
+pub(crate) struct CookieSource;
+
+#[async_trait]
+impl UserLanguageSource for CookieSource {
+    async fn languages_from_parts(&self, parts: &mut Parts) -> Vec<String> {
+        CookieJar::from_headers(&parts.headers)
+            .get(LANGUAGE_COOKIE)
+            .map(|cookie| vec![cookie.value().to_owned()])
+            .unwrap_or_default()
+    }
+}
@@ -1,11 +1,57 @@
+mod analytics;
+mod api;
 mod assets;
+mod auth;
+pub mod bench;
+mod billing;
+mod bot;
+mod bridge;
+mod cache;
+mod chaos;
+mod components;
 mod config;
 mod context;
+mod csrf;
+pub mod doctor;
+mod drain;
+pub mod events;
+mod extract;
+mod feature;
+mod flash;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+mod geoip;
+mod graphql;
+mod grpc;
 mod i18n;
+mod live;
+pub mod localized;
+mod mailer;
+mod maintenance;
+mod meta;
+mod minify;
+mod module;
 mod pages;
+mod push;
+mod rate_limit;
+mod reload;
+pub mod routes;
+mod scheduler;
+mod search;
+pub mod secrets;
+pub mod sse;
+mod storage;
+mod stream;
+mod telemetry;
+mod tenant;
+#[cfg(feature = "test-api")]
+mod test_api;
+mod theme;
+pub mod users;
+mod wizard;
 
 use anyhow::Result;
-use axum::{routing::get, Extension, Router};
+use axum::{middleware, routing::get, Extension, Router};
 use config::Config;
 use context::Context;
 use evento::PgConsumer;
@@ -13,74 +59,494 @@ use evento_axum::{AcceptLanguageSource, QuerySource, UserLanguage};
 #[cfg(debug_assertions)]
 use pikav_client::timada::SimpleEvent;
 use sqlx::PgPool;
-use tracing::info;
+use std::{future::IntoFuture, net::SocketAddr};
+use tracing::{info, warn};
 use twa_jwks::JwksClient;
 
 use crate::assets::static_handler;
 
-pub async fn serve() -> Result<()> {
+pub use billing::Subscribed;
+pub use bot::IsBot;
+pub use config::{check_config, ConfigCheck, ConfigReport};
+pub use feature::{Feature, FeatureFlag};
+pub use flash::{Flash, FlashLevel};
+pub use live::{FakePikav, LivePublisher};
+pub use module::MigrationStatus;
+pub use reload::{on_log_filter, Reloader};
+pub use telemetry::otlp_layer;
+pub use wizard::{Wizard, WizardAction, WizardForm};
+
+/// Every route of `serve`, with `Config::base_url` applied.
+pub fn routes() -> Result<Vec<routes::RouteInfo>> {
     let config = Config::new()?;
+    let routes = module::create_router(&pages::modules()).routes().to_vec();
+
+    Ok(match config.base_url.as_ref() {
+        Some(base_url) => routes
+            .into_iter()
+            .map(|route| routes::RouteInfo {
+                path: routes::nested_path(base_url, &route.path),
+                ..route
+            })
+            .collect(),
+        _ => routes,
+    })
+}
+
+/// Runs the pending migrations of every module, in the schema of every
+/// tenant, as `serve` and `work` do when they start.
+pub async fn migrate() -> Result<()> {
+    for config in Config::load().await?.tenants() {
+        let db = connect(&config).await?;
+
+        module::migrate(&db, &pages::modules()).await?;
+    }
+
+    Ok(())
+}
+
+/// Migrations of every module, with whether they were applied, in the
+/// schema of the first tenant.
+pub async fn migrations() -> Result<Vec<MigrationStatus>> {
+    let Some(config) = Config::load().await?.tenants().into_iter().next() else {
+        anyhow::bail!("tenancy.tenants is empty");
+    };
+    let db = connect(&config).await?;
+
+    module::migrations(&db, &pages::modules()).await
+}
 
+/// Pretty-printed OpenAPI document of the json routes of `serve`, their
+/// paths being relative to the public url with `Config::base_url` applied.
+pub fn openapi() -> Result<String> {
+    let config = Config::new()?;
+
+    Ok(api::openapi(config.create_absolute_url("")).to_pretty_json()?)
+}
+
+/// Router of `serve` with the consumers, the job workers and the cron tasks
+/// running, `overrides` taking precedence over the config as with
+/// `Config::with_overrides`. Secrets are taken as they are. Requests are
+/// sent to it by the end-to-end tests of `starter-test`, without listening.
+/// Live updates are kept by the `FakePikav` returned along, unless
+/// `pikav.url` is set to a reachable pikav, and the config of the first
+/// tenant is reloaded by the `Reloader` returned along.
+pub async fn test_router(
+    overrides: &[(&str, &str)],
+) -> Result<(Router, Option<FakePikav>, Reloader)> {
+    let config = Config::with_overrides(overrides)?;
     let jwks = JwksClient::build(config.jwks_url.to_owned()).await?;
-    let db = PgPool::connect(&config.dsn).await?;
-    let pikva_client = pikav_client::Client::new(pikav_client::ClientOptions {
+    let tenants = start_tenants(config, true).await?;
+    let pikav = tenants.first().live.fake().cloned();
+    let reloader = tenants.first().reloader.clone();
+
+    Ok((router(tenants, jwks), pikav, reloader))
+}
+
+/// Services shared by `serve`, `work` and `bench`.
+struct App {
+    config: Config,
+    command: evento::Command,
+    query: evento::Query,
+    cache: cache::FragmentCache,
+    images: cache::FragmentCache<Vec<u8>>,
+    live: live::Live,
+    mailer: mailer::Mailer,
+    storage: std::sync::Arc<dyn storage::Storage>,
+    jobs: starter_feed::Jobs,
+    features: feature::Features,
+    pusher: push::Pusher,
+    stripe: billing::Stripe,
+    search: std::sync::Arc<dyn search::SearchBackend>,
+    analytics: analytics::Analytics,
+    reloader: reload::Reloader,
+    drain: drain::Drain,
+    geoip: geoip::GeoIp,
+    limiter: rate_limit::RateLimiter,
+}
+
+/// Client of pikav, `None` when `PikavConfig::url` is empty or it can't be
+/// reached so that live updates fall back to `/sse`.
+async fn connect_pikav(config: &Config) -> Option<pikav_client::Client> {
+    if config.pikav.url.is_empty() {
+        info!("pikav disabled, serving live updates in process");
+
+        return None;
+    }
+
+    if let Err(err) = config::check_pikav(&config.pikav.url).await {
+        warn!("pikav unreachable, serving live updates in process: {err}");
+
+        return None;
+    }
+
+    match pikav_client::Client::new(pikav_client::ClientOptions {
         url: config.pikav.url.to_owned(),
         namespace: config.pikav.namespace.to_owned(),
-    })?;
+    }) {
+        Ok(client) => Some(client),
+        Err(err) => {
+            warn!("pikav client failed, serving live updates in process: {err}");
+
+            None
+        }
+    }
+}
+
+/// Pool of `Config::dsn`, the schema of the tenant of the config being
+/// created first.
+async fn connect(config: &Config) -> Result<PgPool> {
+    let db = PgPool::connect(&config.dsn).await?;
+
+    if let Some(schema) = config.schema() {
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {schema}"))
+            .execute(&db)
+            .await?;
+    }
+
+    Ok(db)
+}
+
+/// Starts the services of every tenant, each in its own schema, sharing
+/// `drain`, or the ones of `config` while tenancy is off.
+async fn start_tenants(config: Config, consumers: bool) -> Result<tenant::Tenants> {
+    let drain = drain::Drain::new(&config.drain);
+    let configs = config.tenants();
+
+    if configs.is_empty() {
+        anyhow::bail!("tenancy.tenants is empty");
+    }
+
+    let mut contexts = vec![];
+
+    for config in configs {
+        contexts.push(
+            start(config, consumers, drain.clone())
+                .await?
+                .into_context(),
+        );
+    }
+
+    Ok(tenant::Tenants::new(contexts))
+}
+
+/// Connects to the services of `config`, running the consumers of the rules,
+/// the scheduler, the job workers and the cron tasks unless `consumers` is
+/// unset.
+async fn start(config: Config, consumers: bool, drain: drain::Drain) -> Result<App> {
+    let db = connect(&config).await?;
+    let live = live::Live::new(connect_pikav(&config).await, config.chaos.clone());
+
+    let modules = pages::modules();
+    module::migrate(&db, &modules).await?;
+
+    let redis = match config.redis_url.as_deref() {
+        Some(url) => Some(redis::aio::ConnectionManager::new(redis::Client::open(url)?).await?),
+        _ => None,
+    };
+    let cache = cache::FragmentCache::new(config.namespaced("fragment"), redis.clone());
+    let images = cache::FragmentCache::new(config.namespaced("image"), redis.clone());
+    let limiter = rate_limit::RateLimiter::new(config.namespaced("rate"), redis);
+    let mailer = mailer::Mailer::new(&config.mail)?;
+    let storage = storage::open(&config)?;
+    let jobs = starter_feed::Jobs::new(db.clone());
+    let features = feature::Features::new(db.clone(), &config);
+    let pusher = push::Pusher::new(&config.push, db.clone())?;
+    let stripe = billing::Stripe::new(&config.billing)?;
 
-    sqlx::migrate!("../migrations")
-        .set_locking(false)
-        .run(&db)
-        .await?;
+    let query = evento::Query::new().data(db.clone()).data(config.clone());
+    let search = search::open(&config, query.clone())?;
+    let analytics = analytics::Analytics::new(db.clone());
+    let reloader = reload::Reloader::new(&config, features.clone());
+    let bridge = bridge::Bridge::open(&config.bridge).await?;
+    let geoip = geoip::GeoIp::open(&config)?;
+
+    let gate = starter_feed::ConsumerGate::default();
 
     let producer = PgConsumer::new(&db)
         .name(&config.region)
-        .data(pikva_client.clone())
+        .data(gate.clone())
+        .data(cache.clone())
+        .data(live.clone())
+        .data(mailer.clone())
+        .data(pusher.clone())
+        .data(storage.clone())
+        .data(search.clone())
+        .data(bridge.clone())
         .data(config.clone())
-        .rules(starter_feed::rules())
-        .rules(pages::rules())
-        .start(config.evento_delay.unwrap_or(30))
-        .await?;
+        .data(query.clone());
+
+    let producer = if consumers {
+        let producer = modules
+            .iter()
+            .fold(producer, |producer, module| producer.rules(module.rules()));
+
+        if config.chaos.is_enabled() {
+            warn!("chaos enabled, injecting faults");
+
+            producer.rules(vec![chaos::rule()])
+        } else {
+            producer
+        }
+    } else {
+        producer
+    }
+    .start(config.evento_delay.unwrap_or(30))
+    .await?;
 
     let command = evento::Command::new(&producer);
-    let query = evento::Query::new().data(db.clone());
 
-    let router = pages::create_router();
+    if consumers {
+        if let Err(err) = search.setup().await {
+            warn!("search index setup failed: {err}");
+        }
+
+        scheduler::spawn(command.clone(), query.clone());
+        bridge.spawn_ingest(command.clone());
+
+        let workers = starter_feed::Worker::new(jobs.clone())
+            .concurrency(config.job_workers)
+            .handler(
+                starter_feed::JOB_UNFURL,
+                starter_feed::UnfurlJob { db: db.clone() },
+            )
+            .handler(
+                pages::JOB_EXPORT,
+                pages::ExportJob {
+                    config: config.clone(),
+                    query: query.clone(),
+                    storage: storage.clone(),
+                    mailer: mailer.clone(),
+                    pusher: pusher.clone(),
+                    live: live.clone(),
+                },
+            )
+            .spawn();
+
+        drain.add_workers(workers);
+        drain.add_consumer(gate);
+
+        modules
+            .iter()
+            .try_fold(
+                starter_feed::Cron::new(db.clone())
+                    .data(db.clone())
+                    .data(cache.clone())
+                    .data(live.clone())
+                    .data(mailer.clone())
+                    .data(pusher.clone())
+                    .data(storage.clone())
+                    .data(search.clone())
+                    .data(bridge.clone())
+                    .data(config.clone())
+                    .data(query.clone())
+                    .data(command.clone())
+                    .data(jobs.clone())
+                    .data(analytics.clone()),
+                |cron, module| cron.jobs(module.cron_jobs(&config)),
+            )?
+            .spawn();
+    }
+
+    Ok(App {
+        config,
+        command,
+        query,
+        cache,
+        images,
+        live,
+        mailer,
+        storage,
+        jobs,
+        features,
+        pusher,
+        stripe,
+        search,
+        analytics,
+        reloader,
+        drain,
+        geoip,
+        limiter,
+    })
+}
+
+/// Runs the consumers, the scheduler and the job workers without listening
+/// for requests, so that they scale apart from the web servers, until told
+/// to terminate and the running jobs and events are done.
+pub async fn work() -> Result<()> {
+    let config = Config::load().await?;
+    let tenants = start_tenants(config, true).await?;
+
+    for ctx in tenants.all() {
+        ctx.reloader.spawn();
+    }
+
+    let drain = tenants.first().drain.clone();
+    drain.spawn_signals();
+
+    info!("worker started");
+
+    drain.requested().await;
+    drain.finish().await;
+    telemetry::shutdown();
+
+    Ok(())
+}
+
+impl App {
+    /// `Context` the extractors start from, before reading the request.
+    fn into_context(self) -> Context {
+        let App {
+            config,
+            command,
+            query,
+            cache,
+            images,
+            live,
+            mailer,
+            storage,
+            jobs,
+            features,
+            pusher,
+            stripe,
+            search,
+            analytics,
+            reloader,
+            drain,
+            geoip,
+            limiter,
+        } = self;
 
-    let app = match config.base_url.as_ref() {
+        Context {
+            command,
+            query,
+            config,
+            user_language: None,
+            fl_loader: None,
+            user_id: None,
+            role: None,
+            permissions: vec![],
+            hx: Default::default(),
+            timezone: chrono_tz::Tz::UTC,
+            bot: false,
+            streaming: false,
+            print: false,
+            flashes: vec![],
+            theme: Default::default(),
+            cache,
+            images,
+            live,
+            mailer,
+            storage,
+            jobs,
+            features,
+            pusher,
+            stripe,
+            search,
+            analytics,
+            reloader,
+            drain,
+            geoip,
+            limiter,
+            country: None,
+            request_id: None,
+            csrf_token: None,
+        }
+    }
+}
+
+/// Every page along with the static files and the layers they rely on, the
+/// requests being handled with the `Context` of their tenant.
+fn router(tenants: tenant::Tenants, jwks: JwksClient) -> Router {
+    let ctx = tenants.first().clone();
+    let router = module::create_router(&pages::modules())
+        .into_router()
+        .route_layer(middleware::from_fn(telemetry::record));
+
+    #[cfg(debug_assertions)]
+    let router = router.merge(api::swagger_ui(ctx.config.base_url.as_deref()));
+
+    match ctx.config.base_url.as_ref() {
         Some(base_url) => Router::new().nest(base_url, router),
         _ => router,
     }
     .fallback(get(static_handler))
+    .layer(middleware::from_fn(flash::clear_flash))
+    .layer(middleware::from_fn(minify::minify_html))
+    .layer(middleware::from_fn(maintenance::maintenance))
+    .layer(middleware::from_fn(chaos::chaos))
+    .layer(middleware::from_fn(csrf::protect))
+    .layer(middleware::from_fn(rate_limit::limit))
+    .layer(middleware::from_fn(tenant::scope))
+    .layer(middleware::from_fn(auth::session))
     .layer(Extension(
         UserLanguage::config()
+            .add_source(i18n::CookieSource)
             .add_source(QuerySource::new("lang"))
             .add_source(AcceptLanguageSource)
+            .add_source(geoip::GeoIpSource(ctx.geoip.clone()))
             .build(),
     ))
     .layer(Extension(jwks))
-    .layer(Extension(Context {
-        command,
-        query,
-        config: config.clone(),
-        user_language: None,
-        fl_loader: None,
-        user_id: None,
-    }));
+    .layer(Extension(tenants))
+    .layer(Extension(ctx))
+    .layer(middleware::from_fn(telemetry::trace))
+}
 
-    #[cfg(debug_assertions)]
-    pikva_client.publish(vec![SimpleEvent {
-        user_id: "*".into(),
-        topic: "sys".into(),
-        event: "hot-reload".into(),
-        data: "App was updated".into(),
-    }]);
+/// Listens for requests until drained, on `/__drain` or `SIGTERM`, or runs as `work` does when
+/// `Config::worker` is set.
+pub async fn serve() -> Result<()> {
+    let config = Config::load().await?;
+
+    if config.worker {
+        return work().await;
+    }
+
+    let jwks = JwksClient::build(config.jwks_url.to_owned()).await?;
+    let tenants = start_tenants(config, true).await?;
+
+    for ctx in tenants.all() {
+        ctx.reloader.spawn();
+
+        #[cfg(debug_assertions)]
+        ctx.live.publish(vec![SimpleEvent {
+            user_id: "*".into(),
+            topic: "sys".into(),
+            event: "hot-reload".into(),
+            data: "App was updated".into(),
+        }]);
+    }
 
-    info!("app listening on http://{}", &config.addr);
+    let ctx = tenants.first().clone();
+    ctx.drain.spawn_signals();
 
-    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    if let Some(addr) = ctx.config.grpc_addr.as_deref() {
+        if ctx.config.tenancy.is_enabled() {
+            warn!("grpc_addr ignored, the gRPC service is not served to several tenants");
+        } else {
+            grpc::spawn(addr.parse()?, ctx.clone(), jwks.clone());
+        }
+    }
+
+    info!("app listening on http://{}", &ctx.config.addr);
+
+    let listener = tokio::net::TcpListener::bind(ctx.config.addr.to_owned()).await?;
+    let drain = ctx.drain.clone();
+    let app = router(tenants, jwks);
+
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(drain.clone().stopped());
+
+    tokio::select! {
+        result = server.into_future() => result?,
+        _ = drain.clone().expired() => warn!("requests still in flight after the drain timeout"),
+    }
 
-    axum::serve(listener, app.into_make_service()).await?;
+    drain.finish().await;
+    telemetry::shutdown();
 
     Ok(())
 }
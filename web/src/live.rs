@@ -0,0 +1,315 @@
+use askama_axum::{IntoResponse, Response};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use futures_util::stream;
+use pikav_client::timada::SimpleEvent;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+use crate::{
+    chaos,
+    config::ChaosConfig,
+    context::Context,
+    extract::Path,
+    sse::{sse_response, SseEvent},
+};
+
+/// Events kept for the slowest `/ws` subscriber before it misses some.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Topics a `/ws` connection can subscribe to at once.
+const MAX_SUBSCRIPTIONS: usize = 32;
+
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Events kept by `FakePikav`, the oldest being dropped first.
+const FAKE_CAPACITY: usize = 1024;
+
+/// Sends the live updates to the pages of every replica, `pikav_client::Client`
+/// through pikav.
+pub trait LivePublisher: Send + Sync {
+    fn publish(&self, events: Vec<SimpleEvent>);
+}
+
+impl LivePublisher for pikav_client::Client {
+    fn publish(&self, events: Vec<SimpleEvent>) {
+        pikav_client::Client::publish(self, events);
+    }
+}
+
+/// Publisher of `Live` when no pikav can be reached, keeping the last events
+/// published so that tests can assert on them and they show in the debug
+/// logs of offline development.
+#[derive(Clone, Default)]
+pub struct FakePikav {
+    events: Arc<Mutex<VecDeque<SimpleEvent>>>,
+}
+
+impl FakePikav {
+    /// Events published so far, oldest first.
+    pub fn events(&self) -> Vec<SimpleEvent> {
+        self.events
+            .lock()
+            .expect("pikav lock poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Last event `event` published on `topic`.
+    pub fn find(&self, topic: &str, event: &str) -> Option<SimpleEvent> {
+        self.events
+            .lock()
+            .expect("pikav lock poisoned")
+            .iter()
+            .rev()
+            .find(|published| published.topic == topic && published.event == event)
+            .cloned()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().expect("pikav lock poisoned").clear();
+    }
+}
+
+impl LivePublisher for FakePikav {
+    fn publish(&self, events: Vec<SimpleEvent>) {
+        let mut published = self.events.lock().expect("pikav lock poisoned");
+
+        for event in events {
+            debug!("{} {} for {}", event.topic, event.event, event.user_id);
+
+            if published.len() == FAKE_CAPACITY {
+                published.pop_front();
+            }
+
+            published.push_back(event);
+        }
+    }
+}
+
+/// Publishes the live updates to pikav and to the `/ws` connections of this
+/// process, the latter only reaching the ones connected to the replica that
+/// published. Without pikav the pages get them from `/sse` instead, which
+/// only suits single node deployments, and they are kept by `FakePikav`.
+#[derive(Clone)]
+pub struct Live {
+    publisher: Arc<dyn LivePublisher>,
+    fake: Option<FakePikav>,
+    local: broadcast::Sender<Arc<SimpleEvent>>,
+    chaos: ChaosConfig,
+}
+
+impl Live {
+    /// `chaos` dropping some of the events published, see
+    /// `ChaosConfig::drop_publish_rate`.
+    pub fn new(pikav: Option<pikav_client::Client>, chaos: ChaosConfig) -> Self {
+        match pikav {
+            Some(pikav) => Self::with_publisher(Arc::new(pikav), None, chaos),
+            None => {
+                let fake = FakePikav::default();
+
+                Self::with_publisher(Arc::new(fake.clone()), Some(fake), chaos)
+            }
+        }
+    }
+
+    fn with_publisher(
+        publisher: Arc<dyn LivePublisher>,
+        fake: Option<FakePikav>,
+        chaos: ChaosConfig,
+    ) -> Self {
+        let (local, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self {
+            publisher,
+            fake,
+            local,
+            chaos,
+        }
+    }
+
+    /// Whether the pages connect to pikav rather than to `/sse`.
+    pub fn is_pikav(&self) -> bool {
+        self.fake.is_none()
+    }
+
+    /// Events kept in place of pikav, when it can't be reached.
+    pub fn fake(&self) -> Option<&FakePikav> {
+        self.fake.as_ref()
+    }
+
+    /// Publishes `events` to their `user_id`, `*` being everyone.
+    pub fn publish(&self, mut events: Vec<SimpleEvent>) {
+        events.retain(|event| {
+            let dropped = chaos::drop_publish(&self.chaos);
+
+            if dropped {
+                warn!("chaos: dropped {} {}", event.topic, event.event);
+            }
+
+            !dropped
+        });
+
+        if events.is_empty() {
+            return;
+        }
+
+        if self.local.receiver_count() > 0 {
+            for event in &events {
+                let _ = self.local.send(Arc::new(event.clone()));
+            }
+        }
+
+        self.publisher.publish(events);
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<SimpleEvent>> {
+        self.local.subscribe()
+    }
+}
+
+fn is_for(event: &SimpleEvent, user_id: Option<&str>) -> bool {
+    event.user_id == "*" || Some(event.user_id.as_str()) == user_id
+}
+
+/// Live updates of `topic` for the pages when pikav is unreachable, at the
+/// url of `Context::create_sse_url` then. Missed events are not replayed.
+pub async fn sse(ctx: Context, Path((topic,)): Path<(String,)>) -> Response {
+    let events = stream::unfold(
+        (ctx.live.subscribe(), ctx.user_id, topic),
+        |(mut receiver, user_id, topic)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.topic == topic && is_for(&event, user_id.as_deref()) => {
+                        let event = SseEvent::new(&event.event, &event.data);
+
+                        return Some((event, (receiver, user_id, topic)));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("sse subscriber missed {missed} events");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    sse_response(events)
+}
+
+/// Sent by the client as json text messages.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum ClientMessage {
+    Subscribe { topic: String },
+    Unsubscribe { topic: String },
+}
+
+/// Sent to the client as json text messages, `lagged` telling it missed
+/// `missed` events for being too slow and should reload what it shows.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum ServerMessage<'a> {
+    Event {
+        topic: &'a str,
+        event: &'a str,
+        data: &'a str,
+    },
+    Lagged {
+        missed: u64,
+    },
+    Error {
+        message: &'a str,
+    },
+}
+
+impl ServerMessage<'_> {
+    fn into_message(self) -> Option<Message> {
+        serde_json::to_string(&self).ok().map(Message::Text)
+    }
+}
+
+/// Live updates over a WebSocket for the clients that can't reach pikav, the
+/// signed in user being the one of the upgrade request. Topics are the ones
+/// of pikav, followed with `{"type":"subscribe","topic":"comments"}`.
+pub async fn ws(ctx: Context, upgrade: WebSocketUpgrade) -> Response {
+    let receiver = ctx.live.subscribe();
+
+    upgrade
+        .on_upgrade(move |socket| run(socket, ctx.user_id, receiver))
+        .into_response()
+}
+
+async fn run(
+    mut socket: WebSocket,
+    user_id: Option<String>,
+    mut receiver: broadcast::Receiver<Arc<SimpleEvent>>,
+) {
+    let mut topics = HashSet::<String>::new();
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+
+    loop {
+        let message = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => subscription(&mut topics, &text),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => None,
+            },
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if !is_for(&event, user_id.as_deref()) || !topics.contains(&event.topic) {
+                        continue;
+                    }
+
+                    ServerMessage::Event {
+                        topic: &event.topic,
+                        event: &event.event,
+                        data: &event.data,
+                    }
+                    .into_message()
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("ws subscriber missed {missed} events");
+
+                    ServerMessage::Lagged { missed }.into_message()
+                }
+                Err(RecvError::Closed) => return,
+            },
+            _ = ping.tick() => Some(Message::Ping(vec![])),
+        };
+
+        // Sending waits for the client, the events piling up in the channel
+        // meanwhile until it lags.
+        if let Some(message) = message {
+            if socket.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Applies a message of the client, answering an error when it is not one
+/// of `ClientMessage`.
+fn subscription(topics: &mut HashSet<String>, text: &str) -> Option<Message> {
+    let error = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(ClientMessage::Subscribe { topic }) if topics.len() < MAX_SUBSCRIPTIONS => {
+            topics.insert(topic);
+            return None;
+        }
+        Ok(ClientMessage::Subscribe { .. }) => "too many subscriptions",
+        Ok(ClientMessage::Unsubscribe { topic }) => {
+            topics.remove(&topic);
+            return None;
+        }
+        Err(_) => "invalid message",
+    };
+
+    ServerMessage::Error { message: error }.into_message()
+}
@@ -0,0 +1,115 @@
+use chrono::NaiveDate;
+use serde::{de::Error, Deserialize, Deserializer};
+use std::future::Future;
+
+tokio::task_local! {
+    static USER_LANGUAGE: String;
+}
+
+/// Runs `f` with `lang` available to the `deserialize_with` helpers of this
+/// module, used by `extract::Form` and `extract::Query`.
+pub async fn scope<F: Future>(lang: String, f: F) -> F::Output {
+    USER_LANGUAGE.scope(lang, f).await
+}
+
+fn user_language() -> String {
+    USER_LANGUAGE
+        .try_with(|lang| lang.to_owned())
+        .unwrap_or_else(|_| "en".to_owned())
+}
+
+fn separators(lang: &str) -> (char, char) {
+    match lang {
+        "fr" => ('.', ','),
+        _ => (',', '.'),
+    }
+}
+
+fn date_format(lang: &str) -> &'static str {
+    match lang {
+        "fr" => "%d/%m/%Y",
+        _ => "%m/%d/%Y",
+    }
+}
+
+/// Parses `1 234,56` or `1.234,56` (fr) and `1,234.56` or `1 234.56` (en).
+/// Group separators are only accepted between the thousands before the
+/// decimal mark, anything else being rejected.
+pub fn parse_decimal(lang: &str, value: &str) -> Option<f64> {
+    let (group, decimal) = separators(lang);
+    let value = value.trim();
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => ("-", value),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (integer, fraction) = match value.split_once(decimal) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (value, None),
+    };
+
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let groups = integer
+        .split(|c: char| c == group || c.is_whitespace())
+        .collect::<Vec<_>>();
+    let grouped = groups.len() == 1
+        || groups.iter().enumerate().all(|(index, part)| match index {
+            0 => (1..=3).contains(&part.len()),
+            _ => part.len() == 3,
+        });
+
+    if !grouped
+        || !groups.iter().all(|part| is_digits(part))
+        || (integer.is_empty() && fraction.is_none())
+        || fraction.is_some_and(|fraction| fraction.is_empty() || !is_digits(fraction))
+    {
+        return None;
+    }
+
+    format!("{sign}0{}.{}", groups.concat(), fraction.unwrap_or("0"))
+        .parse()
+        .ok()
+}
+
+/// Parses the localized date format, falling back to `%Y-%m-%d` sent by
+/// `<input type="date">`.
+pub fn parse_date(lang: &str, value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+
+    NaiveDate::parse_from_str(value, date_format(lang))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+pub fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    parse_decimal(&user_language(), &value)
+        .ok_or_else(|| D::Error::custom(format!("invalid decimal {value}")))
+}
+
+pub fn option_decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if !value.trim().is_empty() => parse_decimal(&user_language(), &value)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid decimal {value}"))),
+        _ => Ok(None),
+    }
+}
+
+pub fn date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    parse_date(&user_language(), &value)
+        .ok_or_else(|| D::Error::custom(format!("invalid date {value}")))
+}
+
+pub fn option_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if !value.trim().is_empty() => parse_date(&user_language(), &value)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid date {value}"))),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::{bail, Result};
+use askama::Template;
+use chrono::{DateTime, Utc};
+use i18n_embed::fluent::FluentLanguageLoader;
+use lettre::{
+    message::{Mailbox, MultiPart},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tracing::error;
+use unic_langid::LanguageIdentifier;
+
+use crate::{
+    config::{Config, MailConfig},
+    i18n::LANGUAGE_LOADER,
+};
+
+/// Emails kept by the mailbox transport, the oldest being dropped first.
+const MAILBOX_SIZE: usize = 100;
+
+/// What email templates render with, in the language of their recipient.
+pub struct EmailContext {
+    config: Config,
+    fl_loader: FluentLanguageLoader,
+    pub lang: String,
+}
+
+impl EmailContext {
+    pub fn new(config: &Config, lang: &str) -> Self {
+        let langs = lang
+            .parse::<LanguageIdentifier>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        Self {
+            config: config.clone(),
+            fl_loader: LANGUAGE_LOADER.select_languages(&langs),
+            lang: lang.to_owned(),
+        }
+    }
+
+    pub fn t(&self, id: &str) -> String {
+        self.fl_loader.get(id)
+    }
+
+    /// Links of emails are absolute, they are opened outside of the site.
+    pub fn create_absolute_url(&self, uri: impl Into<String>) -> String {
+        self.config.create_absolute_url(uri)
+    }
+}
+
+/// Email with its html and plain text alternatives.
+#[derive(Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+impl Email {
+    pub fn render(
+        to: impl Into<String>,
+        subject: impl Into<String>,
+        html: &impl Template,
+        text: &impl Template,
+    ) -> Result<Self> {
+        Ok(Self {
+            to: to.into(),
+            subject: subject.into(),
+            html: html.render()?,
+            text: text.render()?,
+        })
+    }
+}
+
+/// Email kept by the mailbox transport.
+#[derive(Clone)]
+pub struct SentEmail {
+    pub email: Email,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+enum Transport {
+    Mailbox(Arc<RwLock<VecDeque<SentEmail>>>),
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    Api {
+        client: reqwest::Client,
+        url: String,
+        key: Option<String>,
+    },
+}
+
+/// Sends emails through the transport of `MailConfig`, shared by the
+/// consumer rules and the `/admin/mailbox` page.
+#[derive(Clone)]
+pub struct Mailer {
+    from: Mailbox,
+    transport: Transport,
+}
+
+impl Mailer {
+    pub fn new(config: &MailConfig) -> Result<Self> {
+        let transport = match (config.transport.as_str(), &config.smtp_url, &config.api_url) {
+            ("mailbox", ..) => Transport::Mailbox(Default::default()),
+            ("smtp", Some(url), _) => {
+                Transport::Smtp(AsyncSmtpTransport::<Tokio1Executor>::from_url(url)?.build())
+            }
+            ("api", _, Some(url)) => Transport::Api {
+                client: reqwest::Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .build()?,
+                url: url.to_owned(),
+                key: config.api_key.to_owned(),
+            },
+            (transport, ..) => {
+                bail!("mail transport {transport} is not configured, see `config check`")
+            }
+        };
+
+        Ok(Self {
+            from: config.from.parse()?,
+            transport,
+        })
+    }
+
+    /// Sends `email` in the background, failures being logged as the rules
+    /// sending them are not retried for an email.
+    pub fn send(&self, email: Email) {
+        let mailer = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(err) = mailer.deliver(&email).await {
+                error!("failed to email {}: {err}", email.to);
+            }
+        });
+    }
+
+    async fn deliver(&self, email: &Email) -> Result<()> {
+        match &self.transport {
+            Transport::Mailbox(mailbox) => {
+                let Ok(mut mailbox) = mailbox.write() else {
+                    bail!("mailbox poisoned");
+                };
+
+                if mailbox.len() == MAILBOX_SIZE {
+                    mailbox.pop_back();
+                }
+
+                mailbox.push_front(SentEmail {
+                    email: email.clone(),
+                    sent_at: Utc::now(),
+                });
+            }
+            Transport::Smtp(transport) => {
+                let message = Message::builder()
+                    .from(self.from.clone())
+                    .to(email.to.parse()?)
+                    .subject(&email.subject)
+                    .multipart(MultiPart::alternative_plain_html(
+                        email.text.to_owned(),
+                        email.html.to_owned(),
+                    ))?;
+
+                transport.send(message).await?;
+            }
+            Transport::Api { client, url, key } => {
+                let body = serde_json::json!({
+                    "from": self.from.to_string(),
+                    "to": email.to,
+                    "subject": email.subject,
+                    "html": email.html,
+                    "text": email.text,
+                });
+
+                let request = client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(serde_json::to_vec(&body)?);
+
+                let request = match key {
+                    Some(key) => request.bearer_auth(key),
+                    None => request,
+                };
+
+                request.send().await?.error_for_status()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emails kept by the mailbox transport, the latest first, none with the
+    /// other transports.
+    pub fn mailbox(&self) -> Vec<SentEmail> {
+        match &self.transport {
+            Transport::Mailbox(mailbox) => mailbox
+                .read()
+                .map(|mailbox| mailbox.iter().cloned().collect())
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+}
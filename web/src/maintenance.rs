@@ -0,0 +1,225 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    Extension, Json,
+};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    time::{Duration, Instant},
+};
+use tracing::{error, warn};
+
+use crate::{config::check_pikav, context::Context, feature::FeatureFlag};
+
+/// Seconds the clients are told to wait before trying again.
+const RETRY_AFTER: &str = "120";
+
+/// Time given to each dependency of `/readyz` to answer.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Puts the app in maintenance, turned on from `Config::features` with
+/// `maintenance = true` or by the admins from `/admin/status`.
+pub struct Maintenance;
+
+impl FeatureFlag for Maintenance {
+    const NAME: &'static str = "maintenance";
+}
+
+/// Path of `path` below `Config::base_url`.
+pub fn app_path<'a>(ctx: &Context, path: &'a str) -> &'a str {
+    ctx.config
+        .base_url
+        .as_deref()
+        .and_then(|base_url| path.strip_prefix(base_url))
+        .unwrap_or(path)
+}
+
+/// Whether `path` is one of the routes of the load balancers and the
+/// deploys, which must answer whatever state the app is in.
+pub fn is_probe(path: &str) -> bool {
+    matches!(path, "/healthz" | "/readyz" | "/metrics" | "/__drain")
+}
+
+/// Responds with the maintenance page to anyone but admins while
+/// `Maintenance` is on, `is_probe` routes, the static files and `/auth`
+/// being served as usual so that admins can still sign in.
+pub async fn maintenance(Extension(ctx): Extension<Context>, req: Request, next: Next) -> Response {
+    let path = app_path(&ctx, req.uri().path());
+
+    if is_probe(path) || path.starts_with("/static/") || path.starts_with("/auth/") {
+        return next.run(req).await;
+    }
+
+    match ctx.features.is_enabled(Maintenance::NAME, None).await {
+        Ok(true) => {}
+        Ok(false) => return next.run(req).await,
+        Err(err) => {
+            error!("{err}");
+
+            return next.run(req).await;
+        }
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let ctx = match Context::from_request_parts(&mut parts, &()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return response,
+    };
+
+    if ctx.is_admin() {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    (
+        [(header::RETRY_AFTER, RETRY_AFTER)],
+        ctx.error_response(StatusCode::SERVICE_UNAVAILABLE),
+    )
+        .into_response()
+}
+
+/// `ok`, or `draining` while in maintenance so that load balancers keep the
+/// instance in rotation and deploys know to wait. Fails once the features
+/// can't be read from the database, and from the start of `/__drain`.
+pub async fn healthz(Extension(ctx): Extension<Context>) -> Response {
+    if ctx.drain.is_draining() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "stopping" })),
+        )
+            .into_response();
+    }
+
+    match ctx.features.is_enabled(Maintenance::NAME, None).await {
+        Ok(false) => Json(serde_json::json!({ "status": "ok" })).into_response(),
+        Ok(true) => Json(serde_json::json!({ "status": "draining" })).into_response(),
+        Err(err) => {
+            error!("{err}");
+
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({ "status": "unavailable" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Outcome of a dependency of `/readyz`, `skipped` when this process doesn't
+/// use it.
+#[derive(Serialize)]
+pub struct ReadyCheck {
+    status: &'static str,
+    latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ReadyCheck {
+    fn skipped() -> Self {
+        Self {
+            status: "skipped",
+            latency_ms: 0,
+            error: None,
+        }
+    }
+
+    async fn run(check: impl Future<Output = Result<(), String>>) -> Self {
+        let started = Instant::now();
+        let result = tokio::time::timeout(READY_TIMEOUT, check)
+            .await
+            .unwrap_or_else(|_| Err("did not answer in time".to_owned()));
+        let latency_ms = started.elapsed().as_millis();
+
+        match result {
+            Ok(()) => Self {
+                status: "ok",
+                latency_ms,
+                error: None,
+            },
+            Err(err) => Self {
+                status: "failing",
+                latency_ms,
+                error: Some(err),
+            },
+        }
+    }
+
+    fn is_failing(&self) -> bool {
+        self.status == "failing"
+    }
+}
+
+/// Fails once a rule of the consumers got disabled by evento, or none is
+/// registered yet.
+async fn check_consumers(db: &PgPool) -> Result<(), String> {
+    let (rules, disabled) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COUNT(*), COUNT(*) FILTER (WHERE NOT enabled) FROM ev_queue",
+    )
+    .fetch_one(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match (rules, disabled) {
+        (0, _) => Err("no consumer started".to_owned()),
+        (_, 0) => Ok(()),
+        (_, disabled) => Err(format!("{disabled} of {rules} rules disabled")),
+    }
+}
+
+/// Whether the instance can take traffic, with the status and latency of
+/// the database, pikav and the consumers. Answers a 503 while one of them is
+/// failing, and from the start of `/__drain`, for the load balancers to move
+/// requests to other instances while `/healthz` keeps it alive.
+pub async fn readyz(Extension(ctx): Extension<Context>) -> Response {
+    let db = ctx.query.extract::<PgPool>();
+
+    let database = ReadyCheck::run(async {
+        sqlx::query("SELECT 1")
+            .execute(&db)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await;
+
+    let pikav = if ctx.live.is_pikav() {
+        ReadyCheck::run(check_pikav(&ctx.config.pikav.url)).await
+    } else {
+        ReadyCheck::skipped()
+    };
+
+    let consumers = ReadyCheck::run(check_consumers(&db)).await;
+
+    let checks = BTreeMap::from([
+        ("database", database),
+        ("pikav", pikav),
+        ("consumers", consumers),
+    ]);
+
+    let failing = checks
+        .iter()
+        .filter(|(_, check)| check.is_failing())
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>();
+
+    let (status, label) = if ctx.drain.is_draining() {
+        (StatusCode::SERVICE_UNAVAILABLE, "stopping")
+    } else if !failing.is_empty() {
+        warn!("not ready, {} failing", failing.join(", "));
+
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    } else {
+        (StatusCode::OK, "ok")
+    };
+
+    (
+        status,
+        Json(serde_json::json!({ "status": label, "checks": checks })),
+    )
+        .into_response()
+}
@@ -0,0 +1,77 @@
+/// Document head metadata of a page, rendered by `_meta.html` from the page
+/// `head` block:
+///
+/// ```ignore
+/// {% block head %}{% include "_meta.html" %}{% endblock %}
+/// ```
+#[derive(Default, Clone, Debug)]
+pub struct PageMeta {
+    pub title: String,
+    pub description: Option<String>,
+    pub canonical: Option<String>,
+    pub image: Option<String>,
+    pub og_type: String,
+    pub alternates: Vec<Alternate>,
+}
+
+/// `<link rel="alternate">` of a page, like its oembed or atom documents.
+#[derive(Clone, Debug)]
+pub struct Alternate {
+    pub kind: String,
+    pub href: String,
+    pub title: String,
+}
+
+impl PageMeta {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            og_type: "website".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Absolute url, see `Context::create_absolute_url`.
+    pub fn canonical(mut self, url: impl Into<String>) -> Self {
+        self.canonical = Some(url.into());
+        self
+    }
+
+    pub fn image(mut self, url: impl Into<String>) -> Self {
+        self.image = Some(url.into());
+        self
+    }
+
+    pub fn og_type(mut self, og_type: impl Into<String>) -> Self {
+        self.og_type = og_type.into();
+        self
+    }
+
+    /// Absolute url of the page as `kind`, a mime type.
+    pub fn alternate(
+        mut self,
+        kind: impl Into<String>,
+        href: impl Into<String>,
+        title: impl Into<String>,
+    ) -> Self {
+        self.alternates.push(Alternate {
+            kind: kind.into(),
+            href: href.into(),
+            title: title.into(),
+        });
+        self
+    }
+
+    pub fn twitter_card(&self) -> &'static str {
+        if self.image.is_some() {
+            "summary_large_image"
+        } else {
+            "summary"
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use axum::{
+    body::{self, Body, Bytes, HttpBody},
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures_util::StreamExt;
+use tracing::error;
+
+use crate::{config::MinifyConfig, context::Context};
+
+fn minify_config(config: &MinifyConfig) -> minify_html::Cfg {
+    let mut cfg = minify_html::Cfg::new();
+    cfg.keep_comments = config.keep_comments;
+    cfg.keep_closing_tags = config.keep_closing_tags;
+    cfg.minify_js = config.minify_js;
+    cfg.minify_css = config.minify_css;
+
+    cfg
+}
+
+/// Minifies html responses according to `Config::minify`. Streamed responses,
+/// without a known size, are minified chunk by chunk to keep their early
+/// flush, each chunk being a template of its own as `render_to_stream` sends
+/// them, or sent as is unless `MinifyConfig::streamed`.
+pub async fn minify_html(Extension(ctx): Extension<Context>, req: Request, next: Next) -> Response {
+    let res = next.run(req).await;
+    let config = ctx.reloader.config().minify;
+
+    let is_html = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+
+    if !config.enabled || !is_html {
+        return res;
+    }
+
+    if res.body().size_hint().exact().is_none() {
+        if !config.streamed {
+            return res;
+        }
+
+        let cfg = minify_config(&config);
+        let (parts, body) = res.into_parts();
+        let chunks = body
+            .into_data_stream()
+            .map(move |chunk| chunk.map(|bytes| Bytes::from(minify_html::minify(&bytes, &cfg))));
+
+        return Response::from_parts(parts, Body::from_stream(chunks));
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!("minify_html {err}");
+
+            return parts.status.into_response();
+        }
+    };
+
+    let minified = minify_html::minify(&bytes, &minify_config(&config));
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    Response::from_parts(parts, Body::from(minified))
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use evento::Rule;
+use sqlx::{migrate::Migrator, PgPool};
+
+use starter_feed::CronJob;
+
+use crate::{config::Config, pages::RouteMeta, routes::Routes};
+
+/// Feature of the app, its pages, consumer rules, cron jobs, migrations,
+/// translations and links being assembled by `serve`, `work` and `bench` from the modules
+/// of `pages::modules`.
+///
+/// ```ignore
+/// pub struct BookmarkModule;
+///
+/// impl FeatureModule for BookmarkModule {
+///     fn name(&self) -> &'static str {
+///         "bookmark"
+///     }
+///
+///     fn routes(&self) -> Routes {
+///         Routes::new().nest("/bookmark", create_router())
+///     }
+///
+///     fn rules(&self) -> Vec<Rule> {
+///         starter_bookmark::rules()
+///     }
+/// }
+/// ```
+pub trait FeatureModule: Send + Sync {
+    /// Name of the module, like `bookmark`.
+    fn name(&self) -> &'static str;
+
+    /// Routes of the module, from the root of the app.
+    fn routes(&self) -> Routes {
+        Routes::new()
+    }
+
+    /// Rules of the consumers, run by the processes running them.
+    fn rules(&self) -> Vec<Rule> {
+        vec![]
+    }
+
+    /// Jobs run on their schedule by the processes running the consumers,
+    /// each occurrence by only one of them.
+    fn cron_jobs(&self, _config: &Config) -> Vec<CronJob> {
+        vec![]
+    }
+
+    /// Migrations of the tables of the module, run after the ones of the
+    /// modules before it. Their versions must be unique among all the
+    /// modules, which migrations share the `_sqlx_migrations` table.
+    fn migrator(&self) -> Option<Migrator> {
+        None
+    }
+
+    /// Fluent messages by language, like
+    /// `("en", include_str!("../i18n/en/bookmark.ftl"))`, read by `ctx.t`
+    /// and overriding the ones of `i18n/` of the same id.
+    fn translations(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    /// Pages linked from the header for the signed in users, found by the
+    /// breadcrumbs like the ones of `pages::ROUTES`.
+    fn nav(&self) -> Vec<RouteMeta> {
+        vec![]
+    }
+}
+
+/// Routes of every module.
+pub fn create_router(modules: &[Box<dyn FeatureModule>]) -> Routes {
+    modules.iter().fold(Routes::new(), |routes, module| {
+        routes.merge(module.routes())
+    })
+}
+
+/// Runs the migrations of every module, in order. Each migrator skips the
+/// versions applied by the others.
+pub async fn migrate(db: &PgPool, modules: &[Box<dyn FeatureModule>]) -> Result<()> {
+    for module in modules {
+        if let Some(mut migrator) = module.migrator() {
+            migrator
+                .set_ignore_missing(true)
+                .set_locking(false)
+                .run(db)
+                .await
+                .with_context(|| format!("migrations of {}", module.name()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Migration of a module, as listed by `migrations`.
+pub struct MigrationStatus {
+    pub module: &'static str,
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+/// Migrations of every module, in order, with whether they were applied.
+pub async fn migrations(
+    db: &PgPool,
+    modules: &[Box<dyn FeatureModule>],
+) -> Result<Vec<MigrationStatus>> {
+    let created =
+        sqlx::query_scalar::<_, bool>("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(db)
+            .await?;
+
+    let applied = match created {
+        true => {
+            sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(db)
+                .await?
+        }
+        false => vec![],
+    };
+
+    let mut migrations = vec![];
+
+    for module in modules {
+        let Some(migrator) = module.migrator() else {
+            continue;
+        };
+
+        for migration in migrator
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+        {
+            migrations.push(MigrationStatus {
+                module: module.name(),
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: applied.contains(&migration.version),
+            });
+        }
+    }
+
+    Ok(migrations)
+}
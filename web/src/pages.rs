@@ -1,27 +1,371 @@
+mod admin;
+mod atom;
+mod drafts;
+mod embed;
 mod error;
 mod feed;
+mod follow;
+#[cfg(debug_assertions)]
+mod gallery;
 mod index;
+mod language;
+mod notifications;
+mod og;
+mod reaction;
+mod resized;
+mod search;
+mod settings;
+mod theme;
+mod trending;
+mod upload;
+mod user;
 
-use axum::{
-    routing::{get, post},
-    Router,
-};
+use axum::extract::DefaultBodyLimit;
 
 pub use error::*;
 use evento::Rule;
-use starter_feed::FeedRule;
+use once_cell::sync::Lazy;
+pub use resized::resized_image_path;
+pub use settings::{ExportJob, JOB_EXPORT};
+use sqlx::migrate::Migrator;
+use starter_feed::{side_effect_rule, traced_rule, CronJob, FeedRule};
+use utoipa::OpenApi;
+
+use crate::{
+    analytics::collect,
+    config::Config,
+    drain::drain,
+    live::{sse, ws},
+    maintenance::{healthz, readyz},
+    module::FeatureModule,
+    push::service_worker,
+    routes::{on, Routes},
+};
+
+use self::{
+    atom::*, drafts::*, embed::*, follow::*, index::*, language::*, notifications::*, og::*,
+    reaction::*, resized::*, search::*, theme::*, trending::*, upload::*, user::*,
+};
+
+/// OpenAPI document of the json routes of the pages, merged into the one of
+/// the api.
+#[derive(OpenApi)]
+#[openapi(paths(oembed), components(schemas(OEmbedResponse)))]
+pub struct PagesDoc;
+
+/// Name, localized title and parent of a page, used to build breadcrumbs.
+pub struct RouteMeta {
+    pub name: &'static str,
+    pub path: &'static str,
+    pub title: &'static str,
+    pub parent: Option<&'static str>,
+}
+
+pub const ROUTES: &[RouteMeta] = &[
+    RouteMeta {
+        name: "index",
+        path: "/",
+        title: "pages-routes_index",
+        parent: None,
+    },
+    RouteMeta {
+        name: "feed",
+        path: "/feed/:id",
+        title: "pages-routes_feed",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "feed-edit",
+        path: "/feed/:id/edit",
+        title: "pages-routes_feed_edit",
+        parent: Some("feed"),
+    },
+    RouteMeta {
+        name: "feed-history",
+        path: "/feed/:id/history",
+        title: "pages-routes_feed_history",
+        parent: Some("feed"),
+    },
+    RouteMeta {
+        name: "search",
+        path: "/search",
+        title: "pages-routes_search",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "following",
+        path: "/following",
+        title: "pages-routes_following",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "scheduled",
+        path: "/scheduled",
+        title: "pages-routes_scheduled",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "drafts",
+        path: "/drafts",
+        title: "pages-routes_drafts",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "drafts-edit",
+        path: "/drafts/:id",
+        title: "pages-routes_drafts_edit",
+        parent: Some("drafts"),
+    },
+    RouteMeta {
+        name: "user",
+        path: "/users/:user_id",
+        title: "pages-routes_user",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "trending",
+        path: "/trending",
+        title: "pages-routes_trending",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "notifications",
+        path: "/notifications",
+        title: "pages-routes_notifications",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "settings-billing",
+        path: "/settings/billing",
+        title: "pages-routes_settings_billing",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "settings-export",
+        path: "/settings/export",
+        title: "pages-routes_settings_export",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "settings-feeds",
+        path: "/settings/feeds",
+        title: "pages-routes_settings_feeds",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "settings-notifications",
+        path: "/settings/notifications",
+        title: "pages-routes_settings_notifications",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "settings-webhooks",
+        path: "/settings/webhooks",
+        title: "pages-routes_settings_webhooks",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-dead-letters",
+        path: "/admin/dead-letters",
+        title: "pages-routes_admin_dead_letters",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-analytics",
+        path: "/admin/analytics",
+        title: "pages-routes_admin_analytics",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-features",
+        path: "/admin/features",
+        title: "pages-routes_admin_features",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-jobs",
+        path: "/admin/jobs",
+        title: "pages-routes_admin_jobs",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-mailbox",
+        path: "/admin/mailbox",
+        title: "pages-routes_admin_mailbox",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-moderation",
+        path: "/admin/moderation",
+        title: "pages-routes_admin_moderation",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-status",
+        path: "/admin/status",
+        title: "pages-routes_admin_status",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-users",
+        path: "/admin/users",
+        title: "pages-routes_admin_users",
+        parent: Some("index"),
+    },
+    RouteMeta {
+        name: "admin-webhooks",
+        path: "/admin/webhooks",
+        title: "pages-routes_admin_webhooks",
+        parent: Some("index"),
+    },
+];
+
+/// Pages of the modules, after `ROUTES`.
+pub static MODULE_ROUTES: Lazy<Vec<RouteMeta>> =
+    Lazy::new(|| modules().iter().flat_map(|module| module.nav()).collect());
+
+pub fn route_meta(name: &str) -> Option<&'static RouteMeta> {
+    ROUTES
+        .iter()
+        .chain(MODULE_ROUTES.iter())
+        .find(|route| route.name == name)
+}
 
-use self::index::*;
+/// Modules the app is assembled from, the feeds first.
+pub fn modules() -> Vec<Box<dyn FeatureModule>> {
+    vec![Box::new(FeedModule)]
+}
+
+/// Feeds, comments and the pages around them, with the migrations of
+/// `migrations/`.
+pub struct FeedModule;
+
+impl FeatureModule for FeedModule {
+    fn name(&self) -> &'static str {
+        "feed"
+    }
+
+    fn routes(&self) -> Routes {
+        create_router()
+    }
+
+    fn rules(&self) -> Vec<Rule> {
+        starter_feed::rules().into_iter().chain(rules()).collect()
+    }
+
+    fn cron_jobs(&self, config: &Config) -> Vec<CronJob> {
+        let cron = &config.cron;
 
-pub fn create_router() -> Router {
-    Router::new()
-        .route("/", get(index))
-        .route("/_create-feed", post(create_feed))
-        .route("/_load-more", get(load_more))
-        .route("/_feed", get(feed))
+        vec![
+            CronJob::new("compact", &cron.compact, starter_feed::CompactTask),
+            CronJob::new("cleanup", &cron.cleanup, starter_feed::CleanupTask),
+            CronJob::new("trending", &cron.trending, starter_feed::TrendingTask),
+            CronJob::new(
+                "analytics",
+                &cron.analytics,
+                crate::analytics::AnalyticsTask,
+            ),
+        ]
+    }
+
+    fn migrator(&self) -> Option<Migrator> {
+        Some(sqlx::migrate!("../migrations"))
+    }
+}
+
+pub fn create_router() -> Routes {
+    let routes = Routes::new()
+        .route("/", on!(Public get(index)))
+        .route(
+            "/_create-feed",
+            on!(User get(new_feed), User post(create_feed)),
+        )
+        .route("/_markdown-preview", on!(User post(markdown_preview)))
+        .route("/_load-more", on!(Public get(load_more)))
+        .route("/_feed", on!(User get(feed)))
+        .route("/_reactions", on!(Public get(reactions)))
+        .route("/_react", on!(User post(react)))
+        .route("/_theme", on!(Public post(set_theme)))
+        .route("/_language", on!(Public post(set_language)))
+        .route(
+            "/_upload",
+            on!(User post(upload)).map(|route| route.layer(DefaultBodyLimit::disable())),
+        )
+        .route("/uploads/:key", on!(Public get(uploaded_file)))
+        .route("/img/:key", on!(Public get(resized_image)))
+        .route("/og/:file", on!(Public get(feed_image)))
+        .route("/feed.atom", on!(Public get(atom)))
+        .route("/oembed", on!(Public get(oembed)))
+        .route("/embed/feed/:id", on!(Public get(embed_feed)))
+        .route("/feed/tag/:tag", on!(Public get(tag)))
+        .route("/search", on!(Public get(search)))
+        .route("/trending", on!(Public get(trending)))
+        .route("/following", on!(User get(following)))
+        .route("/scheduled", on!(User get(scheduled)))
+        .route("/following/:user_id", on!(User post(follow)))
+        .route("/following/:user_id/delete", on!(User post(unfollow)))
+        .route("/users/:user_id", on!(Public get(user)))
+        .route("/notifications", on!(User get(notifications)))
+        .route("/sse/*topic", on!(Public get(sse)))
+        .route("/ws", on!(Public get(ws)))
+        .route("/sw.js", on!(Public get(service_worker)))
+        .route("/_analytics", on!(Public post(collect)))
+        .route("/healthz", on!(Public get(healthz)))
+        .route("/readyz", on!(Public get(readyz)))
+        .route("/metrics", on!(Public get(crate::telemetry::metrics)))
+        .route("/__drain", on!(Public post(drain)))
+        .route("/drafts", on!(User get(drafts)))
+        .route("/drafts/new", on!(User get(new_draft)))
+        .route("/drafts/:id", on!(User get(edit_draft)))
+        .route("/drafts/:id/_autosave", on!(User post(autosave_draft)))
+        .route("/drafts/:id/publish", on!(User post(publish_draft)))
+        .route("/drafts/:id/delete", on!(User post(discard_draft)))
         .nest("/feed/:id", feed::create_router())
+        .nest("/settings", settings::create_router())
+        .nest("/admin", admin::create_router())
+        .nest("/api/v1", crate::api::create_router())
+        .nest("/graphql", crate::graphql::create_router())
+        .nest("/billing", crate::billing::create_router())
+        .nest("/auth", crate::auth::create_router());
+
+    #[cfg(debug_assertions)]
+    let routes = routes.route("/_components", on!(Public get(gallery::gallery)));
+
+    #[cfg(feature = "test-api")]
+    let routes = routes.nest("/__test", crate::test_api::create_router());
+
+    routes
 }
 
 pub fn rules() -> Vec<Rule> {
-    vec![Rule::new(FeedRule::FeedDetails).handler("feed/**", index::IndexFeedHandler)]
+    vec![
+        traced_rule(FeedRule::FeedDetails, "feed/**", index::IndexFeedHandler),
+        side_effect_rule(
+            FeedRule::Comments,
+            "comment/**",
+            feed::CommentSectionHandler,
+        ),
+        side_effect_rule(FeedRule::Moderation, "feed/**", feed::ReportersNotifier),
+        side_effect_rule(FeedRule::Mentions, "feed/**", MentionsNotifier),
+        side_effect_rule(FeedRule::CommentMentions, "comment/**", MentionsNotifier),
+        side_effect_rule(FeedRule::Exports, "export/**", settings::ExportsGenerator),
+        side_effect_rule(
+            FeedRule::Preferences,
+            "preferences/**",
+            settings::VerificationMailer,
+        ),
+        side_effect_rule(FeedRule::Pushes, "feed/**", FeedPushNotifier),
+        side_effect_rule(FeedRule::CommentPushes, "comment/**", CommentPushNotifier),
+        traced_rule(
+            FeedRule::SearchIndex,
+            "feed/**",
+            crate::search::SearchIndexer,
+        ),
+        side_effect_rule(FeedRule::FeedBridge, "feed/**", crate::bridge::EventBridge),
+        side_effect_rule(
+            FeedRule::CommentBridge,
+            "comment/**",
+            crate::bridge::EventBridge,
+        ),
+    ]
 }
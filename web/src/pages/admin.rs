@@ -0,0 +1,48 @@
+mod analytics;
+mod dead_letters;
+mod features;
+mod jobs;
+mod mailbox;
+mod moderation;
+mod status;
+mod users;
+mod webhooks;
+
+use analytics::*;
+use dead_letters::*;
+use features::*;
+use jobs::*;
+use mailbox::*;
+use moderation::*;
+use status::*;
+use users::*;
+use webhooks::*;
+
+use crate::routes::{on, Routes};
+
+/// Routes of the admin area, those of the moderation queue being open to
+/// the users granted `Moderate` too.
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/analytics", on!(Admin get(analytics)))
+        .route("/dead-letters", on!(Admin get(dead_letters)))
+        .route("/features", on!(Admin get(features)))
+        .route("/features/:name", on!(Admin post(set_feature)))
+        .route("/features/:name/reset", on!(Admin post(reset_feature)))
+        .route("/jobs", on!(Admin get(jobs)))
+        .route("/jobs/:id/retry", on!(Admin post(retry_job)))
+        .route("/mailbox", on!(Admin get(mailbox)))
+        .route("/status", on!(Admin get(status)))
+        .route("/status/maintenance", on!(Admin post(set_maintenance)))
+        .route("/users", on!(Admin get(users)))
+        .route("/webhooks", on!(Admin get(webhooks)))
+        .route("/webhooks/:id/requeue", on!(Admin post(requeue_delivery)))
+        .admin_only()
+        .merge(
+            Routes::new()
+                .route("/moderation", on!(User get(moderation)))
+                .route("/moderation/:feed_id/hide", on!(User post(hide_feed)))
+                .route("/moderation/:feed_id/restore", on!(User post(restore_feed)))
+                .permitted::<Moderate>(),
+        )
+}
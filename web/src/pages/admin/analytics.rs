@@ -0,0 +1,37 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use tracing::error;
+
+use crate::{analytics::AnalyticsSummary, context::Context};
+
+/// Days summed up by the dashboard, today included.
+const SUMMARY_DAYS: i64 = 30;
+
+#[derive(Template)]
+#[template(path = "admin/analytics.html")]
+pub struct AnalyticsTemplate {
+    ctx: Context,
+    summary: AnalyticsSummary,
+    views: i64,
+    max_views: i64,
+}
+
+/// Page views, top pages and referrers of the last days.
+pub async fn analytics(ctx: Context) -> Result<AnalyticsTemplate, Response> {
+    let since = (Utc::now() - Duration::days(SUMMARY_DAYS - 1)).date_naive();
+
+    let summary = ctx.analytics.summary(since).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok(AnalyticsTemplate {
+        views: summary.days.iter().map(|day| day.views).sum(),
+        max_views: summary.days.iter().map(|day| day.views).max().unwrap_or(0),
+        ctx,
+        summary,
+    })
+}
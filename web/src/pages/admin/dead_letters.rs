@@ -0,0 +1,129 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{Query as EventoQuery, QueryHandler, QueryOutput};
+use i18n_embed_fl::fl;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::{
+    components::{Column, DataTable, TableQuery},
+    context::Context,
+    extract::Query,
+};
+
+const SORT_COLUMNS: [&str; 4] = ["name", "aggregate_id", "version", "created_at"];
+
+#[derive(sqlx::FromRow)]
+pub struct DeadLetter {
+    pub name: String,
+    pub aggregate_id: String,
+    pub version: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct ListDeadLettersInput {
+    pub table: TableQuery,
+}
+
+impl ListDeadLettersInput {
+    fn push_filter(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(search) = self.table.search() {
+            let pattern = format!("%{search}%");
+            builder
+                .push(" WHERE name ILIKE ")
+                .push_bind(pattern.to_owned())
+                .push(" OR aggregate_id ILIKE ")
+                .push_bind(pattern);
+        }
+    }
+}
+
+#[async_trait]
+impl QueryHandler for ListDeadLettersInput {
+    type Output = (Vec<DeadLetter>, i64);
+    async fn handle(&self, query: &EventoQuery) -> QueryOutput<Self::Output> {
+        let db = query.extract::<PgPool>();
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM ev_deadletter_event");
+        self.push_filter(&mut count);
+        let total = count.build_query_scalar::<i64>().fetch_one(&db).await?;
+
+        let mut select = QueryBuilder::new(
+            "SELECT name, aggregate_id, version, created_at FROM ev_deadletter_event",
+        );
+        self.push_filter(&mut select);
+        select
+            .push(format!(
+                " ORDER BY {} {}, id",
+                self.table.sort_column(&SORT_COLUMNS, "created_at"),
+                self.table.dir.as_sql()
+            ))
+            .push(" LIMIT ")
+            .push_bind(i64::from(self.table.size()))
+            .push(" OFFSET ")
+            .push_bind(self.table.offset());
+
+        let dead_letters = select.build_query_as::<DeadLetter>().fetch_all(&db).await?;
+
+        Ok((dead_letters, total))
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/dead_letters.html")]
+pub struct DeadLettersTemplate {
+    ctx: Context,
+    table: DataTable,
+}
+
+pub async fn dead_letters(
+    ctx: Context,
+    Query(table): Query<TableQuery>,
+) -> Result<DeadLettersTemplate, Response> {
+    let (dead_letters, total) = ctx
+        .query(ListDeadLettersInput {
+            table: table.clone(),
+        })
+        .await?;
+
+    let columns = vec![
+        Column::new(
+            "name",
+            fl!(ctx.fl_loader(), "pages_admin-DeadLettersPage_name"),
+        )
+        .sortable(),
+        Column::new(
+            "aggregate_id",
+            fl!(ctx.fl_loader(), "pages_admin-DeadLettersPage_aggregate_id"),
+        )
+        .sortable(),
+        Column::new(
+            "version",
+            fl!(ctx.fl_loader(), "pages_admin-DeadLettersPage_version"),
+        )
+        .sortable(),
+        Column::new(
+            "created_at",
+            fl!(ctx.fl_loader(), "pages_admin-DeadLettersPage_created_at"),
+        )
+        .sortable(),
+    ];
+
+    let rows = dead_letters
+        .iter()
+        .map(|dead_letter| {
+            vec![
+                dead_letter.name.to_owned(),
+                dead_letter.aggregate_id.to_owned(),
+                dead_letter.version.to_string(),
+                ctx.format_localized(&dead_letter.created_at, "%x %X"),
+            ]
+        })
+        .collect();
+
+    Ok(DeadLettersTemplate {
+        table: DataTable::new(&ctx, "/admin/dead-letters", columns, rows, total, &table),
+        ctx,
+    })
+}
@@ -0,0 +1,102 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+    context::Context,
+    extract::{Form, Path},
+    feature::{FeatureRule, FeatureState},
+    flash::Flash,
+};
+
+#[derive(Template)]
+#[template(path = "admin/features.html")]
+pub struct FeaturesTemplate {
+    ctx: Context,
+    features: Vec<FeatureState>,
+}
+
+impl FeaturesTemplate {
+    fn rule_label(&self, rule: Option<FeatureRule>) -> String {
+        let Some(rule) = rule else {
+            return String::new();
+        };
+
+        match (rule.enabled, rule.rollout) {
+            (false, _) => self.ctx.t("pages_admin-FeaturesPage_disabled"),
+            (true, None) => self.ctx.t("pages_admin-FeaturesPage_everyone"),
+            (true, Some(rollout)) => format!("{rollout}%"),
+        }
+    }
+}
+
+/// Features of the config and the ones overridden here, the override
+/// winning until it is reset.
+pub async fn features(ctx: Context) -> Result<FeaturesTemplate, Response> {
+    let features = ctx.features.list().await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok(FeaturesTemplate { ctx, features })
+}
+
+#[derive(Deserialize)]
+pub struct SetFeatureForm {
+    /// Only sent when the checkbox is checked.
+    enabled: Option<String>,
+    /// Percent of the users, everyone when left empty.
+    rollout: Option<String>,
+}
+
+pub async fn set_feature(
+    ctx: Context,
+    Path((name,)): Path<(String,)>,
+    Form(input): Form<SetFeatureForm>,
+) -> Result<Response, Response> {
+    let rollout = match input.rollout.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(rollout) => match rollout.parse::<u8>() {
+            Ok(rollout) if rollout <= 100 => Some(rollout),
+            _ => return Err(ctx.error_response(StatusCode::BAD_REQUEST)),
+        },
+    };
+
+    let rule = FeatureRule {
+        enabled: input.enabled.is_some(),
+        rollout,
+    };
+
+    ctx.features.set(&name, rule).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_admin-FeaturesPage_saved"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/admin/features"))).into_response())
+}
+
+pub async fn reset_feature(
+    ctx: Context,
+    Path((name,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    let reset = ctx.features.reset(&name).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    if !reset {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_admin-FeaturesPage_reset_done"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/admin/features"))).into_response())
+}
@@ -0,0 +1,45 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use i18n_embed_fl::fl;
+use starter_feed::FailedJob;
+use tracing::error;
+
+use crate::{context::Context, extract::Path, flash::Flash};
+
+/// Failed jobs listed, older ones being left out.
+const FAILED_LIMIT: i64 = 100;
+
+#[derive(Template)]
+#[template(path = "admin/jobs.html")]
+pub struct JobsTemplate {
+    ctx: Context,
+    jobs: Vec<FailedJob>,
+}
+
+/// Jobs whose attempts ran out, with their last error.
+pub async fn jobs(ctx: Context) -> Result<JobsTemplate, Response> {
+    let jobs = ctx.jobs.failed(FAILED_LIMIT).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok(JobsTemplate { ctx, jobs })
+}
+
+pub async fn retry_job(ctx: Context, Path((id,)): Path<(String,)>) -> Result<Response, Response> {
+    let retried = ctx.jobs.retry(&id).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    if !retried {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_admin-JobsPage_retried"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/admin/jobs"))).into_response())
+}
@@ -0,0 +1,20 @@
+use askama::Template;
+use askama_axum::Response;
+
+use crate::{context::Context, mailer::SentEmail};
+
+#[derive(Template)]
+#[template(path = "admin/mailbox.html")]
+pub struct MailboxTemplate {
+    ctx: Context,
+    emails: Vec<SentEmail>,
+}
+
+/// Emails kept by the mailbox transport, to read them locally without
+/// sending them.
+pub async fn mailbox(ctx: Context) -> Result<MailboxTemplate, Response> {
+    Ok(MailboxTemplate {
+        emails: ctx.mailer.mailbox(),
+        ctx,
+    })
+}
@@ -0,0 +1,142 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use starter_feed::{
+    HideFeedInput, ListModerationQueueInput, ModerationItem, RestoreFeedInput, REPORT_REASONS,
+};
+
+use crate::{
+    context::{Context, Permission, UserContext},
+    extract::{Form, Path, Query},
+};
+
+const STATUSES: [&str; 3] = ["pending", "hidden", "restored"];
+
+/// Lets moderators who aren't admins handle the reported feeds.
+pub struct Moderate;
+
+impl Permission for Moderate {
+    const NAME: &'static str = "moderate";
+}
+
+#[derive(Template)]
+#[template(path = "admin/moderation.html")]
+pub struct ModerationTemplate {
+    ctx: Context,
+    items: Vec<ModerationItem>,
+    status: Option<String>,
+    statuses: Vec<(String, String)>,
+    previous_url: Option<String>,
+    next_url: Option<String>,
+}
+
+pub async fn moderation(
+    ctx: Context,
+    Query(input): Query<ListModerationQueueInput>,
+) -> Result<ModerationTemplate, Response> {
+    let input = ListModerationQueueInput {
+        status: input
+            .status
+            .filter(|status| STATUSES.contains(&status.as_str())),
+        page: input.page,
+    };
+
+    let (items, has_next_page) = ctx.query(input.clone()).await?;
+
+    let page = input.page();
+    let page_url = |page: u16| {
+        let query = serde_urlencoded::to_string([
+            ("status", input.status.as_deref()),
+            ("page", Some(page.to_string().as_str())),
+        ])
+        .unwrap_or_default();
+
+        ctx.create_url(format!("/admin/moderation?{query}"))
+    };
+
+    Ok(ModerationTemplate {
+        previous_url: (page > 1).then(|| page_url(page - 1)),
+        next_url: has_next_page.then(|| page_url(page + 1)),
+        statuses: STATUSES
+            .iter()
+            .map(|status| {
+                (
+                    status.to_string(),
+                    ctx.t(&format!("pages_admin-ModerationPage_status_{status}")),
+                )
+            })
+            .collect(),
+        status: input.status,
+        items,
+        ctx,
+    })
+}
+
+impl ModerationTemplate {
+    fn reason_label(&self, reason: &str) -> String {
+        if REPORT_REASONS.contains(&reason) {
+            self.ctx
+                .t(&format!("pages_feed-IndexPage_report_reason_{reason}"))
+        } else {
+            reason.to_owned()
+        }
+    }
+
+    fn status_label(&self, status: &str) -> String {
+        self.statuses
+            .iter()
+            .find_map(|(value, label)| (value == status).then(|| label.to_owned()))
+            .unwrap_or_else(|| status.to_owned())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HideInput {
+    pub reason: Option<String>,
+}
+
+pub async fn hide_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+    Form(input): Form<HideInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(HideFeedInput {
+            feed_id,
+            reason: input
+                .reason
+                .map(|reason| reason.trim().to_owned())
+                .filter(|reason| !reason.is_empty()),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn restore_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(RestoreFeedInput {
+            feed_id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
@@ -0,0 +1,107 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+    context::Context,
+    extract::Form,
+    feature::{FeatureFlag, FeatureRule},
+    flash::Flash,
+    maintenance::Maintenance,
+};
+
+#[derive(Template)]
+#[template(path = "admin/status.html")]
+pub struct StatusTemplate {
+    ctx: Context,
+    /// Label and value of each service, the values being the configured
+    /// backends.
+    services: Vec<(String, String)>,
+    maintenance: bool,
+}
+
+/// Backends this process runs with, live updates falling back to `/sse` when
+/// pikav was unreachable at start.
+pub async fn status(ctx: Context) -> Result<StatusTemplate, Response> {
+    let live = if ctx.live.is_pikav() {
+        ctx.t("pages_admin-StatusPage_live_pikav")
+    } else {
+        ctx.t("pages_admin-StatusPage_live_in_process")
+    };
+
+    let cache = if ctx.config.redis_url.is_some() {
+        "redis".to_owned()
+    } else {
+        ctx.t("pages_admin-StatusPage_cache_memory")
+    };
+
+    let services = vec![
+        (ctx.t("pages_admin-StatusPage_live"), live),
+        (ctx.t("pages_admin-StatusPage_cache"), cache),
+        (
+            ctx.t("pages_admin-StatusPage_mail"),
+            ctx.config.mail.transport.to_owned(),
+        ),
+        (
+            ctx.t("pages_admin-StatusPage_storage"),
+            ctx.config.storage.backend.to_owned(),
+        ),
+        (
+            ctx.t("pages_admin-StatusPage_region"),
+            ctx.config.region.to_owned(),
+        ),
+    ];
+
+    let maintenance = ctx.is_feature_enabled(Maintenance::NAME).await?;
+
+    Ok(StatusTemplate {
+        ctx,
+        services,
+        maintenance,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct MaintenanceForm {
+    /// `on` to serve the maintenance page, anything else to stop.
+    maintenance: String,
+}
+
+/// Turns maintenance on or off for every instance by overriding the
+/// `maintenance` feature.
+pub async fn set_maintenance(
+    ctx: Context,
+    Form(input): Form<MaintenanceForm>,
+) -> Result<Response, Response> {
+    let enabled = input.maintenance == "on";
+    let rule = FeatureRule {
+        enabled,
+        rollout: None,
+    };
+
+    ctx.features
+        .set(Maintenance::NAME, rule)
+        .await
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    let flash = if enabled {
+        Flash::success(fl!(
+            ctx.fl_loader(),
+            "pages_admin-StatusPage_maintenance_started"
+        ))
+    } else {
+        Flash::success(fl!(
+            ctx.fl_loader(),
+            "pages_admin-StatusPage_maintenance_ended"
+        ))
+    };
+
+    Ok((flash, Redirect::to(&ctx.create_url("/admin/status"))).into_response())
+}
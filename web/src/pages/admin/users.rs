@@ -0,0 +1,122 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::async_trait;
+use evento::{Query as EventoQuery, QueryHandler, QueryOutput};
+use i18n_embed_fl::fl;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use starter_feed::UserDetails;
+
+use crate::{
+    components::{Column, DataTable, TableQuery},
+    context::Context,
+    extract::Query,
+};
+
+const SORT_COLUMNS: [&str; 4] = ["name", "role", "disabled", "created_at"];
+
+pub struct ListUsersPageInput {
+    pub table: TableQuery,
+}
+
+impl ListUsersPageInput {
+    fn push_filter(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(search) = self.table.search() {
+            let pattern = format!("%{search}%");
+            builder
+                .push(" WHERE name ILIKE ")
+                .push_bind(pattern.to_owned())
+                .push(" OR role ILIKE ")
+                .push_bind(pattern.to_owned())
+                .push(" OR id::text ILIKE ")
+                .push_bind(pattern);
+        }
+    }
+}
+
+#[async_trait]
+impl QueryHandler for ListUsersPageInput {
+    type Output = (Vec<UserDetails>, i64);
+    async fn handle(&self, query: &EventoQuery) -> QueryOutput<Self::Output> {
+        let db = query.extract::<PgPool>();
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM feed_users");
+        self.push_filter(&mut count);
+        let total = count.build_query_scalar::<i64>().fetch_one(&db).await?;
+
+        let mut select =
+            QueryBuilder::new("SELECT id, name, role, disabled, created_at FROM feed_users");
+        self.push_filter(&mut select);
+        select
+            .push(format!(
+                " ORDER BY {} {}, id",
+                self.table.sort_column(&SORT_COLUMNS, "created_at"),
+                self.table.dir.as_sql()
+            ))
+            .push(" LIMIT ")
+            .push_bind(i64::from(self.table.size()))
+            .push(" OFFSET ")
+            .push_bind(self.table.offset());
+
+        let users = select
+            .build_query_as::<UserDetails>()
+            .fetch_all(&db)
+            .await?;
+
+        Ok((users, total))
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/users.html")]
+pub struct UsersTemplate {
+    ctx: Context,
+    table: DataTable,
+}
+
+pub async fn users(
+    ctx: Context,
+    Query(table): Query<TableQuery>,
+) -> Result<UsersTemplate, Response> {
+    let (users, total) = ctx
+        .query(ListUsersPageInput {
+            table: table.clone(),
+        })
+        .await?;
+
+    let columns = vec![
+        Column::new("id", fl!(ctx.fl_loader(), "pages_admin-UsersPage_id")),
+        Column::new("name", fl!(ctx.fl_loader(), "pages_admin-UsersPage_name")).sortable(),
+        Column::new("role", fl!(ctx.fl_loader(), "pages_admin-UsersPage_role")).sortable(),
+        Column::new(
+            "disabled",
+            fl!(ctx.fl_loader(), "pages_admin-UsersPage_status"),
+        )
+        .sortable(),
+        Column::new(
+            "created_at",
+            fl!(ctx.fl_loader(), "pages_admin-UsersPage_created_at"),
+        )
+        .sortable(),
+    ];
+
+    let rows = users
+        .iter()
+        .map(|user| {
+            vec![
+                user.id.to_string(),
+                user.name.to_owned(),
+                user.role.to_owned(),
+                match user.disabled {
+                    true => fl!(ctx.fl_loader(), "pages_admin-UsersPage_disabled"),
+                    false => fl!(ctx.fl_loader(), "pages_admin-UsersPage_active"),
+                },
+                ctx.format_localized(&user.created_at, "%x %X"),
+            ]
+        })
+        .collect();
+
+    Ok(UsersTemplate {
+        table: DataTable::new(&ctx, "/admin/users", columns, rows, total, &table),
+        ctx,
+    })
+}
@@ -0,0 +1,99 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use starter_feed::{
+    ListAllWebhookDeliveriesInput, ListWebhookAttemptsInput, RequeueWebhookDeliveryInput,
+    UserWebhookDelivery, WebhookAttempt,
+};
+use std::collections::HashMap;
+
+use crate::{
+    context::{Context, UserContext},
+    extract::{Path, Query},
+    flash::Flash,
+};
+
+/// Deliveries listed, older ones being left out.
+const DELIVERIES_LIMIT: i64 = 100;
+
+#[derive(Template)]
+#[template(path = "admin/webhooks.html")]
+pub struct WebhooksTemplate {
+    ctx: Context,
+    deliveries: Vec<(UserWebhookDelivery, Vec<WebhookAttempt>)>,
+    dead: bool,
+}
+
+#[derive(Deserialize)]
+pub struct WebhooksQuery {
+    pub status: Option<String>,
+}
+
+/// Last deliveries of every user with their attempts, or the dead ones only.
+pub async fn webhooks(
+    ctx: Context,
+    Query(input): Query<WebhooksQuery>,
+) -> Result<WebhooksTemplate, Response> {
+    let dead = input.status.as_deref() == Some("dead");
+    let deliveries = ctx
+        .query(ListAllWebhookDeliveriesInput {
+            dead,
+            limit: DELIVERIES_LIMIT,
+        })
+        .await?;
+
+    let ids = deliveries
+        .iter()
+        .map(|delivery| delivery.id.to_owned())
+        .collect();
+
+    let mut attempts = HashMap::<String, Vec<WebhookAttempt>>::new();
+    for attempt in ctx.query(ListWebhookAttemptsInput { ids }).await? {
+        attempts
+            .entry(attempt.delivery_id.to_owned())
+            .or_default()
+            .push(attempt);
+    }
+
+    let deliveries = deliveries
+        .into_iter()
+        .map(|delivery| {
+            let delivery_attempts = attempts.remove(&delivery.id).unwrap_or_default();
+
+            (delivery, delivery_attempts)
+        })
+        .collect();
+
+    Ok(WebhooksTemplate {
+        ctx,
+        deliveries,
+        dead,
+    })
+}
+
+pub async fn requeue_delivery(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(RequeueWebhookDeliveryInput {
+            id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_admin-WebhooksPage_requeued"));
+
+    Ok((
+        flash,
+        Redirect::to(&ctx.create_url("/admin/webhooks?status=dead")),
+    )
+        .into_response())
+}
@@ -0,0 +1,93 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::http::{header, StatusCode};
+use i18n_embed_fl::fl;
+use starter_feed::ListFeedsInput;
+use tracing::error;
+
+use crate::{components::Markdown, context::Context};
+
+const ENTRIES: u16 = 20;
+
+pub struct AtomEntry {
+    id: String,
+    title: String,
+    author: String,
+    link: String,
+    updated: String,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "atom/feed.xml")]
+pub struct AtomFeedTemplate {
+    lang: String,
+    title: String,
+    self_link: String,
+    link: String,
+    updated: String,
+    entries: Vec<AtomEntry>,
+}
+
+/// Latest public feed items as an Atom document, served from `/feed.atom`.
+pub async fn atom(ctx: Context) -> Result<Response, Response> {
+    let feeds = ctx
+        .query(ListFeedsInput {
+            first: Some(ENTRIES),
+            after: None,
+            last: None,
+            before: None,
+            tag: None,
+            author: None,
+            unpinned: false,
+        })
+        .await?;
+
+    let entries: Vec<AtomEntry> = feeds
+        .edges
+        .into_iter()
+        .map(|edge| {
+            // Ids stay the same when the slug changes, readers telling
+            // entries apart by them.
+            let id = ctx.create_absolute_url(format!("/feed/{}", edge.node.id));
+            let link = ctx.create_absolute_url(edge.node.path());
+
+            AtomEntry {
+                id,
+                title: edge.node.title,
+                author: edge.node.author,
+                link,
+                updated: edge.node.created_at.to_rfc3339(),
+                content: Markdown::new(&edge.node.content).to_string(),
+            }
+        })
+        .collect();
+
+    let template = AtomFeedTemplate {
+        lang: ctx.user_language(),
+        title: fl!(ctx.fl_loader(), "pages_atom-AtomFeed_title"),
+        self_link: ctx.create_absolute_url("/feed.atom"),
+        link: ctx.create_absolute_url(""),
+        updated: entries
+            .first()
+            .map(|entry| entry.updated.to_owned())
+            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+        entries,
+    };
+
+    let xml = template.render().map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/atom+xml; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=300"),
+            (header::VARY, "Accept-Language"),
+        ],
+        xml,
+    )
+        .into_response())
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use starter_feed::{
+    DiscardDraftInput, GetDraftInput, ListDraftsInput, PublishDraftInput, SaveDraftInput, UserDraft,
+};
+use ulid::Ulid;
+
+use crate::{
+    components::Breadcrumbs,
+    context::UserContext,
+    extract::{Form, Path},
+};
+
+#[derive(Template)]
+#[template(path = "drafts/index.html")]
+pub struct DraftsTemplate {
+    ctx: UserContext,
+    drafts: Vec<UserDraft>,
+    breadcrumbs: Breadcrumbs,
+}
+
+pub async fn drafts(ctx: UserContext) -> Result<DraftsTemplate, Response> {
+    let drafts = ctx
+        .query(ListDraftsInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?;
+
+    Ok(DraftsTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "drafts", &[], None),
+        ctx,
+        drafts,
+    })
+}
+
+#[derive(Template)]
+#[template(path = "drafts/edit.html")]
+pub struct EditDraftTemplate {
+    ctx: UserContext,
+    id: String,
+    title: String,
+    content: String,
+    saved_at: Option<DateTime<Utc>>,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+fn template(
+    ctx: UserContext,
+    id: String,
+    input: DraftInput,
+    saved_at: Option<DateTime<Utc>>,
+    errors: HashMap<String, Vec<String>>,
+) -> EditDraftTemplate {
+    EditDraftTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "drafts-edit", &[("id", &id)], None),
+        ctx,
+        id,
+        title: input.title,
+        content: input.content,
+        saved_at,
+        errors,
+    }
+}
+
+/// Opens the editor on a new id, the draft being created by its first
+/// autosave.
+pub async fn new_draft(ctx: UserContext) -> EditDraftTemplate {
+    template(
+        ctx,
+        Ulid::new().to_string(),
+        DraftInput::default(),
+        None,
+        Default::default(),
+    )
+}
+
+pub async fn edit_draft(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<EditDraftTemplate, Response> {
+    let Some(draft) = ctx
+        .query(GetDraftInput {
+            id: id.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?
+    else {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    };
+
+    Ok(template(
+        ctx,
+        id,
+        DraftInput {
+            title: draft.title,
+            content: draft.content,
+        },
+        Some(draft.updated_at),
+        Default::default(),
+    ))
+}
+
+#[derive(Deserialize, Default)]
+pub struct DraftInput {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Template)]
+#[template(path = "drafts/status.html")]
+pub struct DraftStatusTemplate {
+    ctx: UserContext,
+    saved_at: Option<DateTime<Utc>>,
+}
+
+/// Saves the editor every few seconds while the user types, answering with
+/// the time of the save.
+pub async fn autosave_draft(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<DraftInput>,
+) -> Result<DraftStatusTemplate, Response> {
+    if ctx
+        .execute(SaveDraftInput {
+            id,
+            title: input.title,
+            content: input.content,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(DraftStatusTemplate {
+        ctx,
+        saved_at: Some(Utc::now()),
+    })
+}
+
+/// Saves the editor one last time then publishes the draft as a feed with
+/// the same id.
+pub async fn publish_draft(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<DraftInput>,
+) -> Result<Response, Response> {
+    let mut errors = ctx
+        .execute(SaveDraftInput {
+            id: id.to_owned(),
+            title: input.title.to_owned(),
+            content: input.content.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?;
+
+    if errors.is_none() {
+        errors = ctx
+            .execute(PublishDraftInput {
+                id: id.to_owned(),
+                user_id: ctx.user_id.to_owned(),
+                request_id: ctx.request_id(),
+            })
+            .await?;
+    }
+
+    let Some(errors) = errors else {
+        return Ok(Redirect::to(&ctx.create_url(format!("/feed/{id}"))).into_response());
+    };
+
+    Ok((
+        StatusCode::UNPROCESSABLE_ENTITY,
+        template(ctx, id, input, None, errors),
+    )
+        .into_response())
+}
+
+pub async fn discard_draft(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(DiscardDraftInput {
+            id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(Redirect::to(&ctx.create_url("/drafts")).into_response())
+}
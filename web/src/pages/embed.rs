@@ -0,0 +1,154 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use starter_feed::{GetFeedInput, UserFeed};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    components::{Avatar, Markdown},
+    context::Context,
+    extract::{Path, Query},
+};
+
+const DEFAULT_WIDTH: u32 = 550;
+const DEFAULT_HEIGHT: u32 = 400;
+
+#[derive(Template)]
+#[template(path = "embed/feed.html")]
+pub struct EmbedFeedTemplate {
+    ctx: Context,
+    feed: UserFeed,
+    avatar: Avatar,
+    content: Markdown,
+    link: String,
+}
+
+/// Standalone feed item meant to be framed, only by the `embed_origins` of the
+/// config.
+pub async fn embed_feed(ctx: Context, Path((id,)): Path<(String,)>) -> Result<Response, Response> {
+    let feed = ctx.query(GetFeedInput { id }).await?;
+    if feed.hidden || !feed.is_visible_to(None) {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    let frame_ancestors = std::iter::once("'self'")
+        .chain(
+            ctx.config
+                .embed_origins
+                .iter()
+                .map(|origin| origin.as_str()),
+        )
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let template = EmbedFeedTemplate {
+        avatar: Avatar::new(&feed.author, feed.user_id),
+        content: Markdown::new(&feed.content),
+        link: ctx.create_absolute_url(feed.path()),
+        ctx,
+        feed,
+    };
+
+    let mut res = template.into_response();
+
+    if let Ok(value) = HeaderValue::from_str(&format!("frame-ancestors {frame_ancestors}")) {
+        res.headers_mut()
+            .insert(header::CONTENT_SECURITY_POLICY, value);
+    }
+
+    Ok(res)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OEmbedQuery {
+    /// Absolute url of the feed page.
+    pub url: String,
+    /// Only `json` is supported.
+    pub format: Option<String>,
+    /// Capped at 550.
+    pub maxwidth: Option<u32>,
+    /// Capped at 400.
+    pub maxheight: Option<u32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OEmbedResponse {
+    #[schema(example = "1.0")]
+    version: &'static str,
+    #[serde(rename = "type")]
+    #[schema(example = "rich")]
+    kind: &'static str,
+    provider_name: String,
+    provider_url: String,
+    title: String,
+    author_name: String,
+    html: String,
+    width: u32,
+    height: u32,
+}
+
+/// oEmbed discovery of the feed pages, only the json format is supported.
+#[utoipa::path(
+    get,
+    path = "/oembed",
+    tag = "embed",
+    params(OEmbedQuery),
+    responses(
+        (status = 200, description = "Rich oEmbed of the feed", body = OEmbedResponse),
+        (status = 404, description = "The url is not the one of a visible feed"),
+        (status = 501, description = "The format is not json"),
+    )
+)]
+pub async fn oembed(
+    ctx: Context,
+    Query(input): Query<OEmbedQuery>,
+) -> Result<Json<OEmbedResponse>, Response> {
+    if input
+        .format
+        .as_deref()
+        .is_some_and(|format| format != "json")
+    {
+        return Err(StatusCode::NOT_IMPLEMENTED.into_response());
+    }
+
+    let prefix = ctx.create_absolute_url("/feed/");
+    let Some(id) = input
+        .url
+        .strip_prefix(&prefix)
+        .map(|id| id.trim_end_matches('/'))
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+    else {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let feed = ctx.query(GetFeedInput { id: id.to_owned() }).await?;
+    if feed.hidden || !feed.is_visible_to(None) {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let width = input.maxwidth.unwrap_or(DEFAULT_WIDTH).min(DEFAULT_WIDTH);
+    let height = input
+        .maxheight
+        .unwrap_or(DEFAULT_HEIGHT)
+        .min(DEFAULT_HEIGHT);
+    let src = ctx.create_absolute_url(format!("/embed/feed/{}", feed.id));
+
+    Ok(Json(OEmbedResponse {
+        version: "1.0",
+        kind: "rich",
+        provider_name: ctx.t("layout-Header_HomeLink_title"),
+        provider_url: ctx.create_absolute_url(""),
+        html: format!(
+            r#"<iframe src="{src}" width="{width}" height="{height}" frameborder="0" loading="lazy"></iframe>"#
+        ),
+        title: feed.title,
+        author_name: feed.author,
+        width,
+        height,
+    }))
+}
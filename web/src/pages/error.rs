@@ -1,4 +1,5 @@
 use askama::Template;
+use axum::http::StatusCode;
 use i18n_embed_fl::fl;
 
 use crate::context::Context;
@@ -72,3 +73,199 @@ impl InternalServerErrorPage {
         }
     }
 }
+
+pub struct BadRequestPageHomeLinkFl {
+    title: String,
+}
+
+pub struct BadRequestPageFl {
+    title: String,
+    content: String,
+    home_link: BadRequestPageHomeLinkFl,
+}
+
+#[derive(Template)]
+#[template(path = "400.html")]
+pub struct BadRequestPage {
+    ctx: Context,
+    fl: BadRequestPageFl,
+}
+
+impl BadRequestPage {
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            fl: BadRequestPageFl {
+                title: fl!(ctx.fl_loader(), "pages_error-BadRequestPage_title"),
+                content: fl!(ctx.fl_loader(), "pages_error-BadRequestPage_content"),
+                home_link: BadRequestPageHomeLinkFl {
+                    title: fl!(ctx.fl_loader(), "pages_error-BadRequestPage_HomeLink_title"),
+                },
+            },
+            ctx,
+        }
+    }
+}
+
+pub struct UnauthorizedPageHomeLinkFl {
+    title: String,
+}
+
+pub struct UnauthorizedPageFl {
+    title: String,
+    content: String,
+    home_link: UnauthorizedPageHomeLinkFl,
+}
+
+#[derive(Template)]
+#[template(path = "401.html")]
+pub struct UnauthorizedPage {
+    ctx: Context,
+    fl: UnauthorizedPageFl,
+}
+
+impl UnauthorizedPage {
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            fl: UnauthorizedPageFl {
+                title: fl!(ctx.fl_loader(), "pages_error-UnauthorizedPage_title"),
+                content: fl!(ctx.fl_loader(), "pages_error-UnauthorizedPage_content"),
+                home_link: UnauthorizedPageHomeLinkFl {
+                    title: fl!(
+                        ctx.fl_loader(),
+                        "pages_error-UnauthorizedPage_HomeLink_title"
+                    ),
+                },
+            },
+            ctx,
+        }
+    }
+}
+
+pub struct ForbiddenPageHomeLinkFl {
+    title: String,
+}
+
+pub struct ForbiddenPageFl {
+    title: String,
+    content: String,
+    home_link: ForbiddenPageHomeLinkFl,
+}
+
+#[derive(Template)]
+#[template(path = "403.html")]
+pub struct ForbiddenPage {
+    ctx: Context,
+    fl: ForbiddenPageFl,
+}
+
+impl ForbiddenPage {
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            fl: ForbiddenPageFl {
+                title: fl!(ctx.fl_loader(), "pages_error-ForbiddenPage_title"),
+                content: fl!(ctx.fl_loader(), "pages_error-ForbiddenPage_content"),
+                home_link: ForbiddenPageHomeLinkFl {
+                    title: fl!(ctx.fl_loader(), "pages_error-ForbiddenPage_HomeLink_title"),
+                },
+            },
+            ctx,
+        }
+    }
+}
+
+pub struct MaintenancePageFl {
+    title: String,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "503.html")]
+pub struct MaintenancePage {
+    ctx: Context,
+    fl: MaintenancePageFl,
+}
+
+impl MaintenancePage {
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            fl: MaintenancePageFl {
+                title: fl!(ctx.fl_loader(), "pages_error-MaintenancePage_title"),
+                content: fl!(ctx.fl_loader(), "pages_error-MaintenancePage_content"),
+            },
+            ctx,
+        }
+    }
+}
+
+pub struct TooManyRequestsPageFl {
+    title: String,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "429.html")]
+pub struct TooManyRequestsPage {
+    ctx: Context,
+    fl: TooManyRequestsPageFl,
+}
+
+impl TooManyRequestsPage {
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            fl: TooManyRequestsPageFl {
+                title: fl!(ctx.fl_loader(), "pages_error-TooManyRequestsPage_title"),
+                content: fl!(ctx.fl_loader(), "pages_error-TooManyRequestsPage_content"),
+            },
+            ctx,
+        }
+    }
+}
+
+pub struct ErrorAlertFl {
+    title: String,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "error_alert.html")]
+pub struct ErrorAlert {
+    fl: ErrorAlertFl,
+}
+
+impl ErrorAlert {
+    pub fn new(ctx: &Context, status: StatusCode) -> Self {
+        let fl_loader = ctx.fl_loader();
+        let fl = match status {
+            StatusCode::UNAUTHORIZED => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-UnauthorizedPage_title"),
+                content: fl!(fl_loader, "pages_error-UnauthorizedPage_content"),
+            },
+            StatusCode::FORBIDDEN => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-ForbiddenPage_title"),
+                content: fl!(fl_loader, "pages_error-ForbiddenPage_content"),
+            },
+            StatusCode::NOT_FOUND => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-NotFoundPage_title"),
+                content: fl!(fl_loader, "pages_error-NotFoundPage_content"),
+            },
+            StatusCode::TOO_MANY_REQUESTS => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-TooManyRequestsPage_title"),
+                content: fl!(fl_loader, "pages_error-TooManyRequestsPage_content"),
+            },
+            status if status.is_client_error() => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-BadRequestPage_title"),
+                content: fl!(fl_loader, "pages_error-BadRequestPage_content"),
+            },
+            StatusCode::SERVICE_UNAVAILABLE => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-MaintenancePage_title"),
+                content: fl!(fl_loader, "pages_error-MaintenancePage_content"),
+            },
+            _ => ErrorAlertFl {
+                title: fl!(fl_loader, "pages_error-InternalServerErrorPage_title"),
+                content: fl!(fl_loader, "pages_error-InternalServerErrorPage_content"),
+            },
+        };
+
+        Self { fl }
+    }
+}
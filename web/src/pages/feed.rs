@@ -1,8 +1,41 @@
+mod comments;
+mod edit;
+mod history;
 mod index;
+mod pin;
+mod report;
+mod tags;
 
-use axum::{routing::get, Router};
+use comments::*;
+use edit::*;
+use history::*;
 use index::*;
+use pin::*;
+use report::*;
+use tags::*;
 
-pub fn create_router() -> Router {
-    Router::new().route("/", get(index))
+use crate::routes::{on, Routes};
+
+pub use comments::CommentSectionHandler;
+pub use report::ReportersNotifier;
+
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/", on!(Public get(index)))
+        .route("/edit", on!(User get(edit), User post(update)))
+        .route("/history", on!(Public get(history)))
+        .route(
+            "/comments",
+            on!(Public get(comments), User post(create_comment)),
+        )
+        .route("/comments/:comment_id", on!(User post(edit_comment)))
+        .route(
+            "/comments/:comment_id/delete",
+            on!(User post(delete_comment)),
+        )
+        .route("/tags", on!(User post(tag_feed)))
+        .route("/tags/delete", on!(User post(untag_feed)))
+        .route("/report", on!(User post(report_feed)))
+        .route("/pin", on!(User post(pin_feed)))
+        .route("/pin/delete", on!(User post(unpin_feed)))
 }
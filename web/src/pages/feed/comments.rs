@@ -0,0 +1,130 @@
+use askama_axum::Response;
+use axum::{
+    async_trait,
+    http::{HeaderMap, StatusCode},
+};
+use evento::{store::Event, ConsumerContext, RuleHandler};
+use pikav_client::timada::SimpleEvent;
+use serde::Deserialize;
+use starter_feed::{
+    CommentCreated, CommentDeleted, CommentEdited, CommentEvent, CreateCommentInput,
+    DeleteCommentInput, EditCommentInput, ListCommentsInput,
+};
+
+use crate::{
+    components::CommentSection,
+    context::{Context, UserContext},
+    extract::{Form, Path},
+    live::Live,
+};
+
+pub async fn comments(
+    ctx: Context,
+    Path((feed_id,)): Path<(String,)>,
+) -> Result<CommentSection, Response> {
+    let comments = ctx
+        .query(ListCommentsInput {
+            feed_id: feed_id.to_owned(),
+        })
+        .await?;
+
+    Ok(CommentSection::new(&ctx, feed_id, comments))
+}
+
+#[derive(Deserialize)]
+pub struct CommentInput {
+    pub parent_id: Option<String>,
+    pub content: String,
+}
+
+pub async fn create_comment(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+    Form(input): Form<CommentInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(CreateCommentInput {
+            feed_id,
+            parent_id: input.parent_id.filter(|parent_id| !parent_id.is_empty()),
+            content: input.content,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn edit_comment(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((_, id)): Path<(String, String)>,
+    Form(input): Form<CommentInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(EditCommentInput {
+            id,
+            content: input.content,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn delete_comment(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((_, id)): Path<(String, String)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(DeleteCommentInput {
+            id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+/// Tells the comment sections of the feed to refresh.
+#[derive(Clone)]
+pub struct CommentSectionHandler;
+
+#[async_trait]
+impl RuleHandler for CommentSectionHandler {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let live = ctx.extract::<Live>();
+
+        let feed_id = match event.name.parse()? {
+            CommentEvent::Created => event.to_data::<CommentCreated>()?.feed_id,
+            CommentEvent::Edited => event.to_data::<CommentEdited>()?.feed_id,
+            CommentEvent::Deleted => event.to_data::<CommentDeleted>()?.feed_id,
+            CommentEvent::Mentioned => return Ok(()),
+        };
+
+        live.publish(vec![SimpleEvent {
+            user_id: "*".into(),
+            topic: "comments".into(),
+            event: format!("feed-{feed_id}"),
+            data: "".into(),
+        }]);
+
+        Ok(())
+    }
+}
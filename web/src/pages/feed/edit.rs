@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use serde::Deserialize;
+use starter_feed::{EditFeedInput, GetFeedInput, UserFeed};
+
+use crate::{
+    components::Breadcrumbs,
+    context::UserContext,
+    extract::{Form, Path},
+};
+
+#[derive(Template)]
+#[template(path = "feed/edit.html")]
+pub struct EditTemplate {
+    ctx: UserContext,
+    feed: UserFeed,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+async fn own_feed(ctx: &UserContext, id: String) -> Result<UserFeed, Response> {
+    let feed = ctx.query(GetFeedInput { id }).await?;
+
+    if feed.user_id.to_string() != ctx.user_id {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    Ok(feed)
+}
+
+fn template(
+    ctx: UserContext,
+    feed: UserFeed,
+    errors: HashMap<String, Vec<String>>,
+) -> EditTemplate {
+    EditTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "feed-edit", &[("id", &feed.id)], None),
+        ctx,
+        feed,
+        errors,
+    }
+}
+
+pub async fn edit(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<EditTemplate, Response> {
+    let feed = own_feed(&ctx, id).await?;
+
+    Ok(template(ctx, feed, Default::default()))
+}
+
+#[derive(Deserialize)]
+pub struct EditInput {
+    pub title: String,
+    pub content: String,
+}
+
+pub async fn update(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<EditInput>,
+) -> Result<Response, Response> {
+    let errors = ctx
+        .execute(EditFeedInput {
+            id: id.to_owned(),
+            title: input.title.to_owned(),
+            content: input.content.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?;
+
+    let Some(errors) = errors else {
+        return Ok(Redirect::to(&ctx.create_url(format!("/feed/{id}"))).into_response());
+    };
+
+    let mut feed = own_feed(&ctx, id).await?;
+    feed.title = input.title;
+    feed.content = input.content;
+
+    Ok((
+        StatusCode::UNPROCESSABLE_ENTITY,
+        template(ctx, feed, errors),
+    )
+        .into_response())
+}
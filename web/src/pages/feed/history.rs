@@ -0,0 +1,64 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::http::StatusCode;
+use starter_feed::{FeedRevision, GetFeedInput, ListFeedRevisionsInput};
+
+use crate::{
+    components::{Breadcrumbs, Diff},
+    context::Context,
+    extract::Path,
+};
+
+pub struct Revision {
+    version: i32,
+    edited_at: String,
+    title: Diff,
+    content: Diff,
+}
+
+#[derive(Template)]
+#[template(path = "feed/history.html")]
+pub struct HistoryTemplate {
+    ctx: Context,
+    breadcrumbs: Breadcrumbs,
+    revisions: Vec<Revision>,
+}
+
+/// Every version of a feed diffed against the previous one, newest first.
+pub async fn history(
+    ctx: Context,
+    Path((id,)): Path<(String,)>,
+) -> Result<HistoryTemplate, Response> {
+    let feed = ctx.query(GetFeedInput { id: id.to_owned() }).await?;
+    if !feed.is_visible_to(ctx.user_id.as_deref()) {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    let revisions = ctx.query(ListFeedRevisionsInput { feed_id: id }).await?;
+
+    let mut previous: Option<&FeedRevision> = None;
+    let mut diffs = vec![];
+
+    for revision in revisions.iter() {
+        let (title, content) = previous
+            .map(|previous| (previous.title.as_str(), previous.content.as_str()))
+            .unwrap_or_default();
+
+        diffs.push(Revision {
+            version: revision.version,
+            edited_at: ctx.format_localized(&revision.created_at, "%x %X"),
+            title: Diff::new(title, &revision.title),
+            content: Diff::new(content, &revision.content),
+        });
+
+        previous = Some(revision);
+    }
+
+    diffs.reverse();
+
+    Ok(HistoryTemplate {
+        breadcrumbs: Breadcrumbs::new(&ctx, "feed-history", &[("id", &feed.id)], None),
+        ctx,
+        revisions: diffs,
+    })
+}
@@ -1,43 +1,107 @@
 use askama::Template;
-use askama_axum::Response;
-use axum::extract::Path;
-use i18n_embed_fl::fl;
-use starter_feed::{GetFeedInput, UserFeed};
+use askama_axum::{IntoResponse, Response};
+use axum::http::{header, StatusCode};
+use starter_feed::{GetFeedByPermalinkInput, IsFollowingInput, UserFeed, VISIBILITY_PUBLIC};
 
-use crate::context::UserContext;
-
-pub struct IndexTemplateHomeLinkFl {
-    title: String,
-}
-
-pub struct IndexTemplateFl {
-    home_link: IndexTemplateHomeLinkFl,
-}
+use crate::{
+    components::{Breadcrumbs, Markdown},
+    context::Context,
+    extract::Path,
+    meta::PageMeta,
+};
 
 #[derive(Template)]
 #[template(path = "feed/index.html")]
 pub struct IndexTemplate {
-    ctx: UserContext,
+    ctx: Context,
     feed: UserFeed,
-    fl: IndexTemplateFl,
+    breadcrumbs: Breadcrumbs,
+    content: Markdown,
+    meta: PageMeta,
+    own: bool,
+    /// Whether the signed in user follows the author.
+    following: bool,
+}
+
+impl IndexTemplate {
+    /// Only listed feeds can be pinned.
+    fn pinnable(&self) -> bool {
+        !self.feed.hidden
+            && self.feed.visibility == VISIBILITY_PUBLIC
+            && self.feed.publish_at.is_none()
+    }
 }
 
+/// Feed of a permalink, its id and previous slugs moving permanently to the
+/// current slug.
 pub async fn index(
-    ctx: UserContext,
-    Path((id,)): Path<(String,)>,
-) -> Result<IndexTemplate, Response> {
-    let feed = ctx.query(GetFeedInput { id }).await?;
+    ctx: Context,
+    Path((permalink,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    let feed = ctx
+        .query(GetFeedByPermalinkInput {
+            permalink: permalink.to_owned(),
+        })
+        .await?;
+
+    let own = ctx.user_id.as_deref() == Some(feed.user_id.to_string().as_str());
+
+    // Hidden feeds stay visible to their author and to admins only.
+    if (feed.hidden && !own && !ctx.is_admin()) || !feed.is_visible_to(ctx.user_id.as_deref()) {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    if feed.slug.as_ref().is_some_and(|slug| *slug != permalink) {
+        return Ok((
+            StatusCode::MOVED_PERMANENTLY,
+            [(header::LOCATION, ctx.create_url(feed.path()))],
+        )
+            .into_response());
+    }
+
+    let following = match ctx.user_id.to_owned().filter(|_| !own) {
+        Some(user_id) => {
+            ctx.query(IsFollowingInput {
+                user_id,
+                followed_id: feed.user_id,
+            })
+            .await?
+        }
+        None => false,
+    };
+
+    let meta = PageMeta::new(&feed.title)
+        .description(&feed.content_short)
+        .canonical(ctx.create_absolute_url(feed.path()))
+        .image(ctx.create_absolute_url(format!("/og/{}.png", feed.id)))
+        .og_type("article");
+
+    let oembed_query = serde_urlencoded::to_string([
+        ("format", "json"),
+        ("url", meta.canonical.as_deref().unwrap_or_default()),
+    ])
+    .unwrap_or_default();
+    let meta = meta.alternate(
+        "application/json+oembed",
+        ctx.create_absolute_url(format!("/oembed?{oembed_query}")),
+        &feed.title,
+    );
 
     Ok(IndexTemplate {
-        fl: IndexTemplateFl {
-            home_link: IndexTemplateHomeLinkFl {
-                title: fl!(
-                    ctx.fl_loader(),
-                    "pages_feed_index-IndexTemplate_HomeLink_title"
-                ),
-            },
-        },
+        breadcrumbs: Breadcrumbs::new(
+            &ctx,
+            "feed",
+            &[("id", &feed.id)],
+            Some(feed.title.to_owned()),
+        ),
+        content: Markdown::with_mentions(&feed.content, |user_id| {
+            ctx.create_url(format!("/users/{user_id}"))
+        }),
+        own,
+        following,
         ctx,
         feed,
-    })
+        meta,
+    }
+    .into_response())
 }
@@ -0,0 +1,48 @@
+use askama_axum::Response;
+use axum::http::{HeaderMap, StatusCode};
+use starter_feed::{PinFeedInput, UnpinFeedInput};
+
+use crate::{context::UserContext, extract::Path};
+
+pub async fn pin_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(PinFeedInput {
+            feed_id,
+            max_pins: ctx.context().config.max_pins,
+            is_admin: ctx.is_admin(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn unpin_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(UnpinFeedInput {
+            feed_id,
+            is_admin: ctx.is_admin(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
@@ -0,0 +1,109 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    async_trait,
+    http::{HeaderMap, StatusCode},
+};
+use evento::{store::Event, Aggregate, ConsumerContext, RuleHandler};
+use i18n_embed_fl::fl;
+use pikav_client::timada::SimpleEvent;
+use serde::Deserialize;
+use sqlx::PgPool;
+use starter_feed::{Feed, FeedEvent, ReportFeedInput, CHANNEL_IN_APP, NOTIFICATION_MODERATION};
+use unic_langid::LanguageIdentifier;
+
+use crate::{
+    context::UserContext,
+    extract::{Form, Path},
+    flash::Flash,
+    i18n::LANGUAGE_LOADER,
+    live::Live,
+};
+
+#[derive(Deserialize)]
+pub struct ReportInput {
+    pub reason: String,
+}
+
+pub async fn report_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+    Form(input): Form<ReportInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(ReportFeedInput {
+            feed_id,
+            reason: input.reason,
+            lang: ctx.user_language(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_feed-IndexPage_reported"));
+
+    Ok((flash, ctx.submitted(&headers)).into_response())
+}
+
+/// Tells every reporter of a feed, in the language they reported in, once a
+/// moderator hid or restored it, unless they muted it.
+#[derive(Clone)]
+pub struct ReportersNotifier;
+
+#[async_trait]
+impl RuleHandler for ReportersNotifier {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let message_id = match event.name.parse()? {
+            FeedEvent::Hidden => "pages_feed-IndexPage_report_hidden",
+            FeedEvent::Restored => "pages_feed-IndexPage_report_restored",
+            _ => return Ok(()),
+        };
+
+        let db = ctx.extract::<PgPool>();
+        let live = ctx.extract::<Live>();
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        let reporters = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT user_id::text, lang FROM feed_reports r
+            WHERE feed_id = $1 AND NOT EXISTS (
+                SELECT 1 FROM feed_muted_notifications m
+                WHERE m.user_id = r.user_id AND m.kind = $2 AND m.channel = $3
+            )
+            "#,
+        )
+        .bind(&feed_id)
+        .bind(NOTIFICATION_MODERATION)
+        .bind(CHANNEL_IN_APP)
+        .fetch_all(&db)
+        .await?;
+
+        let events = reporters
+            .into_iter()
+            .map(|(user_id, lang)| {
+                let langs = lang
+                    .parse::<LanguageIdentifier>()
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let message = LANGUAGE_LOADER.select_languages(&langs).get(message_id);
+
+                SimpleEvent {
+                    user_id,
+                    topic: "toasts".into(),
+                    event: "toast".into(),
+                    data: Flash::info(message).to_html(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !events.is_empty() {
+            live.publish(events);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use askama_axum::Response;
+use axum::http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use starter_feed::{TagFeedInput, UntagFeedInput};
+
+use crate::{
+    context::UserContext,
+    extract::{Form, Path},
+};
+
+#[derive(Deserialize)]
+pub struct TagInput {
+    pub tag: String,
+}
+
+pub async fn tag_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+    Form(input): Form<TagInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(TagFeedInput {
+            feed_id,
+            tag: input.tag.trim().to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn untag_feed(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((feed_id,)): Path<(String,)>,
+    Form(input): Form<TagInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(UntagFeedInput {
+            feed_id,
+            tag: input.tag,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
@@ -0,0 +1,45 @@
+use askama_axum::Response;
+use axum::http::{HeaderMap, StatusCode};
+use starter_feed::{FollowUserInput, UnfollowUserInput};
+
+use crate::{context::UserContext, extract::Path};
+
+pub async fn follow(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((followed_id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(FollowUserInput {
+            followed_id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
+
+pub async fn unfollow(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Path((followed_id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(UnfollowUserInput {
+            followed_id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(ctx.submitted(&headers))
+}
@@ -0,0 +1,137 @@
+use askama::Template;
+use chrono::{DateTime, TimeZone, Utc};
+use starter_feed::{ReactionCount, TagCount, UserComment};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::{components::*, context::Context};
+
+const FEED_ID: &str = "01HQ0000000000000000000000";
+const USER_ID: Uuid = Uuid::from_u128(0x0000_0000_0000_4000_8000_0000_0000_0001);
+
+pub struct GalleryItem {
+    name: &'static str,
+    html: String,
+}
+
+fn item(name: &'static str, component: impl fmt::Display) -> GalleryItem {
+    GalleryItem {
+        name,
+        html: component.to_string(),
+    }
+}
+
+fn date(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap()
+}
+
+fn comment(id: &str, parent_id: Option<&str>, content: &str, day: u32) -> UserComment {
+    UserComment {
+        id: id.to_owned(),
+        feed_id: FEED_ID.to_owned(),
+        parent_id: parent_id.map(str::to_owned),
+        user_id: USER_ID,
+        author: "Ada Lovelace".to_owned(),
+        content: content.to_owned(),
+        deleted: false,
+        created_at: date(day),
+        updated_at: None,
+    }
+}
+
+fn tags() -> Vec<TagCount> {
+    [("rust", 12), ("htmx", 5), ("askama", 1)]
+        .into_iter()
+        .map(|(tag, total_count)| TagCount {
+            tag: tag.to_owned(),
+            total_count,
+        })
+        .collect()
+}
+
+/// Components rendered with fixed fixtures, one `data-component` section
+/// each, in the language of `?lang=`. Only served by debug builds, for the
+/// snapshots of `starter-test`.
+#[derive(Template)]
+#[template(path = "gallery.html")]
+pub struct GalleryTemplate {
+    ctx: Context,
+    items: Vec<GalleryItem>,
+}
+
+pub async fn gallery(ctx: Context) -> GalleryTemplate {
+    let comments = vec![
+        comment("01HQ0000000000000000000001", None, "First!", 1),
+        comment(
+            "01HQ0000000000000000000002",
+            Some("01HQ0000000000000000000001"),
+            "A **reply**",
+            2,
+        ),
+    ];
+
+    let reactions = vec![ReactionCount {
+        reaction: "👍".to_owned(),
+        total_count: 3,
+        reacted: false,
+    }];
+
+    let items = vec![
+        item("avatar", Avatar::new("Ada Lovelace", USER_ID)),
+        item(
+            "breadcrumbs",
+            Breadcrumbs::new(&ctx, "feed-edit", &[("id", FEED_ID)], None),
+        ),
+        item(
+            "comment_section",
+            CommentSection::new(&ctx, FEED_ID, comments),
+        ),
+        item("diff", Diff::new("one\ntwo\nthree", "one\n2\nthree")),
+        item(
+            "feed_item_skeleton",
+            FeedItemSkeleton {
+                url: ctx.create_url(format!("/_feed?id={FEED_ID}")),
+            },
+        ),
+        item(
+            "markdown",
+            Markdown::new("**Bold**, `code` and [a link](https://example.com)"),
+        ),
+        item(
+            "modal",
+            Modal::new(&ctx, ctx.t("pages-routes_index"), "<p>Body</p>"),
+        ),
+        item(
+            "pinned_badge",
+            PinnedBadge::new(ctx.t("pages_index-FeedItem_pinned")),
+        ),
+        item("popular_tags", PopularTags::new(&ctx, tags())),
+        item(
+            "progress_bar",
+            ProgressBar {
+                label: "Export".to_owned(),
+                value: 40,
+            },
+        ),
+        item(
+            "reaction_picker",
+            ReactionPicker::new(&ctx, FEED_ID, reactions),
+        ),
+        item("tag_cloud", TagCloud::new(&ctx, tags())),
+        item("upload_field", UploadField::new(&ctx, "image")),
+        item(
+            "wizard_steps",
+            WizardSteps::new(
+                &ctx,
+                &[
+                    "pages-routes_index",
+                    "pages-routes_trending",
+                    "pages-routes_search",
+                ],
+                1,
+            ),
+        ),
+    ];
+
+    GalleryTemplate { ctx, items }
+}
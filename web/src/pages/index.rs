@@ -1,33 +1,67 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, future::ready, time::Duration};
 
 use askama::Template;
-use askama_axum::Response;
-use axum::{async_trait, extract::Query, Form};
+use askama_axum::{IntoResponse, Response};
+use axum::{async_trait, extract::RawQuery, http::StatusCode, response::Redirect};
+use chrono::{NaiveDateTime, TimeZone, Utc};
 use evento::{store::Event, Aggregate, ConsumerContext, RuleHandler};
 use evento_query::{Cursor, CursorType, Edge, QueryResult};
 use pikav_client::timada::SimpleEvent;
 use serde::Deserialize;
+use sqlx::PgPool;
 use starter_feed::{
-    Created, Feed, FeedEvent, FeedMetadata, ListFeedsInput, ListPopularTagsInput, TagCount,
-    UserFeed,
+    Created, Feed, FeedEvent, FeedMetadata, ListFeedsInput, ListFollowingFeedsInput,
+    ListPinnedFeedsInput, ListPopularTagsInput, ListScheduledFeedsInput, ListTagsInput,
+    ListTrendingFeedsInput, Tagged, UserFeed, VISIBILITIES, VISIBILITY_PUBLIC,
 };
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
+    cache::{Cached, FragmentCache},
+    components::{
+        ErrorBoundary, FeedItemSkeleton, Markdown, Modal, Paginator, PopularTags, TagCloud,
+        TrendingFeeds, UploadField,
+    },
     config::Config,
     context::{Context, UserContext},
+    extract::{Form, Path, Query},
+    live::Live,
+    meta::PageMeta,
+    stream::render_to_stream,
 };
 
+use super::upload::stored_attachment;
+
+const POPULAR_TAGS_CACHE_KEY: &str = "index-popular-tags";
+const POPULAR_TAGS_CACHE_TTL: Duration = Duration::from_secs(300);
+const TAG_CLOUD_CACHE_KEY: &str = "tag-cloud";
+const TRENDING_CACHE_KEY: &str = "index-trending-feeds";
+/// Short as rankings change with every reaction and comment, without
+/// invalidating it on each of them.
+const TRENDING_CACHE_TTL: Duration = Duration::from_secs(60);
+/// Largest page of the index, the one offered by its paginator.
+const MAX_PAGE_SIZE: u16 = 50;
+
 #[derive(Template)]
 #[template(path = "index.html")]
 pub struct IndexTemplate {
     ctx: Context,
     tag: Option<String>,
     prev_tag: Option<String>,
+    author: Option<Uuid>,
+    view: FeedsView,
+    page_size: u16,
     feeds: QueryResult<UserFeed>,
-    popular_tags: Vec<TagCount>,
+    /// Pinned feeds floated above the first page of the global feed.
+    pinned: Vec<Edge<UserFeed>>,
+    popular_tags: ErrorBoundary<Cached>,
+    trending: ErrorBoundary<Cached>,
     errors: HashMap<String, Vec<String>>,
     global_link: String,
+    paginator: Paginator,
+    upload: UploadField,
+    visibilities: Vec<(String, String)>,
 }
 
 impl IndexTemplate {
@@ -46,17 +80,75 @@ impl IndexTemplate {
     }
 
     fn query_tag(&self) -> String {
-        self.tag
+        query_tag(&self.tag, self.author, self.view, self.page_size)
+    }
+
+    /// Feeds of an author are not pushed, new ones showing up once the page
+    /// is loaded again.
+    fn sse_topic(&self) -> Option<String> {
+        if self.author.is_some() {
+            return None;
+        }
+
+        match self.view {
+            FeedsView::Following => Some("/following".to_owned()),
+            FeedsView::Scheduled => None,
+            FeedsView::Global => Some(
+                self.tag
+                    .as_ref()
+                    .map(|tag| format!("/tag/{tag}"))
+                    .unwrap_or("/index".to_owned()),
+            ),
+        }
+    }
+
+    fn following(&self) -> bool {
+        self.view == FeedsView::Following
+    }
+
+    fn scheduled(&self) -> bool {
+        self.view == FeedsView::Scheduled
+    }
+}
+
+/// Feeds listed by the index, the other views than `Global` being the ones
+/// of the signed in user.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedsView {
+    #[default]
+    Global,
+    Following,
+    Scheduled,
+}
+
+/// Query of the next page loaded by the last feed item.
+fn query_tag(tag: &Option<String>, author: Option<Uuid>, view: FeedsView, size: u16) -> String {
+    let filter = match view {
+        FeedsView::Following => "&view=following".to_owned(),
+        FeedsView::Scheduled => "&view=scheduled".to_owned(),
+        FeedsView::Global => tag
             .as_ref()
             .map(|tag| format!("&tag={tag}"))
-            .unwrap_or("".to_owned())
+            .unwrap_or("".to_owned()),
+    };
+
+    format!("&first={size}{filter}{}", query_author(author, view))
+}
+
+fn query_author(author: Option<Uuid>, view: FeedsView) -> String {
+    match (author, view) {
+        (Some(author), FeedsView::Global) => format!("&author={author}"),
+        _ => "".to_owned(),
     }
+}
 
-    fn sse_tag_suffix(&self) -> String {
-        self.tag
-            .as_ref()
-            .map(|tag| format!("-{tag}"))
-            .unwrap_or_default()
+/// Pages larger than `MAX_PAGE_SIZE` are cut down to it.
+fn bounded(input: ListFeedsInput) -> ListFeedsInput {
+    ListFeedsInput {
+        first: input.first.map(|first| first.min(MAX_PAGE_SIZE)),
+        last: input.last.map(|last| last.min(MAX_PAGE_SIZE)),
+        ..input
     }
 }
 
@@ -68,35 +160,260 @@ pub struct IndexQuery {
 
 pub async fn index(
     ctx: Context,
+    RawQuery(raw_query): RawQuery,
     Query(input): Query<IndexQuery>,
     Query(list_feeds_input): Query<ListFeedsInput>,
-) -> Result<IndexTemplate, Response> {
-    let (feeds, popular_tags) =
-        tokio::try_join!(ctx.query(list_feeds_input), ctx.query(ListPopularTagsInput))?;
+) -> Response {
+    if let Some(tag) = input.tag {
+        let query = raw_query
+            .map(|query| format!("?{query}"))
+            .unwrap_or_default();
 
-    let global_link = input
-        .tag
-        .as_ref()
-        .map(|tag| format!("?prev_tag={tag}"))
-        .unwrap_or_default();
+        return Redirect::permanent(&ctx.create_url(format!("/feed/tag/{tag}{query}")))
+            .into_response();
+    }
 
-    Ok(IndexTemplate {
+    render_index(
         ctx,
-        feeds,
-        popular_tags,
-        global_link,
-        tag: input.tag,
-        prev_tag: input.prev_tag,
-        errors: Default::default(),
+        None,
+        input.prev_tag,
+        FeedsView::Global,
+        list_feeds_input,
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct TagQuery {
+    prev_tag: Option<String>,
+}
+
+/// Feed items having a tag, new ones being pushed on the `tag/{tag}` pikav
+/// topic.
+pub async fn tag(
+    ctx: Context,
+    Path((tag,)): Path<(String,)>,
+    Query(input): Query<TagQuery>,
+    Query(mut list_feeds_input): Query<ListFeedsInput>,
+) -> Response {
+    list_feeds_input.tag = Some(tag.to_owned());
+
+    render_index(
+        ctx,
+        Some(tag),
+        input.prev_tag,
+        FeedsView::Global,
+        list_feeds_input,
+    )
+    .await
+}
+
+/// Feed items of the users followed by the signed in user, materialized in
+/// their timeline and pushed on the `following` pikav topic.
+pub async fn following(
+    ctx: UserContext,
+    Query(list_feeds_input): Query<ListFeedsInput>,
+) -> Response {
+    render_index(
+        ctx.context().clone(),
+        None,
+        None,
+        FeedsView::Following,
+        list_feeds_input,
+    )
+    .await
+}
+
+/// Feeds of the signed in user waiting for their publish time, only listed to
+/// them.
+pub async fn scheduled(
+    ctx: UserContext,
+    Query(list_feeds_input): Query<ListFeedsInput>,
+) -> Response {
+    render_index(
+        ctx.context().clone(),
+        None,
+        None,
+        FeedsView::Scheduled,
+        list_feeds_input,
+    )
+    .await
+}
+
+async fn render_index(
+    ctx: Context,
+    tag: Option<String>,
+    prev_tag: Option<String>,
+    view: FeedsView,
+    list_feeds_input: ListFeedsInput,
+) -> Response {
+    let list_feeds_input = bounded(list_feeds_input);
+    let author = list_feeds_input
+        .author
+        .filter(|_| view == FeedsView::Global);
+
+    let uri = match (&tag, view) {
+        (_, FeedsView::Following) => "/following".to_owned(),
+        (_, FeedsView::Scheduled) => "/scheduled".to_owned(),
+        (Some(tag), _) => format!("/feed/tag/{tag}"),
+        _ => "".to_owned(),
+    };
+
+    let title = match (&tag, view) {
+        (_, FeedsView::Following) => ctx.t("pages-routes_following"),
+        (_, FeedsView::Scheduled) => ctx.t("pages-routes_scheduled"),
+        (Some(tag), _) => format!("#{tag}"),
+        _ => "Timada Starter app".to_owned(),
+    };
+
+    let meta = PageMeta::new(title).canonical(ctx.create_absolute_url(match uri.as_str() {
+        "" => "/",
+        uri => uri,
+    }));
+
+    render_to_stream(ctx, ready(Ok(meta)), |ctx| async move {
+        let page_size = list_feeds_input
+            .first
+            .or(list_feeds_input.last)
+            .unwrap_or(20);
+
+        let first_page = list_feeds_input.after.is_none() && list_feeds_input.before.is_none();
+        let pinned = async {
+            if view != FeedsView::Global || tag.is_some() || author.is_some() || !first_page {
+                return Ok(vec![]);
+            }
+
+            ctx.query(ListPinnedFeedsInput).await
+        };
+
+        let sidebar = async {
+            match &tag {
+                Some(_) => {
+                    ctx.cached(TAG_CLOUD_CACHE_KEY, POPULAR_TAGS_CACHE_TTL, || async {
+                        ctx.query(ListTagsInput { limit: None })
+                            .await
+                            .map(|tags| TagCloud::new(&ctx, tags))
+                    })
+                    .await
+                }
+                None => {
+                    ctx.cached(POPULAR_TAGS_CACHE_KEY, POPULAR_TAGS_CACHE_TTL, || async {
+                        ctx.query(ListPopularTagsInput)
+                            .await
+                            .map(|tags| PopularTags::new(&ctx, tags))
+                    })
+                    .await
+                }
+            }
+        };
+
+        let trending = ctx.cached(TRENDING_CACHE_KEY, TRENDING_CACHE_TTL, || async {
+            ctx.query(ListTrendingFeedsInput { limit: 5 })
+                .await
+                .map(|feeds| TrendingFeeds::new(&ctx, feeds))
+        });
+
+        let (feeds, pinned, popular_tags, trending) = tokio::join!(
+            list_feeds(&ctx, view, list_feeds_input),
+            pinned,
+            sidebar,
+            trending
+        );
+
+        let feeds = feeds?;
+        let pinned = pinned?
+            .into_iter()
+            .map(|feed| Edge {
+                cursor: feed.to_cursor(),
+                node: feed,
+            })
+            .collect();
+        let popular_tags = ErrorBoundary::new(&ctx, popular_tags);
+        let trending = ErrorBoundary::new(&ctx, trending);
+
+        let global_link = ctx.create_url(
+            tag.as_ref()
+                .map(|tag| format!("/?prev_tag={tag}"))
+                .unwrap_or("/".to_owned()),
+        );
+
+        let paginator = Paginator::new(
+            &ctx,
+            &feeds.page_info,
+            uri,
+            page_size,
+            query_author(author, view),
+        );
+
+        Ok(IndexTemplate {
+            upload: UploadField::new(&ctx, "attachment"),
+            visibilities: visibility_options(&ctx),
+            ctx,
+            paginator,
+            feeds,
+            pinned,
+            popular_tags,
+            trending,
+            global_link,
+            tag,
+            prev_tag,
+            author,
+            view,
+            page_size,
+            errors: Default::default(),
+        })
     })
 }
 
+/// Global or tag feeds, or the ones of the signed in user for the other
+/// views.
+async fn list_feeds(
+    ctx: &Context,
+    view: FeedsView,
+    input: ListFeedsInput,
+) -> Result<QueryResult<UserFeed>, Response> {
+    match (view, ctx.user_id.to_owned()) {
+        (FeedsView::Following, Some(user_id)) => {
+            ctx.query(ListFollowingFeedsInput {
+                user_id,
+                first: input.first,
+                after: input.after,
+                last: input.last,
+                before: input.before,
+            })
+            .await
+        }
+        (FeedsView::Scheduled, Some(user_id)) => {
+            ctx.query(ListScheduledFeedsInput {
+                user_id,
+                first: input.first,
+                after: input.after,
+                last: input.last,
+                before: input.before,
+            })
+            .await
+        }
+        _ => {
+            ctx.query(ListFeedsInput {
+                unpinned: view == FeedsView::Global
+                    && input.tag.is_none()
+                    && input.author.is_none(),
+                ..input
+            })
+            .await
+        }
+    }
+}
+
 #[derive(Template)]
 #[template(path = "feeds_list.html")]
 pub struct FeedsListTemplate {
-    ctx: UserContext,
+    ctx: Context,
     feeds: QueryResult<UserFeed>,
     tag: Option<String>,
+    author: Option<Uuid>,
+    view: FeedsView,
+    page_size: u16,
 }
 
 impl FeedsListTemplate {
@@ -115,21 +432,47 @@ impl FeedsListTemplate {
     }
 
     fn query_tag(&self) -> String {
-        self.tag
-            .as_ref()
-            .map(|tag| format!("&tag={tag}"))
-            .unwrap_or("".to_owned())
+        query_tag(&self.tag, self.author, self.view, self.page_size)
     }
 }
 
+#[derive(Deserialize)]
+pub struct LoadMoreQuery {
+    #[serde(default)]
+    view: FeedsView,
+}
+
+/// Next page of feed items appended by the last item once revealed, browsers
+/// without htmx are sent to the same page of the index.
 pub async fn load_more(
-    ctx: UserContext,
+    ctx: Context,
+    RawQuery(raw_query): RawQuery,
+    Query(LoadMoreQuery { view }): Query<LoadMoreQuery>,
     Query(input): Query<ListFeedsInput>,
-) -> Result<FeedsListTemplate, Response> {
+) -> Result<Response, Response> {
+    if !ctx.hx.request {
+        let query = raw_query
+            .map(|query| format!("?{query}"))
+            .unwrap_or_default();
+
+        return Ok(Redirect::to(&ctx.create_url(query)).into_response());
+    }
+
+    let input = bounded(input);
     let tag = input.tag.to_owned();
-    let feeds = ctx.query(input).await?;
+    let author = input.author.filter(|_| view == FeedsView::Global);
+    let page_size = input.first.or(input.last).unwrap_or(20);
+    let feeds = list_feeds(&ctx, view, input).await?;
 
-    Ok(FeedsListTemplate { ctx, feeds, tag })
+    Ok(FeedsListTemplate {
+        ctx,
+        feeds,
+        tag,
+        author,
+        view,
+        page_size,
+    }
+    .into_response())
 }
 
 #[derive(Template)]
@@ -137,32 +480,113 @@ pub async fn load_more(
 pub struct CreateFeedFormTemplate {
     ctx: UserContext,
     errors: HashMap<String, Vec<String>>,
+    upload: UploadField,
+    visibilities: Vec<(String, String)>,
+}
+
+/// Values of `VISIBILITIES` with their localized label.
+fn visibility_options(ctx: &Context) -> Vec<(String, String)> {
+    VISIBILITIES
+        .iter()
+        .map(|visibility| {
+            (
+                visibility.to_string(),
+                ctx.t(&format!(
+                    "pages_index-CreateFeedForm_visibility_{visibility}"
+                )),
+            )
+        })
+        .collect()
+}
+
+pub async fn new_feed(ctx: UserContext) -> Modal {
+    let title = ctx.t("pages_index-NewFeedModal_title");
+    let form = CreateFeedFormTemplate {
+        upload: UploadField::new(ctx.context(), "attachment"),
+        visibilities: visibility_options(ctx.context()),
+        ctx: ctx.clone(),
+        errors: Default::default(),
+    };
+
+    Modal::new(ctx.context(), title, form)
 }
 
 #[derive(Deserialize, Validate)]
 pub struct CreateFeedInput {
     #[validate(length(min = 3, max = 100))]
     pub title: String,
+    pub content: Option<String>,
+    pub attachment: Option<String>,
+    pub visibility: Option<String>,
+    /// `datetime-local` value in the timezone of the user.
+    pub publish_at: Option<String>,
 }
 
 pub async fn create_feed(
     ctx: UserContext,
     Form(input): Form<CreateFeedInput>,
 ) -> Result<CreateFeedFormTemplate, Response> {
+    let attachment = match input.attachment.filter(|key| !key.is_empty()) {
+        Some(key) => match stored_attachment(ctx.context(), &key).await {
+            Some(attachment) => Some(attachment),
+            None => return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY)),
+        },
+        None => None,
+    };
+
+    let publish_at = match input.publish_at.filter(|value| !value.is_empty()) {
+        Some(value) => match NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M")
+            .ok()
+            .and_then(|naive| ctx.context().timezone.from_local_datetime(&naive).single())
+        {
+            Some(publish_at) => Some(publish_at.with_timezone(&Utc)),
+            None => return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY)),
+        },
+        None => None,
+    };
+
     let errors = ctx
         .execute(starter_feed::CreateFeedInput {
             title: input.title,
+            content: input.content.filter(|content| !content.trim().is_empty()),
+            attachments: attachment.into_iter().collect(),
+            visibility: input
+                .visibility
+                .unwrap_or_else(|| VISIBILITY_PUBLIC.to_owned()),
+            publish_at,
             user_id: ctx.user_id.to_owned(),
-            request_id: None,
+            request_id: ctx.request_id(),
         })
         .await?;
 
     Ok(CreateFeedFormTemplate {
+        upload: UploadField::new(ctx.context(), "attachment"),
+        visibilities: visibility_options(ctx.context()),
         ctx,
         errors: errors.unwrap_or_default(),
     })
 }
 
+#[derive(Template)]
+#[template(path = "markdown_preview.html")]
+pub struct MarkdownPreviewTemplate {
+    markdown: Markdown,
+}
+
+#[derive(Deserialize)]
+pub struct MarkdownPreviewInput {
+    pub content: String,
+}
+
+pub async fn markdown_preview(
+    _ctx: UserContext,
+    Form(input): Form<MarkdownPreviewInput>,
+) -> MarkdownPreviewTemplate {
+    MarkdownPreviewTemplate {
+        markdown: Markdown::new(&input.content),
+    }
+}
+
 #[derive(Template)]
 #[template(path = "feed_item.html")]
 pub struct FeedItemTemplate {
@@ -182,6 +606,9 @@ pub async fn feed(
     Query(input): Query<starter_feed::GetFeedInput>,
 ) -> Result<FeedItemTemplate, Response> {
     let feed = ctx.query(input).await?;
+    if !feed.is_visible_to(Some(&ctx.user_id)) {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
 
     Ok(FeedItemTemplate {
         ctx,
@@ -200,36 +627,142 @@ pub struct IndexFeedHandler;
 impl RuleHandler for IndexFeedHandler {
     async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
         let id = Feed::from_aggregate_id(&event.aggregate_id);
-        let pikav = ctx.extract::<pikav_client::Client>();
+        let live = ctx.extract::<Live>();
         let config = ctx.extract::<Config>();
+        let cache = ctx.extract::<FragmentCache>();
+        let db = ctx.extract::<PgPool>();
         let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
             return Ok(());
         };
 
-        match event.name.parse()? {
-            FeedEvent::Created => {
-                let data: Created = event.to_data()?;
-                let html = format!(
-                    r#"<div hx-get="{}?id={id}" hx-swap="outerHTML" hx-trigger="load"></div>"#,
-                    config.create_url("/_feed")
-                );
+        let event_name: FeedEvent = event.name.parse()?;
+
+        match event_name {
+            FeedEvent::Created | FeedEvent::Published => {
+                let tags = match event_name {
+                    FeedEvent::Created => {
+                        let data: Created = event.to_data()?;
+
+                        cache.invalidate(POPULAR_TAGS_CACHE_KEY).await;
+                        cache.invalidate(TAG_CLOUD_CACHE_KEY).await;
+
+                        // Scheduled feeds are pushed once published.
+                        if data.visibility != VISIBILITY_PUBLIC || data.publish_at.is_some() {
+                            return Ok(());
+                        }
+
+                        data.tags
+                    }
+                    _ => {
+                        let tags = sqlx::query_scalar::<_, Vec<String>>(
+                            "SELECT tags FROM feed_feeds WHERE id = $1 AND visibility = 'public'",
+                        )
+                        .bind(&id)
+                        .fetch_optional(&db)
+                        .await?;
 
-                for tag in data.tags {
-                    pikav.publish(vec![SimpleEvent {
+                        let Some(tags) = tags else {
+                            return Ok(());
+                        };
+
+                        tags
+                    }
+                };
+
+                let html = FeedItemSkeleton {
+                    url: config.create_url(format!("/_feed?id={id}")),
+                }
+                .render()?;
+
+                for tag in tags {
+                    live.publish(vec![SimpleEvent {
                         user_id: metadata.req_user.to_string(),
-                        topic: "index".into(),
-                        event: format!("created-{tag}"),
+                        topic: format!("tag/{tag}"),
+                        event: "created".into(),
                         data: html.to_owned(),
                     }]);
                 }
 
-                pikav.publish(vec![SimpleEvent {
+                let followers = sqlx::query_scalar::<_, Uuid>(
+                    "SELECT follower_id FROM feed_follows WHERE followed_id = $1",
+                )
+                .bind(metadata.req_user)
+                .fetch_all(&db)
+                .await?;
+
+                let events = followers
+                    .into_iter()
+                    .map(|follower_id| SimpleEvent {
+                        user_id: follower_id.to_string(),
+                        topic: "following".into(),
+                        event: "created".into(),
+                        data: html.to_owned(),
+                    })
+                    .collect::<Vec<_>>();
+
+                if !events.is_empty() {
+                    live.publish(events);
+                }
+
+                live.publish(vec![SimpleEvent {
                     user_id: metadata.req_user.to_string(),
                     topic: "index".into(),
                     event: "created".into(),
                     data: html,
                 }]);
             }
+            FeedEvent::Tagged => {
+                let data: Tagged = event.to_data()?;
+
+                cache.invalidate(POPULAR_TAGS_CACHE_KEY).await;
+                cache.invalidate(TAG_CLOUD_CACHE_KEY).await;
+
+                let listed = sqlx::query_scalar::<_, bool>(
+                    "SELECT visibility = 'public' AND publish_at IS NULL FROM feed_feeds WHERE id = $1",
+                )
+                .bind(&id)
+                .fetch_optional(&db)
+                .await?;
+
+                if listed != Some(true) {
+                    return Ok(());
+                }
+
+                let html = FeedItemSkeleton {
+                    url: config.create_url(format!("/_feed?id={id}")),
+                }
+                .render()?;
+
+                live.publish(vec![SimpleEvent {
+                    user_id: metadata.req_user.to_string(),
+                    topic: format!("tag/{}", data.tag),
+                    event: "created".into(),
+                    data: html,
+                }]);
+            }
+            FeedEvent::Untagged => {
+                cache.invalidate(POPULAR_TAGS_CACHE_KEY).await;
+                cache.invalidate(TAG_CLOUD_CACHE_KEY).await;
+            }
+            FeedEvent::Reacted | FeedEvent::Unreacted => {
+                live.publish(vec![SimpleEvent {
+                    user_id: "*".into(),
+                    topic: "reactions".into(),
+                    event: format!("feed-{id}"),
+                    data: "".into(),
+                }]);
+            }
+            FeedEvent::Attached
+            | FeedEvent::Edited
+            | FeedEvent::Reported
+            | FeedEvent::Hidden
+            | FeedEvent::Restored
+            | FeedEvent::Mentioned
+            | FeedEvent::Archived
+            | FeedEvent::Unarchived
+            | FeedEvent::Trashed
+            | FeedEvent::Untrashed
+            | FeedEvent::Deleted => {}
         };
 
         Ok(())
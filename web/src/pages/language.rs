@@ -0,0 +1,45 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::Redirect,
+};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use serde::Deserialize;
+
+use crate::{
+    context::Context,
+    extract::Form,
+    i18n::{LANGUAGES, LANGUAGE_COOKIE},
+};
+
+#[derive(Deserialize)]
+pub struct SetLanguageInput {
+    pub lang: String,
+}
+
+/// Keeps `lang` in a cookie for a year, with the attributes of
+/// `Config::cookies`, and goes back to the page it was picked from.
+pub async fn set_language(
+    ctx: Context,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Form(input): Form<SetLanguageInput>,
+) -> Response {
+    if !LANGUAGES
+        .iter()
+        .any(|language| language.to_string() == input.lang)
+    {
+        return ctx.error_response(StatusCode::BAD_REQUEST);
+    }
+
+    let referer = ctx.referer_path(&headers);
+
+    let cookie = Cookie::build((LANGUAGE_COOKIE, input.lang))
+        .path("/")
+        .max_age(time::Duration::days(365))
+        .secure(ctx.config.cookies.secure)
+        .same_site(ctx.config.cookies.same_site())
+        .build();
+
+    (jar.add(cookie), Redirect::to(&referer)).into_response()
+}
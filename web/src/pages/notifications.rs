@@ -0,0 +1,260 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::async_trait;
+use evento::{store::Event, Aggregate, ConsumerContext, Query, QueryError, RuleHandler};
+use pikav_client::timada::SimpleEvent;
+use sqlx::PgPool;
+use starter_feed::{
+    CommentEvent, CommentMentioned, Feed, FeedEvent, GetFeedInput, ListEmailRecipientsInput,
+    ListNotificationsInput, ListNotifiedUsersInput, Mentioned, UserNotification, CHANNEL_IN_APP,
+    NOTIFICATION_MENTION, NOTIFICATION_MODERATION,
+};
+use uuid::Uuid;
+
+use crate::{
+    components::Breadcrumbs,
+    config::Config,
+    context::UserContext,
+    flash::Flash,
+    i18n::LANGUAGE_LOADER,
+    live::Live,
+    mailer::{Email, EmailContext, Mailer},
+    push::{PushMessage, Pusher},
+};
+
+#[derive(Template)]
+#[template(path = "notifications.html")]
+pub struct NotificationsTemplate {
+    ctx: UserContext,
+    notifications: Vec<UserNotification>,
+    breadcrumbs: Breadcrumbs,
+}
+
+impl NotificationsTemplate {
+    fn url(&self, notification: &UserNotification) -> String {
+        match notification.comment_id.as_str() {
+            "" => self
+                .ctx
+                .create_url(format!("/feed/{}", notification.feed_id)),
+            comment_id => self.ctx.create_url(format!(
+                "/feed/{}#comment-{comment_id}",
+                notification.feed_id
+            )),
+        }
+    }
+}
+
+pub async fn notifications(ctx: UserContext) -> Result<NotificationsTemplate, Response> {
+    let notifications = ctx
+        .query(ListNotificationsInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?;
+
+    Ok(NotificationsTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "notifications", &[], None),
+        ctx,
+        notifications,
+    })
+}
+
+#[derive(Template)]
+#[template(path = "emails/mention.html")]
+struct MentionEmail<'a> {
+    ctx: &'a EmailContext,
+    subject: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/mention.txt")]
+struct MentionText<'a> {
+    ctx: &'a EmailContext,
+}
+
+/// Toasts and emails the users mentioned by a feed or a comment, both
+/// `mentioned` events carrying their `user_ids`.
+#[derive(Clone)]
+pub struct MentionsNotifier;
+
+#[async_trait]
+impl RuleHandler for MentionsNotifier {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        if event.name != "mentioned" {
+            return Ok(());
+        }
+
+        let live = ctx.extract::<Live>();
+        let query = ctx.extract::<Query>();
+        let data: Mentioned = event.to_data()?;
+        let message = LANGUAGE_LOADER.get("pages_notifications-NotificationsPage_mention_toast");
+
+        let user_ids = match query
+            .execute(&ListNotifiedUsersInput {
+                user_ids: data.user_ids.to_owned(),
+                kind: NOTIFICATION_MENTION.to_owned(),
+                channel: CHANNEL_IN_APP.to_owned(),
+            })
+            .await
+        {
+            Ok(user_ids) => user_ids,
+            Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+            Err(QueryError::NotFound(_)) => return Ok(()),
+        };
+
+        let events = user_ids
+            .into_iter()
+            .map(|user_id| SimpleEvent {
+                user_id: user_id.to_string(),
+                topic: "toasts".into(),
+                event: "toast".into(),
+                data: Flash::info(&message).to_html(),
+            })
+            .collect::<Vec<_>>();
+
+        if !events.is_empty() {
+            live.publish(events);
+        }
+
+        let recipients = match query
+            .execute(&ListEmailRecipientsInput {
+                user_ids: data.user_ids,
+                kind: NOTIFICATION_MENTION.to_owned(),
+            })
+            .await
+        {
+            Ok(recipients) => recipients,
+            Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+            Err(QueryError::NotFound(_)) => return Ok(()),
+        };
+
+        let config = ctx.extract::<Config>();
+        let mailer = ctx.extract::<Mailer>();
+
+        for recipient in recipients {
+            let ctx = EmailContext::new(&config, &recipient.lang);
+            let subject = ctx.t("emails_mention_subject");
+
+            mailer.send(Email::render(
+                recipient.email,
+                &subject,
+                &MentionEmail {
+                    ctx: &ctx,
+                    subject: &subject,
+                },
+                &MentionText { ctx: &ctx },
+            )?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Pushes the notification of `message_id` about the feed `feed_id`, titled
+/// after it, to `user_ids`.
+async fn push_feed(
+    ctx: &ConsumerContext,
+    feed_id: String,
+    user_ids: &[Uuid],
+    kind: &str,
+    message_id: &str,
+    path: Option<&str>,
+) -> anyhow::Result<()> {
+    let feed = match ctx
+        .extract::<Query>()
+        .execute(&GetFeedInput { id: feed_id })
+        .await
+    {
+        Ok(feed) => feed,
+        Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+        Err(QueryError::NotFound(_)) => return Ok(()),
+    };
+
+    let config = ctx.extract::<Config>();
+    let url = config.create_absolute_url(path.map(str::to_owned).unwrap_or_else(|| feed.path()));
+
+    ctx.extract::<Pusher>()
+        .notify(user_ids, kind, |fl_loader| PushMessage {
+            title: fl_loader.get(message_id),
+            body: feed.title.to_owned(),
+            url: url.to_owned(),
+        })
+        .await
+}
+
+async fn reporters(ctx: &ConsumerContext, feed_id: &str) -> anyhow::Result<Vec<Uuid>> {
+    Ok(
+        sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM feed_reports WHERE feed_id = $1")
+            .bind(feed_id)
+            .fetch_all(&ctx.extract::<PgPool>())
+            .await?,
+    )
+}
+
+/// Pushes the mentions of feeds to the users mentioned, and to the reporters
+/// of a feed once a moderator hid or restored it.
+#[derive(Clone)]
+pub struct FeedPushNotifier;
+
+#[async_trait]
+impl RuleHandler for FeedPushNotifier {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let feed_id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        let (user_ids, kind, message_id, path) = match event.name.parse()? {
+            FeedEvent::Mentioned => {
+                let data: Mentioned = event.to_data()?;
+
+                (
+                    data.user_ids,
+                    NOTIFICATION_MENTION,
+                    "pages_notifications-NotificationsPage_mention_toast",
+                    Some("/notifications"),
+                )
+            }
+            FeedEvent::Hidden => (
+                reporters(&ctx, &feed_id).await?,
+                NOTIFICATION_MODERATION,
+                "pages_feed-IndexPage_report_hidden",
+                Some("/"),
+            ),
+            FeedEvent::Restored => (
+                reporters(&ctx, &feed_id).await?,
+                NOTIFICATION_MODERATION,
+                "pages_feed-IndexPage_report_restored",
+                None,
+            ),
+            _ => return Ok(()),
+        };
+
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        push_feed(&ctx, feed_id, &user_ids, kind, message_id, path).await
+    }
+}
+
+/// Pushes the mentions of comments to the users mentioned.
+#[derive(Clone)]
+pub struct CommentPushNotifier;
+
+#[async_trait]
+impl RuleHandler for CommentPushNotifier {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let CommentEvent::Mentioned = event.name.parse()? else {
+            return Ok(());
+        };
+
+        let data: CommentMentioned = event.to_data()?;
+
+        push_feed(
+            &ctx,
+            data.feed_id,
+            &data.user_ids,
+            NOTIFICATION_MENTION,
+            "pages_notifications-NotificationsPage_mention_toast",
+            Some("/notifications"),
+        )
+        .await
+    }
+}
@@ -0,0 +1,126 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::http::{header, StatusCode};
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+use starter_feed::{GetFeedInput, UserFeed};
+use std::time::Duration;
+use tracing::error;
+
+use crate::{context::Context, extract::Path};
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const TITLE_LINE_CHARS: usize = 32;
+const TITLE_MAX_LINES: usize = 3;
+const CACHE_TTL: Duration = Duration::from_secs(86400);
+
+static FONTS: Lazy<usvg::fontdb::Database> = Lazy::new(|| {
+    let mut fonts = usvg::fontdb::Database::new();
+    fonts.load_system_fonts();
+    fonts
+});
+
+#[derive(Template)]
+#[template(path = "og/feed.svg")]
+pub struct FeedCardTemplate {
+    width: u32,
+    height: u32,
+    title_lines: Vec<String>,
+    author: String,
+    date: String,
+}
+
+fn wrap_title(title: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for word in title.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + word.chars().count() < TITLE_LINE_CHARS => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+
+    if lines.len() > TITLE_MAX_LINES {
+        lines.truncate(TITLE_MAX_LINES);
+        lines[TITLE_MAX_LINES - 1].push('…');
+    }
+
+    lines
+}
+
+fn render_png(svg: &str) -> anyhow::Result<Vec<u8>> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default(), &FONTS)?;
+    let mut pixmap = tiny_skia::Pixmap::new(WIDTH, HEIGHT)
+        .ok_or_else(|| anyhow::anyhow!("invalid og image size"))?;
+
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    Ok(pixmap.encode_png()?)
+}
+
+fn card(ctx: &Context, feed: &UserFeed) -> FeedCardTemplate {
+    FeedCardTemplate {
+        width: WIDTH,
+        height: HEIGHT,
+        title_lines: wrap_title(&feed.title),
+        author: feed.author.to_owned(),
+        date: ctx.format_localized(&feed.created_at, "%e %B %Y"),
+    }
+}
+
+/// Social card of a feed as png, served from `/og/{id}.png` and referenced by
+/// the feed page meta.
+pub async fn feed_image(ctx: Context, Path((file,)): Path<(String,)>) -> Response {
+    let Some(id) = file.strip_suffix(".png") else {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    };
+
+    // Checked before the cache, so that the card of a feed is gone as soon
+    // as the feed is hidden or made private.
+    let feed = match ctx.query(GetFeedInput { id: id.to_owned() }).await {
+        Ok(feed) if !feed.hidden && feed.is_visible_to(None) => feed,
+        Ok(_) => return ctx.error_response(StatusCode::NOT_FOUND),
+        Err(response) => return response,
+    };
+
+    let key = format!("og-{id}");
+    let lang = ctx.user_language();
+
+    let png = match ctx.images.get(&key, &lang).await {
+        Some(png) => png,
+        None => {
+            let png = match card(&ctx, &feed).render() {
+                Ok(svg) => tokio::task::spawn_blocking(move || render_png(&svg))
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|png| png),
+                Err(err) => Err(err.into()),
+            };
+
+            match png {
+                Ok(png) => {
+                    ctx.images.set(&key, &lang, png.to_owned(), CACHE_TTL).await;
+                    png
+                }
+                Err(err) => {
+                    error!("{err}");
+
+                    return ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        png,
+    )
+        .into_response()
+}
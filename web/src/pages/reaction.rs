@@ -0,0 +1,86 @@
+use askama_axum::{IntoResponse, Response};
+use axum::http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use starter_feed::{ListFeedReactionsInput, ReactFeedInput};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{
+    components::ReactionPicker,
+    context::{Context, UserContext},
+    extract::{Form, Query},
+};
+
+#[derive(Deserialize)]
+pub struct ReactionsQuery {
+    pub feed_id: String,
+}
+
+pub async fn reactions(
+    ctx: Context,
+    Query(input): Query<ReactionsQuery>,
+) -> Result<ReactionPicker, Response> {
+    let reactions = ctx
+        .query(ListFeedReactionsInput {
+            feed_id: input.feed_id.to_owned(),
+            user_id: ctx
+                .user_id
+                .as_deref()
+                .and_then(|user_id| Uuid::from_str(user_id).ok()),
+        })
+        .await?;
+
+    Ok(ReactionPicker::new(&ctx, input.feed_id, reactions))
+}
+
+#[derive(Deserialize)]
+pub struct ReactInput {
+    pub feed_id: String,
+    pub reaction: String,
+}
+
+/// Htmx requests get the picker back with the reaction already toggled, the
+/// real counts being refreshed over pikav once the reaction is projected.
+pub async fn react(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Form(input): Form<ReactInput>,
+) -> Result<Response, Response> {
+    let reactions = match ctx.context().hx.request {
+        true => Some(
+            ctx.query(ListFeedReactionsInput {
+                feed_id: input.feed_id.to_owned(),
+                user_id: Uuid::from_str(&ctx.user_id).ok(),
+            })
+            .await?,
+        ),
+        false => None,
+    };
+
+    if ctx
+        .execute(ReactFeedInput {
+            feed_id: input.feed_id.to_owned(),
+            reaction: input.reaction.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let Some(mut reactions) = reactions else {
+        return Ok(ctx.submitted(&headers));
+    };
+
+    if let Some(count) = reactions
+        .iter_mut()
+        .find(|count| count.reaction == input.reaction)
+    {
+        count.total_count += if count.reacted { -1 } else { 1 };
+        count.reacted = !count.reacted;
+    }
+
+    Ok(ReactionPicker::new(ctx.context(), input.feed_id, reactions).into_response())
+}
@@ -0,0 +1,212 @@
+use askama_axum::{IntoResponse, Response};
+use axum::http::{header, StatusCode};
+use hmac::{Hmac, Mac};
+use image::{imageops::FilterType, DynamicImage, ImageOutputFormat};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::{io::Cursor, time::Duration};
+use tracing::{error, warn};
+
+use crate::{
+    context::Context,
+    extract::{Path, Query},
+};
+
+/// Widest image served, wider ones being asked for the original.
+const MAX_WIDTH: u32 = 2048;
+
+/// Bytes of the HMAC kept in the `s` parameter.
+const SIGNATURE_LEN: usize = 16;
+
+const CACHE_TTL: Duration = Duration::from_secs(86400);
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Formats of `/img/:key`, by their `format` name.
+const FORMATS: [(&str, &str); 3] = [
+    ("webp", "image/webp"),
+    ("png", "image/png"),
+    ("jpeg", "image/jpeg"),
+];
+
+fn mac(secret: &str, key: &str, width: u32, format: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes keys of any size");
+
+    mac.update(format!("{key}:{width}:{format}").as_bytes());
+    mac
+}
+
+/// Hex encoded signature of the parameters of `/img/:key`.
+fn sign_image(secret: &str, key: &str, width: u32, format: &str) -> String {
+    mac(secret, key, width, format).finalize().into_bytes()[..SIGNATURE_LEN]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Path of the attachment `key` scaled down to `width`, kept in the format
+/// it was uploaded with.
+pub fn resized_image_path(secret: &str, key: &str, width: u32) -> String {
+    let signature = sign_image(secret, key, width, default_format(key));
+
+    format!("/img/{key}?w={width}&s={signature}")
+}
+
+fn verify_image(secret: &str, key: &str, width: u32, format: &str, signature: &str) -> bool {
+    let signature = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(signature.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<_>>>();
+
+    signature.is_some_and(|signature| {
+        signature.len() == SIGNATURE_LEN
+            && mac(secret, key, width, format)
+                .verify_truncated_left(&signature)
+                .is_ok()
+    })
+}
+
+/// Format of the original kept when none is asked for, gifs being served
+/// still as png.
+fn default_format(key: &str) -> &'static str {
+    match key.rsplit_once('.') {
+        Some((_, "jpg")) => "jpeg",
+        _ => "png",
+    }
+}
+
+/// Scales the image down to `width`, never up, then encodes it as `format`.
+fn resize(data: &[u8], width: u32, format: &str) -> anyhow::Result<Vec<u8>> {
+    let mut image = image::load_from_memory(data)?;
+
+    if image.width() > width {
+        image = image.resize(width, u32::MAX, FilterType::Lanczos3);
+    }
+
+    let output = match format {
+        "webp" => ImageOutputFormat::WebP,
+        "jpeg" => {
+            // Jpeg has no alpha channel.
+            image = DynamicImage::ImageRgb8(image.to_rgb8());
+
+            ImageOutputFormat::Jpeg(JPEG_QUALITY)
+        }
+        _ => ImageOutputFormat::Png,
+    };
+
+    let mut bytes = Cursor::new(vec![]);
+    image.write_to(&mut bytes, output)?;
+
+    Ok(bytes.into_inner())
+}
+
+#[derive(Deserialize)]
+pub struct ImageQuery {
+    pub w: u32,
+    pub format: Option<String>,
+    /// Signature of the key and the other parameters, from `sign_image`.
+    pub s: String,
+}
+
+/// Attachment `key` scaled down to `w` pixels wide and encoded as `format`,
+/// from urls signed by `Context::image_url` so that nobody can have the
+/// server render any size. Renditions are kept in the storage under
+/// `resized/` and in the image cache.
+pub async fn resized_image(
+    ctx: Context,
+    Path((key,)): Path<(String,)>,
+    Query(input): Query<ImageQuery>,
+) -> Response {
+    let Some(secret) = ctx.config.upload.image_secret.as_deref() else {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    };
+
+    let format = input.format.as_deref().unwrap_or(default_format(&key));
+
+    let Some((_, content_type)) = FORMATS.iter().find(|(name, _)| *name == format) else {
+        return ctx.error_response(StatusCode::BAD_REQUEST);
+    };
+
+    if input.w == 0 || input.w > MAX_WIDTH {
+        return ctx.error_response(StatusCode::BAD_REQUEST);
+    }
+
+    // An encoded slash would reach the folders, such as the private exports.
+    if key.contains('/') || !verify_image(secret, &key, input.w, format, &input.s) {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    }
+
+    let cache_key = format!("img-{key}-{}.{format}", input.w);
+    let storage_key = format!("resized/{key}/{}.{format}", input.w);
+    let storage = ctx.storage();
+
+    let bytes = match ctx.images.get(&cache_key, "").await {
+        Some(bytes) => bytes,
+        None => {
+            let stored = match storage.get(&storage_key).await {
+                Ok(stored) => stored,
+                Err(err) => {
+                    warn!("{err}");
+
+                    None
+                }
+            };
+
+            let bytes = match stored {
+                Some(bytes) => bytes,
+                None => {
+                    let data = match storage.get(&key).await {
+                        Ok(Some(data)) => data,
+                        Ok(None) => return ctx.error_response(StatusCode::NOT_FOUND),
+                        Err(err) => {
+                            warn!("{err}");
+
+                            return ctx.error_response(StatusCode::NOT_FOUND);
+                        }
+                    };
+
+                    let width = input.w;
+                    let target = format.to_owned();
+                    let resized =
+                        tokio::task::spawn_blocking(move || resize(&data, width, &target))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|bytes| bytes);
+
+                    let bytes = match resized {
+                        Ok(bytes) => bytes,
+                        Err(err) => {
+                            error!("{err}");
+
+                            return ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY);
+                        }
+                    };
+
+                    if let Err(err) = storage.put(&storage_key, &bytes).await {
+                        warn!("{err}");
+                    }
+
+                    bytes
+                }
+            };
+
+            ctx.images
+                .set(&cache_key, "", bytes.to_owned(), CACHE_TTL)
+                .await;
+
+            bytes
+        }
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, *content_type),
+            // Keys are unique and the signature ties the parameters to them.
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        bytes,
+    )
+        .into_response()
+}
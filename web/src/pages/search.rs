@@ -0,0 +1,97 @@
+use askama::Template;
+use askama_axum::Response;
+use axum::http::StatusCode;
+use i18n_embed_fl::fl;
+use pulldown_cmark::escape::escape_html;
+use starter_feed::{FeedSearchResult, SearchFeedsInput, SNIPPET_START, SNIPPET_STOP};
+use tracing::error;
+
+use crate::{context::Context, extract::Query};
+
+pub struct SearchResult {
+    feed: FeedSearchResult,
+    url: String,
+    snippet: String,
+}
+
+#[derive(Template)]
+#[template(path = "search.html")]
+pub struct SearchTemplate {
+    ctx: Context,
+    input: SearchFeedsInput,
+    results: Vec<SearchResult>,
+    previous_url: Option<String>,
+    next_url: Option<String>,
+    empty: String,
+}
+
+impl SearchTemplate {
+    fn searched(&self) -> bool {
+        !self.input.q.trim().is_empty()
+    }
+}
+
+/// Escapes the snippet then highlights the words marked by the search query.
+fn highlight(snippet: &str) -> String {
+    let mut html = String::new();
+    let _ = escape_html(&mut html, snippet);
+
+    html.replace(SNIPPET_START, "<mark>")
+        .replace(SNIPPET_STOP, "</mark>")
+}
+
+pub async fn search(
+    ctx: Context,
+    Query(input): Query<SearchFeedsInput>,
+) -> Result<SearchTemplate, Response> {
+    let input = SearchFeedsInput {
+        q: input.q,
+        tag: input.tag.filter(|tag| !tag.is_empty()),
+        author: input.author.filter(|author| !author.is_empty()),
+        page: input.page,
+    };
+
+    let (feeds, has_next_page) = ctx.search.search(&input).await.map_err(|err| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    let page = input.page();
+    let page_url = |page: u16| {
+        let query = serde_urlencoded::to_string([
+            ("q", Some(input.q.as_str())),
+            ("tag", input.tag.as_deref()),
+            ("author", input.author.as_deref()),
+            ("page", Some(page.to_string().as_str())),
+        ])
+        .unwrap_or_default();
+
+        ctx.create_url(format!("/search?{query}"))
+    };
+
+    let previous_url = (page > 1).then(|| page_url(page - 1));
+    let next_url = has_next_page.then(|| page_url(page + 1));
+
+    let results = feeds
+        .into_iter()
+        .map(|feed| SearchResult {
+            url: ctx.create_url(format!("/feed/{}", feed.id)),
+            snippet: highlight(&feed.snippet),
+            feed,
+        })
+        .collect();
+
+    Ok(SearchTemplate {
+        empty: fl!(
+            ctx.fl_loader(),
+            "pages_search-SearchPage_empty",
+            q = input.q.trim()
+        ),
+        ctx,
+        input,
+        results,
+        previous_url,
+        next_url,
+    })
+}
@@ -0,0 +1,47 @@
+mod billing;
+mod export;
+mod feeds;
+mod notifications;
+mod webhooks;
+
+use billing::*;
+use export::*;
+pub use export::{ExportJob, ExportsGenerator, JOB_EXPORT};
+use feeds::*;
+pub use notifications::VerificationMailer;
+use notifications::*;
+use webhooks::*;
+
+use crate::routes::{on, Routes};
+
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/billing", on!(User get(billing)))
+        .route("/billing/checkout", on!(User post(checkout)))
+        .route("/export", on!(User get(exports), User post(request_export)))
+        .route("/export/:id", on!(User get(download_export)))
+        .route("/feeds", on!(User get(own_feeds)))
+        .route("/feeds/archive", on!(User post(archive_feeds)))
+        .route("/feeds/unarchive", on!(User post(unarchive_feeds)))
+        .route("/feeds/delete", on!(User post(trash_feeds)))
+        .route("/feeds/undo", on!(User post(undo_batch)))
+        .route(
+            "/notifications",
+            on!(
+                User get(notification_preferences),
+                User post(update_notification_preferences)
+            ),
+        )
+        .route("/notifications/email", on!(User post(set_email)))
+        .route("/notifications/push", on!(User post(subscribe_push)))
+        .route(
+            "/notifications/push/unsubscribe",
+            on!(User post(unsubscribe_push)),
+        )
+        .route("/notifications/verify", on!(User get(verify_email)))
+        .route(
+            "/webhooks",
+            on!(User get(webhooks), User post(register_webhook)),
+        )
+        .route("/webhooks/:id/delete", on!(User post(remove_webhook)))
+}
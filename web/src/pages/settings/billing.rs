@@ -0,0 +1,69 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use starter_feed::{GetSubscriptionInput, UserSubscription};
+use tracing::error;
+
+use crate::{components::Breadcrumbs, context::UserContext};
+
+#[derive(Template)]
+#[template(path = "settings/billing.html")]
+pub struct BillingTemplate {
+    ctx: UserContext,
+    subscription: Option<UserSubscription>,
+    breadcrumbs: Breadcrumbs,
+}
+
+impl BillingTemplate {
+    fn status_label(&self, status: &str) -> String {
+        self.ctx
+            .t(&format!("pages_settings-BillingPage_status_{status}"))
+    }
+
+    fn can_subscribe(&self) -> bool {
+        self.ctx.context().stripe.is_enabled()
+            && !self
+                .subscription
+                .as_ref()
+                .is_some_and(|subscription| subscription.is_active())
+    }
+}
+
+/// Subscription of the user, as last told by Stripe.
+pub async fn billing(ctx: UserContext) -> Result<BillingTemplate, Response> {
+    let subscription = ctx
+        .query(GetSubscriptionInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?;
+
+    Ok(BillingTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "settings-billing", &[], None),
+        ctx,
+        subscription,
+    })
+}
+
+/// Sends the user to a Stripe Checkout page for the plan, back to
+/// `/settings/billing` once done. The subscription only shows once its
+/// webhook event is received.
+pub async fn checkout(ctx: UserContext) -> Result<Response, Response> {
+    let back_url = ctx.create_absolute_url("/settings/billing");
+
+    let url = ctx
+        .context()
+        .stripe
+        .create_checkout_session(&ctx.user_id, &back_url, &back_url)
+        .await
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    let Some(url) = url else {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    };
+
+    Ok(Redirect::to(&url).into_response())
+}
@@ -0,0 +1,406 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    async_trait,
+    http::{header, StatusCode},
+    response::Redirect,
+};
+use evento::{store::Event, Aggregate, ConsumerContext, Query, QueryError, RuleHandler};
+use i18n_embed_fl::fl;
+use pikav_client::timada::SimpleEvent;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use starter_feed::{
+    Export, ExportData, ExportDataInput, ExportEvent, ExportRequested, FeedMetadata,
+    GetExportInput, JobHandler, Jobs, ListEmailRecipientsInput, ListExportsInput,
+    ListNotifiedUsersInput, RequestExportInput, UserExport, CHANNEL_IN_APP, EXPORT_FORMATS,
+    EXPORT_FORMAT_CSV, NOTIFICATION_EXPORT,
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tracing::warn;
+use ulid::Ulid;
+use uuid::Uuid;
+
+use crate::{
+    components::Breadcrumbs,
+    config::Config,
+    context::UserContext,
+    extract::{Form, Path},
+    flash::Flash,
+    i18n::LANGUAGE_LOADER,
+    live::Live,
+    mailer::{Email, EmailContext, Mailer},
+    push::{PushMessage, Pusher},
+    storage::Storage,
+};
+
+/// Kind of the jobs generating the archive of an export.
+pub const JOB_EXPORT: &str = "export";
+
+/// Lifetime of the signed urls downloads are redirected to, when the
+/// storage signs them.
+const PRESIGNED_TTL: Duration = Duration::from_secs(300);
+
+/// Archives are kept in the `exports/` folder, `/uploads/:key` serving the
+/// files outside of folders only.
+fn storage_key(id: &str, format: &str) -> String {
+    format!("exports/{id}.{format}")
+}
+
+#[derive(Template)]
+#[template(path = "settings/export.html")]
+pub struct ExportsTemplate {
+    ctx: UserContext,
+    /// Exports of the user, with whether their archive is ready.
+    exports: Vec<(UserExport, bool)>,
+    formats: Vec<(String, String)>,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+pub async fn exports(ctx: UserContext) -> Result<ExportsTemplate, Response> {
+    let storage = ctx.storage();
+    let mut exports = vec![];
+
+    for export in ctx
+        .query(ListExportsInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?
+    {
+        let ready = storage
+            .exists(&storage_key(&export.id, &export.format))
+            .await
+            .unwrap_or_default();
+
+        exports.push((export, ready));
+    }
+
+    Ok(ExportsTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "settings-export", &[], None),
+        ctx,
+        exports,
+        formats: EXPORT_FORMATS
+            .iter()
+            .map(|format| (format.to_string(), format.to_uppercase()))
+            .collect(),
+        errors: Default::default(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ExportInput {
+    pub format: String,
+}
+
+pub async fn request_export(
+    ctx: UserContext,
+    Form(input): Form<ExportInput>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(RequestExportInput {
+            id: Ulid::new().to_string(),
+            format: input.format,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::info(fl!(ctx.fl_loader(), "pages_settings-ExportPage_requested"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/settings/export"))).into_response())
+}
+
+pub async fn download_export(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    let Some(export) = ctx
+        .query(GetExportInput {
+            id,
+            user_id: ctx.user_id.to_owned(),
+        })
+        .await?
+    else {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    };
+
+    let storage = ctx.storage();
+    let key = storage_key(&export.id, &export.format);
+    let file_name = format!("starter-export-{}.{}", export.id, export.format);
+
+    match storage
+        .presigned_url(&key, PRESIGNED_TTL, Some(&file_name))
+        .await
+    {
+        Ok(Some(url)) => return Ok(Redirect::to(&url).into_response()),
+        Ok(None) => {}
+        Err(err) => {
+            warn!("{err}");
+
+            return Err(ctx.error_response(StatusCode::NOT_FOUND));
+        }
+    }
+
+    let data = match storage.get(&key).await {
+        Ok(Some(data)) => data,
+        Ok(None) => return Err(ctx.error_response(StatusCode::NOT_FOUND)),
+        Err(err) => {
+            warn!("{err}");
+
+            return Err(ctx.error_response(StatusCode::NOT_FOUND));
+        }
+    };
+
+    let content_type = match export.format.as_str() {
+        EXPORT_FORMAT_CSV => "text/csv; charset=utf-8",
+        _ => "application/json",
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+            (header::CACHE_CONTROL, "private, no-store".to_owned()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_owned()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Quotes the field when needed, prefixing the ones a spreadsheet would run
+/// as a formula.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+
+    value
+}
+
+/// One row per feed, comment and reaction, told apart by their `type`.
+fn to_csv(data: &ExportData) -> String {
+    let mut rows = vec![[
+        "type",
+        "id",
+        "feed_id",
+        "title",
+        "content",
+        "reaction",
+        "created_at",
+    ]
+    .map(str::to_owned)];
+
+    for feed in data.feeds.iter() {
+        rows.push([
+            "feed".to_owned(),
+            feed.id.to_owned(),
+            feed.id.to_owned(),
+            feed.title.to_owned(),
+            feed.content.to_owned(),
+            String::new(),
+            feed.created_at.to_rfc3339(),
+        ]);
+    }
+
+    for comment in data.comments.iter() {
+        rows.push([
+            "comment".to_owned(),
+            comment.id.to_owned(),
+            comment.feed_id.to_owned(),
+            String::new(),
+            comment.content.to_owned(),
+            String::new(),
+            comment.created_at.to_rfc3339(),
+        ]);
+    }
+
+    for reaction in data.reactions.iter() {
+        rows.push([
+            "reaction".to_owned(),
+            String::new(),
+            reaction.feed_id.to_owned(),
+            String::new(),
+            String::new(),
+            reaction.reaction.to_owned(),
+            reaction.created_at.to_rfc3339(),
+        ]);
+    }
+
+    rows.into_iter()
+        .map(|row| row.map(|field| csv_field(&field)).join(",") + "\r\n")
+        .collect()
+}
+
+#[derive(Template)]
+#[template(path = "emails/export_ready.html")]
+struct ExportReadyEmail<'a> {
+    ctx: &'a EmailContext,
+    subject: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/export_ready.txt")]
+struct ExportReadyText<'a> {
+    ctx: &'a EmailContext,
+}
+
+/// Queues the generation of the archive of a requested export, see
+/// `ExportJob`.
+#[derive(Clone)]
+pub struct ExportsGenerator;
+
+#[async_trait]
+impl RuleHandler for ExportsGenerator {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let ExportEvent::Requested = event.name.parse()?;
+        let Some(metadata) = event.to_metadata::<FeedMetadata>()? else {
+            return Ok(());
+        };
+
+        Jobs::new(ctx.extract::<PgPool>())
+            .enqueue(
+                JOB_EXPORT,
+                &ExportJobPayload {
+                    id: Export::from_aggregate_id(&event.aggregate_id),
+                    format: event.to_data::<ExportRequested>()?.format,
+                    user_id: metadata.req_user,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportJobPayload {
+    pub id: String,
+    pub format: String,
+    pub user_id: Uuid,
+}
+
+/// Generates the archive of an export then toasts, pushes and emails its
+/// user unless muted, the exports page linking to it once stored.
+pub struct ExportJob {
+    pub config: Config,
+    pub query: Query,
+    pub storage: Arc<dyn Storage>,
+    pub mailer: Mailer,
+    pub pusher: Pusher,
+    pub live: Live,
+}
+
+#[async_trait]
+impl JobHandler for ExportJob {
+    async fn run(&self, payload: serde_json::Value) -> anyhow::Result<()> {
+        let ExportJobPayload {
+            id,
+            format,
+            user_id,
+        } = serde_json::from_value(payload)?;
+        let (config, query) = (&self.config, &self.query);
+
+        let data = match query
+            .execute(&ExportDataInput {
+                user_id: user_id.to_string(),
+            })
+            .await
+        {
+            Ok(data) => data,
+            Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+            Err(QueryError::NotFound(_)) => return Ok(()),
+        };
+
+        let archive = match format.as_str() {
+            EXPORT_FORMAT_CSV => to_csv(&data).into_bytes(),
+            _ => serde_json::to_vec_pretty(&data)?,
+        };
+
+        self.storage
+            .put(&storage_key(&id, &format), &archive)
+            .await?;
+
+        let notified = match query
+            .execute(&ListNotifiedUsersInput {
+                user_ids: vec![user_id],
+                kind: NOTIFICATION_EXPORT.to_owned(),
+                channel: CHANNEL_IN_APP.to_owned(),
+            })
+            .await
+        {
+            Ok(user_ids) => !user_ids.is_empty(),
+            Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+            Err(QueryError::NotFound(_)) => false,
+        };
+
+        if notified {
+            let message = LANGUAGE_LOADER.get("pages_settings-ExportPage_ready_toast");
+
+            self.live.publish(vec![SimpleEvent {
+                user_id: user_id.to_string(),
+                topic: "toasts".into(),
+                event: "toast".into(),
+                data: Flash::success(message).to_html(),
+            }]);
+        }
+
+        let url = config.create_absolute_url("/settings/export");
+
+        // The archive is stored, failing to push must not generate it again.
+        if let Err(err) = self
+            .pusher
+            .notify(&[user_id], NOTIFICATION_EXPORT, |fl_loader| PushMessage {
+                title: fl_loader.get("pages_settings-ExportPage_ready_toast"),
+                body: String::new(),
+                url: url.to_owned(),
+            })
+            .await
+        {
+            warn!("push of export {id} failed: {err}");
+        }
+
+        let recipients = match query
+            .execute(&ListEmailRecipientsInput {
+                user_ids: vec![user_id],
+                kind: NOTIFICATION_EXPORT.to_owned(),
+            })
+            .await
+        {
+            Ok(recipients) => recipients,
+            Err(QueryError::Server(err)) => anyhow::bail!("{err}"),
+            Err(QueryError::NotFound(_)) => return Ok(()),
+        };
+
+        for recipient in recipients {
+            let ctx = EmailContext::new(config, &recipient.lang);
+            let subject = ctx.t("emails_export_ready_subject");
+
+            self.mailer.send(Email::render(
+                recipient.email,
+                &subject,
+                &ExportReadyEmail {
+                    ctx: &ctx,
+                    subject: &subject,
+                },
+                &ExportReadyText { ctx: &ctx },
+            )?);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use evento_query::QueryResult;
+use i18n_embed_fl::fl;
+use starter_feed::{
+    ArchiveFeedsInput, ListFeedsInput, ListOwnFeedsInput, TrashFeedsInput, UnarchiveFeedsInput,
+    UndoBatchInput, UserFeed, UNDO_WINDOW_SECS,
+};
+use uuid::Uuid;
+
+use crate::{
+    components::{Breadcrumbs, Paginator},
+    context::UserContext,
+    extract::{Form, Query},
+    flash::Flash,
+};
+
+/// Bulk operation the user can still undo, with the feeds it applied to.
+pub struct Undo {
+    batch_id: String,
+    ids: Vec<String>,
+    message: String,
+}
+
+#[derive(Template)]
+#[template(path = "settings/feeds.html")]
+pub struct OwnFeedsTemplate {
+    ctx: UserContext,
+    feeds: QueryResult<UserFeed>,
+    breadcrumbs: Breadcrumbs,
+    paginator: Paginator,
+    undo: Option<Undo>,
+}
+
+async fn template(
+    ctx: UserContext,
+    input: ListFeedsInput,
+    undo: Option<Undo>,
+) -> Result<OwnFeedsTemplate, Response> {
+    let page_size = input.first.or(input.last).unwrap_or(20);
+
+    let feeds = ctx
+        .query(ListOwnFeedsInput {
+            user_id: ctx.user_id.to_owned(),
+            first: input.first,
+            after: input.after,
+            last: input.last,
+            before: input.before,
+        })
+        .await?;
+
+    Ok(OwnFeedsTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "settings-feeds", &[], None),
+        paginator: Paginator::new(
+            ctx.context(),
+            &feeds.page_info,
+            "/settings/feeds",
+            page_size,
+            "",
+        ),
+        ctx,
+        feeds,
+        undo,
+    })
+}
+
+/// Feeds of the user with checkboxes to archive or delete several at once.
+pub async fn own_feeds(
+    ctx: UserContext,
+    Query(input): Query<ListFeedsInput>,
+) -> Result<OwnFeedsTemplate, Response> {
+    template(ctx, input, None).await
+}
+
+/// Values of the repeated `name` field, the form extractor not collecting
+/// them into a `Vec` itself.
+fn form_values(fields: &[(String, String)], name: &str) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|(key, _)| key == name)
+        .map(|(_, value)| value.to_owned())
+        .collect()
+}
+
+/// Renders the list right away with the undo form of the batch, its feeds
+/// being marked by the projection only once it caught up.
+async fn bulk_response(ctx: UserContext, undo: Undo) -> Result<Response, Response> {
+    let input = ListFeedsInput {
+        first: None,
+        after: None,
+        last: None,
+        before: None,
+        tag: None,
+        author: None,
+        unpinned: false,
+    };
+
+    Ok(template(ctx, input, Some(undo)).await?.into_response())
+}
+
+pub async fn archive_feeds(
+    ctx: UserContext,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Result<Response, Response> {
+    let batch_id = Uuid::new_v4().to_string();
+    let ids = form_values(&fields, "ids");
+
+    if ctx
+        .execute(ArchiveFeedsInput {
+            ids: ids.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: batch_id.to_owned(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let message = fl!(
+        ctx.fl_loader(),
+        "pages_settings-FeedsPage_archived",
+        count = ids.len(),
+        seconds = UNDO_WINDOW_SECS
+    );
+
+    bulk_response(
+        ctx,
+        Undo {
+            batch_id,
+            ids,
+            message,
+        },
+    )
+    .await
+}
+
+pub async fn trash_feeds(
+    ctx: UserContext,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Result<Response, Response> {
+    let batch_id = Uuid::new_v4().to_string();
+    let ids = form_values(&fields, "ids");
+
+    if ctx
+        .execute(TrashFeedsInput {
+            ids: ids.to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: batch_id.to_owned(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let message = fl!(
+        ctx.fl_loader(),
+        "pages_settings-FeedsPage_trashed",
+        count = ids.len(),
+        seconds = UNDO_WINDOW_SECS
+    );
+
+    bulk_response(
+        ctx,
+        Undo {
+            batch_id,
+            ids,
+            message,
+        },
+    )
+    .await
+}
+
+pub async fn unarchive_feeds(
+    ctx: UserContext,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(UnarchiveFeedsInput {
+            ids: form_values(&fields, "ids"),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    Ok(Redirect::to(&ctx.create_url("/settings/feeds")).into_response())
+}
+
+pub async fn undo_batch(
+    ctx: UserContext,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Result<Response, Response> {
+    let Some(batch_id) = form_values(&fields, "batch_id").pop() else {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    };
+
+    let errors = ctx
+        .execute(UndoBatchInput {
+            ids: form_values(&fields, "ids"),
+            batch_id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?;
+
+    let flash = match errors {
+        Some(_) => Flash::error(fl!(
+            ctx.fl_loader(),
+            "pages_settings-FeedsPage_undo_expired"
+        )),
+        None => Flash::success(fl!(ctx.fl_loader(), "pages_settings-FeedsPage_undone")),
+    };
+
+    Ok((flash, Redirect::to(&ctx.create_url("/settings/feeds"))).into_response())
+}
@@ -0,0 +1,343 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{async_trait, http::StatusCode, response::Redirect};
+use evento::{store::Event, ConsumerContext, RuleHandler};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use starter_feed::{
+    EmailSet, GetNotificationPreferencesInput, GetUserEmailInput, MutedNotification,
+    PreferencesEvent, PushSubscription, SetEmailInput, UpdateNotificationPreferencesInput,
+    UserEmail, VerifyEmailInput, NOTIFICATION_CHANNELS, NOTIFICATION_KINDS,
+};
+use std::collections::HashMap;
+use tracing::error;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    api::Json,
+    components::Breadcrumbs,
+    config::Config,
+    context::UserContext,
+    extract::{Form, Query},
+    flash::Flash,
+    mailer::{Email, EmailContext, Mailer},
+};
+
+/// Notification kind with, per channel, the value of its checkbox and whether
+/// it is enabled.
+pub struct NotificationRow {
+    label: String,
+    channels: Vec<(String, bool)>,
+}
+
+#[derive(Template)]
+#[template(path = "settings/notifications.html")]
+pub struct NotificationPreferencesTemplate {
+    ctx: UserContext,
+    channels: Vec<String>,
+    rows: Vec<NotificationRow>,
+    /// Address notifications are emailed to, with whether it is verified.
+    email: Option<UserEmail>,
+    /// Value of the email field.
+    email_input: String,
+    /// Application server key of the pushes, `None` when nothing is pushed.
+    push_key: Option<String>,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+async fn template(
+    ctx: UserContext,
+    email_input: Option<String>,
+    errors: HashMap<String, Vec<String>>,
+) -> Result<NotificationPreferencesTemplate, Response> {
+    let (muted, email) = tokio::join!(
+        ctx.query(GetNotificationPreferencesInput {
+            user_id: ctx.user_id.to_owned(),
+        }),
+        ctx.query(GetUserEmailInput {
+            user_id: ctx.user_id.to_owned(),
+        })
+    );
+    let (muted, email) = (muted?, email?);
+
+    let rows = NOTIFICATION_KINDS
+        .iter()
+        .map(|kind| NotificationRow {
+            label: ctx.t(&format!("pages_settings-NotificationsPage_kind_{kind}")),
+            channels: NOTIFICATION_CHANNELS
+                .iter()
+                .map(|channel| {
+                    let enabled = !muted.contains(&MutedNotification {
+                        kind: kind.to_string(),
+                        channel: channel.to_string(),
+                    });
+
+                    (format!("{kind}:{channel}"), enabled)
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(NotificationPreferencesTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "settings-notifications", &[], None),
+        channels: NOTIFICATION_CHANNELS
+            .iter()
+            .map(|channel| {
+                ctx.t(&format!(
+                    "pages_settings-NotificationsPage_channel_{channel}"
+                ))
+            })
+            .collect(),
+        email_input: email_input
+            .or_else(|| email.as_ref().map(|email| email.email.to_owned()))
+            .unwrap_or_default(),
+        email,
+        push_key: ctx.context().pusher.public_key().map(str::to_owned),
+        ctx,
+        rows,
+        errors,
+    })
+}
+
+pub async fn notification_preferences(
+    ctx: UserContext,
+) -> Result<NotificationPreferencesTemplate, Response> {
+    template(ctx, None, Default::default()).await
+}
+
+/// Mutes every notification whose checkbox was left unchecked, browsers not
+/// submitting those.
+pub async fn update_notification_preferences(
+    ctx: UserContext,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Result<Response, Response> {
+    let enabled = fields
+        .iter()
+        .filter(|(key, _)| key == "enabled")
+        .map(|(_, value)| value.as_str())
+        .collect::<Vec<_>>();
+
+    let muted = NOTIFICATION_KINDS
+        .iter()
+        .flat_map(|kind| {
+            NOTIFICATION_CHANNELS
+                .iter()
+                .map(move |channel| MutedNotification {
+                    kind: kind.to_string(),
+                    channel: channel.to_string(),
+                })
+        })
+        .filter(|muted| !enabled.contains(&format!("{}:{}", muted.kind, muted.channel).as_str()))
+        .collect();
+
+    if ctx
+        .execute(UpdateNotificationPreferencesInput {
+            muted,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::success(fl!(
+        ctx.fl_loader(),
+        "pages_settings-NotificationsPage_saved"
+    ));
+
+    Ok((
+        flash,
+        Redirect::to(&ctx.create_url("/settings/notifications")),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct EmailInput {
+    pub email: String,
+}
+
+/// Sets the address notifications are emailed to, `VerificationMailer`
+/// sending it the link to verify it.
+pub async fn set_email(
+    ctx: UserContext,
+    Form(input): Form<EmailInput>,
+) -> Result<Response, Response> {
+    if let Some(errors) = ctx
+        .execute(SetEmailInput {
+            email: input.email.trim().to_owned(),
+            lang: ctx.user_language(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+    {
+        let template = template(ctx, Some(input.email), errors).await?;
+
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, template).into_response());
+    }
+
+    let flash = Flash::info(fl!(
+        ctx.fl_loader(),
+        "pages_settings-NotificationsPage_email_sent"
+    ));
+
+    Ok((
+        flash,
+        Redirect::to(&ctx.create_url("/settings/notifications")),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+/// Target of the link emailed by `VerificationMailer`.
+pub async fn verify_email(
+    ctx: UserContext,
+    Query(input): Query<VerifyEmailQuery>,
+) -> Result<Response, Response> {
+    let flash = match ctx
+        .execute(VerifyEmailInput {
+            token: input.token,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+    {
+        Some(_) => Flash::error(fl!(
+            ctx.fl_loader(),
+            "pages_settings-NotificationsPage_email_invalid_link"
+        )),
+        None => Flash::success(fl!(
+            ctx.fl_loader(),
+            "pages_settings-NotificationsPage_email_verified"
+        )),
+    };
+
+    Ok((
+        flash,
+        Redirect::to(&ctx.create_url("/settings/notifications")),
+    )
+        .into_response())
+}
+
+/// Subscribes the browser to the pushes of the user, posted by the settings
+/// page with the json of its `PushSubscription`.
+pub async fn subscribe_push(
+    ctx: UserContext,
+    Json(subscription): Json<PushSubscription>,
+) -> Result<Response, Response> {
+    let pusher = &ctx.context().pusher;
+
+    if pusher.public_key().is_none() {
+        return Err(ctx.error_response(StatusCode::NOT_FOUND));
+    }
+
+    if subscription.validate().is_err() || !subscription.endpoint.starts_with("https://") {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let user_id =
+        Uuid::parse_str(&ctx.user_id).map_err(|_| ctx.error_response(StatusCode::BAD_REQUEST))?;
+
+    pusher
+        .subscriptions
+        .subscribe(user_id, &subscription, &ctx.user_language())
+        .await
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribePushInput {
+    pub endpoint: String,
+}
+
+pub async fn unsubscribe_push(
+    ctx: UserContext,
+    Json(input): Json<UnsubscribePushInput>,
+) -> Result<Response, Response> {
+    let user_id =
+        Uuid::parse_str(&ctx.user_id).map_err(|_| ctx.error_response(StatusCode::BAD_REQUEST))?;
+
+    ctx.context()
+        .pusher
+        .subscriptions
+        .unsubscribe(user_id, &input.endpoint)
+        .await
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[derive(Template)]
+#[template(path = "emails/verification.html")]
+struct VerificationEmail<'a> {
+    ctx: &'a EmailContext,
+    subject: &'a str,
+    url: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/verification.txt")]
+struct VerificationText<'a> {
+    ctx: &'a EmailContext,
+    url: &'a str,
+}
+
+/// Emails the link verifying an address to it, in the language it was set
+/// in.
+#[derive(Clone)]
+pub struct VerificationMailer;
+
+#[async_trait]
+impl RuleHandler for VerificationMailer {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> anyhow::Result<()> {
+        let PreferencesEvent::EmailSet = event.name.parse()? else {
+            return Ok(());
+        };
+
+        let data: EmailSet = event.to_data()?;
+        let config = ctx.extract::<Config>();
+        let mailer = ctx.extract::<Mailer>();
+
+        let ctx = EmailContext::new(&config, &data.lang);
+        let subject = ctx.t("emails_verification_subject");
+        let url = ctx.create_absolute_url(format!(
+            "/settings/notifications/verify?token={}",
+            data.token
+        ));
+
+        mailer.send(Email::render(
+            data.email,
+            &subject,
+            &VerificationEmail {
+                ctx: &ctx,
+                subject: &subject,
+                url: &url,
+            },
+            &VerificationText {
+                ctx: &ctx,
+                url: &url,
+            },
+        )?);
+
+        Ok(())
+    }
+}
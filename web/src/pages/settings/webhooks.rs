@@ -0,0 +1,114 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, response::Redirect};
+use i18n_embed_fl::fl;
+use serde::Deserialize;
+use starter_feed::{
+    ListWebhookDeliveriesInput, ListWebhooksInput, RegisterWebhookInput, RemoveWebhookInput,
+    UserWebhook, UserWebhookDelivery,
+};
+use std::collections::HashMap;
+use ulid::Ulid;
+
+use crate::{
+    components::Breadcrumbs,
+    context::UserContext,
+    extract::{Form, Path},
+    flash::Flash,
+};
+
+/// Deliveries listed by the log, older ones being left out.
+const DELIVERIES_LIMIT: i64 = 50;
+
+#[derive(Template)]
+#[template(path = "settings/webhooks.html")]
+pub struct WebhooksTemplate {
+    ctx: UserContext,
+    webhooks: Vec<UserWebhook>,
+    deliveries: Vec<UserWebhookDelivery>,
+    url: String,
+    breadcrumbs: Breadcrumbs,
+    errors: HashMap<String, Vec<String>>,
+}
+
+async fn template(
+    ctx: UserContext,
+    url: String,
+    errors: HashMap<String, Vec<String>>,
+) -> Result<WebhooksTemplate, Response> {
+    let (webhooks, deliveries) = tokio::join!(
+        ctx.query(ListWebhooksInput {
+            user_id: ctx.user_id.to_owned(),
+        }),
+        ctx.query(ListWebhookDeliveriesInput {
+            user_id: ctx.user_id.to_owned(),
+            limit: DELIVERIES_LIMIT,
+        })
+    );
+
+    Ok(WebhooksTemplate {
+        breadcrumbs: Breadcrumbs::new(ctx.context(), "settings-webhooks", &[], None),
+        webhooks: webhooks?,
+        deliveries: deliveries?,
+        ctx,
+        url,
+        errors,
+    })
+}
+
+/// Webhooks of the user with the log of their last deliveries.
+pub async fn webhooks(ctx: UserContext) -> Result<WebhooksTemplate, Response> {
+    template(ctx, "".to_owned(), Default::default()).await
+}
+
+#[derive(Deserialize)]
+pub struct WebhookInput {
+    pub url: String,
+}
+
+pub async fn register_webhook(
+    ctx: UserContext,
+    Form(input): Form<WebhookInput>,
+) -> Result<Response, Response> {
+    if let Some(errors) = ctx
+        .execute(RegisterWebhookInput {
+            id: Ulid::new().to_string(),
+            url: input.url.trim().to_owned(),
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+    {
+        let template = template(ctx, input.url, errors).await?;
+
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, template).into_response());
+    }
+
+    let flash = Flash::success(fl!(
+        ctx.fl_loader(),
+        "pages_settings-WebhooksPage_registered"
+    ));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/settings/webhooks"))).into_response())
+}
+
+pub async fn remove_webhook(
+    ctx: UserContext,
+    Path((id,)): Path<(String,)>,
+) -> Result<Response, Response> {
+    if ctx
+        .execute(RemoveWebhookInput {
+            id,
+            user_id: ctx.user_id.to_owned(),
+            request_id: ctx.request_id(),
+        })
+        .await?
+        .is_some()
+    {
+        return Err(ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let flash = Flash::success(fl!(ctx.fl_loader(), "pages_settings-WebhooksPage_removed"));
+
+    Ok((flash, Redirect::to(&ctx.create_url("/settings/webhooks"))).into_response())
+}
@@ -0,0 +1,21 @@
+use axum::{http::HeaderMap, response::Redirect};
+use axum_extra::extract::CookieJar;
+use serde::Deserialize;
+
+use crate::{context::Context, extract::Form, theme::Theme};
+
+#[derive(Deserialize)]
+pub struct SetThemeInput {
+    pub theme: Theme,
+}
+
+pub async fn set_theme(
+    ctx: Context,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Form(input): Form<SetThemeInput>,
+) -> (CookieJar, Redirect) {
+    let referer = ctx.referer_path(&headers);
+
+    (jar.add(input.theme.to_cookie()), Redirect::to(&referer))
+}
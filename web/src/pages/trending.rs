@@ -0,0 +1,44 @@
+use askama::Template;
+use askama_axum::Response;
+use evento_query::{Cursor, CursorType, Edge};
+use starter_feed::{ListTrendingFeedsInput, UserFeed};
+
+use crate::{components::Breadcrumbs, context::Context};
+
+#[derive(Template)]
+#[template(path = "trending.html")]
+pub struct TrendingTemplate {
+    ctx: Context,
+    feeds: Vec<Edge<UserFeed>>,
+    breadcrumbs: Breadcrumbs,
+}
+
+impl TrendingTemplate {
+    /// Only the best ranked feeds are listed, there is no next page.
+    fn end_cursor(&self, _feed: &Edge<UserFeed>) -> Option<CursorType> {
+        None
+    }
+
+    fn query_tag(&self) -> String {
+        "".into()
+    }
+}
+
+/// Feeds with the most recent reactions and comments.
+pub async fn trending(ctx: Context) -> Result<TrendingTemplate, Response> {
+    let feeds = ctx
+        .query(ListTrendingFeedsInput { limit: 20 })
+        .await?
+        .into_iter()
+        .map(|feed| Edge {
+            cursor: feed.to_cursor(),
+            node: feed,
+        })
+        .collect();
+
+    Ok(TrendingTemplate {
+        breadcrumbs: Breadcrumbs::new(&ctx, "trending", &[], None),
+        ctx,
+        feeds,
+    })
+}
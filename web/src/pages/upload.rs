@@ -0,0 +1,256 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::Multipart,
+    http::{header, HeaderMap, StatusCode},
+    response::Redirect,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use i18n_embed_fl::fl;
+use resvg::{tiny_skia, usvg};
+use serde::Deserialize;
+use starter_feed::Attached;
+use std::time::Duration;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    components::ProgressBar,
+    context::{Context, UserContext},
+    extract::{Path, Query},
+};
+
+/// Percent between two progress events, to not flood the pikav topic.
+const PROGRESS_STEP: u8 = 5;
+const THUMBNAIL_WIDTH: u32 = 640;
+const THUMBNAIL_HEIGHT: u32 = 360;
+
+/// Lifetime of the signed urls files are redirected to, when the storage
+/// signs them.
+const PRESIGNED_TTL: Duration = Duration::from_secs(3600);
+
+/// Image formats accepted as attachment, with their extension and magic bytes.
+const IMAGE_TYPES: [(&str, &str, &[u8]); 3] = [
+    ("image/png", "png", b"\x89PNG\r\n\x1a\n"),
+    ("image/jpeg", "jpg", b"\xff\xd8\xff"),
+    ("image/gif", "gif", b"GIF8"),
+];
+
+#[derive(Deserialize)]
+pub struct UploadQuery {
+    pub request_id: String,
+    pub field: String,
+}
+
+#[derive(Template)]
+#[template(path = "upload_result.html")]
+pub struct UploadResultTemplate {
+    progress: ProgressBar,
+    field: String,
+    key: String,
+    name: String,
+    thumbnail_url: String,
+}
+
+/// Content type of the image from its first bytes, the file name and the
+/// announced content type being not trusted.
+fn image_type(data: &[u8]) -> Option<(&'static str, &'static str)> {
+    IMAGE_TYPES
+        .iter()
+        .find(|(_, _, magic)| data.starts_with(magic))
+        .map(|(content_type, extension, _)| (*content_type, *extension))
+}
+
+/// Stored attachment of `key`, as submitted by the hidden input of the upload
+/// result.
+pub async fn stored_attachment(ctx: &Context, key: &str) -> Option<Attached> {
+    let (id, extension) = key.split_once('.')?;
+    let id = Uuid::parse_str(id).ok()?;
+    let (content_type, _, _) = IMAGE_TYPES.iter().find(|(_, ext, _)| *ext == extension)?;
+    let thumbnail_key = format!("{id}.thumb.png");
+
+    if !ctx.storage().exists(&thumbnail_key).await.ok()? {
+        return None;
+    }
+
+    Some(Attached {
+        key: key.to_owned(),
+        thumbnail_key,
+        content_type: content_type.to_string(),
+    })
+}
+
+/// Renders the image centered and cropped to the thumbnail size.
+fn render_thumbnail(content_type: &str, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{THUMBNAIL_WIDTH}" height="{THUMBNAIL_HEIGHT}"><image xlink:href="data:{content_type};base64,{}" width="{THUMBNAIL_WIDTH}" height="{THUMBNAIL_HEIGHT}" preserveAspectRatio="xMidYMid slice"/></svg>"#,
+        STANDARD.encode(data)
+    );
+
+    let tree = usvg::Tree::from_str(
+        &svg,
+        &usvg::Options::default(),
+        &usvg::fontdb::Database::new(),
+    )?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+        .ok_or_else(|| anyhow::anyhow!("invalid thumbnail size"))?;
+
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    Ok(pixmap.encode_png()?)
+}
+
+/// Stores the first file of the multipart body when it is an image, with a
+/// thumbnail, publishing its progress on the `uploads/{request_id}` topic.
+pub async fn upload(
+    ctx: UserContext,
+    headers: HeaderMap,
+    Query(input): Query<UploadQuery>,
+    mut multipart: Multipart,
+) -> Result<UploadResultTemplate, Response> {
+    let max_size = ctx.context().config.upload.max_size;
+    let total = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    if total.is_some_and(|total| total > max_size) {
+        return Err(ctx.error_response(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+
+    let bad_request = |err: axum::extract::multipart::MultipartError| {
+        warn!("{err}");
+
+        ctx.error_response(StatusCode::BAD_REQUEST)
+    };
+
+    let Some(mut field) = multipart.next_field().await.map_err(bad_request)? else {
+        return Err(ctx.error_response(StatusCode::BAD_REQUEST));
+    };
+
+    let name = field.file_name().unwrap_or_default().to_owned();
+    let label = fl!(ctx.fl_loader(), "pages_upload-UploadResult_label");
+    let topic = format!("uploads/{}", input.request_id);
+    let mut data = Vec::new();
+    let mut published = 0u8;
+
+    while let Some(chunk) = field.chunk().await.map_err(bad_request)? {
+        data.extend_from_slice(&chunk);
+
+        if data.len() as u64 > max_size {
+            return Err(ctx.error_response(StatusCode::PAYLOAD_TOO_LARGE));
+        }
+
+        let Some(total) = total.filter(|total| *total > 0) else {
+            continue;
+        };
+
+        let value = (data.len() as u64 * 100 / total).min(100) as u8;
+
+        if value < published + PROGRESS_STEP {
+            continue;
+        }
+
+        published = value;
+
+        let progress = ProgressBar {
+            label: label.to_owned(),
+            value,
+        };
+
+        if let Ok(html) = progress.render() {
+            ctx.publish(&topic, "progress", html);
+        }
+    }
+
+    let Some((content_type, extension)) = image_type(&data) else {
+        return Err(ctx.error_response(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    };
+
+    let server_error = |err: anyhow::Error| {
+        error!("{err}");
+
+        ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+    };
+
+    let id = Uuid::new_v4();
+    let key = format!("{id}.{extension}");
+    let thumbnail_key = format!("{id}.thumb.png");
+    let (data, thumbnail) = tokio::task::spawn_blocking(move || {
+        let thumbnail = render_thumbnail(content_type, &data);
+        (data, thumbnail)
+    })
+    .await
+    .map_err(|err| server_error(err.into()))?;
+
+    let thumbnail = thumbnail.map_err(|err| {
+        warn!("{err}");
+
+        ctx.error_response(StatusCode::UNPROCESSABLE_ENTITY)
+    })?;
+
+    let storage = ctx.storage();
+    storage
+        .put(&key, &data)
+        .await
+        .map_err(|err| server_error(err.into()))?;
+    storage
+        .put(&thumbnail_key, &thumbnail)
+        .await
+        .map_err(|err| server_error(err.into()))?;
+
+    Ok(UploadResultTemplate {
+        progress: ProgressBar { label, value: 100 },
+        field: input.field,
+        thumbnail_url: ctx.create_url(format!("/uploads/{thumbnail_key}")),
+        key,
+        name,
+    })
+}
+
+/// Stored file, keys being unique they are cached forever, or a redirect to
+/// its signed url.
+pub async fn uploaded_file(ctx: Context, Path((key,)): Path<(String,)>) -> Response {
+    // An encoded slash would reach the folders, such as the private exports.
+    if key.contains('/') {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    }
+
+    let storage = ctx.storage();
+
+    match storage.presigned_url(&key, PRESIGNED_TTL, None).await {
+        Ok(Some(url)) => return Redirect::to(&url).into_response(),
+        Ok(None) => {}
+        Err(err) => {
+            warn!("{err}");
+
+            return ctx.error_response(StatusCode::NOT_FOUND);
+        }
+    }
+
+    let data = match storage.get(&key).await {
+        Ok(Some(data)) => data,
+        Ok(None) => return ctx.error_response(StatusCode::NOT_FOUND),
+        Err(err) => {
+            warn!("{err}");
+
+            return ctx.error_response(StatusCode::NOT_FOUND);
+        }
+    };
+
+    let content_type = mime_guess::from_path(&key).first_or_octet_stream();
+
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CACHE_CONTROL,
+                "public, max-age=31536000, immutable".to_owned(),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_owned()),
+        ],
+        data,
+    )
+        .into_response()
+}
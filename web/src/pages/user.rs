@@ -0,0 +1,90 @@
+use askama::Template;
+use askama_axum::Response;
+use evento_query::{CursorType, Edge, QueryResult};
+use starter_feed::{IsFollowingInput, ListFeedsInput, ListUserFeedsInput, UserFeed};
+use uuid::Uuid;
+
+use crate::{
+    components::{mention_handle, Breadcrumbs, Paginator},
+    context::Context,
+    extract::{Path, Query},
+};
+
+#[derive(Template)]
+#[template(path = "user.html")]
+pub struct UserTemplate {
+    ctx: Context,
+    user_id: Uuid,
+    handle: String,
+    feeds: QueryResult<UserFeed>,
+    breadcrumbs: Breadcrumbs,
+    paginator: Paginator,
+    own: bool,
+    following: bool,
+}
+
+impl UserTemplate {
+    /// Pages of the profile are browsed with the paginator only.
+    fn end_cursor(&self, _feed: &Edge<UserFeed>) -> Option<CursorType> {
+        None
+    }
+
+    fn query_tag(&self) -> String {
+        "".into()
+    }
+}
+
+/// Listed feeds of a user, linked by their mentions.
+pub async fn user(
+    ctx: Context,
+    Path((user_id,)): Path<(Uuid,)>,
+    Query(input): Query<ListFeedsInput>,
+) -> Result<UserTemplate, Response> {
+    let page_size = input.first.or(input.last).unwrap_or(20);
+    let own = ctx.user_id.as_deref() == Some(user_id.to_string().as_str());
+
+    let feeds = ctx
+        .query(ListUserFeedsInput {
+            user_id: user_id.to_string(),
+            first: input.first,
+            after: input.after,
+            last: input.last,
+            before: input.before,
+        })
+        .await?;
+
+    let following = match ctx.user_id.to_owned().filter(|_| !own) {
+        Some(follower_id) => {
+            ctx.query(IsFollowingInput {
+                user_id: follower_id,
+                followed_id: user_id,
+            })
+            .await?
+        }
+        None => false,
+    };
+
+    let handle = mention_handle(&user_id);
+
+    Ok(UserTemplate {
+        breadcrumbs: Breadcrumbs::new(
+            &ctx,
+            "user",
+            &[("user_id", &user_id.to_string())],
+            Some(handle.to_owned()),
+        ),
+        paginator: Paginator::new(
+            &ctx,
+            &feeds.page_info,
+            format!("/users/{user_id}"),
+            page_size,
+            "",
+        ),
+        ctx,
+        user_id,
+        handle,
+        feeds,
+        own,
+        following,
+    })
+}
@@ -0,0 +1,156 @@
+use anyhow::Result;
+use askama_axum::{IntoResponse, Response};
+use axum::http::header;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use i18n_embed::fluent::FluentLanguageLoader;
+use serde::Serialize;
+use sqlx::PgPool;
+use starter_feed::{PushRecipient, PushSubscriptions};
+use std::sync::Arc;
+use tracing::warn;
+use unic_langid::LanguageIdentifier;
+use uuid::Uuid;
+use web_push::{
+    ContentEncoding, HyperWebPushClient, PartialVapidSignatureBuilder, SubscriptionInfo,
+    VapidSignatureBuilder, WebPushClient, WebPushError, WebPushMessageBuilder,
+};
+
+use crate::{config::PushConfig, i18n::LANGUAGE_LOADER};
+
+/// Seconds a push service keeps a notification for an offline browser.
+const PUSH_TTL: u32 = 24 * 60 * 60;
+
+/// Payload of a push, shown by `/sw.js` as a notification opening `url`.
+#[derive(Serialize)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+struct Vapid {
+    key: PartialVapidSignatureBuilder,
+    public_key: String,
+    subject: String,
+    client: HyperWebPushClient,
+}
+
+/// Pushes the notifications to the browsers subscribed with
+/// `PushSubscriptions`, shared by the consumer rules and the settings page.
+#[derive(Clone)]
+pub struct Pusher {
+    vapid: Option<Arc<Vapid>>,
+    pub subscriptions: PushSubscriptions,
+}
+
+impl Pusher {
+    pub fn new(config: &PushConfig, db: PgPool) -> Result<Self> {
+        let vapid = match config.vapid_private_key.as_deref() {
+            Some(private_key) => {
+                let key = VapidSignatureBuilder::from_base64_no_sub(private_key)?;
+
+                Some(Arc::new(Vapid {
+                    public_key: URL_SAFE_NO_PAD.encode(key.get_public_key()),
+                    key,
+                    subject: config.subject.to_owned(),
+                    client: HyperWebPushClient::new(),
+                }))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            vapid,
+            subscriptions: PushSubscriptions::new(db),
+        })
+    }
+
+    /// Application server key the browsers subscribe with, `None` when
+    /// nothing is pushed.
+    pub fn public_key(&self) -> Option<&str> {
+        self.vapid.as_ref().map(|vapid| vapid.public_key.as_str())
+    }
+
+    /// Pushes `message`, rendered in the language of each browser, to the
+    /// users out of `user_ids` not muting the pushes of `kind`. Browsers
+    /// their push service told gone are unsubscribed, other failures are
+    /// only logged.
+    pub async fn notify(
+        &self,
+        user_ids: &[Uuid],
+        kind: &str,
+        message: impl Fn(&FluentLanguageLoader) -> PushMessage,
+    ) -> Result<()> {
+        let Some(vapid) = &self.vapid else {
+            return Ok(());
+        };
+
+        for recipient in self.subscriptions.recipients(user_ids, kind).await? {
+            let langs = recipient
+                .lang
+                .parse::<LanguageIdentifier>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            let payload = serde_json::to_vec(&message(&LANGUAGE_LOADER.select_languages(&langs)))?;
+
+            match send(vapid, &recipient, &payload).await {
+                Ok(()) => {}
+                Err(WebPushError::EndpointNotValid) | Err(WebPushError::EndpointNotFound) => {
+                    self.subscriptions.remove(&recipient.endpoint).await?;
+                }
+                Err(err) => warn!("push to {} failed: {err}", recipient.user_id),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn send(
+    vapid: &Vapid,
+    recipient: &PushRecipient,
+    payload: &[u8],
+) -> Result<(), WebPushError> {
+    let subscription =
+        SubscriptionInfo::new(&recipient.endpoint, &recipient.p256dh, &recipient.auth);
+
+    let mut signature = vapid.key.clone().add_sub_info(&subscription);
+    signature.add_claim("sub", vapid.subject.as_str());
+
+    let mut message = WebPushMessageBuilder::new(&subscription);
+    message.set_ttl(PUSH_TTL);
+    message.set_payload(ContentEncoding::Aes128Gcm, payload);
+    message.set_vapid_signature(signature.build()?);
+
+    vapid.client.send(message.build()?).await
+}
+
+/// Checked by `check_config`, without reaching any push service.
+pub fn check_push(config: &PushConfig) -> Result<(), String> {
+    if let Some(private_key) = config.vapid_private_key.as_deref() {
+        VapidSignatureBuilder::from_base64_no_sub(private_key)
+            .map_err(|_| "vapid_private_key is not a base64url P-256 private key".to_owned())?;
+    }
+
+    if !config.subject.starts_with("mailto:") && !config.subject.starts_with("https://") {
+        return Err(format!(
+            "{} must be a mailto: or an https url",
+            config.subject
+        ));
+    }
+
+    Ok(())
+}
+
+/// Service worker showing the pushes, served from the root so that its
+/// scope is the whole site.
+pub async fn service_worker() -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/javascript"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        include_str!("../public/sw.js"),
+    )
+        .into_response()
+}
@@ -0,0 +1,215 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    Extension,
+};
+use redis::aio::ConnectionManager;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+use twa_jwks::axum::JwtPayloadOption;
+
+use crate::{
+    auth::SessionClaims,
+    config::RateLimitConfig,
+    context::{Context, JwtClaims},
+    extract::ClientIp,
+    maintenance::{app_path, is_probe},
+};
+
+/// Groups and clients counted in memory, the least recently seen being
+/// dropped past it.
+const MAX_WINDOWS: usize = 10_000;
+
+struct Window {
+    started: u64,
+    count: u64,
+    /// Hit of `Windows::tick` the window was last counted at.
+    seen: u64,
+}
+
+/// Windows counted in memory, evicted least recently seen first.
+#[derive(Default)]
+struct Windows {
+    windows: HashMap<String, Window>,
+    /// Keys of `windows` by the tick they were last seen at.
+    seen: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Windows {
+    fn hit(&mut self, key: String, started: u64) -> u64 {
+        self.tick += 1;
+
+        let seen = self.tick;
+        let window = self.windows.entry(key.clone()).or_insert(Window {
+            started,
+            count: 0,
+            seen,
+        });
+
+        self.seen.remove(&window.seen);
+
+        if window.started != started {
+            window.started = started;
+            window.count = 0;
+        }
+
+        window.count += 1;
+        window.seen = seen;
+
+        let count = window.count;
+
+        self.seen.insert(seen, key);
+
+        while self.windows.len() > MAX_WINDOWS {
+            let Some((_, key)) = self.seen.pop_first() else {
+                break;
+            };
+
+            self.windows.remove(&key);
+        }
+
+        count
+    }
+}
+
+/// Requests counted by group and client over fixed windows, in Redis when
+/// `Config::redis_url` is set so that the replicas share the counts, and in
+/// memory otherwise or while Redis is unreachable.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    windows: Arc<Mutex<Windows>>,
+    redis: Option<(String, ConnectionManager)>,
+}
+
+impl RateLimiter {
+    /// Counts kept in Redis under the `{namespace}:` prefix, or in memory
+    /// without `redis`.
+    pub fn new(namespace: impl Into<String>, redis: Option<ConnectionManager>) -> Self {
+        Self {
+            windows: Default::default(),
+            redis: redis.map(|redis| (namespace.into(), redis)),
+        }
+    }
+
+    /// Counts a request of `client` to the group of `limit`, returning the
+    /// seconds left in the window once its requests are used up.
+    pub async fn hit(&self, limit: &RateLimitConfig, client: &str) -> Option<u64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let window = now / limit.window;
+        let key = format!("{}:{client}", limit.group);
+
+        let count = match self.count_in_redis(&key, window, limit.window).await {
+            Some(count) => count,
+            None => self.count_in_memory(key, window),
+        };
+
+        (count > limit.requests).then(|| limit.window - now % limit.window)
+    }
+
+    async fn count_in_redis(&self, key: &str, window: u64, ttl: u64) -> Option<u64> {
+        let (namespace, redis) = self.redis.as_ref()?;
+        let key = format!("{namespace}:{key}:{window}");
+
+        match redis::pipe()
+            .atomic()
+            .cmd("INCR")
+            .arg(&key)
+            .cmd("EXPIRE")
+            .arg(&key)
+            .arg(ttl)
+            .ignore()
+            .query_async::<_, (u64,)>(&mut redis.clone())
+            .await
+        {
+            Ok((count,)) => Some(count),
+            Err(err) => {
+                warn!("rate limiter falls back to memory: {err}");
+
+                None
+            }
+        }
+    }
+
+    fn count_in_memory(&self, key: String, window: u64) -> u64 {
+        match self.windows.lock() {
+            Ok(mut windows) => windows.hit(key, window),
+            Err(_) => 0,
+        }
+    }
+}
+
+/// Signed in user of the request, or its address.
+async fn client(parts: &mut Parts) -> Option<String> {
+    let claims = JwtPayloadOption::<JwtClaims>::from_request_parts(parts, &())
+        .await
+        .ok()
+        .and_then(|JwtPayloadOption(claims)| claims)
+        .or_else(|| {
+            parts
+                .extensions
+                .get::<SessionClaims>()
+                .map(|claims| claims.0.clone())
+        });
+
+    if let Some(claims) = claims {
+        return Some(format!("user:{}", claims.sub));
+    }
+
+    ClientIp::from_request_parts(parts, &())
+        .await
+        .ok()
+        .and_then(|ClientIp(ip)| ip)
+        .map(|ip| format!("ip:{ip}"))
+}
+
+/// Answers with the too many requests page, and when to try again, the
+/// clients that used up the requests of the first group of
+/// `Config::rate_limits` their request is of. Probes and static files are
+/// never limited.
+pub async fn limit(Extension(ctx): Extension<Context>, req: Request, next: Next) -> Response {
+    let config = ctx.reloader.config();
+    let path = app_path(&ctx, req.uri().path());
+
+    if is_probe(path) || path.starts_with("/static/") {
+        return next.run(req).await;
+    }
+
+    let Some(limit) = config
+        .rate_limits
+        .iter()
+        .find(|limit| limit.includes(req.method(), path))
+    else {
+        return next.run(req).await;
+    };
+
+    let (mut parts, body) = req.into_parts();
+
+    let Some(client) = client(&mut parts).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    let Some(retry_after) = ctx.limiter.hit(limit, &client).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    let ctx = match Context::from_request_parts(&mut parts, &()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return response,
+    };
+
+    (
+        [(header::RETRY_AFTER, retry_after.to_string())],
+        ctx.error_response(StatusCode::TOO_MANY_REQUESTS),
+    )
+        .into_response()
+}
@@ -0,0 +1,199 @@
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::OnceCell;
+use std::{
+    env,
+    path::Path,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+use tracing::{error, info};
+
+use crate::{config::Config, feature::Features};
+
+/// Settings applied to the running process when the config is reloaded,
+/// changes to the others needing a restart.
+const RELOADABLE: [&str; 5] = [
+    "log",
+    "minify",
+    "features",
+    "feature_rollouts",
+    "rate_limits",
+];
+
+/// How often the file of `STARTER_CONFIG_PATH` is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+type LogFilter = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+static LOG_FILTER: OnceCell<LogFilter> = OnceCell::new();
+
+/// Lets `Config::log` replace the log filter of the process, `set_filter`
+/// receiving filters like `info,sqlx=warn`.
+pub fn on_log_filter(set_filter: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static) {
+    let _ = LOG_FILTER.set(Box::new(set_filter));
+}
+
+fn apply_log(log: Option<&str>) -> Result<()> {
+    match (log, LOG_FILTER.get()) {
+        (Some(log), Some(set_filter)) => set_filter(log).map_err(|e| anyhow!("log: {e}")),
+        _ => Ok(()),
+    }
+}
+
+fn settings(config: &Config) -> Result<serde_json::Map<String, serde_json::Value>> {
+    match serde_json::to_value(config)? {
+        serde_json::Value::Object(settings) => Ok(settings),
+        _ => bail!("config is not an object"),
+    }
+}
+
+fn modified_at(path: &str) -> Option<SystemTime> {
+    Path::new(path)
+        .metadata()
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Config of the running process, reloaded on `SIGHUP` and whenever the
+/// file of `STARTER_CONFIG_PATH` changes. A new config is applied only when
+/// the settings it changes are all `RELOADABLE`, requests reading it from
+/// `Context::config` as they start.
+#[derive(Clone)]
+pub struct Reloader {
+    config: Arc<RwLock<Config>>,
+    features: Features,
+}
+
+impl Reloader {
+    pub fn new(config: &Config, features: Features) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config.clone())),
+            features,
+        }
+    }
+
+    pub fn config(&self) -> Config {
+        self.config.read().expect("config lock poisoned").clone()
+    }
+
+    /// Loads the config again and applies it, returning the settings that
+    /// changed.
+    pub async fn reload(&self) -> Result<Vec<String>> {
+        self.apply(Config::load().await?)
+    }
+
+    /// `reload` with `Config::with_overrides`, for the tests setting the
+    /// config of the app the same way.
+    pub fn reload_with(&self, overrides: &[(&str, &str)]) -> Result<Vec<String>> {
+        self.apply(Config::with_overrides(overrides)?)
+    }
+
+    fn apply(&self, config: Config) -> Result<Vec<String>> {
+        let current = self.config();
+        let config = match current.tenant.as_deref() {
+            Some(tenant) => config.for_tenant(tenant),
+            None => config,
+        };
+        let current = settings(&current)?;
+
+        let changed = settings(&config)?
+            .into_iter()
+            .filter(|(name, value)| current.get(name) != Some(value))
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        let fixed = changed
+            .iter()
+            .filter(|name| !RELOADABLE.contains(&name.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        if !fixed.is_empty() {
+            bail!("{} changed, restart to apply", fixed.join(", "));
+        }
+
+        if changed.iter().any(|name| name == "log") {
+            apply_log(config.log.as_deref())?;
+        }
+
+        self.features.set_defaults(&config);
+        *self.config.write().expect("config lock poisoned") = config;
+
+        Ok(changed)
+    }
+
+    /// Applies `Config::log` and reloads the config in the background from
+    /// now on.
+    pub fn spawn(&self) {
+        if let Err(err) = apply_log(self.config().log.as_deref()) {
+            error!("{err}");
+        }
+
+        let reloader = self.clone();
+
+        tokio::spawn(async move {
+            let mut hangup = Hangup::new();
+            let path = env::var("STARTER_CONFIG_PATH").ok();
+            let mut modified = path.as_deref().and_then(modified_at);
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => {}
+                    _ = interval.tick() => {
+                        let now = path.as_deref().and_then(modified_at);
+
+                        if now == modified {
+                            continue;
+                        }
+
+                        modified = now;
+                    }
+                }
+
+                match reloader.reload().await {
+                    Ok(changed) if changed.is_empty() => info!("config reloaded, unchanged"),
+                    Ok(changed) => info!("config reloaded, {} changed", changed.join(", ")),
+                    Err(err) => error!("config not reloaded: {err}"),
+                }
+            }
+        });
+    }
+}
+
+/// `SIGHUP` of the process, never received where there are no signals.
+struct Hangup {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangup {
+    #[cfg(unix)]
+    fn new() -> Self {
+        let signal = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+            .map_err(|err| error!("SIGHUP not handled: {err}"))
+            .ok();
+
+        Self { signal }
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[cfg(unix)]
+    async fn recv(&mut self) {
+        match &mut self.signal {
+            Some(signal) => {
+                signal.recv().await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) {
+        std::future::pending().await
+    }
+}
@@ -0,0 +1,194 @@
+use axum::{handler::Handler, middleware, routing::MethodRouter, Router};
+use std::fmt;
+
+use crate::context::{Admin, Permission, RequirePermission};
+
+/// Who a route responds to, as enforced by the extractors of its handler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    Public,
+    User,
+    Admin,
+    /// Users granted the permission, and the admins.
+    Permission(&'static str),
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Public => f.write_str("public"),
+            Role::User => f.write_str("user"),
+            Role::Admin => f.write_str("admin"),
+            Role::Permission(name) => write!(f, "permission:{name}"),
+        }
+    }
+}
+
+/// Route registered by `Routes`, as listed by `starter routes`.
+#[derive(Clone, Debug)]
+pub struct RouteInfo {
+    pub method: &'static str,
+    pub path: String,
+    pub handler: &'static str,
+    pub role: Role,
+}
+
+/// Handlers of a path by method, recorded with their name. Built with `on!`
+/// rather than by hand so that names can't drift from handlers.
+#[derive(Default)]
+pub struct Endpoint {
+    method_router: MethodRouter,
+    handlers: Vec<(&'static str, &'static str, Role)>,
+}
+
+impl Endpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<H: Handler<T, ()>, T: 'static>(
+        mut self,
+        role: Role,
+        handler: H,
+        name: &'static str,
+    ) -> Self {
+        self.method_router = self.method_router.get(handler);
+        self.handlers.push(("GET", name, role));
+        self
+    }
+
+    pub fn post<H: Handler<T, ()>, T: 'static>(
+        mut self,
+        role: Role,
+        handler: H,
+        name: &'static str,
+    ) -> Self {
+        self.method_router = self.method_router.post(handler);
+        self.handlers.push(("POST", name, role));
+        self
+    }
+
+    pub fn patch<H: Handler<T, ()>, T: 'static>(
+        mut self,
+        role: Role,
+        handler: H,
+        name: &'static str,
+    ) -> Self {
+        self.method_router = self.method_router.patch(handler);
+        self.handlers.push(("PATCH", name, role));
+        self
+    }
+
+    pub fn delete<H: Handler<T, ()>, T: 'static>(
+        mut self,
+        role: Role,
+        handler: H,
+        name: &'static str,
+    ) -> Self {
+        self.method_router = self.method_router.delete(handler);
+        self.handlers.push(("DELETE", name, role));
+        self
+    }
+
+    /// Changes the method router, to add a layer to it for instance.
+    pub fn map(mut self, f: impl FnOnce(MethodRouter) -> MethodRouter) -> Self {
+        self.method_router = f(self.method_router);
+        self
+    }
+}
+
+/// `Endpoint` with the given handlers, each preceded by its role, like
+/// `on!(Public get(comments), User post(create_comment))`.
+macro_rules! on {
+    ($($role:ident $method:ident($handler:path)),+ $(,)?) => {
+        $crate::routes::Endpoint::new()
+            $(.$method($crate::routes::Role::$role, $handler, stringify!($handler)))+
+    };
+}
+
+pub(crate) use on;
+
+/// Router keeping track of the routes it registers.
+#[derive(Default)]
+pub struct Routes {
+    router: Router,
+    routes: Vec<RouteInfo>,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, path: &str, endpoint: Endpoint) -> Self {
+        self.routes.extend(
+            endpoint
+                .handlers
+                .iter()
+                .map(|&(method, handler, role)| RouteInfo {
+                    method,
+                    path: path.to_owned(),
+                    handler,
+                    role,
+                }),
+        );
+        self.router = self.router.route(path, endpoint.method_router);
+        self
+    }
+
+    pub fn nest(mut self, path: &str, routes: Routes) -> Self {
+        self.routes
+            .extend(routes.routes.into_iter().map(|route| RouteInfo {
+                path: nested_path(path, &route.path),
+                ..route
+            }));
+        self.router = self.router.nest(path, routes.router);
+        self
+    }
+
+    /// Routes of both, which paths must not overlap.
+    pub fn merge(mut self, routes: Routes) -> Self {
+        self.routes.extend(routes.routes);
+        self.router = self.router.merge(routes.router);
+        self
+    }
+
+    /// Responds with the not found page to anyone but admins.
+    pub fn admin_only(mut self) -> Self {
+        self.router = self
+            .router
+            .route_layer(middleware::from_extractor::<Admin>());
+        for route in &mut self.routes {
+            route.role = Role::Admin;
+        }
+        self
+    }
+
+    /// Responds with the forbidden page to the users not granted `P`.
+    pub fn permitted<P: Permission>(mut self) -> Self {
+        self.router = self
+            .router
+            .route_layer(middleware::from_extractor::<RequirePermission<P>>());
+        for route in &mut self.routes {
+            route.role = Role::Permission(P::NAME);
+        }
+        self
+    }
+
+    pub fn routes(&self) -> &[RouteInfo] {
+        &self.routes
+    }
+
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+}
+
+/// `path` under `prefix`, the root of a nested router being the prefix
+/// itself like for axum.
+pub fn nested_path(prefix: &str, path: &str) -> String {
+    match path {
+        "/" => prefix.to_owned(),
+        path => format!("{prefix}{path}"),
+    }
+}
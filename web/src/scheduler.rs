@@ -0,0 +1,119 @@
+use evento::{Command, CommandError, Query, QueryError};
+use starter_feed::{
+    DeleteFeedInput, DeliverWebhookInput, ListDueDeletionsInput, ListDueFeedsInput,
+    ListDueWebhookDeliveriesInput, PublishFeedInput,
+};
+use std::time::Duration;
+use tracing::error;
+
+const INTERVAL: Duration = Duration::from_secs(15);
+const BATCH_SIZE: i64 = 100;
+
+/// Publishes the scheduled feeds whose `publish_at` is due and deletes the
+/// trashed ones whose undo window elapsed every `INTERVAL`, each server doing
+/// it being harmless as published and deleted feeds are skipped.
+///
+/// Deliveries due to webhooks are sent apart, so that slow webhooks never
+/// hold publishing back. Servers sending the same delivery at once may post
+/// it twice, receivers telling them apart by `X-Starter-Delivery`.
+pub fn spawn(command: Command, query: Query) {
+    let (webhooks_command, webhooks_query) = (command.clone(), query.clone());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+
+        loop {
+            interval.tick().await;
+            publish_due_feeds(&command, &query).await;
+            delete_due_feeds(&command, &query).await;
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+
+        loop {
+            interval.tick().await;
+            deliver_due_webhooks(&webhooks_command, &webhooks_query).await;
+        }
+    });
+}
+
+async fn publish_due_feeds(command: &Command, query: &Query) {
+    let ids = match query
+        .execute(&ListDueFeedsInput { limit: BATCH_SIZE })
+        .await
+    {
+        Ok(ids) => ids,
+        Err(QueryError::Server(err)) => {
+            error!("{err}");
+            return;
+        }
+        Err(_) => return,
+    };
+
+    for id in ids {
+        let input = PublishFeedInput {
+            id,
+            request_id: None,
+        };
+
+        if let Err(CommandError::Server(err)) = command.execute("en".to_owned(), &input).await {
+            error!("{err}");
+        }
+    }
+}
+
+async fn delete_due_feeds(command: &Command, query: &Query) {
+    let ids = match query
+        .execute(&ListDueDeletionsInput { limit: BATCH_SIZE })
+        .await
+    {
+        Ok(ids) => ids,
+        Err(QueryError::Server(err)) => {
+            error!("{err}");
+            return;
+        }
+        Err(_) => return,
+    };
+
+    for id in ids {
+        let input = DeleteFeedInput {
+            id,
+            request_id: None,
+        };
+
+        if let Err(CommandError::Server(err)) = command.execute("en".to_owned(), &input).await {
+            error!("{err}");
+        }
+    }
+}
+
+async fn deliver_due_webhooks(command: &Command, query: &Query) {
+    let deliveries = match query
+        .execute(&ListDueWebhookDeliveriesInput { limit: BATCH_SIZE })
+        .await
+    {
+        Ok(deliveries) => deliveries,
+        Err(QueryError::Server(err)) => {
+            error!("{err}");
+            return;
+        }
+        Err(_) => return,
+    };
+
+    for delivery in deliveries {
+        let input = DeliverWebhookInput {
+            id: delivery.id,
+            url: delivery.url,
+            secret: delivery.secret,
+            body: delivery.body,
+            user_id: delivery.user_id.to_string(),
+            request_id: None,
+        };
+
+        if let Err(CommandError::Server(err)) = command.execute("en".to_owned(), &input).await {
+            error!("{err}");
+        }
+    }
+}
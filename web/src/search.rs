@@ -0,0 +1,360 @@
+use anyhow::{bail, Result};
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use evento::{store::Event, Aggregate, ConsumerContext, Query, QueryError, RuleHandler};
+use serde::{Deserialize, Serialize};
+use starter_feed::{
+    Feed, FeedEvent, FeedSearchResult, GetFeedInput, SearchFeedsInput, UserFeed, SNIPPET_START,
+    SNIPPET_STOP, VISIBILITY_PUBLIC,
+};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::config::{Config, SearchConfig};
+
+/// Results of a page, as many as the postgres search gives.
+const PAGE_SIZE: usize = 20;
+
+/// Words of content around the matches kept in the snippets.
+const SNIPPET_WORDS: usize = 35;
+
+/// Public feed as indexed by the external engines, the only feeds they get
+/// being the ones anyone can search.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SearchDocument {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub author: String,
+    pub tags: Vec<String>,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SearchDocument {
+    /// Document of `feed`, `None` when it is not listed to everyone.
+    pub fn new(feed: UserFeed) -> Option<Self> {
+        let searchable = feed.visibility == VISIBILITY_PUBLIC
+            && feed.publish_at.is_none()
+            && !feed.hidden
+            && !feed.archived
+            && feed.delete_at.is_none();
+
+        searchable.then(|| Self {
+            id: feed.id,
+            title: feed.title,
+            content: feed.content,
+            author: feed.author,
+            tags: feed.tags,
+            user_id: feed.user_id,
+            created_at: feed.created_at,
+        })
+    }
+}
+
+/// Engine behind `/search`, the feeds reaching it through `SearchIndexer`
+/// unless it searches them in place.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Results of the page of `input` and whether there is a next one, the
+    /// matched words of the snippets being marked with `SNIPPET_START` and
+    /// `SNIPPET_STOP`.
+    async fn search(&self, input: &SearchFeedsInput) -> Result<(Vec<FeedSearchResult>, bool)>;
+
+    /// Whether the feeds are sent to it by `SearchIndexer`.
+    fn is_indexed(&self) -> bool {
+        true
+    }
+
+    /// Adds `document`, replacing the one of the same feed.
+    async fn index(&self, _document: &SearchDocument) -> Result<()> {
+        Ok(())
+    }
+
+    async fn remove(&self, _id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Creates the index or updates its settings, run when the consumers
+    /// start.
+    async fn setup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reaches the engine, for `check_config`.
+    async fn health(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Backend of `Config::search`, the postgres one searching through `query`.
+pub fn open(config: &Config, query: Query) -> Result<Arc<dyn SearchBackend>> {
+    match config.search.backend.as_str() {
+        "postgres" => Ok(Arc::new(PostgresSearch { query })),
+        "meilisearch" => Ok(Arc::new(MeilisearchSearch::new(&config.search)?)),
+        backend => bail!("search backend {backend} is not postgres or meilisearch"),
+    }
+}
+
+/// Full text search of the feeds table, with `SearchFeedsInput`.
+pub struct PostgresSearch {
+    query: Query,
+}
+
+#[async_trait]
+impl SearchBackend for PostgresSearch {
+    async fn search(&self, input: &SearchFeedsInput) -> Result<(Vec<FeedSearchResult>, bool)> {
+        match self.query.execute(input).await {
+            Ok(results) => Ok(results),
+            Err(QueryError::NotFound(err)) => bail!("{err}"),
+            Err(QueryError::Server(err)) => bail!("{err}"),
+        }
+    }
+
+    fn is_indexed(&self) -> bool {
+        false
+    }
+}
+
+/// Meilisearch index of the public feeds. Tags are filtered on exactly and
+/// authors on their whole name, case insensitively.
+pub struct MeilisearchSearch {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    index: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MeilisearchQuery<'a> {
+    q: &'a str,
+    offset: usize,
+    limit: usize,
+    filter: Vec<String>,
+    attributes_to_crop: [&'a str; 1],
+    crop_length: usize,
+    attributes_to_highlight: [&'a str; 1],
+    highlight_pre_tag: String,
+    highlight_post_tag: String,
+    show_ranking_score: bool,
+}
+
+#[derive(Deserialize)]
+struct MeilisearchResults {
+    hits: Vec<MeilisearchHit>,
+}
+
+#[derive(Deserialize)]
+struct MeilisearchHit {
+    #[serde(flatten)]
+    document: SearchDocument,
+    #[serde(rename = "_formatted")]
+    formatted: MeilisearchFormatted,
+    #[serde(rename = "_rankingScore", default)]
+    ranking_score: f32,
+}
+
+#[derive(Deserialize)]
+struct MeilisearchFormatted {
+    content: String,
+}
+
+/// `value` quoted for a filter expression.
+fn filter_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl MeilisearchSearch {
+    pub fn new(config: &SearchConfig) -> Result<Self> {
+        let Some(url) = &config.url else {
+            bail!("search.url is required by meilisearch");
+        };
+
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            url: url.trim_end_matches('/').to_owned(),
+            api_key: config.api_key.to_owned(),
+            index: config.index.to_owned(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{path}", self.url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for MeilisearchSearch {
+    async fn search(&self, input: &SearchFeedsInput) -> Result<(Vec<FeedSearchResult>, bool)> {
+        let q = input.q.trim();
+
+        if q.is_empty() {
+            return Ok((vec![], false));
+        }
+
+        let mut filter = vec![];
+
+        if let Some(tag) = input.tag.as_ref().filter(|tag| !tag.is_empty()) {
+            filter.push(format!("tags = {}", filter_value(tag)));
+        }
+
+        if let Some(author) = input.author.as_ref().filter(|author| !author.is_empty()) {
+            filter.push(format!("author = {}", filter_value(author)));
+        }
+
+        let body = MeilisearchQuery {
+            q,
+            offset: usize::from(input.page() - 1) * PAGE_SIZE,
+            limit: PAGE_SIZE + 1,
+            filter,
+            attributes_to_crop: ["content"],
+            crop_length: SNIPPET_WORDS,
+            attributes_to_highlight: ["content"],
+            highlight_pre_tag: SNIPPET_START.to_string(),
+            highlight_post_tag: SNIPPET_STOP.to_string(),
+            show_ranking_score: true,
+        };
+
+        let text = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/indexes/{}/search", self.index),
+            )
+            .body(serde_json::to_vec(&body)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let hits = serde_json::from_str::<MeilisearchResults>(&text)?.hits;
+        let has_next_page = hits.len() > PAGE_SIZE;
+
+        let results = hits
+            .into_iter()
+            .take(PAGE_SIZE)
+            .map(|hit| FeedSearchResult {
+                id: hit.document.id,
+                title: hit.document.title,
+                author: hit.document.author,
+                snippet: hit.formatted.content,
+                tags: hit.document.tags,
+                user_id: hit.document.user_id,
+                created_at: hit.document.created_at,
+                rank: hit.ranking_score,
+            })
+            .collect();
+
+        Ok((results, has_next_page))
+    }
+
+    async fn index(&self, document: &SearchDocument) -> Result<()> {
+        self.request(
+            reqwest::Method::POST,
+            &format!("/indexes/{}/documents?primaryKey=id", self.index),
+        )
+        .body(serde_json::to_vec(&[document])?)
+        .send()
+        .await?
+        .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<()> {
+        self.request(
+            reqwest::Method::DELETE,
+            &format!("/indexes/{}/documents/{id}", self.index),
+        )
+        .send()
+        .await?
+        .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn setup(&self) -> Result<()> {
+        let settings = serde_json::json!({
+            "searchableAttributes": ["title", "content"],
+            "filterableAttributes": ["tags", "author"],
+        });
+
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/indexes/{}/settings", self.index),
+        )
+        .body(serde_json::to_vec(&settings)?)
+        .send()
+        .await?
+        .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn health(&self) -> Result<()> {
+        self.request(reqwest::Method::GET, "/health")
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Sends the feeds to the external search engine of `Config::search` as
+/// they change, those no longer public being removed from it. Feeds are read
+/// back from the feeds table, reactions and reports leaving them as they
+/// were.
+pub struct SearchIndexer;
+
+#[async_trait]
+impl RuleHandler for SearchIndexer {
+    async fn handle(&self, event: Event, ctx: ConsumerContext) -> Result<()> {
+        let search = ctx.extract::<Arc<dyn SearchBackend>>();
+
+        if !search.is_indexed() {
+            return Ok(());
+        }
+
+        let event_name: FeedEvent = event.name.parse()?;
+
+        if matches!(
+            event_name,
+            FeedEvent::Reacted
+                | FeedEvent::Unreacted
+                | FeedEvent::Reported
+                | FeedEvent::Mentioned
+                | FeedEvent::Attached
+        ) {
+            return Ok(());
+        }
+
+        let id = Feed::from_aggregate_id(&event.aggregate_id);
+
+        let feed = match ctx
+            .extract::<Query>()
+            .execute(&GetFeedInput { id: id.to_owned() })
+            .await
+        {
+            Ok(feed) => Some(feed),
+            Err(QueryError::NotFound(_)) => None,
+            Err(QueryError::Server(err)) => bail!("{err}"),
+        };
+
+        match feed.and_then(SearchDocument::new) {
+            Some(document) => search.index(&document).await,
+            None => search.remove(&id).await,
+        }
+    }
+}
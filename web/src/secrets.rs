@@ -0,0 +1,227 @@
+use anyhow::{bail, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::{env, time::Duration};
+use web_push::VapidSignatureBuilder;
+
+/// Prefix of the values read from Vault, like
+/// `vault:secret/data/starter#dsn`.
+const VAULT_PREFIX: &str = "vault:";
+
+/// Prefix of the values read from AWS Secrets Manager, like
+/// `aws-sm:starter/prod#dsn`.
+const AWS_PREFIX: &str = "aws-sm:";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Value of the secret `value` refers to, `value` itself unless it starts
+/// with `vault:` or `aws-sm:`. The part after `#` is the field of the
+/// secret, the whole secret string being taken without it.
+pub async fn resolve(value: &str) -> Result<String> {
+    if let Some(reference) = value.strip_prefix(VAULT_PREFIX) {
+        let (path, field) = split_field(reference);
+
+        return vault(path, field).await;
+    }
+
+    if let Some(reference) = value.strip_prefix(AWS_PREFIX) {
+        let (secret_id, field) = split_field(reference);
+
+        return aws_secrets_manager(secret_id, field).await;
+    }
+
+    Ok(value.to_owned())
+}
+
+/// New secrets of the config by key, like `oidc.session_secret`, in the
+/// format their setting expects.
+pub fn generate() -> Result<Vec<(&'static str, String)>> {
+    Ok(vec![
+        ("oidc.session_secret", random(64)),
+        ("upload.image_secret", random(32)),
+        ("push.vapid_private_key", vapid_private_key()?),
+    ])
+}
+
+/// `len` random bytes, base64url encoded.
+fn random(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// P-256 private key, drawn again in the unlikely case the bytes are not a
+/// valid scalar of the curve.
+fn vapid_private_key() -> Result<String> {
+    for _ in 0..8 {
+        let key = random(32);
+
+        if VapidSignatureBuilder::from_base64_no_sub(&key).is_ok() {
+            return Ok(key);
+        }
+    }
+
+    bail!("no vapid private key generated")
+}
+
+fn split_field(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once('#') {
+        Some((path, field)) => (path, Some(field)),
+        None => (reference, None),
+    }
+}
+
+fn var(name: &str) -> Result<String> {
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(value),
+        _ => bail!("{name} is required to read the secrets"),
+    }
+}
+
+fn client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder().timeout(TIMEOUT).build()?)
+}
+
+/// `field` of the json `secret`, as json unless it is a string.
+fn secret_field(secret: &serde_json::Value, field: &str) -> Result<String> {
+    match &secret[field] {
+        serde_json::Value::String(value) => Ok(value.to_owned()),
+        serde_json::Value::Null => bail!("secret has no field {field}"),
+        value => Ok(value.to_string()),
+    }
+}
+
+/// Secret at `path` of the Vault of `VAULT_ADDR`, read with `VAULT_TOKEN`.
+/// Both versions of the key/value engine are read, the fields of a version
+/// 2 secret being under `data.data`.
+async fn vault(path: &str, field: Option<&str>) -> Result<String> {
+    let Some(field) = field else {
+        bail!("vault:{path} needs a #field");
+    };
+
+    let addr = var("VAULT_ADDR")?;
+    let token = var("VAULT_TOKEN")?;
+
+    let body = client()?
+        .get(format!(
+            "{}/v1/{}",
+            addr.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        ))
+        .header("X-Vault-Token", token)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let response = serde_json::from_str::<serde_json::Value>(&body)?;
+    let data = match &response["data"]["data"] {
+        serde_json::Value::Object(_) => &response["data"]["data"],
+        _ => &response["data"],
+    };
+
+    secret_field(data, field)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac takes keys of any size");
+    mac.update(data.as_bytes());
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Secret `secret_id` of AWS Secrets Manager in `AWS_REGION`, requested with
+/// the credentials of `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+/// `AWS_SESSION_TOKEN` signed with Signature Version 4.
+async fn aws_secrets_manager(secret_id: &str, field: Option<&str>) -> Result<String> {
+    let region = var("AWS_REGION").or_else(|_| var("AWS_DEFAULT_REGION"))?;
+    let access_key_id = var("AWS_ACCESS_KEY_ID")?;
+    let secret_access_key = var("AWS_SECRET_ACCESS_KEY")?;
+    let session_token = var("AWS_SESSION_TOKEN").ok();
+
+    let host = format!("secretsmanager.{region}.amazonaws.com");
+    let body = serde_json::to_string(&serde_json::json!({ "SecretId": secret_id }))?;
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers = vec![
+        ("content-type", "application/x-amz-json-1.1".to_owned()),
+        ("host", host.to_owned()),
+        ("x-amz-date", amz_date.to_owned()),
+        ("x-amz-target", "secretsmanager.GetSecretValue".to_owned()),
+    ];
+
+    if let Some(session_token) = session_token {
+        headers.push(("x-amz-security-token", session_token));
+    }
+
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect::<String>();
+
+    let canonical_request = format!(
+        "POST\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex(&Sha256::digest(body.as_bytes()))
+    );
+
+    let scope = format!("{date}/{region}/secretsmanager/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [region.as_str(), "secretsmanager", "aws4_request"]
+        .into_iter()
+        .fold(
+            hmac(format!("AWS4{secret_access_key}").as_bytes(), &date),
+            |key, part| hmac(&key, part),
+        );
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={access_key_id}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+        hex(&hmac(&key, &string_to_sign))
+    );
+
+    let mut request = client()?
+        .post(format!("https://{host}/"))
+        .header(reqwest::header::AUTHORIZATION, authorization);
+
+    for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+        request = request.header(name, value);
+    }
+
+    let response = request
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let response = serde_json::from_str::<serde_json::Value>(&response)?;
+    let Some(secret) = response["SecretString"].as_str() else {
+        bail!("aws-sm:{secret_id} has no secret string");
+    };
+
+    match field {
+        Some(field) => secret_field(&serde_json::from_str(secret)?, field),
+        None => Ok(secret.to_owned()),
+    }
+}
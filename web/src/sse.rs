@@ -0,0 +1,94 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use std::{convert::Infallible, time::Duration};
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A server-sent event, `data` is sent as is so it can be an html fragment for
+/// htmx `sse-swap` or json created with [`SseEvent::json`].
+#[derive(Clone, Debug)]
+pub struct SseEvent {
+    pub id: Option<String>,
+    pub name: String,
+    pub data: String,
+}
+
+impl SseEvent {
+    pub fn new(name: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn json<T: Serialize>(name: impl Into<String>, data: &T) -> serde_json::Result<Self> {
+        Ok(Self::new(name, serde_json::to_string(data)?))
+    }
+
+    /// Sent back by the browser as `Last-Event-ID` when reconnecting.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+impl From<SseEvent> for Event {
+    fn from(value: SseEvent) -> Self {
+        let event = Event::default().event(value.name).data(value.data);
+
+        match value.id {
+            Some(id) => event.id(id),
+            _ => event,
+        }
+    }
+}
+
+/// Streams `events` to the client with keep-alive comments.
+///
+/// To resume after a reconnection, chain the events missed since
+/// [`LastEventId`] before the live stream:
+///
+/// ```ignore
+/// let missed = load_events_after(last_event_id).await?;
+/// sse_response(futures_util::stream::iter(missed).chain(live))
+/// ```
+pub fn sse_response<S>(events: S) -> Response
+where
+    S: Stream<Item = SseEvent> + Send + 'static,
+{
+    Sse::new(events.map(|event| Ok::<Event, Infallible>(event.into())))
+        .keep_alive(KeepAlive::new().interval(KEEP_ALIVE_INTERVAL))
+        .into_response()
+}
+
+/// The `Last-Event-ID` header sent by `EventSource` when reconnecting.
+#[derive(Clone, Debug, Default)]
+pub struct LastEventId(pub Option<String>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for LastEventId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            parts
+                .headers
+                .get("Last-Event-ID")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned()),
+        ))
+    }
+}
@@ -0,0 +1,298 @@
+use axum::async_trait;
+use s3::{creds::Credentials, Bucket, Region};
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::fs;
+
+use crate::config::{Config, StorageConfig};
+
+/// Object storage of uploaded files and export archives addressed by key,
+/// keys being names optionally prefixed by folders like `exports/`.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: &[u8]) -> io::Result<()>;
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    async fn exists(&self, key: &str) -> io::Result<bool>;
+
+    async fn delete(&self, key: &str) -> io::Result<()>;
+
+    /// Url reading `key` for `ttl` without going through the server,
+    /// downloaded as an attachment given a `file_name`, `None` when the
+    /// backend can't sign urls.
+    async fn presigned_url(
+        &self,
+        key: &str,
+        ttl: Duration,
+        file_name: Option<&str>,
+    ) -> io::Result<Option<String>>;
+}
+
+/// Backend of `Config::storage`, the local one keeping files in
+/// `Config::upload.dir`, the keys of a tenant being under its folder.
+pub fn open(config: &Config) -> anyhow::Result<Arc<dyn Storage>> {
+    let storage: Arc<dyn Storage> = match config.storage.backend.as_str() {
+        "local" => Arc::new(LocalStorage::new(&config.upload.dir)),
+        "s3" => Arc::new(S3Storage::new(&config.storage)?),
+        backend => anyhow::bail!("storage backend {backend} is not local or s3"),
+    };
+
+    Ok(match &config.tenant {
+        Some(tenant) => Arc::new(TenantStorage {
+            folder: tenant.to_owned(),
+            inner: storage,
+        }),
+        None => storage,
+    })
+}
+
+/// Storage of a tenant, its keys prefixed by its folder so that no tenant
+/// reads the files of another.
+struct TenantStorage {
+    folder: String,
+    inner: Arc<dyn Storage>,
+}
+
+impl TenantStorage {
+    fn key(&self, key: &str) -> String {
+        format!("{}/{key}", self.folder)
+    }
+}
+
+#[async_trait]
+impl Storage for TenantStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        self.inner.put(&self.key(key), data).await
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        self.inner.get(&self.key(key)).await
+    }
+
+    async fn exists(&self, key: &str) -> io::Result<bool> {
+        self.inner.exists(&self.key(key)).await
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        self.inner.delete(&self.key(key)).await
+    }
+
+    async fn presigned_url(
+        &self,
+        key: &str,
+        ttl: Duration,
+        file_name: Option<&str>,
+    ) -> io::Result<Option<String>> {
+        self.inner
+            .presigned_url(&self.key(key), ttl, file_name)
+            .await
+    }
+}
+
+/// Anything that could leave the directory, or be taken for a temporary
+/// file, is rejected.
+fn check_key(key: &str) -> io::Result<()> {
+    let valid = !key.contains('\\')
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && !segment.starts_with('.'));
+
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid storage key {key}"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Storage backed by a local directory.
+#[derive(Clone)]
+pub struct LocalStorage {
+    dir: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        check_key(key)?;
+
+        Ok(self.dir.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+        let (dir, name) = match key.rsplit_once('/') {
+            Some((folder, name)) => (self.dir.join(folder), name),
+            None => (self.dir.to_owned(), key),
+        };
+        let tmp = dir.join(format!(".{name}.tmp"));
+
+        fs::create_dir_all(&dir).await?;
+        fs::write(&tmp, data).await?;
+        fs::rename(&tmp, path).await
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> io::Result<bool> {
+        fs::try_exists(self.path(key)?).await
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    async fn presigned_url(
+        &self,
+        key: &str,
+        _: Duration,
+        _: Option<&str>,
+    ) -> io::Result<Option<String>> {
+        check_key(key)?;
+
+        Ok(None)
+    }
+}
+
+/// Storage backed by a bucket of S3 or of a compatible service like MinIO,
+/// credentials being read from the environment when not configured.
+#[derive(Clone)]
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    pub fn new(config: &StorageConfig) -> anyhow::Result<Self> {
+        let region = match &config.endpoint {
+            Some(endpoint) => Region::Custom {
+                region: config.region.to_owned(),
+                endpoint: endpoint.to_owned(),
+            },
+            None => config.region.parse()?,
+        };
+
+        let credentials = match (&config.access_key_id, &config.secret_access_key) {
+            (Some(access_key), Some(secret_key)) => {
+                Credentials::new(Some(access_key), Some(secret_key), None, None, None)?
+            }
+            _ => Credentials::from_env()?,
+        };
+
+        let bucket = Bucket::new(&config.bucket, region, credentials)?;
+        let bucket = if config.path_style {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
+
+        Ok(Self { bucket })
+    }
+}
+
+fn s3_error(err: s3::error::S3Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+fn status_error(key: &str, status: u16) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        format!("storage answered {status} for {key}"),
+    )
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        check_key(key)?;
+
+        let response = self.bucket.put_object(key, data).await.map_err(s3_error)?;
+
+        match response.status_code() {
+            200..=299 => Ok(()),
+            status => Err(status_error(key, status)),
+        }
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        check_key(key)?;
+
+        let response = self.bucket.get_object(key).await.map_err(s3_error)?;
+
+        match response.status_code() {
+            200..=299 => Ok(Some(response.bytes().to_vec())),
+            404 => Ok(None),
+            status => Err(status_error(key, status)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> io::Result<bool> {
+        check_key(key)?;
+
+        let (_, status) = self.bucket.head_object(key).await.map_err(s3_error)?;
+
+        match status {
+            200..=299 => Ok(true),
+            404 => Ok(false),
+            status => Err(status_error(key, status)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        check_key(key)?;
+
+        let response = self.bucket.delete_object(key).await.map_err(s3_error)?;
+
+        match response.status_code() {
+            200..=299 | 404 => Ok(()),
+            status => Err(status_error(key, status)),
+        }
+    }
+
+    async fn presigned_url(
+        &self,
+        key: &str,
+        ttl: Duration,
+        file_name: Option<&str>,
+    ) -> io::Result<Option<String>> {
+        check_key(key)?;
+
+        let queries = file_name.map(|file_name| {
+            HashMap::from([(
+                "response-content-disposition".to_owned(),
+                format!("attachment; filename=\"{file_name}\""),
+            )])
+        });
+
+        let url = self
+            .bucket
+            .presign_get(key, ttl.as_secs().try_into().unwrap_or(u32::MAX), queries)
+            .map_err(s3_error)?;
+
+        Ok(Some(url))
+    }
+}
@@ -0,0 +1,98 @@
+use askama::Template;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    body::{self, Body},
+    http::header,
+    BoxError,
+};
+use futures_util::{stream, Future, StreamExt};
+
+use crate::{context::Context, meta::PageMeta};
+
+#[derive(Template)]
+#[template(path = "_stream_head.html")]
+struct StreamHeadTemplate {
+    ctx: Context,
+    meta: PageMeta,
+}
+
+#[derive(Template)]
+#[template(path = "_stream_tail.html")]
+struct StreamTailTemplate {
+    ctx: Context,
+}
+
+/// Sends the document head, with the title, canonical url and alternates of
+/// `meta`, before `render` resolves so the browser can fetch assets while the
+/// page queries run.
+///
+/// `meta` is awaited before anything is sent, its error answering with its
+/// own status: the lookups telling whether the page exists at all belong
+/// there. `render` receives a context rendering pages without the
+/// `_base.html` shell, an error it returns after the head was sent with a
+/// `200` is rendered as an inline alert.
+///
+/// ```ignore
+/// render_to_stream(
+///     ctx.clone(),
+///     async move {
+///         let user = ctx.query(GetUserInput { id }).await?;
+///
+///         Ok(PageMeta::new(&user.name).canonical(ctx.create_absolute_url(user.path())))
+///     },
+///     |ctx| async move {
+///         let feeds = ctx.query(ListFeedsInput { author: Some(id), ..Default::default() }).await?;
+///
+///         Ok(UserTemplate { ctx, feeds })
+///     },
+/// )
+/// .await
+/// ```
+pub async fn render_to_stream<M, F, Fut, T>(ctx: Context, meta: M, render: F) -> Response
+where
+    M: Future<Output = Result<PageMeta, Response>>,
+    F: FnOnce(Context) -> Fut,
+    Fut: Future<Output = Result<T, Response>> + Send + 'static,
+    T: Template,
+{
+    let meta = match meta.await {
+        Ok(meta) => meta,
+        Err(response) => return response,
+    };
+
+    let (head, tail) = if ctx.fragment() {
+        (Ok(String::new()), Ok(String::new()))
+    } else {
+        (
+            StreamHeadTemplate {
+                ctx: ctx.clone(),
+                meta,
+            }
+            .render(),
+            StreamTailTemplate { ctx: ctx.clone() }.render(),
+        )
+    };
+
+    let mut fragment_ctx = ctx;
+    fragment_ctx.streaming = true;
+
+    let page = render(fragment_ctx);
+
+    let body = stream::once(async move { head.map_err(BoxError::from) })
+        .chain(stream::once(async move {
+            match page.await {
+                Ok(template) => template.render().map_err(BoxError::from),
+                Err(response) => body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .map_err(BoxError::from),
+            }
+        }))
+        .chain(stream::once(async move { tail.map_err(BoxError::from) }));
+
+    (
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
@@ -0,0 +1,211 @@
+use anyhow::Result;
+use askama_axum::{IntoResponse, Response};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+};
+use once_cell::sync::Lazy;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+use prometheus::{Encoder, GaugeVec, HistogramVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use sqlx::PgPool;
+use std::time::Instant;
+use tracing::{error, info_span, Instrument, Subscriber};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+use ulid::Ulid;
+
+use crate::{config::Config, context::Context, drain::is_bearer};
+
+/// Header of the id of a request, taken from the client or the proxy when
+/// they send one.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Id of the request, in its extensions, carried by the metadata of the
+/// events of its commands as `req_id`.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+static HTTP_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register(HistogramVec::new(
+        prometheus::HistogramOpts::new(
+            "http_request_duration_seconds",
+            "Time taken to answer the requests, by route",
+        ),
+        &["method", "route", "status"],
+    ))
+});
+
+static DB_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new("db_pool_connections", "Connections of the database pool"),
+        &["state"],
+    ))
+});
+
+static CONSUMER_LAG: Lazy<GaugeVec> = Lazy::new(|| {
+    register(GaugeVec::new(
+        Opts::new(
+            "evento_consumer_lag_seconds",
+            "Seconds between the last event and the last time a rule moved its cursor",
+        ),
+        &["rule"],
+    ))
+});
+
+fn register<M: prometheus::core::Collector + Clone + 'static>(metric: prometheus::Result<M>) -> M {
+    let metric = metric.expect("invalid metric");
+
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric registered twice");
+
+    metric
+}
+
+/// Layer sending the spans to `TelemetryConfig::otlp_endpoint`, `None` when
+/// it is unset.
+pub fn otlp_layer<S>() -> Result<Option<OpenTelemetryLayer<S, Tracer>>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let config = Config::new()?.telemetry;
+
+    let Some(endpoint) = config.otlp_endpoint else {
+        return Ok(None);
+    };
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(Sampler::TraceIdRatioBased(config.sample_ratio))
+                .with_resource(Resource::new([KeyValue::new(
+                    "service.name",
+                    config.service_name,
+                )])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Sends the spans not exported yet, before exiting.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Runs the request in a `request` span of its `RequestId`, which the
+/// response carries in `REQUEST_ID_HEADER`.
+pub async fn trace(mut req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= 128)
+        .map(str::to_owned)
+        .unwrap_or_else(|| Ulid::new().to_string());
+
+    let span = info_span!(
+        "request",
+        method = %req.method(),
+        path = req.uri().path(),
+        request_id = %request_id,
+    );
+
+    req.extensions_mut()
+        .insert(RequestId(request_id.to_owned()));
+
+    let mut res = next.run(req).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    res
+}
+
+/// Records the time taken by the routes, under their path rather than the
+/// one requested. Layered on the routes so that the path is known.
+pub async fn record(req: Request, next: Next) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_default();
+    let started = Instant::now();
+
+    let res = next.run(req).await;
+
+    HTTP_DURATION
+        .with_label_values(&[&method, &route, res.status().as_str()])
+        .observe(started.elapsed().as_secs_f64());
+
+    res
+}
+
+/// Reads the gauges computed when scraped, the connections of `db` and the
+/// lag of the rules.
+async fn observe(db: &PgPool) -> Result<()> {
+    let idle = db.num_idle() as i64;
+
+    DB_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+    DB_CONNECTIONS
+        .with_label_values(&["active"])
+        .set(i64::from(db.size()) - idle);
+
+    let lags = sqlx::query_as::<_, (String, f64)>(
+        "SELECT rule, GREATEST(EXTRACT(EPOCH FROM (SELECT MAX(created_at) FROM ev_event) - updated_at), 0)::FLOAT8 FROM ev_queue WHERE updated_at IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await?;
+
+    for (rule, lag) in lags {
+        CONSUMER_LAG.with_label_values(&[&rule]).set(lag);
+    }
+
+    Ok(())
+}
+
+/// Metrics in the Prometheus text format, for the admins and the bearers of
+/// `TelemetryConfig::metrics_token`, others getting the not found page.
+pub async fn metrics(ctx: Context, headers: HeaderMap) -> Response {
+    let authorized = ctx.is_admin()
+        || ctx
+            .config
+            .telemetry
+            .metrics_token
+            .as_deref()
+            .is_some_and(|token| is_bearer(&headers, token));
+
+    if !authorized {
+        return ctx.error_response(StatusCode::NOT_FOUND);
+    }
+
+    if let Err(err) = observe(&ctx.query.extract::<PgPool>()).await {
+        error!("{err}");
+    }
+
+    let mut body = vec![];
+
+    if let Err(err) = TextEncoder::new().encode(&REGISTRY.gather(), &mut body) {
+        error!("{err}");
+
+        return ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response()
+}
@@ -0,0 +1,156 @@
+use askama_axum::Response;
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    Extension,
+};
+use std::{collections::HashMap, sync::Arc};
+use twa_jwks::axum::JwtPayloadOption;
+
+use crate::{
+    auth::SessionClaims,
+    config::Config,
+    context::{Context, JwtClaims},
+    maintenance::{app_path, is_probe},
+};
+
+/// `Context` of every tenant, each one with the services of its own schema,
+/// the first one answering the probes and the static files.
+#[derive(Clone)]
+pub struct Tenants {
+    first: Context,
+    contexts: Arc<HashMap<String, Context>>,
+}
+
+impl Tenants {
+    pub fn new(contexts: Vec<Context>) -> Self {
+        let first = contexts.first().cloned().expect("no tenant started");
+        let contexts = contexts
+            .into_iter()
+            .filter_map(|ctx| Some((ctx.config.tenant.to_owned()?, ctx)))
+            .collect();
+
+        Self {
+            first,
+            contexts: Arc::new(contexts),
+        }
+    }
+
+    pub fn first(&self) -> &Context {
+        &self.first
+    }
+
+    /// Every tenant, or the single `Context` while tenancy is off.
+    pub fn all(&self) -> Vec<&Context> {
+        match self.contexts.is_empty() {
+            true => vec![&self.first],
+            false => self.contexts.values().collect(),
+        }
+    }
+
+    fn get(&self, tenant: &str) -> Option<&Context> {
+        self.contexts.get(tenant)
+    }
+}
+
+/// Tenant the request is of, after `TenancyConfig::source`.
+fn resolve(config: &Config, parts: &Parts, claims: Option<&JwtClaims>) -> Option<String> {
+    let tenancy = &config.tenancy;
+
+    match tenancy.source.as_deref()? {
+        "subdomain" => {
+            let host = parts
+                .headers
+                .get(header::HOST)
+                .and_then(|host| host.to_str().ok())?;
+            let host = host.split(':').next().unwrap_or(host);
+            let public_host = config
+                .public_url
+                .parse::<axum::http::Uri>()
+                .ok()?
+                .host()?
+                .to_owned();
+
+            host.strip_suffix(&format!(".{public_host}"))
+                .map(str::to_owned)
+        }
+        "header" => parts
+            .headers
+            .get(&tenancy.header)
+            .and_then(|tenant| tenant.to_str().ok())
+            .map(str::to_owned),
+        "claim" => claim(claims?, &tenancy.claim),
+        _ => None,
+    }
+}
+
+fn claim(claims: &JwtClaims, name: &str) -> Option<String> {
+    claims
+        .claims
+        .get(name)
+        .and_then(|tenant| tenant.as_str())
+        .map(str::to_owned)
+}
+
+/// Has the request handled with the `Context` of its tenant, the not found
+/// page answering the tenants not served and the forbidden one the signed in
+/// users whose token names another tenant, or none. Probes and static files
+/// are answered by the first tenant while no tenant is found.
+pub async fn scope(
+    Extension(ctx): Extension<Context>,
+    Extension(tenants): Extension<Tenants>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let config = ctx.reloader.config();
+
+    if !config.tenancy.is_enabled() {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let claims = match JwtPayloadOption::<JwtClaims>::from_request_parts(&mut parts, &()).await {
+        Ok(JwtPayloadOption(claims)) => claims.or_else(|| {
+            parts
+                .extensions
+                .get::<SessionClaims>()
+                .map(|claims| claims.0.clone())
+        }),
+        Err(_) => return error_response(&mut parts, StatusCode::BAD_REQUEST).await,
+    };
+
+    let tenant_ctx =
+        resolve(&config, &parts, claims.as_ref()).and_then(|tenant| tenants.get(&tenant).cloned());
+
+    let Some(tenant_ctx) = tenant_ctx else {
+        let path = app_path(&ctx, parts.uri.path());
+
+        if is_probe(path) || path.starts_with("/static/") {
+            return next.run(Request::from_parts(parts, body)).await;
+        }
+
+        return error_response(&mut parts, StatusCode::NOT_FOUND).await;
+    };
+
+    let claimed = claims
+        .as_ref()
+        .and_then(|claims| claim(claims, &config.tenancy.claim));
+    let tenant = tenant_ctx.config.tenant.to_owned();
+
+    parts.extensions.insert(tenant_ctx);
+
+    if claims.is_some() && claimed != tenant {
+        return error_response(&mut parts, StatusCode::FORBIDDEN).await;
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Page of `status` in the language of the request.
+async fn error_response(parts: &mut Parts, status: StatusCode) -> Response {
+    match Context::from_request_parts(parts, &()).await {
+        Ok(ctx) => ctx.error_response(status),
+        Err(response) => response,
+    }
+}
@@ -0,0 +1,148 @@
+use askama_axum::{IntoResponse, Response};
+use axum::{http::StatusCode, Json};
+use evento::CommandHandler;
+use serde::Serialize;
+use sqlx::PgPool;
+use starter_feed::{CreateFeedInput, CreateUserInput, Feed, ROLE_ADMIN, ROLE_USER};
+use tracing::{error, info};
+use validator::Validate;
+
+use crate::{
+    context::Context,
+    routes::{on, Routes},
+};
+
+/// Users of `/__test/seed`, by id, name and role.
+const SEED_USERS: [(&str, &str, &str); 3] = [
+    ("00000000-0000-4000-8000-000000000001", "Admin", ROLE_ADMIN),
+    ("00000000-0000-4000-8000-000000000002", "Alice", ROLE_USER),
+    ("00000000-0000-4000-8000-000000000003", "Bob", ROLE_USER),
+];
+
+/// Public feeds of every user of `SEED_USERS`.
+const SEED_FEEDS: usize = 2;
+
+#[derive(Serialize)]
+pub struct SeededUser {
+    id: &'static str,
+    name: &'static str,
+    role: &'static str,
+    feeds: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct Seeded {
+    users: Vec<SeededUser>,
+}
+
+/// Routes of the end-to-end suite, only built with the `test-api` feature:
+/// they let anyone wipe the database.
+pub fn create_router() -> Routes {
+    Routes::new()
+        .route("/reset", on!(Public post(reset)))
+        .route("/seed", on!(Public post(seed)))
+}
+
+/// Aggregate ids of the events written by `input`.
+async fn execute<I: Validate + CommandHandler>(
+    ctx: &Context,
+    input: I,
+) -> Result<Vec<String>, Response> {
+    ctx.command
+        .execute("en".to_owned(), &input)
+        .await
+        .map(|events| events.into_iter().map(|event| event.aggregate_id).collect())
+        .map_err(|err| {
+            error!("{err}");
+
+            ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR)
+        })
+}
+
+/// Empties every table but the one of the migrations, events included.
+pub async fn reset(ctx: Context) -> Result<StatusCode, Response> {
+    let db = ctx.query.extract::<PgPool>();
+
+    let result = async {
+        let tables = sqlx::query_scalar::<_, String>(
+            "SELECT quote_ident(tablename) FROM pg_tables WHERE schemaname = current_schema() AND tablename <> '_sqlx_migrations'",
+        )
+        .fetch_all(&db)
+        .await?;
+
+        if !tables.is_empty() {
+            sqlx::query(&format!("TRUNCATE {} CASCADE", tables.join(", ")))
+                .execute(&db)
+                .await?;
+        }
+
+        Ok::<_, sqlx::Error>(tables.len())
+    }
+    .await;
+
+    match result {
+        Ok(tables) => {
+            info!("test api emptied {tables} tables");
+
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Err(err) => {
+            error!("{err}");
+
+            Err(ctx.error_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+/// Creates the users of `SEED_USERS` along with their feeds through the
+/// commands, on a database emptied by `/__test/reset`. Feeds get ids of
+/// their own, which are returned by user.
+pub async fn seed(ctx: Context) -> Result<Response, Response> {
+    let mut users = vec![];
+
+    for (id, name, role) in SEED_USERS {
+        execute(
+            &ctx,
+            CreateUserInput {
+                id: id.to_owned(),
+                name: name.to_owned(),
+                role: role.to_owned(),
+                request_id: None,
+            },
+        )
+        .await?;
+
+        let mut feeds = vec![];
+
+        for index in 1..=SEED_FEEDS {
+            let aggregate_ids = execute(
+                &ctx,
+                CreateFeedInput {
+                    title: format!("{name} feed {index}"),
+                    content: Some(format!("Feed {index} seeded for {name}.")),
+                    attachments: vec![],
+                    visibility: "public".to_owned(),
+                    publish_at: None,
+                    user_id: id.to_owned(),
+                    request_id: None,
+                },
+            )
+            .await?;
+
+            feeds.extend(
+                aggregate_ids
+                    .first()
+                    .map(|aggregate_id| Feed::from_aggregate_id(aggregate_id)),
+            );
+        }
+
+        users.push(SeededUser {
+            id,
+            name,
+            role,
+            feeds,
+        });
+    }
+
+    Ok((StatusCode::CREATED, Json(Seeded { users })).into_response())
+}
@@ -0,0 +1,56 @@
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use serde::Deserialize;
+
+pub const THEME_COOKIE: &str = "theme";
+
+/// Color theme from the `theme` cookie, `System` leaves the choice to
+/// `prefers-color-scheme`.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn from_jar(jar: &CookieJar) -> Self {
+        match jar.get(THEME_COOKIE).map(|cookie| cookie.value()) {
+            Some("light") => Theme::Light,
+            Some("dark") => Theme::Dark,
+            _ => Theme::System,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    /// Value of the `data-theme` attribute of `<html>` used by daisyUI.
+    pub fn data_theme(&self) -> Option<&'static str> {
+        match self {
+            Theme::System => None,
+            theme => Some(theme.as_str()),
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            Theme::Dark => Theme::Light,
+            _ => Theme::Dark,
+        }
+    }
+
+    pub fn to_cookie(self) -> Cookie<'static> {
+        Cookie::build((THEME_COOKIE, self.as_str()))
+            .path("/")
+            .max_age(time::Duration::days(365))
+            .same_site(axum_extra::extract::cookie::SameSite::Lax)
+            .build()
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::{bail, Result};
+use evento::{Command, CommandError, CommandHandler, PgConsumer, Query, QueryError};
+use sqlx::PgPool;
+use starter_feed::{
+    CreateUserInput, DisableUserInput, ListUsersInput, RevokeUserRoleInput, SetUserRoleInput,
+    UserDetails,
+};
+use validator::Validate;
+
+use crate::config::Config;
+
+/// Command and query of the configured database, without any rule: events
+/// are handled by the consumers of the running server.
+async fn connect() -> Result<(Command, Query)> {
+    let config = Config::load().await?;
+    let db = PgPool::connect(&config.dsn).await?;
+
+    crate::module::migrate(&db, &crate::pages::modules()).await?;
+
+    let producer = PgConsumer::new(&db).start(0).await?;
+
+    Ok((Command::new(&producer), Query::new().data(db).data(config)))
+}
+
+/// Whether `input` wrote events, errors being flattened into messages.
+async fn execute<I: Validate + CommandHandler>(command: &Command, input: I) -> Result<bool> {
+    match command.execute("en".to_owned(), &input).await {
+        Ok(events) => Ok(!events.is_empty()),
+        Err(CommandError::Validation(errors)) => {
+            let mut errors = errors
+                .into_iter()
+                .map(|(field, messages)| format!("{field}: {}", messages.join(", ")))
+                .collect::<Vec<_>>();
+            errors.sort();
+
+            bail!("invalid {}", errors.join("; "))
+        }
+        Err(CommandError::NotFound(err)) => bail!("{err}"),
+        Err(CommandError::Server(err)) => bail!("{err}"),
+    }
+}
+
+/// Creates the user signing in with `id` as the `sub` of their tokens.
+pub async fn create(id: String, name: String, role: String) -> Result<()> {
+    let (command, _) = connect().await?;
+
+    execute(
+        &command,
+        CreateUserInput {
+            id,
+            name,
+            role,
+            request_id: None,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Whether the user wasn't already disabled.
+pub async fn disable(id: String) -> Result<bool> {
+    let (command, _) = connect().await?;
+
+    execute(
+        &command,
+        DisableUserInput {
+            id,
+            request_id: None,
+        },
+    )
+    .await
+}
+
+/// Whether the user didn't already have `role`.
+pub async fn set_role(id: String, role: String) -> Result<bool> {
+    let (command, _) = connect().await?;
+
+    execute(
+        &command,
+        SetUserRoleInput {
+            id,
+            role,
+            request_id: None,
+        },
+    )
+    .await
+}
+
+/// Whether the user had `role`, given back the user role. Like other
+/// changes of role, it applies once the running server handled the event,
+/// roles being read on every request rather than cached.
+pub async fn revoke_role(id: String, role: String) -> Result<bool> {
+    let (command, _) = connect().await?;
+
+    execute(
+        &command,
+        RevokeUserRoleInput {
+            id,
+            role,
+            request_id: None,
+        },
+    )
+    .await
+}
+
+/// Users as last handled by the running server.
+pub async fn list() -> Result<Vec<UserDetails>> {
+    let (_, query) = connect().await?;
+
+    match query.execute(&ListUsersInput).await {
+        Ok(users) => Ok(users),
+        Err(QueryError::NotFound(err)) => bail!("{err}"),
+        Err(QueryError::Server(err)) => bail!("{err}"),
+    }
+}
@@ -0,0 +1,171 @@
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+use validator::{Validate, ValidationErrors};
+
+use crate::{auth::random_token, components::WizardSteps, context::Context};
+
+/// How long the state of a wizard left unfinished is kept.
+const WIZARD_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// State of a multi-step form, kept in `Context::cache` between steps under
+/// the random id of the `wizard-{NAME}` cookie, so that the browser can
+/// neither read nor change it. Each step validates its own input, and
+/// `finish` validates the whole of it again.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize, Validate, Default)]
+/// pub struct Onboarding {
+///     #[validate(length(min = 1))]
+///     pub name: String,
+///     pub tags: Vec<String>,
+/// }
+///
+/// impl WizardForm for Onboarding {
+///     const NAME: &'static str = "onboarding";
+///     const STEPS: &'static [&'static str] = &["pages_onboarding-Step_profile", "pages_onboarding-Step_tags"];
+/// }
+/// ```
+pub trait WizardForm: Serialize + DeserializeOwned + Validate + Default {
+    const NAME: &'static str;
+
+    /// Translation ids of the step titles.
+    const STEPS: &'static [&'static str];
+}
+
+/// Button of a wizard step submitted as `action`.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WizardAction {
+    Back,
+    #[default]
+    Next,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Wizard<T> {
+    pub step: usize,
+    pub data: T,
+    /// Id of the state, in the cookie of the wizard.
+    #[serde(skip)]
+    id: Option<String>,
+}
+
+impl<T: WizardForm> Wizard<T> {
+    fn cookie_name() -> String {
+        format!("wizard-{}", T::NAME)
+    }
+
+    fn cache_key(id: &str) -> String {
+        format!("wizard:{id}")
+    }
+
+    /// State of the wizard of the request, a new one on its first step when
+    /// it has none or it expired.
+    pub async fn load(ctx: &Context, jar: &CookieJar) -> Self {
+        let Some(id) = jar.get(&Self::cookie_name()).map(|cookie| cookie.value()) else {
+            return Self::default();
+        };
+
+        let wizard = ctx
+            .cache
+            .get(&Self::cache_key(id), T::NAME)
+            .await
+            .and_then(|state| serde_json::from_str::<Self>(&state).ok());
+
+        match wizard {
+            Some(mut wizard) => {
+                wizard.step = wizard.step.min(T::STEPS.len().saturating_sub(1));
+                wizard.id = Some(id.to_owned());
+                wizard
+            }
+            None => Self::default(),
+        }
+    }
+
+    /// Keeps the state for the next step, `jar` getting the cookie of its id.
+    pub async fn save(&self, ctx: &Context, jar: CookieJar) -> CookieJar {
+        let id = self.id.to_owned().unwrap_or_else(random_token);
+        let state = serde_json::to_string(self).unwrap_or_default();
+
+        ctx.cache
+            .set(&Self::cache_key(&id), T::NAME, state, WIZARD_TTL)
+            .await;
+
+        let cookies = &ctx.config.cookies;
+        let cookie = Cookie::build((Self::cookie_name(), id))
+            .path("/")
+            .http_only(true)
+            .secure(cookies.secure)
+            .same_site(cookies.same_site())
+            .build();
+
+        jar.add(cookie)
+    }
+
+    /// Forgets the state once the wizard is completed or cancelled.
+    pub async fn clear(&self, ctx: &Context, jar: CookieJar) -> CookieJar {
+        if let Some(id) = &self.id {
+            ctx.cache.remove(&Self::cache_key(id), T::NAME).await;
+        }
+
+        jar.remove(Cookie::build(Self::cookie_name()).path("/"))
+    }
+
+    pub fn is_first(&self) -> bool {
+        self.step == 0
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.step + 1 >= T::STEPS.len()
+    }
+
+    pub fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn next(&mut self) {
+        if !self.is_last() {
+            self.step += 1;
+        }
+    }
+
+    /// Validates the input of the current step, errors are keyed by field
+    /// like the ones returned by `Context::execute`.
+    pub fn validate<I: Validate>(&self, input: &I) -> Result<(), HashMap<String, Vec<String>>> {
+        input.validate().map_err(field_errors)
+    }
+
+    /// Data of every step once the last is submitted, validated again as a
+    /// whole, the errors being keyed by field like the ones of `validate`.
+    pub fn finish(&self) -> Result<&T, HashMap<String, Vec<String>>> {
+        self.data.validate().map_err(field_errors)?;
+
+        Ok(&self.data)
+    }
+
+    pub fn steps(&self, ctx: &Context) -> WizardSteps {
+        WizardSteps::new(ctx, T::STEPS, self.step)
+    }
+}
+
+fn field_errors(errors: ValidationErrors) -> HashMap<String, Vec<String>> {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let messages = errors
+                .iter()
+                .map(|error| {
+                    error
+                        .message
+                        .as_ref()
+                        .map(|message| message.to_string())
+                        .unwrap_or_else(|| error.code.to_string())
+                })
+                .collect();
+
+            (field.to_owned(), messages)
+        })
+        .collect()
+}